@@ -0,0 +1,78 @@
+//! clap [Args](clap::Args) for clique proof-of-authority validation
+
+use clap::Args;
+use reth_clique_consensus::CliqueConsensus;
+use reth_db::database::Database;
+use reth_interfaces::consensus::Consensus;
+use reth_primitives::ChainSpec;
+use std::sync::Arc;
+
+/// Parameters for validating the signers of clique headers
+#[derive(Debug, Args, PartialEq, Default)]
+#[command(next_help_heading = "Clique")]
+pub struct CliqueArgs {
+    /// Validate headers with clique proof-of-authority consensus.
+    ///
+    /// Headers are checked against the authorized signers of the chain, which requires the chain
+    /// specification to have a clique configuration.
+    #[arg(long = "clique.verify")]
+    pub verify: bool,
+}
+
+impl CliqueArgs {
+    /// Returns a [CliqueConsensus] if clique validation is enabled.
+    ///
+    /// Fails if clique validation is enabled, but the chain has no clique configuration.
+    pub fn consensus<DB>(
+        &self,
+        chain_spec: &Arc<ChainSpec>,
+        db: DB,
+    ) -> eyre::Result<Option<Arc<dyn Consensus>>>
+    where
+        DB: Database + Clone + 'static,
+    {
+        if !self.verify {
+            return Ok(None)
+        }
+        if chain_spec.clique.is_none() {
+            eyre::bail!("--clique.verify requires a chain with a clique configuration")
+        }
+        Ok(Some(Arc::new(CliqueConsensus::new(Arc::clone(chain_spec), db))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use reth_db::mdbx::test_utils::create_test_rw_db;
+    use reth_primitives::{GOERLI, MAINNET};
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[clap(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn test_parse_clique_args() {
+        let args = CommandParser::<CliqueArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, CliqueArgs { verify: false });
+
+        let args = CommandParser::<CliqueArgs>::parse_from(["reth", "--clique.verify"]).args;
+        assert_eq!(args, CliqueArgs { verify: true });
+    }
+
+    #[test]
+    fn test_clique_consensus_is_opt_in() {
+        let db = create_test_rw_db();
+
+        let args = CliqueArgs::default();
+        assert!(args.consensus(&GOERLI, Arc::clone(&db)).unwrap().is_none());
+
+        let args = CliqueArgs { verify: true };
+        assert!(args.consensus(&GOERLI, Arc::clone(&db)).unwrap().is_some());
+        assert!(args.consensus(&MAINNET, db).is_err());
+    }
+}
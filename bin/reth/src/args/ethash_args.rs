@@ -0,0 +1,110 @@
+//! clap [Args](clap::Args) for pre-merge proof-of-work validation
+
+use clap::Args;
+use reth_ethash_consensus::{EthashConfig, EthashConsensus};
+use reth_interfaces::consensus::Consensus;
+use reth_primitives::ChainSpec;
+use std::{path::PathBuf, sync::Arc};
+
+/// Parameters for validating the proof-of-work of pre-merge headers
+#[derive(Debug, Args, PartialEq, Default)]
+#[command(next_help_heading = "Ethash")]
+pub struct EthashArgs {
+    /// Validate the difficulty and ethash seal of pre-merge headers.
+    ///
+    /// Use this when syncing pre-merge history from untrusted peers or files. Seals are verified
+    /// with the light algorithm, which needs a verification cache for every epoch.
+    #[arg(long = "ethash.verify")]
+    pub verify: bool,
+
+    /// Only validate the difficulty of pre-merge headers, but not their ethash seal.
+    #[arg(long = "ethash.difficulty-only", requires = "verify")]
+    pub difficulty_only: bool,
+
+    /// The directory to store the ethash verification caches in.
+    ///
+    /// Defaults to the `ethash` directory of the chain specific data dir.
+    #[arg(long = "ethash.cachedir", value_name = "PATH")]
+    pub cache_dir: Option<PathBuf>,
+
+    /// The number of recent ethash verification caches to keep in memory.
+    #[arg(long = "ethash.cachesinmem", default_value_t = 2)]
+    pub caches_in_mem: u32,
+
+    /// The number of recent ethash verification caches to keep on disk.
+    #[arg(long = "ethash.cachesondisk", default_value_t = 3)]
+    pub caches_on_disk: u64,
+}
+
+impl EthashArgs {
+    /// Returns the [EthashConfig] if pre-merge validation is enabled.
+    ///
+    /// Caches are stored in the given directory unless `--ethash.cachedir` is set.
+    pub fn ethash_config(&self, default_cache_dir: PathBuf) -> Option<EthashConfig> {
+        self.verify.then(|| EthashConfig {
+            verify_seal: !self.difficulty_only,
+            cache_dir: Some(self.cache_dir.clone().unwrap_or(default_cache_dir)),
+            caches_in_mem: self.caches_in_mem,
+            caches_on_disk: self.caches_on_disk,
+        })
+    }
+
+    /// Returns the consensus, wrapped in an [EthashConsensus] if pre-merge validation is enabled.
+    pub fn consensus<C: Consensus + 'static>(
+        &self,
+        consensus: C,
+        chain_spec: &Arc<ChainSpec>,
+        default_cache_dir: PathBuf,
+    ) -> Arc<dyn Consensus> {
+        match self.ethash_config(default_cache_dir) {
+            Some(config) => {
+                Arc::new(EthashConsensus::new(consensus, Arc::clone(chain_spec), config))
+            }
+            None => Arc::new(consensus),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[clap(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn test_parse_ethash_args() {
+        let args = CommandParser::<EthashArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, EthashArgs { caches_in_mem: 2, caches_on_disk: 3, ..Default::default() });
+        assert_eq!(args.ethash_config(PathBuf::from("ethash")), None);
+
+        let args = CommandParser::<EthashArgs>::parse_from([
+            "reth",
+            "--ethash.verify",
+            "--ethash.difficulty-only",
+            "--ethash.cachedir",
+            "/tmp/ethash",
+        ])
+        .args;
+        assert_eq!(
+            args.ethash_config(PathBuf::from("ethash")),
+            Some(EthashConfig {
+                verify_seal: false,
+                cache_dir: Some(PathBuf::from("/tmp/ethash")),
+                caches_in_mem: 2,
+                caches_on_disk: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn test_difficulty_only_requires_verify() {
+        assert!(CommandParser::<EthashArgs>::try_parse_from(["reth", "--ethash.difficulty-only"])
+            .is_err());
+    }
+}
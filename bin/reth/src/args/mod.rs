@@ -12,6 +12,14 @@ pub use rpc_server_args::RpcServerArgs;
 mod debug_args;
 pub use debug_args::DebugArgs;
 
+/// EthashArgs struct for validating pre-merge proof-of-work
+mod ethash_args;
+pub use ethash_args::EthashArgs;
+
+/// CliqueArgs struct for validating proof-of-authority headers
+mod clique_args;
+pub use clique_args::CliqueArgs;
+
 mod secret_key;
 pub use secret_key::{get_secret_key, SecretKeyError};
 
@@ -25,3 +33,7 @@ pub use stage_args::StageEnum;
 
 mod gas_price_oracle_args;
 pub use gas_price_oracle_args::GasPriceOracleArgs;
+
+/// TxPoolArgs for configuring the transaction pool
+mod txpool_args;
+pub use txpool_args::TxPoolArgs;
@@ -4,10 +4,13 @@ use crate::version::P2P_CLIENT_VERSION;
 use clap::Args;
 use reth_config::Config;
 use reth_net_nat::NatResolver;
-use reth_network::{HelloMessage, NetworkConfigBuilder};
+use reth_network::{
+    transactions::{PeerPropagationLimit, TransactionPropagationMode, TransactionsManagerConfig},
+    HelloMessage, NetworkConfigBuilder,
+};
 use reth_primitives::{mainnet_nodes, ChainSpec, NodeRecord};
 use secp256k1::SecretKey;
-use std::{path::PathBuf, sync::Arc};
+use std::{path::PathBuf, sync::Arc, time::Duration};
 
 /// Parameters for configuring the network more granularity via CLI
 #[derive(Debug, Args)]
@@ -60,6 +63,10 @@ pub struct NetworkArgs {
     /// Network listening port. default: 30303
     #[arg(long = "port", value_name = "PORT")]
     pub port: Option<u16>,
+
+    /// Configures how transactions are propagated.
+    #[command(flatten)]
+    pub tx_propagation: TxPropagationArgs,
 }
 
 impl NetworkArgs {
@@ -143,6 +150,49 @@ impl DiscoveryArgs {
     }
 }
 
+/// Arguments to configure transaction propagation
+#[derive(Debug, Args)]
+pub struct TxPropagationArgs {
+    /// Only propagate transactions to trusted peers.
+    #[arg(long = "txprop.trusted-only", name = "txprop.trusted-only")]
+    pub trusted_only: bool,
+
+    /// Maximum number of hashes in a single transaction announcement.
+    #[arg(long = "txprop.max-announcement-batch", name = "txprop.max-announcement-batch")]
+    pub max_announcement_batch: Option<usize>,
+
+    /// Maximum number of transactions propagated to a single peer per second.
+    #[arg(long = "txprop.peer-rate-limit", name = "txprop.peer-rate-limit")]
+    pub peer_rate_limit: Option<usize>,
+
+    /// Never broadcast full transactions to eth/68 peers, only announce their hashes.
+    #[arg(long = "txprop.eth68-announce-only", name = "txprop.eth68-announce-only")]
+    pub eth68_announce_only: bool,
+}
+
+impl TxPropagationArgs {
+    /// Returns the [TransactionsManagerConfig] for these arguments.
+    pub fn transactions_manager_config(&self) -> TransactionsManagerConfig {
+        let mut config =
+            TransactionsManagerConfig::default().with_eth68_announce_only(self.eth68_announce_only);
+
+        if self.trusted_only {
+            config = config.with_propagation_mode(TransactionPropagationMode::TrustedOnly);
+        }
+        if let Some(max) = self.max_announcement_batch {
+            config = config.with_max_announcement_batch_size(max);
+        }
+        if let Some(max_transactions) = self.peer_rate_limit {
+            config = config.with_peer_rate_limit(PeerPropagationLimit {
+                max_transactions,
+                interval: Duration::from_secs(1),
+            });
+        }
+
+        config
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -163,4 +213,26 @@ mod tests {
             CommandParser::<NetworkArgs>::parse_from(["reth", "--nat", "extip:0.0.0.0"]).args;
         assert_eq!(args.nat, NatResolver::ExternalIp("0.0.0.0".parse().unwrap()));
     }
+
+    #[test]
+    fn parse_tx_propagation_args() {
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--txprop.trusted-only",
+            "--txprop.max-announcement-batch",
+            "256",
+            "--txprop.peer-rate-limit",
+            "1000",
+        ])
+        .args;
+
+        let config = args.tx_propagation.transactions_manager_config();
+        assert_eq!(config.propagation_mode, TransactionPropagationMode::TrustedOnly);
+        assert_eq!(config.max_announcement_batch_size, 256);
+        assert_eq!(
+            config.peer_rate_limit,
+            Some(PeerPropagationLimit { max_transactions: 1000, interval: Duration::from_secs(1) })
+        );
+        assert!(!config.eth68_announce_only);
+    }
 }
@@ -7,8 +7,9 @@ use clap::{
 };
 use futures::FutureExt;
 use reth_network_api::{NetworkInfo, Peers};
+use reth_payload_builder::BundleStore;
 use reth_provider::{
-    BlockProviderIdExt, CanonStateSubscriptions, EvmEnvProvider, HeaderProvider,
+    BlockProviderIdExt, CanonStateSubscriptions, ChainSpecProvider, EvmEnvProvider, HeaderProvider,
     StateProviderFactory,
 };
 use reth_rpc::{eth::gas_oracle::GasPriceOracleConfig, JwtError, JwtSecret};
@@ -203,12 +204,14 @@ impl RpcServerArgs {
         events: Events,
         engine_api: Engine,
         jwt_secret: JwtSecret,
+        bundle_store: BundleStore,
     ) -> Result<(RpcServerHandle, AuthServerHandle), RpcError>
     where
         Client: BlockProviderIdExt
             + HeaderProvider
             + StateProviderFactory
             + EvmEnvProvider
+            + ChainSpecProvider
             + Clone
             + Unpin
             + 'static,
@@ -229,6 +232,7 @@ impl RpcServerArgs {
             .with_network(network)
             .with_events(events)
             .with_executor(executor)
+            .with_bundle_store(bundle_store)
             .build_with_auth_server(module_config, engine_api);
 
         let server_config = self.rpc_server_config();
@@ -261,6 +265,7 @@ impl RpcServerArgs {
             + HeaderProvider
             + StateProviderFactory
             + EvmEnvProvider
+            + ChainSpecProvider
             + Clone
             + Unpin
             + 'static,
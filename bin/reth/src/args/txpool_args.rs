@@ -0,0 +1,155 @@
+use crate::utils::parse_duration_from_secs;
+use clap::Args;
+use reth_primitives::Address;
+use reth_transaction_pool::{JournalConfig, LocalTransactionConfig, PoolConfig, PriceBumpConfig};
+use std::{path::PathBuf, time::Duration};
+
+/// Parameters for configuring the transaction pool
+#[derive(Debug, Args, PartialEq, Default)]
+#[command(next_help_heading = "TXPOOL")]
+pub struct TxPoolArgs {
+    /// Max number of executable transaction slots guaranteed per account.
+    #[arg(long = "txpool.accountslots", default_value = "16")]
+    pub max_account_slots: usize,
+
+    /// Max number of transactions in the entire pool, the least valuable non-local transactions
+    /// are evicted once this is exceeded.
+    #[arg(long = "txpool.globalslots", default_value = "30000")]
+    pub max_total_transactions: usize,
+
+    /// Price bump (in %) of the fee cap a replacement transaction needs to pay.
+    #[arg(long = "txpool.pricebump", default_value = "10")]
+    pub fee_cap_bump: u128,
+
+    /// Price bump (in %) of the priority fee a replacement transaction needs to pay.
+    #[arg(long = "txpool.tipbump", default_value = "10")]
+    pub priority_fee_bump: u128,
+
+    /// Minimum priority fee (gas price for legacy transactions) required for acceptance into the
+    /// pool.
+    #[arg(long = "txpool.mintip", default_value = "0")]
+    pub minimal_priority_fee: u128,
+
+    /// Max size (in bytes) of a transaction in its network encoding.
+    #[arg(long = "txpool.maxtxsize", default_value = "131072")]
+    pub max_tx_size: usize,
+
+    /// Comma separated addresses whose transactions are treated as local.
+    #[arg(long = "txpool.locals", value_delimiter = ',')]
+    pub locals: Vec<Address>,
+
+    /// Disables the exemptions of local transactions from the pool's limits.
+    #[arg(long = "txpool.nolocals")]
+    pub no_locals: bool,
+
+    /// Disables the journal of local transactions, local transactions are lost on restart.
+    #[arg(long = "txpool.nojournal")]
+    pub no_journal: bool,
+
+    /// The path of the journal of local transactions.
+    ///
+    /// Defaults to the `txpool-journal.rlp` file in the data directory.
+    #[arg(long = "txpool.journal", value_name = "PATH", verbatim_doc_comment)]
+    pub journal: Option<PathBuf>,
+
+    /// The interval at which the journal of local transactions is rotated (in seconds).
+    #[arg(long = "txpool.rejournal", value_parser = parse_duration_from_secs, default_value = "3600", value_name = "SECONDS")]
+    pub rejournal: Duration,
+}
+
+impl TxPoolArgs {
+    /// Returns the [PoolConfig] for the configured arguments.
+    ///
+    /// The journal is stored at the given default path, unless a path was configured.
+    pub fn pool_config(&self, default_journal_path: PathBuf) -> PoolConfig {
+        let journal = (!self.no_journal).then(|| JournalConfig {
+            path: self.journal.clone().unwrap_or(default_journal_path),
+            rotation_interval: self.rejournal,
+        });
+        PoolConfig {
+            max_account_slots: self.max_account_slots,
+            max_total_transactions: self.max_total_transactions,
+            price_bumps: PriceBumpConfig {
+                fee_cap_bump: self.fee_cap_bump,
+                priority_fee_bump: self.priority_fee_bump,
+            },
+            minimal_priority_fee: self.minimal_priority_fee,
+            max_tx_size: self.max_tx_size,
+            local_transactions: LocalTransactionConfig {
+                no_exemptions: self.no_locals,
+                local_addresses: self.locals.iter().copied().collect(),
+            },
+            journal,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[clap(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn test_parse_txpool_args() {
+        let args = CommandParser::<TxPoolArgs>::parse_from(["reth"]).args;
+        assert_eq!(
+            args,
+            TxPoolArgs {
+                max_account_slots: 16,
+                max_total_transactions: 30_000,
+                fee_cap_bump: 10,
+                priority_fee_bump: 10,
+                minimal_priority_fee: 0,
+                max_tx_size: 128 * 1024,
+                locals: vec![],
+                no_locals: false,
+                no_journal: false,
+                journal: None,
+                rejournal: Duration::from_secs(3600)
+            }
+        );
+        let config = args.pool_config("txpool-journal.rlp".into());
+        assert_eq!(config.journal.unwrap().path, PathBuf::from("txpool-journal.rlp"));
+        assert_eq!(config.price_bumps, PriceBumpConfig::default());
+        assert_eq!(config.max_account_slots, PoolConfig::default().max_account_slots);
+        assert_eq!(config.max_tx_size, PoolConfig::default().max_tx_size);
+
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--txpool.nojournal",
+            "--txpool.rejournal",
+            "60",
+        ])
+        .args;
+        assert!(args.no_journal);
+        assert_eq!(args.rejournal, Duration::from_secs(60));
+        assert!(args.pool_config("txpool-journal.rlp".into()).journal.is_none());
+    }
+
+    #[test]
+    fn test_parse_txpool_policy_args() {
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--txpool.pricebump",
+            "25",
+            "--txpool.mintip",
+            "1000000000",
+            "--txpool.locals",
+            "0x0000000000000000000000000000000000000001,0x0000000000000000000000000000000000000002",
+        ])
+        .args;
+        let config = args.pool_config("txpool-journal.rlp".into());
+        assert_eq!(config.price_bumps, PriceBumpConfig { fee_cap_bump: 25, priority_fee_bump: 10 });
+        assert_eq!(config.minimal_priority_fee, 1_000_000_000);
+        assert_eq!(config.local_transactions.local_addresses.len(), 2);
+        assert!(config.local_transactions.local_addresses.contains(&Address::from_low_u64_be(1)));
+    }
+}
@@ -0,0 +1,553 @@
+use crate::dirs::{DataDirPath, MaybePlatformPath};
+use clap::{Parser, ValueEnum};
+use eyre::Context;
+use reth_db::mdbx::{Env, EnvKind, NoWriteMap};
+use reth_downloaders::era::{Era1, Era1Block, MAX_BLOCKS_PER_ERA1};
+use reth_primitives::{
+    stage::StageId, Block, BlockBody, BlockHashOrNumber, BlockNumber, ChainSpec, Hardfork, Receipt,
+    ReceiptWithBloom, SealedBlock, TransactionMeta, U256,
+};
+use reth_provider::{
+    BlockProvider, HeaderProvider, ReceiptProvider, ShareableDatabase, StageCheckpointProvider,
+};
+use reth_rlp::Encodable;
+use reth_rpc::eth::build_transaction_receipt_with_block_receipts;
+use reth_rpc_types::{Block as RpcBlock, TransactionReceipt};
+use reth_staged_sync::utils::chainspec::genesis_value_parser;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    ops::{Range, RangeInclusive},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::{info, warn};
+
+/// The name of the file that tracks the progress of an export.
+const CHECKPOINT_FILE_NAME: &str = "export-checkpoint.json";
+
+/// The extension of files that are still being written.
+const PARTIAL_EXTENSION: &str = "partial";
+
+/// Exports canonical blocks and receipts from the database to files.
+#[derive(Debug, Parser)]
+pub struct ExportCommand {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    ///
+    /// Built-in chains:
+    /// - mainnet
+    /// - goerli
+    /// - sepolia
+    /// - holesky
+    /// - devnet:<seed>, a generated development chain
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        verbatim_doc_comment,
+        default_value = "mainnet",
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    /// The first block to export.
+    #[arg(long, value_name = "BLOCK", default_value_t = 0)]
+    from: BlockNumber,
+
+    /// The last block to export, inclusive.
+    ///
+    /// Defaults to the latest fully synced block.
+    #[arg(long, value_name = "BLOCK", verbatim_doc_comment)]
+    to: Option<BlockNumber>,
+
+    /// The format of the exported files.
+    ///
+    /// - rlp: concatenated RLP encoded blocks, as read by `reth import`
+    /// - era1: era1 archives of 8192 blocks with receipts and total difficulty
+    /// - json: newline-delimited JSON with one block and its receipts per line
+    ///
+    /// Era1 archives only contain pre-merge blocks and always cover a full epoch, so the range is
+    /// extended to the start of the first epoch and an incomplete last epoch is skipped. The last
+    /// pre-merge epoch is the only one that is exported without being full.
+    #[arg(long, value_enum, verbatim_doc_comment, default_value_t = ExportFormat::Rlp)]
+    format: ExportFormat,
+
+    /// The size in bytes after which a new output file is started.
+    ///
+    /// Only applies to the rlp and json formats, era1 files always contain a full epoch.
+    #[arg(long, value_name = "BYTES", verbatim_doc_comment)]
+    max_file_size: Option<u64>,
+
+    /// The directory to write the exported files to.
+    ///
+    /// Progress is tracked in a checkpoint file in this directory, so an interrupted export
+    /// resumes after the last completed file when run again.
+    #[arg(value_name = "OUTPUT_DIR", verbatim_doc_comment)]
+    output: PathBuf,
+}
+
+/// The supported export formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// Concatenated RLP encoded blocks.
+    Rlp,
+    /// Era1 archives.
+    Era1,
+    /// Newline-delimited JSON.
+    Json,
+}
+
+impl ExportFormat {
+    /// The extension of the files written in this format.
+    fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Rlp => "rlp",
+            ExportFormat::Era1 => "era1",
+            ExportFormat::Json => "jsonl",
+        }
+    }
+}
+
+/// The progress of an export, persisted after every completed file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportCheckpoint {
+    /// The format of the export.
+    format: ExportFormat,
+    /// The next block to export.
+    next_block: BlockNumber,
+}
+
+/// A single line of the JSON export.
+#[derive(Debug, Serialize)]
+struct JsonBlock {
+    block: RpcBlock,
+    receipts: Vec<TransactionReceipt>,
+}
+
+impl ExportCommand {
+    /// Execute `export` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        // add network name to data dir
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let db_path = data_dir.db_path();
+
+        info!(target: "reth::cli", path = ?db_path, "Opening database");
+        let db = Env::<NoWriteMap>::open(db_path.as_ref(), EnvKind::RO)?;
+        let provider = ShareableDatabase::new(db, self.chain.clone());
+
+        let to = match self.to {
+            Some(to) => to,
+            None => {
+                provider.get_stage_checkpoint(StageId::Finish)?.unwrap_or_default().block_number
+            }
+        };
+
+        std::fs::create_dir_all(&self.output)?;
+        remove_partial_files(&self.output)?;
+
+        let checkpoint_path = self.output.join(CHECKPOINT_FILE_NAME);
+        let from = match read_checkpoint(&checkpoint_path)? {
+            Some(checkpoint) if checkpoint.format != self.format => {
+                eyre::bail!("output directory contains an export in {:?} format", checkpoint.format)
+            }
+            Some(checkpoint) => {
+                info!(target: "reth::cli", next_block = checkpoint.next_block, "Resuming export");
+                self.from.max(checkpoint.next_block)
+            }
+            None => self.from,
+        };
+
+        if from > to {
+            info!(target: "reth::cli", from, to, "Nothing to export");
+            return Ok(())
+        }
+
+        info!(target: "reth::cli", from, to, format = ?self.format, "Exporting blocks");
+        let exporter = Exporter { provider, command: &self, checkpoint_path };
+        match self.format {
+            ExportFormat::Era1 => exporter.export_era1(from..=to)?,
+            ExportFormat::Rlp | ExportFormat::Json => exporter.export_rotating(from..=to)?,
+        }
+
+        info!(target: "reth::cli", "Export finished");
+        Ok(())
+    }
+}
+
+/// Reads canonical blocks from the database and writes them to files.
+struct Exporter<'a> {
+    provider: ShareableDatabase<Env<NoWriteMap>>,
+    command: &'a ExportCommand,
+    checkpoint_path: PathBuf,
+}
+
+impl<'a> Exporter<'a> {
+    /// Exports the range as RLP or JSON, starting a new file whenever the maximum file size is
+    /// exceeded.
+    fn export_rotating(&self, range: RangeInclusive<BlockNumber>) -> eyre::Result<()> {
+        let format = self.command.format;
+        let mut file: Option<OutputFile> = None;
+
+        for number in range {
+            let mut buf = Vec::new();
+            match format {
+                ExportFormat::Rlp => self.block(number)?.encode(&mut buf),
+                ExportFormat::Json => {
+                    serde_json::to_writer(&mut buf, &self.json_block(number)?)?;
+                    buf.push(b'\n');
+                }
+                ExportFormat::Era1 => unreachable!("era1 is exported per epoch"),
+            }
+
+            if file.is_none() {
+                file = Some(OutputFile::create(&self.command.output, format, number)?);
+            }
+            let output = file.as_mut().expect("file exists");
+            output.write(number, &buf)?;
+
+            if self.command.max_file_size.map_or(false, |max| output.size >= max) {
+                let output = file.take().expect("file exists");
+                self.finish_file(output)?;
+            }
+        }
+
+        if let Some(output) = file {
+            self.finish_file(output)?;
+        }
+        Ok(())
+    }
+
+    /// Exports the epochs of the range as era1 archives, one per epoch.
+    ///
+    /// Epochs that were already written to the output directory are skipped.
+    fn export_era1(&self, range: RangeInclusive<BlockNumber>) -> eyre::Result<()> {
+        if self.command.max_file_size.is_some() {
+            warn!(target: "reth::cli", "Ignoring max file size, era1 files contain full epochs");
+        }
+
+        let (from, to) = range.into_inner();
+        if self.is_post_merge(to)?.unwrap_or_default() {
+            eyre::bail!(
+                "era1 archives only contain pre-merge blocks, but block {to} is post-merge, set \
+                 --to to a pre-merge block"
+            )
+        }
+        let ends_at_merge = self.is_post_merge(to + 1)?.unwrap_or_default();
+        let epochs = era1_epochs(from, to, ends_at_merge);
+        if epochs.end * EPOCH_LEN <= to {
+            warn!(target: "reth::cli", epoch = epochs.end, "Skipping incomplete epoch");
+        }
+
+        let network = self.command.chain.chain.to_string();
+        for epoch in epochs {
+            if let Some(path) = find_era1_file(&self.command.output, &network, epoch)? {
+                info!(target: "reth::cli", ?path, epoch, "Skipping exported epoch");
+                continue
+            }
+
+            let start = epoch * EPOCH_LEN;
+            let end = ((epoch + 1) * EPOCH_LEN - 1).min(to);
+            let blocks =
+                (start..=end).map(|number| self.era1_block(number)).collect::<eyre::Result<_>>()?;
+            let era = Era1::new(blocks)?;
+
+            let final_path = self.command.output.join(era.file_name(&network));
+            let partial_path = final_path.with_extension(PARTIAL_EXTENSION);
+            era.write_to_file(&partial_path)?;
+            std::fs::rename(&partial_path, &final_path)?;
+            self.save_checkpoint(end + 1)?;
+
+            info!(target: "reth::cli", path = ?final_path, start, end, "Wrote era1 file");
+        }
+        Ok(())
+    }
+
+    /// Returns whether the block is post-merge, or `None` if the block is unknown.
+    fn is_post_merge(&self, number: BlockNumber) -> eyre::Result<Option<bool>> {
+        let Some(header) = self.provider.header_by_number(number)? else { return Ok(None) };
+        let total_difficulty = self.total_difficulty(number)?;
+        let paris = self.command.chain.fork(Hardfork::Paris);
+        Ok(Some(
+            paris.active_at_block(number) ||
+                paris.active_at_ttd(total_difficulty, header.difficulty),
+        ))
+    }
+
+    /// Flushes the file, moves it to its final name and records the progress.
+    fn finish_file(&self, output: OutputFile) -> eyre::Result<()> {
+        let last_block = output.last_block;
+        let path = output.finish()?;
+        self.save_checkpoint(last_block + 1)?;
+        info!(target: "reth::cli", ?path, "Wrote export file");
+        Ok(())
+    }
+
+    fn save_checkpoint(&self, next_block: BlockNumber) -> eyre::Result<()> {
+        let checkpoint = ExportCheckpoint { format: self.command.format, next_block };
+        let tmp = self.checkpoint_path.with_extension(PARTIAL_EXTENSION);
+        std::fs::write(&tmp, serde_json::to_vec(&checkpoint)?)?;
+        std::fs::rename(tmp, &self.checkpoint_path)?;
+        Ok(())
+    }
+
+    fn block(&self, number: BlockNumber) -> eyre::Result<Block> {
+        self.provider
+            .block(BlockHashOrNumber::Number(number))?
+            .ok_or_else(|| eyre::eyre!("block {number} not found"))
+    }
+
+    fn receipts(&self, number: BlockNumber) -> eyre::Result<Vec<Receipt>> {
+        self.provider
+            .receipts_by_block(BlockHashOrNumber::Number(number))?
+            .ok_or_else(|| eyre::eyre!("receipts of block {number} not found"))
+    }
+
+    fn total_difficulty(&self, number: BlockNumber) -> eyre::Result<U256> {
+        self.provider
+            .header_td_by_number(number)?
+            .ok_or_else(|| eyre::eyre!("total difficulty of block {number} not found"))
+    }
+
+    fn era1_block(&self, number: BlockNumber) -> eyre::Result<Era1Block> {
+        let SealedBlock { header, body, ommers, withdrawals } = self.block(number)?.seal_slow();
+        let receipts = self.receipts(number)?.into_iter().map(ReceiptWithBloom::from).collect();
+        let total_difficulty = self.total_difficulty(number)?;
+        Ok(Era1Block {
+            header,
+            body: BlockBody { transactions: body, ommers, withdrawals },
+            receipts,
+            total_difficulty,
+        })
+    }
+
+    fn json_block(&self, number: BlockNumber) -> eyre::Result<JsonBlock> {
+        let block = self.block(number)?;
+        let receipts = self.receipts(number)?;
+        let total_difficulty = self.total_difficulty(number)?;
+        let block_hash = block.header.hash_slow();
+
+        let rpc_receipts = block
+            .body
+            .iter()
+            .zip(receipts.iter())
+            .enumerate()
+            .map(|(index, (tx, receipt))| {
+                let meta = TransactionMeta {
+                    tx_hash: tx.hash(),
+                    index: index as u64,
+                    block_hash,
+                    block_number: number,
+                    base_fee: block.base_fee_per_gas,
+                };
+                build_transaction_receipt_with_block_receipts(
+                    tx.clone(),
+                    meta,
+                    receipt.clone(),
+                    &receipts,
+                )
+                .wrap_err_with(|| format!("invalid transaction in block {number}"))
+            })
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        let block = RpcBlock::from_block_full(block, total_difficulty, Some(block_hash))
+            .wrap_err_with(|| format!("invalid block {number}"))?;
+
+        Ok(JsonBlock { block, receipts: rpc_receipts })
+    }
+}
+
+/// An output file that is being written.
+///
+/// The file is written under a temporary name and only moved to its final name, which includes
+/// the exported block range, once it is complete.
+struct OutputFile {
+    writer: BufWriter<File>,
+    dir: PathBuf,
+    format: ExportFormat,
+    partial_path: PathBuf,
+    first_block: BlockNumber,
+    last_block: BlockNumber,
+    size: u64,
+}
+
+impl OutputFile {
+    fn create(dir: &Path, format: ExportFormat, first_block: BlockNumber) -> eyre::Result<Self> {
+        let partial_path = dir.join(format!("blocks-{first_block:010}.{PARTIAL_EXTENSION}"));
+        let writer = BufWriter::new(File::create(&partial_path)?);
+        Ok(Self {
+            writer,
+            dir: dir.to_path_buf(),
+            format,
+            partial_path,
+            first_block,
+            last_block: first_block,
+            size: 0,
+        })
+    }
+
+    fn write(&mut self, number: BlockNumber, buf: &[u8]) -> eyre::Result<()> {
+        self.writer.write_all(buf)?;
+        self.size += buf.len() as u64;
+        self.last_block = number;
+        Ok(())
+    }
+
+    /// Flushes the file and moves it to its final name.
+    fn finish(mut self) -> eyre::Result<PathBuf> {
+        self.writer.flush()?;
+        let path = self.dir.join(format!(
+            "blocks-{:010}-{:010}.{}",
+            self.first_block,
+            self.last_block,
+            self.format.extension()
+        ));
+        std::fs::rename(&self.partial_path, &path)?;
+        Ok(path)
+    }
+}
+
+/// The number of blocks in an era1 epoch.
+const EPOCH_LEN: u64 = MAX_BLOCKS_PER_ERA1 as u64;
+
+/// Returns the era1 epochs to export for the pre-merge block range `from..=to`.
+///
+/// All epochs that overlap the range are included, except for an incomplete last epoch. If `to`
+/// is the last pre-merge block, the last epoch is included even if it's incomplete.
+fn era1_epochs(from: BlockNumber, to: BlockNumber, ends_at_merge: bool) -> Range<u64> {
+    let end = if ends_at_merge { to / EPOCH_LEN + 1 } else { (to + 1) / EPOCH_LEN };
+    from / EPOCH_LEN..end
+}
+
+/// Returns the path of the era1 file of the given epoch in the directory, if it exists.
+fn find_era1_file(dir: &Path, network: &str, epoch: u64) -> eyre::Result<Option<PathBuf>> {
+    let prefix = format!("{network}-{epoch:05}-");
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_match = path.extension().map_or(false, |ext| ext == ExportFormat::Era1.extension()) &&
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .map_or(false, |name| name.starts_with(&prefix));
+        if is_match {
+            return Ok(Some(path))
+        }
+    }
+    Ok(None)
+}
+
+/// Reads the export checkpoint, if any.
+fn read_checkpoint(path: &Path) -> eyre::Result<Option<ExportCheckpoint>> {
+    if !path.exists() {
+        return Ok(None)
+    }
+    let checkpoint = serde_json::from_slice(&std::fs::read(path)?)
+        .wrap_err_with(|| format!("Could not read export checkpoint {path:?}"))?;
+    Ok(Some(checkpoint))
+}
+
+/// Removes files of an interrupted export that were not completed.
+fn remove_partial_files(dir: &Path) -> eyre::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().map_or(false, |ext| ext == PARTIAL_EXTENSION) {
+            warn!(target: "reth::cli", ?path, "Removing incomplete export file");
+            std::fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_export_command() {
+        let args: ExportCommand = ExportCommand::parse_from([
+            "reth",
+            "--from",
+            "100",
+            "--to",
+            "200",
+            "--format",
+            "era1",
+            "--max-file-size",
+            "1000",
+            "out",
+        ]);
+        assert_eq!(args.from, 100);
+        assert_eq!(args.to, Some(200));
+        assert_eq!(args.format, ExportFormat::Era1);
+        assert_eq!(args.max_file_size, Some(1000));
+        assert_eq!(args.output, PathBuf::from("out"));
+    }
+
+    #[test]
+    fn checkpoint_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CHECKPOINT_FILE_NAME);
+        assert_eq!(read_checkpoint(&path).unwrap(), None);
+
+        let checkpoint = ExportCheckpoint { format: ExportFormat::Json, next_block: 42 };
+        std::fs::write(&path, serde_json::to_vec(&checkpoint).unwrap()).unwrap();
+        assert_eq!(read_checkpoint(&path).unwrap(), Some(checkpoint));
+    }
+
+    #[test]
+    fn era1_export_epochs() {
+        // only full epochs are exported, the range is extended to the start of the first epoch
+        assert_eq!(era1_epochs(0, EPOCH_LEN - 2, false), 0..0);
+        assert_eq!(era1_epochs(0, EPOCH_LEN - 1, false), 0..1);
+        assert_eq!(era1_epochs(10, 3 * EPOCH_LEN + 5, false), 0..3);
+        assert_eq!(era1_epochs(EPOCH_LEN + 1, 3 * EPOCH_LEN - 1, false), 1..3);
+
+        // the last pre-merge epoch may be incomplete
+        assert_eq!(era1_epochs(0, 3 * EPOCH_LEN + 5, true), 0..4);
+        assert_eq!(era1_epochs(0, 3 * EPOCH_LEN - 1, true), 0..3);
+    }
+
+    #[test]
+    fn find_exported_era1_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("mainnet-00001-aabbccdd.era1"), []).unwrap();
+        std::fs::write(dir.path().join("mainnet-00002-aabbccdd.partial"), []).unwrap();
+
+        assert_eq!(
+            find_era1_file(dir.path(), "mainnet", 1).unwrap(),
+            Some(dir.path().join("mainnet-00001-aabbccdd.era1"))
+        );
+        assert_eq!(find_era1_file(dir.path(), "mainnet", 2).unwrap(), None);
+        assert_eq!(find_era1_file(dir.path(), "goerli", 1).unwrap(), None);
+    }
+
+    #[test]
+    fn rotated_file_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut file = OutputFile::create(dir.path(), ExportFormat::Rlp, 5).unwrap();
+        file.write(5, &[1, 2]).unwrap();
+        file.write(6, &[3]).unwrap();
+        assert_eq!(file.size, 3);
+
+        let path = file.finish().unwrap();
+        assert_eq!(path, dir.path().join("blocks-0000000005-0000000006.rlp"));
+        assert_eq!(std::fs::read(path).unwrap(), vec![1, 2, 3]);
+
+        // partial files are removed on resume
+        OutputFile::create(dir.path(), ExportFormat::Rlp, 7).unwrap();
+        remove_partial_files(dir.path()).unwrap();
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::{
+    args::{CliqueArgs, EthashArgs},
     dirs::{DataDirPath, MaybePlatformPath},
     node::events::{handle_events, NodeEvent},
     version::SHORT_VERSION,
@@ -7,15 +8,18 @@ use clap::Parser;
 use eyre::Context;
 use futures::{Stream, StreamExt};
 use reth_beacon_consensus::BeaconConsensus;
-
 use reth_config::Config;
 use reth_db::database::Database;
 use reth_downloaders::{
     bodies::bodies::BodiesDownloaderBuilder,
-    headers::reverse_headers::ReverseHeadersDownloaderBuilder, test_utils::FileClient,
+    era::{accumulator::TrustedRoots, Era1, Era1Block},
+    file_client::{ChunkedFileReader, FileClient},
+    headers::reverse_headers::ReverseHeadersDownloaderBuilder,
 };
 use reth_interfaces::consensus::Consensus;
-use reth_primitives::{ChainSpec, H256};
+use reth_primitives::{stage::StageId, ChainSpec, H256};
+use reth_provider::providers::get_stage_checkpoint;
+use reth_revm::parallel::ParallelExecutionConfig;
 use reth_staged_sync::utils::{
     chainspec::genesis_value_parser,
     init::{init_db, init_genesis},
@@ -27,11 +31,14 @@ use reth_stages::{
         TotalDifficultyStage,
     },
 };
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use tokio::sync::watch;
 use tracing::{debug, info};
 
-/// Syncs RLP encoded blocks from a file.
+/// Syncs RLP encoded blocks or era1 archives from one or more files.
 #[derive(Debug, Parser)]
 pub struct ImportCommand {
     /// The path to the configuration file to use.
@@ -56,6 +63,8 @@ pub struct ImportCommand {
     /// - mainnet
     /// - goerli
     /// - sepolia
+    /// - holesky
+    /// - devnet:<seed>, a generated development chain
     #[arg(
         long,
         value_name = "CHAIN_OR_PATH",
@@ -65,12 +74,37 @@ pub struct ImportCommand {
     )]
     chain: Arc<ChainSpec>,
 
-    /// The path to a block file for import.
+    /// Chunk byte length to read RLP block files with.
+    ///
+    /// Large files are imported one chunk at a time, so this bounds the amount of blocks held in
+    /// memory. Defaults to 1GB.
+    #[arg(long, value_name = "CHUNK_LEN", verbatim_doc_comment)]
+    chunk_len: Option<u64>,
+
+    #[clap(flatten)]
+    ethash: EthashArgs,
+
+    #[clap(flatten)]
+    clique: CliqueArgs,
+
+    /// The file with the trusted accumulator roots of the era1 epochs.
+    ///
+    /// One hex encoded root per line, in epoch order starting at epoch zero. Required to import
+    /// era1 archives.
+    #[arg(long = "era1.trusted-roots", value_name = "FILE", verbatim_doc_comment)]
+    era1_trusted_roots: Option<PathBuf>,
+
+    /// The paths to the files to import, in order.
+    ///
+    /// Files with the `.era1` extension are read as era1 archives. Each archive is checked for
+    /// internal consistency, and its accumulator must match the trusted root of its epoch, see
+    /// `--era1.trusted-roots`. All other files are read as concatenated RLP encoded blocks.
+    /// Directories are expanded to the files they contain, sorted by name.
     ///
     /// The online stages (headers and bodies) are replaced by a file import, after which the
     /// remaining stages are executed.
-    #[arg(value_name = "IMPORT_PATH", verbatim_doc_comment)]
-    path: PathBuf,
+    #[arg(value_name = "IMPORT_PATH", verbatim_doc_comment, required = true, num_args = 1..)]
+    paths: Vec<PathBuf>,
 }
 
 impl ImportCommand {
@@ -95,19 +129,100 @@ impl ImportCommand {
 
         init_genesis(db.clone(), self.chain.clone())?;
 
-        let consensus = Arc::new(BeaconConsensus::new(self.chain.clone()));
+        let consensus: Arc<dyn Consensus> =
+            if let Some(consensus) = self.clique.consensus(&self.chain, db.clone())? {
+                consensus
+            } else {
+                let consensus = BeaconConsensus::new(self.chain.clone());
+                self.ethash.consensus(consensus, &self.chain, data_dir.ethash_path())
+            };
         info!(target: "reth::cli", "Consensus engine initialized");
 
-        // create a new FileClient
-        info!(target: "reth::cli", "Importing chain file");
-        let file_client = Arc::new(FileClient::new(&self.path).await?);
+        let files = expand_import_paths(&self.paths)?;
+        let trusted_roots = match &self.era1_trusted_roots {
+            Some(path) => Arc::new(
+                TrustedRoots::load(path)
+                    .wrap_err_with(|| format!("Could not read trusted roots {path:?}"))?,
+            ),
+            None if files.iter().any(|path| is_era1_file(path)) => {
+                eyre::bail!("importing era1 archives requires --era1.trusted-roots")
+            }
+            None => Default::default(),
+        };
 
-        // override the tip
-        let tip = file_client.tip().expect("file client has no tip");
-        info!(target: "reth::cli", "Chain file imported");
+        tokio::select! {
+            res = self.import_files(&files, &trusted_roots, &config, &db, &consensus) => res?,
+            _ = tokio::signal::ctrl_c() => {},
+        };
+
+        info!(target: "reth::cli", "Finishing up");
+        Ok(())
+    }
+
+    /// Imports the given files one after another.
+    async fn import_files<DB>(
+        &self,
+        files: &[PathBuf],
+        trusted_roots: &Arc<TrustedRoots>,
+        config: &Config,
+        db: &DB,
+        consensus: &Arc<dyn Consensus>,
+    ) -> eyre::Result<()>
+    where
+        DB: Database + Clone + Unpin + 'static,
+    {
+        for path in files {
+            info!(target: "reth::cli", ?path, "Importing chain file");
+
+            if is_era1_file(path) {
+                let era = read_era1(path.clone(), trusted_roots.clone()).await?;
+                let file_client =
+                    FileClient::from_blocks(era.blocks.into_iter().map(Era1Block::into_block));
+                self.import_client(config, db, consensus, file_client).await?;
+            } else {
+                let mut reader = ChunkedFileReader::new(path, self.chunk_len).await?;
+                while let Some(file_client) = reader.next_chunk().await? {
+                    self.import_client(config, db, consensus, file_client).await?;
+                }
+            }
+
+            info!(target: "reth::cli", ?path, "Chain file imported");
+        }
+        Ok(())
+    }
+
+    /// Runs the import pipeline until all blocks of the given client are synced.
+    async fn import_client<DB>(
+        &self,
+        config: &Config,
+        db: &DB,
+        consensus: &Arc<dyn Consensus>,
+        file_client: FileClient,
+    ) -> eyre::Result<()>
+    where
+        DB: Database + Clone + Unpin + 'static,
+    {
+        let (Some(tip), Some(max_block)) = (file_client.tip(), file_client.max_block()) else {
+            return Ok(())
+        };
 
-        let (mut pipeline, events) =
-            self.build_import_pipeline(config, db, &consensus, file_client).await?;
+        let synced =
+            get_stage_checkpoint(&db.tx()?, StageId::Finish)?.unwrap_or_default().block_number;
+        if max_block <= synced {
+            debug!(target: "reth::cli", max_block, synced, "Skipping already imported blocks");
+            return Ok(())
+        }
+
+        info!(
+            target: "reth::cli",
+            from = file_client.min_block(),
+            to = max_block,
+            "Importing blocks"
+        );
+
+        let (mut pipeline, events) = self
+            .build_import_pipeline(config.clone(), db.clone(), consensus, Arc::new(file_client))
+            .await?;
 
         // override the tip
         pipeline.set_tip(tip);
@@ -117,25 +232,20 @@ impl ImportCommand {
 
         // Run pipeline
         info!(target: "reth::cli", "Starting sync pipeline");
-        tokio::select! {
-            res = pipeline.run() => res?,
-            _ = tokio::signal::ctrl_c() => {},
-        };
+        pipeline.run().await?;
 
-        info!(target: "reth::cli", "Finishing up");
         Ok(())
     }
 
-    async fn build_import_pipeline<DB, C>(
+    async fn build_import_pipeline<DB>(
         &self,
         config: Config,
         db: DB,
-        consensus: &Arc<C>,
+        consensus: &Arc<dyn Consensus>,
         file_client: Arc<FileClient>,
     ) -> eyre::Result<(Pipeline<DB>, impl Stream<Item = NodeEvent>)>
     where
         DB: Database + Clone + Unpin + 'static,
-        C: Consensus + 'static,
     {
         if !file_client.has_canonical_blocks() {
             eyre::bail!("unable to import non canonical blocks");
@@ -150,7 +260,10 @@ impl ImportCommand {
             .into_task();
 
         let (tip_tx, tip_rx) = watch::channel(H256::zero());
-        let factory = reth_revm::Factory::new(self.chain.clone());
+        let mut factory = reth_revm::Factory::new(self.chain.clone());
+        if let Some(workers) = config.stages.execution.parallel_workers {
+            factory = factory.with_parallel_execution(ParallelExecutionConfig::new(workers));
+        }
 
         let mut pipeline = Pipeline::builder()
             .with_tip_sender(tip_tx)
@@ -194,6 +307,40 @@ impl ImportCommand {
     }
 }
 
+/// Returns true if the file is an era1 archive.
+fn is_era1_file(path: &Path) -> bool {
+    path.extension().map_or(false, |ext| ext == "era1")
+}
+
+/// Expands directories to the files they contain, sorted by name.
+fn expand_import_paths(paths: &[PathBuf]) -> eyre::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let mut entries = std::fs::read_dir(path)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<Vec<_>, _>>()?;
+            entries.retain(|entry| entry.is_file());
+            entries.sort();
+            files.extend(entries);
+        } else {
+            files.push(path.clone());
+        }
+    }
+    Ok(files)
+}
+
+/// Reads an era1 archive and checks it against the trusted accumulator roots.
+async fn read_era1(path: PathBuf, trusted_roots: Arc<TrustedRoots>) -> eyre::Result<Era1> {
+    tokio::task::spawn_blocking(move || {
+        let era = Era1::open(&path).wrap_err_with(|| format!("Could not read {path:?}"))?;
+        era.verify_trusted(&trusted_roots)
+            .wrap_err_with(|| format!("Invalid era1 file {path:?}"))?;
+        Ok(era)
+    })
+    .await?
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -205,4 +352,19 @@ mod tests {
             assert_eq!(args.chain.chain, chain.parse().unwrap());
         }
     }
+
+    #[test]
+    fn parse_multiple_import_paths() {
+        let args: ImportCommand =
+            ImportCommand::parse_from(["reth", "--chunk-len", "1000", "a.rlp", "b.era1"]);
+        assert_eq!(args.chunk_len, Some(1000));
+        assert_eq!(args.paths, vec![PathBuf::from("a.rlp"), PathBuf::from("b.era1")]);
+        assert!(!is_era1_file(&args.paths[0]));
+        assert!(is_era1_file(&args.paths[1]));
+        assert_eq!(args.era1_trusted_roots, None);
+
+        let args: ImportCommand =
+            ImportCommand::parse_from(["reth", "--era1.trusted-roots", "roots.txt", "b.era1"]);
+        assert_eq!(args.era1_trusted_roots, Some(PathBuf::from("roots.txt")));
+    }
 }
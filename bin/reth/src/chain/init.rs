@@ -29,6 +29,8 @@ pub struct InitCommand {
     /// - mainnet
     /// - goerli
     /// - sepolia
+    /// - holesky
+    /// - devnet:<seed>, a generated development chain
     #[arg(
         long,
         value_name = "CHAIN_OR_PATH",
@@ -30,6 +30,7 @@ pub fn run() -> eyre::Result<()> {
         Commands::Node(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
         Commands::Init(command) => runner.run_blocking_until_ctrl_c(command.execute()),
         Commands::Import(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+        Commands::Export(command) => runner.run_blocking_until_ctrl_c(command.execute()),
         Commands::Db(command) => runner.run_blocking_until_ctrl_c(command.execute()),
         Commands::Stage(command) => runner.run_blocking_until_ctrl_c(command.execute()),
         Commands::P2P(command) => runner.run_until_ctrl_c(command.execute()),
@@ -51,6 +52,9 @@ pub enum Commands {
     /// This syncs RLP encoded blocks from a file.
     #[command(name = "import")]
     Import(chain::ImportCommand),
+    /// This exports canonical blocks and receipts to files.
+    #[command(name = "export")]
+    Export(chain::ExportCommand),
     /// Database debugging utilities
     #[command(name = "db")]
     Db(db::Command),
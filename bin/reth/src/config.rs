@@ -1,9 +1,11 @@
 //! CLI command to show configs
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::Arc};
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use eyre::{bail, WrapErr};
 use reth_config::Config;
+use reth_primitives::{ChainSpec, ForkCondition, ForkHash, ForkId, Hardfork, Head};
+use reth_staged_sync::utils::chainspec::genesis_value_parser;
 
 /// `reth config` command
 #[derive(Debug, Parser)]
@@ -15,11 +17,25 @@ pub struct Command {
     /// Show the default config
     #[arg(long, verbatim_doc_comment, conflicts_with = "config")]
     default: bool,
+
+    #[clap(subcommand)]
+    command: Option<Subcommands>,
+}
+
+#[derive(Subcommand, Debug)]
+/// `reth config` subcommands
+pub enum Subcommands {
+    /// Show the chain specification derived from a genesis file
+    Genesis(GenesisCommand),
 }
 
 impl Command {
     /// Execute `config` command
     pub async fn execute(&self) -> eyre::Result<()> {
+        if let Some(Subcommands::Genesis(command)) = &self.command {
+            return command.execute()
+        }
+
         let config = if self.default {
             Config::default()
         } else {
@@ -35,3 +51,174 @@ impl Command {
         Ok(())
     }
 }
+
+/// `reth config genesis` command
+#[derive(Debug, Parser)]
+pub struct GenesisCommand {
+    /// The chain to show.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification or geth
+    /// genesis file.
+    ///
+    /// Built-in chains:
+    /// - mainnet
+    /// - goerli
+    /// - sepolia
+    /// - holesky
+    /// - devnet:<seed>, a generated development chain
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        verbatim_doc_comment,
+        default_value = "mainnet",
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    /// Validate the fork schedule and print it together with the fork hashes, instead of the
+    /// chain specification.
+    #[arg(long)]
+    validate: bool,
+}
+
+impl GenesisCommand {
+    /// Execute `config genesis` command
+    pub fn execute(&self) -> eyre::Result<()> {
+        if !self.validate {
+            println!("{}", serde_json::to_string_pretty(&*self.chain)?);
+            return Ok(())
+        }
+
+        validate_fork_order(&self.chain)?;
+
+        let genesis_hash = self.chain.genesis_hash();
+        println!("Chain:        {}", self.chain.chain);
+        println!("Genesis hash: {genesis_hash:?}");
+        println!("Fork hash:    {}", format_fork_hash(ForkHash::from(genesis_hash)));
+        println!();
+
+        for (fork, condition) in self.chain.forks_iter() {
+            let Some((activation, head)) = fork_activation(&self.chain, condition) else {
+                continue
+            };
+            let fork_id = match head {
+                Some(head) => format_fork_id(self.chain.fork_id(&head)),
+                None => "not part of the fork id".to_string(),
+            };
+            println!("{:<16} {activation:<48} {fork_id}", fork.to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the description of the fork's activation, and the first head it is active at if the
+/// fork is part of the fork id.
+fn fork_activation(chain: &ChainSpec, condition: ForkCondition) -> Option<(String, Option<Head>)> {
+    let genesis_timestamp = chain.genesis().timestamp;
+    Some(match condition {
+        ForkCondition::Block(block) => (
+            format!("block {block}"),
+            Some(Head { number: block, timestamp: genesis_timestamp, ..Default::default() }),
+        ),
+        ForkCondition::TTD { total_difficulty, fork_block: Some(block) } => (
+            format!("total difficulty {total_difficulty}, block {block}"),
+            Some(Head { number: block, timestamp: genesis_timestamp, ..Default::default() }),
+        ),
+        ForkCondition::TTD { total_difficulty, fork_block: None } => {
+            (format!("total difficulty {total_difficulty}"), None)
+        }
+        // timestamp forks are activated after all block forks
+        ForkCondition::Timestamp(timestamp) => (
+            format!("timestamp {timestamp}"),
+            Some(Head { number: u64::MAX, timestamp, ..Default::default() }),
+        ),
+        ForkCondition::Never => return None,
+    })
+}
+
+/// Checks that the forks are scheduled in the order they were introduced in: block-based forks
+/// must not activate before the forks preceding them, and must not follow a timestamp-based fork.
+fn validate_fork_order(chain: &ChainSpec) -> eyre::Result<()> {
+    let mut last_block: Option<(Hardfork, u64)> = None;
+    let mut last_timestamp: Option<(Hardfork, u64)> = None;
+
+    for (fork, condition) in chain.forks_iter() {
+        match condition {
+            ForkCondition::Block(block) | ForkCondition::TTD { fork_block: Some(block), .. } => {
+                if let Some((previous, _)) = last_timestamp {
+                    bail!("{fork} is scheduled by block after {previous}, which uses a timestamp")
+                }
+                if let Some((previous, previous_block)) = last_block {
+                    if block < previous_block {
+                        bail!(
+                            "{fork} at block {block} is scheduled before {previous} at block {}",
+                            previous_block
+                        )
+                    }
+                }
+                last_block = Some((fork, block));
+            }
+            ForkCondition::Timestamp(timestamp) => {
+                if let Some((previous, previous_timestamp)) = last_timestamp {
+                    if timestamp < previous_timestamp {
+                        bail!(
+                            "{fork} at timestamp {timestamp} is scheduled before {previous} at {}",
+                            previous_timestamp
+                        )
+                    }
+                }
+                last_timestamp = Some((fork, timestamp));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn format_fork_hash(hash: ForkHash) -> String {
+    format!("0x{}", hex::encode(hash.0))
+}
+
+fn format_fork_id(fork_id: ForkId) -> String {
+    format!("{} (next: {})", format_fork_hash(fork_id.hash), fork_id.next)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Chain, Genesis, MAINNET};
+
+    #[test]
+    fn parse_genesis_command() {
+        let command =
+            Command::try_parse_from(["reth", "genesis", "--chain", "sepolia", "--validate"])
+                .unwrap();
+        assert!(matches!(
+            command.command,
+            Some(Subcommands::Genesis(GenesisCommand { validate: true, .. }))
+        ));
+    }
+
+    #[test]
+    fn validates_fork_order() {
+        validate_fork_order(&MAINNET).unwrap();
+
+        let spec = ChainSpec::builder()
+            .chain(Chain::mainnet())
+            .genesis(Genesis::default())
+            .with_fork(Hardfork::Homestead, ForkCondition::Block(10))
+            .with_fork(Hardfork::Byzantium, ForkCondition::Block(5))
+            .build();
+        assert!(validate_fork_order(&spec).is_err());
+
+        let spec = ChainSpec::builder()
+            .chain(Chain::mainnet())
+            .genesis(Genesis::default())
+            .with_fork(Hardfork::Shanghai, ForkCondition::Timestamp(10))
+            .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(5))
+            .build();
+        assert!(validate_fork_order(&spec).is_err());
+    }
+}
@@ -66,7 +66,8 @@ impl Command {
             AccountsTrie,
             TxSenders,
             SyncStage,
-            SyncStageProgress
+            SyncStageProgress,
+            CliqueSnapshots
         ]);
     }
 
@@ -38,6 +38,8 @@ pub struct Command {
     /// - mainnet
     /// - goerli
     /// - sepolia
+    /// - holesky
+    /// - devnet:<seed>, a generated development chain
     #[arg(
         long,
         value_name = "CHAIN_OR_PATH",
@@ -211,7 +213,8 @@ impl Command {
                     StoragesTrie,
                     TxSenders,
                     SyncStage,
-                    SyncStageProgress
+                    SyncStageProgress,
+                    CliqueSnapshots
                 ]);
             }
             Subcommands::Get(command) => {
@@ -5,6 +5,7 @@ use crate::runner::CliContext;
 
 mod execution;
 mod merkle;
+mod stateless;
 
 /// `reth debug` command
 #[derive(Debug, Parser)]
@@ -20,6 +21,8 @@ pub enum Subcommands {
     Execution(execution::Command),
     /// Debug the clean & incremental state root calculations.
     Merkle(merkle::Command),
+    /// Record the execution witness of a block and verify the block statelessly.
+    Stateless(stateless::Command),
 }
 
 impl Command {
@@ -28,6 +31,7 @@ impl Command {
         match self.command {
             Subcommands::Execution(command) => command.execute(ctx).await,
             Subcommands::Merkle(command) => command.execute().await,
+            Subcommands::Stateless(command) => command.execute().await,
         }
     }
 }
@@ -0,0 +1,94 @@
+//! Command for debugging the stateless verification of blocks.
+use crate::dirs::{DataDirPath, MaybePlatformPath};
+use clap::Parser;
+use eyre::bail;
+use reth_primitives::{BlockHashOrNumber, ChainSpec};
+use reth_provider::{BlockProvider, HeaderProvider, ShareableDatabase};
+use reth_revm::witness::{execution_witness, verify_stateless};
+use reth_staged_sync::utils::{chainspec::genesis_value_parser, init::init_db};
+use std::{path::PathBuf, sync::Arc};
+use tracing::info;
+
+/// `reth debug stateless` command
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    ///
+    /// Built-in chains:
+    /// - mainnet
+    /// - goerli
+    /// - sepolia
+    /// - holesky
+    /// - devnet:<seed>, a generated development chain
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        verbatim_doc_comment,
+        default_value = "mainnet",
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    /// The block to verify.
+    #[arg(long)]
+    block: u64,
+
+    /// Write the execution witness of the block as JSON to the given file.
+    #[arg(long, value_name = "FILE")]
+    witness: Option<PathBuf>,
+}
+
+impl Command {
+    /// Execute `debug stateless` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        if self.block == 0 {
+            bail!("The genesis block can not be executed")
+        }
+
+        // add network name to data dir
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let db = Arc::new(init_db(data_dir.db_path())?);
+        let provider = ShareableDatabase::new(db, self.chain.clone());
+
+        let block = provider
+            .block(BlockHashOrNumber::Number(self.block))?
+            .ok_or_else(|| eyre::eyre!("block {} not found", self.block))?;
+        let total_difficulty = provider
+            .header_td_by_number(self.block)?
+            .ok_or_else(|| eyre::eyre!("total difficulty of block {} not found", self.block))?;
+
+        let state = provider.history_by_block_number(self.block - 1)?;
+        let witness =
+            execution_witness(self.chain.clone(), &block, total_difficulty, state, |oldest| {
+                provider.headers_range(oldest..self.block)
+            })?;
+        info!(
+            target: "reth::cli",
+            nodes = witness.state.len(),
+            codes = witness.codes.len(),
+            headers = witness.headers.len(),
+            "Recorded execution witness"
+        );
+
+        if let Some(path) = &self.witness {
+            std::fs::write(path, serde_json::to_string_pretty(&witness)?)?;
+            info!(target: "reth::cli", path = %path.display(), "Wrote execution witness");
+        }
+
+        let state_root = verify_stateless(self.chain, &block, total_difficulty, &witness)?;
+        info!(target: "reth::cli", block = self.block, ?state_root, "Verified block statelessly");
+        Ok(())
+    }
+}
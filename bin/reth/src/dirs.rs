@@ -283,6 +283,22 @@ impl<D> ChainPath<D> {
     pub fn jwt_path(&self) -> PathBuf {
         self.0.join("jwt.hex").into()
     }
+
+    /// Returns the path to the blob sidecar store of the transaction pool for this chain.
+    pub fn blobstore_path(&self) -> PathBuf {
+        self.0.join("blobstore").into()
+    }
+
+    /// Returns the path to the journal of local transactions of the transaction pool for this
+    /// chain.
+    pub fn txpool_journal_path(&self) -> PathBuf {
+        self.0.join("txpool-journal.rlp").into()
+    }
+
+    /// Returns the path to the ethash verification caches for this chain.
+    pub fn ethash_path(&self) -> PathBuf {
+        self.0.join("ethash").into()
+    }
 }
 
 impl<D> AsRef<Path> for ChainPath<D> {
@@ -2,18 +2,20 @@
 //!
 //! Starts the client
 use crate::{
-    args::{get_secret_key, DebugArgs, NetworkArgs, RpcServerArgs},
+    args::{
+        get_secret_key, CliqueArgs, DebugArgs, EthashArgs, NetworkArgs, RpcServerArgs, TxPoolArgs,
+    },
     dirs::DataDirPath,
     prometheus_exporter,
     runner::CliContext,
-    utils::get_single_header,
+    utils::{get_single_header, parse_duration_from_secs},
     version::SHORT_VERSION,
 };
 use clap::Parser;
 use eyre::Context;
 use fdlimit::raise_fd_limit;
 use futures::{pin_mut, stream::select as stream_select, StreamExt};
-use reth_auto_seal_consensus::{AutoSealBuilder, AutoSealConsensus};
+use reth_auto_seal_consensus::{AutoSealBuilder, AutoSealConsensus, MiningMode};
 use reth_basic_payload_builder::{BasicPayloadJobGenerator, BasicPayloadJobGeneratorConfig};
 use reth_beacon_consensus::{BeaconConsensus, BeaconConsensusEngine};
 use reth_blockchain_tree::{
@@ -48,7 +50,7 @@ use reth_provider::{
     providers::get_stage_checkpoint, BlockProvider, CanonStateSubscriptions, HeaderProvider,
     ShareableDatabase,
 };
-use reth_revm::Factory;
+use reth_revm::{parallel::ParallelExecutionConfig, Factory};
 use reth_revm_inspectors::stack::Hook;
 use reth_rpc_engine_api::EngineApi;
 use reth_staged_sync::utils::{
@@ -64,19 +66,20 @@ use reth_stages::{
     },
 };
 use reth_tasks::TaskExecutor;
-use reth_transaction_pool::{EthTransactionValidator, TransactionPool};
+use reth_transaction_pool::{DiskFileBlobStore, EthTransactionValidator, TransactionPool};
 use secp256k1::SecretKey;
 use std::{
     net::{Ipv4Addr, SocketAddr, SocketAddrV4},
     path::PathBuf,
     sync::Arc,
+    time::Duration,
 };
 use tokio::sync::{mpsc::unbounded_channel, oneshot, watch};
 use tracing::*;
 
 use crate::{args::PayloadBuilderArgs, dirs::MaybePlatformPath};
 use reth_interfaces::p2p::headers::client::HeadersClient;
-use reth_payload_builder::PayloadBuilderService;
+use reth_payload_builder::{BundleStore, PayloadBuilderService};
 use reth_primitives::bytes::BytesMut;
 use reth_provider::providers::BlockchainProvider;
 use reth_rlp::Encodable;
@@ -108,6 +111,8 @@ pub struct Command {
     /// - mainnet
     /// - goerli
     /// - sepolia
+    /// - holesky
+    /// - devnet:<seed>, a generated development chain
     #[arg(
         long,
         value_name = "CHAIN_OR_PATH",
@@ -135,9 +140,27 @@ pub struct Command {
     #[clap(flatten)]
     debug: DebugArgs,
 
+    #[clap(flatten)]
+    ethash: EthashArgs,
+
+    #[clap(flatten)]
+    clique: CliqueArgs,
+
+    #[clap(flatten)]
+    txpool: TxPoolArgs,
+
     /// Automatically mine blocks for new transactions
     #[arg(long)]
     auto_mine: bool,
+
+    /// The interval between automatically mined blocks.
+    #[arg(
+        long = "auto-mine.block-time",
+        value_parser = parse_duration_from_secs,
+        default_value = "1",
+        value_name = "SECONDS"
+    )]
+    auto_mine_block_time: Duration,
 }
 
 impl Command {
@@ -172,8 +195,12 @@ impl Command {
         let consensus: Arc<dyn Consensus> = if self.auto_mine {
             debug!(target: "reth::cli", "Using auto seal");
             Arc::new(AutoSealConsensus::new(Arc::clone(&self.chain)))
+        } else if let Some(consensus) = self.clique.consensus(&self.chain, Arc::clone(&db))? {
+            debug!(target: "reth::cli", "Using clique consensus");
+            consensus
         } else {
-            Arc::new(BeaconConsensus::new(Arc::clone(&self.chain)))
+            let consensus = BeaconConsensus::new(Arc::clone(&self.chain));
+            self.ethash.consensus(consensus, &self.chain, data_dir.ethash_path())
         };
 
         self.init_trusted_nodes(&mut config);
@@ -200,9 +227,13 @@ impl Command {
         let shareable_db = ShareableDatabase::new(Arc::clone(&db), Arc::clone(&self.chain));
         let blockchain_db = BlockchainProvider::new(shareable_db, blockchain_tree.clone())?;
 
+        let blob_store = DiskFileBlobStore::open(data_dir.blobstore_path())?;
+        let pool_config = self.txpool.pool_config(data_dir.txpool_journal_path());
         let transaction_pool = reth_transaction_pool::Pool::eth_pool(
-            EthTransactionValidator::new(blockchain_db.clone(), Arc::clone(&self.chain)),
-            Default::default(),
+            EthTransactionValidator::new(blockchain_db.clone(), Arc::clone(&self.chain))
+                .with_max_tx_size(pool_config.max_tx_size),
+            Arc::new(blob_store),
+            pool_config,
         );
         info!(target: "reth::cli", "Transaction pool initialized");
 
@@ -218,6 +249,7 @@ impl Command {
                         client,
                         pool,
                         chain_events,
+                        Default::default(),
                     )
                     .await
                 }),
@@ -225,6 +257,15 @@ impl Command {
             debug!(target: "reth::cli", "Spawned txpool maintenance task");
         }
 
+        // spawn task that journals local transactions
+        if let Some(journal) = transaction_pool.config().journal.clone() {
+            let pool = transaction_pool.clone();
+            ctx.task_executor.spawn(Box::pin(async move {
+                reth_transaction_pool::maintain::journal_local_transactions(pool, journal).await
+            }));
+            debug!(target: "reth::cli", "Spawned txpool journal task");
+        }
+
         info!(target: "reth::cli", "Connecting to P2P network");
         let network_secret_path =
             self.network.p2p_secret_key.clone().unwrap_or_else(|| data_dir.p2p_secret_path());
@@ -255,6 +296,8 @@ impl Command {
         // configure the payload builder
         let mut extradata = BytesMut::new();
         self.builder.extradata.as_bytes().encode(&mut extradata);
+        // bundles submitted via the `mev` rpc namespace
+        let bundle_store = BundleStore::default();
         let payload_generator = BasicPayloadJobGenerator::new(
             blockchain_db.clone(),
             transaction_pool.clone(),
@@ -266,7 +309,8 @@ impl Command {
                 .extradata(extradata.freeze())
                 .max_gas_limit(self.builder.max_gas_limit),
             Arc::clone(&self.chain),
-        );
+        )
+        .with_bundle_store(bundle_store.clone());
         let (payload_service, payload_builder) = PayloadBuilderService::new(payload_generator);
 
         debug!(target: "reth::cli", "Spawning payload builder service");
@@ -281,6 +325,7 @@ impl Command {
                 consensus_engine_tx.clone(),
                 canon_state_notification_sender,
             )
+            .mode(MiningMode::interval(self.auto_mine_block_time))
             .build();
 
             let mut pipeline = self
@@ -377,6 +422,7 @@ impl Command {
                 blockchain_tree,
                 engine_api,
                 jwt_secret,
+                bundle_store,
             )
             .await?;
 
@@ -489,7 +535,10 @@ impl Command {
         let client = config.client.clone();
         let (handle, network, txpool, eth) = NetworkManager::builder(config)
             .await?
-            .transactions(pool)
+            .transactions_with_config(
+                pool,
+                self.network.tx_propagation.transactions_manager_config(),
+            )
             .request_handler(client)
             .split_with_handle();
 
@@ -653,9 +702,14 @@ impl Command {
             } else {
                 Hook::None
             },
+            ..Default::default()
         };
 
-        let factory = factory.with_stack_config(stack_config);
+        let mut factory = factory.with_stack_config(stack_config);
+        if let Some(workers) = stage_conf.execution.parallel_workers {
+            debug!(target: "reth::cli", workers, "Enabling parallel execution");
+            factory = factory.with_parallel_execution(ParallelExecutionConfig::new(workers));
+        }
 
         let header_mode =
             if continuous { HeaderSyncMode::Continuous } else { HeaderSyncMode::Tip(tip_rx) };
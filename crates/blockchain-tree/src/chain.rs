@@ -15,7 +15,6 @@ use reth_primitives::{
 };
 use reth_provider::{
     providers::PostStateProvider, BlockExecutor, Chain, ExecutorFactory, PostStateDataProvider,
-    StateRootProvider,
 };
 use std::{
     collections::BTreeMap,
@@ -234,13 +233,16 @@ impl AppendableChain {
         let history_provider = db.history_by_block_number(canonical_fork.number)?;
         let state_provider = history_provider;
 
+        // the state of the chain this block extends, needed for the state root below
+        let mut state = post_state_data_provider.state().clone();
         let provider = PostStateProvider::new(state_provider, post_state_data_provider);
 
         let mut executor = externals.executor_factory.with_sp(&provider);
         let post_state = executor.execute_and_verify_receipt(&block, U256::MAX, Some(senders))?;
 
-        // check state root
-        let state_root = provider.state_root(post_state.clone())?;
+        // check state root, computing the storage roots of changed accounts in parallel
+        state.extend(post_state.clone());
+        let state_root = db.state_root_parallel(canonical_fork.number, &state)?;
         if block.state_root != state_root {
             return Err(ConsensusError::BodyStateRootDiff {
                 got: state_root,
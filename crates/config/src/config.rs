@@ -202,6 +202,11 @@ pub struct ExecutionConfig {
     /// If this is lower than `max_gas`, then history is periodically flushed to the database
     /// transaction, which frees up memory.
     pub max_changesets: Option<u64>,
+    /// The number of threads used to speculatively execute the transactions of a block in
+    /// parallel.
+    ///
+    /// If unset, transactions are executed sequentially.
+    pub parallel_workers: Option<usize>,
 }
 
 impl Default for ExecutionConfig {
@@ -210,6 +215,7 @@ impl Default for ExecutionConfig {
             max_blocks: Some(500_000),
             max_changes: Some(5_000_000),
             max_changesets: Some(1_000_000),
+            parallel_workers: None,
         }
     }
 }
@@ -3,11 +3,11 @@ use futures_util::{future::BoxFuture, FutureExt, StreamExt};
 use reth_beacon_consensus::BeaconEngineMessage;
 use reth_interfaces::consensus::ForkchoiceState;
 use reth_primitives::{
-    constants::{EMPTY_RECEIPTS, EMPTY_TRANSACTIONS},
+    constants::{EMPTY_RECEIPTS, EMPTY_TRANSACTIONS, EMPTY_WITHDRAWALS},
     proofs,
     stage::StageId,
-    Block, BlockBody, ChainSpec, Header, IntoRecoveredTransaction, ReceiptWithBloom,
-    SealedBlockWithSenders, EMPTY_OMMER_ROOT, U256,
+    Block, BlockBody, ChainSpec, Hardfork, Header, IntoRecoveredTransaction, ReceiptWithBloom,
+    SealedBlockWithSenders, EMPTY_OMMER_ROOT, H256, U256,
 };
 use reth_provider::{CanonChainTracker, CanonStateNotificationSender, Chain, StateProviderFactory};
 use reth_revm::{
@@ -126,10 +126,34 @@ where
                     let mut storage = storage.write().await;
 
                     // check previous block for base fee
-                    let base_fee_per_gas = storage
-                        .headers
-                        .get(&storage.best_block)
-                        .and_then(|parent| parent.next_block_base_fee());
+                    let parent = storage.headers.get(&storage.best_block);
+                    let base_fee_per_gas = parent.and_then(|parent| parent.next_block_base_fee());
+                    let timestamp =
+                        SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+
+                    // post-merge blocks have no difficulty
+                    let difficulty = if chain_spec
+                        .fork(Hardfork::Paris)
+                        .active_at_ttd(storage.total_difficulty, U256::ZERO)
+                    {
+                        U256::ZERO
+                    } else {
+                        U256::from(1)
+                    };
+
+                    // blocks never contain withdrawals, but need to commit to an empty list once
+                    // shanghai is active
+                    let withdrawals =
+                        chain_spec.is_shanghai_activated_at_timestamp(timestamp).then(Vec::new);
+
+                    let (excess_blob_gas, parent_beacon_block_root) =
+                        if chain_spec.is_cancun_activated_at_timestamp(timestamp) {
+                            let excess_blob_gas =
+                                parent.and_then(|parent| parent.next_block_excess_blob_gas());
+                            (Some(excess_blob_gas.unwrap_or_default()), Some(H256::zero()))
+                        } else {
+                            (None, None)
+                        };
 
                     let mut header = Header {
                         parent_hash: storage.best_hash,
@@ -138,19 +162,19 @@ where
                         state_root: Default::default(),
                         transactions_root: Default::default(),
                         receipts_root: Default::default(),
-                        withdrawals_root: None,
+                        withdrawals_root: withdrawals.as_ref().map(|_| EMPTY_WITHDRAWALS),
                         logs_bloom: Default::default(),
-                        difficulty: U256::from(1),
+                        difficulty,
                         number: storage.best_block + 1,
                         gas_limit: 30_000_000,
                         gas_used: 0,
-                        timestamp: SystemTime::now()
-                            .duration_since(UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .as_secs(),
+                        timestamp,
                         mix_hash: Default::default(),
                         nonce: 0,
                         base_fee_per_gas,
+                        blob_gas_used: None,
+                        excess_blob_gas,
+                        parent_beacon_block_root,
                         extra_data: Default::default(),
                     };
 
@@ -164,9 +188,17 @@ where
                     } else {
                         proofs::calculate_transaction_root(&transactions)
                     };
+                    if header.excess_blob_gas.is_some() {
+                        header.blob_gas_used =
+                            Some(transactions.iter().map(|tx| tx.blob_gas()).sum());
+                    }
 
-                    let block =
-                        Block { header, body: transactions, ommers: vec![], withdrawals: None };
+                    let block = Block {
+                        header,
+                        body: transactions,
+                        ommers: vec![],
+                        withdrawals: withdrawals.clone(),
+                    };
 
                     // execute the new block
                     let substate = SubState::new(State::new(client.latest().unwrap()));
@@ -198,8 +230,11 @@ where
                                 proofs::calculate_receipt_root(&receipts_with_bloom)
                             };
                             let transactions = body.clone();
-                            let body =
-                                BlockBody { transactions: body, ommers: vec![], withdrawals: None };
+                            let body = BlockBody {
+                                transactions: body,
+                                ommers: vec![],
+                                withdrawals: withdrawals.clone(),
+                            };
                             header.gas_used = gas_used;
 
                             // calculate the state root
@@ -248,7 +283,7 @@ where
                                 header: header.clone(),
                                 body: transactions,
                                 ommers: vec![],
-                                withdrawals: None,
+                                withdrawals,
                             };
                             let sealed_block = block.seal_slow();
 
@@ -2,8 +2,8 @@
 use reth_consensus_common::validation;
 use reth_interfaces::consensus::{Consensus, ConsensusError};
 use reth_primitives::{
-    constants::MAXIMUM_EXTRA_DATA_SIZE, Chain, ChainSpec, Hardfork, Header, SealedBlock,
-    SealedHeader, EMPTY_OMMER_ROOT, U256,
+    constants::MAXIMUM_EXTRA_DATA_SIZE, ChainSpec, Hardfork, Header, SealedBlock, SealedHeader,
+    EMPTY_OMMER_ROOT, U256,
 };
 use std::sync::Arc;
 
@@ -65,14 +65,14 @@ impl Consensus for BeaconConsensus {
             // mixHash is used instead of difficulty inside EVM
             // https://eips.ethereum.org/EIPS/eip-4399#using-mixhash-field-instead-of-difficulty
         } else {
-            // TODO Consensus checks for old blocks:
-            //  * difficulty, mix_hash & nonce aka PoW stuff
-            // low priority as syncing is done in reverse order
+            // The proof-of-work of old blocks (difficulty, mix_hash & nonce) is only validated if
+            // the ethash consensus is enabled on top of this one, since syncing from a trusted tip
+            // is done in reverse order.
 
-            // Goerli exception:
-            //  * If the network is goerli pre-merge, ignore the extradata check, since we do not
-            //  support clique.
-            if self.chain_spec.chain != Chain::goerli() {
+            // Clique exception:
+            //  * If the network runs clique pre-merge (e.g. goerli), ignore the extradata check,
+            //  since the extradata holds the signer seal.
+            if self.chain_spec.clique.is_none() {
                 validate_header_extradata(header)?;
             }
         }
@@ -24,8 +24,8 @@ use reth_provider::{
     BlockProvider, BlockSource, CanonChainTracker, ProviderError, StageCheckpointProvider,
 };
 use reth_rpc_types::engine::{
-    ExecutionPayload, ForkchoiceUpdated, PayloadAttributes, PayloadStatus, PayloadStatusEnum,
-    PayloadValidationError,
+    CancunPayloadFields, ExecutionPayload, ForkchoiceUpdated, PayloadAttributes, PayloadError,
+    PayloadStatus, PayloadStatusEnum, PayloadValidationError,
 };
 use reth_stages::Pipeline;
 use reth_tasks::TaskSpawner;
@@ -81,13 +81,17 @@ impl BeaconConsensusEngineHandle {
 
     /// Sends a new payload message to the beacon consensus engine and waits for a response.
     ///
+    /// The `cancun_fields` are only set for `engine_newPayloadV3`.
+    ///
     ///See also <https://github.com/ethereum/execution-apis/blob/8db51dcd2f4bdfbd9ad6e4a7560aac97010ad063/src/engine/specification.md#engine_newpayloadv2>
     pub async fn new_payload(
         &self,
         payload: ExecutionPayload,
+        cancun_fields: Option<CancunPayloadFields>,
     ) -> Result<PayloadStatus, BeaconOnNewPayloadError> {
         let (tx, rx) = oneshot::channel();
-        let _ = self.to_engine.send(BeaconEngineMessage::NewPayload { payload, tx });
+        let _ =
+            self.to_engine.send(BeaconEngineMessage::NewPayload { payload, cancun_fields, tx });
         rx.await.map_err(|_| BeaconOnNewPayloadError::EngineUnavailable)?
     }
 
@@ -627,10 +631,11 @@ where
     fn on_new_payload(
         &mut self,
         payload: ExecutionPayload,
+        cancun_fields: Option<CancunPayloadFields>,
     ) -> Result<PayloadStatus, BeaconOnNewPayloadError> {
         trace!(target: "consensus::engine", "Received new payload");
 
-        let block = match self.ensure_well_formed_payload(payload) {
+        let block = match self.ensure_well_formed_payload(payload, cancun_fields) {
             Ok(block) => block,
             Err(status) => return Ok(status),
         };
@@ -668,20 +673,36 @@ where
     ///    - missing or invalid base fee
     ///    - invalid extra data
     ///    - invalid transactions
+    ///    - versioned hashes that don't match the blob transactions (`engine_newPayloadV3`)
     fn ensure_well_formed_payload(
         &self,
         payload: ExecutionPayload,
+        cancun_fields: Option<CancunPayloadFields>,
     ) -> Result<SealedBlock, PayloadStatus> {
         let parent_hash = payload.parent_hash;
-        let block = match SealedBlock::try_from(payload) {
+        let parent_beacon_block_root = cancun_fields.as_ref().map(|f| f.parent_beacon_block_root);
+        let block = payload.try_into_sealed_block(parent_beacon_block_root).and_then(|block| {
+            if let Some(CancunPayloadFields { versioned_hashes, .. }) = cancun_fields {
+                let block_versioned_hashes = block.blob_versioned_hashes();
+                if block_versioned_hashes != versioned_hashes {
+                    return Err(PayloadError::InvalidVersionedHashes {
+                        expected: versioned_hashes,
+                        got: block_versioned_hashes,
+                    })
+                }
+            }
+            Ok(block)
+        });
+        let block = match block {
             Ok(block) => block,
             Err(error) => {
                 error!(target: "consensus::engine", ?error, "Invalid payload");
 
                 let mut latest_valid_hash = None;
-                if !error.is_block_hash_mismatch() {
+                if !error.is_block_hash_mismatch() && !error.is_versioned_hashes_mismatch() {
                     // Engine-API rule:
-                    // > `latestValidHash: null` if the blockHash validation has failed
+                    // > `latestValidHash: null` if the blockHash or versioned hashes validation
+                    // > has failed
                     latest_valid_hash =
                         self.latest_valid_hash_for_invalid_payload(parent_hash, None);
                 }
@@ -931,9 +952,9 @@ where
                         return Poll::Ready(Ok(()))
                     }
                 }
-                BeaconEngineMessage::NewPayload { payload, tx } => {
+                BeaconEngineMessage::NewPayload { payload, cancun_fields, tx } => {
                     this.metrics.new_payload_messages.increment(1);
-                    let res = this.on_new_payload(payload);
+                    let res = this.on_new_payload(payload, cancun_fields);
                     let _ = tx.send(res);
                 }
                 BeaconEngineMessage::EventListener(tx) => {
@@ -1036,7 +1057,7 @@ mod tests {
             &self,
             payload: ExecutionPayload,
         ) -> Result<PayloadStatus, BeaconOnNewPayloadError> {
-            self.engine_handle.new_payload(payload).await
+            self.engine_handle.new_payload(payload, None).await
         }
 
         /// Sends the `ExecutionPayload` message to the consensus engine and retries if the engine
@@ -0,0 +1,714 @@
+//! Consensus for clique proof-of-authority networks.
+use crate::{
+    seal,
+    snapshot::{Snapshot, NONCE_AUTH_VOTE, NONCE_DROP_VOTE},
+};
+use parking_lot::Mutex;
+use reth_consensus_common::validation;
+use reth_db::{
+    database::Database,
+    models::StoredCliqueSnapshot,
+    tables,
+    transaction::{DbTx, DbTxMut},
+    DatabaseError,
+};
+use reth_interfaces::consensus::{Consensus, ConsensusError};
+use reth_primitives::{
+    constants::MAXIMUM_EXTRA_DATA_SIZE, keccak256, Address, BlockNumber, ChainSpec, CliqueConfig,
+    ForkCondition, Hardfork, Header, SealedBlock, SealedHeader, EMPTY_OMMER_ROOT, H256, U256,
+};
+use schnellru::{ByLength, LruMap};
+use secp256k1::{PublicKey, SecretKey, SECP256K1};
+use std::{fmt, sync::Arc};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tracing::{debug, trace, warn};
+
+/// Number of blocks after which the vote snapshot is persisted to the database.
+pub const CHECKPOINT_INTERVAL: u64 = 1024;
+
+/// Block difficulty for in-turn signatures.
+pub const DIFF_IN_TURN: U256 = U256::from_limbs([2, 0, 0, 0]);
+
+/// Block difficulty for out-of-turn signatures.
+pub const DIFF_NO_TURN: U256 = U256::from_limbs([1, 0, 0, 0]);
+
+/// Number of recent vote snapshots to keep in memory.
+const INMEMORY_SNAPSHOTS: u32 = 128;
+
+/// Number of recent block signatures to keep in memory.
+const INMEMORY_SIGNATURES: u32 = 4096;
+
+/// Clique proof-of-authority consensus
+///
+/// Besides the standalone header checks, this tracks the authorized signers through vote
+/// [Snapshot]s. Snapshots can only be built if the ancestors of a header are known, either from
+/// the in-memory cache or the database. This is not the case while the pipeline downloads headers
+/// in reverse, so the signer checks of these headers are deferred until their total difficulty is
+/// validated: the total difficulty stage walks the stored headers in ascending order, before any
+/// bodies are downloaded or executed. A header is never accepted there without a snapshot of its
+/// parent.
+pub struct CliqueConsensus<DB> {
+    /// Configuration
+    chain_spec: Arc<ChainSpec>,
+    /// The clique parameters of the chain
+    config: CliqueConfig,
+    /// The database to look up ancestors and persisted snapshots in
+    db: DB,
+    /// Recent snapshots by block hash
+    snapshots: Mutex<LruMap<H256, Snapshot>>,
+    /// Recovered signers by block hash
+    signatures: Mutex<LruMap<H256, Address>>,
+    /// Sends snapshots to the task that persists them
+    persist_tx: UnboundedSender<(BlockNumber, StoredCliqueSnapshot)>,
+}
+
+impl<DB> CliqueConsensus<DB>
+where
+    DB: Database + Clone + 'static,
+{
+    /// Create a new instance of [CliqueConsensus]
+    ///
+    /// This spawns a thread that persists snapshots to the database, since the database might be
+    /// locked for writing while headers are validated.
+    ///
+    /// # Panics
+    ///
+    /// If the chain spec does not have a clique configuration.
+    pub fn new(chain_spec: Arc<ChainSpec>, db: DB) -> Self {
+        let config = chain_spec.clique.expect("chain spec has no clique configuration");
+
+        let (persist_tx, mut persist_rx) =
+            unbounded_channel::<(BlockNumber, StoredCliqueSnapshot)>();
+        let persist_db = db.clone();
+        std::thread::Builder::new()
+            .name("clique-snapshots".to_string())
+            .spawn(move || {
+                while let Some((number, snapshot)) = persist_rx.blocking_recv() {
+                    let res = persist_db
+                        .update(|tx| tx.put::<tables::CliqueSnapshots>(number, snapshot))
+                        .and_then(|res| res);
+                    match res {
+                        Ok(()) => {
+                            trace!(target: "consensus::clique", number, "Persisted snapshot")
+                        }
+                        Err(err) => {
+                            warn!(target: "consensus::clique", number, ?err, "Failed to persist snapshot");
+                        }
+                    }
+                }
+            })
+            .expect("failed to spawn clique snapshot thread");
+
+        Self {
+            chain_spec,
+            config,
+            db,
+            snapshots: Mutex::new(LruMap::new(ByLength::new(INMEMORY_SNAPSHOTS))),
+            signatures: Mutex::new(LruMap::new(ByLength::new(INMEMORY_SIGNATURES))),
+            persist_tx,
+        }
+    }
+
+    /// Returns the vote snapshot at the given header.
+    ///
+    /// Returns `None` if the snapshot can't be built because some of the ancestors between the
+    /// header and the last checkpoint are unknown.
+    pub fn snapshot(&self, header: &SealedHeader) -> Result<Option<Snapshot>, ConsensusError> {
+        let mut headers = Vec::new();
+        let mut current = header.clone();
+
+        let mut snapshot = loop {
+            if let Some(snapshot) = self.snapshots.lock().get(&current.hash()) {
+                break snapshot.clone()
+            }
+
+            // Try to load a persisted snapshot
+            if current.number % CHECKPOINT_INTERVAL == 0 {
+                if let Some(snapshot) = self.load_snapshot(current.number, current.hash()) {
+                    trace!(target: "consensus::clique", number = current.number, "Loaded snapshot");
+                    break snapshot
+                }
+            }
+
+            // The signers of genesis and epoch checkpoints are listed in the header
+            if current.number % self.config.epoch == 0 {
+                let snapshot = Snapshot::from_checkpoint(&current)?;
+                self.snapshots.lock().insert(snapshot.hash, snapshot.clone());
+                break snapshot
+            }
+
+            let parent_hash = current.parent_hash;
+            headers.push(current);
+            match self.read_header(parent_hash) {
+                Some(parent) => current = parent,
+                None => return Ok(None),
+            }
+        };
+
+        if headers.is_empty() {
+            return Ok(Some(snapshot))
+        }
+
+        for header in headers.iter().rev() {
+            let signer = self.recover_signer(header)?;
+            snapshot = snapshot.apply(header, signer, self.config.epoch)?;
+        }
+        self.insert_snapshot(snapshot.clone());
+
+        Ok(Some(snapshot))
+    }
+
+    /// Prepares a header built on top of the given parent to be sealed by the given signer.
+    ///
+    /// This sets the difficulty according to the signer's turn, clears the vote, adds the signer
+    /// list to checkpoint headers and makes sure the timestamp respects the block period. The
+    /// first bytes of the existing extra-data are kept as the signer vanity.
+    pub fn prepare_header(
+        &self,
+        header: &mut Header,
+        parent: &SealedHeader,
+        signer: Address,
+    ) -> Result<(), ConsensusError> {
+        let snapshot = self
+            .snapshot(parent)?
+            .ok_or_else(|| ConsensusError::ParentUnknown { hash: parent.hash() })?;
+
+        header.number = parent.number + 1;
+        header.parent_hash = parent.hash();
+        header.beneficiary = Address::zero();
+        header.nonce = NONCE_DROP_VOTE;
+        header.mix_hash = H256::zero();
+        header.ommers_hash = EMPTY_OMMER_ROOT;
+        header.timestamp = header.timestamp.max(parent.timestamp + self.config.period);
+        header.difficulty =
+            if snapshot.inturn(header.number, signer) { DIFF_IN_TURN } else { DIFF_NO_TURN };
+
+        let signers =
+            if header.number % self.config.epoch == 0 { snapshot.signers() } else { Vec::new() };
+        header.extra_data = seal::unsealed_extra_data(&header.extra_data, &signers);
+
+        Ok(())
+    }
+
+    /// Seals a header that was prepared with [Self::prepare_header] with the given key.
+    ///
+    /// Fails if the signer is not authorized or has to wait for other signers to seal a block
+    /// first.
+    pub fn seal_header(
+        &self,
+        header: Header,
+        parent: &SealedHeader,
+        secret_key: &SecretKey,
+    ) -> Result<SealedHeader, ConsensusError> {
+        let snapshot = self
+            .snapshot(parent)?
+            .ok_or_else(|| ConsensusError::ParentUnknown { hash: parent.hash() })?;
+
+        let public_key = PublicKey::from_secret_key(SECP256K1, secret_key);
+        let signer =
+            Address::from_slice(&keccak256(&public_key.serialize_uncompressed()[1..])[12..]);
+        if !snapshot.signers.contains(&signer) {
+            return Err(ConsensusError::CliqueUnauthorizedSigner { signer })
+        }
+        if snapshot.recently_signed(header.number, signer) {
+            return Err(ConsensusError::CliqueRecentlySigned { signer })
+        }
+
+        let header = seal::seal_header(header, H256::from_slice(&secret_key.secret_bytes()))?;
+        debug!(target: "consensus::clique", number = header.number, ?signer, "Sealed block");
+        Ok(header.seal_slow())
+    }
+
+    /// Returns the vote snapshot at the parent of the given header, see [Self::snapshot].
+    fn parent_snapshot(&self, header: &SealedHeader) -> Result<Option<Snapshot>, ConsensusError> {
+        if let Some(snapshot) = self.snapshots.lock().get(&header.parent_hash) {
+            return Ok(Some(snapshot.clone()))
+        }
+        match self.read_header(header.parent_hash) {
+            Some(parent) => self.snapshot(&parent),
+            None => Ok(None),
+        }
+    }
+
+    /// Validates the signer and difficulty of the header against the snapshot of its parent and
+    /// caches the resulting snapshot.
+    fn validate_signer(
+        &self,
+        header: &SealedHeader,
+        snapshot: Snapshot,
+    ) -> Result<(), ConsensusError> {
+        let signer = self.recover_signer(header)?;
+
+        // Checkpoint headers must list the current signers
+        if header.number % self.config.epoch == 0 &&
+            seal::checkpoint_signers(header)? != snapshot.signers()
+        {
+            return Err(ConsensusError::CliqueMismatchingCheckpointSigners)
+        }
+
+        let expected =
+            if snapshot.inturn(header.number, signer) { DIFF_IN_TURN } else { DIFF_NO_TURN };
+        if header.difficulty != expected {
+            return Err(ConsensusError::CliqueWrongDifficulty { expected, got: header.difficulty })
+        }
+
+        // this also rejects unauthorized and recent signers
+        self.insert_snapshot(snapshot.apply(header, signer, self.config.epoch)?);
+
+        Ok(())
+    }
+
+    /// Returns whether the header is a post-merge header, to which the clique rules don't apply.
+    fn is_post_merge(&self, header: &Header) -> bool {
+        header.difficulty == U256::ZERO &&
+            self.chain_spec.fork(Hardfork::Paris) != ForkCondition::Never
+    }
+
+    /// Recovers the signer of the header, using the cache if possible.
+    fn recover_signer(&self, header: &SealedHeader) -> Result<Address, ConsensusError> {
+        if let Some(signer) = self.signatures.lock().get(&header.hash()) {
+            return Ok(*signer)
+        }
+        let signer = seal::recover_signer(header)?;
+        self.signatures.lock().insert(header.hash(), signer);
+        Ok(signer)
+    }
+
+    /// Caches the snapshot and persists it if it is at a checkpoint.
+    fn insert_snapshot(&self, snapshot: Snapshot) {
+        if snapshot.number % CHECKPOINT_INTERVAL == 0 {
+            let _ = self.persist_tx.send((snapshot.number, snapshot.clone().into()));
+        }
+        self.snapshots.lock().insert(snapshot.hash, snapshot);
+    }
+
+    /// Loads the persisted snapshot at the given block, if it exists and belongs to the given
+    /// hash.
+    fn load_snapshot(&self, number: BlockNumber, hash: H256) -> Option<Snapshot> {
+        let res = self.db.view(|tx| tx.get::<tables::CliqueSnapshots>(number)).and_then(|res| res);
+        let stored = self.log_db_error(res)??;
+        if stored.hash != hash {
+            return None
+        }
+        let mut snapshot = Snapshot::from(stored);
+        snapshot.number = number;
+        Some(snapshot)
+    }
+
+    /// Reads the header with the given hash from the database.
+    fn read_header(&self, hash: H256) -> Option<SealedHeader> {
+        let res = self
+            .db
+            .view(|tx| {
+                let Some(number) = tx.get::<tables::HeaderNumbers>(hash)? else { return Ok(None) };
+                tx.get::<tables::Headers>(number)
+            })
+            .and_then(|res| res);
+        let header = self.log_db_error(res)??;
+        Some(header.seal(hash))
+    }
+
+    /// Logs database errors, which are treated like missing entries.
+    fn log_db_error<T>(&self, res: Result<T, DatabaseError>) -> Option<T> {
+        res.map_err(|err| warn!(target: "consensus::clique", ?err, "Failed to read from database"))
+            .ok()
+    }
+}
+
+impl<DB> fmt::Debug for CliqueConsensus<DB> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CliqueConsensus").field("config", &self.config).finish_non_exhaustive()
+    }
+}
+
+impl<DB> Consensus for CliqueConsensus<DB>
+where
+    DB: Database + Clone + 'static,
+{
+    fn validate_header(&self, header: &SealedHeader) -> Result<(), ConsensusError> {
+        validation::validate_header_standalone(header, &self.chain_spec)?;
+        if self.is_post_merge(header) {
+            return Ok(())
+        }
+
+        // Checkpoint blocks need to enforce a zero beneficiary and vote
+        let checkpoint = header.number % self.config.epoch == 0;
+        if checkpoint && header.beneficiary != Address::zero() {
+            return Err(ConsensusError::CliqueInvalidCheckpointBeneficiary {
+                beneficiary: header.beneficiary,
+            })
+        }
+        match header.nonce {
+            NONCE_DROP_VOTE => {}
+            NONCE_AUTH_VOTE if !checkpoint => {}
+            nonce if checkpoint => {
+                return Err(ConsensusError::CliqueInvalidCheckpointVote { nonce })
+            }
+            nonce => return Err(ConsensusError::CliqueInvalidVote { nonce }),
+        }
+
+        // The extra-data contains the vanity, the signers on checkpoints and the seal
+        if header.extra_data.len() < seal::EXTRA_VANITY {
+            return Err(ConsensusError::CliqueMissingVanity)
+        }
+        if header.extra_data.len() < seal::EXTRA_VANITY + seal::EXTRA_SEAL {
+            return Err(ConsensusError::CliqueMissingSignature)
+        }
+        if checkpoint {
+            seal::checkpoint_signers(header)?;
+        } else if header.extra_data.len() != seal::EXTRA_VANITY + seal::EXTRA_SEAL {
+            return Err(ConsensusError::CliqueExtraSigners)
+        }
+
+        if header.mix_hash != H256::zero() {
+            return Err(ConsensusError::CliqueInvalidMixDigest)
+        }
+        if header.ommers_hash != EMPTY_OMMER_ROOT {
+            return Err(ConsensusError::CliqueInvalidOmmersHash)
+        }
+        if header.number > 0 &&
+            header.difficulty != DIFF_IN_TURN &&
+            header.difficulty != DIFF_NO_TURN
+        {
+            return Err(ConsensusError::CliqueInvalidDifficulty { difficulty: header.difficulty })
+        }
+
+        Ok(())
+    }
+
+    fn validate_header_against_parent(
+        &self,
+        header: &SealedHeader,
+        parent: &SealedHeader,
+    ) -> Result<(), ConsensusError> {
+        if self.is_post_merge(header) {
+            validation::validate_header_regarding_parent(parent, header, &self.chain_spec)?;
+            return Ok(())
+        }
+
+        if parent.number + 1 != header.number {
+            return Err(ConsensusError::ParentBlockNumberMismatch {
+                parent_block_number: parent.number,
+                block_number: header.number,
+            })
+        }
+        if parent.timestamp + self.config.period > header.timestamp {
+            return Err(ConsensusError::CliqueInvalidTimestamp {
+                parent_timestamp: parent.timestamp,
+                timestamp: header.timestamp,
+                period: self.config.period,
+            })
+        }
+        validation::validate_header_gas_regarding_parent(parent, header, &self.chain_spec)?;
+
+        let Some(snapshot) = self.snapshot(parent)? else {
+            // enforced by `validate_header_with_total_difficulty` once the ancestors are stored
+            trace!(target: "consensus::clique", number = header.number, "Deferring signer checks");
+            return Ok(())
+        };
+
+        self.validate_signer(header, snapshot)
+    }
+
+    fn validate_header_with_total_difficulty(
+        &self,
+        header: &Header,
+        total_difficulty: U256,
+    ) -> Result<(), ConsensusError> {
+        if self.chain_spec.fork(Hardfork::Paris).active_at_ttd(total_difficulty, header.difficulty)
+        {
+            // EIP-3675: Upgrade consensus to Proof-of-Stake:
+            // https://eips.ethereum.org/EIPS/eip-3675#replacing-difficulty-with-0
+            if header.difficulty != U256::ZERO {
+                return Err(ConsensusError::TheMergeDifficultyIsNotZero)
+            }
+
+            if header.nonce != 0 {
+                return Err(ConsensusError::TheMergeNonceIsNotZero)
+            }
+
+            if header.ommers_hash != EMPTY_OMMER_ROOT {
+                return Err(ConsensusError::TheMergeOmmerRootIsNotEmpty)
+            }
+
+            if header.extra_data.len() > MAXIMUM_EXTRA_DATA_SIZE {
+                return Err(ConsensusError::ExtraDataExceedsMax { len: header.extra_data.len() })
+            }
+        }
+
+        if header.number == 0 || self.is_post_merge(header) {
+            return Ok(())
+        }
+
+        // The headers are validated in ascending order here, so the snapshot of the parent can
+        // always be built, unless the parent is unknown.
+        let header = header.clone().seal_slow();
+        let snapshot = self
+            .parent_snapshot(&header)?
+            .ok_or(ConsensusError::ParentUnknown { hash: header.parent_hash })?;
+        self.validate_signer(&header, snapshot)
+    }
+
+    fn validate_block(&self, block: &SealedBlock) -> Result<(), ConsensusError> {
+        validation::validate_block_standalone(block, &self.chain_spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NONCE_AUTH_VOTE;
+    use reth_db::mdbx::{test_utils::create_test_rw_db, Env, WriteMap};
+    use reth_primitives::{ChainSpecBuilder, Genesis, GOERLI, GOERLI_GENESIS, MAINNET};
+
+    const EPOCH: u64 = 8;
+
+    struct TestSigner {
+        secret_key: SecretKey,
+        address: Address,
+    }
+
+    /// Returns the given number of signers sorted by address.
+    fn signers(count: usize) -> Vec<TestSigner> {
+        let mut signers = (0..count)
+            .map(|_| {
+                let secret_key = SecretKey::new(&mut rand::thread_rng());
+                let public_key = PublicKey::from_secret_key(SECP256K1, &secret_key);
+                let address = Address::from_slice(
+                    &keccak256(&public_key.serialize_uncompressed()[1..])[12..],
+                );
+                TestSigner { secret_key, address }
+            })
+            .collect::<Vec<_>>();
+        signers.sort_by_key(|signer| signer.address);
+        signers
+    }
+
+    fn consensus(signers: &[TestSigner]) -> (CliqueConsensus<Arc<Env<WriteMap>>>, SealedHeader) {
+        let addresses = signers.iter().map(|signer| signer.address).collect::<Vec<_>>();
+        let genesis = Genesis::default()
+            .with_gas_limit(30_000_000)
+            .with_extra_data(seal::unsealed_extra_data(&[], &addresses));
+        let chain_spec = ChainSpecBuilder::default()
+            .chain(MAINNET.chain)
+            .genesis(genesis)
+            .istanbul_activated()
+            .clique(CliqueConfig { period: 0, epoch: EPOCH })
+            .build();
+        let genesis = chain_spec.genesis_header().seal_slow();
+        (CliqueConsensus::new(Arc::new(chain_spec), create_test_rw_db()), genesis)
+    }
+
+    /// Builds and seals the child of the given parent.
+    fn child(
+        consensus: &CliqueConsensus<Arc<Env<WriteMap>>>,
+        parent: &SealedHeader,
+        signer: &TestSigner,
+    ) -> SealedHeader {
+        let mut header = Header {
+            gas_limit: parent.gas_limit,
+            timestamp: parent.timestamp + 1,
+            ..Default::default()
+        };
+        consensus.prepare_header(&mut header, parent, signer.address).unwrap();
+        consensus.seal_header(header, parent, &signer.secret_key).unwrap()
+    }
+
+    fn validate(
+        consensus: &CliqueConsensus<Arc<Env<WriteMap>>>,
+        header: &SealedHeader,
+        parent: &SealedHeader,
+    ) -> Result<(), ConsensusError> {
+        consensus.validate_header(header)?;
+        consensus.validate_header_against_parent(header, parent)
+    }
+
+    #[test]
+    fn sealed_headers_are_valid() {
+        let signers = signers(3);
+        let (consensus, genesis) = consensus(&signers);
+
+        let mut parent = genesis;
+        for number in 1..=2 * EPOCH {
+            let signer = &signers[number as usize % signers.len()];
+            let header = child(&consensus, &parent, signer);
+            assert_eq!(header.difficulty, DIFF_IN_TURN);
+            assert_eq!(seal::recover_signer(&header), Ok(signer.address));
+            validate(&consensus, &header, &parent).unwrap();
+            parent = header;
+        }
+    }
+
+    #[test]
+    fn out_of_turn_difficulty() {
+        let signers = signers(3);
+        let (consensus, genesis) = consensus(&signers);
+
+        // signer 1 is in-turn at block 1
+        let header = child(&consensus, &genesis, &signers[2]);
+        assert_eq!(header.difficulty, DIFF_NO_TURN);
+        validate(&consensus, &header, &genesis).unwrap();
+
+        let mut header = header.unseal();
+        header.difficulty = DIFF_IN_TURN;
+        let header = consensus.seal_header(header, &genesis, &signers[2].secret_key).unwrap();
+        assert_eq!(
+            validate(&consensus, &header, &genesis),
+            Err(ConsensusError::CliqueWrongDifficulty {
+                expected: DIFF_NO_TURN,
+                got: DIFF_IN_TURN
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_unauthorized_and_recent_signers() {
+        let signers = signers(3);
+        let (consensus, genesis) = consensus(&signers);
+
+        let outsider = &self::signers(1)[0];
+        assert_eq!(
+            consensus.seal_header(Header::default(), &genesis, &outsider.secret_key),
+            Err(ConsensusError::CliqueUnauthorizedSigner { signer: outsider.address })
+        );
+
+        let block1 = child(&consensus, &genesis, &signers[1]);
+        validate(&consensus, &block1, &genesis).unwrap();
+
+        let mut header = Header { gas_limit: block1.gas_limit, ..Default::default() };
+        consensus.prepare_header(&mut header, &block1, signers[1].address).unwrap();
+        assert_eq!(
+            consensus.seal_header(header.clone(), &block1, &signers[1].secret_key),
+            Err(ConsensusError::CliqueRecentlySigned { signer: signers[1].address })
+        );
+
+        // a block sealed by a recent signer is rejected by the snapshot
+        let header =
+            seal::seal_header(header, H256::from_slice(&signers[1].secret_key.secret_bytes()))
+                .unwrap()
+                .seal_slow();
+        assert_eq!(
+            validate(&consensus, &header, &block1),
+            Err(ConsensusError::CliqueRecentlySigned { signer: signers[1].address })
+        );
+    }
+
+    #[test]
+    fn checkpoint_rules() {
+        let signers = signers(1);
+        let (consensus, genesis) = consensus(&signers);
+
+        let mut parent = genesis;
+        for _ in 1..EPOCH {
+            let header = child(&consensus, &parent, &signers[0]);
+            validate(&consensus, &header, &parent).unwrap();
+            parent = header;
+        }
+
+        let checkpoint = child(&consensus, &parent, &signers[0]);
+        assert_eq!(seal::checkpoint_signers(&checkpoint), Ok(vec![signers[0].address]));
+
+        let mut header = checkpoint.clone().unseal();
+        header.nonce = NONCE_AUTH_VOTE;
+        let header = consensus.seal_header(header, &parent, &signers[0].secret_key).unwrap();
+        assert_eq!(
+            consensus.validate_header(&header),
+            Err(ConsensusError::CliqueInvalidCheckpointVote { nonce: NONCE_AUTH_VOTE })
+        );
+
+        let mut header = checkpoint.clone().unseal();
+        header.extra_data = seal::unsealed_extra_data(&[], &[Address::random()]);
+        let header = consensus.seal_header(header, &parent, &signers[0].secret_key).unwrap();
+        assert_eq!(
+            validate(&consensus, &header, &parent),
+            Err(ConsensusError::CliqueMismatchingCheckpointSigners)
+        );
+
+        validate(&consensus, &checkpoint, &parent).unwrap();
+    }
+
+    /// Writes the header to the database of the instance.
+    fn store_header(consensus: &CliqueConsensus<Arc<Env<WriteMap>>>, header: &SealedHeader) {
+        consensus
+            .db
+            .update(|tx| {
+                tx.put::<tables::HeaderNumbers>(header.hash(), header.number)?;
+                tx.put::<tables::Headers>(header.number, header.clone().unseal())
+            })
+            .unwrap()
+            .unwrap();
+    }
+
+    #[test]
+    fn rejects_forged_chain_once_ancestors_are_known() {
+        let signers = signers(2);
+        let (consensus, genesis) = consensus(&signers);
+
+        let block1 = child(&consensus, &genesis, &signers[1]);
+
+        // block 2 is sealed by a key that is not an authorized signer
+        let outsider = &self::signers(1)[0];
+        let mut header = Header { gas_limit: block1.gas_limit, ..Default::default() };
+        consensus.prepare_header(&mut header, &block1, outsider.address).unwrap();
+        let forged =
+            seal::seal_header(header, H256::from_slice(&outsider.secret_key.secret_bytes()))
+                .unwrap()
+                .seal_slow();
+
+        // a fresh instance neither knows the genesis nor block 1, like the pipeline that
+        // downloads headers in reverse, so the signer checks are deferred
+        let (fresh, _) = self::consensus(&signers);
+        assert_eq!(fresh.snapshot(&block1), Ok(None));
+        validate(&fresh, &forged, &block1).unwrap();
+
+        // the header is never accepted without the snapshot of its parent
+        assert_eq!(
+            fresh.validate_header_with_total_difficulty(&forged, U256::ZERO),
+            Err(ConsensusError::ParentUnknown { hash: block1.hash() })
+        );
+
+        // once the ancestors are stored, the forged header is rejected
+        store_header(&fresh, &genesis);
+        store_header(&fresh, &block1);
+        assert_eq!(
+            fresh.validate_header_with_total_difficulty(&forged, U256::ZERO),
+            Err(ConsensusError::CliqueUnauthorizedSigner { signer: outsider.address })
+        );
+
+        let block2 = child(&consensus, &block1, &signers[0]);
+        fresh.validate_header_with_total_difficulty(&block2, U256::ZERO).unwrap();
+    }
+
+    #[test]
+    fn loads_persisted_snapshot() {
+        let signers = signers(2);
+        let (consensus, _) = consensus(&signers);
+
+        let header = Header { number: CHECKPOINT_INTERVAL, ..Default::default() }.seal_slow();
+        let snapshot = Snapshot::new(
+            header.number,
+            header.hash(),
+            signers.iter().map(|signer| signer.address),
+        );
+        consensus
+            .db
+            .update(|tx| tx.put::<tables::CliqueSnapshots>(header.number, snapshot.clone().into()))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(consensus.snapshot(&header), Ok(Some(snapshot)));
+    }
+
+    #[test]
+    fn goerli_genesis_signers() {
+        let consensus = CliqueConsensus::new(GOERLI.clone(), create_test_rw_db());
+        let genesis = GOERLI.genesis_header().seal_slow();
+        assert_eq!(genesis.hash(), GOERLI_GENESIS);
+
+        let snapshot = consensus.snapshot(&genesis).unwrap().unwrap();
+        assert_eq!(
+            snapshot.signers(),
+            vec!["0xe0a2bd4258d2768837baa26a28fe71dc079f84c7".parse::<Address>().unwrap()]
+        );
+    }
+}
@@ -0,0 +1,21 @@
+#![warn(missing_docs, unreachable_pub, unused_crate_dependencies)]
+#![deny(unused_must_use, rust_2018_idioms)]
+#![doc(test(
+    no_crate_inject,
+    attr(deny(warnings, rust_2018_idioms), allow(dead_code, unused_variables))
+))]
+
+//! A [Consensus](reth_interfaces::consensus::Consensus) implementation for networks that run the
+//! clique proof-of-authority protocol, see [EIP-225](https://eips.ethereum.org/EIPS/eip-225).
+//!
+//! Blocks are sealed by an authorized signer, whose signature is appended to the header's
+//! extra-data. Signers vote on adding or removing signers through the header's beneficiary and
+//! nonce, and the voting state is tracked in [Snapshot]s that are periodically persisted to the
+//! [CliqueSnapshots](reth_db::tables::CliqueSnapshots) table.
+
+mod clique;
+pub mod seal;
+pub mod snapshot;
+
+pub use clique::{CliqueConsensus, CHECKPOINT_INTERVAL, DIFF_IN_TURN, DIFF_NO_TURN};
+pub use snapshot::{Snapshot, Tally, Vote};
@@ -0,0 +1,85 @@
+//! Helpers for reading and writing the clique seal of a header.
+use reth_interfaces::consensus::ConsensusError;
+use reth_primitives::{sign_message, Address, Bytes, Header, H256};
+
+/// Fixed number of extra-data prefix bytes reserved for signer vanity.
+pub const EXTRA_VANITY: usize = 32;
+
+/// Fixed number of extra-data suffix bytes reserved for the signer seal.
+///
+/// 65 bytes fixed as signatures are based on the standard secp256k1 curve.
+pub const EXTRA_SEAL: usize = 65;
+
+/// Returns the hash the signer signs to seal the header, i.e. the hash of the header with the seal
+/// stripped from the extra-data.
+pub fn seal_hash(header: &Header) -> H256 {
+    let mut unsealed = header.clone();
+    let len = header.extra_data.len().saturating_sub(EXTRA_SEAL);
+    unsealed.extra_data = Bytes::from(&header.extra_data[..len]);
+    unsealed.hash_slow()
+}
+
+/// Recovers the address that signed the header from the seal in its extra-data.
+pub fn recover_signer(header: &Header) -> Result<Address, ConsensusError> {
+    let len = header.extra_data.len();
+    if len < EXTRA_SEAL {
+        return Err(ConsensusError::CliqueMissingSignature)
+    }
+
+    let signature: [u8; EXTRA_SEAL] =
+        header.extra_data[len - EXTRA_SEAL..].try_into().expect("length is checked");
+    reth_primitives::recover_signer(&signature, seal_hash(header).as_fixed_bytes())
+        .map_err(|_| ConsensusError::CliqueSignerRecoveryError)
+}
+
+/// Returns the signers listed in the extra-data of a checkpoint header.
+pub fn checkpoint_signers(header: &Header) -> Result<Vec<Address>, ConsensusError> {
+    let len = header.extra_data.len();
+    if len < EXTRA_VANITY {
+        return Err(ConsensusError::CliqueMissingVanity)
+    }
+    if len < EXTRA_VANITY + EXTRA_SEAL {
+        return Err(ConsensusError::CliqueMissingSignature)
+    }
+
+    let signers = &header.extra_data[EXTRA_VANITY..len - EXTRA_SEAL];
+    if signers.len() % Address::len_bytes() != 0 {
+        return Err(ConsensusError::CliqueInvalidCheckpointSigners)
+    }
+    Ok(signers.chunks_exact(Address::len_bytes()).map(Address::from_slice).collect())
+}
+
+/// Builds the extra-data of a header that is ready to be sealed: the given vanity padded or
+/// truncated to [EXTRA_VANITY] bytes, the signers if this is a checkpoint block, and an empty
+/// seal.
+pub fn unsealed_extra_data(vanity: &[u8], signers: &[Address]) -> Bytes {
+    let mut extra_data = vanity[..vanity.len().min(EXTRA_VANITY)].to_vec();
+    extra_data.resize(EXTRA_VANITY, 0);
+    for signer in signers {
+        extra_data.extend_from_slice(signer.as_bytes());
+    }
+    extra_data.resize(extra_data.len() + EXTRA_SEAL, 0);
+    extra_data.into()
+}
+
+/// Signs the header with the given secret key and writes the seal into the last [EXTRA_SEAL]
+/// bytes of its extra-data.
+///
+/// The extra-data must already reserve space for the seal, see [unsealed_extra_data].
+pub fn seal_header(mut header: Header, secret: H256) -> Result<Header, ConsensusError> {
+    let len = header.extra_data.len();
+    if len < EXTRA_VANITY + EXTRA_SEAL {
+        return Err(ConsensusError::CliqueMissingSignature)
+    }
+
+    let signature = sign_message(secret, seal_hash(&header))
+        .map_err(|_| ConsensusError::CliqueSignerRecoveryError)?;
+
+    let mut extra_data = header.extra_data[..len - EXTRA_SEAL].to_vec();
+    extra_data.extend_from_slice(&signature.r.to_be_bytes::<32>());
+    extra_data.extend_from_slice(&signature.s.to_be_bytes::<32>());
+    extra_data.push(signature.odd_y_parity as u8);
+    header.extra_data = extra_data.into();
+
+    Ok(header)
+}
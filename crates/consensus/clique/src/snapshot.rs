@@ -0,0 +1,388 @@
+//! Clique vote snapshots.
+use crate::seal;
+use reth_db::models::{StoredCliqueRecent, StoredCliqueSnapshot, StoredCliqueVote};
+use reth_interfaces::consensus::ConsensusError;
+use reth_primitives::{Address, BlockNumber, SealedHeader, H256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Vote nonce to add a new signer.
+pub const NONCE_AUTH_VOTE: u64 = u64::MAX;
+
+/// Vote nonce to remove a signer.
+pub const NONCE_DROP_VOTE: u64 = 0;
+
+/// A single vote that an authorized signer made to modify the list of authorizations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vote {
+    /// The authorized signer that cast this vote.
+    pub signer: Address,
+    /// The block number the vote was cast in.
+    pub block_number: BlockNumber,
+    /// The account being voted on.
+    pub address: Address,
+    /// Whether to authorize or deauthorize the voted account.
+    pub authorize: bool,
+}
+
+/// A simple vote tally to keep the current score of votes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tally {
+    /// Whether the vote is about authorizing or kicking someone.
+    pub authorize: bool,
+    /// Number of votes until now wanting to pass the proposal.
+    pub votes: usize,
+}
+
+/// The state of the authorization voting at a given point in time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// The number of the block this snapshot was created at.
+    pub number: BlockNumber,
+    /// The hash of the block this snapshot was created at.
+    pub hash: H256,
+    /// The authorized signers, sorted in ascending order.
+    pub signers: BTreeSet<Address>,
+    /// The signers of the most recent blocks, used for spam protection.
+    pub recents: BTreeMap<BlockNumber, Address>,
+    /// The pending votes in chronological order.
+    pub votes: Vec<Vote>,
+    /// The current vote tally to avoid recalculating.
+    pub tally: HashMap<Address, Tally>,
+}
+
+impl Snapshot {
+    /// Creates a new snapshot with the given signers and no pending votes.
+    ///
+    /// This is used for the genesis block and epoch checkpoints.
+    pub fn new(
+        number: BlockNumber,
+        hash: H256,
+        signers: impl IntoIterator<Item = Address>,
+    ) -> Self {
+        Self {
+            number,
+            hash,
+            signers: signers.into_iter().collect(),
+            recents: Default::default(),
+            votes: Default::default(),
+            tally: Default::default(),
+        }
+    }
+
+    /// Creates a new snapshot from the signers listed in a checkpoint header.
+    pub fn from_checkpoint(header: &SealedHeader) -> Result<Self, ConsensusError> {
+        Ok(Self::new(header.number, header.hash(), seal::checkpoint_signers(header)?))
+    }
+
+    /// Returns the authorized signers in ascending order.
+    pub fn signers(&self) -> Vec<Address> {
+        self.signers.iter().copied().collect()
+    }
+
+    /// Returns whether the given signer is in-turn at the given block number.
+    pub fn inturn(&self, number: BlockNumber, signer: Address) -> bool {
+        match self.signers.iter().position(|s| *s == signer) {
+            Some(offset) => (number % self.signers.len() as u64) as usize == offset,
+            None => false,
+        }
+    }
+
+    /// Returns whether the given signer sealed one of the recent blocks and therefore has to wait
+    /// before it may seal the given block number.
+    pub fn recently_signed(&self, number: BlockNumber, signer: Address) -> bool {
+        let limit = self.signer_limit();
+        self.recents.iter().any(|(seen, recent)| *recent == signer && number < limit + seen)
+    }
+
+    /// Number of consecutive blocks out of which a signer may only sign one.
+    fn signer_limit(&self) -> u64 {
+        (self.signers.len() / 2 + 1) as u64
+    }
+
+    /// Returns whether it makes sense to cast the given vote, i.e. it does not try to add an
+    /// existing signer or remove a non-signer.
+    fn valid_vote(&self, address: Address, authorize: bool) -> bool {
+        self.signers.contains(&address) != authorize
+    }
+
+    /// Adds a new vote into the tally.
+    fn cast(&mut self, address: Address, authorize: bool) -> bool {
+        if !self.valid_vote(address, authorize) {
+            return false
+        }
+        self.tally
+            .entry(address)
+            .and_modify(|tally| tally.votes += 1)
+            .or_insert(Tally { authorize, votes: 1 });
+        true
+    }
+
+    /// Removes a previously cast vote from the tally.
+    fn uncast(&mut self, address: Address, authorize: bool) -> bool {
+        let Some(tally) = self.tally.get_mut(&address) else { return false };
+        if tally.authorize != authorize {
+            return false
+        }
+        if tally.votes > 1 {
+            tally.votes -= 1;
+        } else {
+            self.tally.remove(&address);
+        }
+        true
+    }
+
+    /// Applies the header with the given signer on top of this snapshot, returning the snapshot
+    /// at that header.
+    ///
+    /// The header must be the direct child of the block this snapshot was created at, and the
+    /// signer must have been recovered from its seal.
+    pub fn apply(
+        &self,
+        header: &SealedHeader,
+        signer: Address,
+        epoch: u64,
+    ) -> Result<Self, ConsensusError> {
+        let mut snapshot = self.clone();
+        let number = header.number;
+
+        // Remove any votes on checkpoint blocks
+        if number % epoch == 0 {
+            snapshot.votes.clear();
+            snapshot.tally.clear();
+        }
+
+        // Delete the oldest signer from the recent list to allow it signing again
+        let limit = snapshot.signer_limit();
+        if number >= limit {
+            snapshot.recents.remove(&(number - limit));
+        }
+
+        if !snapshot.signers.contains(&signer) {
+            return Err(ConsensusError::CliqueUnauthorizedSigner { signer })
+        }
+        if snapshot.recents.values().any(|recent| *recent == signer) {
+            return Err(ConsensusError::CliqueRecentlySigned { signer })
+        }
+        snapshot.recents.insert(number, signer);
+
+        // Discard any previous votes from the signer on the same account
+        let address = header.beneficiary;
+        if let Some(idx) =
+            snapshot.votes.iter().position(|vote| vote.signer == signer && vote.address == address)
+        {
+            let vote = snapshot.votes.remove(idx);
+            snapshot.uncast(vote.address, vote.authorize);
+        }
+
+        let authorize = match header.nonce {
+            NONCE_AUTH_VOTE => true,
+            NONCE_DROP_VOTE => false,
+            nonce => return Err(ConsensusError::CliqueInvalidVote { nonce }),
+        };
+        if snapshot.cast(address, authorize) {
+            snapshot.votes.push(Vote { signer, block_number: number, address, authorize });
+        }
+
+        // If the vote passed, update the list of signers
+        if let Some(tally) = snapshot.tally.get(&address).copied() {
+            if tally.votes > snapshot.signers.len() / 2 {
+                if tally.authorize {
+                    snapshot.signers.insert(address);
+                } else {
+                    snapshot.signers.remove(&address);
+
+                    // Signer list shrunk, delete any leftover recent caches
+                    let limit = snapshot.signer_limit();
+                    if number >= limit {
+                        snapshot.recents.remove(&(number - limit));
+                    }
+
+                    // Discard any previous votes the deauthorized signer cast
+                    let (cast, votes) = std::mem::take(&mut snapshot.votes)
+                        .into_iter()
+                        .partition::<Vec<_>, _>(|vote| vote.signer == address);
+                    snapshot.votes = votes;
+                    for vote in cast {
+                        snapshot.uncast(vote.address, vote.authorize);
+                    }
+                }
+
+                // Discard any previous votes around the just changed account
+                snapshot.votes.retain(|vote| vote.address != address);
+                snapshot.tally.remove(&address);
+            }
+        }
+
+        snapshot.number = number;
+        snapshot.hash = header.hash();
+
+        Ok(snapshot)
+    }
+}
+
+impl From<StoredCliqueSnapshot> for Snapshot {
+    fn from(stored: StoredCliqueSnapshot) -> Self {
+        // The snapshot number is the table key and not part of the stored value
+        let mut snapshot = Snapshot::new(0, stored.hash, stored.signers);
+        snapshot.recents =
+            stored.recents.into_iter().map(|recent| (recent.block_number, recent.signer)).collect();
+        for vote in stored.votes {
+            snapshot.cast(vote.address, vote.authorize);
+            snapshot.votes.push(Vote {
+                signer: vote.signer,
+                block_number: vote.block_number,
+                address: vote.address,
+                authorize: vote.authorize,
+            });
+        }
+        snapshot
+    }
+}
+
+impl From<Snapshot> for StoredCliqueSnapshot {
+    fn from(snapshot: Snapshot) -> Self {
+        StoredCliqueSnapshot {
+            hash: snapshot.hash,
+            signers: snapshot.signers.into_iter().collect(),
+            recents: snapshot
+                .recents
+                .into_iter()
+                .map(|(block_number, signer)| StoredCliqueRecent { block_number, signer })
+                .collect(),
+            votes: snapshot
+                .votes
+                .into_iter()
+                .map(|vote| StoredCliqueVote {
+                    signer: vote.signer,
+                    block_number: vote.block_number,
+                    address: vote.address,
+                    authorize: vote.authorize,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::Header;
+
+    /// Creates a header at the given number that votes on the given address.
+    fn voting_header(number: BlockNumber, address: Address, authorize: bool) -> SealedHeader {
+        Header {
+            number,
+            beneficiary: address,
+            nonce: if authorize { NONCE_AUTH_VOTE } else { NONCE_DROP_VOTE },
+            ..Default::default()
+        }
+        .seal_slow()
+    }
+
+    fn signers(count: usize) -> Vec<Address> {
+        let mut signers = (0..count).map(|_| Address::random()).collect::<Vec<_>>();
+        signers.sort();
+        signers
+    }
+
+    #[test]
+    fn inturn_rotates_over_sorted_signers() {
+        let signers = signers(3);
+        let snapshot = Snapshot::new(0, H256::zero(), signers.clone());
+
+        for number in 0..6 {
+            let expected = signers[number as usize % 3];
+            assert!(snapshot.inturn(number, expected));
+            for other in signers.iter().filter(|s| **s != expected) {
+                assert!(!snapshot.inturn(number, *other));
+            }
+        }
+        assert!(!snapshot.inturn(0, Address::random()));
+    }
+
+    #[test]
+    fn rejects_unauthorized_and_recent_signers() {
+        let signers = signers(3);
+        let snapshot = Snapshot::new(0, H256::zero(), signers.clone());
+
+        let outsider = Address::random();
+        assert_eq!(
+            snapshot.apply(&voting_header(1, Address::zero(), false), outsider, 30000),
+            Err(ConsensusError::CliqueUnauthorizedSigner { signer: outsider })
+        );
+
+        // with 3 signers, a signer may sign one out of two consecutive blocks
+        let snapshot =
+            snapshot.apply(&voting_header(1, Address::zero(), false), signers[0], 30000).unwrap();
+        assert!(snapshot.recently_signed(2, signers[0]));
+        assert_eq!(
+            snapshot.apply(&voting_header(2, Address::zero(), false), signers[0], 30000),
+            Err(ConsensusError::CliqueRecentlySigned { signer: signers[0] })
+        );
+
+        let snapshot =
+            snapshot.apply(&voting_header(2, Address::zero(), false), signers[1], 30000).unwrap();
+        assert!(!snapshot.recently_signed(3, signers[0]));
+        snapshot.apply(&voting_header(3, Address::zero(), false), signers[0], 30000).unwrap();
+    }
+
+    #[test]
+    fn majority_vote_authorizes_and_drops_signers() {
+        let signers = signers(2);
+        let candidate = Address::random();
+        let snapshot = Snapshot::new(0, H256::zero(), signers.clone());
+
+        // a single vote out of two signers is not a majority
+        let snapshot =
+            snapshot.apply(&voting_header(1, candidate, true), signers[0], 30000).unwrap();
+        assert!(!snapshot.signers.contains(&candidate));
+        assert_eq!(snapshot.tally[&candidate], Tally { authorize: true, votes: 1 });
+
+        let snapshot =
+            snapshot.apply(&voting_header(2, candidate, true), signers[1], 30000).unwrap();
+        assert!(snapshot.signers.contains(&candidate));
+        assert!(snapshot.votes.is_empty());
+        assert!(snapshot.tally.is_empty());
+
+        // two out of three signers drop the candidate again
+        let snapshot =
+            snapshot.apply(&voting_header(3, candidate, false), signers[0], 30000).unwrap();
+        assert!(snapshot.signers.contains(&candidate));
+        let snapshot =
+            snapshot.apply(&voting_header(4, candidate, false), signers[1], 30000).unwrap();
+        assert!(!snapshot.signers.contains(&candidate));
+        assert_eq!(snapshot.signers(), signers);
+    }
+
+    #[test]
+    fn epoch_checkpoint_resets_votes() {
+        let signers = signers(3);
+        let candidate = Address::random();
+        let snapshot = Snapshot::new(0, H256::zero(), signers.clone());
+
+        let snapshot = snapshot.apply(&voting_header(1, candidate, true), signers[1], 4).unwrap();
+        assert_eq!(snapshot.votes.len(), 1);
+
+        let snapshot =
+            snapshot.apply(&voting_header(2, Address::zero(), false), signers[2], 4).unwrap();
+        let snapshot =
+            snapshot.apply(&voting_header(3, Address::zero(), false), signers[0], 4).unwrap();
+        let snapshot =
+            snapshot.apply(&voting_header(4, Address::zero(), false), signers[1], 4).unwrap();
+        assert!(snapshot.votes.is_empty());
+        assert!(snapshot.tally.is_empty());
+    }
+
+    #[test]
+    fn stored_snapshot_roundtrip() {
+        let signers = signers(2);
+        let candidate = Address::random();
+        let snapshot = Snapshot::new(0, H256::zero(), signers.clone())
+            .apply(&voting_header(1, candidate, true), signers[0], 30000)
+            .unwrap();
+
+        let mut restored = Snapshot::from(StoredCliqueSnapshot::from(snapshot.clone()));
+        restored.number = snapshot.number;
+        assert_eq!(restored, snapshot);
+    }
+}
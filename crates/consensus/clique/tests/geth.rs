@@ -0,0 +1,72 @@
+//! Validates the blocks sealed by a clique geth instance.
+use ethers_core::{
+    types::{transaction::eip2718::TypedTransaction, Eip1559TransactionRequest, H160, U64},
+    utils::{secret_key_to_address, Geth},
+};
+use ethers_providers::Middleware;
+use reth_clique_consensus::CliqueConsensus;
+use reth_db::mdbx::test_utils::create_test_rw_db;
+use reth_interfaces::consensus::Consensus;
+use reth_primitives::{Address, ChainSpec, SealedHeader};
+use reth_staged_sync::test_utils::{CliqueGethInstance, CliqueMiddleware};
+use std::sync::Arc;
+
+#[tokio::test(flavor = "multi_thread")]
+#[cfg_attr(not(feature = "geth-tests"), ignore)]
+async fn validates_geth_sealed_blocks() {
+    reth_tracing::init_test_tracing();
+
+    let data_dir = tempfile::tempdir().expect("should be able to create temp geth datadir");
+    let clique_geth = Geth::new().chain_id(13337u64).data_dir(data_dir.path().to_str().unwrap());
+    let mut clique = CliqueGethInstance::new(clique_geth, None).await;
+
+    // don't print logs, but drain the stderr
+    clique.prevent_blocking().await;
+
+    // get geth to start producing blocks - use a blank password
+    let clique_private_key = clique
+        .instance
+        .clique_private_key()
+        .clone()
+        .expect("clique should be configured with a private key");
+    let signer = Address::from(secret_key_to_address(&clique_private_key).0);
+    clique.provider.enable_mining(clique_private_key, "".into()).await.unwrap();
+
+    let chainspec: ChainSpec = clique
+        .instance
+        .genesis()
+        .clone()
+        .expect("clique should be configured with a genesis")
+        .into();
+    assert!(chainspec.clique.is_some(), "geth genesis should configure clique");
+
+    // === create blocks ===
+
+    let txs = (0..100u64).map(|nonce| {
+        // create a tx that just sends to the zero addr
+        TypedTransaction::Eip1559(
+            Eip1559TransactionRequest::new().to(H160::zero()).value(1u64).nonce(nonce),
+        )
+    });
+    clique.provider.send_requests(txs).await.unwrap();
+
+    let tip = clique.provider.get_block_number().await.unwrap();
+    assert!(tip > U64::zero());
+
+    // === validate all blocks in order ===
+
+    let consensus = CliqueConsensus::new(Arc::new(chainspec), create_test_rw_db());
+    let mut parent = SealedHeader::from(&clique.provider.remote_genesis_block().await.unwrap());
+    for number in 1..=tip.as_u64() {
+        let block = clique.provider.get_block(number).await.unwrap().expect("block should exist");
+        let header = SealedHeader::from(&block);
+
+        consensus.validate_header(&header).unwrap();
+        consensus.validate_header_against_parent(&header, &parent).unwrap();
+        parent = header;
+    }
+
+    let snapshot = consensus.snapshot(&parent).unwrap().expect("all ancestors were validated");
+    assert_eq!(snapshot.signers(), vec![signer]);
+    assert_eq!(snapshot.number, tip.as_u64());
+}
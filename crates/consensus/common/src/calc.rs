@@ -1,4 +1,26 @@
-use reth_primitives::{constants::ETH_TO_WEI, BlockNumber, Chain, ChainSpec, Hardfork, U256};
+use reth_primitives::{
+    constants::{EMPTY_OMMER_ROOT, ETH_TO_WEI},
+    BlockNumber, Chain, ChainSpec, Hardfork, Header, U256,
+};
+
+/// The lower bound of the difficulty of a proof-of-work block.
+pub const MINIMUM_DIFFICULTY: U256 = U256::from_limbs([131_072, 0, 0, 0]);
+
+/// The divisor of the parent difficulty that bounds the difficulty adjustment of a block.
+pub const DIFFICULTY_BOUND_DIVISOR: U256 = U256::from_limbs([2048, 0, 0, 0]);
+
+/// The number of blocks between each doubling of the difficulty bomb.
+pub const DIFFICULTY_BOMB_PERIOD: u64 = 100_000;
+
+/// The hardforks that delayed the difficulty bomb, and by how many blocks, latest first.
+const DIFFICULTY_BOMB_DELAYS: [(Hardfork, u64); 6] = [
+    (Hardfork::GrayGlacier, 11_400_000),
+    (Hardfork::ArrowGlacier, 10_700_000),
+    (Hardfork::London, 9_700_000),
+    (Hardfork::MuirGlacier, 9_000_000),
+    (Hardfork::Constantinople, 5_000_000),
+    (Hardfork::Byzantium, 3_000_000),
+];
 
 /// Calculates the base block reward.
 ///
@@ -102,10 +124,89 @@ pub fn ommer_reward(
     U256::from(((8 + ommer_block_number - block_number) as u128 * base_block_reward) >> 3)
 }
 
+/// Returns the number of blocks the difficulty bomb is delayed by at the given block, if any.
+///
+/// Every delay is paired with the Byzantium difficulty adjustment, so a delay is returned for all
+/// blocks from Byzantium onwards.
+pub fn difficulty_bomb_delay(chain_spec: &ChainSpec, block_number: BlockNumber) -> Option<u64> {
+    DIFFICULTY_BOMB_DELAYS
+        .into_iter()
+        .find(|(fork, _)| chain_spec.fork(*fork).active_at_block(block_number))
+        .map(|(_, delay)| delay)
+}
+
+/// Calculates the expected difficulty of a proof-of-work block from its parent and timestamp.
+///
+/// The difficulty is the parent difficulty, adjusted by `parent_difficulty / 2048` per step of
+/// the adjustment factor, bounded by [MINIMUM_DIFFICULTY], plus the exponential difficulty bomb:
+///
+/// - For Byzantium and later: the factor is `max(y - (timestamp - parent_timestamp) / 9, -99)`
+///   where `y` is 2 if the parent has ommers and 1 otherwise, and the bomb is computed from a block
+///   number reduced by the [delay](difficulty_bomb_delay) of the latest bomb delay hardfork
+/// - For Homestead and later: the factor is `max(1 - (timestamp - parent_timestamp) / 10, -99)`
+/// - Otherwise: the factor is 1 if the block was sealed less than 13 seconds after its parent, and
+///   -1 otherwise
+///
+/// This is only meaningful for blocks before the Paris hardfork.
+///
+/// # References
+///
+/// - Definition: [Yellow Paper][yp] (page 6, 4.3.4)
+/// - Adjustments: [EIP-2], [EIP-100], bomb delays [EIP-649], [EIP-1234], [EIP-2384], [EIP-3554],
+///   [EIP-4345], [EIP-5133]
+///
+/// [yp]: https://ethereum.github.io/yellowpaper/paper.pdf
+/// [EIP-2]: https://eips.ethereum.org/EIPS/eip-2
+/// [EIP-100]: https://eips.ethereum.org/EIPS/eip-100
+/// [EIP-649]: https://eips.ethereum.org/EIPS/eip-649
+/// [EIP-1234]: https://eips.ethereum.org/EIPS/eip-1234
+/// [EIP-2384]: https://eips.ethereum.org/EIPS/eip-2384
+/// [EIP-3554]: https://eips.ethereum.org/EIPS/eip-3554
+/// [EIP-4345]: https://eips.ethereum.org/EIPS/eip-4345
+/// [EIP-5133]: https://eips.ethereum.org/EIPS/eip-5133
+pub fn block_difficulty(chain_spec: &ChainSpec, parent: &Header, timestamp: u64) -> U256 {
+    let block_number = parent.number + 1;
+    let time_delta = timestamp.saturating_sub(parent.timestamp);
+    let bomb_delay = difficulty_bomb_delay(chain_spec, block_number);
+
+    // the adjustment factor is bounded to [-99, 2], so the time delta is capped to avoid overflows
+    let factor = if bomb_delay.is_some() {
+        let ommers = if parent.ommers_hash == EMPTY_OMMER_ROOT { 1 } else { 2 };
+        (ommers - time_delta.min(1_000) as i64 / 9).max(-99)
+    } else if chain_spec.fork(Hardfork::Homestead).active_at_block(block_number) {
+        (1 - time_delta.min(1_000) as i64 / 10).max(-99)
+    } else if time_delta < 13 {
+        1
+    } else {
+        -1
+    };
+
+    let adjustment =
+        parent.difficulty / DIFFICULTY_BOUND_DIVISOR * U256::from(factor.unsigned_abs());
+    let difficulty = if factor >= 0 {
+        parent.difficulty.saturating_add(adjustment)
+    } else {
+        parent.difficulty.saturating_sub(adjustment)
+    };
+    let mut difficulty = difficulty.max(MINIMUM_DIFFICULTY);
+
+    // the delayed bomb uses a fake block number that is computed from the parent number
+    let bomb_number = match bomb_delay {
+        Some(delay) => parent.number.saturating_sub(delay - 1),
+        None => block_number,
+    };
+    let period = bomb_number / DIFFICULTY_BOMB_PERIOD;
+    if period > 1 {
+        difficulty = difficulty.saturating_add(U256::from(1) << (period - 2) as usize);
+    }
+
+    difficulty
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use reth_primitives::{MAINNET, U256};
+    use reth_primitives::{ChainSpecBuilder, MAINNET, U256};
 
     #[test]
     fn calc_base_block_reward() {
@@ -142,4 +243,107 @@ mod tests {
             assert_eq!(block_reward(base_reward, num_ommers), U256::from(expected_reward));
         }
     }
+
+    #[test]
+    fn calc_frontier_block_difficulty() {
+        // mainnet genesis and blocks 1 and 2
+        let genesis = MAINNET.genesis_header();
+        let difficulty = block_difficulty(&MAINNET, &genesis, 1438269988);
+        assert_eq!(difficulty, U256::from(17_171_480_576u64));
+
+        let block_1 = Header {
+            number: 1,
+            timestamp: 1438269988,
+            difficulty,
+            ommers_hash: EMPTY_OMMER_ROOT,
+            ..Default::default()
+        };
+        assert_eq!(block_difficulty(&MAINNET, &block_1, 1438270017), U256::from(17_163_096_064u64));
+        assert_eq!(block_difficulty(&MAINNET, &block_1, 1438269990), U256::from(17_179_865_088u64));
+    }
+
+    #[test]
+    fn calc_homestead_block_difficulty() {
+        let chain_spec = ChainSpecBuilder::mainnet().homestead_activated().build();
+        let parent = Header {
+            number: 10,
+            timestamp: 1000,
+            difficulty: U256::from(2048 * 1000),
+            ..Default::default()
+        };
+
+        // (time delta, expected difficulty)
+        let cases = [
+            (5, 2048 * 1000 + 1000),
+            (15, 2048 * 1000),
+            (25, 2048 * 1000 - 1000),
+            // the adjustment is bounded by -99
+            (100_000, 2048 * 1000 - 99 * 1000),
+        ];
+        for (delta, expected) in cases {
+            assert_eq!(
+                block_difficulty(&chain_spec, &parent, parent.timestamp + delta),
+                U256::from(expected)
+            );
+        }
+
+        // the difficulty never drops below the minimum
+        let parent = Header { difficulty: MINIMUM_DIFFICULTY, ..parent };
+        assert_eq!(block_difficulty(&chain_spec, &parent, 100_000), MINIMUM_DIFFICULTY);
+    }
+
+    #[test]
+    fn calc_byzantium_block_difficulty() {
+        let chain_spec = ChainSpecBuilder::mainnet().byzantium_activated().build();
+        let parent = Header {
+            number: 10,
+            timestamp: 1000,
+            difficulty: U256::from(2048 * 1000),
+            ommers_hash: EMPTY_OMMER_ROOT,
+            ..Default::default()
+        };
+
+        assert_eq!(block_difficulty(&chain_spec, &parent, 1005), U256::from(2048 * 1000 + 1000));
+        assert_eq!(block_difficulty(&chain_spec, &parent, 1009), U256::from(2048 * 1000));
+
+        // ommers increase the target block time
+        let parent = Header { ommers_hash: Default::default(), ..parent };
+        assert_eq!(block_difficulty(&chain_spec, &parent, 1005), U256::from(2048 * 1000 + 2000));
+        assert_eq!(block_difficulty(&chain_spec, &parent, 1009), U256::from(2048 * 1000 + 1000));
+    }
+
+    #[test]
+    fn calc_difficulty_bomb() {
+        let parent = Header {
+            timestamp: 1000,
+            difficulty: U256::from(2048 * 1000),
+            ommers_hash: EMPTY_OMMER_ROOT,
+            ..Default::default()
+        };
+
+        // frontier block 1_000_000 is in period 10
+        let frontier = Header { number: 999_999, ..parent.clone() };
+        let chain_spec = ChainSpecBuilder::mainnet().frontier_activated().build();
+        assert_eq!(
+            block_difficulty(&chain_spec, &frontier, 1020),
+            U256::from(2048 * 1000 - 1000 + (1 << 8))
+        );
+
+        // byzantium delays the bomb by 3_000_000 blocks
+        let byzantium = Header { number: 4_369_999, ..parent.clone() };
+        assert_eq!(difficulty_bomb_delay(&MAINNET, 4_370_000), Some(3_000_000));
+        assert_eq!(
+            block_difficulty(&MAINNET, &byzantium, 1009),
+            U256::from(2048 * 1000 + (1 << 11))
+        );
+
+        // gray glacier delays the bomb by 11_400_000 blocks, back to period 36
+        let gray_glacier = Header { number: 15_050_000, ..parent };
+        assert_eq!(difficulty_bomb_delay(&MAINNET, 15_050_001), Some(11_400_000));
+        assert_eq!(
+            block_difficulty(&MAINNET, &gray_glacier, 1009),
+            U256::from(2048 * 1000 + (1 << 34))
+        );
+        assert_eq!(difficulty_bomb_delay(&MAINNET, 1), None);
+    }
 }
@@ -1,8 +1,11 @@
 //! Collection of methods for block validation.
+use crate::calc;
 use reth_interfaces::{consensus::ConsensusError, Result as RethResult};
 use reth_primitives::{
-    constants, BlockNumber, ChainSpec, Hardfork, Header, InvalidTransactionError, SealedBlock,
-    SealedHeader, Transaction, TransactionSignedEcRecovered, TxEip1559, TxEip2930, TxLegacy,
+    constants::{self, DATA_GAS_PER_BLOB, MAX_DATA_GAS_PER_BLOCK, VERSIONED_HASH_VERSION_KZG},
+    BlockNumber, ChainSpec, Hardfork, Header, InvalidTransactionError, SealedBlock, SealedHeader,
+    Transaction, TransactionKind, TransactionSignedEcRecovered, TxEip1559, TxEip2930, TxEip4844,
+    TxLegacy,
 };
 use reth_provider::{AccountProvider, HeaderProvider, WithdrawalsProvider};
 use std::{
@@ -51,6 +54,50 @@ pub fn validate_header_standalone(
         return Err(ConsensusError::WithdrawalsRootUnexpected)
     }
 
+    // EIP-4844: Shard Blob Transactions
+    // EIP-4788: Beacon block root in the EVM
+    if chain_spec.is_cancun_activated_at_timestamp(header.timestamp) {
+        validate_4844_header_standalone(header)?;
+        if header.parent_beacon_block_root.is_none() {
+            return Err(ConsensusError::ParentBeaconBlockRootMissing)
+        }
+    } else if header.blob_gas_used.is_some() {
+        return Err(ConsensusError::BlobGasUsedUnexpected)
+    } else if header.excess_blob_gas.is_some() {
+        return Err(ConsensusError::ExcessBlobGasUnexpected)
+    } else if header.parent_beacon_block_root.is_some() {
+        return Err(ConsensusError::ParentBeaconBlockRootUnexpected)
+    }
+
+    Ok(())
+}
+
+/// Validates the blob gas fields of a header that is post Cancun.
+///
+/// - Both `blob_gas_used` and `excess_blob_gas` are present
+/// - `blob_gas_used` doesn't exceed the maximum allowance
+/// - `blob_gas_used` is a multiple of `DATA_GAS_PER_BLOB`
+pub fn validate_4844_header_standalone(header: &SealedHeader) -> Result<(), ConsensusError> {
+    let blob_gas_used = header.blob_gas_used.ok_or(ConsensusError::BlobGasUsedMissing)?;
+
+    if header.excess_blob_gas.is_none() {
+        return Err(ConsensusError::ExcessBlobGasMissing)
+    }
+
+    if blob_gas_used > MAX_DATA_GAS_PER_BLOCK {
+        return Err(ConsensusError::BlobGasUsedExceedsMaxBlobGasPerBlock {
+            blob_gas_used,
+            max_blob_gas_per_block: MAX_DATA_GAS_PER_BLOCK,
+        })
+    }
+
+    if blob_gas_used % DATA_GAS_PER_BLOB != 0 {
+        return Err(ConsensusError::BlobGasUsedNotMultipleOfBlobGasPerBlob {
+            blob_gas_used,
+            blob_gas_per_blob: DATA_GAS_PER_BLOB,
+        })
+    }
+
     Ok(())
 }
 
@@ -97,6 +144,34 @@ pub fn validate_transaction_regarding_header(
                 return Err(InvalidTransactionError::TipAboveFeeCap.into())
             }
 
+            Some(*chain_id)
+        }
+        Transaction::Eip4844(TxEip4844 {
+            chain_id,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            to,
+            blob_versioned_hashes,
+            ..
+        }) => {
+            // EIP-4844 is activated by timestamp, whether it is active is checked against the
+            // block in [validate_block_standalone]
+
+            if max_priority_fee_per_gas > max_fee_per_gas {
+                return Err(InvalidTransactionError::TipAboveFeeCap.into())
+            }
+
+            // EIP-4844: Shard Blob Transactions https://eips.ethereum.org/EIPS/eip-4844
+            if blob_versioned_hashes.is_empty() {
+                return Err(InvalidTransactionError::BlobTransactionMissingBlobHashes.into())
+            }
+            if matches!(to, TransactionKind::Create) {
+                return Err(InvalidTransactionError::BlobTransactionIsCreate.into())
+            }
+            if blob_versioned_hashes.iter().any(|hash| hash[0] != VERSIONED_HASH_VERSION_KZG) {
+                return Err(InvalidTransactionError::BlobVersionedHashVersionInvalid.into())
+            }
+
             Some(*chain_id)
         }
     };
@@ -230,6 +305,22 @@ pub fn validate_block_standalone(
         }
     }
 
+    // EIP-4844: Shard Blob Transactions
+    if chain_spec.is_cancun_activated_at_timestamp(block.timestamp) {
+        // Check that the blob gas used in the header matches the sum of the blob gas used by each
+        // blob tx
+        let header_blob_gas_used = block.blob_gas_used.ok_or(ConsensusError::BlobGasUsedMissing)?;
+        let total_blob_gas = block.body.iter().map(|tx| tx.blob_gas()).sum::<u64>();
+        if total_blob_gas != header_blob_gas_used {
+            return Err(ConsensusError::BlobGasUsedDiff {
+                got: header_blob_gas_used,
+                expected: total_blob_gas,
+            })
+        }
+    } else if block.body.iter().any(|tx| tx.is_eip4844()) {
+        return Err(InvalidTransactionError::Eip4844Disabled.into())
+    }
+
     Ok(())
 }
 
@@ -255,9 +346,21 @@ pub fn validate_header_regarding_parent(
         })
     }
 
-    // TODO Check difficulty increment between parent and child
-    // Ace age did increment it by some formula that we need to follow.
+    // The proof-of-work difficulty is checked separately by [validate_header_difficulty], since
+    // it only applies to pre-merge ethash headers.
 
+    validate_header_gas_regarding_parent(parent, child, chain_spec)
+}
+
+/// Validate the gas limit, base fee and excess blob gas of a block in regards to its parent.
+///
+/// This is split from [validate_header_regarding_parent] so that consensus engines with their own
+/// block number and timestamp rules can reuse it.
+pub fn validate_header_gas_regarding_parent(
+    parent: &SealedHeader,
+    child: &SealedHeader,
+    chain_spec: &ChainSpec,
+) -> Result<(), ConsensusError> {
     let mut parent_gas_limit = parent.gas_limit;
 
     // By consensus, gas_limit is multiplied by elasticity (*2) on
@@ -297,6 +400,36 @@ pub fn validate_header_regarding_parent(
         }
     }
 
+    // EIP-4844: Shard Blob Transactions
+    if chain_spec.is_cancun_activated_at_timestamp(child.timestamp) {
+        let excess_blob_gas = child.excess_blob_gas.ok_or(ConsensusError::ExcessBlobGasMissing)?;
+
+        // A pre-Cancun parent is treated as having zero excess and used blob gas
+        let expected_excess_blob_gas = parent.next_block_excess_blob_gas().unwrap_or_default();
+        if expected_excess_blob_gas != excess_blob_gas {
+            return Err(ConsensusError::ExcessBlobGasDiff {
+                expected: expected_excess_blob_gas,
+                got: excess_blob_gas,
+            })
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate the proof-of-work difficulty of a pre-merge block in regards to its parent.
+///
+/// See [calc::block_difficulty] for the difficulty calculation of every pre-Paris hardfork.
+pub fn validate_header_difficulty(
+    parent: &SealedHeader,
+    child: &SealedHeader,
+    chain_spec: &ChainSpec,
+) -> Result<(), ConsensusError> {
+    let expected = calc::block_difficulty(chain_spec, parent, child.timestamp);
+    if child.difficulty != expected {
+        return Err(ConsensusError::DifficultyDiff { expected, got: child.difficulty })
+    }
+
     Ok(())
 }
 
@@ -390,7 +523,7 @@ mod tests {
     use reth_interfaces::{Error::Consensus, Result};
     use reth_primitives::{
         hex_literal::hex, proofs, Account, Address, BlockHash, BlockHashOrNumber, Bytes,
-        ChainSpecBuilder, Header, Signature, TransactionKind, TransactionSigned, Withdrawal,
+        ChainSpecBuilder, Header, Signature, TransactionKind, TransactionSigned, Withdrawal, H256,
         MAINNET, U256,
     };
     use std::ops::RangeBounds;
@@ -536,6 +669,9 @@ mod tests {
             nonce: 0x0000000000000000,
             base_fee_per_gas: 0x28f0001df.into(),
             withdrawals_root: None,
+            blob_gas_used: None,
+            excess_blob_gas: None,
+            parent_beacon_block_root: None,
         };
         // size: 0x9b5
 
@@ -717,4 +853,169 @@ mod tests {
 
         assert_eq!(validate_header_standalone(&header, &chain_spec), Ok(()));
     }
+
+    #[test]
+    fn cancun_block_blob_gas_fields() {
+        let chain_spec = ChainSpecBuilder::mainnet().cancun_activated().build();
+        let cancun_header = |blob_gas_used, excess_blob_gas| {
+            Header {
+                base_fee_per_gas: Some(1337u64),
+                withdrawals_root: Some(proofs::calculate_withdrawals_root(&[])),
+                blob_gas_used,
+                excess_blob_gas,
+                parent_beacon_block_root: Some(H256::zero()),
+                ..Default::default()
+            }
+            .seal_slow()
+        };
+
+        let header = cancun_header(Some(2 * DATA_GAS_PER_BLOB), Some(0));
+        assert_eq!(validate_header_standalone(&header, &chain_spec), Ok(()));
+
+        let header = cancun_header(None, Some(0));
+        assert_eq!(
+            validate_header_standalone(&header, &chain_spec),
+            Err(ConsensusError::BlobGasUsedMissing)
+        );
+
+        let header = cancun_header(Some(0), None);
+        assert_eq!(
+            validate_header_standalone(&header, &chain_spec),
+            Err(ConsensusError::ExcessBlobGasMissing)
+        );
+
+        let header = cancun_header(Some(MAX_DATA_GAS_PER_BLOCK + DATA_GAS_PER_BLOB), Some(0));
+        assert_matches!(
+            validate_header_standalone(&header, &chain_spec),
+            Err(ConsensusError::BlobGasUsedExceedsMaxBlobGasPerBlock { .. })
+        );
+
+        let header = cancun_header(Some(DATA_GAS_PER_BLOB + 1), Some(0));
+        assert_matches!(
+            validate_header_standalone(&header, &chain_spec),
+            Err(ConsensusError::BlobGasUsedNotMultipleOfBlobGasPerBlob { .. })
+        );
+
+        let header =
+            Header { parent_beacon_block_root: None, ..cancun_header(Some(0), Some(0)).unseal() }
+                .seal_slow();
+        assert_eq!(
+            validate_header_standalone(&header, &chain_spec),
+            Err(ConsensusError::ParentBeaconBlockRootMissing)
+        );
+
+        // blob gas fields are not allowed before cancun
+        let shanghai = ChainSpecBuilder::mainnet().shanghai_activated().build();
+        let header = cancun_header(Some(0), Some(0));
+        assert_eq!(
+            validate_header_standalone(&header, &shanghai),
+            Err(ConsensusError::BlobGasUsedUnexpected)
+        );
+    }
+
+    #[test]
+    fn cancun_excess_blob_gas() {
+        let chain_spec = ChainSpecBuilder::mainnet().cancun_activated().build();
+        let parent = Header {
+            number: 1,
+            timestamp: 1,
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(7),
+            blob_gas_used: Some(MAX_DATA_GAS_PER_BLOCK),
+            excess_blob_gas: Some(DATA_GAS_PER_BLOB),
+            ..Default::default()
+        };
+        let mut child = Header {
+            number: 2,
+            timestamp: 2,
+            base_fee_per_gas: parent.next_block_base_fee(),
+            excess_blob_gas: parent.next_block_excess_blob_gas(),
+            ..parent.clone()
+        };
+        let parent = parent.seal_slow();
+
+        assert_eq!(
+            validate_header_regarding_parent(&parent, &child.clone().seal_slow(), &chain_spec),
+            Ok(())
+        );
+
+        child.excess_blob_gas = Some(0);
+        assert_eq!(
+            validate_header_regarding_parent(&parent, &child.seal_slow(), &chain_spec),
+            Err(ConsensusError::ExcessBlobGasDiff {
+                expected: MAX_DATA_GAS_PER_BLOCK + DATA_GAS_PER_BLOB - DATA_GAS_PER_BLOB * 3,
+                got: 0
+            })
+        );
+    }
+
+    #[test]
+    fn cancun_block_blob_gas_used() {
+        let chain_spec = ChainSpecBuilder::mainnet().cancun_activated().build();
+
+        let blob_tx = TransactionSigned::from_transaction_and_signature(
+            Transaction::Eip4844(TxEip4844 {
+                chain_id: 1,
+                to: TransactionKind::Call(Address::default()),
+                blob_versioned_hashes: vec![H256::default(); 2],
+                ..Default::default()
+            }),
+            Signature::default(),
+        );
+        let body = vec![blob_tx];
+        let block = |blob_gas_used| SealedBlock {
+            header: Header {
+                transactions_root: proofs::calculate_transaction_root(&body),
+                withdrawals_root: Some(proofs::calculate_withdrawals_root(&[])),
+                blob_gas_used: Some(blob_gas_used),
+                excess_blob_gas: Some(0),
+                ..Default::default()
+            }
+            .seal_slow(),
+            body: body.clone(),
+            withdrawals: Some(vec![]),
+            ..Default::default()
+        };
+
+        assert_eq!(validate_block_standalone(&block(2 * DATA_GAS_PER_BLOB), &chain_spec), Ok(()));
+        assert_eq!(
+            validate_block_standalone(&block(DATA_GAS_PER_BLOB), &chain_spec),
+            Err(ConsensusError::BlobGasUsedDiff {
+                got: DATA_GAS_PER_BLOB,
+                expected: 2 * DATA_GAS_PER_BLOB
+            })
+        );
+
+        // blob transactions are not allowed before cancun
+        let shanghai = ChainSpecBuilder::mainnet().shanghai_activated().build();
+        assert_eq!(
+            validate_block_standalone(&block(0), &shanghai),
+            Err(InvalidTransactionError::Eip4844Disabled.into())
+        );
+    }
+
+    #[test]
+    fn pre_merge_difficulty() {
+        let parent = MAINNET.genesis_header().seal_slow();
+        let mut child = Header {
+            number: 1,
+            timestamp: 1438269988,
+            difficulty: U256::from(17_171_480_576u64),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            validate_header_difficulty(&parent, &child.clone().seal_slow(), &MAINNET),
+            Ok(())
+        );
+
+        child.difficulty = parent.difficulty;
+        assert_eq!(
+            validate_header_difficulty(&parent, &child.seal_slow(), &MAINNET),
+            Err(ConsensusError::DifficultyDiff {
+                expected: U256::from(17_171_480_576u64),
+                got: parent.difficulty
+            })
+        );
+    }
 }
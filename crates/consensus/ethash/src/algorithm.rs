@@ -0,0 +1,360 @@
+//! The light ethash algorithm, which verifies a seal with the verification cache of an epoch
+//! instead of the full dataset.
+//!
+//! See the [ethash spec](https://ethereum.org/en/developers/docs/consensus-mechanisms/pow/mining-algorithms/ethash/).
+use reth_primitives::{keccak256, Header, H256, U256};
+use reth_rlp::Encodable;
+use tiny_keccak::{Hasher, Keccak};
+
+/// Number of blocks per ethash epoch.
+pub const EPOCH_LENGTH: u64 = 30_000;
+
+/// Bytes in word.
+const WORD_BYTES: usize = 4;
+/// Bytes in a hash of the cache and dataset.
+const HASH_BYTES: usize = 64;
+/// Words in a hash of the cache and dataset.
+const HASH_WORDS: usize = HASH_BYTES / WORD_BYTES;
+/// Width of the mix.
+const MIX_BYTES: usize = 128;
+/// Words in the mix.
+const MIX_WORDS: usize = MIX_BYTES / WORD_BYTES;
+/// Bytes of the cache at genesis.
+const CACHE_BYTES_INIT: u64 = 1 << 24;
+/// Cache growth per epoch.
+const CACHE_BYTES_GROWTH: u64 = 1 << 17;
+/// Bytes of the dataset at genesis.
+const DATASET_BYTES_INIT: u64 = 1 << 30;
+/// Dataset growth per epoch.
+const DATASET_BYTES_GROWTH: u64 = 1 << 23;
+/// Number of rounds in cache production.
+const CACHE_ROUNDS: usize = 3;
+/// Number of parents of each dataset element.
+const DATASET_PARENTS: u32 = 256;
+/// Number of accesses in the hashimoto loop.
+const ACCESSES: u32 = 64;
+
+/// Returns the epoch of the given block.
+pub fn epoch(block_number: u64) -> u64 {
+    block_number / EPOCH_LENGTH
+}
+
+/// Returns the size of the verification cache of the given epoch, in bytes.
+pub fn cache_size(epoch: u64) -> u64 {
+    let mut size = CACHE_BYTES_INIT + CACHE_BYTES_GROWTH * epoch - HASH_BYTES as u64;
+    while !is_prime(size / HASH_BYTES as u64) {
+        size -= 2 * HASH_BYTES as u64;
+    }
+    size
+}
+
+/// Returns the size of the full dataset of the given epoch, in bytes.
+pub fn dataset_size(epoch: u64) -> u64 {
+    let mut size = DATASET_BYTES_INIT + DATASET_BYTES_GROWTH * epoch - MIX_BYTES as u64;
+    while !is_prime(size / MIX_BYTES as u64) {
+        size -= 2 * MIX_BYTES as u64;
+    }
+    size
+}
+
+/// Returns the seed of the verification cache of the given epoch.
+pub fn seed_hash(epoch: u64) -> H256 {
+    (0..epoch).fold(H256::zero(), |seed, _| keccak256(seed))
+}
+
+/// Generates a verification cache of the given size in bytes from the seed, as little endian
+/// words.
+pub fn make_cache(size: u64, seed: H256) -> Vec<u32> {
+    let n = (size / HASH_BYTES as u64) as usize;
+
+    // sequentially produce the initial dataset
+    let mut items = Vec::with_capacity(n);
+    items.push(keccak512(seed.as_bytes()));
+    for i in 1..n {
+        let item = keccak512(&items[i - 1]);
+        items.push(item);
+    }
+
+    // apply a low-round version of randmemohash
+    for _ in 0..CACHE_ROUNDS {
+        for i in 0..n {
+            let v = u32::from_le_bytes(items[i][..WORD_BYTES].try_into().unwrap()) as usize % n;
+            let mut temp = items[(i + n - 1) % n];
+            for (t, b) in temp.iter_mut().zip(items[v].iter()) {
+                *t ^= b;
+            }
+            items[i] = keccak512(&temp);
+        }
+    }
+
+    items.iter().flat_map(to_words).collect()
+}
+
+/// Runs the light hashimoto loop for the seal hash and nonce of a header, and returns the mix
+/// digest and the proof-of-work result.
+///
+/// Dataset items are computed on demand from the verification cache.
+pub fn hashimoto_light(hash: H256, nonce: u64, dataset_size: u64, cache: &[u32]) -> (H256, H256) {
+    let rows = (dataset_size / MIX_BYTES as u64) as u32;
+    let mix_hashes = MIX_BYTES / HASH_BYTES;
+
+    // combine the header and nonce into a 64 byte seed
+    let mut seed = [0u8; 40];
+    seed[..32].copy_from_slice(hash.as_bytes());
+    seed[32..].copy_from_slice(&nonce.to_le_bytes());
+    let seed = keccak512(&seed);
+    let seed_head = u32::from_le_bytes(seed[..WORD_BYTES].try_into().unwrap());
+
+    // start the mix with the replicated seed
+    let seed_words = to_words(&seed);
+    let mut mix = [0u32; MIX_WORDS];
+    for (i, word) in mix.iter_mut().enumerate() {
+        *word = seed_words[i % HASH_WORDS];
+    }
+
+    // mix in random dataset nodes
+    for i in 0..ACCESSES {
+        let parent = fnv(i ^ seed_head, mix[i as usize % MIX_WORDS]) % rows * mix_hashes as u32;
+        for j in 0..mix_hashes {
+            let item = calc_dataset_item(cache, parent + j as u32);
+            for (word, item_word) in mix[j * HASH_WORDS..(j + 1) * HASH_WORDS].iter_mut().zip(item)
+            {
+                *word = fnv(*word, item_word);
+            }
+        }
+    }
+
+    // compress the mix
+    let mut digest = [0u8; 32];
+    for (i, words) in mix.chunks_exact(4).enumerate() {
+        let word = fnv(fnv(fnv(words[0], words[1]), words[2]), words[3]);
+        digest[i * WORD_BYTES..(i + 1) * WORD_BYTES].copy_from_slice(&word.to_le_bytes());
+    }
+
+    let mut result = [0u8; 96];
+    result[..64].copy_from_slice(&seed);
+    result[64..].copy_from_slice(&digest);
+    (H256(digest), keccak256(result))
+}
+
+/// Returns true if the proof-of-work result satisfies the difficulty, i.e. is at most
+/// `2^256 / difficulty`.
+pub fn meets_difficulty(result: H256, difficulty: U256) -> bool {
+    if difficulty == U256::ZERO {
+        return false
+    }
+
+    // `2^256 / difficulty` does not fit, but only differs from `(2^256 - 1) / difficulty` if the
+    // difficulty is a power of two.
+    let mut target = U256::MAX / difficulty;
+    if difficulty.count_ones() == 1 {
+        match target.checked_add(U256::from(1)) {
+            Some(exact) => target = exact,
+            None => return true,
+        }
+    }
+    U256::from_be_bytes(result.to_fixed_bytes()) <= target
+}
+
+/// Returns the hash that is sealed by the proof-of-work, i.e. the hash of the header without the
+/// mix digest and nonce.
+pub fn seal_hash(header: &Header) -> H256 {
+    let mut payload = Vec::new();
+    header.parent_hash.encode(&mut payload);
+    header.ommers_hash.encode(&mut payload);
+    header.beneficiary.encode(&mut payload);
+    header.state_root.encode(&mut payload);
+    header.transactions_root.encode(&mut payload);
+    header.receipts_root.encode(&mut payload);
+    header.logs_bloom.encode(&mut payload);
+    header.difficulty.encode(&mut payload);
+    U256::from(header.number).encode(&mut payload);
+    U256::from(header.gas_limit).encode(&mut payload);
+    U256::from(header.gas_used).encode(&mut payload);
+    header.timestamp.encode(&mut payload);
+    header.extra_data.encode(&mut payload);
+    if let Some(base_fee) = header.base_fee_per_gas {
+        U256::from(base_fee).encode(&mut payload);
+    }
+
+    let mut out = Vec::with_capacity(payload.len() + 4);
+    reth_rlp::Header { list: true, payload_length: payload.len() }.encode(&mut out);
+    out.extend_from_slice(&payload);
+    keccak256(out)
+}
+
+/// Computes a dataset item from the verification cache.
+fn calc_dataset_item(cache: &[u32], index: u32) -> [u32; HASH_WORDS] {
+    let n = cache.len() / HASH_WORDS;
+    let start = (index as usize % n) * HASH_WORDS;
+
+    let mut mix = [0u32; HASH_WORDS];
+    mix.copy_from_slice(&cache[start..start + HASH_WORDS]);
+    mix[0] ^= index;
+    let mut mix = keccak512_words(mix);
+
+    for j in 0..DATASET_PARENTS {
+        let parent = fnv(index ^ j, mix[j as usize % HASH_WORDS]) as usize % n;
+        let parent = &cache[parent * HASH_WORDS..(parent + 1) * HASH_WORDS];
+        for (word, parent_word) in mix.iter_mut().zip(parent) {
+            *word = fnv(*word, *parent_word);
+        }
+    }
+
+    keccak512_words(mix)
+}
+
+/// The FNV-1 inspired mixing function of ethash.
+fn fnv(a: u32, b: u32) -> u32 {
+    a.wrapping_mul(0x01000193) ^ b
+}
+
+fn keccak512(data: &[u8]) -> [u8; HASH_BYTES] {
+    let mut hasher = Keccak::v512();
+    hasher.update(data);
+    let mut output = [0u8; HASH_BYTES];
+    hasher.finalize(&mut output);
+    output
+}
+
+fn keccak512_words(words: [u32; HASH_WORDS]) -> [u32; HASH_WORDS] {
+    let mut bytes = [0u8; HASH_BYTES];
+    for (chunk, word) in bytes.chunks_exact_mut(WORD_BYTES).zip(words) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    to_words(&keccak512(&bytes))
+}
+
+fn to_words(bytes: &[u8; HASH_BYTES]) -> [u32; HASH_WORDS] {
+    let mut words = [0u32; HASH_WORDS];
+    for (word, chunk) in words.iter_mut().zip(bytes.chunks_exact(WORD_BYTES)) {
+        *word = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+    words
+}
+
+fn is_prime(n: u64) -> bool {
+    if n < 2 {
+        return false
+    }
+    if n % 2 == 0 {
+        return n == 2
+    }
+    let mut i = 3;
+    while i * i <= n {
+        if n % i == 0 {
+            return false
+        }
+        i += 2;
+    }
+    true
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use reth_primitives::{hex_literal::hex, Bytes, EMPTY_OMMER_ROOT};
+
+    /// Mainnet block 1.
+    pub(crate) fn mainnet_block_1() -> Header {
+        Header {
+            parent_hash: H256(hex!(
+                "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+            )),
+            ommers_hash: EMPTY_OMMER_ROOT,
+            beneficiary: hex!("05a56e2d52c817161883f50c441c3228cfe54d9f").into(),
+            state_root: H256(hex!(
+                "d67e4d450343046425ae4271474353857ab860dbc0a1dde64b41b5cd3a532bf3"
+            )),
+            transactions_root: H256(hex!(
+                "56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421"
+            )),
+            receipts_root: H256(hex!(
+                "56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421"
+            )),
+            difficulty: U256::from(17_171_480_576u64),
+            number: 1,
+            gas_limit: 5000,
+            timestamp: 1438269988,
+            extra_data: Bytes::from(&b"Geth/v1.0.0/linux/go1.4.2"[..]),
+            mix_hash: H256(hex!(
+                "969b900de27b6ac6a67742365dd65f55a0526c41fd18e1b16f1a1215c2e66f59"
+            )),
+            nonce: 0x539bd4979fef1ec4,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sizes_and_seeds() {
+        assert_eq!(cache_size(0), 16776896);
+        assert_eq!(dataset_size(0), 1073739904);
+        assert_eq!(cache_size(1), 16907456);
+        assert_eq!(dataset_size(1), 1082130304);
+
+        assert_eq!(seed_hash(0), H256::zero());
+        assert_eq!(
+            seed_hash(1),
+            H256(hex!("290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563"))
+        );
+        assert_eq!(epoch(29_999), 0);
+        assert_eq!(epoch(30_000), 1);
+    }
+
+    #[test]
+    fn mainnet_seal_hash() {
+        let header = mainnet_block_1();
+        assert_eq!(
+            header.hash_slow(),
+            H256(hex!("88e96d4537bea4d9c05d12549907b32561d3bf31f45aae734cdc119f13406cb6"))
+        );
+        assert_eq!(
+            seal_hash(&header),
+            H256(hex!("85913a3057ea8bec78cd916871ca73802e77724e014dda65add3405d02240eb7"))
+        );
+    }
+
+    #[test]
+    fn hashimoto_small_cache() {
+        let cache = make_cache(1024, H256::zero());
+        let hash = H256(hex!("c9149cc0386e689d789a1c2f3d5d169a61a6218ed30e74414dc736e442ef3d1d"));
+        let (digest, result) = hashimoto_light(hash, 0, 32 * 1024, &cache);
+        assert_eq!(
+            digest,
+            H256(hex!("45c86c6de1746d12b70ec26c7b8af5c6feadf09f40f16854995fc18dcfbbfb71"))
+        );
+        assert_eq!(
+            result,
+            H256(hex!("a97457c9dd491fed997976e911f72edb3903471ea613ccce8d7e1317b0296955"))
+        );
+    }
+
+    // Generating the epoch 0 cache takes a while in debug builds.
+    #[test]
+    #[ignore]
+    fn hashimoto_mainnet_block() {
+        let header = mainnet_block_1();
+        let cache = make_cache(cache_size(0), seed_hash(0));
+        let (digest, result) =
+            hashimoto_light(seal_hash(&header), header.nonce, dataset_size(0), &cache);
+        assert_eq!(digest, header.mix_hash);
+        assert!(meets_difficulty(result, header.difficulty));
+    }
+
+    #[test]
+    fn difficulty_target() {
+        let result = H256(hex!("000000002bc095dd4de049873e6302c3f14a7f2e5b5a1f60cdf1f1798164d610"));
+        assert!(meets_difficulty(result, U256::from(17_171_480_576u64)));
+        assert!(!meets_difficulty(result, U256::from(1) << 40));
+        assert!(!meets_difficulty(result, U256::ZERO));
+
+        // the target of power of two difficulties is exact
+        assert!(meets_difficulty(H256::repeat_byte(0xff), U256::from(1)));
+        assert!(meets_difficulty(H256::zero(), U256::from(2)));
+        let half = H256(hex!("8000000000000000000000000000000000000000000000000000000000000000"));
+        assert!(meets_difficulty(half, U256::from(2)));
+        let above_half =
+            H256(hex!("8000000000000000000000000000000000000000000000000000000000000001"));
+        assert!(!meets_difficulty(above_half, U256::from(2)));
+    }
+}
@@ -0,0 +1,193 @@
+//! Generation and storage of the ethash verification caches.
+use crate::algorithm;
+use parking_lot::Mutex;
+use reth_primitives::H256;
+use schnellru::{ByLength, LruMap};
+use std::{
+    fmt, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::{debug, trace, warn};
+
+/// Revision of the cache files, bumped if the file format changes.
+const CACHE_REVISION: u32 = 23;
+
+/// The verification cache of an epoch.
+#[derive(Debug, PartialEq, Eq)]
+pub struct EpochCache {
+    /// The epoch of the cache
+    pub epoch: u64,
+    /// The size of the full dataset of the epoch, in bytes
+    pub dataset_size: u64,
+    /// The cache, as little endian words
+    pub cache: Vec<u32>,
+}
+
+impl EpochCache {
+    /// Generates the verification cache of the given epoch.
+    pub fn generate(epoch: u64) -> Self {
+        let cache =
+            algorithm::make_cache(algorithm::cache_size(epoch), algorithm::seed_hash(epoch));
+        Self { epoch, dataset_size: algorithm::dataset_size(epoch), cache }
+    }
+
+    /// Runs the light hashimoto loop for the given seal hash and nonce, see
+    /// [algorithm::hashimoto_light].
+    pub fn hashimoto(&self, hash: H256, nonce: u64) -> (H256, H256) {
+        algorithm::hashimoto_light(hash, nonce, self.dataset_size, &self.cache)
+    }
+}
+
+/// Keeps the verification caches of the most recently used epochs in memory, and optionally
+/// stores generated caches in a directory so they don't need to be regenerated on restart.
+pub struct EthashCaches {
+    /// The directory caches are stored in, if any
+    cache_dir: Option<PathBuf>,
+    /// The number of caches to keep on disk
+    caches_on_disk: u64,
+    /// The most recently used caches
+    caches: Mutex<LruMap<u64, Arc<EpochCache>>>,
+}
+
+impl EthashCaches {
+    /// Creates a new instance that keeps `caches_in_mem` caches in memory and, if a directory is
+    /// given, `caches_on_disk` caches on disk.
+    pub fn new(cache_dir: Option<PathBuf>, caches_in_mem: u32, caches_on_disk: u64) -> Self {
+        Self {
+            cache_dir,
+            caches_on_disk,
+            caches: Mutex::new(LruMap::new(ByLength::new(caches_in_mem.max(1)))),
+        }
+    }
+
+    /// Returns the verification cache of the given epoch, loading or generating it if it isn't
+    /// in memory.
+    ///
+    /// This holds the lock while a cache is generated, so concurrent callers wait for it instead
+    /// of generating the same cache again.
+    pub fn get(&self, epoch: u64) -> Arc<EpochCache> {
+        let mut caches = self.caches.lock();
+        if let Some(cache) = caches.get(&epoch) {
+            return Arc::clone(cache)
+        }
+
+        let cache = match self.load(epoch) {
+            Some(cache) => cache,
+            None => {
+                debug!(target: "consensus::ethash", epoch, "Generating verification cache");
+                let cache = EpochCache::generate(epoch);
+                self.store(&cache);
+                cache
+            }
+        };
+
+        let cache = Arc::new(cache);
+        caches.insert(epoch, Arc::clone(&cache));
+        cache
+    }
+
+    /// Loads the cache of the given epoch from disk, if it exists and has the expected size.
+    fn load(&self, epoch: u64) -> Option<EpochCache> {
+        let path = cache_path(self.cache_dir.as_ref()?, epoch);
+        let bytes = fs::read(&path).ok()?;
+        if bytes.len() as u64 != algorithm::cache_size(epoch) {
+            warn!(target: "consensus::ethash", ?path, "Ignoring cache file of invalid size");
+            return None
+        }
+
+        trace!(target: "consensus::ethash", epoch, ?path, "Loaded verification cache");
+        let cache = bytes
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes(word.try_into().expect("chunks of 4 bytes")))
+            .collect();
+        Some(EpochCache { epoch, dataset_size: algorithm::dataset_size(epoch), cache })
+    }
+
+    /// Writes the cache to disk and removes the cache that dropped out of the retained range.
+    ///
+    /// Failures are logged, since the cache can always be regenerated.
+    fn store(&self, cache: &EpochCache) {
+        let Some(dir) = self.cache_dir.as_ref() else { return };
+
+        if let Err(err) = write_cache(dir, cache) {
+            let epoch = cache.epoch;
+            warn!(target: "consensus::ethash", epoch, ?err, "Failed to store verification cache");
+        }
+
+        if self.caches_on_disk > 0 && cache.epoch >= self.caches_on_disk {
+            let stale = cache_path(dir, cache.epoch - self.caches_on_disk);
+            if stale.exists() {
+                trace!(target: "consensus::ethash", path = ?stale, "Removing stale cache file");
+                let _ = fs::remove_file(stale);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for EthashCaches {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EthashCaches")
+            .field("cache_dir", &self.cache_dir)
+            .field("caches_on_disk", &self.caches_on_disk)
+            .field("caches_in_mem", &self.caches.lock().len())
+            .finish()
+    }
+}
+
+/// Returns the path of the cache file of the given epoch, which is named after the revision and
+/// the first bytes of the seed.
+fn cache_path(dir: &Path, epoch: u64) -> PathBuf {
+    let seed = algorithm::seed_hash(epoch);
+    let seed: String = seed.as_bytes()[..8].iter().map(|byte| format!("{byte:02x}")).collect();
+    dir.join(format!("cache-R{CACHE_REVISION}-{seed}"))
+}
+
+/// Writes the cache to a temporary file that is then moved in place, so a partially written
+/// cache is never loaded.
+fn write_cache(dir: &Path, cache: &EpochCache) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let path = cache_path(dir, cache.epoch);
+    let tmp = path.with_extension("tmp");
+
+    let mut file = io::BufWriter::new(fs::File::create(&tmp)?);
+    for word in &cache.cache {
+        file.write_all(&word.to_le_bytes())?;
+    }
+    file.into_inner().map_err(|err| err.into_error())?.sync_all()?;
+
+    fs::rename(tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_file_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let caches = EthashCaches::new(Some(dir.path().to_path_buf()), 1, 2);
+
+        let cache = EpochCache { epoch: 0, dataset_size: 32 * 1024, cache: vec![1, 2, 3] };
+        write_cache(dir.path(), &cache).unwrap();
+        // the file does not have the size of the epoch 0 cache
+        assert_eq!(caches.load(0), None);
+
+        let cache = EpochCache {
+            epoch: 0,
+            dataset_size: algorithm::dataset_size(0),
+            cache: (0..algorithm::cache_size(0) as u32 / 4).collect(),
+        };
+        write_cache(dir.path(), &cache).unwrap();
+        assert_eq!(caches.load(0), Some(cache));
+        assert!(!cache_path(dir.path(), 0).with_extension("tmp").exists());
+    }
+
+    #[test]
+    fn cache_path_uses_seed() {
+        let dir = Path::new("ethash");
+        assert_eq!(cache_path(dir, 0), dir.join("cache-R23-0000000000000000"));
+        assert_eq!(cache_path(dir, 1), dir.join("cache-R23-290decd9548b62a8"));
+    }
+}
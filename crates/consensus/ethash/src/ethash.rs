@@ -0,0 +1,216 @@
+//! Consensus for pre-merge ethash proof-of-work headers.
+use crate::{algorithm, EthashCaches};
+use reth_consensus_common::validation;
+use reth_interfaces::consensus::{Consensus, ConsensusError};
+use reth_primitives::{ChainSpec, Header, SealedBlock, SealedHeader, U256};
+use std::{path::PathBuf, sync::Arc};
+
+/// Configuration of [EthashConsensus].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EthashConfig {
+    /// Whether to verify the ethash seal of headers, and not only their difficulty
+    pub verify_seal: bool,
+    /// The directory to store verification caches in, if any
+    pub cache_dir: Option<PathBuf>,
+    /// The number of verification caches to keep in memory
+    pub caches_in_mem: u32,
+    /// The number of verification caches to keep on disk
+    pub caches_on_disk: u64,
+}
+
+impl Default for EthashConfig {
+    fn default() -> Self {
+        Self { verify_seal: true, cache_dir: None, caches_in_mem: 2, caches_on_disk: 3 }
+    }
+}
+
+/// Ethash proof-of-work consensus
+///
+/// This wraps another consensus implementation and additionally validates the difficulty and the
+/// ethash seal of pre-merge headers, which the beacon consensus rules don't cover.
+///
+/// Headers are treated as pre-merge if they are below the known Paris block of the chain, or,
+/// if the Paris block isn't known, if their difficulty is not zero.
+#[derive(Debug)]
+pub struct EthashConsensus<C> {
+    /// The consensus implementation that validates everything but the proof-of-work
+    inner: C,
+    /// Configuration
+    chain_spec: Arc<ChainSpec>,
+    /// The verification caches, if seals are verified
+    caches: Option<EthashCaches>,
+}
+
+impl<C> EthashConsensus<C> {
+    /// Create a new instance of [EthashConsensus]
+    pub fn new(inner: C, chain_spec: Arc<ChainSpec>, config: EthashConfig) -> Self {
+        let caches = config.verify_seal.then(|| {
+            EthashCaches::new(config.cache_dir, config.caches_in_mem, config.caches_on_disk)
+        });
+        Self { inner, chain_spec, caches }
+    }
+
+    /// Returns true if the header is sealed with proof-of-work.
+    fn is_proof_of_work(&self, header: &Header) -> bool {
+        match self.chain_spec.paris_block_and_final_difficulty {
+            Some((paris_block, _)) => header.number < paris_block,
+            None => header.difficulty != U256::ZERO,
+        }
+    }
+
+    /// Verifies the ethash seal of the header with the light algorithm.
+    ///
+    /// This generates the verification cache of the header's epoch if it isn't available yet.
+    pub fn verify_seal(&self, header: &Header) -> Result<(), ConsensusError> {
+        let Some(caches) = self.caches.as_ref() else { return Ok(()) };
+
+        // the target is undefined for a zero difficulty
+        if header.difficulty == U256::ZERO {
+            return Err(ConsensusError::EthashInvalidProofOfWork)
+        }
+
+        let cache = caches.get(algorithm::epoch(header.number));
+        let (mix_digest, result) = cache.hashimoto(algorithm::seal_hash(header), header.nonce);
+        if mix_digest != header.mix_hash {
+            return Err(ConsensusError::EthashInvalidMixDigest {
+                expected: mix_digest,
+                got: header.mix_hash,
+            })
+        }
+        if !algorithm::meets_difficulty(result, header.difficulty) {
+            return Err(ConsensusError::EthashInvalidProofOfWork)
+        }
+
+        Ok(())
+    }
+}
+
+impl<C: Consensus> Consensus for EthashConsensus<C> {
+    fn validate_header(&self, header: &SealedHeader) -> Result<(), ConsensusError> {
+        self.inner.validate_header(header)?;
+
+        if self.is_proof_of_work(header) {
+            self.verify_seal(header)?;
+        }
+
+        Ok(())
+    }
+
+    fn validate_header_against_parent(
+        &self,
+        header: &SealedHeader,
+        parent: &SealedHeader,
+    ) -> Result<(), ConsensusError> {
+        self.inner.validate_header_against_parent(header, parent)?;
+
+        if self.is_proof_of_work(header) {
+            validation::validate_header_difficulty(parent, header, &self.chain_spec)?;
+        }
+
+        Ok(())
+    }
+
+    fn validate_header_with_total_difficulty(
+        &self,
+        header: &Header,
+        total_difficulty: U256,
+    ) -> Result<(), ConsensusError> {
+        self.inner.validate_header_with_total_difficulty(header, total_difficulty)
+    }
+
+    fn validate_block(&self, block: &SealedBlock) -> Result<(), ConsensusError> {
+        self.inner.validate_block(block)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::tests::mainnet_block_1;
+    use assert_matches::assert_matches;
+    use reth_primitives::MAINNET;
+
+    /// Accepts everything, so only the proof-of-work checks are tested.
+    #[derive(Debug)]
+    struct NoopConsensus;
+
+    impl Consensus for NoopConsensus {
+        fn validate_header(&self, _: &SealedHeader) -> Result<(), ConsensusError> {
+            Ok(())
+        }
+
+        fn validate_header_against_parent(
+            &self,
+            _: &SealedHeader,
+            _: &SealedHeader,
+        ) -> Result<(), ConsensusError> {
+            Ok(())
+        }
+
+        fn validate_header_with_total_difficulty(
+            &self,
+            _: &Header,
+            _: U256,
+        ) -> Result<(), ConsensusError> {
+            Ok(())
+        }
+
+        fn validate_block(&self, _: &SealedBlock) -> Result<(), ConsensusError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn validates_difficulty_before_paris() {
+        let config = EthashConfig { verify_seal: false, ..Default::default() };
+        let consensus = EthashConsensus::new(NoopConsensus, Arc::new(MAINNET.clone()), config);
+
+        let parent = MAINNET.genesis_header().seal_slow();
+        let header = mainnet_block_1();
+        assert_eq!(
+            consensus.validate_header_against_parent(&header.clone().seal_slow(), &parent),
+            Ok(())
+        );
+
+        let fabricated = Header { difficulty: parent.difficulty, ..header }.seal_slow();
+        assert_eq!(
+            consensus.validate_header_against_parent(&fabricated, &parent),
+            Err(ConsensusError::DifficultyDiff {
+                expected: U256::from(17_171_480_576u64),
+                got: parent.difficulty
+            })
+        );
+
+        // post-merge headers are left to the inner consensus
+        let paris_block = MAINNET.paris_block_and_final_difficulty.unwrap().0;
+        let parent = Header { number: paris_block - 1, ..Default::default() }.seal_slow();
+        let header = Header { number: paris_block, timestamp: 1, ..Default::default() };
+        assert_eq!(consensus.validate_header_against_parent(&header.seal_slow(), &parent), Ok(()));
+    }
+
+    // Generating the epoch 0 cache takes a while in debug builds.
+    #[test]
+    #[ignore]
+    fn verifies_mainnet_seal() {
+        let dir = tempfile::tempdir().unwrap();
+        let config =
+            EthashConfig { cache_dir: Some(dir.path().to_path_buf()), ..Default::default() };
+        let consensus = EthashConsensus::new(NoopConsensus, Arc::new(MAINNET.clone()), config);
+
+        let header = mainnet_block_1();
+        assert_eq!(consensus.validate_header(&header.clone().seal_slow()), Ok(()));
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let fabricated = Header { nonce: header.nonce + 1, ..header.clone() };
+        assert_matches!(
+            consensus.validate_header(&fabricated.seal_slow()),
+            Err(ConsensusError::EthashInvalidMixDigest { .. })
+        );
+
+        let fabricated = Header { timestamp: header.timestamp + 1, ..header };
+        assert_matches!(
+            consensus.validate_header(&fabricated.seal_slow()),
+            Err(ConsensusError::EthashInvalidMixDigest { .. })
+        );
+    }
+}
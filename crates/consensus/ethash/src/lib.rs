@@ -0,0 +1,23 @@
+#![warn(missing_docs, unreachable_pub, unused_crate_dependencies)]
+#![deny(unused_must_use, rust_2018_idioms)]
+#![doc(test(
+    no_crate_inject,
+    attr(deny(warnings, rust_2018_idioms), allow(dead_code, unused_variables))
+))]
+
+//! A [Consensus](reth_interfaces::consensus::Consensus) implementation that validates the
+//! proof-of-work of pre-merge headers.
+//!
+//! The difficulty of every pre-Paris header is checked against its parent, and the ethash seal is
+//! verified with the light algorithm, which only needs the verification cache of the header's
+//! epoch. Caches are kept in memory and on disk, since generating one takes a while.
+//!
+//! This is meant for syncing pre-merge history from untrusted sources, e.g. peers or history
+//! files, and is applied on top of another consensus implementation.
+
+pub mod algorithm;
+mod cache;
+mod ethash;
+
+pub use cache::{EpochCache, EthashCaches};
+pub use ethash::{EthashConfig, EthashConsensus};
@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use reth_primitives::{
-    BlockHash, BlockNumber, Header, InvalidTransactionError, SealedBlock, SealedHeader, H256, U256,
+    Address, BlockHash, BlockNumber, Header, InvalidTransactionError, SealedBlock, SealedHeader,
+    H256, U256,
 };
 use std::fmt::Debug;
 
@@ -95,6 +96,8 @@ pub enum ConsensusError {
     TransactionSignerRecoveryError,
     #[error("Extra data {len} exceeds max length: ")]
     ExtraDataExceedsMax { len: usize },
+    #[error("Block difficulty ({got}) is different than expected: ({expected}).")]
+    DifficultyDiff { expected: U256, got: U256 },
     #[error("Difficulty after merge is not zero")]
     TheMergeDifficultyIsNotZero,
     #[error("Nonce after merge is not zero")]
@@ -109,6 +112,64 @@ pub enum ConsensusError {
     WithdrawalIndexInvalid { got: u64, expected: u64 },
     #[error("Missing withdrawals")]
     BodyWithdrawalsMissing,
+    #[error("Missing blob gas used")]
+    BlobGasUsedMissing,
+    #[error("Unexpected blob gas used")]
+    BlobGasUsedUnexpected,
+    #[error("Missing excess blob gas")]
+    ExcessBlobGasMissing,
+    #[error("Unexpected excess blob gas")]
+    ExcessBlobGasUnexpected,
+    #[error("Blob gas used {blob_gas_used} exceeds maximum allowance {max_blob_gas_per_block}.")]
+    BlobGasUsedExceedsMaxBlobGasPerBlock { blob_gas_used: u64, max_blob_gas_per_block: u64 },
+    #[error(
+        "Blob gas used {blob_gas_used} is not a multiple of blob gas per blob {blob_gas_per_blob}."
+    )]
+    BlobGasUsedNotMultipleOfBlobGasPerBlob { blob_gas_used: u64, blob_gas_per_blob: u64 },
+    #[error("Block blob gas used ({got}) is different from the blob gas of its transactions ({expected}).")]
+    BlobGasUsedDiff { got: u64, expected: u64 },
+    #[error("Block excess blob gas ({got}) is different than expected: ({expected}).")]
+    ExcessBlobGasDiff { expected: u64, got: u64 },
+    #[error("Missing parent beacon block root")]
+    ParentBeaconBlockRootMissing,
+    #[error("Unexpected parent beacon block root")]
+    ParentBeaconBlockRootUnexpected,
+    #[error("Clique extra-data is missing the 32 byte signer vanity.")]
+    CliqueMissingVanity,
+    #[error("Clique extra-data is missing the 65 byte signer seal.")]
+    CliqueMissingSignature,
+    #[error("Clique non-checkpoint block contains a list of signers in its extra-data.")]
+    CliqueExtraSigners,
+    #[error("Clique checkpoint block contains an invalid list of signers.")]
+    CliqueInvalidCheckpointSigners,
+    #[error("Clique checkpoint block signers differ from the local snapshot.")]
+    CliqueMismatchingCheckpointSigners,
+    #[error("Clique checkpoint block has a non-zero beneficiary: {beneficiary:?}.")]
+    CliqueInvalidCheckpointBeneficiary { beneficiary: Address },
+    #[error("Clique vote nonce is neither 0x00..0 nor 0xff..f: {nonce}.")]
+    CliqueInvalidVote { nonce: u64 },
+    #[error("Clique checkpoint block has a non-zero vote nonce: {nonce}.")]
+    CliqueInvalidCheckpointVote { nonce: u64 },
+    #[error("Clique block has a non-zero mix digest.")]
+    CliqueInvalidMixDigest,
+    #[error("Clique block has a non-empty ommers hash.")]
+    CliqueInvalidOmmersHash,
+    #[error("Clique block difficulty is neither 1 nor 2: {difficulty}.")]
+    CliqueInvalidDifficulty { difficulty: U256 },
+    #[error("Clique block difficulty ({got}) does not match the signer turn ({expected}).")]
+    CliqueWrongDifficulty { expected: U256, got: U256 },
+    #[error("Clique block timestamp {timestamp} is earlier than parent {parent_timestamp} plus the period {period}.")]
+    CliqueInvalidTimestamp { parent_timestamp: u64, timestamp: u64, period: u64 },
+    #[error("Failed to recover the clique signer from the seal.")]
+    CliqueSignerRecoveryError,
+    #[error("Clique signer {signer:?} is not authorized.")]
+    CliqueUnauthorizedSigner { signer: Address },
+    #[error("Clique signer {signer:?} signed one of the recent blocks.")]
+    CliqueRecentlySigned { signer: Address },
+    #[error("Ethash mix digest ({got:?}) is different than expected: ({expected:?}).")]
+    EthashInvalidMixDigest { expected: H256, got: H256 },
+    #[error("Ethash proof-of-work does not meet the difficulty target.")]
+    EthashInvalidProofOfWork,
     /// Error for a transaction that violates consensus.
     #[error(transparent)]
     InvalidTransaction(#[from] InvalidTransactionError),
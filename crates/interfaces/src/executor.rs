@@ -1,4 +1,4 @@
-use reth_primitives::{BlockHash, BlockNumHash, Bloom, H256};
+use reth_primitives::{BlockHash, BlockNumHash, Bloom, H256, U256};
 use thiserror::Error;
 
 /// BlockExecutor Errors
@@ -20,6 +20,20 @@ pub enum BlockExecutionError {
     },
     #[error("Block gas used {got} is different from expected gas used {expected}.")]
     BlockGasUsed { got: u64, expected: u64 },
+    #[error("Blob transaction {hash:?} in a block without a blob gas price")]
+    MissingBlobGasPrice { hash: H256 },
+    #[error("Blob transaction {hash:?} max fee per blob gas {max_fee_per_blob_gas} is less than the block's blob gas price {blob_gasprice}")]
+    BlobFeeCapTooLow { hash: H256, max_fee_per_blob_gas: u128, blob_gasprice: u128 },
+    #[error("Sender of blob transaction {hash:?} can't pay for the blob gas")]
+    InsufficientFundsForBlobFee { hash: H256 },
+    #[error("Sender balance {balance} of blob transaction {hash:?} can't cover its maximum cost {max_cost}")]
+    InsufficientFundsForMaxCost { hash: H256, balance: U256, max_cost: U256 },
+    #[error("Block blob gas used {got} is more than the maximum allowed {max}")]
+    BlockBlobGasExceedsMax { got: u64, max: u64 },
+    #[error("Block blob gas used {got} is different from expected blob gas used {expected}.")]
+    BlockBlobGasUsed { got: u64, expected: u64 },
+    #[error("Block {number} is post Cancun, which the EVM doesn't support yet")]
+    CancunUnsupported { number: u64 },
     #[error("Provider error")]
     ProviderError,
     // TODO(mattsse): move this to tree error
@@ -62,4 +62,7 @@ pub enum ProviderError {
     /// Unable to compute state root on top of historical block
     #[error("Unable to compute state root on top of historical block")]
     StateRootNotAvailableForHistoricalBlock,
+    /// The execution witness does not contain the requested state.
+    #[error("Execution witness error: {0}")]
+    ExecutionWitness(String),
 }
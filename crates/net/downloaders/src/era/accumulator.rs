@@ -0,0 +1,167 @@
+//! The epoch accumulator of an era1 file.
+//!
+//! The accumulator is the SSZ `hash_tree_root` of a `List[HeaderRecord, 8192]`, where
+//!
+//! ```text
+//! HeaderRecord := { block_hash: Bytes32, total_difficulty: Uint256 }
+//! ```
+//!
+//! This is the same value the Portal network uses for its pre-merge header accumulator.
+//!
+//! The accumulator stored in a file only proves the file is consistent. To rule out a consistently
+//! forged archive, it must also match the root of its epoch in a list of [TrustedRoots].
+
+use super::{EraError, MAX_BLOCKS_PER_ERA1};
+use reth_primitives::{H256, U256};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+/// Depth of the merkle tree over [MAX_BLOCKS_PER_ERA1] leaves.
+const TREE_DEPTH: usize = MAX_BLOCKS_PER_ERA1.trailing_zeros() as usize;
+
+/// An entry of the epoch accumulator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeaderRecord {
+    /// The hash of the block.
+    pub block_hash: H256,
+    /// The total difficulty of the chain including this block.
+    pub total_difficulty: U256,
+}
+
+impl HeaderRecord {
+    /// Returns the SSZ `hash_tree_root` of the record.
+    fn tree_root(&self) -> [u8; 32] {
+        hash_pair(self.block_hash.as_fixed_bytes(), &self.total_difficulty.to_le_bytes::<32>())
+    }
+}
+
+/// Trusted accumulator roots of consecutive epochs, starting at epoch zero.
+///
+/// For mainnet these are the epoch roots of the Portal network's pre-merge header accumulator.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrustedRoots(Vec<H256>);
+
+impl TrustedRoots {
+    /// Creates a new list from the roots of epoch zero onwards.
+    pub fn new(roots: Vec<H256>) -> Self {
+        Self(roots)
+    }
+
+    /// Parses a list of hex encoded roots, one per line and in epoch order.
+    ///
+    /// Empty lines and lines starting with `#` are skipped.
+    pub fn parse(s: &str) -> Result<Self, EraError> {
+        s.lines()
+            .enumerate()
+            .map(|(idx, line)| (idx + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(line, root)| root.parse().map_err(|_| EraError::InvalidTrustedRoot { line }))
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+
+    /// Reads and parses the list of roots from the file at the given path.
+    ///
+    /// See [TrustedRoots::parse] for the format.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, EraError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Returns the trusted root of the given epoch.
+    pub fn get(&self, epoch: u64) -> Option<H256> {
+        self.0.get(usize::try_from(epoch).ok()?).copied()
+    }
+
+    /// Returns the number of epochs with a trusted root.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if there are no trusted roots.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Computes the accumulator root of the given records.
+///
+/// # Panics
+///
+/// If more than [MAX_BLOCKS_PER_ERA1] records are provided.
+pub fn accumulator_root(records: &[HeaderRecord]) -> H256 {
+    assert!(records.len() <= MAX_BLOCKS_PER_ERA1, "too many header records");
+
+    // precompute the roots of empty subtrees for every level
+    let mut zero_hashes = [[0u8; 32]; TREE_DEPTH + 1];
+    for depth in 1..=TREE_DEPTH {
+        zero_hashes[depth] = hash_pair(&zero_hashes[depth - 1], &zero_hashes[depth - 1]);
+    }
+
+    let mut layer = records.iter().map(HeaderRecord::tree_root).collect::<Vec<_>>();
+    for zero_hash in zero_hashes.iter().take(TREE_DEPTH) {
+        layer = layer
+            .chunks(2)
+            .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(zero_hash)))
+            .collect();
+    }
+    let root = layer.first().copied().unwrap_or(zero_hashes[TREE_DEPTH]);
+
+    // mix in the list length
+    let mut length = [0u8; 32];
+    length[..8].copy_from_slice(&(records.len() as u64).to_le_bytes());
+    H256(hash_pair(&root, &length))
+}
+
+/// Returns `sha256(left || right)`.
+fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_accumulator() {
+        // hash_tree_root of an empty list is the zero root mixed with a zero length
+        let mut zero = [0u8; 32];
+        for _ in 0..TREE_DEPTH {
+            zero = hash_pair(&zero, &zero);
+        }
+        assert_eq!(accumulator_root(&[]), H256(hash_pair(&zero, &[0u8; 32])));
+    }
+
+    #[test]
+    fn parse_trusted_roots() {
+        let (a, b) = (H256::random(), H256::random());
+        let roots = TrustedRoots::parse(&format!(
+            "# epoch roots\n{a:?}\n\n{}\n",
+            reth_primitives::hex::encode(b)
+        ))
+        .unwrap();
+        assert_eq!(roots, TrustedRoots::new(vec![a, b]));
+        assert_eq!(roots.get(1), Some(b));
+        assert_eq!(roots.get(2), None);
+
+        assert!(matches!(
+            TrustedRoots::parse(&format!("{a:?}\n0x1234")),
+            Err(EraError::InvalidTrustedRoot { line: 2 })
+        ));
+    }
+
+    #[test]
+    fn accumulator_depends_on_records() {
+        let a = HeaderRecord { block_hash: H256::random(), total_difficulty: U256::from(1) };
+        let b = HeaderRecord { block_hash: H256::random(), total_difficulty: U256::from(2) };
+
+        assert_ne!(accumulator_root(&[a]), accumulator_root(&[a, b]));
+        assert_ne!(accumulator_root(&[a, b]), accumulator_root(&[b, a]));
+        assert_ne!(
+            accumulator_root(&[a]),
+            accumulator_root(&[HeaderRecord { total_difficulty: U256::from(3), ..a }])
+        );
+    }
+}
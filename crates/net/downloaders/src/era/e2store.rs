@@ -0,0 +1,214 @@
+//! Reader and writer for the `e2store` container format.
+//!
+//! An e2store file is a flat sequence of type-length-value records:
+//!
+//! ```text
+//! entry  := header | data
+//! header := type | length | reserved
+//! ```
+//!
+//! where `type` is a little-endian `u16`, `length` a little-endian `u32` and `reserved` two zero
+//! bytes.
+//!
+//! See also <https://github.com/status-im/nimbus-eth2/blob/stable/docs/e2store.md>
+
+use super::EraError;
+use std::io::{self, Read, Write};
+
+/// Size of an e2store entry header in bytes.
+pub const HEADER_SIZE: u64 = 8;
+
+/// The maximum length of an entry's data accepted by the [E2StoreReader].
+///
+/// The length is read from the file, so it is capped before allocating. Compressed blocks and
+/// receipts are far smaller than this, even for full blocks.
+pub const MAX_ENTRY_LEN: u32 = 64 * 1024 * 1024;
+
+/// A single e2store record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The type of the record.
+    pub entry_type: u16,
+    /// The raw payload of the record.
+    pub data: Vec<u8>,
+}
+
+impl Entry {
+    /// Creates a new entry.
+    pub fn new(entry_type: u16, data: Vec<u8>) -> Self {
+        Self { entry_type, data }
+    }
+
+    /// Returns the number of bytes this entry occupies in the file, including its header.
+    pub fn encoded_len(&self) -> u64 {
+        HEADER_SIZE + self.data.len() as u64
+    }
+}
+
+/// Reads [Entry]s one after another from the underlying reader.
+#[derive(Debug)]
+pub struct E2StoreReader<R> {
+    reader: R,
+    /// Offset of the next entry in the underlying reader.
+    position: u64,
+}
+
+impl<R: Read> E2StoreReader<R> {
+    /// Creates a new reader starting at offset zero.
+    pub fn new(reader: R) -> Self {
+        Self { reader, position: 0 }
+    }
+
+    /// Returns the offset of the next entry.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Reads the next entry.
+    ///
+    /// Returns `Ok(None)` if the reader is exhausted at an entry boundary.
+    pub fn next_entry(&mut self) -> Result<Option<Entry>, EraError> {
+        let mut header = [0u8; HEADER_SIZE as usize];
+        match read_exact_or_eof(&mut self.reader, &mut header)? {
+            0 => return Ok(None),
+            n if n < header.len() => return Err(EraError::TruncatedEntry { offset: self.position }),
+            _ => {}
+        }
+
+        let entry_type = u16::from_le_bytes([header[0], header[1]]);
+        let length = u32::from_le_bytes([header[2], header[3], header[4], header[5]]);
+        if header[6] != 0 || header[7] != 0 {
+            return Err(EraError::ReservedBytesSet { offset: self.position })
+        }
+
+        if length > MAX_ENTRY_LEN {
+            return Err(EraError::EntryTooLarge { len: length as usize })
+        }
+
+        let mut data = vec![0u8; length as usize];
+        if read_exact_or_eof(&mut self.reader, &mut data)? < data.len() {
+            return Err(EraError::TruncatedEntry { offset: self.position })
+        }
+
+        let entry = Entry { entry_type, data };
+        self.position += entry.encoded_len();
+        Ok(Some(entry))
+    }
+
+    /// Consumes the reader and returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// Writes [Entry]s one after another to the underlying writer.
+#[derive(Debug)]
+pub struct E2StoreWriter<W> {
+    writer: W,
+    /// Offset of the next entry in the underlying writer.
+    position: u64,
+}
+
+impl<W: Write> E2StoreWriter<W> {
+    /// Creates a new writer starting at offset zero.
+    pub fn new(writer: W) -> Self {
+        Self { writer, position: 0 }
+    }
+
+    /// Returns the offset at which the next entry will be written.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Appends an entry.
+    pub fn write_entry(&mut self, entry: &Entry) -> Result<(), EraError> {
+        let length = u32::try_from(entry.data.len())
+            .map_err(|_| EraError::EntryTooLarge { len: entry.data.len() })?;
+
+        let mut header = [0u8; HEADER_SIZE as usize];
+        header[..2].copy_from_slice(&entry.entry_type.to_le_bytes());
+        header[2..6].copy_from_slice(&length.to_le_bytes());
+
+        self.writer.write_all(&header)?;
+        self.writer.write_all(&entry.data)?;
+        self.position += entry.encoded_len();
+        Ok(())
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> Result<(), EraError> {
+        Ok(self.writer.flush()?)
+    }
+
+    /// Consumes the writer and returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Like [Read::read_exact], but returns the number of bytes read if the reader is exhausted before
+/// the buffer is filled.
+fn read_exact_or_eof<R: Read>(reader: &mut R, mut buf: &mut [u8]) -> io::Result<usize> {
+    let len = buf.len();
+    while !buf.is_empty() {
+        match reader.read(buf) {
+            Ok(0) => break,
+            Ok(n) => buf = &mut buf[n..],
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(len - buf.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+
+    #[test]
+    fn entries_roundtrip() {
+        let entries = vec![
+            Entry::new(0x3265, vec![]),
+            Entry::new(0x03, vec![1, 2, 3]),
+            Entry::new(0x3266, vec![0xff; 300]),
+        ];
+
+        let mut writer = E2StoreWriter::new(Vec::new());
+        for entry in &entries {
+            writer.write_entry(entry).unwrap();
+        }
+        assert_eq!(writer.position(), entries.iter().map(Entry::encoded_len).sum::<u64>());
+        let buf = writer.into_inner();
+        // version record is `e2` followed by a zero length
+        assert_eq!(&buf[..8], &[0x65, 0x32, 0, 0, 0, 0, 0, 0]);
+
+        let mut reader = E2StoreReader::new(&buf[..]);
+        let mut decoded = Vec::new();
+        while let Some(entry) = reader.next_entry().unwrap() {
+            decoded.push(entry);
+        }
+        assert_eq!(decoded, entries);
+    }
+
+    #[test]
+    fn truncated_entry() {
+        let mut writer = E2StoreWriter::new(Vec::new());
+        writer.write_entry(&Entry::new(0x03, vec![1, 2, 3])).unwrap();
+        let buf = writer.into_inner();
+
+        let mut reader = E2StoreReader::new(&buf[..buf.len() - 1]);
+        assert_matches!(reader.next_entry(), Err(EraError::TruncatedEntry { offset: 0 }));
+    }
+
+    #[test]
+    fn oversized_entry() {
+        // a header claiming the maximum u32 length must be rejected without allocating
+        let header = [0x03, 0x00, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00];
+        let mut reader = E2StoreReader::new(&header[..]);
+        assert_matches!(
+            reader.next_entry(),
+            Err(EraError::EntryTooLarge { len }) if len == u32::MAX as usize
+        );
+    }
+}
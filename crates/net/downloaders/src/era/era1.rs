@@ -0,0 +1,492 @@
+//! Reader, writer and verifier for era1 archives.
+//!
+//! An era1 file contains up to [MAX_BLOCKS_PER_ERA1] consecutive pre-merge blocks:
+//!
+//! ```text
+//! era1          := Version | block-tuple* | other-entries* | Accumulator | BlockIndex
+//! block-tuple   := CompressedHeader | CompressedBody | CompressedReceipts | TotalDifficulty
+//! block-index   := starting-number | offset* | count
+//! ```
+//!
+//! Headers, bodies and receipts are RLP encoded and compressed with the snappy framing format.
+//!
+//! See also <https://github.com/ethereum/go-ethereum/blob/master/internal/era/era.go>
+
+use super::{
+    accumulator::{accumulator_root, HeaderRecord, TrustedRoots},
+    e2store::{E2StoreReader, E2StoreWriter, Entry},
+    EraError, MAX_BLOCKS_PER_ERA1,
+};
+use reth_primitives::{
+    proofs, Block, BlockBody, BlockNumber, Header, ReceiptWithBloom, SealedHeader, H256, U256,
+};
+use reth_rlp::{Decodable, Encodable};
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+/// The e2store record types used by era1 files.
+pub mod entry_type {
+    /// The version record, `e2` in ASCII.
+    pub const VERSION: u16 = 0x3265;
+    /// A snappy-compressed RLP header.
+    pub const COMPRESSED_HEADER: u16 = 0x03;
+    /// A snappy-compressed RLP body.
+    pub const COMPRESSED_BODY: u16 = 0x04;
+    /// Snappy-compressed RLP receipts.
+    pub const COMPRESSED_RECEIPTS: u16 = 0x05;
+    /// A little-endian `U256` total difficulty.
+    pub const TOTAL_DIFFICULTY: u16 = 0x06;
+    /// The epoch accumulator root.
+    pub const ACCUMULATOR: u16 = 0x07;
+    /// The block index.
+    pub const BLOCK_INDEX: u16 = 0x3266;
+}
+
+/// A block stored in an era1 file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Era1Block {
+    /// The block header.
+    pub header: SealedHeader,
+    /// The block body.
+    pub body: BlockBody,
+    /// The receipts of the block's transactions.
+    pub receipts: Vec<ReceiptWithBloom>,
+    /// The total difficulty of the chain including this block.
+    pub total_difficulty: U256,
+}
+
+impl Era1Block {
+    /// Consumes the era1 block and returns the full block.
+    pub fn into_block(self) -> Block {
+        let BlockBody { transactions, ommers, withdrawals } = self.body;
+        Block { header: self.header.unseal(), body: transactions, ommers, withdrawals }
+    }
+
+    /// Checks that the body and receipts match the roots committed to in the header.
+    fn verify_roots(&self) -> Result<(), EraError> {
+        let number = self.header.number;
+        if proofs::calculate_transaction_root(&self.body.transactions) !=
+            self.header.transactions_root ||
+            proofs::calculate_ommers_root(&self.body.ommers) != self.header.ommers_hash
+        {
+            return Err(EraError::BodyRootMismatch { number })
+        }
+        if self.receipts.len() != self.body.transactions.len() ||
+            proofs::calculate_receipt_root(&self.receipts) != self.header.receipts_root
+        {
+            return Err(EraError::ReceiptsRootMismatch { number })
+        }
+        Ok(())
+    }
+}
+
+/// The decoded content of an era1 file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Era1 {
+    /// The blocks of the epoch, in ascending order.
+    pub blocks: Vec<Era1Block>,
+    /// The epoch accumulator root stored in the file.
+    pub accumulator: H256,
+}
+
+impl Era1 {
+    /// Creates a new archive for the given blocks and computes its accumulator.
+    pub fn new(blocks: Vec<Era1Block>) -> Result<Self, EraError> {
+        if blocks.len() > MAX_BLOCKS_PER_ERA1 {
+            return Err(EraError::TooManyBlocks(blocks.len()))
+        }
+        let accumulator = accumulator_root(&header_records(&blocks));
+        Ok(Self { blocks, accumulator })
+    }
+
+    /// Reads and decodes the era1 file at the given path.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, EraError> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Reads and decodes an era1 archive.
+    ///
+    /// This only checks the structure of the archive, see [Era1::verify] for content validation.
+    pub fn read<R: Read>(reader: R) -> Result<Self, EraError> {
+        let mut reader = E2StoreReader::new(reader);
+
+        match reader.next_entry()? {
+            Some(entry) if entry.entry_type == entry_type::VERSION && entry.data.is_empty() => {}
+            _ => return Err(EraError::InvalidVersion),
+        }
+
+        let mut headers = Vec::new();
+        let mut bodies = Vec::new();
+        let mut receipts = Vec::new();
+        let mut total_difficulties = Vec::new();
+        let mut accumulator = None;
+        let mut block_index = None;
+
+        while let Some(entry) = reader.next_entry()? {
+            match entry.entry_type {
+                entry_type::COMPRESSED_HEADER => {
+                    headers.push(Header::decode(&mut &decompress(&entry.data)?[..])?.seal_slow())
+                }
+                entry_type::COMPRESSED_BODY => {
+                    bodies.push(BlockBody::decode(&mut &decompress(&entry.data)?[..])?)
+                }
+                entry_type::COMPRESSED_RECEIPTS => receipts
+                    .push(Vec::<ReceiptWithBloom>::decode(&mut &decompress(&entry.data)?[..])?),
+                entry_type::TOTAL_DIFFICULTY => {
+                    let bytes: [u8; 32] = entry.data.try_into().map_err(|_| {
+                        EraError::InvalidEntryLength { entry_type: entry_type::TOTAL_DIFFICULTY }
+                    })?;
+                    total_difficulties.push(U256::from_le_bytes(bytes));
+                }
+                entry_type::ACCUMULATOR => {
+                    if entry.data.len() != 32 {
+                        return Err(EraError::InvalidEntryLength {
+                            entry_type: entry_type::ACCUMULATOR,
+                        })
+                    }
+                    accumulator = Some(H256::from_slice(&entry.data));
+                }
+                entry_type::BLOCK_INDEX => block_index = Some(BlockIndex::decode(&entry.data)?),
+                // unknown records are allowed by the format and skipped
+                _ => {}
+            }
+        }
+
+        let accumulator = accumulator.ok_or(EraError::MissingEntry(entry_type::ACCUMULATOR))?;
+        let block_index = block_index.ok_or(EraError::MissingEntry(entry_type::BLOCK_INDEX))?;
+
+        let count = headers.len();
+        if bodies.len() != count || receipts.len() != count || total_difficulties.len() != count {
+            return Err(EraError::IncompleteBlockTuples)
+        }
+        if count > MAX_BLOCKS_PER_ERA1 {
+            return Err(EraError::TooManyBlocks(count))
+        }
+        if block_index.offsets.len() != count ||
+            headers.first().map_or(false, |header| header.number != block_index.starting_number)
+        {
+            return Err(EraError::InvalidBlockIndex)
+        }
+
+        let blocks = headers
+            .into_iter()
+            .zip(bodies)
+            .zip(receipts)
+            .zip(total_difficulties)
+            .map(|(((header, body), receipts), total_difficulty)| Era1Block {
+                header,
+                body,
+                receipts,
+                total_difficulty,
+            })
+            .collect();
+
+        Ok(Self { blocks, accumulator })
+    }
+
+    /// Encodes the archive and writes it to the given writer.
+    pub fn write<W: Write>(&self, writer: W) -> Result<W, EraError> {
+        let mut writer = E2StoreWriter::new(writer);
+        writer.write_entry(&Entry::new(entry_type::VERSION, Vec::new()))?;
+
+        let mut offsets = Vec::with_capacity(self.blocks.len());
+        for block in &self.blocks {
+            offsets.push(writer.position());
+            writer.write_entry(&Entry::new(
+                entry_type::COMPRESSED_HEADER,
+                compress(&block.header.clone().unseal())?,
+            ))?;
+            writer.write_entry(&Entry::new(entry_type::COMPRESSED_BODY, compress(&block.body)?))?;
+            writer.write_entry(&Entry::new(
+                entry_type::COMPRESSED_RECEIPTS,
+                compress(&block.receipts)?,
+            ))?;
+            writer.write_entry(&Entry::new(
+                entry_type::TOTAL_DIFFICULTY,
+                block.total_difficulty.to_le_bytes::<32>().to_vec(),
+            ))?;
+        }
+
+        writer.write_entry(&Entry::new(
+            entry_type::ACCUMULATOR,
+            self.accumulator.as_bytes().to_vec(),
+        ))?;
+
+        let index_position = writer.position();
+        let block_index = BlockIndex {
+            starting_number: self.starting_number().unwrap_or_default(),
+            offsets: offsets
+                .into_iter()
+                .map(|offset| offset as i64 - index_position as i64)
+                .collect(),
+        };
+        writer.write_entry(&Entry::new(entry_type::BLOCK_INDEX, block_index.encode()))?;
+
+        writer.flush()?;
+        Ok(writer.into_inner())
+    }
+
+    /// Encodes the archive and writes it to a new file at the given path.
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> Result<(), EraError> {
+        let mut writer = self.write(BufWriter::new(File::create(path)?))?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Returns the number of the first block in the archive.
+    pub fn starting_number(&self) -> Option<BlockNumber> {
+        self.blocks.first().map(|block| block.header.number)
+    }
+
+    /// Returns the canonical file name of the archive, `<network>-<epoch>-<short-root>.era1`.
+    pub fn file_name(&self, network: &str) -> String {
+        let epoch = self.starting_number().unwrap_or_default() / MAX_BLOCKS_PER_ERA1 as u64;
+        format!(
+            "{network}-{epoch:05}-{}.era1",
+            reth_primitives::hex::encode(&self.accumulator.as_bytes()[..4])
+        )
+    }
+
+    /// Validates the content of the archive.
+    ///
+    /// This checks that
+    ///  - the blocks are consecutive and each block is the child of the previous one,
+    ///  - the bodies and receipts match the roots in their headers,
+    ///  - the total difficulty increases by the difficulty of each block,
+    ///  - the stored accumulator matches the one computed from the headers.
+    ///
+    /// These are consistency checks only: the accumulator is not compared against a trusted root,
+    /// so a file that was forged consistently passes, see [Era1::verify_trusted].
+    pub fn verify(&self) -> Result<(), EraError> {
+        for window in self.blocks.windows(2) {
+            let (parent, block) = (&window[0], &window[1]);
+            if block.header.number != parent.header.number + 1 ||
+                block.header.parent_hash != parent.header.hash()
+            {
+                return Err(EraError::DisconnectedBlock { number: block.header.number })
+            }
+            if block.total_difficulty != parent.total_difficulty + block.header.difficulty {
+                return Err(EraError::InvalidTotalDifficulty { number: block.header.number })
+            }
+        }
+
+        for block in &self.blocks {
+            block.verify_roots()?;
+        }
+
+        let accumulator = accumulator_root(&header_records(&self.blocks));
+        if accumulator != self.accumulator {
+            return Err(EraError::AccumulatorMismatch {
+                expected: self.accumulator,
+                got: accumulator,
+            })
+        }
+
+        Ok(())
+    }
+
+    /// Validates the content of the archive with [Era1::verify] and checks that its accumulator is
+    /// the trusted root of its epoch.
+    pub fn verify_trusted(&self, roots: &TrustedRoots) -> Result<(), EraError> {
+        self.verify()?;
+
+        let number = self.starting_number().unwrap_or_default();
+        if number % MAX_BLOCKS_PER_ERA1 as u64 != 0 {
+            return Err(EraError::UnalignedEpoch { number })
+        }
+        let epoch = number / MAX_BLOCKS_PER_ERA1 as u64;
+        match roots.get(epoch) {
+            Some(root) if root == self.accumulator => Ok(()),
+            trusted => {
+                Err(EraError::UntrustedAccumulator { epoch, trusted, got: self.accumulator })
+            }
+        }
+    }
+}
+
+/// The block index of an era1 file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BlockIndex {
+    /// The number of the first block.
+    starting_number: BlockNumber,
+    /// Offsets of the block tuples, relative to the start of the block index record.
+    offsets: Vec<i64>,
+}
+
+impl BlockIndex {
+    fn decode(data: &[u8]) -> Result<Self, EraError> {
+        if data.len() < 16 || data.len() % 8 != 0 {
+            return Err(EraError::InvalidBlockIndex)
+        }
+        let mut words =
+            data.chunks_exact(8).map(|word| u64::from_le_bytes(word.try_into().expect("8 bytes")));
+        let starting_number = words.next().expect("length checked");
+        let count = words.next_back().expect("length checked");
+        let offsets = words.map(|offset| offset as i64).collect::<Vec<_>>();
+        if offsets.len() as u64 != count {
+            return Err(EraError::InvalidBlockIndex)
+        }
+        Ok(Self { starting_number, offsets })
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity((self.offsets.len() + 2) * 8);
+        out.extend_from_slice(&self.starting_number.to_le_bytes());
+        for offset in &self.offsets {
+            out.extend_from_slice(&offset.to_le_bytes());
+        }
+        out.extend_from_slice(&(self.offsets.len() as u64).to_le_bytes());
+        out
+    }
+}
+
+/// Returns the accumulator records of the given blocks.
+fn header_records(blocks: &[Era1Block]) -> Vec<HeaderRecord> {
+    blocks
+        .iter()
+        .map(|block| HeaderRecord {
+            block_hash: block.header.hash(),
+            total_difficulty: block.total_difficulty,
+        })
+        .collect()
+}
+
+/// RLP encodes the value and compresses it with the snappy framing format.
+fn compress<T: Encodable>(value: &T) -> Result<Vec<u8>, EraError> {
+    let mut rlp = Vec::with_capacity(value.length());
+    value.encode(&mut rlp);
+
+    let mut encoder = snap::write::FrameEncoder::new(Vec::new());
+    encoder.write_all(&rlp)?;
+    encoder
+        .into_inner()
+        .map_err(|err| io::Error::new(err.error().kind(), err.error().to_string()).into())
+}
+
+/// Decompresses snappy framed data.
+fn decompress(data: &[u8]) -> Result<Vec<u8>, EraError> {
+    let mut out = Vec::new();
+    snap::read::FrameDecoder::new(data).read_to_end(&mut out)?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use reth_interfaces::test_utils::generators::random_block;
+    use reth_primitives::{Receipt, TxType};
+
+    /// Creates a valid chain of blocks with receipts and total difficulties.
+    fn era1_blocks(start: BlockNumber, count: u64) -> Vec<Era1Block> {
+        let mut blocks: Vec<Era1Block> = Vec::new();
+        for number in start..start + count {
+            let parent = blocks.last().map(|block| block.header.hash());
+            let block = random_block(number, parent, Some((number % 3) as u8), Some(0));
+            let (header, transactions, ommers) = block.split();
+
+            let receipts = transactions
+                .iter()
+                .enumerate()
+                .map(|(idx, _)| {
+                    Receipt {
+                        tx_type: TxType::Legacy,
+                        success: true,
+                        cumulative_gas_used: 21_000 * (idx as u64 + 1),
+                        logs: vec![],
+                    }
+                    .with_bloom()
+                })
+                .collect::<Vec<_>>();
+
+            let mut header = header.unseal();
+            header.base_fee_per_gas = None;
+            header.difficulty = U256::from(number + 1);
+            header.receipts_root = proofs::calculate_receipt_root(&receipts);
+            let total_difficulty = blocks
+                .last()
+                .map_or(header.difficulty, |block| block.total_difficulty + header.difficulty);
+
+            blocks.push(Era1Block {
+                header: header.seal_slow(),
+                body: BlockBody { transactions, ommers, withdrawals: None },
+                receipts,
+                total_difficulty,
+            });
+        }
+        blocks
+    }
+
+    #[test]
+    fn era1_roundtrip() {
+        let era = Era1::new(era1_blocks(8192, 10)).unwrap();
+        era.verify().unwrap();
+
+        let encoded = era.write(Vec::new()).unwrap();
+        let decoded = Era1::read(&encoded[..]).unwrap();
+        assert_eq!(decoded, era);
+        decoded.verify().unwrap();
+
+        assert!(era.file_name("mainnet").starts_with("mainnet-00001-"));
+        assert!(era.file_name("mainnet").ends_with(".era1"));
+    }
+
+    #[test]
+    fn era1_detects_tampering() {
+        let era = Era1::new(era1_blocks(0, 5)).unwrap();
+
+        let mut bad_accumulator = era.clone();
+        bad_accumulator.accumulator = H256::random();
+        assert_matches!(bad_accumulator.verify(), Err(EraError::AccumulatorMismatch { .. }));
+
+        let mut bad_td = era.clone();
+        bad_td.blocks[3].total_difficulty += U256::from(1);
+        assert_matches!(bad_td.verify(), Err(EraError::InvalidTotalDifficulty { number: 3 }));
+
+        let mut bad_receipts = era.clone();
+        bad_receipts.blocks[2].receipts[0].receipt.success = false;
+        assert_matches!(bad_receipts.verify(), Err(EraError::ReceiptsRootMismatch { number: 2 }));
+
+        let mut disconnected = era;
+        disconnected.blocks.remove(2);
+        assert_matches!(disconnected.verify(), Err(EraError::DisconnectedBlock { number: 3 }));
+    }
+
+    #[test]
+    fn era1_verify_trusted() {
+        let era = Era1::new(era1_blocks(0, 5)).unwrap();
+        let trusted = TrustedRoots::new(vec![era.accumulator]);
+        era.verify_trusted(&trusted).unwrap();
+
+        // a consistently forged archive passes the consistency checks, but not the trusted root
+        let forged = Era1::new(era1_blocks(0, 5)).unwrap();
+        forged.verify().unwrap();
+        assert_matches!(
+            forged.verify_trusted(&trusted),
+            Err(EraError::UntrustedAccumulator { epoch: 0, trusted: Some(_), .. })
+        );
+
+        // no trusted root for the epoch
+        assert_matches!(
+            era.verify_trusted(&TrustedRoots::default()),
+            Err(EraError::UntrustedAccumulator { epoch: 0, trusted: None, .. })
+        );
+
+        let unaligned = Era1::new(era1_blocks(1, 5)).unwrap();
+        assert_matches!(
+            unaligned.verify_trusted(&trusted),
+            Err(EraError::UnalignedEpoch { number: 1 })
+        );
+    }
+
+    #[test]
+    fn era1_rejects_missing_version() {
+        let era = Era1::new(era1_blocks(0, 2)).unwrap();
+        let encoded = era.write(Vec::new()).unwrap();
+        // skip the version record
+        assert_matches!(Era1::read(&encoded[8..]), Err(EraError::InvalidVersion));
+    }
+}
@@ -0,0 +1,120 @@
+//! Era1 files bundle the headers, bodies, receipts and total difficulties of
+//! [MAX_BLOCKS_PER_ERA1] pre-merge blocks into a single [e2store](e2store) file, together with an
+//! accumulator root that commits to the block hashes and total difficulties of the epoch.
+
+use reth_primitives::{BlockNumber, H256};
+use thiserror::Error;
+
+pub mod accumulator;
+pub mod e2store;
+mod era1;
+
+pub use era1::{entry_type, Era1, Era1Block};
+
+/// The maximum number of blocks in an era1 file.
+pub const MAX_BLOCKS_PER_ERA1: usize = 8192;
+
+/// Errors that can occur when reading, writing or verifying era1 files.
+#[derive(Debug, Error)]
+pub enum EraError {
+    /// An error occurred when reading or writing the file.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// An error occurred when decoding a record.
+    #[error(transparent)]
+    Rlp(#[from] reth_rlp::DecodeError),
+    /// The file ended in the middle of a record.
+    #[error("truncated e2store entry at offset {offset}")]
+    TruncatedEntry {
+        /// The offset of the truncated record.
+        offset: u64,
+    },
+    /// The reserved bytes of a record header are not zero.
+    #[error("non-zero reserved bytes in e2store entry at offset {offset}")]
+    ReservedBytesSet {
+        /// The offset of the record.
+        offset: u64,
+    },
+    /// The record is too large to be encoded, or exceeds [MAX_ENTRY_LEN](e2store::MAX_ENTRY_LEN)
+    /// when read.
+    #[error("e2store entry of {len} bytes is too large")]
+    EntryTooLarge {
+        /// The length of the record data.
+        len: usize,
+    },
+    /// The file does not start with a version record.
+    #[error("missing or invalid version entry")]
+    InvalidVersion,
+    /// A required record is missing.
+    #[error("missing entry of type {0:#06x}")]
+    MissingEntry(u16),
+    /// A fixed-size record has an unexpected length.
+    #[error("invalid length for entry of type {entry_type:#06x}")]
+    InvalidEntryLength {
+        /// The type of the record.
+        entry_type: u16,
+    },
+    /// The numbers of headers, bodies, receipts and total difficulties differ.
+    #[error("incomplete block tuples")]
+    IncompleteBlockTuples,
+    /// The file contains more blocks than an epoch.
+    #[error("too many blocks in era1 file: {0}")]
+    TooManyBlocks(usize),
+    /// The block index does not match the block tuples.
+    #[error("invalid block index")]
+    InvalidBlockIndex,
+    /// A block is not the child of the previous block.
+    #[error("block {number} does not extend the previous block")]
+    DisconnectedBlock {
+        /// The number of the block.
+        number: BlockNumber,
+    },
+    /// The transactions or ommers of a block do not match its header.
+    #[error("body of block {number} does not match its header")]
+    BodyRootMismatch {
+        /// The number of the block.
+        number: BlockNumber,
+    },
+    /// The receipts of a block do not match its header.
+    #[error("receipts of block {number} do not match its header")]
+    ReceiptsRootMismatch {
+        /// The number of the block.
+        number: BlockNumber,
+    },
+    /// The total difficulty of a block is inconsistent with its parent.
+    #[error("invalid total difficulty for block {number}")]
+    InvalidTotalDifficulty {
+        /// The number of the block.
+        number: BlockNumber,
+    },
+    /// The accumulator does not match the blocks of the file.
+    #[error("accumulator mismatch: expected {expected:?}, got {got:?}")]
+    AccumulatorMismatch {
+        /// The accumulator stored in the file.
+        expected: H256,
+        /// The accumulator computed from the blocks.
+        got: H256,
+    },
+    /// The file does not start at the first block of an epoch.
+    #[error("era1 file starts at block {number}, which is not the first block of an epoch")]
+    UnalignedEpoch {
+        /// The number of the first block of the file.
+        number: BlockNumber,
+    },
+    /// The accumulator of the file is not the trusted root of its epoch.
+    #[error("accumulator {got:?} of epoch {epoch} does not match the trusted root {trusted:?}")]
+    UntrustedAccumulator {
+        /// The epoch of the file.
+        epoch: u64,
+        /// The trusted root of the epoch, if known.
+        trusted: Option<H256>,
+        /// The accumulator of the file.
+        got: H256,
+    },
+    /// A line of a trusted roots list is not a valid root.
+    #[error("invalid trusted accumulator root on line {line}")]
+    InvalidTrustedRoot {
+        /// The line number, starting at one.
+        line: usize,
+    },
+}
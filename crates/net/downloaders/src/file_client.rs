@@ -1,4 +1,4 @@
-use super::file_codec::BlockFileCodec;
+use crate::file_codec::BlockFileCodec;
 use itertools::Either;
 use reth_interfaces::{
     p2p::{
@@ -11,8 +11,8 @@ use reth_interfaces::{
     sync::{NetworkSyncUpdater, SyncState, SyncStateProvider},
 };
 use reth_primitives::{
-    Block, BlockBody, BlockHash, BlockHashOrNumber, BlockNumber, Header, HeadersDirection, PeerId,
-    H256,
+    bytes::BytesMut, Block, BlockBody, BlockHash, BlockHashOrNumber, BlockNumber, Header,
+    HeadersDirection, PeerId, H256,
 };
 use reth_rlp::{Decodable, Header as RlpHeader};
 use std::{
@@ -30,7 +30,7 @@ use tokio::{
     io::{AsyncReadExt, BufReader},
 };
 use tokio_stream::StreamExt;
-use tokio_util::codec::FramedRead;
+use tokio_util::codec::{Decoder, FramedRead};
 use tracing::{trace, warn};
 
 /// Front-end API for fetching chain data from a file.
@@ -43,8 +43,9 @@ use tracing::{trace, warn};
 /// Blocks are assumed to have populated transactions, so reading headers will also buffer
 /// transactions in memory for use in the bodies stage.
 ///
-/// This reads the entire file into memory, so it is not suitable for large files.
-#[derive(Debug)]
+/// This keeps all blocks of the file in memory, so it is not suitable for large files. Use a
+/// [`ChunkedFileReader`](ChunkedFileReader) to import large files one chunk at a time.
+#[derive(Debug, Default)]
 pub struct FileClient {
     /// The buffered headers retrieved when fetching new bodies.
     headers: HashMap<BlockNumber, Header>,
@@ -76,24 +77,22 @@ impl FileClient {
     }
 
     /// Initialize the [`FileClient`](FileClient) with a file directly.
-    pub(crate) async fn from_file(mut file: File) -> Result<Self, FileClientError> {
-        // get file len from metadata before reading
-        let metadata = file.metadata().await?;
-        let file_len = metadata.len();
-
-        // read the entire file into memory
-        let mut reader = vec![];
-        file.read_to_end(&mut reader).await.unwrap();
+    pub(crate) async fn from_file(file: File) -> Result<Self, FileClientError> {
+        let mut blocks = Vec::new();
+        let mut stream = FramedRead::new(file, BlockFileCodec);
+        while let Some(block_res) = stream.next().await {
+            blocks.push(block_res?);
+        }
+        Ok(FileClient::from_blocks(blocks))
+    }
 
+    /// Initialize the [`FileClient`](FileClient) with the given blocks.
+    pub fn from_blocks(blocks: impl IntoIterator<Item = Block>) -> Self {
         let mut headers = HashMap::new();
         let mut hash_to_number = HashMap::new();
         let mut bodies = HashMap::new();
 
-        // use with_capacity to make sure the internal buffer contains the entire file
-        let mut stream = FramedRead::with_capacity(&reader[..], BlockFileCodec, file_len as usize);
-
-        while let Some(block_res) = stream.next().await {
-            let block = block_res?;
+        for block in blocks {
             let block_hash = block.header.hash_slow();
 
             // add to the internal maps
@@ -111,12 +110,12 @@ impl FileClient {
 
         trace!(blocks = headers.len(), "Initialized file client");
 
-        Ok(Self { headers, hash_to_number, bodies })
+        Self { headers, hash_to_number, bodies }
     }
 
     /// Get the tip hash of the chain.
     pub fn tip(&self) -> Option<H256> {
-        self.headers.get(&(self.headers.len() as u64)).map(|h| h.hash_slow())
+        self.max_block().and_then(|number| self.headers.get(&number)).map(|h| h.hash_slow())
     }
 
     /// Returns the highest block number of this client has or `None` if empty
@@ -124,6 +123,21 @@ impl FileClient {
         self.headers.keys().max().copied()
     }
 
+    /// Returns the lowest block number of this client has or `None` if empty
+    pub fn min_block(&self) -> Option<u64> {
+        self.headers.keys().min().copied()
+    }
+
+    /// Returns the number of blocks buffered in this client.
+    pub fn len(&self) -> usize {
+        self.headers.len()
+    }
+
+    /// Returns true if this client has no blocks.
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty()
+    }
+
     /// Returns true if all blocks are canonical (no gaps)
     pub fn has_canonical_blocks(&self) -> bool {
         if self.headers.is_empty() {
@@ -143,13 +157,13 @@ impl FileClient {
     }
 
     /// Use the provided bodies as the file client's block body buffer.
-    pub(crate) fn with_bodies(mut self, bodies: HashMap<BlockHash, BlockBody>) -> Self {
+    pub fn with_bodies(mut self, bodies: HashMap<BlockHash, BlockBody>) -> Self {
         self.bodies = bodies;
         self
     }
 
     /// Use the provided headers as the file client's block body buffer.
-    pub(crate) fn with_headers(mut self, headers: HashMap<BlockNumber, Header>) -> Self {
+    pub fn with_headers(mut self, headers: HashMap<BlockNumber, Header>) -> Self {
         self.headers = headers;
         for (number, header) in &self.headers {
             self.hash_to_number.insert(header.hash_slow(), *number);
@@ -158,6 +172,91 @@ impl FileClient {
     }
 }
 
+/// The default number of bytes a [`ChunkedFileReader`](ChunkedFileReader) reads per chunk, 1GB.
+pub const DEFAULT_BYTE_LEN_CHUNK_CHAIN_FILE: u64 = 1_000_000_000;
+
+/// Reads an RLP encoded block file in chunks of roughly equal byte size, yielding a
+/// [`FileClient`](FileClient) for the blocks of each chunk.
+///
+/// Blocks that fall across two chunks are carried over to the next chunk, so every yielded client
+/// contains only complete blocks and files of arbitrary size can be imported with bounded memory.
+#[derive(Debug)]
+pub struct ChunkedFileReader {
+    /// The file to read from.
+    file: File,
+    /// Bytes read from the file that have not been decoded into a block yet.
+    buf: BytesMut,
+    /// The number of bytes left to read from the file.
+    remaining: u64,
+    /// The number of bytes to read per chunk.
+    chunk_byte_len: u64,
+}
+
+impl ChunkedFileReader {
+    /// Opens the file at the given path.
+    ///
+    /// If no chunk size is given, [`DEFAULT_BYTE_LEN_CHUNK_CHAIN_FILE`] is used.
+    pub async fn new<P: AsRef<Path>>(
+        path: P,
+        chunk_byte_len: Option<u64>,
+    ) -> Result<Self, FileClientError> {
+        let file = File::open(path).await?;
+        ChunkedFileReader::from_file(file, chunk_byte_len).await
+    }
+
+    /// Initialize the [`ChunkedFileReader`](ChunkedFileReader) with a file directly.
+    pub(crate) async fn from_file(
+        file: File,
+        chunk_byte_len: Option<u64>,
+    ) -> Result<Self, FileClientError> {
+        let remaining = file.metadata().await?.len();
+        let chunk_byte_len = chunk_byte_len.unwrap_or(DEFAULT_BYTE_LEN_CHUNK_CHAIN_FILE).max(1);
+        Ok(Self { file, buf: BytesMut::new(), remaining, chunk_byte_len })
+    }
+
+    /// Reads the next chunk of the file and returns a [`FileClient`](FileClient) containing its
+    /// blocks, or `None` if the file has been read entirely.
+    pub async fn next_chunk(&mut self) -> Result<Option<FileClient>, FileClientError> {
+        let mut codec = BlockFileCodec;
+        let mut blocks = Vec::new();
+
+        loop {
+            let to_read = self.chunk_byte_len.min(self.remaining);
+            if to_read > 0 {
+                let start = self.buf.len();
+                self.buf.resize(start + to_read as usize, 0);
+                self.file.read_exact(&mut self.buf[start..]).await?;
+                self.remaining -= to_read;
+            }
+
+            while let Some(block) = codec.decode(&mut self.buf)? {
+                blocks.push(block);
+            }
+
+            if !blocks.is_empty() {
+                break
+            }
+
+            if self.remaining == 0 {
+                if self.buf.is_empty() {
+                    return Ok(None)
+                }
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "block file ends with an incomplete block",
+                )
+                .into())
+            }
+
+            // a single block is larger than the chunk size, keep reading
+        }
+
+        trace!(target: "downloaders::file", blocks = blocks.len(), remaining_bytes = self.remaining, "Read chunk of block file");
+
+        Ok(Some(FileClient::from_blocks(blocks)))
+    }
+}
+
 impl HeadersClient for FileClient {
     type Output = HeadersFut;
 
@@ -382,4 +481,29 @@ mod tests {
             Some(Ok(res)) => assert_eq!(res, zip_blocks(headers.iter(), &mut bodies))
         );
     }
+
+    #[tokio::test]
+    async fn test_chunked_file_reader() {
+        let (file, headers, _) = generate_bodies_file(0..=19).await;
+        let file_len = file.metadata().await.unwrap().len();
+
+        // read the file in chunks that are smaller than the file, so blocks are split across
+        // chunks
+        let mut reader = ChunkedFileReader::from_file(file, Some(file_len / 3)).await.unwrap();
+
+        let mut chunks = 0;
+        let mut read_headers = Vec::new();
+        while let Some(client) = reader.next_chunk().await.unwrap() {
+            assert!(client.has_canonical_blocks());
+            let (min, max) = (client.min_block().unwrap(), client.max_block().unwrap());
+            assert_eq!(client.tip(), Some(client.headers[&max].hash_slow()));
+            for number in min..=max {
+                read_headers.push(client.headers[&number].clone().seal_slow());
+            }
+            chunks += 1;
+        }
+
+        assert!(chunks >= 3);
+        assert_eq!(read_headers, headers);
+    }
 }
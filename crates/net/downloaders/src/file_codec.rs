@@ -0,0 +1,53 @@
+//! Codec for reading raw block bodies from a file.
+use crate::file_client::FileClientError;
+use reth_primitives::{
+    bytes::{Buf, BytesMut},
+    Block,
+};
+use reth_rlp::{Decodable, DecodeError, Encodable, Header as RlpHeader};
+use tokio_util::codec::{Decoder, Encoder};
+
+/// Codec for reading raw block bodies from a file.
+///
+/// The decoder only consumes a block once its entire RLP encoding is buffered. If a block falls
+/// across two read buffers, [`decode`](Decoder::decode) returns `Ok(None)` and reserves enough
+/// space for the remainder of the block, so the codec can be used with a
+/// [`FramedRead`](tokio_util::codec::FramedRead) of any capacity.
+pub(crate) struct BlockFileCodec;
+
+impl Decoder for BlockFileCodec {
+    type Item = Block;
+    type Error = FileClientError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if src.is_empty() {
+            return Ok(None)
+        }
+
+        // peek at the rlp header to find out whether the entire block is buffered
+        let mut header_slice = src.as_ref();
+        let header = match RlpHeader::decode(&mut header_slice) {
+            Ok(header) => header,
+            Err(DecodeError::InputTooShort) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let block_len = src.len() - header_slice.len() + header.payload_length;
+        if src.len() < block_len {
+            src.reserve(block_len - src.len());
+            return Ok(None)
+        }
+
+        let body = Block::decode(&mut &src[..block_len])?;
+        src.advance(block_len);
+        Ok(Some(body))
+    }
+}
+
+impl Encoder<Block> for BlockFileCodec {
+    type Error = FileClientError;
+
+    fn encode(&mut self, item: Block, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        item.encode(dst);
+        Ok(())
+    }
+}
@@ -17,5 +17,13 @@ pub mod headers;
 /// Common downloader metrics.
 pub mod metrics;
 
+/// Reading, writing and verification of era1 archives.
+pub mod era;
+
+/// A client that serves blocks read from RLP encoded files.
+pub mod file_client;
+
+mod file_codec;
+
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
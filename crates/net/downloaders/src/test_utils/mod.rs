@@ -1,6 +1,6 @@
 #![allow(unused)]
 //! Test helper impls
-use crate::bodies::test_utils::create_raw_bodies;
+use crate::{bodies::test_utils::create_raw_bodies, file_codec::BlockFileCodec};
 use futures::SinkExt;
 use reth_interfaces::test_utils::generators::random_block_range;
 use reth_primitives::{BlockBody, SealedHeader, H256};
@@ -12,12 +12,8 @@ use tokio::{
 use tokio_util::codec::FramedWrite;
 
 mod bodies_client;
-mod file_client;
-mod file_codec;
 
 pub use bodies_client::TestBodiesClient;
-pub use file_client::{FileClient, FileClientError};
-pub(crate) use file_codec::BlockFileCodec;
 
 /// Metrics scope used for testing.
 pub(crate) const TEST_SCOPE: &str = "downloaders.test";
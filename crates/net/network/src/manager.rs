@@ -39,7 +39,7 @@ use reth_eth_wire::{
     DisconnectReason, EthVersion, Status,
 };
 use reth_net_common::bandwidth_meter::BandwidthMeter;
-use reth_network_api::ReputationChangeKind;
+use reth_network_api::{PeerKind, ReputationChangeKind};
 use reth_primitives::{listener::EventListeners, NodeRecord, PeerId, H256};
 use reth_provider::BlockProvider;
 use reth_rpc_types::{EthProtocolInfo, NetworkStatus};
@@ -685,6 +685,8 @@ where
                                     .peers_mut()
                                     .on_incoming_session_established(peer_id, remote_addr);
                             }
+                            let kind =
+                                this.swarm.state().peers().peer_kind(&peer_id).unwrap_or_default();
                             this.event_listeners.notify(NetworkEvent::SessionEstablished {
                                 peer_id,
                                 remote_addr,
@@ -693,6 +695,7 @@ where
                                 version,
                                 status,
                                 messages,
+                                kind,
                             });
                         }
                         SwarmEvent::PeerAdded(peer_id) => {
@@ -909,6 +912,8 @@ pub enum NetworkEvent {
         status: Status,
         /// negotiated eth version of the session
         version: EthVersion,
+        /// The kind of the peer at the time the session was established.
+        kind: PeerKind,
     },
     /// Event emitted when a new peer is added
     PeerAdded(PeerId),
@@ -54,6 +54,12 @@ pub struct TransactionsManagerMetrics {
     pub(crate) messages_with_already_seen_hashes: Counter,
     /// Total number of messages with already seen full transactions
     pub(crate) messages_with_already_seen_transactions: Counter,
+    /// Total number of transactions or hashes a peer sent us that we already received from it
+    pub(crate) duplicate_transactions_received: Counter,
+    /// Total number of transactions received in responses that we did not request
+    pub(crate) unsolicited_transactions_received: Counter,
+    /// Total number of times propagation to a peer was cut short by its rate limit
+    pub(crate) rate_limited_propagations: Counter,
 }
 
 /// Metrics for Disconnection types
@@ -337,6 +337,11 @@ impl PeersManager {
         self.peers.get(peer_id).map(|peer| peer.reputation)
     }
 
+    /// Returns the [PeerKind] of the given peer, if it is tracked.
+    pub(crate) fn peer_kind(&self, peer_id: &PeerId) -> Option<PeerKind> {
+        self.peers.get(peer_id).map(|peer| peer.kind)
+    }
+
     /// Apply the corresponding reputation change to the given peer
     pub(crate) fn apply_reputation_change(&mut self, peer_id: &PeerId, rep: ReputationChangeKind) {
         let outcome = if let Some(peer) = self.peers.get_mut(peer_id) {
@@ -0,0 +1,529 @@
+//! Fetching of announced transactions via `GetPooledTransactions` requests.
+
+use super::Peer;
+use crate::message::PeerRequest;
+use futures::{ready, stream::FuturesUnordered, FutureExt, StreamExt};
+use linked_hash_set::LinkedHashSet;
+use reth_eth_wire::{GetPooledTransactions, PooledTransactions};
+use reth_interfaces::p2p::error::{RequestError, RequestResult};
+use reth_primitives::{PeerId, PooledTransactionsElement, TxHash, EIP4844_TX_TYPE_ID};
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::sync::oneshot;
+use tracing::trace;
+
+/// Maximum number of hashes requested in a single `GetPooledTransactions` request.
+///
+/// This is the soft limit recommended by the [eth spec](https://github.com/ethereum/devp2p/blob/master/caps/eth.md#getpooledtransactions-0x09).
+const MAX_HASHES_PER_REQUEST: usize = 256;
+
+/// Soft limit for the size of the expected `PooledTransactions` response, based on the sizes
+/// announced by `eth/68` peers.
+const SOFT_LIMIT_RESPONSE_SIZE: usize = 2 * 1024 * 1024;
+
+/// Maximum number of concurrent `GetPooledTransactions` requests to a single peer.
+const MAX_CONCURRENT_REQUESTS_PER_PEER: usize = 1;
+
+/// Maximum number of peers that are remembered as announcers of a single hash.
+const MAX_ANNOUNCERS_PER_HASH: usize = 4;
+
+/// Maximum number of hashes that are tracked at the same time.
+const MAX_TRACKED_HASHES: usize = 32 * 1024;
+
+/// Fetches announced transactions from the peers that announced them.
+///
+/// Each hash is requested from at most one peer at a time. Other peers that announce a hash while
+/// it is in flight are remembered and the hash is requested from one of them if the request fails,
+/// times out or the response does not include the transaction.
+///
+/// Note: request timeouts are enforced by the session of the peer, which then responds with
+/// [RequestError::Timeout].
+#[derive(Default)]
+pub(super) struct TransactionFetcher {
+    /// All currently active requests for pooled transactions.
+    inflight_requests: FuturesUnordered<GetPooledTxRequest>,
+    /// Number of currently active requests by peer.
+    active_requests: HashMap<PeerId, usize>,
+    /// All hashes that are either in flight or waiting for one of their announcers to be idle.
+    hashes: HashMap<TxHash, FetchState>,
+    /// Hashes that are not in flight, indexed by their announcers, oldest first.
+    idle_by_peer: HashMap<PeerId, LinkedHashSet<TxHash>>,
+}
+
+// === impl TransactionFetcher ===
+
+impl TransactionFetcher {
+    /// Tracks the hashes announced by the peer and requests those that aren't in flight yet.
+    ///
+    /// Hashes that are already requested from another peer are only requested from this peer if
+    /// that request fails.
+    pub(super) fn on_announced(
+        &mut self,
+        peer_id: PeerId,
+        announced: Vec<(TxHash, Option<AnnouncedTx>)>,
+        peers: &HashMap<PeerId, Peer>,
+    ) {
+        for (hash, announced) in announced {
+            if !self.hashes.contains_key(&hash) && self.hashes.len() >= MAX_TRACKED_HASHES {
+                trace!(target: "net::tx", ?peer_id, ?hash, "Too many tracked hashes, ignoring announcement");
+                continue
+            }
+
+            let state = self.hashes.entry(hash).or_insert_with(|| FetchState {
+                announced,
+                announcers: Vec::new(),
+                inflight: None,
+            });
+            if state.announced.is_none() {
+                state.announced = announced;
+            }
+            if state.announcers.len() < MAX_ANNOUNCERS_PER_HASH &&
+                !state.announcers.contains(&peer_id)
+            {
+                state.announcers.push(peer_id);
+                if state.inflight.is_none() {
+                    self.idle_by_peer.entry(peer_id).or_default().insert(hash);
+                }
+            }
+        }
+
+        self.dispatch([peer_id], peers);
+    }
+
+    /// Removes the peer from the announcers of all tracked hashes and requests its idle hashes
+    /// from their remaining announcers.
+    ///
+    /// Requests that are in flight to the peer resolve with an error once the session is gone.
+    pub(super) fn on_session_closed(&mut self, peer_id: &PeerId, peers: &HashMap<PeerId, Peer>) {
+        let idle = self.idle_by_peer.remove(peer_id).unwrap_or_default();
+        let mut fallback = HashSet::new();
+
+        self.hashes.retain(|hash, state| {
+            state.announcers.retain(|announcer| announcer != peer_id);
+            if idle.contains(hash) {
+                fallback.extend(state.announcers.iter().copied());
+            }
+            !state.announcers.is_empty() || state.inflight.is_some()
+        });
+
+        self.dispatch(fallback, peers);
+    }
+
+    /// Requests idle hashes from the given peers.
+    ///
+    /// If a request can't be sent to a peer, its hashes are requested from their other announcers
+    /// instead. Every peer is tried at most once.
+    fn dispatch(
+        &mut self,
+        peer_ids: impl IntoIterator<Item = PeerId>,
+        peers: &HashMap<PeerId, Peer>,
+    ) {
+        let mut queue = peer_ids.into_iter().collect::<VecDeque<_>>();
+        let mut tried = HashSet::new();
+
+        while let Some(peer_id) = queue.pop_front() {
+            if !tried.insert(peer_id) {
+                continue
+            }
+            let Some(unsent) = self.request_from(peer_id, peers) else { continue };
+
+            for hash in unsent {
+                let Some(state) = self.hashes.get(&hash) else { continue };
+                for announcer in state.announcers.iter() {
+                    if !tried.contains(announcer) {
+                        trace!(target: "net::tx", busy=?peer_id, ?announcer, "Requesting transactions from alternative announcer");
+                        queue.push_back(*announcer);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sends requests for the idle hashes announced by the peer as long as the peer has capacity.
+    ///
+    /// Returns the batch that couldn't be sent to the peer, if any.
+    fn request_from(
+        &mut self,
+        peer_id: PeerId,
+        peers: &HashMap<PeerId, Peer>,
+    ) -> Option<Vec<TxHash>> {
+        let peer = peers.get(&peer_id)?;
+
+        while self.active_requests.get(&peer_id).copied().unwrap_or_default() <
+            MAX_CONCURRENT_REQUESTS_PER_PEER
+        {
+            let batch = self.next_batch(&peer_id);
+            if batch.is_empty() {
+                return None
+            }
+
+            let (response, rx) = oneshot::channel();
+            let req = PeerRequest::GetPooledTransactions {
+                request: GetPooledTransactions(batch.clone()),
+                response,
+            };
+            if peer.request_tx.try_send(req).is_err() {
+                trace!(target: "net::tx", ?peer_id, "Failed to send pooled transactions request");
+                return Some(batch)
+            }
+
+            for hash in batch.iter() {
+                if let Some(state) = self.hashes.get_mut(hash) {
+                    state.inflight = Some(peer_id);
+                    remove_idle(&mut self.idle_by_peer, hash, &state.announcers);
+                }
+            }
+            *self.active_requests.entry(peer_id).or_default() += 1;
+            self.inflight_requests.push(GetPooledTxRequest {
+                peer_id,
+                requested: batch,
+                response: rx,
+            });
+        }
+
+        None
+    }
+
+    /// Returns the next batch of idle hashes announced by the peer.
+    ///
+    /// The batch is bounded by [MAX_HASHES_PER_REQUEST] and the sizes of the transactions, if they
+    /// were announced.
+    fn next_batch(&self, peer_id: &PeerId) -> Vec<TxHash> {
+        let mut batch = Vec::new();
+        let mut size = 0;
+
+        let Some(idle) = self.idle_by_peer.get(peer_id) else { return batch };
+        for hash in idle.iter() {
+            let Some(state) = self.hashes.get(hash) else { continue };
+
+            // the first hash is always requested, even if it exceeds the limit on its own
+            let tx_size = state.announced.map(|announced| announced.size).unwrap_or_default();
+            if !batch.is_empty() && size + tx_size > SOFT_LIMIT_RESPONSE_SIZE {
+                continue
+            }
+
+            size += tx_size;
+            batch.push(*hash);
+            if batch.len() == MAX_HASHES_PER_REQUEST {
+                break
+            }
+        }
+
+        batch
+    }
+
+    /// Marks the hashes as failed for the peer and requests them from their other announcers.
+    ///
+    /// Hashes that have no announcers left are no longer tracked.
+    fn on_failed(&mut self, peer_id: PeerId, hashes: Vec<TxHash>, peers: &HashMap<PeerId, Peer>) {
+        let mut fallback = HashSet::new();
+
+        for hash in hashes {
+            let Entry::Occupied(mut entry) = self.hashes.entry(hash) else { continue };
+            let state = entry.get_mut();
+            state.inflight = None;
+            state.announcers.retain(|announcer| *announcer != peer_id);
+            if state.announcers.is_empty() {
+                entry.remove();
+            } else {
+                for announcer in state.announcers.iter() {
+                    self.idle_by_peer.entry(*announcer).or_default().insert(hash);
+                }
+                fallback.extend(state.announcers.iter().copied());
+            }
+        }
+
+        for announcer in fallback.iter() {
+            trace!(target: "net::tx", failed=?peer_id, ?announcer, "Requesting transactions from alternative announcer");
+        }
+        self.dispatch(fallback, peers);
+    }
+
+    /// Advances all inflight requests and returns the next outcome.
+    ///
+    /// Transactions that don't match the type they were announced with are dropped.
+    pub(super) fn poll(
+        &mut self,
+        cx: &mut Context<'_>,
+        peers: &HashMap<PeerId, Peer>,
+    ) -> Poll<FetchEvent> {
+        let Poll::Ready(Some(response)) = self.inflight_requests.poll_next_unpin(cx) else {
+            return Poll::Pending
+        };
+        let GetPooledTxResponse { peer_id, requested, result } = response;
+
+        if let Entry::Occupied(mut entry) = self.active_requests.entry(peer_id) {
+            *entry.get_mut() -= 1;
+            if *entry.get() == 0 {
+                entry.remove();
+            }
+        }
+
+        let event = match result {
+            Ok(Ok(PooledTransactions(mut transactions))) => {
+                let mut announcement_mismatch = false;
+                transactions.retain(|tx| {
+                    let matches = self
+                        .hashes
+                        .get(&tx.hash())
+                        .and_then(|state| state.announced)
+                        .map_or(true, |announced| announced.tx_type == tx_type(tx));
+                    announcement_mismatch |= !matches;
+                    matches
+                });
+
+                let delivered = transactions.iter().map(|tx| tx.hash()).collect::<HashSet<_>>();
+                let mut failed = Vec::new();
+                for hash in requested.iter() {
+                    if delivered.contains(hash) {
+                        self.hashes.remove(hash);
+                    } else {
+                        failed.push(*hash);
+                    }
+                }
+                self.on_failed(peer_id, failed, peers);
+
+                FetchEvent::TransactionsFetched {
+                    peer_id,
+                    requested: requested.into_iter().collect(),
+                    transactions,
+                    announcement_mismatch,
+                }
+            }
+            Ok(Err(error)) => {
+                self.on_failed(peer_id, requested, peers);
+                FetchEvent::FetchError { peer_id, error }
+            }
+            Err(_) => {
+                self.on_failed(peer_id, requested, peers);
+                FetchEvent::FetchError { peer_id, error: RequestError::ChannelClosed }
+            }
+        };
+
+        // the peer has capacity again
+        self.dispatch([peer_id], peers);
+
+        Poll::Ready(event)
+    }
+}
+
+/// Outcome of a `GetPooledTransactions` request sent by the [TransactionFetcher].
+pub(super) enum FetchEvent {
+    /// The peer responded to the request.
+    TransactionsFetched {
+        peer_id: PeerId,
+        /// All hashes that were requested.
+        requested: HashSet<TxHash>,
+        /// The delivered transactions, this can include transactions that weren't requested.
+        transactions: Vec<PooledTransactionsElement>,
+        /// Whether the peer delivered transactions that don't match their announced type.
+        announcement_mismatch: bool,
+    },
+    /// The request failed.
+    FetchError { peer_id: PeerId, error: RequestError },
+}
+
+/// The type and size of a transaction as announced via `eth/68`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(super) struct AnnouncedTx {
+    pub(super) tx_type: u8,
+    pub(super) size: usize,
+}
+
+/// Tracks the fetch state of a single hash.
+struct FetchState {
+    /// The type and size of the transaction, if announced via `eth/68`.
+    announced: Option<AnnouncedTx>,
+    /// Peers that announced the hash and haven't failed to deliver it yet, oldest first.
+    announcers: Vec<PeerId>,
+    /// The peer the hash is currently requested from.
+    inflight: Option<PeerId>,
+}
+
+/// An inflight request for `PooledTransactions` from a peer
+struct GetPooledTxRequest {
+    peer_id: PeerId,
+    requested: Vec<TxHash>,
+    response: oneshot::Receiver<RequestResult<PooledTransactions>>,
+}
+
+/// The resolved [GetPooledTxRequest].
+struct GetPooledTxResponse {
+    peer_id: PeerId,
+    requested: Vec<TxHash>,
+    result: Result<RequestResult<PooledTransactions>, oneshot::error::RecvError>,
+}
+
+impl Future for GetPooledTxRequest {
+    type Output = GetPooledTxResponse;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let result = ready!(this.response.poll_unpin(cx));
+        Poll::Ready(GetPooledTxResponse {
+            peer_id: this.peer_id,
+            requested: std::mem::take(&mut this.requested),
+            result,
+        })
+    }
+}
+
+/// Removes the hash from the idle hashes of its announcers.
+fn remove_idle(
+    idle_by_peer: &mut HashMap<PeerId, LinkedHashSet<TxHash>>,
+    hash: &TxHash,
+    announcers: &[PeerId],
+) {
+    for announcer in announcers {
+        if let Entry::Occupied(mut entry) = idle_by_peer.entry(*announcer) {
+            entry.get_mut().remove(hash);
+            if entry.get().is_empty() {
+                entry.remove();
+            }
+        }
+    }
+}
+
+/// Returns the `eth/68` type identifier of the transaction.
+fn tx_type(tx: &PooledTransactionsElement) -> u8 {
+    match tx {
+        PooledTransactionsElement::Transaction(tx) => tx.tx_type().into(),
+        PooledTransactionsElement::BlobTransaction(_) => EIP4844_TX_TYPE_ID,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::PeerRequestSender;
+    use reth_eth_wire::EthVersion;
+    use reth_network_api::PeerKind;
+    use reth_primitives::H256;
+    use tokio::sync::mpsc;
+
+    fn add_peer(peers: &mut HashMap<PeerId, Peer>) -> (PeerId, mpsc::Receiver<PeerRequest>) {
+        add_peer_with_capacity(peers, 8)
+    }
+
+    fn add_peer_with_capacity(
+        peers: &mut HashMap<PeerId, Peer>,
+        capacity: usize,
+    ) -> (PeerId, mpsc::Receiver<PeerRequest>) {
+        let peer_id = PeerId::random();
+        let (tx, rx) = mpsc::channel(capacity);
+        let peer = Peer::new(
+            PeerRequestSender::new(peer_id, tx),
+            EthVersion::Eth68,
+            Default::default(),
+            PeerKind::Basic,
+        );
+        peers.insert(peer_id, peer);
+        (peer_id, rx)
+    }
+
+    /// Fills the request channel of the peer, which has a capacity of one.
+    fn fill_channel(peers: &HashMap<PeerId, Peer>, peer_id: &PeerId) {
+        let (response, _) = oneshot::channel();
+        let req = PeerRequest::GetPooledTransactions {
+            request: GetPooledTransactions(Vec::new()),
+            response,
+        };
+        peers[peer_id].request_tx.try_send(req).unwrap();
+    }
+
+    fn requested_hashes(rx: &mut mpsc::Receiver<PeerRequest>) -> Vec<TxHash> {
+        match rx.try_recv() {
+            Ok(PeerRequest::GetPooledTransactions { request, .. }) => request.0,
+            _ => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_batch_by_announced_size() {
+        let mut fetcher = TransactionFetcher::default();
+        let mut peers = HashMap::new();
+        let (peer_id, mut rx) = add_peer(&mut peers);
+
+        let announced = (0..3)
+            .map(|_| {
+                let tx = AnnouncedTx { tx_type: 2, size: SOFT_LIMIT_RESPONSE_SIZE / 2 };
+                (H256::random(), Some(tx))
+            })
+            .collect();
+        fetcher.on_announced(peer_id, announced, &peers);
+
+        // only two transactions fit into a single response and the peer is busy afterwards
+        assert_eq!(requested_hashes(&mut rx).len(), 2);
+        assert!(requested_hashes(&mut rx).is_empty());
+    }
+
+    #[test]
+    fn test_deduplicate_inflight_hashes() {
+        let mut fetcher = TransactionFetcher::default();
+        let mut peers = HashMap::new();
+        let (peer0, mut rx0) = add_peer(&mut peers);
+        let (peer1, mut rx1) = add_peer(&mut peers);
+
+        let hash = H256::random();
+        fetcher.on_announced(peer0, vec![(hash, None)], &peers);
+        fetcher.on_announced(peer1, vec![(hash, None)], &peers);
+
+        assert_eq!(requested_hashes(&mut rx0), vec![hash]);
+        assert!(requested_hashes(&mut rx1).is_empty());
+
+        // the hash is no longer tracked once all announcers disconnected
+        fetcher.on_session_closed(&peer1, &peers);
+        assert!(fetcher.hashes.contains_key(&hash));
+        fetcher.on_failed(peer0, vec![hash], &peers);
+        assert!(!fetcher.hashes.contains_key(&hash));
+    }
+
+    #[test]
+    fn test_unsent_request_falls_back_to_other_announcer() {
+        let mut fetcher = TransactionFetcher::default();
+        let mut peers = HashMap::new();
+        let (peer0, rx0) = add_peer(&mut peers);
+        let (peer1, mut rx1) = add_peer_with_capacity(&mut peers, 1);
+
+        // the request to peer1 can't be sent while its channel is full
+        let hash = H256::random();
+        fill_channel(&peers, &peer1);
+        fetcher.on_announced(peer1, vec![(hash, None)], &peers);
+        assert!(fetcher.idle_by_peer[&peer1].contains(&hash));
+        rx1.try_recv().unwrap();
+
+        // the request to peer0 can't be sent either, so the hash is requested from peer1 instead
+        drop(rx0);
+        fetcher.on_announced(peer0, vec![(hash, None)], &peers);
+        assert_eq!(requested_hashes(&mut rx1), vec![hash]);
+        assert_eq!(fetcher.hashes[&hash].inflight, Some(peer1));
+        assert!(fetcher.idle_by_peer.is_empty());
+    }
+
+    #[test]
+    fn test_session_closed_requests_from_other_announcer() {
+        let mut fetcher = TransactionFetcher::default();
+        let mut peers = HashMap::new();
+        let (peer0, mut rx0) = add_peer_with_capacity(&mut peers, 1);
+        let (peer1, mut rx1) = add_peer_with_capacity(&mut peers, 1);
+
+        // neither request can be sent, so the hash remains idle
+        let hash = H256::random();
+        fill_channel(&peers, &peer0);
+        fill_channel(&peers, &peer1);
+        fetcher.on_announced(peer0, vec![(hash, None)], &peers);
+        fetcher.on_announced(peer1, vec![(hash, None)], &peers);
+        rx0.try_recv().unwrap();
+        rx1.try_recv().unwrap();
+        assert!(requested_hashes(&mut rx1).is_empty());
+
+        fetcher.on_session_closed(&peer0, &peers);
+        assert!(requested_hashes(&mut rx0).is_empty());
+        assert_eq!(requested_hashes(&mut rx1), vec![hash]);
+        assert!(fetcher.idle_by_peer.is_empty());
+    }
+}
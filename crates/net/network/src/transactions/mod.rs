@@ -0,0 +1,1456 @@
+//! Transactions management for the p2p network.
+
+use self::fetcher::{AnnouncedTx, FetchEvent, TransactionFetcher};
+use crate::{
+    cache::LruCache, manager::NetworkEvent, message::PeerRequestSender,
+    metrics::TransactionsManagerMetrics, NetworkHandle,
+};
+use futures::{stream::FuturesUnordered, StreamExt};
+use reth_eth_wire::{
+    EthVersion, GetPooledTransactions, NewPooledTransactionHashes, NewPooledTransactionHashes66,
+    NewPooledTransactionHashes68, PooledTransactions, Transactions,
+};
+use reth_interfaces::{p2p::error::RequestResult, sync::SyncStateProvider};
+use reth_network_api::{PeerKind, Peers, ReputationChangeKind};
+use reth_primitives::{
+    IntoRecoveredTransaction, PeerId, PooledTransactionsElement, TransactionSigned, TxHash,
+    EIP4844_TX_TYPE_ID, H256,
+};
+use reth_rlp::Encodable;
+use reth_transaction_pool::{
+    error::PoolResult, PoolTransaction, PropagateKind, PropagatedTransactions, TransactionPool,
+    ValidPoolTransaction,
+};
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    future::Future,
+    num::NonZeroUsize,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::wrappers::{ReceiverStream, UnboundedReceiverStream};
+use tracing::{debug, trace};
+
+mod fetcher;
+
+/// Cache limit of transactions to keep track of for a single peer.
+const PEER_TRANSACTION_CACHE_LIMIT: usize = 1024 * 10;
+
+/// Soft limit for NewPooledTransactions
+const NEW_POOLED_TRANSACTION_HASHES_SOFT_LIMIT: usize = 4096;
+
+/// The target size for the message of full transactions.
+const MAX_FULL_TRANSACTIONS_PACKET_SIZE: usize = 100 * 1024;
+
+/// Number of transactions or hashes a peer can send us more than once before it is penalized
+/// with a bad message, on top of the penalty for every message that includes duplicates.
+const DUPLICATE_RECEIPTS_THRESHOLD: u64 = 1024;
+
+/// The future for inserting a function into the pool
+pub type PoolImportFuture = Pin<Box<dyn Future<Output = PoolResult<TxHash>> + Send + 'static>>;
+
+/// Configures how the [`TransactionsManager`] propagates transactions to its peers.
+#[derive(Debug, Clone)]
+pub struct TransactionsManagerConfig {
+    /// Determines to which peers transactions are propagated.
+    pub propagation_mode: TransactionPropagationMode,
+    /// Maximum number of hashes included in a single `NewPooledTransactionHashes` message.
+    ///
+    /// Larger announcements are split into multiple messages.
+    pub max_announcement_batch_size: usize,
+    /// Limits the number of transactions propagated to a single peer, if set.
+    pub peer_rate_limit: Option<PeerPropagationLimit>,
+    /// Whether peers with an `eth/68` session only receive typed hash announcements.
+    ///
+    /// If enabled, full transactions are never broadcast to `eth/68` peers, they request the
+    /// transactions they don't know yet via `GetPooledTransactions` instead.
+    pub eth68_announce_only: bool,
+}
+
+impl Default for TransactionsManagerConfig {
+    fn default() -> Self {
+        Self {
+            propagation_mode: Default::default(),
+            max_announcement_batch_size: NEW_POOLED_TRANSACTION_HASHES_SOFT_LIMIT,
+            peer_rate_limit: None,
+            eth68_announce_only: false,
+        }
+    }
+}
+
+// === impl TransactionsManagerConfig ===
+
+impl TransactionsManagerConfig {
+    /// Sets the [TransactionPropagationMode].
+    pub fn with_propagation_mode(mut self, mode: TransactionPropagationMode) -> Self {
+        self.propagation_mode = mode;
+        self
+    }
+
+    /// Sets the maximum number of hashes in a single announcement, at least one.
+    pub fn with_max_announcement_batch_size(mut self, max: usize) -> Self {
+        self.max_announcement_batch_size = max.max(1);
+        self
+    }
+
+    /// Limits the number of transactions propagated to a single peer.
+    pub fn with_peer_rate_limit(mut self, limit: PeerPropagationLimit) -> Self {
+        self.peer_rate_limit = Some(limit);
+        self
+    }
+
+    /// Configures whether `eth/68` peers only receive hash announcements.
+    pub fn with_eth68_announce_only(mut self, announce_only: bool) -> Self {
+        self.eth68_announce_only = announce_only;
+        self
+    }
+
+    /// Returns true if transactions should be propagated to a peer of the given kind.
+    fn should_propagate_to(&self, kind: PeerKind) -> bool {
+        match self.propagation_mode {
+            TransactionPropagationMode::All => true,
+            TransactionPropagationMode::TrustedOnly => kind == PeerKind::Trusted,
+        }
+    }
+
+    /// Returns true if full transactions may be broadcast to a peer with the given version.
+    fn allows_full_broadcast(&self, version: EthVersion) -> bool {
+        !(self.eth68_announce_only && version == EthVersion::Eth68)
+    }
+}
+
+/// Determines to which of the connected peers transactions are propagated.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum TransactionPropagationMode {
+    /// Propagate transactions to all connected peers.
+    #[default]
+    All,
+    /// Only propagate transactions to [trusted](PeerKind::Trusted) peers.
+    ///
+    /// This is intended for sentry setups where a node must only gossip with its own sentry
+    /// nodes. `GetPooledTransactions` requests of other peers are answered with an empty response.
+    ///
+    /// Note: the kind of a peer is determined when the session is established.
+    TrustedOnly,
+}
+
+/// Limits the number of transactions that are propagated to a single peer per interval.
+///
+/// Transactions that exceed the limit are not queued: they're not sent to the peer and are not
+/// retried once the next interval starts. The peer can still learn about them from other peers or
+/// from the announcement of the pool's transactions on the next session.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PeerPropagationLimit {
+    /// Maximum number of transactions, broadcast in full or announced, per interval.
+    pub max_transactions: usize,
+    /// The length of the interval.
+    pub interval: Duration,
+}
+
+/// Api to interact with [`TransactionsManager`] task.
+pub struct TransactionsHandle {
+    /// Command channel to the [`TransactionsManager`]
+    manager_tx: mpsc::UnboundedSender<TransactionsCommand>,
+}
+
+// === impl TransactionsHandle ===
+
+impl TransactionsHandle {
+    fn send(&self, cmd: TransactionsCommand) {
+        let _ = self.manager_tx.send(cmd);
+    }
+
+    /// Manually propagate the transaction that belongs to the hash.
+    pub fn propagate(&self, hash: TxHash) {
+        self.send(TransactionsCommand::PropagateHash(hash))
+    }
+}
+
+/// Manages transactions on top of the p2p network.
+///
+/// This can be spawned to another task and is supposed to be run as background service while
+/// [`TransactionsHandle`] is used as frontend to send commands to.
+///
+/// The [`TransactionsManager`] is responsible for:
+///    - handling incoming eth messages for transactions.
+///    - serving transaction requests.
+///    - fetching announced transactions, see [`TransactionFetcher`].
+///    - propagate transactions
+///
+/// This type communicates with the [`NetworkManager`](crate::NetworkManager) in both directions.
+///   - receives incoming network messages.
+///   - sends messages to dispatch (responses, propagate tx)
+///
+/// It is directly connected to the [`TransactionPool`] to retrieve requested transactions and
+/// propagate new transactions over the network.
+#[must_use = "Manager does nothing unless polled."]
+pub struct TransactionsManager<Pool> {
+    /// Access to the transaction pool.
+    pool: Pool,
+    /// Network access.
+    network: NetworkHandle,
+    /// Subscriptions to all network related events.
+    ///
+    /// From which we get all new incoming transaction related messages.
+    network_events: UnboundedReceiverStream<NetworkEvent>,
+    /// Requests the transactions announced by peers.
+    transaction_fetcher: TransactionFetcher,
+    /// All currently pending transactions grouped by peers.
+    ///
+    /// This way we can track incoming transactions and prevent multiple pool imports for the same
+    /// transaction
+    transactions_by_peers: HashMap<TxHash, Vec<PeerId>>,
+    /// Transactions that are currently imported into the `Pool`
+    pool_imports: FuturesUnordered<PoolImportFuture>,
+    /// All the connected peers.
+    peers: HashMap<PeerId, Peer>,
+    /// Send half for the command channel.
+    command_tx: mpsc::UnboundedSender<TransactionsCommand>,
+    /// Incoming commands from [`TransactionsHandle`].
+    command_rx: UnboundedReceiverStream<TransactionsCommand>,
+    /// Incoming commands from [`TransactionsHandle`].
+    pending_transactions: ReceiverStream<TxHash>,
+    /// Incoming events from the [`NetworkManager`](crate::NetworkManager).
+    transaction_events: UnboundedReceiverStream<NetworkTransactionEvent>,
+    /// How transactions are propagated.
+    config: TransactionsManagerConfig,
+    /// TransactionsManager metrics
+    metrics: TransactionsManagerMetrics,
+}
+
+impl<Pool: TransactionPool> TransactionsManager<Pool> {
+    /// Sets up a new instance.
+    ///
+    /// Note: This expects an existing [`NetworkManager`](crate::NetworkManager) instance.
+    pub fn new(
+        network: NetworkHandle,
+        pool: Pool,
+        from_network: mpsc::UnboundedReceiver<NetworkTransactionEvent>,
+    ) -> Self {
+        Self::with_config(network, pool, from_network, Default::default())
+    }
+
+    /// Sets up a new instance with the given [TransactionsManagerConfig].
+    ///
+    /// Note: This expects an existing [`NetworkManager`](crate::NetworkManager) instance.
+    pub fn with_config(
+        network: NetworkHandle,
+        pool: Pool,
+        from_network: mpsc::UnboundedReceiver<NetworkTransactionEvent>,
+        config: TransactionsManagerConfig,
+    ) -> Self {
+        let network_events = network.event_listener();
+        let (command_tx, command_rx) = mpsc::unbounded_channel();
+
+        // install a listener for new transactions
+        let pending = pool.pending_transactions_listener();
+
+        Self {
+            pool,
+            network,
+            network_events,
+            transaction_fetcher: Default::default(),
+            transactions_by_peers: Default::default(),
+            pool_imports: Default::default(),
+            peers: Default::default(),
+            command_tx,
+            command_rx: UnboundedReceiverStream::new(command_rx),
+            pending_transactions: ReceiverStream::new(pending),
+            transaction_events: UnboundedReceiverStream::new(from_network),
+            config,
+            metrics: Default::default(),
+        }
+    }
+}
+
+// === impl TransactionsManager ===
+
+impl<Pool> TransactionsManager<Pool>
+where
+    Pool: TransactionPool + 'static,
+    <Pool as TransactionPool>::Transaction: IntoRecoveredTransaction,
+{
+    /// Returns a new handle that can send commands to this type.
+    pub fn handle(&self) -> TransactionsHandle {
+        TransactionsHandle { manager_tx: self.command_tx.clone() }
+    }
+
+    /// Request handler for an incoming request for transactions
+    fn on_get_pooled_transactions(
+        &mut self,
+        peer_id: PeerId,
+        request: GetPooledTransactions,
+        response: oneshot::Sender<RequestResult<PooledTransactions>>,
+    ) {
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            // transactions are only served to peers they would be propagated to
+            if !self.config.should_propagate_to(peer.kind) {
+                let _ = response.send(Ok(PooledTransactions::default()));
+                return
+            }
+
+            // blob transactions are returned with their sidecar
+            let transactions = self.pool.get_pooled_transaction_elements(request.0);
+
+            // we sent a response at which point we assume that the peer is aware of the transaction
+            peer.transactions.extend(transactions.iter().map(|tx| tx.hash()));
+
+            let resp = PooledTransactions::from(transactions);
+            let _ = response.send(Ok(resp));
+        }
+    }
+
+    /// Invoked when a new transaction is pending.
+    ///
+    /// When new transactions appear in the pool, we propagate them to the network using the
+    /// `Transactions` and `NewPooledTransactionHashes` messages. The Transactions message relays
+    /// complete transaction objects and is typically sent to a small, random fraction of connected
+    /// peers.
+    ///
+    /// All other peers receive a notification of the transaction hash and can request the
+    /// complete transaction object if it is unknown to them. The dissemination of complete
+    /// transactions to a fraction of peers usually ensures that all nodes receive the transaction
+    /// and won't need to request it.
+    ///
+    /// Private transactions are never propagated, see
+    /// [TransactionOrigin::Private](reth_transaction_pool::TransactionOrigin::Private).
+    fn on_new_transactions(&mut self, hashes: impl IntoIterator<Item = TxHash>) {
+        // Nothing to propagate while syncing
+        if self.network.is_syncing() {
+            return
+        }
+
+        trace!(target: "net::tx", "Start propagating transactions");
+
+        let propagated = self.propagate_transactions(
+            self.pool
+                .get_all(hashes)
+                .into_iter()
+                // private transactions are never propagated
+                .filter(|tx| tx.propagate)
+                .map(|tx| {
+                    let tx = Arc::new(tx.transaction.to_recovered_transaction().into_signed());
+                    PropagateTransaction::new(tx)
+                })
+                .collect(),
+        );
+
+        // notify pool so events get fired
+        self.pool.on_propagated(propagated);
+    }
+
+    /// Propagate the transactions to all connected peers either as full objects or hashes
+    ///
+    /// Blob transactions are never broadcast in full, they're always announced by hash and peers
+    /// need to request them, including their sidecar, via `GetPooledTransactions`.
+    ///
+    /// The message for new pooled hashes depends on the negotiated version of the stream.
+    /// See [NewPooledTransactionHashes](NewPooledTransactionHashes)
+    ///
+    /// Which peers are considered, how many transactions each peer receives and whether `eth/68`
+    /// peers receive full transactions at all is determined by the [TransactionsManagerConfig].
+    fn propagate_transactions(
+        &mut self,
+        to_propagate: Vec<PropagateTransaction>,
+    ) -> PropagatedTransactions {
+        let mut propagated = PropagatedTransactions::default();
+        let now = Instant::now();
+
+        // send full transactions to a fraction fo the peers we propagate to (square root of the
+        // total number of these peers)
+        let num_targets =
+            self.peers.values().filter(|peer| self.config.should_propagate_to(peer.kind)).count();
+        let max_num_full = (num_targets as f64).sqrt() as usize + 1;
+
+        let targets =
+            self.peers.iter_mut().filter(|(_, peer)| self.config.should_propagate_to(peer.kind));
+
+        // Note: Assuming ~random~ order due to random state of the peers map hasher
+        for (peer_idx, (peer_id, peer)) in targets.enumerate() {
+            let mut budget = peer.propagation_budget(self.config.peer_rate_limit.as_ref(), now);
+            let broadcast_full =
+                peer_idx <= max_num_full && self.config.allows_full_broadcast(peer.version);
+
+            // filter all transactions unknown to the peer
+            let mut full_transactions = FullTransactionsBuilder::default();
+            let mut announcements = Vec::new();
+            let mut rate_limited = false;
+
+            for tx in to_propagate.iter() {
+                if budget == 0 {
+                    rate_limited = true;
+                    break
+                }
+                if !peer.transactions.insert(tx.hash()) {
+                    continue
+                }
+                budget -= 1;
+
+                // blob transactions are only announced, and so are transactions that exceed the
+                // full transactions message size
+                if broadcast_full && !tx.is_eip4844() && full_transactions.push(tx) {
+                    propagated.0.entry(tx.hash()).or_default().push(PropagateKind::Full(*peer_id));
+                } else {
+                    propagated.0.entry(tx.hash()).or_default().push(PropagateKind::Hash(*peer_id));
+                    announcements.push(tx);
+                }
+            }
+
+            // the remaining transactions are dropped for this peer, see [PeerPropagationLimit]
+            if rate_limited {
+                self.metrics.rate_limited_propagations.increment(1);
+                trace!(target: "net::tx", ?peer_id, "Propagation rate limit reached for peer");
+            }
+
+            let full_transactions = full_transactions.build();
+            peer.consume_propagation_budget(full_transactions.len() + announcements.len());
+
+            if !full_transactions.is_empty() {
+                self.network.send_transactions(*peer_id, full_transactions);
+            }
+
+            // send the hashes in batches of at most `max_announcement_batch_size`
+            for batch in announcements.chunks(self.config.max_announcement_batch_size.max(1)) {
+                let mut hashes = PooledTransactionsHashesBuilder::new(peer.version);
+                for tx in batch {
+                    hashes.push(tx);
+                }
+                self.network.send_transactions_hashes(*peer_id, hashes.build());
+            }
+        }
+
+        // Update propagated transactions metrics
+        self.metrics.propagated_transactions.increment(propagated.0.len() as u64);
+
+        propagated
+    }
+
+    /// Request handler for an incoming `NewPooledTransactionHashes`
+    fn on_new_pooled_transaction_hashes(
+        &mut self,
+        peer_id: PeerId,
+        msg: NewPooledTransactionHashes,
+    ) {
+        // If the node is currently syncing, ignore transactions
+        if self.network.is_syncing() {
+            return
+        }
+
+        let Some(peer) = self.peers.get_mut(&peer_id) else { return };
+
+        // eth/68 announcements include the type and size of the transactions
+        let mut announced = HashMap::new();
+        let mut hashes = match msg {
+            NewPooledTransactionHashes::Eth66(msg) => msg.0,
+            NewPooledTransactionHashes::Eth68(msg) => {
+                for ((hash, tx_type), size) in msg.hashes.iter().zip(msg.types).zip(msg.sizes) {
+                    announced.insert(*hash, AnnouncedTx { tx_type, size });
+                }
+                msg.hashes
+            }
+        };
+
+        // keep track of the transactions the peer knows
+        let mut num_already_seen = 0u64;
+        for tx in hashes.iter().copied() {
+            if !peer.transactions.insert(tx) {
+                num_already_seen += 1;
+            }
+        }
+
+        if num_already_seen > 0 {
+            let exceeded = peer.record_duplicate_receipts(num_already_seen);
+            self.metrics.messages_with_already_seen_hashes.increment(1);
+            self.metrics.duplicate_transactions_received.increment(num_already_seen);
+            debug!(target: "net::tx", num_hashes=%num_already_seen, total=%peer.duplicate_receipts, ?peer_id, client=?peer.client_version, "Peer sent already seen hashes");
+            self.report_already_seen(peer_id);
+            if exceeded {
+                self.report_excess_duplicates(peer_id);
+            }
+        }
+
+        // transactions that are currently imported into the pool are not requested again
+        hashes.retain(|hash| !self.transactions_by_peers.contains_key(hash));
+        self.pool.retain_unknown(&mut hashes);
+
+        if hashes.is_empty() {
+            // nothing to request
+            return
+        }
+
+        // request the missing transactions, hashes that are already in flight are deduplicated by
+        // the fetcher
+        let hashes = hashes
+            .into_iter()
+            .map(|hash| {
+                let announced = announced.remove(&hash);
+                (hash, announced)
+            })
+            .collect();
+        self.transaction_fetcher.on_announced(peer_id, hashes, &self.peers);
+    }
+
+    /// Handles dedicated transaction events related to the `eth` protocol.
+    fn on_network_tx_event(&mut self, event: NetworkTransactionEvent) {
+        match event {
+            NetworkTransactionEvent::IncomingTransactions { peer_id, msg } => {
+                let transactions = msg.0.into_iter().map(Into::into).collect();
+                self.import_transactions(peer_id, transactions, TransactionSource::Broadcast);
+            }
+            NetworkTransactionEvent::IncomingPooledTransactionHashes { peer_id, msg } => {
+                self.on_new_pooled_transaction_hashes(peer_id, msg)
+            }
+            NetworkTransactionEvent::GetPooledTransactions { peer_id, request, response } => {
+                self.on_get_pooled_transactions(peer_id, request, response)
+            }
+        }
+    }
+
+    /// Handles a command received from a detached [`TransactionsHandle`]
+    fn on_command(&mut self, cmd: TransactionsCommand) {
+        match cmd {
+            TransactionsCommand::PropagateHash(hash) => {
+                self.on_new_transactions(std::iter::once(hash))
+            }
+        }
+    }
+
+    /// Handles a received event related to common network events.
+    fn on_network_event(&mut self, event: NetworkEvent) {
+        match event {
+            NetworkEvent::SessionClosed { peer_id, .. } => {
+                // remove the peer
+                self.peers.remove(&peer_id);
+                self.transaction_fetcher.on_session_closed(&peer_id, &self.peers);
+            }
+            NetworkEvent::SessionEstablished {
+                peer_id,
+                client_version,
+                messages,
+                version,
+                kind,
+                ..
+            } => {
+                // insert a new peer into the peerset
+                self.peers.insert(peer_id, Peer::new(messages, version, client_version, kind));
+
+                // Send a `NewPooledTransactionHashes` to the peer with up to
+                // `NEW_POOLED_TRANSACTION_HASHES_SOFT_LIMIT` transactions in the
+                // pool
+                if !self.network.is_syncing() && self.config.should_propagate_to(kind) {
+                    let peer = self.peers.get_mut(&peer_id).expect("is present; qed");
+
+                    let budget = peer
+                        .propagation_budget(self.config.peer_rate_limit.as_ref(), Instant::now());
+
+                    // private transactions are never announced
+                    let pooled_txs = self
+                        .pool
+                        .pooled_transactions_max(
+                            NEW_POOLED_TRANSACTION_HASHES_SOFT_LIMIT.min(budget),
+                        )
+                        .into_iter()
+                        .filter(|tx| tx.propagate)
+                        .collect::<Vec<_>>();
+                    if pooled_txs.is_empty() {
+                        // do not send a message if there are no transactions in the pool
+                        return
+                    }
+
+                    peer.consume_propagation_budget(pooled_txs.len());
+
+                    for batch in pooled_txs.chunks(self.config.max_announcement_batch_size.max(1)) {
+                        let mut msg_builder = PooledTransactionsHashesBuilder::new(version);
+                        for pooled_tx in batch {
+                            peer.transactions.insert(*pooled_tx.hash());
+                            msg_builder.push_pooled(Arc::clone(pooled_tx));
+                        }
+
+                        let msg = msg_builder.build();
+                        self.network.send_transactions_hashes(peer_id, msg);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Starts the import process for the given transactions.
+    fn import_transactions(
+        &mut self,
+        peer_id: PeerId,
+        transactions: Vec<PooledTransactionsElement>,
+        source: TransactionSource,
+    ) {
+        // If the node is currently syncing, ignore transactions
+        if self.network.is_syncing() {
+            return
+        }
+
+        // tracks the quality of the given transactions
+        let mut has_bad_transactions = false;
+        let mut has_blob_broadcast = false;
+        let mut num_already_seen = 0u64;
+        let mut exceeded_duplicates = false;
+
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            for tx in transactions {
+                let (tx, sidecar) = tx.into_parts();
+
+                // blob transactions must not be broadcast, only announced
+                if source.is_broadcast() && tx.is_eip4844() {
+                    has_blob_broadcast = true;
+                    continue
+                }
+
+                // recover transaction
+                let tx = if let Some(tx) = tx.into_ecrecovered() {
+                    tx
+                } else {
+                    has_bad_transactions = true;
+                    continue
+                };
+
+                // track that the peer knows this transaction, but only if this is a new broadcast.
+                // If we received the transactions as the response to our GetPooledTransactions
+                // requests (based on received `NewPooledTransactionHashes`) then we already
+                // recorded the hashes in [`Self::on_new_pooled_transaction_hashes`]
+                if source.is_broadcast() && !peer.transactions.insert(tx.hash()) {
+                    num_already_seen += 1;
+                }
+
+                match self.transactions_by_peers.entry(tx.hash()) {
+                    Entry::Occupied(mut entry) => {
+                        // transaction was already inserted
+                        entry.get_mut().push(peer_id);
+                    }
+                    Entry::Vacant(entry) => {
+                        // this is a new transaction that should be imported into the pool
+                        let pool_transaction =
+                            <Pool::Transaction>::from_recovered_pooled_transaction(tx, sidecar);
+
+                        let pool = self.pool.clone();
+
+                        let import = Box::pin(async move {
+                            pool.add_external_transaction(pool_transaction).await
+                        });
+
+                        self.pool_imports.push(import);
+                        entry.insert(vec![peer_id]);
+                    }
+                }
+            }
+
+            if num_already_seen > 0 {
+                exceeded_duplicates = peer.record_duplicate_receipts(num_already_seen);
+                self.metrics.messages_with_already_seen_transactions.increment(1);
+                self.metrics.duplicate_transactions_received.increment(num_already_seen);
+                debug!(target: "net::tx", num_txs=%num_already_seen, total=%peer.duplicate_receipts, ?peer_id, client=?peer.client_version, "Peer sent already seen transactions");
+            }
+        }
+
+        if has_bad_transactions || num_already_seen > 0 {
+            self.report_already_seen(peer_id);
+        }
+
+        if exceeded_duplicates {
+            self.report_excess_duplicates(peer_id);
+        }
+
+        if has_blob_broadcast {
+            debug!(target: "net::tx", ?peer_id, "Peer broadcast full blob transactions");
+            self.report_bad_message(peer_id);
+        }
+    }
+
+    /// Handles the response to a `GetPooledTransactions` request we sent to the peer.
+    ///
+    /// Transactions we did not request, including repeated ones, are dropped and the peer is
+    /// penalized for sending them.
+    fn on_pooled_transactions_response(
+        &mut self,
+        peer_id: PeerId,
+        mut requested: HashSet<TxHash>,
+        mut transactions: Vec<PooledTransactionsElement>,
+    ) {
+        let num_received = transactions.len();
+        transactions.retain(|tx| requested.remove(&tx.hash()));
+        let num_unsolicited = (num_received - transactions.len()) as u64;
+
+        if num_unsolicited > 0 {
+            self.metrics.unsolicited_transactions_received.increment(num_unsolicited);
+            if let Some(peer) = self.peers.get_mut(&peer_id) {
+                peer.unsolicited_receipts += num_unsolicited;
+                debug!(target: "net::tx", num_txs=%num_unsolicited, total=%peer.unsolicited_receipts, ?peer_id, client=?peer.client_version, "Peer sent unsolicited transactions");
+            }
+            self.report_unsolicited(peer_id);
+        }
+
+        self.import_transactions(peer_id, transactions, TransactionSource::Response);
+    }
+
+    fn report_unsolicited(&self, peer_id: PeerId) {
+        trace!(target: "net::tx", ?peer_id, "Penalizing peer for unsolicited transactions");
+        self.network.reputation_change(peer_id, ReputationChangeKind::BadMessage);
+    }
+
+    fn report_bad_message(&self, peer_id: PeerId) {
+        trace!(target: "net::tx", ?peer_id, "Penalizing peer for bad transaction");
+        self.metrics.reported_bad_transactions.increment(1);
+        self.network.reputation_change(peer_id, ReputationChangeKind::BadTransactions);
+    }
+
+    fn report_already_seen(&self, peer_id: PeerId) {
+        trace!(target: "net::tx", ?peer_id, "Penalizing peer for already seen transaction");
+        self.network.reputation_change(peer_id, ReputationChangeKind::AlreadySeenTransaction);
+    }
+
+    fn report_excess_duplicates(&self, peer_id: PeerId) {
+        trace!(target: "net::tx", ?peer_id, "Penalizing peer for too many already seen transactions");
+        self.network.reputation_change(peer_id, ReputationChangeKind::BadMessage);
+    }
+
+    /// Clear the transaction
+    fn on_good_import(&mut self, hash: TxHash) {
+        self.transactions_by_peers.remove(&hash);
+    }
+
+    /// Penalize the peers that sent the bad transaction
+    fn on_bad_import(&mut self, hash: TxHash) {
+        if let Some(peers) = self.transactions_by_peers.remove(&hash) {
+            for peer_id in peers {
+                self.report_bad_message(peer_id);
+            }
+        }
+    }
+}
+
+/// An endless future.
+///
+/// This should be spawned or used as part of `tokio::select!`.
+impl<Pool> Future for TransactionsManager<Pool>
+where
+    Pool: TransactionPool + Unpin + 'static,
+    <Pool as TransactionPool>::Transaction: IntoRecoveredTransaction,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        // drain network/peer related events
+        while let Poll::Ready(Some(event)) = this.network_events.poll_next_unpin(cx) {
+            this.on_network_event(event);
+        }
+
+        // drain commands
+        while let Poll::Ready(Some(cmd)) = this.command_rx.poll_next_unpin(cx) {
+            this.on_command(cmd);
+        }
+
+        // drain incoming transaction events
+        while let Poll::Ready(Some(event)) = this.transaction_events.poll_next_unpin(cx) {
+            this.on_network_tx_event(event);
+        }
+
+        // Advance all requests.
+        while let Poll::Ready(event) = this.transaction_fetcher.poll(cx, &this.peers) {
+            match event {
+                FetchEvent::TransactionsFetched {
+                    peer_id,
+                    requested,
+                    transactions,
+                    announcement_mismatch,
+                } => {
+                    if announcement_mismatch {
+                        trace!(target: "net::tx", ?peer_id, "Peer sent transactions that don't match their announcement");
+                        this.report_bad_message(peer_id);
+                    }
+                    this.on_pooled_transactions_response(peer_id, requested, transactions);
+                }
+                FetchEvent::FetchError { peer_id, error } => {
+                    trace!(target: "net::tx", ?peer_id, ?error, "Failed to fetch pooled transactions");
+                    this.report_bad_message(peer_id);
+                }
+            }
+        }
+
+        // Advance all imports
+        while let Poll::Ready(Some(import_res)) = this.pool_imports.poll_next_unpin(cx) {
+            match import_res {
+                Ok(hash) => {
+                    this.on_good_import(hash);
+                }
+                Err(err) => {
+                    // if we're syncing and the transaction is bad we ignore it, otherwise we
+                    // penalize the peer that sent the bad transaction with the assumption that the
+                    // peer should have known that this transaction is bad. (e.g. consensus rules)
+                    if err.is_bad_transaction() && !this.network.is_syncing() {
+                        trace!(target: "net::tx", ?err, "Bad transaction import");
+                        this.on_bad_import(*err.hash());
+                        continue
+                    }
+                    this.on_good_import(*err.hash());
+                }
+            }
+        }
+
+        // handle and propagate new transactions
+        let mut new_txs = Vec::new();
+        while let Poll::Ready(Some(hash)) = this.pending_transactions.poll_next_unpin(cx) {
+            new_txs.push(hash);
+        }
+        if !new_txs.is_empty() {
+            this.on_new_transactions(new_txs);
+        }
+
+        // all channels are fully drained and import futures pending
+
+        Poll::Pending
+    }
+}
+
+/// A transaction that's about to be propagated to multiple peers.
+struct PropagateTransaction {
+    tx_type: u8,
+    size: usize,
+    transaction: Arc<TransactionSigned>,
+}
+
+// === impl PropagateTransaction ===
+
+impl PropagateTransaction {
+    fn hash(&self) -> TxHash {
+        self.transaction.hash()
+    }
+
+    fn is_eip4844(&self) -> bool {
+        self.tx_type == EIP4844_TX_TYPE_ID
+    }
+
+    fn new(transaction: Arc<TransactionSigned>) -> Self {
+        Self { tx_type: transaction.tx_type().into(), size: transaction.length(), transaction }
+    }
+}
+
+/// Helper type for constructing the full transaction message that enforces the
+/// `MAX_FULL_TRANSACTIONS_PACKET_SIZE`
+#[derive(Default)]
+struct FullTransactionsBuilder {
+    total_size: usize,
+    transactions: Vec<Arc<TransactionSigned>>,
+}
+
+// === impl FullTransactionsBuilder ===
+
+impl FullTransactionsBuilder {
+    /// Append a transaction to the list if it doesn't exceed the maximum size.
+    ///
+    /// Returns false if the transaction was not added.
+    fn push(&mut self, transaction: &PropagateTransaction) -> bool {
+        let new_size = self.total_size + transaction.size;
+        if new_size > MAX_FULL_TRANSACTIONS_PACKET_SIZE {
+            return false
+        }
+
+        self.total_size = new_size;
+        self.transactions.push(Arc::clone(&transaction.transaction));
+        true
+    }
+
+    /// returns the list of transactions.
+    fn build(self) -> Vec<Arc<TransactionSigned>> {
+        self.transactions
+    }
+}
+
+/// A helper type to create the pooled transactions message based on the negotiated version of the
+/// session with the peer
+enum PooledTransactionsHashesBuilder {
+    Eth66(NewPooledTransactionHashes66),
+    Eth68(NewPooledTransactionHashes68),
+}
+
+// === impl PooledTransactionsHashesBuilder ===
+
+impl PooledTransactionsHashesBuilder {
+    /// Push a transaction from the pool to the list.
+    fn push_pooled<T: PoolTransaction>(&mut self, pooled_tx: Arc<ValidPoolTransaction<T>>) {
+        match self {
+            PooledTransactionsHashesBuilder::Eth66(msg) => msg.0.push(*pooled_tx.hash()),
+            PooledTransactionsHashesBuilder::Eth68(msg) => {
+                msg.hashes.push(*pooled_tx.hash());
+                msg.sizes.push(pooled_tx.encoded_length);
+                msg.types.push(pooled_tx.transaction.tx_type());
+            }
+        }
+    }
+
+    fn push(&mut self, tx: &PropagateTransaction) {
+        match self {
+            PooledTransactionsHashesBuilder::Eth66(msg) => msg.0.push(tx.hash()),
+            PooledTransactionsHashesBuilder::Eth68(msg) => {
+                msg.hashes.push(tx.hash());
+                msg.sizes.push(tx.size);
+                msg.types.push(tx.tx_type);
+            }
+        }
+    }
+
+    /// Create a builder for the negotiated version of the peer's session
+    fn new(version: EthVersion) -> Self {
+        match version {
+            EthVersion::Eth66 | EthVersion::Eth67 => {
+                PooledTransactionsHashesBuilder::Eth66(Default::default())
+            }
+            EthVersion::Eth68 => PooledTransactionsHashesBuilder::Eth68(Default::default()),
+        }
+    }
+
+    fn build(self) -> NewPooledTransactionHashes {
+        match self {
+            PooledTransactionsHashesBuilder::Eth66(msg) => msg.into(),
+            PooledTransactionsHashesBuilder::Eth68(msg) => msg.into(),
+        }
+    }
+}
+
+/// How we received the transactions.
+enum TransactionSource {
+    /// Transactions were broadcast to us via [`Transactions`] message.
+    Broadcast,
+    /// Transactions were sent as the response of a `GetPooledTransactions` request issued by us.
+    Response,
+}
+
+// === impl TransactionSource ===
+
+impl TransactionSource {
+    /// Whether the transaction were sent as broadcast.
+    fn is_broadcast(&self) -> bool {
+        matches!(self, TransactionSource::Broadcast)
+    }
+}
+
+/// Tracks a single peer
+struct Peer {
+    /// Keeps track of transactions that we know the peer has seen.
+    transactions: LruCache<H256>,
+    /// A communication channel directly to the session task.
+    request_tx: PeerRequestSender,
+    /// negotiated version of the session.
+    version: EthVersion,
+    /// The peer's client version.
+    #[allow(unused)]
+    client_version: Arc<String>,
+    /// The kind of the peer when the session was established.
+    kind: PeerKind,
+    /// Start of the current propagation rate limit interval.
+    propagation_interval_start: Instant,
+    /// Number of transactions propagated to the peer in the current interval.
+    num_propagated: usize,
+    /// Number of transactions or hashes the peer sent us more than once.
+    duplicate_receipts: u64,
+    /// Number of transactions the peer sent us that we did not request.
+    unsolicited_receipts: u64,
+}
+
+// === impl Peer ===
+
+impl Peer {
+    fn new(
+        request_tx: PeerRequestSender,
+        version: EthVersion,
+        client_version: Arc<String>,
+        kind: PeerKind,
+    ) -> Self {
+        Self {
+            transactions: LruCache::new(NonZeroUsize::new(PEER_TRANSACTION_CACHE_LIMIT).unwrap()),
+            request_tx,
+            version,
+            client_version,
+            kind,
+            propagation_interval_start: Instant::now(),
+            num_propagated: 0,
+            duplicate_receipts: 0,
+            unsolicited_receipts: 0,
+        }
+    }
+
+    /// Records transactions or hashes the peer sent us more than once.
+    ///
+    /// Returns true if the total number of duplicates crossed another multiple of
+    /// [DUPLICATE_RECEIPTS_THRESHOLD].
+    fn record_duplicate_receipts(&mut self, num: u64) -> bool {
+        let previous = self.duplicate_receipts / DUPLICATE_RECEIPTS_THRESHOLD;
+        self.duplicate_receipts += num;
+        self.duplicate_receipts / DUPLICATE_RECEIPTS_THRESHOLD > previous
+    }
+
+    /// Returns how many more transactions can be propagated to the peer in the current interval.
+    ///
+    /// This is unbounded if there's no limit.
+    fn propagation_budget(&mut self, limit: Option<&PeerPropagationLimit>, now: Instant) -> usize {
+        let Some(limit) = limit else { return usize::MAX };
+        if now.saturating_duration_since(self.propagation_interval_start) >= limit.interval {
+            // start a new interval
+            self.propagation_interval_start = now;
+            self.num_propagated = 0;
+        }
+        limit.max_transactions.saturating_sub(self.num_propagated)
+    }
+
+    /// Records that the given number of transactions were propagated to the peer.
+    fn consume_propagation_budget(&mut self, num: usize) {
+        self.num_propagated = self.num_propagated.saturating_add(num);
+    }
+}
+
+/// Commands to send to the [`TransactionsManager`](crate::transactions::TransactionsManager)
+enum TransactionsCommand {
+    PropagateHash(H256),
+}
+
+/// All events related to transactions emitted by the network.
+#[derive(Debug)]
+#[allow(missing_docs)]
+pub enum NetworkTransactionEvent {
+    /// Received list of transactions from the given peer.
+    IncomingTransactions { peer_id: PeerId, msg: Transactions },
+    /// Received list of transactions hashes to the given peer.
+    IncomingPooledTransactionHashes { peer_id: PeerId, msg: NewPooledTransactionHashes },
+    /// Incoming `GetPooledTransactions` request from a peer.
+    GetPooledTransactions {
+        peer_id: PeerId,
+        request: GetPooledTransactions,
+        response: oneshot::Sender<RequestResult<PooledTransactions>>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::Testnet, NetworkConfigBuilder, NetworkManager};
+    use reth_interfaces::sync::{NetworkSyncUpdater, SyncState};
+    use reth_network_api::NetworkInfo;
+    use reth_primitives::{Transaction, TxEip4844};
+    use reth_provider::test_utils::NoopProvider;
+    use reth_rlp::Decodable;
+    use reth_transaction_pool::test_utils::{testing_pool, MockTransaction, TestPool};
+    use secp256k1::SecretKey;
+
+    async fn transactions_manager(
+        config: TransactionsManagerConfig,
+    ) -> TransactionsManager<TestPool> {
+        let secret_key = SecretKey::new(&mut rand::thread_rng());
+        let config_net = NetworkConfigBuilder::new(secret_key)
+            .disable_discovery()
+            .listener_port(0)
+            .build(NoopProvider::default());
+        let (_, _, transactions, _) = NetworkManager::new(config_net)
+            .await
+            .unwrap()
+            .into_builder()
+            .transactions_with_config(testing_pool(), config)
+            .split_with_handle();
+        transactions
+    }
+
+    fn add_peer(
+        transactions: &mut TransactionsManager<TestPool>,
+        version: EthVersion,
+        kind: PeerKind,
+    ) -> PeerId {
+        let peer_id = PeerId::random();
+        let (tx, _) = mpsc::channel(1);
+        let peer =
+            Peer::new(PeerRequestSender::new(peer_id, tx), version, Default::default(), kind);
+        transactions.peers.insert(peer_id, peer);
+        peer_id
+    }
+
+    fn propagate_transactions(num: usize) -> Vec<PropagateTransaction> {
+        (0..num)
+            .map(|_| {
+                let tx = MockTransaction::eip1559().to_recovered_transaction().into_signed();
+                PropagateTransaction::new(Arc::new(tx))
+            })
+            .collect()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[cfg_attr(not(feature = "geth-tests"), ignore)]
+    async fn test_ignored_tx_broadcasts_while_syncing() {
+        reth_tracing::init_test_tracing();
+
+        let secret_key = SecretKey::new(&mut rand::thread_rng());
+
+        let client = NoopProvider::default();
+        let pool = testing_pool();
+        let config = NetworkConfigBuilder::new(secret_key)
+            .disable_discovery()
+            .listener_port(0)
+            .build(client);
+        let (handle, network, mut transactions, _) = NetworkManager::new(config)
+            .await
+            .unwrap()
+            .into_builder()
+            .transactions(pool.clone())
+            .split_with_handle();
+
+        tokio::task::spawn(network);
+
+        handle.update_sync_state(SyncState::Syncing);
+        assert!(NetworkInfo::is_syncing(&handle));
+
+        let peer_id = PeerId::random();
+
+        transactions.on_network_tx_event(NetworkTransactionEvent::IncomingTransactions {
+            peer_id,
+            msg: Transactions(vec![TransactionSigned::default()]),
+        });
+
+        assert!(pool.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_handle_incoming_transactions() {
+        reth_tracing::init_test_tracing();
+        let net = Testnet::create(3).await;
+
+        let mut handles = net.handles();
+        let handle0 = handles.next().unwrap();
+        let handle1 = handles.next().unwrap();
+
+        drop(handles);
+        let handle = net.spawn();
+
+        let listener0 = handle0.event_listener();
+
+        handle0.add_peer(*handle1.peer_id(), handle1.local_addr());
+        let secret_key = SecretKey::new(&mut rand::thread_rng());
+
+        let client = NoopProvider::default();
+        let pool = testing_pool();
+        let config = NetworkConfigBuilder::new(secret_key)
+            .disable_discovery()
+            .listener_port(0)
+            .build(client);
+        let (network_handle, network, mut transactions, _) = NetworkManager::new(config)
+            .await
+            .unwrap()
+            .into_builder()
+            .transactions(pool.clone())
+            .split_with_handle();
+        tokio::task::spawn(network);
+
+        network_handle.update_sync_state(SyncState::Idle);
+
+        assert!(!NetworkInfo::is_syncing(&network_handle));
+
+        // wait for all initiator connections
+        let mut established = listener0.take(2);
+        while let Some(ev) = established.next().await {
+            match ev {
+                NetworkEvent::SessionEstablished {
+                    peer_id,
+                    remote_addr,
+                    client_version,
+                    capabilities,
+                    messages,
+                    status,
+                    version,
+                    kind,
+                } => {
+                    // to insert a new peer in transactions peerset
+                    transactions.on_network_event(NetworkEvent::SessionEstablished {
+                        peer_id,
+                        remote_addr,
+                        client_version,
+                        capabilities,
+                        messages,
+                        status,
+                        version,
+                        kind,
+                    })
+                }
+                NetworkEvent::PeerAdded(_peer_id) => continue,
+                ev => {
+                    panic!("unexpected event {ev:?}")
+                }
+            }
+        }
+        // random tx: <https://etherscan.io/getRawTx?tx=0x9448608d36e721ef403c53b00546068a6474d6cbab6816c3926de449898e7bce>
+        let input = hex::decode("02f871018302a90f808504890aef60826b6c94ddf4c5025d1a5742cf12f74eec246d4432c295e487e09c3bbcc12b2b80c080a0f21a4eacd0bf8fea9c5105c543be5a1d8c796516875710fafafdf16d16d8ee23a001280915021bb446d1973501a67f93d2b38894a514b976e7b46dc2fe54598d76").unwrap();
+        let signed_tx = TransactionSigned::decode(&mut &input[..]).unwrap();
+        transactions.on_network_tx_event(NetworkTransactionEvent::IncomingTransactions {
+            peer_id: *handle1.peer_id(),
+            msg: Transactions(vec![signed_tx.clone()]),
+        });
+        assert_eq!(
+            *handle1.peer_id(),
+            transactions.transactions_by_peers.get(&signed_tx.hash()).unwrap()[0]
+        );
+        handle.terminate().await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_on_get_pooled_transactions_network() {
+        reth_tracing::init_test_tracing();
+        let net = Testnet::create(2).await;
+
+        let mut handles = net.handles();
+        let handle0 = handles.next().unwrap();
+        let handle1 = handles.next().unwrap();
+
+        drop(handles);
+        let handle = net.spawn();
+
+        let listener0 = handle0.event_listener();
+
+        handle0.add_peer(*handle1.peer_id(), handle1.local_addr());
+        let secret_key = SecretKey::new(&mut rand::thread_rng());
+
+        let client = NoopProvider::default();
+        let pool = testing_pool();
+        let config = NetworkConfigBuilder::new(secret_key)
+            .disable_discovery()
+            .listener_port(0)
+            .build(client);
+        let (network_handle, network, mut transactions, _) = NetworkManager::new(config)
+            .await
+            .unwrap()
+            .into_builder()
+            .transactions(pool.clone())
+            .split_with_handle();
+        tokio::task::spawn(network);
+
+        network_handle.update_sync_state(SyncState::Idle);
+
+        assert!(!NetworkInfo::is_syncing(&network_handle));
+
+        // wait for all initiator connections
+        let mut established = listener0.take(2);
+        while let Some(ev) = established.next().await {
+            match ev {
+                NetworkEvent::SessionEstablished {
+                    peer_id,
+                    remote_addr,
+                    client_version,
+                    capabilities,
+                    messages,
+                    status,
+                    version,
+                    kind,
+                } => transactions.on_network_event(NetworkEvent::SessionEstablished {
+                    peer_id,
+                    remote_addr,
+                    client_version,
+                    capabilities,
+                    messages,
+                    status,
+                    version,
+                    kind,
+                }),
+                NetworkEvent::PeerAdded(_peer_id) => continue,
+                ev => {
+                    panic!("unexpected event {ev:?}")
+                }
+            }
+        }
+        handle.terminate().await;
+
+        let tx = MockTransaction::eip1559();
+        let _ = transactions
+            .pool
+            .add_transaction(reth_transaction_pool::TransactionOrigin::External, tx.clone())
+            .await;
+
+        let request = GetPooledTransactions(vec![tx.get_hash()]);
+
+        let (send, receive) = oneshot::channel::<RequestResult<PooledTransactions>>();
+
+        transactions.on_network_tx_event(NetworkTransactionEvent::GetPooledTransactions {
+            peer_id: *handle1.peer_id(),
+            request,
+            response: send,
+        });
+
+        match receive.await.unwrap() {
+            Ok(PooledTransactions(transactions)) => {
+                assert_eq!(transactions.len(), 1);
+            }
+            Err(e) => {
+                panic!("error: {:?}", e);
+            }
+        }
+
+        // private transactions are never served
+        let private = MockTransaction::eip1559();
+        let origin = reth_transaction_pool::TransactionOrigin::Private { max_block_number: None };
+        transactions.pool.add_transaction(origin, private.clone()).await.unwrap();
+
+        let request = GetPooledTransactions(vec![private.get_hash()]);
+        let (send, receive) = oneshot::channel::<RequestResult<PooledTransactions>>();
+        transactions.on_network_tx_event(NetworkTransactionEvent::GetPooledTransactions {
+            peer_id: *handle1.peer_id(),
+            request,
+            response: send,
+        });
+
+        let PooledTransactions(served) = receive.await.unwrap().unwrap();
+        assert!(served.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_propagate_trusted_only() {
+        let config = TransactionsManagerConfig::default()
+            .with_propagation_mode(TransactionPropagationMode::TrustedOnly);
+        let mut transactions = transactions_manager(config).await;
+        let trusted = add_peer(&mut transactions, EthVersion::Eth68, PeerKind::Trusted);
+        let basic = add_peer(&mut transactions, EthVersion::Eth68, PeerKind::Basic);
+
+        let to_propagate = propagate_transactions(3);
+        let hashes = to_propagate.iter().map(|tx| tx.hash()).collect::<Vec<_>>();
+        let propagated = transactions.propagate_transactions(to_propagate);
+
+        assert_eq!(propagated.0.len(), 3);
+        for hash in hashes {
+            assert_eq!(propagated.0[&hash], vec![PropagateKind::Full(trusted)]);
+            assert!(!transactions.peers[&basic].transactions.contains(&hash));
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_serve_pooled_transactions_trusted_only() {
+        let config = TransactionsManagerConfig::default()
+            .with_propagation_mode(TransactionPropagationMode::TrustedOnly);
+        let mut transactions = transactions_manager(config).await;
+        let trusted = add_peer(&mut transactions, EthVersion::Eth68, PeerKind::Trusted);
+        let basic = add_peer(&mut transactions, EthVersion::Eth68, PeerKind::Basic);
+
+        let tx = MockTransaction::eip1559();
+        let origin = reth_transaction_pool::TransactionOrigin::External;
+        transactions.pool.add_transaction(origin, tx.clone()).await.unwrap();
+
+        for (peer_id, expected) in [(basic, 0), (trusted, 1)] {
+            let request = GetPooledTransactions(vec![tx.get_hash()]);
+            let (send, receive) = oneshot::channel::<RequestResult<PooledTransactions>>();
+            transactions.on_network_tx_event(NetworkTransactionEvent::GetPooledTransactions {
+                peer_id,
+                request,
+                response: send,
+            });
+
+            let PooledTransactions(served) = receive.await.unwrap().unwrap();
+            assert_eq!(served.len(), expected);
+        }
+        assert!(!transactions.peers[&basic].transactions.contains(&tx.get_hash()));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_propagate_eth68_announce_only() {
+        let config = TransactionsManagerConfig::default()
+            .with_eth68_announce_only(true)
+            .with_max_announcement_batch_size(2);
+        let mut transactions = transactions_manager(config).await;
+        let eth68 = add_peer(&mut transactions, EthVersion::Eth68, PeerKind::Basic);
+        let eth67 = add_peer(&mut transactions, EthVersion::Eth67, PeerKind::Basic);
+
+        let to_propagate = propagate_transactions(5);
+        let hashes = to_propagate.iter().map(|tx| tx.hash()).collect::<Vec<_>>();
+        let propagated = transactions.propagate_transactions(to_propagate);
+
+        for hash in hashes {
+            let kinds = &propagated.0[&hash];
+            assert_eq!(kinds.len(), 2);
+            assert!(kinds.contains(&PropagateKind::Hash(eth68)));
+            assert!(kinds.contains(&PropagateKind::Full(eth67)));
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_propagate_blob_transactions_as_hashes() {
+        let mut transactions = transactions_manager(Default::default()).await;
+        let peer_id = add_peer(&mut transactions, EthVersion::Eth68, PeerKind::Basic);
+
+        let mut to_propagate = propagate_transactions(2);
+        let blobs = (0..2)
+            .map(|nonce| {
+                let tx = Transaction::Eip4844(TxEip4844 { nonce, ..Default::default() });
+                let tx = TransactionSigned::from_transaction_and_signature(tx, Default::default());
+                PropagateTransaction::new(Arc::new(tx))
+            })
+            .collect::<Vec<_>>();
+        let blob_hashes = blobs.iter().map(|tx| tx.hash()).collect::<Vec<_>>();
+        let full_hashes = to_propagate.iter().map(|tx| tx.hash()).collect::<Vec<_>>();
+        to_propagate.extend(blobs);
+
+        let propagated = transactions.propagate_transactions(to_propagate);
+        for hash in full_hashes {
+            assert_eq!(propagated.0[&hash], vec![PropagateKind::Full(peer_id)]);
+        }
+        for hash in blob_hashes {
+            assert_eq!(propagated.0[&hash], vec![PropagateKind::Hash(peer_id)]);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_propagate_peer_rate_limit() {
+        let limit =
+            PeerPropagationLimit { max_transactions: 3, interval: Duration::from_secs(3600) };
+        let config = TransactionsManagerConfig::default().with_peer_rate_limit(limit);
+        let mut transactions = transactions_manager(config).await;
+        let peer_id = add_peer(&mut transactions, EthVersion::Eth68, PeerKind::Basic);
+
+        let to_propagate = propagate_transactions(5);
+        let hashes = to_propagate.iter().map(|tx| tx.hash()).collect::<Vec<_>>();
+        let propagated = transactions.propagate_transactions(to_propagate);
+        assert_eq!(propagated.0.len(), 3);
+
+        // transactions that exceeded the limit are not marked as known by the peer
+        let peer = &transactions.peers[&peer_id];
+        assert_eq!(hashes.iter().filter(|hash| peer.transactions.contains(*hash)).count(), 3);
+
+        // the budget is exhausted for the current interval
+        let propagated = transactions.propagate_transactions(propagate_transactions(2));
+        assert!(propagated.0.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_unsolicited_pooled_transactions() {
+        let mut transactions = transactions_manager(Default::default()).await;
+        let peer_id = add_peer(&mut transactions, EthVersion::Eth68, PeerKind::Basic);
+
+        // random tx: <https://etherscan.io/getRawTx?tx=0x9448608d36e721ef403c53b00546068a6474d6cbab6816c3926de449898e7bce>
+        let input = hex::decode("02f871018302a90f808504890aef60826b6c94ddf4c5025d1a5742cf12f74eec246d4432c295e487e09c3bbcc12b2b80c080a0f21a4eacd0bf8fea9c5105c543be5a1d8c796516875710fafafdf16d16d8ee23a001280915021bb446d1973501a67f93d2b38894a514b976e7b46dc2fe54598d76").unwrap();
+        let signed_tx = TransactionSigned::decode(&mut &input[..]).unwrap();
+        let unsolicited = MockTransaction::eip1559().to_recovered_transaction().into_signed();
+
+        let requested = HashSet::from([signed_tx.hash()]);
+        let response =
+            vec![signed_tx.clone().into(), unsolicited.clone().into(), signed_tx.clone().into()];
+        transactions.on_pooled_transactions_response(peer_id, requested, response);
+
+        // the requested transaction is imported once, everything else is dropped
+        assert_eq!(transactions.peers[&peer_id].unsolicited_receipts, 2);
+        assert_eq!(transactions.transactions_by_peers[&signed_tx.hash()], vec![peer_id]);
+        assert!(!transactions.transactions_by_peers.contains_key(&unsolicited.hash()));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_duplicate_receipts_threshold() {
+        let mut transactions = transactions_manager(Default::default()).await;
+        let peer_id = add_peer(&mut transactions, EthVersion::Eth66, PeerKind::Basic);
+        let peer = transactions.peers.get_mut(&peer_id).unwrap();
+
+        assert!(!peer.record_duplicate_receipts(DUPLICATE_RECEIPTS_THRESHOLD - 1));
+        assert!(peer.record_duplicate_receipts(1));
+        assert!(!peer.record_duplicate_receipts(DUPLICATE_RECEIPTS_THRESHOLD - 1));
+        assert!(peer.record_duplicate_receipts(2 * DUPLICATE_RECEIPTS_THRESHOLD));
+        assert_eq!(peer.duplicate_receipts, 4 * DUPLICATE_RECEIPTS_THRESHOLD - 1);
+    }
+}
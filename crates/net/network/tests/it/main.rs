@@ -2,5 +2,6 @@ mod connect;
 mod requests;
 mod session;
 mod startup;
+mod txgossip;
 
 fn main() {}
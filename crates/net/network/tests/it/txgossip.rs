@@ -0,0 +1,111 @@
+//! Transaction gossip tests
+
+use reth_eth_wire::{GetPooledTransactions, NewPooledTransactionHashes68, PooledTransactions};
+use reth_interfaces::p2p::error::RequestResult;
+use reth_network::{
+    test_utils::NetworkEventStream, transactions::NetworkTransactionEvent, NetworkConfigBuilder,
+    NetworkHandle, NetworkManager,
+};
+use reth_network_api::{NetworkInfo, Peers};
+use reth_provider::test_utils::NoopProvider;
+use reth_rlp::{Decodable, Encodable};
+use reth_transaction_pool::{test_utils::testing_pool, TransactionPool};
+use secp256k1::SecretKey;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+/// Launches a node without a transactions manager, its transaction events are returned instead.
+async fn launch_announcer() -> (NetworkHandle, mpsc::UnboundedReceiver<NetworkTransactionEvent>) {
+    let secret_key = SecretKey::new(&mut rand::thread_rng());
+    let config = NetworkConfigBuilder::new(secret_key)
+        .disable_discovery()
+        .listener_port(0)
+        .build(NoopProvider::default());
+    let mut network = NetworkManager::new(config).await.unwrap();
+    let (tx, rx) = mpsc::unbounded_channel();
+    network.set_transactions(tx);
+    let handle = network.handle().clone();
+    tokio::task::spawn(network);
+    (handle, rx)
+}
+
+/// Waits for the next `GetPooledTransactions` request.
+async fn next_pooled_transactions_request(
+    events: &mut mpsc::UnboundedReceiver<NetworkTransactionEvent>,
+) -> (GetPooledTransactions, oneshot::Sender<RequestResult<PooledTransactions>>) {
+    loop {
+        let event = tokio::time::timeout(Duration::from_secs(10), events.recv())
+            .await
+            .expect("no request received")
+            .unwrap();
+        if let NetworkTransactionEvent::GetPooledTransactions { request, response, .. } = event {
+            return (request, response)
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_from_alternative_announcer() {
+    reth_tracing::init_test_tracing();
+
+    let secret_key = SecretKey::new(&mut rand::thread_rng());
+    let config = NetworkConfigBuilder::new(secret_key)
+        .disable_discovery()
+        .listener_port(0)
+        .build(NoopProvider::default());
+    let pool = testing_pool();
+    let (handle, network, transactions, _) = NetworkManager::new(config)
+        .await
+        .unwrap()
+        .into_builder()
+        .transactions(pool.clone())
+        .split_with_handle();
+    tokio::task::spawn(network);
+    tokio::task::spawn(transactions);
+
+    let (announcer0, mut from_announcer0) = launch_announcer().await;
+    let (announcer1, mut from_announcer1) = launch_announcer().await;
+    let mut announcer0_events = NetworkEventStream::new(announcer0.event_listener());
+    let mut announcer1_events = NetworkEventStream::new(announcer1.event_listener());
+
+    handle.add_peer(*announcer0.peer_id(), announcer0.local_addr());
+    handle.add_peer(*announcer1.peer_id(), announcer1.local_addr());
+    assert_eq!(announcer0_events.next_session_established().await.unwrap(), *handle.peer_id());
+    assert_eq!(announcer1_events.next_session_established().await.unwrap(), *handle.peer_id());
+
+    // random tx: <https://etherscan.io/getRawTx?tx=0x9448608d36e721ef403c53b00546068a6474d6cbab6816c3926de449898e7bce>
+    let input = hex::decode("02f871018302a90f808504890aef60826b6c94ddf4c5025d1a5742cf12f74eec246d4432c295e487e09c3bbcc12b2b80c080a0f21a4eacd0bf8fea9c5105c543be5a1d8c796516875710fafafdf16d16d8ee23a001280915021bb446d1973501a67f93d2b38894a514b976e7b46dc2fe54598d76").unwrap();
+    let signed_tx = reth_primitives::TransactionSigned::decode(&mut &input[..]).unwrap();
+    let announcement = NewPooledTransactionHashes68 {
+        types: vec![signed_tx.tx_type().into()],
+        sizes: vec![signed_tx.length()],
+        hashes: vec![signed_tx.hash()],
+    };
+
+    announcer0.send_transactions_hashes(*handle.peer_id(), announcement.clone().into());
+    let (request, response0) = next_pooled_transactions_request(&mut from_announcer0).await;
+    assert_eq!(request.0, vec![signed_tx.hash()]);
+
+    // the hash is already in flight, so it's not requested from the second announcer
+    announcer1.send_transactions_hashes(*handle.peer_id(), announcement.into());
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(from_announcer1.try_recv().is_err());
+
+    // the first announcer fails to deliver the transaction
+    response0.send(Ok(PooledTransactions(vec![]))).unwrap();
+
+    // which is then requested from the second announcer
+    let mut new_transactions = pool.transactions_listener();
+    let (request, response1) = next_pooled_transactions_request(&mut from_announcer1).await;
+    assert_eq!(request.0, vec![signed_tx.hash()]);
+    response1.send(Ok(PooledTransactions(vec![signed_tx.clone().into()]))).unwrap();
+
+    let imported = tokio::time::timeout(Duration::from_secs(10), new_transactions.recv())
+        .await
+        .expect("transaction not imported")
+        .unwrap();
+    assert_eq!(*imported.transaction.hash(), signed_tx.hash());
+
+    // nothing else was requested from the first announcer
+    assert!(from_announcer0.try_recv().is_err());
+}
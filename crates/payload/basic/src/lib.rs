@@ -11,33 +11,40 @@ use crate::metrics::PayloadBuilderMetrics;
 use futures_core::ready;
 use futures_util::FutureExt;
 use reth_payload_builder::{
-    error::PayloadBuilderError, BuiltPayload, KeepPayloadJobAlive, PayloadBuilderAttributes,
-    PayloadJob, PayloadJobGenerator,
+    error::PayloadBuilderError, BuiltPayload, Bundle, BundleStore, KeepPayloadJobAlive,
+    PayloadBuilderAttributes, PayloadJob, PayloadJobGenerator,
 };
 use reth_primitives::{
     bytes::{Bytes, BytesMut},
     constants::{
-        BEACON_NONCE, EMPTY_RECEIPTS, EMPTY_TRANSACTIONS, EMPTY_WITHDRAWALS, RETH_CLIENT_VERSION,
-        SLOT_DURATION,
+        BEACON_NONCE, BLOB_TX_MIN_BLOB_GASPRICE, EMPTY_RECEIPTS, EMPTY_TRANSACTIONS,
+        EMPTY_WITHDRAWALS, MAX_DATA_GAS_PER_BLOCK, RETH_CLIENT_VERSION, SLOT_DURATION,
     },
-    proofs, Block, BlockNumberOrTag, ChainSpec, Header, IntoRecoveredTransaction, Receipt,
-    SealedBlock, Withdrawal, EMPTY_OMMER_ROOT, H256, U256,
+    proofs, Block, BlockNumberOrTag, ChainSpec, Head, Header, IntoRecoveredTransaction, Receipt,
+    SealedBlock, TransactionSigned, Withdrawal, EMPTY_OMMER_ROOT, H160, H256, U256,
+};
+use reth_provider::{
+    BlockProviderIdExt, BlockSource, PostState, StateProviderBox, StateProviderFactory,
 };
-use reth_provider::{BlockProviderIdExt, BlockSource, PostState, StateProviderFactory};
 use reth_revm::{
     database::{State, SubState},
     env::tx_env_with_recovered,
     executor::{
-        commit_state_changes, increment_account_balance, post_block_withdrawals_balance_increments,
+        commit_state_changes, decrement_account_balance, increment_account_balance,
+        post_block_withdrawals_balance_increments,
     },
+    extension::{EvmExtension, ExtensionStack},
     into_reth_log,
 };
 use reth_rlp::Encodable;
 use reth_tasks::TaskSpawner;
-use reth_transaction_pool::TransactionPool;
+use reth_transaction_pool::{PoolTransaction, TransactionPool};
 use revm::{
     db::{CacheDB, DatabaseRef},
-    primitives::{BlockEnv, CfgEnv, EVMError, Env, InvalidTransaction, ResultAndState},
+    primitives::{
+        AccountInfo, BlockEnv, Bytecode, CfgEnv, EVMError, Env, InvalidTransaction, ResultAndState,
+        State as EvmState,
+    },
 };
 use std::{
     future::Future,
@@ -68,6 +75,10 @@ pub struct BasicPayloadJobGenerator<Client, Pool, Tasks> {
     payload_task_guard: PayloadTaskGuard,
     /// The chain spec.
     chain_spec: Arc<ChainSpec>,
+    /// The extension applied to all transactions of built payloads.
+    evm_extension: Option<Arc<dyn EvmExtension>>,
+    /// The bundles that are included at the top of built payloads.
+    bundle_store: Option<BundleStore>,
 }
 
 // === impl BasicPayloadJobGenerator ===
@@ -88,8 +99,22 @@ impl<Client, Pool, Tasks> BasicPayloadJobGenerator<Client, Pool, Tasks> {
             payload_task_guard: PayloadTaskGuard::new(config.max_payload_tasks),
             config,
             chain_spec,
+            evm_extension: None,
+            bundle_store: None,
         }
     }
+
+    /// Applies the given [EvmExtension] to all transactions of built payloads.
+    pub fn with_evm_extension(mut self, extension: Arc<dyn EvmExtension>) -> Self {
+        self.evm_extension = Some(extension);
+        self
+    }
+
+    /// Includes the profitable bundles of the given [BundleStore] at the top of built payloads.
+    pub fn with_bundle_store(mut self, bundle_store: BundleStore) -> Self {
+        self.bundle_store = Some(bundle_store);
+        self
+    }
 }
 
 // === impl BasicPayloadJobGenerator ===
@@ -125,9 +150,23 @@ where
         };
 
         // configure evm env based on parent block
-        let (initialized_cfg, initialized_block_env) =
+        let (mut initialized_cfg, initialized_block_env) =
             attributes.cfg_and_block_env(&self.chain_spec, &parent_block);
 
+        if let Some(extension) = &self.evm_extension {
+            let head = Head {
+                number: parent_block.number + 1,
+                timestamp: attributes.timestamp,
+                ..Default::default()
+            };
+            initialized_cfg.spec_id = extension.spec_id(&head, initialized_cfg.spec_id);
+        }
+
+        if let Some(bundle_store) = &self.bundle_store {
+            // bundles that target blocks before this payload can't be included anymore
+            bundle_store.remove_bundles_before(parent_block.number + 1);
+        }
+
         let config = PayloadConfig {
             initialized_block_env,
             initialized_cfg,
@@ -135,6 +174,8 @@ where
             extra_data: self.config.extradata.clone(),
             attributes,
             chain_spec: Arc::clone(&self.chain_spec),
+            evm_extension: self.evm_extension.clone(),
+            bundle_store: self.bundle_store.clone(),
         };
 
         // create empty
@@ -493,6 +534,10 @@ struct PayloadConfig {
     attributes: PayloadBuilderAttributes,
     /// The chain spec.
     chain_spec: Arc<ChainSpec>,
+    /// The extension applied to all transactions.
+    evm_extension: Option<Arc<dyn EvmExtension>>,
+    /// The bundles to include at the top of the payload.
+    bundle_store: Option<BundleStore>,
 }
 
 #[derive(Debug)]
@@ -536,6 +581,8 @@ fn build_payload<Pool, Client>(
             extra_data,
             attributes,
             chain_spec,
+            evm_extension,
+            bundle_store,
         } = config;
 
         debug!(parent_hash=?parent_block.hash, parent_number=parent_block.number, "building new payload");
@@ -543,17 +590,35 @@ fn build_payload<Pool, Client>(
         let state = client.state_by_block_hash(parent_block.hash)?;
         let mut db = SubState::new(State::new(state));
         let mut post_state = PostState::default();
+        let mut extension = evm_extension.map(ExtensionStack::new);
 
-        let mut cumulative_gas_used = 0;
         let block_gas_limit: u64 = initialized_block_env.gas_limit.try_into().unwrap_or(u64::MAX);
+        let block_number = initialized_block_env.number.to::<u64>();
+
+        // bundles are executed at the top of the block, before any transactions of the pool
+        let bundles = bundle_store
+            .map(|store| store.bundles_for(block_number, attributes.timestamp))
+            .unwrap_or_default();
+        let BundlesOutcome { mut executed_txs, mut cumulative_gas_used, mut total_fees } =
+            commit_bundles(
+                &mut db,
+                &mut post_state,
+                &mut extension,
+                &initialized_cfg,
+                &initialized_block_env,
+                block_gas_limit,
+                bundles,
+            )?;
 
-        let mut executed_txs = Vec::new();
         let mut best_txs = pool.best_transactions();
 
-        let mut total_fees = U256::ZERO;
-        let base_fee = initialized_block_env.basefee.to::<u64>();
+        let blob_gasprice = chain_spec
+            .is_cancun_activated_at_timestamp(attributes.timestamp)
+            .then(|| parent_block.next_block_blob_fee().unwrap_or(BLOB_TX_MIN_BLOB_GASPRICE));
+        let mut cumulative_blob_gas_used = 0;
+        let mut sidecars = Vec::new();
 
-        let block_number = initialized_block_env.number.to::<u64>();
+        let base_fee = initialized_block_env.basefee.to::<u64>();
 
         while let Some(pool_tx) = best_txs.next() {
             // ensure we still have capacity for this transaction
@@ -573,6 +638,55 @@ fn build_payload<Pool, Client>(
             // convert tx to a signed transaction
             let tx = pool_tx.to_recovered_transaction();
 
+            // blob transactions can only be included post cancun, if they fit into the block's
+            // blob gas allowance, and if their sidecar is available to be served with the payload
+            let mut blob_fee = None;
+            if tx.is_eip4844() {
+                let Some(blob_gasprice) = blob_gasprice else {
+                    best_txs.mark_invalid(&pool_tx);
+                    continue
+                };
+                if cumulative_blob_gas_used + tx.blob_gas() > MAX_DATA_GAS_PER_BLOCK ||
+                    tx.max_fee_per_blob_gas().unwrap_or_default() < blob_gasprice
+                {
+                    // the transaction may fit into a later block
+                    best_txs.mark_invalid(&pool_tx);
+                    continue
+                }
+                let Ok(Some(sidecar)) = pool.get_blob(tx.hash()) else {
+                    trace!(?tx, "skipping blob transaction without sidecar");
+                    best_txs.mark_invalid(&pool_tx);
+                    continue
+                };
+
+                // the sender must be able to afford the transaction at its max fees, like the
+                // executor requires
+                let blob_gas = U256::from(tx.blob_gas());
+                let max_cost = U256::from(tx.gas_limit()) * U256::from(tx.max_fee_per_gas()) +
+                    U256::from(tx.value()) +
+                    blob_gas * U256::from(tx.max_fee_per_blob_gas().unwrap_or_default());
+                if db.load_account(tx.signer())?.info.balance < max_cost {
+                    best_txs.mark_invalid(&pool_tx);
+                    continue
+                }
+
+                // revm is not aware of blob gas, so the blob fee is charged (and burned) before the
+                // transaction is executed, like the executor does
+                let fee = blob_gas * U256::from(blob_gasprice);
+                let charged = decrement_account_balance(
+                    &mut db,
+                    &mut post_state,
+                    block_number,
+                    tx.signer(),
+                    fee,
+                )?;
+                if !charged {
+                    best_txs.mark_invalid(&pool_tx);
+                    continue
+                }
+                blob_fee = Some((fee, sidecar));
+            }
+
             // Configure the environment for the block.
             let env = Env {
                 cfg: initialized_cfg.clone(),
@@ -583,9 +697,23 @@ fn build_payload<Pool, Client>(
             let mut evm = revm::EVM::with_env(env);
             evm.database(&mut db);
 
-            let ResultAndState { result, state } = match evm.transact() {
+            let res = match extension.as_mut() {
+                Some(extension) => evm.inspect(extension),
+                None => evm.transact(),
+            };
+            let ResultAndState { result, state } = match res {
                 Ok(res) => res,
                 Err(err) => {
+                    if let Some((fee, _)) = blob_fee {
+                        // refund the blob fee of the transaction that won't be included
+                        increment_account_balance(
+                            &mut db,
+                            &mut post_state,
+                            block_number,
+                            tx.signer(),
+                            fee,
+                        )?;
+                    }
                     match err {
                         EVMError::Transaction(err) => {
                             if matches!(err, InvalidTransaction::NonceTooLow { .. }) {
@@ -636,6 +764,11 @@ fn build_payload<Pool, Client>(
                 .expect("fee is always valid; execution succeeded");
             total_fees += U256::from(miner_fee) * U256::from(gas_used);
 
+            if let Some((_, sidecar)) = blob_fee {
+                cumulative_blob_gas_used += tx.blob_gas();
+                sidecars.push(sidecar);
+            }
+
             // append transaction to the list of executed transactions
             executed_txs.push(tx.into_signed());
         }
@@ -664,6 +797,13 @@ fn build_payload<Pool, Client>(
         // create the block header
         let transactions_root = proofs::calculate_transaction_root(&executed_txs);
 
+        let (blob_gas_used, excess_blob_gas) = blob_gas_fields(
+            &chain_spec,
+            &parent_block,
+            attributes.timestamp,
+            cumulative_blob_gas_used,
+        );
+
         let header = Header {
             parent_hash: parent_block.hash,
             ommers_hash: EMPTY_OMMER_ROOT,
@@ -682,17 +822,215 @@ fn build_payload<Pool, Client>(
             difficulty: U256::ZERO,
             gas_used: cumulative_gas_used,
             extra_data: extra_data.into(),
+            blob_gas_used,
+            excess_blob_gas,
+            parent_beacon_block_root: parent_beacon_block_root(&chain_spec, &attributes),
         };
 
         // seal the block
         let block = Block { header, body: executed_txs, ommers: vec![], withdrawals };
 
         let sealed_block = block.seal_slow();
-        Ok(BuildOutcome::Better(BuiltPayload::new(attributes.id, sealed_block, total_fees)))
+        Ok(BuildOutcome::Better(
+            BuiltPayload::new(attributes.id, sealed_block, total_fees).with_sidecars(sidecars),
+        ))
     }
     let _ = to_job.send(try_build(client, pool, config, cancel, best_payload));
 }
 
+/// The transactions of all bundles that were committed to the pending state.
+#[derive(Default)]
+struct BundlesOutcome {
+    /// The executed transactions of all committed bundles, in order.
+    executed_txs: Vec<TransactionSigned>,
+    /// The gas used by all committed bundles.
+    cumulative_gas_used: u64,
+    /// The total payment of all committed bundles to the block's beneficiary.
+    total_fees: U256,
+}
+
+/// A bundle that was simulated on top of the pending state.
+struct ExecutedBundle {
+    /// The executed transactions of the bundle.
+    executed_txs: Vec<TransactionSigned>,
+    /// The state changes and receipts of the executed transactions, in order.
+    changes: Vec<(EvmState, Receipt)>,
+    /// The cumulative gas used of the block after the bundle.
+    cumulative_gas_used: u64,
+    /// The payment of the bundle to the block's beneficiary.
+    profit: U256,
+}
+
+/// A read-only view of the pending state that bundles are simulated on top of.
+struct PendingState<'a, DB>(&'a CacheDB<DB>);
+
+impl<DB: DatabaseRef> DatabaseRef for PendingState<'_, DB> {
+    type Error = DB::Error;
+
+    fn basic(&self, address: H160) -> Result<Option<AccountInfo>, Self::Error> {
+        DatabaseRef::basic(self.0, address)
+    }
+
+    fn code_by_hash(&self, code_hash: H256) -> Result<Bytecode, Self::Error> {
+        DatabaseRef::code_by_hash(self.0, code_hash)
+    }
+
+    fn storage(&self, address: H160, index: U256) -> Result<U256, Self::Error> {
+        DatabaseRef::storage(self.0, address, index)
+    }
+
+    fn block_hash(&self, number: U256) -> Result<H256, Self::Error> {
+        DatabaseRef::block_hash(self.0, number)
+    }
+}
+
+/// Simulates the given bundles in order on top of the pending state and commits every bundle
+/// that doesn't revert and pays the block's beneficiary.
+///
+/// Each bundle is simulated on a separate cache layered over the pending state, so a rejected
+/// bundle leaves no trace in the pending state.
+fn commit_bundles(
+    db: &mut SubState<StateProviderBox<'_>>,
+    post_state: &mut PostState,
+    extension: &mut Option<ExtensionStack>,
+    initialized_cfg: &CfgEnv,
+    initialized_block_env: &BlockEnv,
+    block_gas_limit: u64,
+    bundles: Vec<Bundle>,
+) -> Result<BundlesOutcome, PayloadBuilderError> {
+    let block_number = initialized_block_env.number.to::<u64>();
+    let mut outcome = BundlesOutcome::default();
+    for bundle in bundles {
+        let simulated = simulate_bundle(
+            db,
+            extension,
+            initialized_cfg,
+            initialized_block_env,
+            block_gas_limit,
+            outcome.cumulative_gas_used,
+            &bundle,
+        )?;
+        let Some(ExecutedBundle { executed_txs, changes, cumulative_gas_used, profit }) = simulated
+        else {
+            trace!(bundle=?bundle.hash, "skipping reverted or unprofitable bundle");
+            continue
+        };
+
+        // merge the changes of the bundle into the pending state
+        for (state, receipt) in changes {
+            // the touched accounts were only loaded into the simulation layer, they must be loaded
+            // into the pending state so their changes are recorded against their previous values
+            for address in state.keys() {
+                db.load_account(*address)?;
+            }
+            commit_state_changes(db, post_state, block_number, state, true);
+            post_state.add_receipt(block_number, receipt);
+        }
+
+        trace!(bundle=?bundle.hash, ?profit, "committed bundle");
+        outcome.executed_txs.extend(executed_txs);
+        outcome.cumulative_gas_used = cumulative_gas_used;
+        outcome.total_fees += profit;
+    }
+    Ok(outcome)
+}
+
+/// Simulates the transactions of the bundle on top of the pending state, without modifying it.
+///
+/// Returns `None` if the bundle must be rejected: if an atomic bundle has a transaction that's
+/// invalid or reverts without being allowed to, or if the bundle doesn't pay the block's
+/// beneficiary.
+#[allow(clippy::too_many_arguments)]
+fn simulate_bundle(
+    db: &SubState<StateProviderBox<'_>>,
+    extension: &mut Option<ExtensionStack>,
+    initialized_cfg: &CfgEnv,
+    initialized_block_env: &BlockEnv,
+    block_gas_limit: u64,
+    mut cumulative_gas_used: u64,
+    bundle: &Bundle,
+) -> Result<Option<ExecutedBundle>, PayloadBuilderError> {
+    let block_number = initialized_block_env.number.to::<u64>();
+    let coinbase = initialized_block_env.coinbase;
+    let balance_before = DatabaseRef::basic(db, coinbase)?.unwrap_or_default().balance;
+
+    // the simulation only writes to this layer, the changes it records are discarded
+    let mut sim = CacheDB::new(PendingState(db));
+    let mut sim_post_state = PostState::default();
+
+    let mut executed_txs = Vec::with_capacity(bundle.transactions.len());
+    let mut changes = Vec::with_capacity(bundle.transactions.len());
+    for tx in bundle.transactions.iter() {
+        // blob transactions are rejected when the bundle is submitted, since their sidecar is not
+        // available
+        if cumulative_gas_used + tx.gas_limit() > block_gas_limit || tx.is_eip4844() {
+            if bundle.atomic {
+                return Ok(None)
+            }
+            continue
+        }
+
+        let env = Env {
+            cfg: initialized_cfg.clone(),
+            block: initialized_block_env.clone(),
+            tx: tx_env_with_recovered(tx),
+        };
+
+        let mut evm = revm::EVM::with_env(env);
+        evm.database(&mut sim);
+
+        let res = match extension.as_mut() {
+            Some(extension) => evm.inspect(extension),
+            None => evm.transact(),
+        };
+        let ResultAndState { result, state } = match res {
+            Ok(res) => res,
+            Err(EVMError::Transaction(err)) => {
+                trace!(?err, ?tx, bundle=?bundle.hash, "invalid bundle transaction");
+                if bundle.atomic {
+                    return Ok(None)
+                }
+                continue
+            }
+            Err(err) => return Err(PayloadBuilderError::EvmExecutionError(err)),
+        };
+
+        if !result.is_success() && !bundle.is_revert_allowed(&tx.hash()) {
+            trace!(?tx, bundle=?bundle.hash, "reverted bundle transaction");
+            if bundle.atomic {
+                return Ok(None)
+            }
+            continue
+        }
+
+        commit_state_changes(&mut sim, &mut sim_post_state, block_number, state.clone(), true);
+
+        cumulative_gas_used += result.gas_used();
+
+        let receipt = Receipt {
+            tx_type: tx.tx_type(),
+            success: result.is_success(),
+            cumulative_gas_used,
+            logs: result.logs().into_iter().map(into_reth_log).collect(),
+        };
+        changes.push((state, receipt));
+        executed_txs.push(tx.clone().into_signed());
+    }
+
+    // the bundle must pay the beneficiary, either via priority fees or a direct transfer
+    let balance_after = DatabaseRef::basic(&sim, coinbase)?.unwrap_or_default().balance;
+    if executed_txs.is_empty() || balance_after <= balance_before {
+        return Ok(None)
+    }
+
+    Ok(Some(ExecutedBundle {
+        executed_txs,
+        changes,
+        cumulative_gas_used,
+        profit: balance_after - balance_before,
+    }))
+}
+
 /// Builds an empty payload without any transactions.
 fn build_empty_payload<Client>(
     client: &Client,
@@ -732,6 +1070,9 @@ where
     // calculate the state root
     let state_root = db.db.0.state_root(post_state)?;
 
+    let (blob_gas_used, excess_blob_gas) =
+        blob_gas_fields(&chain_spec, &parent_block, attributes.timestamp, 0);
+
     let header = Header {
         parent_hash: parent_block.hash,
         ommers_hash: EMPTY_OMMER_ROOT,
@@ -750,6 +1091,9 @@ where
         difficulty: U256::ZERO,
         gas_used: 0,
         extra_data: extra_data.into(),
+        blob_gas_used,
+        excess_blob_gas,
+        parent_beacon_block_root: parent_beacon_block_root(&chain_spec, &attributes),
     };
 
     let block = Block { header, body: vec![], ommers: vec![], withdrawals };
@@ -758,6 +1102,34 @@ where
     Ok(BuiltPayload::new(attributes.id, sealed_block, U256::ZERO))
 }
 
+/// Returns the `blob_gas_used` and `excess_blob_gas` header fields of a new block on top of the
+/// given parent.
+///
+/// These are `None` values pre cancun.
+fn blob_gas_fields(
+    chain_spec: &ChainSpec,
+    parent_block: &SealedBlock,
+    timestamp: u64,
+    blob_gas_used: u64,
+) -> (Option<u64>, Option<u64>) {
+    if !chain_spec.is_cancun_activated_at_timestamp(timestamp) {
+        return (None, None)
+    }
+    (Some(blob_gas_used), Some(parent_block.next_block_excess_blob_gas().unwrap_or_default()))
+}
+
+/// Returns the `parent_beacon_block_root` header field of a new block with the given attributes.
+///
+/// This is `None` pre cancun.
+fn parent_beacon_block_root(
+    chain_spec: &ChainSpec,
+    attributes: &PayloadBuilderAttributes,
+) -> Option<H256> {
+    chain_spec
+        .is_cancun_activated_at_timestamp(attributes.timestamp)
+        .then(|| attributes.parent_beacon_block_root.unwrap_or_default())
+}
+
 /// Represents the outcome of committing withdrawals to the runtime database and post state.
 /// Pre-shanghai these are `None` values.
 struct WithdrawalsOutcome {
@@ -828,3 +1200,66 @@ fn is_better_payload(best_payload: Option<&BuiltPayload>, new_fees: U256) -> boo
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{sign_message, Account, Transaction, TransactionKind, TxEip1559};
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use revm::primitives::SpecId;
+
+    #[test]
+    fn commit_selfdestructing_bundle() {
+        let coinbase = H160::from_low_u64_be(0xc0);
+        let contract = H160::from_low_u64_be(0xdead);
+        let balance = U256::from(10u64.pow(18));
+
+        let tx = Transaction::Eip1559(TxEip1559 {
+            chain_id: 1,
+            nonce: 0,
+            gas_limit: 100_000,
+            max_fee_per_gas: 1,
+            max_priority_fee_per_gas: 1,
+            to: TransactionKind::Call(contract),
+            ..Default::default()
+        });
+        let signature = sign_message(H256::from_low_u64_be(1), tx.signature_hash()).unwrap();
+        let tx = TransactionSigned::from_transaction_and_signature(tx, signature)
+            .into_ecrecovered()
+            .unwrap();
+        let sender = tx.signer();
+
+        // the contract sends its balance to the beneficiary: COINBASE SELFDESTRUCT
+        let provider = MockEthProvider::default();
+        provider.add_account(sender, ExtendedAccount::new(0, balance));
+        provider.add_account(
+            contract,
+            ExtendedAccount::new(0, balance).with_bytecode(vec![0x41, 0xff].into()),
+        );
+        let mut db = SubState::new(State::new(Box::new(provider) as StateProviderBox<'_>));
+        let mut post_state = PostState::default();
+
+        let cfg = CfgEnv { spec_id: SpecId::SHANGHAI, ..Default::default() };
+        let block_env = BlockEnv { number: U256::from(1), coinbase, ..Default::default() };
+
+        let outcome = commit_bundles(
+            &mut db,
+            &mut post_state,
+            &mut None,
+            &cfg,
+            &block_env,
+            30_000_000,
+            vec![Bundle::new(vec![tx], 1)],
+        )
+        .unwrap();
+        assert_eq!(outcome.executed_txs.len(), 1);
+        assert_eq!(post_state.receipts(1).len(), 1);
+
+        // the contract is destroyed and the sender's change is recorded against its previous state
+        assert_eq!(post_state.accounts()[&contract], None);
+        let changes = &post_state.account_changes().inner[&1];
+        assert_eq!(changes[&sender], Some(Account { nonce: 0, balance, bytecode_hash: None }));
+        assert!(changes[&contract].is_some());
+        assert_eq!(changes[&coinbase], None);
+    }
+}
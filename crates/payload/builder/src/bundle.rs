@@ -0,0 +1,337 @@
+//! Bundles of transactions that are included at the top of a payload.
+
+use crate::error::BundleError;
+use parking_lot::RwLock;
+use reth_primitives::{keccak256, TransactionSignedEcRecovered, H256};
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Arc,
+};
+
+/// The default maximum number of bundles the [BundleStore] keeps per block.
+pub const DEFAULT_MAX_BUNDLES_PER_BLOCK: usize = 1024;
+
+/// The default maximum number of bundles the [BundleStore] keeps in total.
+pub const DEFAULT_MAX_BUNDLES: usize = 4096;
+
+/// The default number of blocks past the current head that bundles can target.
+pub const DEFAULT_MAX_FUTURE_BLOCKS: u64 = 8;
+
+/// A bundle of transactions that targets a specific block.
+///
+/// The transactions of a bundle are executed in order at the top of the block, before any
+/// transactions of the pool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bundle {
+    /// The hash of the bundle: the keccak256 hash of the concatenated transaction hashes.
+    pub hash: H256,
+    /// The transactions of the bundle, in execution order.
+    pub transactions: Vec<TransactionSignedEcRecovered>,
+    /// The number of the block the bundle targets.
+    pub block_number: u64,
+    /// The minimum timestamp of the block the bundle can be included in.
+    pub min_timestamp: Option<u64>,
+    /// The maximum timestamp of the block the bundle can be included in.
+    pub max_timestamp: Option<u64>,
+    /// Hashes of the transactions that are allowed to revert.
+    pub reverting_tx_hashes: HashSet<H256>,
+    /// Whether the bundle must be included as a whole or not at all.
+    ///
+    /// If `false`, transactions that fail or revert without being allowed to are dropped from the
+    /// bundle instead.
+    pub atomic: bool,
+}
+
+// === impl Bundle ===
+
+impl Bundle {
+    /// Creates a new atomic bundle of the given transactions that targets the given block.
+    pub fn new(transactions: Vec<TransactionSignedEcRecovered>, block_number: u64) -> Self {
+        let hashes = transactions.iter().flat_map(|tx| tx.hash().0).collect::<Vec<_>>();
+        Self {
+            hash: keccak256(hashes),
+            transactions,
+            block_number,
+            min_timestamp: None,
+            max_timestamp: None,
+            reverting_tx_hashes: Default::default(),
+            atomic: true,
+        }
+    }
+
+    /// Sets the minimum timestamp of the block the bundle can be included in.
+    pub fn with_min_timestamp(mut self, min_timestamp: Option<u64>) -> Self {
+        self.min_timestamp = min_timestamp;
+        self
+    }
+
+    /// Sets the maximum timestamp of the block the bundle can be included in.
+    pub fn with_max_timestamp(mut self, max_timestamp: Option<u64>) -> Self {
+        self.max_timestamp = max_timestamp;
+        self
+    }
+
+    /// Sets the hashes of the transactions that are allowed to revert.
+    pub fn with_reverting_tx_hashes(mut self, hashes: impl IntoIterator<Item = H256>) -> Self {
+        self.reverting_tx_hashes = hashes.into_iter().collect();
+        self
+    }
+
+    /// Sets whether the bundle must be included as a whole or not at all.
+    pub fn with_atomic(mut self, atomic: bool) -> Self {
+        self.atomic = atomic;
+        self
+    }
+
+    /// Returns true if the transaction with the given hash is allowed to revert.
+    pub fn is_revert_allowed(&self, hash: &H256) -> bool {
+        self.reverting_tx_hashes.contains(hash)
+    }
+
+    /// Returns true if the bundle can be included in a block with the given timestamp.
+    pub fn is_valid_at(&self, timestamp: u64) -> bool {
+        self.min_timestamp.map_or(true, |min| timestamp >= min) &&
+            self.max_timestamp.map_or(true, |max| timestamp <= max)
+    }
+
+    /// Ensures the bundle is well formed.
+    fn ensure_valid(&self) -> Result<(), BundleError> {
+        if self.transactions.is_empty() {
+            return Err(BundleError::EmptyBundle)
+        }
+        if let (Some(min), Some(max)) = (self.min_timestamp, self.max_timestamp) {
+            if min > max {
+                return Err(BundleError::InvalidTimestampRange { min, max })
+            }
+        }
+        if let Some(tx) = self.transactions.iter().find(|tx| tx.is_eip4844()) {
+            return Err(BundleError::BlobTransaction(tx.hash()))
+        }
+        Ok(())
+    }
+}
+
+/// Limits of a [BundleStore].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BundleStoreConfig {
+    /// The maximum number of bundles in the store.
+    pub max_bundles: usize,
+    /// The maximum number of bundles per block.
+    pub max_bundles_per_block: usize,
+    /// How many blocks past the current head bundles can target.
+    pub max_future_blocks: u64,
+}
+
+impl Default for BundleStoreConfig {
+    fn default() -> Self {
+        Self {
+            max_bundles: DEFAULT_MAX_BUNDLES,
+            max_bundles_per_block: DEFAULT_MAX_BUNDLES_PER_BLOCK,
+            max_future_blocks: DEFAULT_MAX_FUTURE_BLOCKS,
+        }
+    }
+}
+
+/// An in-memory store for [Bundle]s, keyed by the block they target.
+///
+/// This type is cheap to clone, all clones share the same bundles.
+#[derive(Debug, Clone, Default)]
+pub struct BundleStore {
+    inner: Arc<RwLock<BundleStoreInner>>,
+}
+
+// === impl BundleStore ===
+
+impl BundleStore {
+    /// Creates a new store with the given limits.
+    pub fn new(config: BundleStoreConfig) -> Self {
+        let inner = BundleStoreInner { bundles: Default::default(), len: 0, config };
+        Self { inner: Arc::new(RwLock::new(inner)) }
+    }
+
+    /// Adds the bundle to the store and returns its hash.
+    ///
+    /// The bundle must target a block after the given `head`, at most
+    /// [BundleStoreConfig::max_future_blocks] blocks ahead of it.
+    ///
+    /// Submitting the same bundle for the same block again is a no-op.
+    pub fn insert(&self, bundle: Bundle, head: u64) -> Result<H256, BundleError> {
+        bundle.ensure_valid()?;
+        let hash = bundle.hash;
+        let mut inner = self.inner.write();
+        let BundleStoreConfig { max_bundles, max_bundles_per_block, max_future_blocks } =
+            inner.config;
+
+        let (min, max) = (head + 1, head.saturating_add(max_future_blocks));
+        if bundle.block_number < min || bundle.block_number > max {
+            return Err(BundleError::BlockOutOfRange { block_number: bundle.block_number, min, max })
+        }
+
+        let len = inner.len;
+        let bundles = inner.bundles.entry(bundle.block_number).or_default();
+        if bundles.iter().any(|b| b.hash == hash) {
+            return Ok(hash)
+        }
+        if bundles.len() >= max_bundles_per_block {
+            return Err(BundleError::TooManyBundles(bundle.block_number))
+        }
+        if len >= max_bundles {
+            return Err(BundleError::StoreFull(len))
+        }
+        bundles.push(bundle);
+        inner.len += 1;
+        Ok(hash)
+    }
+
+    /// Returns all bundles that can be included in the block with the given number and timestamp,
+    /// in the order they were submitted.
+    pub fn bundles_for(&self, block_number: u64, timestamp: u64) -> Vec<Bundle> {
+        self.inner
+            .read()
+            .bundles
+            .get(&block_number)
+            .map(|bundles| bundles.iter().filter(|b| b.is_valid_at(timestamp)).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Removes all bundles that target a block before the given block number.
+    pub fn remove_bundles_before(&self, block_number: u64) {
+        let mut inner = self.inner.write();
+        let kept = inner.bundles.split_off(&block_number);
+        let removed = std::mem::replace(&mut inner.bundles, kept);
+        inner.len -= removed.values().map(Vec::len).sum::<usize>();
+    }
+
+    /// Returns the total number of bundles in the store.
+    pub fn len(&self) -> usize {
+        self.inner.read().len
+    }
+
+    /// Returns true if the store holds no bundles.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Debug, Default)]
+struct BundleStoreInner {
+    /// All bundles, keyed by the block they target.
+    bundles: BTreeMap<u64, Vec<Bundle>>,
+    /// The total number of bundles.
+    len: usize,
+    /// The limits of the store.
+    config: BundleStoreConfig,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{
+        Signature, Transaction, TransactionKind, TransactionSigned, TxLegacy, H160,
+    };
+
+    fn transaction(nonce: u64) -> TransactionSignedEcRecovered {
+        let tx = Transaction::Legacy(TxLegacy {
+            nonce,
+            gas_limit: 21_000,
+            to: TransactionKind::Call(H160::random()),
+            ..Default::default()
+        });
+        let signed = TransactionSigned::from_transaction_and_signature(tx, Signature::default());
+        TransactionSignedEcRecovered::from_signed_transaction(signed, H160::random())
+    }
+
+    #[test]
+    fn insert_and_prune_bundles() {
+        let store = BundleStore::default();
+        let bundle = Bundle::new(vec![transaction(0), transaction(1)], 10);
+        let hash = store.insert(bundle.clone(), 9).unwrap();
+        assert_eq!(hash, bundle.hash);
+
+        // resubmitting the same bundle is a no-op
+        assert_eq!(store.insert(bundle.clone(), 9).unwrap(), hash);
+        store.insert(Bundle::new(vec![transaction(2)], 11), 9).unwrap();
+        assert_eq!(store.len(), 2);
+
+        assert_eq!(store.bundles_for(10, 0), vec![bundle]);
+        assert!(store.bundles_for(12, 0).is_empty());
+
+        store.remove_bundles_before(11);
+        assert_eq!(store.len(), 1);
+        assert!(store.bundles_for(10, 0).is_empty());
+        assert_eq!(store.bundles_for(11, 0).len(), 1);
+    }
+
+    #[test]
+    fn filter_bundles_by_timestamp() {
+        let store = BundleStore::default();
+        let bundle = Bundle::new(vec![transaction(0)], 1)
+            .with_min_timestamp(Some(100))
+            .with_max_timestamp(Some(200));
+        store.insert(bundle, 0).unwrap();
+
+        assert!(store.bundles_for(1, 99).is_empty());
+        assert_eq!(store.bundles_for(1, 100).len(), 1);
+        assert_eq!(store.bundles_for(1, 200).len(), 1);
+        assert!(store.bundles_for(1, 201).is_empty());
+    }
+
+    #[test]
+    fn reject_invalid_bundles() {
+        let store =
+            BundleStore::new(BundleStoreConfig { max_bundles_per_block: 1, ..Default::default() });
+        assert_eq!(store.insert(Bundle::new(vec![], 1), 0), Err(BundleError::EmptyBundle));
+
+        let bundle = Bundle::new(vec![transaction(0)], 1)
+            .with_min_timestamp(Some(2))
+            .with_max_timestamp(Some(1));
+        assert_eq!(
+            store.insert(bundle, 0),
+            Err(BundleError::InvalidTimestampRange { min: 2, max: 1 })
+        );
+
+        store.insert(Bundle::new(vec![transaction(0)], 1), 0).unwrap();
+        assert_eq!(
+            store.insert(Bundle::new(vec![transaction(1)], 1), 0),
+            Err(BundleError::TooManyBundles(1))
+        );
+    }
+
+    #[test]
+    fn reject_bundles_out_of_range() {
+        let store =
+            BundleStore::new(BundleStoreConfig { max_future_blocks: 2, ..Default::default() });
+        let head = 10;
+
+        // bundles for blocks that were already mined are rejected
+        assert_eq!(
+            store.insert(Bundle::new(vec![transaction(0)], head), head),
+            Err(BundleError::BlockOutOfRange { block_number: head, min: 11, max: 12 })
+        );
+        // as are bundles too far past the head
+        assert_eq!(
+            store.insert(Bundle::new(vec![transaction(0)], head + 3), head),
+            Err(BundleError::BlockOutOfRange { block_number: head + 3, min: 11, max: 12 })
+        );
+
+        store.insert(Bundle::new(vec![transaction(0)], head + 1), head).unwrap();
+        store.insert(Bundle::new(vec![transaction(0)], head + 2), head).unwrap();
+        assert_eq!(store.len(), 2);
+    }
+
+    #[test]
+    fn reject_bundles_when_full() {
+        let store = BundleStore::new(BundleStoreConfig { max_bundles: 2, ..Default::default() });
+        store.insert(Bundle::new(vec![transaction(0)], 1), 0).unwrap();
+        store.insert(Bundle::new(vec![transaction(1)], 2), 0).unwrap();
+        assert_eq!(
+            store.insert(Bundle::new(vec![transaction(2)], 2), 0),
+            Err(BundleError::StoreFull(2))
+        );
+
+        // pruning frees up space
+        store.remove_bundles_before(2);
+        assert_eq!(store.len(), 1);
+        store.insert(Bundle::new(vec![transaction(2)], 2), 1).unwrap();
+    }
+}
@@ -29,3 +29,40 @@ impl From<oneshot::error::RecvError> for PayloadBuilderError {
         PayloadBuilderError::ChannelClosed
     }
 }
+
+/// Possible error variants when submitting a [Bundle](crate::bundle::Bundle).
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BundleError {
+    /// Thrown if the bundle contains no transactions.
+    #[error("bundle contains no transactions")]
+    EmptyBundle,
+    /// Thrown if the minimum timestamp of the bundle exceeds its maximum timestamp.
+    #[error("invalid bundle timestamp range: min {min} > max {max}")]
+    InvalidTimestampRange {
+        /// The minimum timestamp.
+        min: u64,
+        /// The maximum timestamp.
+        max: u64,
+    },
+    /// Thrown if the bundle contains a blob transaction, whose sidecar can't be served with the
+    /// payload.
+    #[error("blob transactions are not supported in bundles: {0:?}")]
+    BlobTransaction(H256),
+    /// Thrown if the store already holds the maximum number of bundles for the target block.
+    #[error("too many bundles for block {0}")]
+    TooManyBundles(u64),
+    /// Thrown if the store already holds the maximum number of bundles.
+    #[error("bundle store is full: {0} bundles")]
+    StoreFull(usize),
+    /// Thrown if the bundle targets a block that was already mined, or a block too far past the
+    /// current head.
+    #[error("bundle targets block {block_number}, expected a block in {min}..={max}")]
+    BlockOutOfRange {
+        /// The block the bundle targets.
+        block_number: u64,
+        /// The lowest block a bundle can target.
+        min: u64,
+        /// The highest block a bundle can target.
+        max: u64,
+    },
+}
@@ -17,6 +17,7 @@
 //!     on [PayloadAttributes](reth_rpc_types::engine::PayloadAttributes).
 //!   - [PayloadJob]: a type that can yields (better) payloads over time.
 
+pub mod bundle;
 pub mod database;
 pub mod error;
 mod metrics;
@@ -27,6 +28,7 @@ mod traits;
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
 
+pub use bundle::{Bundle, BundleStore, BundleStoreConfig};
 pub use payload::{BuiltPayload, PayloadBuilderAttributes};
 pub use reth_rpc_types::engine::PayloadId;
 pub use service::{PayloadBuilderHandle, PayloadBuilderService, PayloadStore};
@@ -1,10 +1,12 @@
 //! Contains types required for building a payload.
 
-use reth_primitives::{Address, ChainSpec, Header, SealedBlock, Withdrawal, H256, U256};
+use reth_primitives::{
+    Address, BlobTransactionSidecar, ChainSpec, Header, SealedBlock, Withdrawal, H256, U256,
+};
 use reth_revm_primitives::config::revm_spec_by_timestamp_after_merge;
 use reth_rlp::Encodable;
 use reth_rpc_types::engine::{
-    ExecutionPayload, ExecutionPayloadEnvelope, PayloadAttributes, PayloadId,
+    BlobsBundleV1, ExecutionPayload, ExecutionPayloadEnvelope, PayloadAttributes, PayloadId,
 };
 use revm_primitives::{BlockEnv, CfgEnv};
 
@@ -21,6 +23,8 @@ pub struct BuiltPayload {
     pub(crate) block: SealedBlock,
     /// The fees of the block
     pub(crate) fees: U256,
+    /// The sidecars of the block's blob transactions, in transaction order.
+    pub(crate) sidecars: Vec<BlobTransactionSidecar>,
 }
 
 // === impl BuiltPayload ===
@@ -28,7 +32,13 @@ pub struct BuiltPayload {
 impl BuiltPayload {
     /// Initializes the payload with the given initial block.
     pub fn new(id: PayloadId, block: SealedBlock, fees: U256) -> Self {
-        Self { id, block, fees }
+        Self { id, block, fees, sidecars: Vec::new() }
+    }
+
+    /// Sets the sidecars of the block's blob transactions, in transaction order.
+    pub fn with_sidecars(mut self, sidecars: Vec<BlobTransactionSidecar>) -> Self {
+        self.sidecars = sidecars;
+        self
     }
 
     /// Returns the identifier of the payload.
@@ -46,6 +56,11 @@ impl BuiltPayload {
         self.fees
     }
 
+    /// Returns the sidecars of the block's blob transactions.
+    pub fn sidecars(&self) -> &[BlobTransactionSidecar] {
+        &self.sidecars
+    }
+
     /// Converts the type into the response expected by `engine_getPayloadV1`
     pub fn into_v1_payload(self) -> ExecutionPayload {
         self.into()
@@ -53,7 +68,19 @@ impl BuiltPayload {
 
     /// Converts the type into the response expected by `engine_getPayloadV2`
     pub fn into_v2_payload(self) -> ExecutionPayloadEnvelope {
-        self.into()
+        ExecutionPayloadEnvelope::from(self).into_v2_envelope()
+    }
+
+    /// Converts the type into the response expected by `engine_getPayloadV3`
+    pub fn into_v3_payload(self) -> ExecutionPayloadEnvelope {
+        let BuiltPayload { block, fees, sidecars, .. } = self;
+
+        ExecutionPayloadEnvelope {
+            block_value: fees,
+            payload: block.into(),
+            blobs_bundle: Some(BlobsBundleV1::new(sidecars)),
+            should_override_builder: Some(false),
+        }
     }
 }
 
@@ -69,7 +96,12 @@ impl From<BuiltPayload> for ExecutionPayloadEnvelope {
     fn from(value: BuiltPayload) -> Self {
         let BuiltPayload { block, fees, .. } = value;
 
-        ExecutionPayloadEnvelope { block_value: fees, payload: block.into() }
+        ExecutionPayloadEnvelope {
+            block_value: fees,
+            payload: block.into(),
+            blobs_bundle: None,
+            should_override_builder: None,
+        }
     }
 }
 
@@ -88,6 +120,8 @@ pub struct PayloadBuilderAttributes {
     pub prev_randao: H256,
     /// Withdrawals for the generated payload
     pub withdrawals: Vec<Withdrawal>,
+    /// Root of the parent beacon block for the generated payload, post Cancun
+    pub parent_beacon_block_root: Option<H256>,
 }
 
 // === impl PayloadBuilderAttributes ===
@@ -105,6 +139,7 @@ impl PayloadBuilderAttributes {
             suggested_fee_recipient: attributes.suggested_fee_recipient,
             prev_randao: attributes.prev_randao,
             withdrawals: attributes.withdrawals.unwrap_or_default(),
+            parent_beacon_block_root: attributes.parent_beacon_block_root,
         }
     }
 
@@ -161,6 +196,9 @@ pub(crate) fn payload_id(parent: &H256, attributes: &PayloadAttributes) -> Paylo
         withdrawals.encode(&mut buf);
         hasher.update(buf);
     }
+    if let Some(parent_beacon_block_root) = attributes.parent_beacon_block_root {
+        hasher.update(parent_beacon_block_root.as_bytes());
+    }
     let out = hasher.finalize();
     PayloadId::new(out.as_slice()[..8].try_into().expect("sufficient length"))
 }
@@ -140,6 +140,11 @@ impl SealedBlock {
         (self.header, self.body, self.ommers)
     }
 
+    /// Returns the versioned hashes of all blob transactions in the block, in order.
+    pub fn blob_versioned_hashes(&self) -> Vec<H256> {
+        self.body.iter().filter_map(|tx| tx.blob_versioned_hashes()).flatten().copied().collect()
+    }
+
     /// Expensive operation that recovers transaction signer. See [SealedBlockWithSenders].
     pub fn senders(&self) -> Option<Vec<Address>> {
         self.body.iter().map(|tx| tx.recover_signer()).collect::<Option<Vec<Address>>>()
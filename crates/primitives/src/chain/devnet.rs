@@ -0,0 +1,267 @@
+use crate::{
+    constants::ETH_TO_WEI, keccak256, Address, Bytes, ChainConfig, ChainSpec, Genesis,
+    GenesisAccount, H256, U256,
+};
+use secp256k1::{PublicKey, SecretKey, SECP256K1};
+use std::str::FromStr;
+
+/// The runtime code of the contracts deployed at genesis.
+///
+/// The contract increments the counter in storage slot 0 on every call and returns the new value.
+const COUNTER_CONTRACT: [u8; 18] = [
+    0x60, 0x00, 0x54, // PUSH1 0 SLOAD
+    0x60, 0x01, 0x01, // PUSH1 1 ADD
+    0x80, // DUP1
+    0x60, 0x00, 0x55, // PUSH1 0 SSTORE
+    0x60, 0x00, 0x52, // PUSH1 0 MSTORE
+    0x60, 0x20, 0x60, 0x00, 0xf3, // PUSH1 32 PUSH1 0 RETURN
+];
+
+/// The configuration of a generated, throwaway development chain.
+///
+/// All keys and addresses are derived from the seed, so the same configuration always produces
+/// the same chain spec. All forks up to and including Shanghai are active at genesis, and the chain
+/// has no proof-of-work phase, so it is meant to be run with the auto-seal consensus.
+///
+/// A devnet is described as `<seed>[,accounts=<n>][,contracts=<n>][,chain_id=<id>]`, e.g.
+/// `ci-1234,accounts=20`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DevnetConfig {
+    /// The seed all keys and addresses are derived from.
+    pub seed: String,
+    /// The number of funded accounts.
+    pub accounts: usize,
+    /// The balance of every funded account.
+    pub balance: U256,
+    /// The number of contracts deployed at genesis.
+    pub contracts: usize,
+    /// The chain ID.
+    pub chain_id: u64,
+    /// The gas limit of the genesis block.
+    pub gas_limit: u64,
+}
+
+impl DevnetConfig {
+    /// The default number of funded accounts.
+    pub const DEFAULT_ACCOUNTS: usize = 10;
+
+    /// The default number of contracts deployed at genesis.
+    pub const DEFAULT_CONTRACTS: usize = 1;
+
+    /// The default chain ID.
+    pub const DEFAULT_CHAIN_ID: u64 = 1337;
+
+    /// Creates the default configuration for the given seed.
+    pub fn new(seed: impl Into<String>) -> Self {
+        Self {
+            seed: seed.into(),
+            accounts: Self::DEFAULT_ACCOUNTS,
+            balance: U256::from(10_000 * ETH_TO_WEI),
+            contracts: Self::DEFAULT_CONTRACTS,
+            chain_id: Self::DEFAULT_CHAIN_ID,
+            gas_limit: 30_000_000,
+        }
+    }
+
+    /// Returns the funded accounts.
+    ///
+    /// The secret key of the `i`-th account is `keccak256("<seed>:account:<i>")`, hashed again
+    /// until it is a valid secp256k1 key.
+    pub fn accounts(&self) -> Vec<DevnetAccount> {
+        (0..self.accounts)
+            .map(|index| {
+                let mut secret = keccak256(format!("{}:account:{index}", self.seed));
+                let key = loop {
+                    match SecretKey::from_slice(secret.as_bytes()) {
+                        Ok(key) => break key,
+                        Err(_) => secret = keccak256(secret),
+                    }
+                };
+                let public = PublicKey::from_secret_key(SECP256K1, &key);
+                let address =
+                    Address::from_slice(&keccak256(&public.serialize_uncompressed()[1..])[12..]);
+                DevnetAccount { secret_key: secret, address }
+            })
+            .collect()
+    }
+
+    /// Returns the addresses of the contracts deployed at genesis.
+    ///
+    /// The `i`-th contract is deployed at the last 20 bytes of `keccak256("<seed>:contract:<i>")`.
+    pub fn contracts(&self) -> Vec<Address> {
+        (0..self.contracts)
+            .map(|index| {
+                let hash = keccak256(format!("{}:contract:{index}", self.seed));
+                Address::from_slice(&hash[12..])
+            })
+            .collect()
+    }
+
+    /// Returns the genesis of the devnet.
+    pub fn genesis(&self) -> Genesis {
+        let accounts = self
+            .accounts()
+            .into_iter()
+            .map(|account| (account.address, GenesisAccount::default().with_balance(self.balance)));
+        let contracts = self.contracts().into_iter().map(|address| {
+            let code = Bytes::from(COUNTER_CONTRACT.to_vec());
+            (address, GenesisAccount::default().with_nonce(Some(1)).with_code(Some(code)))
+        });
+
+        let config = ChainConfig {
+            chain_id: self.chain_id,
+            homestead_block: Some(0),
+            eip150_block: Some(0),
+            eip155_block: Some(0),
+            eip158_block: Some(0),
+            byzantium_block: Some(0),
+            constantinople_block: Some(0),
+            petersburg_block: Some(0),
+            istanbul_block: Some(0),
+            berlin_block: Some(0),
+            london_block: Some(0),
+            merge_netsplit_block: Some(0),
+            terminal_total_difficulty: Some(U256::ZERO),
+            terminal_total_difficulty_passed: true,
+            shanghai_time: Some(0),
+            ..Default::default()
+        };
+
+        Genesis::default()
+            .with_gas_limit(self.gas_limit)
+            .with_extra_data(Bytes::from(format!("devnet:{}", self.seed).into_bytes()))
+            .with_config(config)
+            .extend_accounts(accounts.chain(contracts))
+    }
+
+    /// Returns the chain spec of the devnet.
+    pub fn chain_spec(&self) -> ChainSpec {
+        let mut spec = ChainSpec::from(self.genesis());
+        // the chain is post-merge from genesis
+        spec.paris_block_and_final_difficulty = Some((0, U256::ZERO));
+        spec
+    }
+}
+
+impl FromStr for DevnetConfig {
+    type Err = DevnetConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(',');
+        let seed = parts.next().unwrap_or_default();
+        if seed.is_empty() {
+            return Err(DevnetConfigError::MissingSeed)
+        }
+
+        let mut config = Self::new(seed);
+        for option in parts {
+            let (key, value) =
+                option.split_once('=').ok_or_else(|| DevnetConfigError::Invalid(option.into()))?;
+            let invalid = |_| DevnetConfigError::Invalid(option.into());
+            match key {
+                "accounts" => config.accounts = value.parse().map_err(invalid)?,
+                "contracts" => config.contracts = value.parse().map_err(invalid)?,
+                "chain_id" => config.chain_id = value.parse().map_err(invalid)?,
+                _ => return Err(DevnetConfigError::Invalid(option.into())),
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+/// A funded account of a devnet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DevnetAccount {
+    /// The secret key of the account.
+    pub secret_key: H256,
+    /// The address of the account.
+    pub address: Address,
+}
+
+/// Errors when parsing a [DevnetConfig].
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum DevnetConfigError {
+    /// The seed is empty.
+    #[error("Missing devnet seed")]
+    MissingSeed,
+    /// An option is malformed or unknown.
+    #[error("Invalid devnet option: {0}")]
+    Invalid(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hex_literal::hex, ForkCondition, Hardfork, Head};
+
+    #[test]
+    fn parse_devnet_config() {
+        assert_eq!("ci".parse::<DevnetConfig>(), Ok(DevnetConfig::new("ci")));
+        assert_eq!(
+            "ci,accounts=3,contracts=0,chain_id=42".parse::<DevnetConfig>(),
+            Ok(DevnetConfig { accounts: 3, contracts: 0, chain_id: 42, ..DevnetConfig::new("ci") })
+        );
+        assert_eq!("".parse::<DevnetConfig>(), Err(DevnetConfigError::MissingSeed));
+        assert_eq!(
+            "ci,accounts".parse::<DevnetConfig>(),
+            Err(DevnetConfigError::Invalid("accounts".into()))
+        );
+        assert_eq!(
+            "ci,blocks=1".parse::<DevnetConfig>(),
+            Err(DevnetConfigError::Invalid("blocks=1".into()))
+        );
+    }
+
+    #[test]
+    fn devnet_is_deterministic() {
+        let config = DevnetConfig::new("ci");
+        assert_eq!(config.chain_spec().genesis_hash(), config.chain_spec().genesis_hash());
+        assert_ne!(
+            config.chain_spec().genesis_hash(),
+            DevnetConfig::new("other").chain_spec().genesis_hash()
+        );
+
+        let accounts = config.accounts();
+        assert_eq!(accounts.len(), DevnetConfig::DEFAULT_ACCOUNTS);
+        assert_eq!(accounts, DevnetConfig { accounts: 20, ..config.clone() }.accounts()[..10]);
+        assert_eq!(accounts[0].secret_key, keccak256("ci:account:0"));
+    }
+
+    #[test]
+    fn devnet_account_address() {
+        // the well-known first hardhat/anvil dev account
+        let secret = H256(hex!("ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"));
+        let key = SecretKey::from_slice(secret.as_bytes()).unwrap();
+        let public = PublicKey::from_secret_key(SECP256K1, &key);
+        let address = Address::from_slice(&keccak256(&public.serialize_uncompressed()[1..])[12..]);
+        assert_eq!(address, Address::from(hex!("f39fd6e51aad88f6f4ce6ab8827279cfffb92266")));
+    }
+
+    #[test]
+    fn devnet_chain_spec() {
+        let config = DevnetConfig { accounts: 2, contracts: 1, ..DevnetConfig::new("ci") };
+        let spec = config.chain_spec();
+
+        assert_eq!(spec.chain.id(), DevnetConfig::DEFAULT_CHAIN_ID);
+        assert_eq!(spec.genesis.alloc.len(), 3);
+        for account in config.accounts() {
+            assert_eq!(spec.genesis.alloc[&account.address].balance, config.balance);
+        }
+        let contract = &spec.genesis.alloc[&config.contracts()[0]];
+        assert_eq!(contract.code, Some(Bytes::from(COUNTER_CONTRACT.to_vec())));
+
+        assert_eq!(spec.fork(Hardfork::London), ForkCondition::Block(0));
+        assert_eq!(spec.fork(Hardfork::Shanghai), ForkCondition::Timestamp(0));
+        assert_eq!(spec.fork(Hardfork::Cancun), ForkCondition::Never);
+        assert_eq!(spec.final_paris_difficulty(0), Some(U256::ZERO));
+
+        // all forks are active at genesis
+        let genesis_fork_id = spec.fork_id(&Head::default());
+        assert_eq!(genesis_fork_id.next, 0);
+
+        let header = spec.genesis_header();
+        assert!(header.withdrawals_root.is_some());
+        assert_eq!(header.excess_blob_gas, Some(0));
+    }
+}
@@ -11,9 +11,14 @@ use std::{fmt, str::FromStr};
 // The chain spec module.
 mod spec;
 pub use spec::{
-    AllGenesisFormats, ChainSpec, ChainSpecBuilder, ForkCondition, GOERLI, MAINNET, SEPOLIA,
+    AllGenesisFormats, ChainSpec, ChainSpecBuilder, CliqueConfig, ForkCondition, GOERLI, HOLESKY,
+    MAINNET, SEPOLIA,
 };
 
+// The devnet chain spec generator module.
+mod devnet;
+pub use devnet::{DevnetAccount, DevnetConfig, DevnetConfigError};
+
 // The chain info module.
 mod info;
 pub use info::ChainInfo;
@@ -44,6 +49,11 @@ impl Chain {
         Chain::Named(ethers_core::types::Chain::Sepolia)
     }
 
+    /// Returns the holesky chain.
+    pub const fn holesky() -> Self {
+        Chain::Id(17000)
+    }
+
     /// The id of the chain
     pub fn id(&self) -> u64 {
         match self {
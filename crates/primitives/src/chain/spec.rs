@@ -3,14 +3,13 @@ use crate::{
     forkid::ForkFilterKey,
     header::Head,
     proofs::genesis_state_root,
-    BlockNumber, Chain, ForkFilter, ForkHash, ForkId, Genesis, GenesisAccount, Hardfork, Header,
-    H160, H256, U256,
+    BlockNumber, Chain, ForkFilter, ForkHash, ForkId, Genesis, Hardfork, Header, H256, U256,
 };
 use ethers_core::utils::Genesis as EthersGenesis;
 use hex_literal::hex;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet};
 
 /// The Ethereum mainnet spec
 pub static MAINNET: Lazy<ChainSpec> = Lazy::new(|| ChainSpec {
@@ -50,6 +49,7 @@ pub static MAINNET: Lazy<ChainSpec> = Lazy::new(|| ChainSpec {
         ),
         (Hardfork::Shanghai, ForkCondition::Timestamp(1681338455)),
     ]),
+    clique: None,
 });
 
 /// The Goerli spec
@@ -81,6 +81,7 @@ pub static GOERLI: Lazy<ChainSpec> = Lazy::new(|| ChainSpec {
         ),
         (Hardfork::Shanghai, ForkCondition::Timestamp(1678832736)),
     ]),
+    clique: Some(CliqueConfig { period: 15, epoch: 30000 }),
 });
 
 /// The Sepolia spec
@@ -116,6 +117,40 @@ pub static SEPOLIA: Lazy<ChainSpec> = Lazy::new(|| ChainSpec {
         ),
         (Hardfork::Shanghai, ForkCondition::Timestamp(1677557088)),
     ]),
+    clique: None,
+});
+
+/// The Holesky spec
+pub static HOLESKY: Lazy<ChainSpec> = Lazy::new(|| ChainSpec {
+    chain: Chain::holesky(),
+    // TODO: vendor the full allocation of the upstream genesis file, until then the genesis block
+    // doesn't match the genesis hash and the node refuses to initialize its database
+    // <https://github.com/eth-clients/holesky/blob/main/metadata/genesis.json>
+    genesis: serde_json::from_str(include_str!("../../res/genesis/holesky.json"))
+        .expect("Can't deserialize Holesky genesis json"),
+    genesis_hash: Some(H256(hex!(
+        "b5f7f912443c940f21fd611f12828d75b534364ed9e95ca4e307729a4661bde4"
+    ))),
+    // Holesky launched with the merge already in effect
+    paris_block_and_final_difficulty: Some((0, U256::from(1))),
+    fork_timestamps: ForkTimestamps::default().shanghai(1696000704).cancun(1707305664),
+    hardforks: BTreeMap::from([
+        (Hardfork::Frontier, ForkCondition::Block(0)),
+        (Hardfork::Homestead, ForkCondition::Block(0)),
+        (Hardfork::Dao, ForkCondition::Block(0)),
+        (Hardfork::Tangerine, ForkCondition::Block(0)),
+        (Hardfork::SpuriousDragon, ForkCondition::Block(0)),
+        (Hardfork::Byzantium, ForkCondition::Block(0)),
+        (Hardfork::Constantinople, ForkCondition::Block(0)),
+        (Hardfork::Petersburg, ForkCondition::Block(0)),
+        (Hardfork::Istanbul, ForkCondition::Block(0)),
+        (Hardfork::Berlin, ForkCondition::Block(0)),
+        (Hardfork::London, ForkCondition::Block(0)),
+        (Hardfork::Paris, ForkCondition::TTD { fork_block: Some(0), total_difficulty: U256::ZERO }),
+        (Hardfork::Shanghai, ForkCondition::Timestamp(1696000704)),
+        (Hardfork::Cancun, ForkCondition::Timestamp(1707305664)),
+    ]),
+    clique: None,
 });
 
 /// An Ethereum chain specification.
@@ -152,6 +187,11 @@ pub struct ChainSpec {
 
     /// The active hard forks and their activation conditions
     pub hardforks: BTreeMap<Hardfork, ForkCondition>,
+
+    /// The clique proof-of-authority configuration, if the chain runs clique before (or instead
+    /// of) the merge.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clique: Option<CliqueConfig>,
 }
 
 impl ChainSpec {
@@ -178,6 +218,15 @@ impl ChainSpec {
             (self.fork(Hardfork::Shanghai).active_at_timestamp(self.genesis.timestamp))
                 .then_some(EMPTY_WITHDRAWALS);
 
+        // If cancun is activated, the genesis block has neither used nor excess blob gas, and its
+        // parent beacon block root is zero.
+        let (blob_gas_used, excess_blob_gas, parent_beacon_block_root) =
+            if self.is_cancun_activated_at_timestamp(self.genesis.timestamp) {
+                (Some(0), Some(0), Some(H256::zero()))
+            } else {
+                (None, None, None)
+            };
+
         Header {
             gas_limit: self.genesis.gas_limit,
             difficulty: self.genesis.difficulty,
@@ -189,14 +238,19 @@ impl ChainSpec {
             beneficiary: self.genesis.coinbase,
             base_fee_per_gas,
             withdrawals_root,
+            blob_gas_used,
+            excess_blob_gas,
+            parent_beacon_block_root,
             ..Default::default()
         }
     }
 
     /// Get the initial base fee of the genesis block.
     pub fn initial_base_fee(&self) -> Option<u64> {
-        // If London is activated at genesis, we set the initial base fee as per EIP-1559.
-        (self.fork(Hardfork::London).active_at_block(0)).then_some(EIP1559_INITIAL_BASE_FEE)
+        // If London is activated at genesis, we set the initial base fee as per EIP-1559, unless
+        // the genesis specifies one.
+        (self.fork(Hardfork::London).active_at_block(0))
+            .then(|| self.genesis.base_fee_per_gas.unwrap_or(EIP1559_INITIAL_BASE_FEE))
     }
 
     /// Get the hash of the genesis block.
@@ -252,43 +306,56 @@ impl ChainSpec {
             .unwrap_or_else(|| self.is_fork_active_at_timestamp(Hardfork::Shanghai, timestamp))
     }
 
-    /// Creates a [`ForkFilter`](crate::ForkFilter) for the block described by [Head].
-    pub fn fork_filter(&self, head: Head) -> ForkFilter {
-        let forks = self.forks_iter().filter_map(|(_, condition)| {
-            // We filter out TTD-based forks w/o a pre-known block since those do not show up in the
-            // fork filter.
-            Some(match condition {
-                ForkCondition::Block(block) => ForkFilterKey::Block(block),
-                ForkCondition::Timestamp(time) => ForkFilterKey::Time(time),
-                ForkCondition::TTD { fork_block: Some(block), .. } => ForkFilterKey::Block(block),
-                _ => return None,
+    /// Convenience method to check if [Hardfork::Cancun] is active at a given timestamp.
+    #[inline]
+    pub fn is_cancun_activated_at_timestamp(&self, timestamp: u64) -> bool {
+        self.fork_timestamps
+            .cancun
+            .map(|cancun| timestamp >= cancun)
+            .unwrap_or_else(|| self.is_fork_active_at_timestamp(Hardfork::Cancun, timestamp))
+    }
+
+    /// Returns the block numbers and timestamps at which forks are activated, in the order they
+    /// are hashed into the [`ForkId`].
+    ///
+    /// Like geth, this skips forks that are already active in the genesis block, deduplicates forks
+    /// that activate at the same block or timestamp, and hashes all block-based forks before the
+    /// timestamp-based ones. TTD-based forks without a pre-known block do not show up.
+    fn fork_filter_keys(&self) -> BTreeSet<ForkFilterKey> {
+        self.forks_iter()
+            .filter_map(|(_, condition)| match condition {
+                ForkCondition::Block(block) => Some(ForkFilterKey::Block(block)),
+                ForkCondition::TTD { fork_block: Some(block), .. } => {
+                    Some(ForkFilterKey::Block(block))
+                }
+                ForkCondition::Timestamp(time) => Some(ForkFilterKey::Time(time)),
+                _ => None,
+            })
+            .filter(|key| match *key {
+                ForkFilterKey::Block(block) => block > 0,
+                ForkFilterKey::Time(time) => time > self.genesis.timestamp,
             })
-        });
+            .collect()
+    }
 
-        ForkFilter::new(head, self.genesis_hash(), forks)
+    /// Creates a [`ForkFilter`](crate::ForkFilter) for the block described by [Head].
+    pub fn fork_filter(&self, head: Head) -> ForkFilter {
+        ForkFilter::new(head, self.genesis_hash(), self.fork_filter_keys())
     }
 
     /// Compute the [`ForkId`] for the given [`Head`]
     pub fn fork_id(&self, head: &Head) -> ForkId {
         let mut curr_forkhash = ForkHash::from(self.genesis_hash());
-        let mut current_applied_value = 0;
-
-        for (_, cond) in self.forks_iter() {
-            let value = match cond {
-                ForkCondition::Block(block) => block,
-                ForkCondition::Timestamp(time) => time,
-                ForkCondition::TTD { fork_block: Some(block), .. } => block,
-                _ => continue,
-            };
 
-            if cond.active_at_head(head) {
-                if value != current_applied_value {
-                    curr_forkhash += value;
-                    current_applied_value = value;
-                }
-            } else {
-                return ForkId { hash: curr_forkhash, next: value }
+        for key in self.fork_filter_keys() {
+            let active = match key {
+                ForkFilterKey::Block(block) => head.number >= block,
+                ForkFilterKey::Time(time) => head.timestamp >= time,
+            };
+            if !active {
+                return ForkId { hash: curr_forkhash, next: key.into() }
             }
+            curr_forkhash += u64::from(key);
         }
         ForkId { hash: curr_forkhash, next: 0 }
     }
@@ -299,40 +366,26 @@ impl ChainSpec {
     }
 }
 
-impl From<EthersGenesis> for ChainSpec {
-    fn from(genesis: EthersGenesis) -> Self {
-        let alloc = genesis
-            .alloc
-            .iter()
-            .map(|(addr, account)| (addr.0.into(), account.clone().into()))
-            .collect::<HashMap<H160, GenesisAccount>>();
-
-        let genesis_block = Genesis {
-            nonce: genesis.nonce.as_u64(),
-            timestamp: genesis.timestamp.as_u64(),
-            gas_limit: genesis.gas_limit.as_u64(),
-            difficulty: genesis.difficulty.into(),
-            mix_hash: genesis.mix_hash.0.into(),
-            coinbase: genesis.coinbase.0.into(),
-            extra_data: genesis.extra_data.0.into(),
-            alloc,
-        };
+impl From<Genesis> for ChainSpec {
+    fn from(genesis: Genesis) -> Self {
+        let config = &genesis.config;
 
         // Block-based hardforks
-        let hardfork_opts = vec![
-            (Hardfork::Homestead, genesis.config.homestead_block),
-            (Hardfork::Dao, genesis.config.dao_fork_block),
-            (Hardfork::Tangerine, genesis.config.eip150_block),
-            (Hardfork::SpuriousDragon, genesis.config.eip155_block),
-            (Hardfork::Byzantium, genesis.config.byzantium_block),
-            (Hardfork::Constantinople, genesis.config.constantinople_block),
-            (Hardfork::Petersburg, genesis.config.petersburg_block),
-            (Hardfork::Istanbul, genesis.config.istanbul_block),
-            (Hardfork::MuirGlacier, genesis.config.muir_glacier_block),
-            (Hardfork::Berlin, genesis.config.berlin_block),
-            (Hardfork::London, genesis.config.london_block),
-            (Hardfork::ArrowGlacier, genesis.config.arrow_glacier_block),
-            (Hardfork::GrayGlacier, genesis.config.gray_glacier_block),
+        let hardfork_opts = [
+            (Hardfork::Frontier, Some(0)),
+            (Hardfork::Homestead, config.homestead_block),
+            (Hardfork::Dao, config.dao_fork_block),
+            (Hardfork::Tangerine, config.eip150_block),
+            (Hardfork::SpuriousDragon, config.eip158_block.or(config.eip155_block)),
+            (Hardfork::Byzantium, config.byzantium_block),
+            (Hardfork::Constantinople, config.constantinople_block),
+            (Hardfork::Petersburg, config.petersburg_block),
+            (Hardfork::Istanbul, config.istanbul_block),
+            (Hardfork::MuirGlacier, config.muir_glacier_block),
+            (Hardfork::Berlin, config.berlin_block),
+            (Hardfork::London, config.london_block),
+            (Hardfork::ArrowGlacier, config.arrow_glacier_block),
+            (Hardfork::GrayGlacier, config.gray_glacier_block),
         ];
         let mut hardforks = hardfork_opts
             .iter()
@@ -340,42 +393,71 @@ impl From<EthersGenesis> for ChainSpec {
             .collect::<BTreeMap<_, _>>();
 
         // Paris
-        if let Some(ttd) = genesis.config.terminal_total_difficulty {
+        if let Some(ttd) = config.terminal_total_difficulty {
             hardforks.insert(
                 Hardfork::Paris,
                 ForkCondition::TTD {
-                    total_difficulty: ttd.into(),
-                    fork_block: genesis.config.merge_netsplit_block,
+                    total_difficulty: ttd,
+                    fork_block: config.merge_netsplit_block,
                 },
             );
         }
 
         // Time-based hardforks
-        let time_hardforks = genesis
-            .config
-            .shanghai_time
-            .map(|time| (Hardfork::Shanghai, ForkCondition::Timestamp(time)))
-            .into_iter()
-            .collect::<BTreeMap<_, _>>();
-
-        hardforks.extend(time_hardforks);
+        let time_hardforks =
+            [(Hardfork::Shanghai, config.shanghai_time), (Hardfork::Cancun, config.cancun_time)];
+        hardforks.extend(time_hardforks.iter().filter_map(|(hardfork, opt)| {
+            opt.map(|time| (*hardfork, ForkCondition::Timestamp(time)))
+        }));
 
         Self {
-            chain: genesis.config.chain_id.into(),
-            genesis: genesis_block,
+            chain: config.chain_id.into(),
             genesis_hash: None,
             fork_timestamps: ForkTimestamps::from_hardforks(&hardforks),
             hardforks,
             paris_block_and_final_difficulty: None,
+            clique: config.clique,
+            genesis,
         }
     }
 }
 
+impl From<EthersGenesis> for ChainSpec {
+    fn from(genesis: EthersGenesis) -> Self {
+        Genesis::from(genesis).into()
+    }
+}
+
+/// The clique proof-of-authority parameters of a chain.
+///
+/// See also [EIP-225](https://eips.ethereum.org/EIPS/eip-225).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CliqueConfig {
+    /// Minimum number of seconds between two consecutive blocks.
+    #[serde(default)]
+    pub period: u64,
+    /// Number of blocks after which the pending votes are reset and the signer set is
+    /// checkpointed in the header.
+    #[serde(default = "CliqueConfig::default_epoch")]
+    pub epoch: u64,
+}
+
+impl CliqueConfig {
+    /// The epoch length used by geth if none is configured.
+    pub const DEFAULT_EPOCH: u64 = 30000;
+
+    const fn default_epoch() -> u64 {
+        Self::DEFAULT_EPOCH
+    }
+}
+
 /// Various timestamps of forks
 #[derive(Debug, Clone, Default, Eq, PartialEq)]
 pub struct ForkTimestamps {
     /// The timestamp of the shanghai fork
     pub shanghai: Option<u64>,
+    /// The timestamp of the cancun fork
+    pub cancun: Option<u64>,
 }
 
 impl ForkTimestamps {
@@ -385,6 +467,9 @@ impl ForkTimestamps {
         if let Some(shanghai) = forks.get(&Hardfork::Shanghai).and_then(|f| f.as_timestamp()) {
             timestamps = timestamps.shanghai(shanghai);
         }
+        if let Some(cancun) = forks.get(&Hardfork::Cancun).and_then(|f| f.as_timestamp()) {
+            timestamps = timestamps.cancun(cancun);
+        }
         timestamps
     }
 
@@ -393,20 +478,28 @@ impl ForkTimestamps {
         self.shanghai = Some(shanghai);
         self
     }
+
+    /// Sets the given cancun timestamp
+    pub fn cancun(mut self, cancun: u64) -> Self {
+        self.cancun = Some(cancun);
+        self
+    }
 }
 
 /// A helper type for compatibility with geth's config
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum AllGenesisFormats {
-    /// The geth genesis format
-    Geth(EthersGenesis),
     /// The reth genesis format
+    ///
+    /// This is tried first, since the geth format would also accept a reth chain spec.
     Reth(ChainSpec),
+    /// The geth genesis format
+    Geth(Genesis),
 }
 
-impl From<EthersGenesis> for AllGenesisFormats {
-    fn from(genesis: EthersGenesis) -> Self {
+impl From<Genesis> for AllGenesisFormats {
+    fn from(genesis: Genesis) -> Self {
         Self::Geth(genesis)
     }
 }
@@ -432,6 +525,7 @@ pub struct ChainSpecBuilder {
     chain: Option<Chain>,
     genesis: Option<Genesis>,
     hardforks: BTreeMap<Hardfork, ForkCondition>,
+    clique: Option<CliqueConfig>,
 }
 
 impl ChainSpecBuilder {
@@ -441,6 +535,7 @@ impl ChainSpecBuilder {
             chain: Some(MAINNET.chain),
             genesis: Some(MAINNET.genesis.clone()),
             hardforks: MAINNET.hardforks.clone(),
+            clique: None,
         }
     }
 
@@ -462,6 +557,12 @@ impl ChainSpecBuilder {
         self
     }
 
+    /// Run clique proof-of-authority with the given configuration.
+    pub fn clique(mut self, config: CliqueConfig) -> Self {
+        self.clique = Some(config);
+        self
+    }
+
     /// Enable the Paris hardfork at the given TTD.
     ///
     /// Does not set the merge netsplit block.
@@ -551,6 +652,13 @@ impl ChainSpecBuilder {
         self
     }
 
+    /// Enable Cancun at genesis.
+    pub fn cancun_activated(mut self) -> Self {
+        self = self.shanghai_activated();
+        self.hardforks.insert(Hardfork::Cancun, ForkCondition::Timestamp(0));
+        self
+    }
+
     /// Build the resulting [`ChainSpec`].
     ///
     /// # Panics
@@ -565,6 +673,7 @@ impl ChainSpecBuilder {
             fork_timestamps: ForkTimestamps::from_hardforks(&self.hardforks),
             hardforks: self.hardforks,
             paris_block_and_final_difficulty: None,
+            clique: self.clique,
         }
     }
 }
@@ -575,6 +684,7 @@ impl From<&ChainSpec> for ChainSpecBuilder {
             chain: Some(value.chain),
             genesis: Some(value.genesis.clone()),
             hardforks: value.hardforks.clone(),
+            clique: value.clique,
         }
     }
 }
@@ -707,8 +817,9 @@ impl ForkCondition {
 #[cfg(test)]
 mod tests {
     use crate::{
-        AllGenesisFormats, Chain, ChainSpec, ChainSpecBuilder, ForkCondition, ForkHash, ForkId,
-        Genesis, Hardfork, Head, GOERLI, H256, MAINNET, SEPOLIA, U256,
+        constants::EIP1559_INITIAL_BASE_FEE, AllGenesisFormats, Chain, ChainSpec, ChainSpecBuilder,
+        ForkCondition, ForkHash, ForkId, Genesis, Hardfork, Head, GOERLI, H256, HOLESKY, MAINNET,
+        SEPOLIA, U256,
     };
     use bytes::BytesMut;
     use ethers_core::types as EtherType;
@@ -738,6 +849,7 @@ mod tests {
         assert_eq!(spec.fork_timestamps.shanghai, Some(1337));
         assert!(spec.is_shanghai_activated_at_timestamp(1337));
         assert!(!spec.is_shanghai_activated_at_timestamp(1336));
+        assert!(!spec.is_cancun_activated_at_timestamp(1337));
     }
 
     // Tests that all predefined timestamps are correctly set up in the chainspecs
@@ -792,6 +904,43 @@ mod tests {
         );
     }
 
+    // Tests that timestamp forks are hashed after block forks, and that timestamp forks at the
+    // genesis timestamp are ignored
+    #[test]
+    fn timestamp_fork_ids() {
+        let mut spec = ChainSpec::builder()
+            .chain(Chain::mainnet())
+            .genesis(Genesis::default().with_timestamp(1000))
+            .with_fork(Hardfork::Frontier, ForkCondition::Block(0))
+            .with_fork(Hardfork::Homestead, ForkCondition::Block(1150000))
+            .with_fork(Hardfork::London, ForkCondition::Block(1150000))
+            .with_fork(Hardfork::Shanghai, ForkCondition::Timestamp(1000))
+            .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(2000))
+            .build();
+        spec.genesis_hash = Some(MAINNET.genesis_hash());
+
+        test_fork_ids(
+            &spec,
+            &[
+                (
+                    Head { number: 0, timestamp: 1000, ..Default::default() },
+                    ForkId { hash: ForkHash([0xfc, 0x64, 0xec, 0x04]), next: 1150000 },
+                ),
+                (
+                    Head { number: 1150000, timestamp: 1999, ..Default::default() },
+                    ForkId { hash: ForkHash([0x97, 0xc2, 0xc3, 0x4c]), next: 2000 },
+                ),
+                (
+                    Head { number: 1150000, timestamp: 2000, ..Default::default() },
+                    ForkId { hash: ForkHash([0x10, 0x71, 0x8d, 0x71]), next: 0 },
+                ),
+            ],
+        );
+
+        let head = Head { number: 1150000, timestamp: 2000, ..Default::default() };
+        assert_eq!(spec.fork_filter(head).current(), spec.fork_id(&head));
+    }
+
     #[test]
     fn ignores_duplicate_fork_blocks() {
         let empty_genesis = Genesis::default();
@@ -964,6 +1113,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn holesky_forkids() {
+        test_fork_ids(
+            &HOLESKY,
+            &[
+                (
+                    Head { number: 0, timestamp: 1695902400, ..Default::default() },
+                    ForkId { hash: ForkHash([0xc6, 0x1a, 0x60, 0x98]), next: 1696000704 },
+                ),
+                (
+                    Head { number: 123, timestamp: 1696000703, ..Default::default() },
+                    ForkId { hash: ForkHash([0xc6, 0x1a, 0x60, 0x98]), next: 1696000704 },
+                ),
+                (
+                    Head { number: 123, timestamp: 1696000704, ..Default::default() },
+                    ForkId { hash: ForkHash([0xfd, 0x4f, 0x01, 0x6b]), next: 1707305664 },
+                ),
+                (
+                    Head { number: 123, timestamp: 1707305664, ..Default::default() },
+                    ForkId { hash: ForkHash([0x9b, 0x19, 0x2a, 0xd0]), next: 0 },
+                ),
+            ],
+        );
+    }
+
     /// Checks that time-based forks work
     ///
     /// This is based off of the test vectors here: https://github.com/ethereum/go-ethereum/blob/5c8cc10d1e05c23ff1108022f4150749e73c0ca1/core/forkid/forkid_test.go#L155-L188
@@ -1259,6 +1433,68 @@ mod tests {
         assert_eq!(hash, expected_hash);
     }
 
+    #[test]
+    fn geth_genesis_with_timestamp_forks() {
+        let geth_genesis = r#"
+        {
+          "config": {
+            "chainId": 1337,
+            "homesteadBlock": 0,
+            "eip150Block": 0,
+            "eip155Block": 0,
+            "eip158Block": 0,
+            "byzantiumBlock": 0,
+            "constantinopleBlock": 0,
+            "petersburgBlock": 0,
+            "istanbulBlock": 0,
+            "berlinBlock": 0,
+            "londonBlock": 0,
+            "mergeNetsplitBlock": 0,
+            "shanghaiTime": 0,
+            "cancunTime": 1700000000,
+            "terminalTotalDifficulty": 0,
+            "terminalTotalDifficultyPassed": true
+          },
+          "timestamp": "0x0",
+          "gasLimit": "0x1c9c380",
+          "difficulty": "0x0",
+          "alloc": {}
+        }
+        "#;
+
+        let genesis = serde_json::from_str::<AllGenesisFormats>(geth_genesis).unwrap();
+        assert!(matches!(genesis, AllGenesisFormats::Geth(_)));
+        let chainspec: ChainSpec = genesis.into();
+
+        assert_eq!(chainspec.chain.id(), 1337);
+        assert_eq!(chainspec.fork(Hardfork::Frontier), ForkCondition::Block(0));
+        assert_eq!(chainspec.fork(Hardfork::SpuriousDragon), ForkCondition::Block(0));
+        assert_eq!(chainspec.fork(Hardfork::Dao), ForkCondition::Never);
+        assert_eq!(
+            chainspec.fork(Hardfork::Paris),
+            ForkCondition::TTD { total_difficulty: U256::ZERO, fork_block: Some(0) }
+        );
+        assert_eq!(chainspec.fork(Hardfork::Shanghai), ForkCondition::Timestamp(0));
+        assert_eq!(chainspec.fork(Hardfork::Cancun), ForkCondition::Timestamp(1700000000));
+        assert_eq!(chainspec.fork_timestamps.cancun, Some(1700000000));
+
+        // only cancun shows up in the fork id, all other forks are active at genesis
+        let genesis_hash = ForkHash::from(chainspec.genesis_hash());
+        assert_eq!(
+            chainspec.fork_id(&Head::default()),
+            ForkId { hash: genesis_hash, next: 1700000000 }
+        );
+        assert_eq!(
+            chainspec.fork_id(&Head { timestamp: 1700000000, ..Default::default() }),
+            ForkId { hash: genesis_hash + 1700000000u64, next: 0 }
+        );
+
+        let header = chainspec.genesis_header();
+        assert_eq!(header.base_fee_per_gas, Some(EIP1559_INITIAL_BASE_FEE));
+        assert!(header.withdrawals_root.is_some());
+        assert!(header.blob_gas_used.is_none());
+    }
+
     #[test]
     fn hive_geth_json() {
         let hive_json = r#"
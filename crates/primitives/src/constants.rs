@@ -68,6 +68,10 @@ pub const GOERLI_GENESIS: H256 =
 pub const SEPOLIA_GENESIS: H256 =
     H256(hex!("25a5cc106eea7138acab33231d7160d69cb777ee0c2c553fcddf5138993e6dd9"));
 
+/// Holesky genesis hash.
+pub const HOLESKY_GENESIS: H256 =
+    H256(hex!("b5f7f912443c940f21fd611f12828d75b534364ed9e95ca4e307729a4661bde4"));
+
 /// Keccak256 over empty array.
 pub const KECCAK_EMPTY: H256 =
     H256(hex!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"));
@@ -89,6 +93,42 @@ pub const EMPTY_TRANSACTIONS: H256 = EMPTY_SET_HASH;
 /// Withdrawals root of empty withdrawals set.
 pub const EMPTY_WITHDRAWALS: H256 = EMPTY_SET_HASH;
 
+/// Size of a single field element of a blob in bytes.
+pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
+
+/// Number of field elements in a blob.
+pub const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
+
+/// Size of a blob in bytes.
+pub const BYTES_PER_BLOB: usize = BYTES_PER_FIELD_ELEMENT * FIELD_ELEMENTS_PER_BLOB;
+
+/// Size of a KZG commitment in bytes.
+pub const BYTES_PER_COMMITMENT: usize = 48;
+
+/// Size of a KZG proof in bytes.
+pub const BYTES_PER_PROOF: usize = 48;
+
+/// Gas consumption of a single blob, see [EIP-4844](https://eips.ethereum.org/EIPS/eip-4844).
+pub const DATA_GAS_PER_BLOB: u64 = 131_072; // 32*4096 = 131072 == 2^17 == 0x20000
+
+/// Maximum blob gas that can be consumed by a block.
+pub const MAX_DATA_GAS_PER_BLOCK: u64 = 786_432; // 0xC0000
+
+/// Target blob gas consumption of a block.
+pub const TARGET_DATA_GAS_PER_BLOCK: u64 = 393_216; // 0x60000
+
+/// Maximum number of blobs per block.
+pub const MAX_BLOBS_PER_BLOCK: usize = (MAX_DATA_GAS_PER_BLOCK / DATA_GAS_PER_BLOB) as usize;
+
+/// Minimum price of blob gas.
+pub const BLOB_TX_MIN_BLOB_GASPRICE: u128 = 1;
+
+/// Controls the maximum rate of change of the blob gas price.
+pub const BLOB_GASPRICE_UPDATE_FRACTION: u128 = 3_338_477;
+
+/// Version byte of blob versioned hashes that are derived from KZG commitments.
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,167 @@
+//! Helpers for working with [EIP-4844](https://eips.ethereum.org/EIPS/eip-4844) blob gas and KZG
+//! commitments.
+
+use crate::{
+    constants::{
+        BLOB_GASPRICE_UPDATE_FRACTION, BLOB_TX_MIN_BLOB_GASPRICE, TARGET_DATA_GAS_PER_BLOCK,
+        VERSIONED_HASH_VERSION_KZG,
+    },
+    H256,
+};
+use c_kzg::{BYTES_PER_G1_POINT, BYTES_PER_G2_POINT};
+use once_cell::sync::Lazy;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+pub use c_kzg::KzgSettings;
+
+/// The KZG trusted setup of the mainnet ceremony, which is used by all networks.
+pub static MAINNET_KZG_TRUSTED_SETUP: Lazy<Arc<KzgSettings>> = Lazy::new(|| {
+    Arc::new(
+        load_trusted_setup(include_str!("../res/eip4844/trusted_setup.txt"))
+            .expect("failed to load the mainnet KZG trusted setup"),
+    )
+});
+
+/// Loads a KZG trusted setup from its text format: the number of G1 and G2 points followed by the
+/// hex encoded points, one per line.
+pub fn load_trusted_setup(setup: &str) -> Result<KzgSettings, c_kzg::Error> {
+    let invalid = |msg: &str| c_kzg::Error::InvalidTrustedSetup(msg.to_string());
+
+    let mut lines = setup.lines().map(str::trim).filter(|line| !line.is_empty());
+    let mut count = || -> Result<usize, c_kzg::Error> {
+        lines
+            .next()
+            .and_then(|line| line.parse().ok())
+            .ok_or_else(|| invalid("missing number of points"))
+    };
+    let num_g1 = count()?;
+    let num_g2 = count()?;
+
+    let g1 = lines
+        .by_ref()
+        .take(num_g1)
+        .map(|line| {
+            hex::decode(line)
+                .ok()
+                .and_then(|bytes| <[u8; BYTES_PER_G1_POINT]>::try_from(bytes).ok())
+                .ok_or_else(|| invalid("invalid G1 point"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let g2 = lines
+        .by_ref()
+        .take(num_g2)
+        .map(|line| {
+            hex::decode(line)
+                .ok()
+                .and_then(|bytes| <[u8; BYTES_PER_G2_POINT]>::try_from(bytes).ok())
+                .ok_or_else(|| invalid("invalid G2 point"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if lines.next().is_some() {
+        return Err(invalid("unexpected trailing data"))
+    }
+
+    KzgSettings::load_trusted_setup(&g1, &g2)
+}
+
+/// Calculates the excess blob gas of the next block from the parent's excess and used blob gas.
+///
+/// Pre-Cancun parents are treated as having neither excess nor used blob gas.
+pub fn calculate_excess_blob_gas(parent_excess_blob_gas: u64, parent_blob_gas_used: u64) -> u64 {
+    (parent_excess_blob_gas + parent_blob_gas_used).saturating_sub(TARGET_DATA_GAS_PER_BLOCK)
+}
+
+/// Calculates the blob gas price from the header's excess blob gas.
+pub fn calculate_blob_gasprice(excess_blob_gas: u64) -> u128 {
+    fake_exponential(
+        BLOB_TX_MIN_BLOB_GASPRICE,
+        excess_blob_gas as u128,
+        BLOB_GASPRICE_UPDATE_FRACTION,
+    )
+}
+
+/// Approximates `factor * e ** (numerator / denominator)` using Taylor expansion.
+///
+/// Saturates instead of overflowing, see the reference implementation in the EIP.
+pub fn fake_exponential(factor: u128, numerator: u128, denominator: u128) -> u128 {
+    let mut i = 1;
+    let mut output: u128 = 0;
+    let mut numerator_accum = factor.saturating_mul(denominator);
+    while numerator_accum > 0 {
+        output = output.saturating_add(numerator_accum);
+        numerator_accum = numerator_accum.saturating_mul(numerator) / denominator.saturating_mul(i);
+        i += 1;
+    }
+    output / denominator
+}
+
+/// Computes the versioned hash of a KZG commitment: `VERSIONED_HASH_VERSION_KZG ++
+/// sha256(commitment)[1..]`.
+pub fn kzg_to_versioned_hash(commitment: &[u8]) -> H256 {
+    let mut hash: [u8; 32] = Sha256::digest(commitment).into();
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    H256(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::DATA_GAS_PER_BLOB;
+
+    #[test]
+    fn excess_blob_gas() {
+        let target_blobs = TARGET_DATA_GAS_PER_BLOCK / DATA_GAS_PER_BLOB;
+        for (excess, blobs, expected) in [
+            (0, 0, 0),
+            (0, 1, 0),
+            (0, target_blobs, 0),
+            (0, target_blobs + 1, DATA_GAS_PER_BLOB),
+            (1, target_blobs + 1, DATA_GAS_PER_BLOB + 1),
+            (1, target_blobs + 2, 2 * DATA_GAS_PER_BLOB + 1),
+            (TARGET_DATA_GAS_PER_BLOCK, 1, DATA_GAS_PER_BLOB),
+            (TARGET_DATA_GAS_PER_BLOCK, 0, 0),
+            (TARGET_DATA_GAS_PER_BLOCK, target_blobs, TARGET_DATA_GAS_PER_BLOCK),
+        ] {
+            assert_eq!(calculate_excess_blob_gas(excess, blobs * DATA_GAS_PER_BLOB), expected);
+        }
+    }
+
+    #[test]
+    fn load_mainnet_trusted_setup() {
+        Lazy::force(&MAINNET_KZG_TRUSTED_SETUP);
+        assert!(load_trusted_setup("4096\n65\n").is_err());
+    }
+
+    #[test]
+    fn blob_gasprice() {
+        // test vectors from the reference implementation
+        for (excess, expected) in
+            [(0, 1), (1, 1), (2_314_057, 1), (2_314_058, 2), (10 * 1024 * 1024, 23)]
+        {
+            assert_eq!(calculate_blob_gasprice(excess), expected, "excess {excess}");
+        }
+    }
+
+    #[test]
+    fn fake_exponential_vectors() {
+        for (factor, numerator, denominator, expected) in [
+            (1u128, 0u128, 1u128, 1u128),
+            (38493, 0, 1000, 38493),
+            (0, 1234, 2345, 0),
+            (1, 2, 1, 6), // approximate 7.389
+            (1, 4, 2, 6),
+            (1, 3, 1, 16), // approximate 20.09
+            (1, 6, 2, 18),
+            (1, 4, 1, 49), // approximate 54.60
+            (1, 8, 2, 50),
+            (10, 8, 2, 542), // approximate 540.598
+            (11, 8, 2, 596), // approximate 600.58
+            (1, 5, 1, 136),  // approximate 148.4
+            (1, 5, 2, 11),   // approximate 12.18
+            (2, 5, 2, 23),   // approximate 24.36
+        ] {
+            assert_eq!(fake_exponential(factor, numerator, denominator), expected);
+        }
+    }
+}
@@ -1,15 +1,15 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use crate::{
     keccak256,
     proofs::{KeccakHasher, EMPTY_ROOT},
-    serde_helper::deserialize_json_u256,
+    serde_helper::{deserialize_json_u256, JsonU256},
     utils::serde_helpers::deserialize_stringified_u64,
-    Account, Address, Bytes, H256, KECCAK_EMPTY, U256,
+    Account, Address, Bytes, CliqueConfig, H256, KECCAK_EMPTY, U256,
 };
-use ethers_core::utils::GenesisAccount as EthersGenesisAccount;
+use ethers_core::utils::{Genesis as EthersGenesis, GenesisAccount as EthersGenesisAccount};
 use reth_rlp::{encode_fixed_size, length_of_length, Encodable, Header as RlpHeader};
-use serde::{Deserialize, Serialize};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
 use triehash::sec_trie_root;
 
 /// The genesis block specification.
@@ -34,8 +34,18 @@ pub struct Genesis {
     pub mix_hash: H256,
     /// The genesis header coinbase address.
     pub coinbase: Address,
+    /// The genesis header base fee.
+    ///
+    /// If this is not set and London is active at genesis, the initial base fee of EIP-1559 is
+    /// used.
+    #[serde(skip_serializing_if = "Option::is_none", deserialize_with = "deserialize_opt_u64")]
+    pub base_fee_per_gas: Option<u64>,
     /// The initial state of accounts in the genesis block.
+    #[serde(deserialize_with = "deserialize_alloc")]
     pub alloc: HashMap<Address, GenesisAccount>,
+    /// The fork configuration of the chain.
+    #[serde(skip_serializing_if = "ChainConfig::is_empty")]
+    pub config: ChainConfig,
 }
 
 impl Genesis {
@@ -81,6 +91,18 @@ impl Genesis {
         self
     }
 
+    /// Set the base fee.
+    pub fn with_base_fee(mut self, base_fee: Option<u64>) -> Self {
+        self.base_fee_per_gas = base_fee;
+        self
+    }
+
+    /// Set the fork configuration.
+    pub fn with_config(mut self, config: ChainConfig) -> Self {
+        self.config = config;
+        self
+    }
+
     /// Add accounts to the genesis block. If the address is already present,
     /// the account is updated.
     pub fn extend_accounts(
@@ -97,7 +119,11 @@ impl Genesis {
 #[serde(deny_unknown_fields)]
 pub struct GenesisAccount {
     /// The nonce of the account at genesis.
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_opt_u64"
+    )]
     pub nonce: Option<u64>,
     /// The balance of the account at genesis.
     #[serde(deserialize_with = "deserialize_json_u256")]
@@ -106,7 +132,11 @@ pub struct GenesisAccount {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub code: Option<Bytes>,
     /// The account's storage at genesis.
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_storage"
+    )]
     pub storage: Option<HashMap<H256, H256>>,
 }
 
@@ -204,6 +234,233 @@ impl From<GenesisAccount> for Account {
     }
 }
 
+/// The fork configuration of a chain, i.e. the `config` object of a geth genesis file.
+///
+/// Fields that are not known to reth are kept in [ChainConfig::extra_fields], so the
+/// configuration round-trips.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainConfig {
+    /// The chain ID.
+    #[serde(default)]
+    pub chain_id: u64,
+    /// The Homestead block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homestead_block: Option<u64>,
+    /// The DAO fork block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dao_fork_block: Option<u64>,
+    /// Whether the chain supports the DAO fork.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dao_fork_support: bool,
+    /// The EIP-150 (Tangerine Whistle) block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eip150_block: Option<u64>,
+    /// The EIP-155 block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eip155_block: Option<u64>,
+    /// The EIP-158 (Spurious Dragon) block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eip158_block: Option<u64>,
+    /// The Byzantium block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub byzantium_block: Option<u64>,
+    /// The Constantinople block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub constantinople_block: Option<u64>,
+    /// The Petersburg block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub petersburg_block: Option<u64>,
+    /// The Istanbul block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub istanbul_block: Option<u64>,
+    /// The Muir Glacier block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub muir_glacier_block: Option<u64>,
+    /// The Berlin block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub berlin_block: Option<u64>,
+    /// The London block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub london_block: Option<u64>,
+    /// The Arrow Glacier block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arrow_glacier_block: Option<u64>,
+    /// The Gray Glacier block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gray_glacier_block: Option<u64>,
+    /// The block at which the network splits after the merge, used in the fork ID.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merge_netsplit_block: Option<u64>,
+    /// The Shanghai timestamp.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shanghai_time: Option<u64>,
+    /// The Cancun timestamp.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cancun_time: Option<u64>,
+    /// The total difficulty at which the merge happens.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_ttd",
+        serialize_with = "serialize_ttd"
+    )]
+    pub terminal_total_difficulty: Option<U256>,
+    /// Whether the terminal total difficulty has been reached.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub terminal_total_difficulty_passed: bool,
+    /// The ethash configuration, if the chain runs proof-of-work before the merge.
+    ///
+    /// This is an empty object in geth.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ethash: Option<serde_json::Value>,
+    /// The clique configuration, if the chain runs proof-of-authority before the merge.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clique: Option<CliqueConfig>,
+    /// The fields of the configuration that are not known to reth.
+    #[serde(flatten)]
+    pub extra_fields: BTreeMap<String, serde_json::Value>,
+}
+
+impl ChainConfig {
+    /// Returns true if nothing is configured.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+impl From<EthersGenesis> for Genesis {
+    fn from(genesis: EthersGenesis) -> Self {
+        let alloc = genesis
+            .alloc
+            .iter()
+            .map(|(addr, account)| (addr.0.into(), account.clone().into()))
+            .collect::<HashMap<Address, GenesisAccount>>();
+
+        let config = genesis.config;
+        let config = ChainConfig {
+            chain_id: config.chain_id,
+            homestead_block: config.homestead_block,
+            dao_fork_block: config.dao_fork_block,
+            eip150_block: config.eip150_block,
+            eip155_block: config.eip155_block,
+            byzantium_block: config.byzantium_block,
+            constantinople_block: config.constantinople_block,
+            petersburg_block: config.petersburg_block,
+            istanbul_block: config.istanbul_block,
+            muir_glacier_block: config.muir_glacier_block,
+            berlin_block: config.berlin_block,
+            london_block: config.london_block,
+            arrow_glacier_block: config.arrow_glacier_block,
+            gray_glacier_block: config.gray_glacier_block,
+            merge_netsplit_block: config.merge_netsplit_block,
+            shanghai_time: config.shanghai_time,
+            terminal_total_difficulty: config.terminal_total_difficulty.map(Into::into),
+            clique: config.clique.map(|clique| CliqueConfig {
+                period: clique.period.unwrap_or_default(),
+                epoch: clique.epoch.unwrap_or(CliqueConfig::DEFAULT_EPOCH),
+            }),
+            ..Default::default()
+        };
+
+        Genesis {
+            nonce: genesis.nonce.as_u64(),
+            timestamp: genesis.timestamp.as_u64(),
+            gas_limit: genesis.gas_limit.as_u64(),
+            difficulty: genesis.difficulty.into(),
+            mix_hash: genesis.mix_hash.0.into(),
+            coinbase: genesis.coinbase.0.into(),
+            extra_data: genesis.extra_data.0.into(),
+            base_fee_per_gas: None,
+            alloc,
+            config,
+        }
+    }
+}
+
+/// Deserializes an optional `u64` from a number or a hex or decimal string.
+fn deserialize_opt_u64<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<JsonU256>::deserialize(deserializer)?
+        .map(|num| u64::try_from(num.0).map_err(D::Error::custom))
+        .transpose()
+}
+
+/// Deserializes the terminal total difficulty, which geth encodes as a json number that does not
+/// necessarily fit into a `u64`.
+fn deserialize_ttd<'de, D>(deserializer: D) -> Result<Option<U256>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<JsonU256>::deserialize(deserializer)?.map(Into::into))
+}
+
+/// Serializes the terminal total difficulty as a json number if it fits into a `u128`, like geth.
+fn serialize_ttd<S>(ttd: &Option<U256>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match ttd.map(u128::try_from) {
+        Some(Ok(ttd)) => serializer.serialize_u128(ttd),
+        _ => ttd.serialize(serializer),
+    }
+}
+
+/// Deserializes the genesis allocation, accepting addresses with and without the `0x` prefix.
+fn deserialize_alloc<'de, D>(deserializer: D) -> Result<HashMap<Address, GenesisAccount>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    HashMap::<String, GenesisAccount>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(address, account)| {
+            let bytes = decode_hex(&address).map_err(D::Error::custom)?;
+            if bytes.len() != Address::len_bytes() {
+                return Err(D::Error::custom(format!("invalid address {address}")))
+            }
+            Ok((Address::from_slice(&bytes), account))
+        })
+        .collect()
+}
+
+/// Deserializes account storage, accepting keys and values that are shorter than 32 bytes.
+fn deserialize_storage<'de, D>(deserializer: D) -> Result<Option<HashMap<H256, H256>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(storage) = Option::<HashMap<String, String>>::deserialize(deserializer)? else {
+        return Ok(None)
+    };
+
+    let to_word = |value: &str| -> Result<H256, D::Error> {
+        let bytes = decode_hex(value).map_err(D::Error::custom)?;
+        if bytes.len() > 32 {
+            return Err(D::Error::custom(format!("storage word {value} exceeds 32 bytes")))
+        }
+        let mut word = H256::zero();
+        word[32 - bytes.len()..].copy_from_slice(&bytes);
+        Ok(word)
+    };
+
+    storage
+        .iter()
+        .map(|(key, value)| Ok((to_word(key)?, to_word(value)?)))
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+
+/// Decodes a hex string with an optional `0x` prefix and an optional leading zero.
+fn decode_hex(value: &str) -> Result<Vec<u8>, hex::FromHexError> {
+    let value = value.strip_prefix("0x").unwrap_or(value);
+    if value.len() % 2 == 1 {
+        hex::decode(format!("0{value}"))
+    } else {
+        hex::decode(value)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -303,4 +560,76 @@ mod tests {
         assert_eq!(genesis_account.code, code);
         assert_eq!(genesis_account.storage, storage);
     }
+
+    #[test]
+    fn geth_genesis_roundtrip() {
+        let geth_genesis = r#"
+        {
+          "config": {
+            "chainId": 1337,
+            "homesteadBlock": 0,
+            "daoForkSupport": true,
+            "eip150Block": 0,
+            "eip155Block": 0,
+            "eip158Block": 0,
+            "byzantiumBlock": 0,
+            "constantinopleBlock": 0,
+            "petersburgBlock": 0,
+            "istanbulBlock": 0,
+            "berlinBlock": 0,
+            "londonBlock": 0,
+            "mergeNetsplitBlock": 10,
+            "shanghaiTime": 1000,
+            "cancunTime": 2000,
+            "terminalTotalDifficulty": 58750000000000000000000,
+            "terminalTotalDifficultyPassed": true,
+            "depositContractAddress": "0x4242424242424242424242424242424242424242",
+            "clique": { "period": 5 }
+          },
+          "nonce": "0x0",
+          "timestamp": "0x0",
+          "gasLimit": 30000000,
+          "difficulty": "0x1",
+          "baseFeePerGas": "0x7",
+          "alloc": {
+            "0x658bdf435d810c91414ec09147daa6db62406379": {
+              "balance": "0x1",
+              "nonce": 2,
+              "storage": { "0x01": "0x2a" }
+            }
+          }
+        }
+        "#;
+
+        let genesis: Genesis = serde_json::from_str(geth_genesis).unwrap();
+        assert_eq!(genesis.base_fee_per_gas, Some(7));
+
+        let config = &genesis.config;
+        assert_eq!(config.chain_id, 1337);
+        assert!(config.dao_fork_support);
+        assert_eq!(config.merge_netsplit_block, Some(10));
+        assert_eq!(config.shanghai_time, Some(1000));
+        assert_eq!(config.cancun_time, Some(2000));
+        assert_eq!(
+            config.terminal_total_difficulty,
+            Some(U256::from(58_750_000_000_000_000_000_000u128))
+        );
+        assert_eq!(config.clique, Some(CliqueConfig { period: 5, epoch: 30000 }));
+        assert_eq!(
+            config.extra_fields.get("depositContractAddress"),
+            Some(&serde_json::json!("0x4242424242424242424242424242424242424242"))
+        );
+
+        let account = &genesis.alloc[&hex!("658bdf435d810c91414ec09147daa6db62406379").into()];
+        assert_eq!(account.nonce, Some(2));
+        assert_eq!(
+            account.storage,
+            Some(HashMap::from([(H256::from_low_u64_be(1), H256::from_low_u64_be(42))]))
+        );
+
+        let serialized = serde_json::to_string(&genesis).unwrap();
+        assert!(serialized.contains(r#""terminalTotalDifficulty":58750000000000000000000"#));
+        assert!(serialized.contains(r#""depositContractAddress""#));
+        assert_eq!(serde_json::from_str::<Genesis>(&serialized).unwrap(), genesis);
+    }
 }
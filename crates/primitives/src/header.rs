@@ -1,5 +1,6 @@
 use crate::{
     basefee::calculate_next_block_base_fee,
+    eip4844::{calculate_blob_gasprice, calculate_excess_blob_gas},
     keccak256,
     proofs::{EMPTY_LIST_HASH, EMPTY_ROOT},
     BlockHash, BlockNumHash, BlockNumber, Bloom, Bytes, H160, H256, U256,
@@ -88,6 +89,20 @@ pub struct Header {
     /// above the gas target, and decreasing when blocks are below the gas target. The base fee per
     /// gas is burned.
     pub base_fee_per_gas: Option<u64>,
+    /// The total amount of blob gas consumed by the transactions within the block, added in
+    /// [EIP-4844](https://eips.ethereum.org/EIPS/eip-4844).
+    pub blob_gas_used: Option<u64>,
+    /// A running total of blob gas consumed in excess of the target, prior to the block. Blocks
+    /// with above-target blob gas consumption increase this value, blocks with below-target blob
+    /// gas consumption decrease it (bounded at 0). This was added in
+    /// [EIP-4844](https://eips.ethereum.org/EIPS/eip-4844).
+    pub excess_blob_gas: Option<u64>,
+    /// The hash of the parent beacon block's root is included in execution blocks, as proposed by
+    /// [EIP-4788](https://eips.ethereum.org/EIPS/eip-4788).
+    ///
+    /// This enables trust-minimized access to consensus state, supporting staking pools, bridges,
+    /// and more.
+    pub parent_beacon_block_root: Option<H256>,
     /// An arbitrary byte array containing data relevant to this block. This must be 32 bytes or
     /// fewer; formally Hx.
     pub extra_data: Bytes,
@@ -113,6 +128,9 @@ impl Default for Header {
             nonce: 0,
             base_fee_per_gas: None,
             withdrawals_root: None,
+            blob_gas_used: None,
+            excess_blob_gas: None,
+            parent_beacon_block_root: None,
         }
     }
 }
@@ -158,6 +176,27 @@ impl Header {
         Some(calculate_next_block_base_fee(self.gas_used, self.gas_limit, self.base_fee_per_gas?))
     }
 
+    /// Calculate the excess blob gas for the next block according to the EIP-4844 spec.
+    ///
+    /// Returns a `None` if no excess blob gas is set, no EIP-4844 support
+    pub fn next_block_excess_blob_gas(&self) -> Option<u64> {
+        Some(calculate_excess_blob_gas(self.excess_blob_gas?, self.blob_gas_used?))
+    }
+
+    /// Returns the blob gas price of this block according to the EIP-4844 spec.
+    ///
+    /// Returns a `None` if no excess blob gas is set, no EIP-4844 support
+    pub fn blob_gasprice(&self) -> Option<u128> {
+        Some(calculate_blob_gasprice(self.excess_blob_gas?))
+    }
+
+    /// Returns the blob gas price of the next block according to the EIP-4844 spec.
+    ///
+    /// Returns a `None` if no excess blob gas is set, no EIP-4844 support
+    pub fn next_block_blob_fee(&self) -> Option<u128> {
+        Some(calculate_blob_gasprice(self.next_block_excess_blob_gas()?))
+    }
+
     /// Seal the header with a known hash.
     ///
     /// WARNING: This method does not perform validation whether the hash is correct.
@@ -191,15 +230,37 @@ impl Header {
 
         if let Some(base_fee) = self.base_fee_per_gas {
             length += U256::from(base_fee).length();
-        } else if self.withdrawals_root.is_some() {
+        } else if self.withdrawals_root.is_some() || self.has_blob_gas_fields() {
             length += 1; // EMTY STRING CODE
         }
         if let Some(root) = self.withdrawals_root {
             length += root.length();
+        } else if self.has_blob_gas_fields() {
+            length += 1; // EMTY STRING CODE
+        }
+        if let Some(blob_gas_used) = self.blob_gas_used {
+            length += U256::from(blob_gas_used).length();
+        } else if self.excess_blob_gas.is_some() || self.parent_beacon_block_root.is_some() {
+            length += 1; // EMTY STRING CODE
+        }
+        if let Some(excess_blob_gas) = self.excess_blob_gas {
+            length += U256::from(excess_blob_gas).length();
+        } else if self.parent_beacon_block_root.is_some() {
+            length += 1; // EMTY STRING CODE
+        }
+        if let Some(parent_beacon_block_root) = self.parent_beacon_block_root {
+            length += parent_beacon_block_root.length();
         }
 
         length
     }
+
+    /// Whether any of the EIP-4844 or later fields are set.
+    fn has_blob_gas_fields(&self) -> bool {
+        self.blob_gas_used.is_some() ||
+            self.excess_blob_gas.is_some() ||
+            self.parent_beacon_block_root.is_some()
+    }
 }
 
 impl Encodable for Header {
@@ -227,12 +288,36 @@ impl Encodable for Header {
         // but withdrawals root is present.
         if let Some(ref base_fee) = self.base_fee_per_gas {
             U256::from(*base_fee).encode(out);
-        } else if self.withdrawals_root.is_some() {
+        } else if self.withdrawals_root.is_some() || self.has_blob_gas_fields() {
             out.put_u8(EMPTY_STRING_CODE);
         }
 
+        // Encode withdrawals root. Put empty string if withdrawals root is missing,
+        // but blob gas fields are present.
         if let Some(ref root) = self.withdrawals_root {
             root.encode(out);
+        } else if self.has_blob_gas_fields() {
+            out.put_u8(EMPTY_STRING_CODE);
+        }
+
+        // Encode blob gas used. Put empty string if blob gas used is missing,
+        // but excess blob gas or parent beacon block root is present.
+        if let Some(ref blob_gas_used) = self.blob_gas_used {
+            U256::from(*blob_gas_used).encode(out);
+        } else if self.excess_blob_gas.is_some() || self.parent_beacon_block_root.is_some() {
+            out.put_u8(EMPTY_STRING_CODE);
+        }
+
+        // Encode excess blob gas. Put empty string if excess blob gas is missing,
+        // but parent beacon block root is present.
+        if let Some(ref excess_blob_gas) = self.excess_blob_gas {
+            U256::from(*excess_blob_gas).encode(out);
+        } else if self.parent_beacon_block_root.is_some() {
+            out.put_u8(EMPTY_STRING_CODE);
+        }
+
+        if let Some(ref parent_beacon_block_root) = self.parent_beacon_block_root {
+            parent_beacon_block_root.encode(out);
         }
     }
 
@@ -269,6 +354,9 @@ impl Decodable for Header {
             nonce: H64::decode(buf)?.to_low_u64_be(),
             base_fee_per_gas: None,
             withdrawals_root: None,
+            blob_gas_used: None,
+            excess_blob_gas: None,
+            parent_beacon_block_root: None,
         };
         if started_len - buf.len() < rlp_head.payload_length {
             if buf.first().map(|b| *b == EMPTY_STRING_CODE).unwrap_or_default() {
@@ -278,7 +366,22 @@ impl Decodable for Header {
             }
         }
         if started_len - buf.len() < rlp_head.payload_length {
-            this.withdrawals_root = Some(Decodable::decode(buf)?);
+            if buf.first().map(|b| *b == EMPTY_STRING_CODE).unwrap_or_default() {
+                buf.advance(1)
+            } else {
+                this.withdrawals_root = Some(Decodable::decode(buf)?);
+            }
+        }
+        // Blob gas used can't be told apart from the empty string placeholder because zero is
+        // encoded as empty string as well, but it's never missing if excess blob gas is present.
+        if started_len - buf.len() < rlp_head.payload_length {
+            this.blob_gas_used = Some(U256::decode(buf)?.to::<u64>());
+        }
+        if started_len - buf.len() < rlp_head.payload_length {
+            this.excess_blob_gas = Some(U256::decode(buf)?.to::<u64>());
+        }
+        if started_len - buf.len() < rlp_head.payload_length {
+            this.parent_beacon_block_root = Some(Decodable::decode(buf)?);
         }
         let consumed = started_len - buf.len();
         if consumed != rlp_head.payload_length {
@@ -330,7 +433,18 @@ impl proptest::arbitrary::Arbitrary for SealedHeader {
     fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
         use proptest::prelude::{any, Strategy};
 
-        any::<(Header, BlockHash)>().prop_map(move |(header, _)| header.seal_slow()).boxed()
+        any::<(Header, BlockHash)>()
+            .prop_map(move |(mut header, _)| {
+                // Otherwise the header does not survive the rlp roundtrip
+                if header.parent_beacon_block_root.is_some() {
+                    header.excess_blob_gas.get_or_insert(0);
+                }
+                if header.excess_blob_gas.is_some() {
+                    header.blob_gas_used.get_or_insert(0);
+                }
+                header.seal_slow()
+            })
+            .boxed()
     }
 
     type Strategy = proptest::strategy::BoxedStrategy<SealedHeader>;
@@ -339,7 +453,15 @@ impl proptest::arbitrary::Arbitrary for SealedHeader {
 #[cfg(any(test, feature = "arbitrary"))]
 impl<'a> arbitrary::Arbitrary<'a> for SealedHeader {
     fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
-        Ok(Header::arbitrary(u)?.seal_slow())
+        let mut header = Header::arbitrary(u)?;
+        // Otherwise the header does not survive the rlp roundtrip
+        if header.parent_beacon_block_root.is_some() {
+            header.excess_blob_gas.get_or_insert(0);
+        }
+        if header.excess_blob_gas.is_some() {
+            header.blob_gas_used.get_or_insert(0);
+        }
+        Ok(header.seal_slow())
     }
 }
 
@@ -362,6 +484,9 @@ impl From<&Block<EthersH256>> for Header {
             ommers_hash: block.uncles_hash.0.into(),
             gas_used: block.gas_used.as_u64(),
             withdrawals_root: None,
+            blob_gas_used: None,
+            excess_blob_gas: None,
+            parent_beacon_block_root: None,
             logs_bloom: block.logs_bloom.unwrap_or_default().0.into(),
         }
     }
@@ -560,6 +685,9 @@ mod tests {
             nonce: 0,
             base_fee_per_gas: Some(0x036b_u64),
             withdrawals_root: None,
+            blob_gas_used: None,
+            excess_blob_gas: None,
+            parent_beacon_block_root: None,
         };
         assert_eq!(header.hash_slow(), expected_hash);
     }
@@ -638,6 +766,61 @@ mod tests {
         assert_eq!(header.hash_slow(), expected_hash);
     }
 
+    #[test]
+    fn blob_gas_fields_roundtrip() {
+        for (blob_gas_used, excess_blob_gas) in
+            [(Some(0), Some(0)), (Some(0x20000), Some(0)), (Some(0), Some(0x60000))]
+        {
+            let header = Header {
+                base_fee_per_gas: Some(7),
+                withdrawals_root: Some(H256::random()),
+                blob_gas_used,
+                excess_blob_gas,
+                ..Default::default()
+            };
+            let mut data = vec![];
+            header.encode(&mut data);
+            assert_eq!(header.length(), data.len());
+            assert_eq!(<Header as Decodable>::decode(&mut data.as_slice()).unwrap(), header);
+        }
+
+        // pre-shanghai fields are encoded as empty strings
+        let header =
+            Header { blob_gas_used: Some(0x20000), excess_blob_gas: Some(0), ..Default::default() };
+        let mut data = vec![];
+        header.encode(&mut data);
+        assert_eq!(header.length(), data.len());
+        assert_eq!(<Header as Decodable>::decode(&mut data.as_slice()).unwrap(), header);
+    }
+
+    #[test]
+    fn parent_beacon_block_root_roundtrip() {
+        let header = Header {
+            base_fee_per_gas: Some(7),
+            withdrawals_root: Some(H256::random()),
+            blob_gas_used: Some(0x20000),
+            excess_blob_gas: Some(0),
+            parent_beacon_block_root: Some(H256::random()),
+            ..Default::default()
+        };
+        let mut data = vec![];
+        header.encode(&mut data);
+        assert_eq!(header.length(), data.len());
+        assert_eq!(<Header as Decodable>::decode(&mut data.as_slice()).unwrap(), header);
+    }
+
+    #[test]
+    fn next_block_excess_blob_gas() {
+        let header = Header {
+            blob_gas_used: Some(0xC0000),
+            excess_blob_gas: Some(0x60000),
+            ..Default::default()
+        };
+        assert_eq!(header.next_block_excess_blob_gas(), Some(0xC0000));
+        assert_eq!(header.blob_gasprice(), Some(1));
+        assert_eq!(Header::default().next_block_excess_blob_gas(), None);
+    }
+
     #[test]
     fn sanity_direction() {
         let reverse = true;
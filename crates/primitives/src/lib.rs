@@ -19,6 +19,7 @@ mod chain;
 mod compression;
 pub mod constants;
 pub mod contract;
+pub mod eip4844;
 mod forkid;
 mod genesis;
 mod hardfork;
@@ -31,10 +32,12 @@ mod net;
 mod peer;
 mod receipt;
 pub mod stage;
+mod state_diff;
 mod storage;
 mod transaction;
 pub mod trie;
 mod withdrawal;
+mod witness;
 
 /// Helper function for calculating Merkle proofs and hashes
 pub mod proofs;
@@ -47,15 +50,16 @@ pub use block::{
 };
 pub use bloom::Bloom;
 pub use chain::{
-    AllGenesisFormats, Chain, ChainInfo, ChainSpec, ChainSpecBuilder, ForkCondition, GOERLI,
-    MAINNET, SEPOLIA,
+    AllGenesisFormats, Chain, ChainInfo, ChainSpec, ChainSpecBuilder, CliqueConfig, DevnetAccount,
+    DevnetConfig, DevnetConfigError, ForkCondition, GOERLI, HOLESKY, MAINNET, SEPOLIA,
 };
 pub use compression::*;
 pub use constants::{
-    EMPTY_OMMER_ROOT, GOERLI_GENESIS, KECCAK_EMPTY, MAINNET_GENESIS, SEPOLIA_GENESIS,
+    EMPTY_OMMER_ROOT, GOERLI_GENESIS, HOLESKY_GENESIS, KECCAK_EMPTY, MAINNET_GENESIS,
+    SEPOLIA_GENESIS,
 };
 pub use forkid::{ForkFilter, ForkHash, ForkId, ForkTransition, ValidationError};
-pub use genesis::{Genesis, GenesisAccount};
+pub use genesis::{ChainConfig, Genesis, GenesisAccount};
 pub use hardfork::Hardfork;
 pub use header::{Head, Header, HeadersDirection, SealedHeader};
 pub use hex_bytes::Bytes;
@@ -69,16 +73,19 @@ pub use peer::{PeerId, WithPeerId};
 pub use receipt::{Receipt, ReceiptWithBloom, ReceiptWithBloomRef};
 pub use revm_primitives::JumpMap;
 pub use serde_helper::JsonU256;
+pub use state_diff::{AccountDiff, BlockStateDiff, ValueChange};
 pub use storage::StorageEntry;
 pub use transaction::{
     util::secp256k1::{recover_signer, sign_message},
-    AccessList, AccessListItem, AccessListWithGasUsed, FromRecoveredTransaction,
-    IntoRecoveredTransaction, InvalidTransactionError, Signature, Transaction, TransactionKind,
+    AccessList, AccessListItem, AccessListWithGasUsed, BlobTransaction, BlobTransactionSidecar,
+    BlobTransactionValidationError, FromRecoveredTransaction, IntoRecoveredTransaction,
+    InvalidTransactionError, PooledTransactionsElement, Signature, Transaction, TransactionKind,
     TransactionMeta, TransactionSigned, TransactionSignedEcRecovered, TransactionSignedNoHash,
-    TxEip1559, TxEip2930, TxLegacy, TxType, EIP1559_TX_TYPE_ID, EIP2930_TX_TYPE_ID,
-    LEGACY_TX_TYPE_ID,
+    TxEip1559, TxEip2930, TxEip4844, TxLegacy, TxType, EIP1559_TX_TYPE_ID, EIP2930_TX_TYPE_ID,
+    EIP4844_TX_TYPE_ID, LEGACY_TX_TYPE_ID,
 };
 pub use withdrawal::Withdrawal;
+pub use witness::ExecutionWitness;
 
 /// A block hash.
 pub type BlockHash = H256;
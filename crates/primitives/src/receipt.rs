@@ -141,6 +141,9 @@ impl Decodable for ReceiptWithBloom {
                 } else if receipt_type == 0x02 {
                     buf.advance(1);
                     Self::decode_receipt(buf, TxType::EIP1559)
+                } else if receipt_type == 0x03 {
+                    buf.advance(1);
+                    Self::decode_receipt(buf, TxType::EIP4844)
                 } else {
                     Err(reth_rlp::DecodeError::Custom("invalid receipt type"))
                 }
@@ -245,6 +248,9 @@ impl<'a> ReceiptWithBloomEncoder<'a> {
             TxType::EIP1559 => {
                 out.put_u8(0x02);
             }
+            TxType::EIP4844 => {
+                out.put_u8(0x03);
+            }
             _ => unreachable!("legacy handled; qed."),
         }
         out.put_slice(payload.as_ref());
@@ -264,7 +270,7 @@ impl<'a> Encodable for ReceiptWithBloomEncoder<'a> {
     fn length(&self) -> usize {
         let mut payload_len = self.receipt_length();
         // account for eip-2718 type prefix and set the list
-        if matches!(self.receipt.tx_type, TxType::EIP1559 | TxType::EIP2930) {
+        if matches!(self.receipt.tx_type, TxType::EIP1559 | TxType::EIP2930 | TxType::EIP4844) {
             payload_len += 1;
             // we include a string header for typed receipts, so include the length here
             payload_len += length_of_length(payload_len);
@@ -55,6 +55,18 @@ impl<'a> Visitor<'a> for JsonU256Visitor {
         Ok(JsonU256(U256::from(value)))
     }
 
+    fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        // json numbers that don't fit into a u64, like the terminal total difficulty in geth
+        // genesis files, are parsed as floats
+        if !value.is_finite() || value < 0.0 || value.fract() != 0.0 {
+            return Err(Error::custom(format!("Parsing JsonU256 from float failed {value}")))
+        }
+        self.visit_str(&value.to_string())
+    }
+
     fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
     where
         E: Error,
@@ -119,4 +131,13 @@ mod test {
 
         assert_eq!(serialized, r#""0x10""#);
     }
+
+    #[test]
+    fn jsonu256_deserialize_big_number() {
+        let deserialized: JsonU256 = serde_json::from_str("58750000000000000000000").unwrap();
+        assert_eq!(deserialized, JsonU256(U256::from(58_750_000_000_000_000_000_000u128)));
+
+        assert!(serde_json::from_str::<JsonU256>("1.5").is_err());
+        assert!(serde_json::from_str::<JsonU256>("-1").is_err());
+    }
 }
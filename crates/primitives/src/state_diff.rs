@@ -0,0 +1,57 @@
+use crate::{Account, Address, BlockHash, BlockNumber, Bytes, H256, U256};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The value of a piece of state before and after a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ValueChange<T> {
+    /// The value before the block.
+    pub pre: T,
+    /// The value after the block.
+    pub post: T,
+}
+
+impl<T> ValueChange<T> {
+    /// Creates a new change from the value before and after the block.
+    pub fn new(pre: T, post: T) -> Self {
+        Self { pre, post }
+    }
+}
+
+/// The changes a block made to a single account.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountDiff {
+    /// The account before and after the block, `None` if the account does not exist.
+    ///
+    /// Not set if only the storage of the account changed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info: Option<ValueChange<Option<Account>>>,
+    /// Whether the storage of the account was cleared by a selfdestruct in the block.
+    ///
+    /// The cleared slots that were not changed earlier in the same chain of blocks are not part
+    /// of `storage`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub storage_wiped: bool,
+    /// The changed storage slots.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<H256, ValueChange<U256>>,
+}
+
+/// The canonical state changes of a single block.
+///
+/// The code changes of an account are reflected by the code hash of its
+/// [info](AccountDiff::info), the bytecode deployed in the block can be found in
+/// [codes](BlockStateDiff::codes).
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockStateDiff {
+    /// The number of the block.
+    pub number: BlockNumber,
+    /// The hash of the block.
+    pub hash: BlockHash,
+    /// The changed accounts.
+    pub accounts: BTreeMap<Address, AccountDiff>,
+    /// The bytecode deployed in the block, keyed by the code hash.
+    pub codes: BTreeMap<H256, Bytes>,
+}
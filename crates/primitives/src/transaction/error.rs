@@ -25,6 +25,22 @@ pub enum InvalidTransactionError {
     /// The transaction requires EIP-1559 which is not enabled currently.
     #[error("EIP-1559 transactions are not valid before London.")]
     Eip1559Disabled,
+    /// The transaction requires EIP-4844 which is not enabled currently.
+    #[error("EIP-4844 transactions are not valid before Cancun.")]
+    Eip4844Disabled,
+    /// The blob transaction does not carry any blobs.
+    #[error("EIP-4844 transactions must have at least one blob.")]
+    BlobTransactionMissingBlobHashes,
+    /// The blob transaction tries to create a contract.
+    #[error("EIP-4844 transactions can't create contracts.")]
+    BlobTransactionIsCreate,
+    /// A blob versioned hash has an unsupported version.
+    #[error("Blob versioned hash has an invalid version.")]
+    BlobVersionedHashVersionInvalid,
+    /// Thrown post Cancun if the transaction's blob fee is less than the blob gas price of the
+    /// block.
+    #[error("Max fee per blob gas less than block blob gas price")]
+    BlobFeeCapTooLow,
     /// Thrown if a transaction is not supported in the current network configuration.
     #[error("Transaction type not supported")]
     TxTypeNotSupported,
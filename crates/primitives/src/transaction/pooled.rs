@@ -0,0 +1,185 @@
+use crate::{
+    BlobTransaction, BlobTransactionSidecar, Bytes, TransactionSigned, TxHash, EIP4844_TX_TYPE_ID,
+};
+use reth_codecs::add_arbitrary_tests;
+use reth_rlp::{Decodable, DecodeError, Encodable, Header, EMPTY_LIST_CODE};
+use serde::{Deserialize, Serialize};
+
+/// A transaction in the form in which it's exchanged in `PooledTransactions` responses.
+///
+/// This is identical to the transaction as it appears in a block, except for blob transactions
+/// which also carry their [BlobTransactionSidecar].
+#[add_arbitrary_tests(rlp)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PooledTransactionsElement {
+    /// A transaction without a sidecar.
+    Transaction(TransactionSigned),
+    /// A blob transaction with its sidecar.
+    BlobTransaction(BlobTransaction),
+}
+
+impl PooledTransactionsElement {
+    /// Returns the transaction hash.
+    pub fn hash(&self) -> TxHash {
+        match self {
+            PooledTransactionsElement::Transaction(tx) => tx.hash(),
+            PooledTransactionsElement::BlobTransaction(tx) => tx.hash(),
+        }
+    }
+
+    /// Returns the transaction as it appears in a block, dropping the sidecar if there is one.
+    pub fn into_transaction(self) -> TransactionSigned {
+        match self {
+            PooledTransactionsElement::Transaction(tx) => tx,
+            PooledTransactionsElement::BlobTransaction(tx) => tx.into_parts().0,
+        }
+    }
+
+    /// Splits the element into the transaction as it appears in a block and the sidecar, if this
+    /// is a blob transaction.
+    pub fn into_parts(self) -> (TransactionSigned, Option<BlobTransactionSidecar>) {
+        match self {
+            PooledTransactionsElement::Transaction(tx) => (tx, None),
+            PooledTransactionsElement::BlobTransaction(tx) => {
+                let (tx, sidecar) = tx.into_parts();
+                (tx, Some(sidecar))
+            }
+        }
+    }
+
+    /// Decodes the "raw" format of transaction (e.g. `eth_sendRawTransaction`).
+    ///
+    /// Unlike [TransactionSigned::decode_enveloped], blob transactions are expected to be in the
+    /// network format that includes the sidecar.
+    pub fn decode_enveloped(tx: Bytes) -> Result<Self, DecodeError> {
+        let mut data = tx.as_ref();
+
+        if data.first() == Some(&EIP4844_TX_TYPE_ID) {
+            Ok(PooledTransactionsElement::BlobTransaction(BlobTransaction::decode_enveloped_inner(
+                &mut data,
+            )?))
+        } else {
+            Ok(PooledTransactionsElement::Transaction(TransactionSigned::decode_enveloped(tx)?))
+        }
+    }
+
+    /// Encodes the transaction into the "raw" format (e.g. `eth_sendRawTransaction`).
+    pub fn encode_enveloped(&self, out: &mut dyn bytes::BufMut) {
+        match self {
+            PooledTransactionsElement::Transaction(tx) => tx.encode_enveloped(out),
+            PooledTransactionsElement::BlobTransaction(tx) => tx.encode_enveloped(out),
+        }
+    }
+}
+
+impl From<TransactionSigned> for PooledTransactionsElement {
+    fn from(tx: TransactionSigned) -> Self {
+        PooledTransactionsElement::Transaction(tx)
+    }
+}
+
+impl From<BlobTransaction> for PooledTransactionsElement {
+    fn from(tx: BlobTransaction) -> Self {
+        PooledTransactionsElement::BlobTransaction(tx)
+    }
+}
+
+impl Encodable for PooledTransactionsElement {
+    fn encode(&self, out: &mut dyn bytes::BufMut) {
+        match self {
+            PooledTransactionsElement::Transaction(tx) => tx.encode(out),
+            PooledTransactionsElement::BlobTransaction(tx) => tx.encode(out),
+        }
+    }
+
+    fn length(&self) -> usize {
+        match self {
+            PooledTransactionsElement::Transaction(tx) => tx.length(),
+            PooledTransactionsElement::BlobTransaction(tx) => tx.length(),
+        }
+    }
+}
+
+/// Decodes a transaction as it appears in a `PooledTransactions` list.
+///
+/// CAUTION: this expects that the given buf contains rlp
+impl Decodable for PooledTransactionsElement {
+    fn decode(buf: &mut &[u8]) -> Result<Self, DecodeError> {
+        // legacy transactions are encoded as a list, everything else is a string with the type as
+        // its first byte
+        if buf.first().map(|b| *b >= EMPTY_LIST_CODE).unwrap_or_default() {
+            return Ok(PooledTransactionsElement::Transaction(TransactionSigned::decode(buf)?))
+        }
+
+        let mut peek = *buf;
+        let header = Header::decode(&mut peek)?;
+        if header.list {
+            return Err(DecodeError::UnexpectedList)
+        }
+
+        if peek.first() == Some(&EIP4844_TX_TYPE_ID) {
+            Ok(PooledTransactionsElement::BlobTransaction(BlobTransaction::decode(buf)?))
+        } else {
+            Ok(PooledTransactionsElement::Transaction(TransactionSigned::decode(buf)?))
+        }
+    }
+}
+
+#[cfg(any(test, feature = "arbitrary"))]
+impl proptest::arbitrary::Arbitrary for PooledTransactionsElement {
+    type Parameters = ();
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use proptest::prelude::{any, Strategy};
+
+        any::<TransactionSigned>().prop_map(PooledTransactionsElement::from_arbitrary).boxed()
+    }
+
+    type Strategy = proptest::strategy::BoxedStrategy<PooledTransactionsElement>;
+}
+
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for PooledTransactionsElement {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let tx = <TransactionSigned as arbitrary::Arbitrary>::arbitrary(u)?;
+        Ok(PooledTransactionsElement::from_arbitrary(tx))
+    }
+}
+
+#[cfg(any(test, feature = "arbitrary"))]
+impl PooledTransactionsElement {
+    /// Blob transactions are always gossiped with their sidecar, so they need to be wrapped to
+    /// survive the rlp roundtrip.
+    fn from_arbitrary(tx: TransactionSigned) -> Self {
+        match BlobTransaction::try_from_signed(tx, Default::default()) {
+            Ok(blob_tx) => PooledTransactionsElement::BlobTransaction(blob_tx),
+            Err((tx, _)) => PooledTransactionsElement::Transaction(tx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex_literal::hex;
+
+    #[test]
+    fn decode_pooled_non_blob_transactions() {
+        // legacy and eip1559 transactions
+        let raw = [
+            &hex!("f86b02843b9aca00830186a094d3e8763675e4c425df46cc3b5c0f6cbdac39604687038d7ea4c68000802ba00eb96ca19e8a77102767a41fc85a36afd5c61ccb09911cec5d3e86e193d9c5aea03a456401896b1b6055311536bf00a718568c744d8c1f9df59879e8350220ca18")[..],
+            &hex!("02f872041a8459682f008459682f0d8252089461815774383099e24810ab832a5b2a5425c154d58829a2241af62c000080c001a059e6b67f48fb32e7e570dfb11e042b5ad2e55e3ce3ce9cd989c7e06e07feeafda0016b83f4f980694ed2eee4d10667242b1f40dc406901b34125b008d334d47469")[..],
+        ];
+        for raw in raw {
+            let mut encoded = vec![];
+            let tx = TransactionSigned::decode_enveloped(Bytes::from(raw)).unwrap();
+            tx.encode(&mut encoded);
+
+            let pooled = PooledTransactionsElement::decode(&mut encoded.as_slice()).unwrap();
+            assert_eq!(pooled, PooledTransactionsElement::Transaction(tx));
+            assert_eq!(pooled.length(), encoded.len());
+
+            let enveloped = PooledTransactionsElement::decode_enveloped(Bytes::from(raw)).unwrap();
+            assert_eq!(enveloped, pooled);
+        }
+    }
+}
@@ -0,0 +1,507 @@
+use crate::{
+    constants::{BYTES_PER_BLOB, BYTES_PER_COMMITMENT, BYTES_PER_PROOF, MAX_BLOBS_PER_BLOCK},
+    eip4844::{kzg_to_versioned_hash, KzgSettings},
+    keccak256, Bytes, Signature, Transaction, TransactionSigned, TxEip4844, TxHash,
+    EIP4844_TX_TYPE_ID, H256,
+};
+use bytes::Buf;
+use c_kzg::{Blob, Bytes48, KzgProof};
+use reth_rlp::{length_of_length, Decodable, DecodeError, Encodable, Header};
+use serde::{Deserialize, Serialize};
+
+/// The blobs of a [TxEip4844] together with their KZG commitments and proofs.
+///
+/// The sidecar is not part of the transaction that is included in a block, it's only gossiped
+/// alongside the transaction, see [BlobTransaction].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct BlobTransactionSidecar {
+    /// The blob data.
+    pub blobs: Vec<Bytes>,
+    /// The blob commitments.
+    pub commitments: Vec<Bytes>,
+    /// The blob proofs.
+    pub proofs: Vec<Bytes>,
+}
+
+impl BlobTransactionSidecar {
+    /// Validates the sidecar against the versioned hashes of the transaction.
+    ///
+    /// This checks the number and sizes of the blobs, commitments and proofs, that each
+    /// commitment hashes to the corresponding versioned hash and finally verifies the KZG proofs
+    /// of the blobs with the given trusted setup.
+    pub fn validate(
+        &self,
+        versioned_hashes: &[H256],
+        kzg_settings: &KzgSettings,
+    ) -> Result<(), BlobTransactionValidationError> {
+        if versioned_hashes.is_empty() {
+            return Err(BlobTransactionValidationError::EmptyBlobs)
+        }
+        if versioned_hashes.len() > MAX_BLOBS_PER_BLOCK {
+            return Err(BlobTransactionValidationError::TooManyBlobs {
+                have: versioned_hashes.len(),
+                max: MAX_BLOBS_PER_BLOCK,
+            })
+        }
+        if self.blobs.len() != versioned_hashes.len() ||
+            self.commitments.len() != versioned_hashes.len() ||
+            self.proofs.len() != versioned_hashes.len()
+        {
+            return Err(BlobTransactionValidationError::CountMismatch {
+                hashes: versioned_hashes.len(),
+                blobs: self.blobs.len(),
+                commitments: self.commitments.len(),
+                proofs: self.proofs.len(),
+            })
+        }
+
+        if let Some(blob) = self.blobs.iter().find(|blob| blob.len() != BYTES_PER_BLOB) {
+            return Err(BlobTransactionValidationError::InvalidBlobSize(blob.len()))
+        }
+        if let Some(proof) = self.proofs.iter().find(|proof| proof.len() != BYTES_PER_PROOF) {
+            return Err(BlobTransactionValidationError::InvalidProofSize(proof.len()))
+        }
+
+        for (index, (commitment, expected)) in
+            self.commitments.iter().zip(versioned_hashes).enumerate()
+        {
+            if commitment.len() != BYTES_PER_COMMITMENT {
+                return Err(BlobTransactionValidationError::InvalidCommitmentSize(commitment.len()))
+            }
+            let got = kzg_to_versioned_hash(commitment);
+            if got != *expected {
+                return Err(BlobTransactionValidationError::VersionedHashMismatch {
+                    index,
+                    expected: *expected,
+                    got,
+                })
+            }
+        }
+
+        self.verify_proofs(kzg_settings)
+    }
+
+    /// Verifies the KZG proofs of the blobs against their commitments.
+    ///
+    /// Expects that the sizes of the blobs, commitments and proofs have been checked.
+    fn verify_proofs(
+        &self,
+        kzg_settings: &KzgSettings,
+    ) -> Result<(), BlobTransactionValidationError> {
+        let kzg_error = |err: c_kzg::Error| BlobTransactionValidationError::Kzg(err.to_string());
+
+        let blobs = self
+            .blobs
+            .iter()
+            .map(|blob| Blob::from_bytes(blob))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(kzg_error)?;
+        let commitments = self
+            .commitments
+            .iter()
+            .map(|commitment| Bytes48::from_bytes(commitment))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(kzg_error)?;
+        let proofs = self
+            .proofs
+            .iter()
+            .map(|proof| Bytes48::from_bytes(proof))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(kzg_error)?;
+
+        let valid =
+            KzgProof::verify_blob_kzg_proof_batch(&blobs, &commitments, &proofs, kzg_settings)
+                .map_err(kzg_error)?;
+        if !valid {
+            return Err(BlobTransactionValidationError::InvalidProof)
+        }
+        Ok(())
+    }
+
+    /// Returns the versioned hashes of the commitments.
+    pub fn versioned_hashes(&self) -> impl Iterator<Item = H256> + '_ {
+        self.commitments.iter().map(|commitment| kzg_to_versioned_hash(commitment))
+    }
+
+    /// Calculates a heuristic for the in-memory size of the sidecar.
+    pub fn size(&self) -> usize {
+        self.blobs.iter().chain(&self.commitments).chain(&self.proofs).map(|b| b.len()).sum()
+    }
+
+    /// Outputs the length of the sidecar's fields, without a RLP header.
+    pub(crate) fn fields_len(&self) -> usize {
+        self.blobs.length() + self.commitments.length() + self.proofs.length()
+    }
+
+    /// Encodes only the sidecar's fields into the desired buffer, without a RLP header.
+    pub(crate) fn encode_fields(&self, out: &mut dyn bytes::BufMut) {
+        self.blobs.encode(out);
+        self.commitments.encode(out);
+        self.proofs.encode(out);
+    }
+
+    /// Decodes the sidecar's fields from the buffer, without a RLP header.
+    pub(crate) fn decode_fields(buf: &mut &[u8]) -> Result<Self, DecodeError> {
+        Ok(Self {
+            blobs: Decodable::decode(buf)?,
+            commitments: Decodable::decode(buf)?,
+            proofs: Decodable::decode(buf)?,
+        })
+    }
+}
+
+/// Encodes the sidecar as `rlp([blobs, commitments, proofs])`.
+impl Encodable for BlobTransactionSidecar {
+    fn encode(&self, out: &mut dyn bytes::BufMut) {
+        Header { list: true, payload_length: self.fields_len() }.encode(out);
+        self.encode_fields(out);
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.fields_len();
+        length_of_length(payload_length) + payload_length
+    }
+}
+
+impl Decodable for BlobTransactionSidecar {
+    fn decode(buf: &mut &[u8]) -> Result<Self, DecodeError> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(DecodeError::UnexpectedString)
+        }
+        let started_len = buf.len();
+        let sidecar = Self::decode_fields(buf)?;
+        let consumed = started_len - buf.len();
+        if consumed != header.payload_length {
+            return Err(DecodeError::ListLengthMismatch {
+                expected: header.payload_length,
+                got: consumed,
+            })
+        }
+        Ok(sidecar)
+    }
+}
+
+/// Errors that can occur when validating a [BlobTransactionSidecar].
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
+pub enum BlobTransactionValidationError {
+    /// The transaction does not carry any blobs.
+    #[error("Blob transaction has no blobs.")]
+    EmptyBlobs,
+    /// The transaction carries more blobs than fit in a block.
+    #[error("Blob transaction has too many blobs: {have} > {max}.")]
+    TooManyBlobs { have: usize, max: usize },
+    /// The number of blobs, commitments and proofs does not match the versioned hashes.
+    #[error("Mismatched number of versioned hashes ({hashes}), blobs ({blobs}), commitments ({commitments}) and proofs ({proofs}).")]
+    CountMismatch { hashes: usize, blobs: usize, commitments: usize, proofs: usize },
+    /// A blob has an invalid size.
+    #[error("Invalid blob size: {0}.")]
+    InvalidBlobSize(usize),
+    /// A commitment has an invalid size.
+    #[error("Invalid blob commitment size: {0}.")]
+    InvalidCommitmentSize(usize),
+    /// A proof has an invalid size.
+    #[error("Invalid blob proof size: {0}.")]
+    InvalidProofSize(usize),
+    /// A commitment does not match the versioned hash of the transaction.
+    #[error("Versioned hash mismatch at index {index}: expected {expected:?}, got {got:?}.")]
+    VersionedHashMismatch { index: usize, expected: H256, got: H256 },
+    /// The KZG proofs don't match the blobs and commitments.
+    #[error("Invalid blob KZG proof.")]
+    InvalidProof,
+    /// A blob, commitment or proof couldn't be decoded for the KZG proof verification.
+    #[error("KZG error: {0}.")]
+    Kzg(String),
+}
+
+/// A [TxEip4844] with its [BlobTransactionSidecar].
+///
+/// This is the form in which blob transactions are exchanged in `PooledTransactions` and submitted
+/// via `eth_sendRawTransaction`: `type || rlp([tx_payload_body, blobs, commitments, proofs])`,
+/// where `tx_payload_body` is the signed transaction as it appears in a block.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BlobTransaction {
+    /// The transaction hash.
+    pub hash: TxHash,
+    /// The transaction payload.
+    pub transaction: TxEip4844,
+    /// The transaction signature.
+    pub signature: Signature,
+    /// The transaction's blob sidecar.
+    pub sidecar: BlobTransactionSidecar,
+}
+
+impl BlobTransaction {
+    /// Constructs a new [BlobTransaction] from a signed transaction and its sidecar.
+    ///
+    /// Returns the arguments back if the transaction is not an EIP-4844 transaction.
+    pub fn try_from_signed(
+        tx: TransactionSigned,
+        sidecar: BlobTransactionSidecar,
+    ) -> Result<Self, (TransactionSigned, BlobTransactionSidecar)> {
+        let TransactionSigned { transaction, signature, hash } = tx;
+        match transaction {
+            Transaction::Eip4844(transaction) => Ok(Self { hash, transaction, signature, sidecar }),
+            transaction => Err((TransactionSigned { transaction, signature, hash }, sidecar)),
+        }
+    }
+
+    /// Returns the transaction hash.
+    pub fn hash(&self) -> TxHash {
+        self.hash
+    }
+
+    /// Validates the sidecar against the transaction's versioned hashes, see
+    /// [BlobTransactionSidecar::validate].
+    pub fn validate(
+        &self,
+        kzg_settings: &KzgSettings,
+    ) -> Result<(), BlobTransactionValidationError> {
+        self.sidecar.validate(&self.transaction.blob_versioned_hashes, kzg_settings)
+    }
+
+    /// Splits the transaction into the signed transaction as it appears in a block and the
+    /// sidecar.
+    pub fn into_parts(self) -> (TransactionSigned, BlobTransactionSidecar) {
+        let BlobTransaction { hash, transaction, signature, sidecar } = self;
+        let tx =
+            TransactionSigned { hash, transaction: Transaction::Eip4844(transaction), signature };
+        (tx, sidecar)
+    }
+
+    /// Outputs the length of the `tx_payload_body` list, including its header.
+    fn tx_payload_body_len(&self) -> usize {
+        let payload_length = self.transaction.fields_len() + self.signature.payload_len();
+        length_of_length(payload_length) + payload_length
+    }
+
+    /// Outputs the payload length of the outer list.
+    fn payload_len(&self) -> usize {
+        self.tx_payload_body_len() + self.sidecar.fields_len()
+    }
+
+    /// Outputs the length of the enveloped encoding: `type || rlp([..])`
+    fn enveloped_len(&self) -> usize {
+        let payload_length = self.payload_len();
+        1 + length_of_length(payload_length) + payload_length
+    }
+
+    /// Encodes the transaction into the "raw" network format:
+    /// `type || rlp([tx_payload_body, blobs, commitments, proofs])`
+    pub fn encode_enveloped(&self, out: &mut dyn bytes::BufMut) {
+        out.put_u8(EIP4844_TX_TYPE_ID);
+        Header { list: true, payload_length: self.payload_len() }.encode(out);
+        Header {
+            list: true,
+            payload_length: self.transaction.fields_len() + self.signature.payload_len(),
+        }
+        .encode(out);
+        self.transaction.encode_fields(out);
+        self.signature.encode(out);
+        self.sidecar.encode_fields(out);
+    }
+
+    /// Decodes the "raw" network format of a blob transaction.
+    ///
+    /// CAUTION: this expects that `data` is `type || rlp([tx_payload_body, blobs, commitments,
+    /// proofs])`
+    pub(crate) fn decode_enveloped_inner(data: &mut &[u8]) -> Result<Self, DecodeError> {
+        let tx_type = *data.first().ok_or(DecodeError::InputTooShort)?;
+        if tx_type != EIP4844_TX_TYPE_ID {
+            return Err(DecodeError::Custom("invalid blob transaction type"))
+        }
+        data.advance(1);
+
+        let outer = Header::decode(data)?;
+        if !outer.list {
+            return Err(DecodeError::Custom("blob transaction must be encoded as a list"))
+        }
+        let outer_start = data.len();
+
+        // keep this around so we can use it to calculate the hash
+        let tx_payload_body = *data;
+        let inner = Header::decode(data)?;
+        if !inner.list {
+            return Err(DecodeError::Custom("typed tx fields must be encoded as a list"))
+        }
+        let inner_start = data.len();
+        let transaction = TxEip4844::decode_inner(data)?;
+        let signature = Signature::decode(data)?;
+        if inner_start - data.len() != inner.payload_length {
+            return Err(DecodeError::ListLengthMismatch {
+                expected: inner.payload_length,
+                got: inner_start - data.len(),
+            })
+        }
+
+        // the hash is computed over `type || rlp(tx_payload_body)`, same as in a block
+        let tx_length = inner.length() + inner.payload_length;
+        let mut buf = Vec::with_capacity(1 + tx_length);
+        buf.push(EIP4844_TX_TYPE_ID);
+        buf.extend_from_slice(&tx_payload_body[..tx_length]);
+        let hash = keccak256(&buf);
+
+        let sidecar = BlobTransactionSidecar::decode_fields(data)?;
+        if outer_start - data.len() != outer.payload_length {
+            return Err(DecodeError::ListLengthMismatch {
+                expected: outer.payload_length,
+                got: outer_start - data.len(),
+            })
+        }
+
+        Ok(Self { hash, transaction, signature, sidecar })
+    }
+}
+
+/// Encodes the transaction with a RLP string header, as it appears in a list of
+/// `PooledTransactions`.
+impl Encodable for BlobTransaction {
+    fn encode(&self, out: &mut dyn bytes::BufMut) {
+        Header { list: false, payload_length: self.enveloped_len() }.encode(out);
+        self.encode_enveloped(out);
+    }
+
+    fn length(&self) -> usize {
+        let len = self.enveloped_len();
+        length_of_length(len) + len
+    }
+}
+
+impl Decodable for BlobTransaction {
+    fn decode(buf: &mut &[u8]) -> Result<Self, DecodeError> {
+        let header = Header::decode(buf)?;
+        if header.list {
+            return Err(DecodeError::UnexpectedList)
+        }
+        Self::decode_enveloped_inner(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{eip4844::MAINNET_KZG_TRUSTED_SETUP, TransactionKind, U256};
+    use c_kzg::KzgCommitment;
+
+    /// Returns a sidecar with valid commitments and proofs for the given blobs.
+    fn sidecar(blobs: Vec<Bytes>) -> BlobTransactionSidecar {
+        let mut sidecar = BlobTransactionSidecar { blobs, ..Default::default() };
+        for blob in sidecar.blobs.iter() {
+            let blob = Blob::from_bytes(blob).unwrap();
+            let commitment =
+                KzgCommitment::blob_to_kzg_commitment(&blob, &MAINNET_KZG_TRUSTED_SETUP).unwrap();
+            let proof = KzgProof::compute_blob_kzg_proof(
+                &blob,
+                &commitment.to_bytes(),
+                &MAINNET_KZG_TRUSTED_SETUP,
+            )
+            .unwrap();
+            sidecar.commitments.push(Bytes::from(commitment.to_bytes().into_inner().to_vec()));
+            sidecar.proofs.push(Bytes::from(proof.to_bytes().into_inner().to_vec()));
+        }
+        sidecar
+    }
+
+    fn blob_transaction(blobs: usize) -> BlobTransaction {
+        // every field element of the blobs must be below the modulus of the scalar field
+        let sidecar =
+            sidecar((0..blobs).map(|i| Bytes::from(vec![i as u8 + 1; BYTES_PER_BLOB])).collect());
+        let transaction = TxEip4844 {
+            chain_id: 1,
+            nonce: 2,
+            gas_limit: 21_000,
+            max_fee_per_gas: 10,
+            max_priority_fee_per_gas: 1,
+            to: TransactionKind::Call(Default::default()),
+            value: 3,
+            access_list: Default::default(),
+            blob_versioned_hashes: sidecar.versioned_hashes().collect(),
+            max_fee_per_blob_gas: 7,
+            input: Bytes::from(vec![4, 5]),
+        };
+        let signature = Signature { odd_y_parity: true, r: U256::from(1), s: U256::from(2) };
+        let signed = TransactionSigned::from_transaction_and_signature(
+            Transaction::Eip4844(transaction),
+            signature,
+        );
+        BlobTransaction::try_from_signed(signed, sidecar).unwrap()
+    }
+
+    #[test]
+    fn blob_transaction_roundtrip() {
+        let tx = blob_transaction(2);
+        assert_eq!(tx.validate(&MAINNET_KZG_TRUSTED_SETUP), Ok(()));
+
+        let mut encoded = vec![];
+        tx.encode(&mut encoded);
+        assert_eq!(tx.length(), encoded.len());
+        let decoded = BlobTransaction::decode(&mut encoded.as_slice()).unwrap();
+        assert_eq!(decoded, tx);
+
+        // the hash must match the hash of the transaction without the sidecar
+        let (signed, _) = decoded.into_parts();
+        assert_eq!(signed.hash(), signed.recalculate_hash());
+        assert_eq!(signed.hash(), tx.hash());
+    }
+
+    #[test]
+    fn sidecar_roundtrip() {
+        let sidecar = blob_transaction(2).sidecar;
+
+        let mut encoded = vec![];
+        sidecar.encode(&mut encoded);
+        assert_eq!(sidecar.length(), encoded.len());
+        let decoded = BlobTransactionSidecar::decode(&mut encoded.as_slice()).unwrap();
+        assert_eq!(decoded, sidecar);
+    }
+
+    #[test]
+    fn validate_sidecar() {
+        let mut tx = blob_transaction(1);
+        tx.sidecar.proofs.clear();
+        assert!(matches!(
+            tx.validate(&MAINNET_KZG_TRUSTED_SETUP),
+            Err(BlobTransactionValidationError::CountMismatch { .. })
+        ));
+
+        let mut tx = blob_transaction(1);
+        tx.sidecar.commitments[0] = Bytes::from(vec![9u8; BYTES_PER_COMMITMENT]);
+        assert!(matches!(
+            tx.validate(&MAINNET_KZG_TRUSTED_SETUP),
+            Err(BlobTransactionValidationError::VersionedHashMismatch { index: 0, .. })
+        ));
+
+        let mut tx = blob_transaction(1);
+        tx.sidecar.blobs[0] = Bytes::from(vec![0u8; 3]);
+        assert_eq!(
+            tx.validate(&MAINNET_KZG_TRUSTED_SETUP),
+            Err(BlobTransactionValidationError::InvalidBlobSize(3))
+        );
+
+        let tx = blob_transaction(0);
+        assert_eq!(
+            tx.validate(&MAINNET_KZG_TRUSTED_SETUP),
+            Err(BlobTransactionValidationError::EmptyBlobs)
+        );
+    }
+
+    #[test]
+    fn validate_sidecar_proofs() {
+        // the proofs of the two blobs are swapped
+        let mut tx = blob_transaction(2);
+        tx.sidecar.proofs.swap(0, 1);
+        assert_eq!(
+            tx.validate(&MAINNET_KZG_TRUSTED_SETUP),
+            Err(BlobTransactionValidationError::InvalidProof)
+        );
+
+        // the proof is not a point on the curve
+        let mut tx = blob_transaction(1);
+        tx.sidecar.proofs[0] = Bytes::from(vec![2u8; BYTES_PER_PROOF]);
+        assert!(matches!(
+            tx.validate(&MAINNET_KZG_TRUSTED_SETUP),
+            Err(BlobTransactionValidationError::Kzg(_))
+        ));
+    }
+}
@@ -2,8 +2,14 @@ use super::{
     nodes::{rlp_hash, BranchNode, ExtensionNode, LeafNode},
     BranchNodeCompact, Nibbles, TrieMask,
 };
-use crate::{keccak256, proofs::EMPTY_ROOT, H256};
-use std::{collections::HashMap, fmt::Debug};
+use crate::{keccak256, proofs::EMPTY_ROOT, Bytes, H256};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+};
+
+mod proof_retainer;
+pub use proof_retainer::ProofRetainer;
 
 mod state;
 pub use state::HashBuilderState;
@@ -47,6 +53,7 @@ pub struct HashBuilder {
     stored_in_database: bool,
 
     updated_branch_nodes: Option<HashMap<Nibbles, BranchNodeCompact>>,
+    proof_retainer: Option<ProofRetainer>,
 
     rlp_buf: Vec<u8>,
 }
@@ -62,6 +69,7 @@ impl From<HashBuilderState> for HashBuilder {
             hash_masks: state.hash_masks,
             stored_in_database: state.stored_in_database,
             updated_branch_nodes: None,
+            proof_retainer: None,
             rlp_buf: Vec::with_capacity(32),
         }
     }
@@ -99,6 +107,20 @@ impl HashBuilder {
         }
     }
 
+    /// Enables the Hash Builder to retain the nodes on the paths to the retainer's targets.
+    ///
+    /// Call [HashBuilder::take_proofs] to get the retained nodes.
+    pub fn with_proof_retainer(mut self, retainer: ProofRetainer) -> Self {
+        self.proof_retainer = Some(retainer);
+        self
+    }
+
+    /// Takes the nodes retained by the proof retainer, keyed by their path.
+    /// Returns an empty map if [Self::with_proof_retainer] was not called.
+    pub fn take_proofs(&mut self) -> BTreeMap<Nibbles, Bytes> {
+        self.proof_retainer.take().map(ProofRetainer::into_proofs).unwrap_or_default()
+    }
+
     /// Splits the [HashBuilder] into a [HashBuilder] and hash builder updates.
     pub fn split(mut self) -> (Self, HashMap<Nibbles, BranchNodeCompact>) {
         let updates = self.updated_branch_nodes.take();
@@ -252,6 +274,7 @@ impl HashBuilder {
 
                         self.rlp_buf.clear();
                         self.stack.push(leaf_node.rlp(&mut self.rlp_buf));
+                        self.retain_proof_from_buf(&current.slice(0, len_from));
                     }
                     HashBuilderValue::Hash(hash) => {
                         tracing::debug!(target: "trie::hash_builder", ?hash, "pushing branch node hash");
@@ -281,6 +304,7 @@ impl HashBuilder {
                 }, "extension node rlp");
                 self.rlp_buf.clear();
                 self.stack.push(extension_node.rlp(&mut self.rlp_buf));
+                self.retain_proof_from_buf(&current.slice(0, len_from));
                 self.resize_masks(len_from);
             }
 
@@ -292,7 +316,7 @@ impl HashBuilder {
             // Insert branch nodes in the stack
             if !succeeding.is_empty() || preceding_exists {
                 // Pushes the corresponding branch node to the stack
-                let children = self.push_branch_node(len);
+                let children = self.push_branch_node(&current, len);
                 // Need to store the branch node in an efficient format
                 // outside of the hash builder
                 self.store_branch_node(&current, len, children);
@@ -323,7 +347,7 @@ impl HashBuilder {
     /// Given the size of the longest common prefix, it proceeds to create a branch node
     /// from the state mask and existing stack state, and store its RLP to the top of the stack,
     /// after popping all the relevant elements from the stack.
-    fn push_branch_node(&mut self, len: usize) -> Vec<H256> {
+    fn push_branch_node(&mut self, current: &Nibbles, len: usize) -> Vec<H256> {
         let state_mask = self.groups[len];
         let hash_mask = self.hash_masks[len];
         let branch_node = BranchNode::new(&self.stack);
@@ -331,6 +355,7 @@ impl HashBuilder {
 
         self.rlp_buf.clear();
         let rlp = branch_node.rlp(state_mask, &mut self.rlp_buf);
+        self.retain_proof_from_buf(&current.slice(0, len));
 
         // Clears the stack from the branch node elements
         let first_child_idx = self.stack.len() - state_mask.count_ones() as usize;
@@ -387,6 +412,13 @@ impl HashBuilder {
         }
     }
 
+    /// Retains the node that was just encoded into the RLP buffer if it is part of a proof.
+    fn retain_proof_from_buf(&mut self, path: &Nibbles) {
+        if let Some(retainer) = self.proof_retainer.as_mut() {
+            retainer.retain(path, &self.rlp_buf);
+        }
+    }
+
     fn update_masks(&mut self, current: &Nibbles, len_from: usize) {
         if len_from > 0 {
             let flag = TrieMask::from_nibble(current[len_from - 1]);
@@ -475,6 +507,25 @@ mod tests {
         assert_eq!(HashBuilder::default().root(), EMPTY_ROOT);
     }
 
+    #[test]
+    fn retains_proof_nodes() {
+        let data = (0..64u64)
+            .map(|i| (keccak256(i.to_be_bytes()), reth_rlp::encode_fixed_size(&U256::from(i))))
+            .collect::<BTreeMap<_, _>>();
+        let target = Nibbles::unpack(data.keys().nth(10).unwrap());
+
+        let mut hb =
+            HashBuilder::default().with_proof_retainer(ProofRetainer::new(vec![target.clone()]));
+        data.iter().for_each(|(key, val)| hb.add_leaf(Nibbles::unpack(key), val));
+        let root = hb.root();
+        let proofs = hb.take_proofs();
+
+        // the root node is the first node of every proof
+        assert_eq!(keccak256(&proofs[&Nibbles::default()]), root);
+        assert!(proofs.keys().all(|path| target.has_prefix(path)));
+        assert!(proofs.len() > 1);
+    }
+
     #[test]
     fn arbitrary_hashed_root() {
         proptest!(|(state: BTreeMap<H256, U256>)| {
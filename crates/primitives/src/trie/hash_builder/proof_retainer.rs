@@ -0,0 +1,75 @@
+use super::super::Nibbles;
+use crate::Bytes;
+use std::collections::BTreeMap;
+
+/// Retains the RLP encoded trie nodes on the paths to a set of target keys while the
+/// [HashBuilder](super::HashBuilder) computes the root.
+///
+/// The retained nodes are keyed by their path in the trie, so iterating over them in order yields
+/// the nodes of every proof from the root down to the leaf.
+#[derive(Debug, Default, Clone)]
+pub struct ProofRetainer {
+    /// The keys to retain the proofs for.
+    targets: Vec<Nibbles>,
+    /// Whether to also retain the siblings of the nodes on the paths to the targets.
+    with_siblings: bool,
+    /// The retained nodes, keyed by their path.
+    proofs: BTreeMap<Nibbles, Bytes>,
+}
+
+impl ProofRetainer {
+    /// Creates a new retainer for the given target keys.
+    pub fn new(targets: Vec<Nibbles>) -> Self {
+        Self { targets, with_siblings: false, proofs: BTreeMap::default() }
+    }
+
+    /// Also retain the direct children of all nodes on the paths to the targets.
+    ///
+    /// The siblings are needed to update the trie without access to the database: removing a key
+    /// can collapse its parent branch node into its only remaining child.
+    pub fn with_siblings(mut self, with_siblings: bool) -> Self {
+        self.with_siblings = with_siblings;
+        self
+    }
+
+    /// Returns `true` if the node at the given path should be retained.
+    pub fn matches(&self, path: &Nibbles) -> bool {
+        self.targets.iter().any(|target| {
+            target.has_prefix(path) ||
+                (self.with_siblings &&
+                    !path.is_empty() &&
+                    target.has_prefix(&path.slice(0, path.len() - 1)))
+        })
+    }
+
+    /// Retains the RLP encoded node at the given path if it is on the path to a target.
+    pub fn retain(&mut self, path: &Nibbles, rlp: &[u8]) {
+        if self.matches(path) {
+            self.proofs.insert(path.clone(), Bytes::from(rlp.to_vec()));
+        }
+    }
+
+    /// Returns the retained nodes, keyed by their path.
+    pub fn into_proofs(self) -> BTreeMap<Nibbles, Bytes> {
+        self.proofs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retains_nodes_on_path() {
+        let target = Nibbles::from_hex(vec![1, 2, 3]);
+        let retainer = ProofRetainer::new(vec![target.clone()]);
+        assert!(retainer.matches(&Nibbles::default()));
+        assert!(retainer.matches(&Nibbles::from_hex(vec![1, 2])));
+        assert!(retainer.matches(&target));
+        assert!(!retainer.matches(&Nibbles::from_hex(vec![1, 3])));
+
+        let retainer = retainer.with_siblings(true);
+        assert!(retainer.matches(&Nibbles::from_hex(vec![1, 3])));
+        assert!(!retainer.matches(&Nibbles::from_hex(vec![1, 3, 0])));
+    }
+}
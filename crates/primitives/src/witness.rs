@@ -0,0 +1,21 @@
+use crate::{Bytes, Header};
+use serde::{Deserialize, Serialize};
+
+/// The data needed to re-execute a block without access to the state database.
+///
+/// The witness contains everything the block touches during execution: the trie nodes proving the
+/// accessed accounts and storage slots against the parent state root, the bytecode of every
+/// executed contract and the ancestor headers whose hashes were read via `BLOCKHASH`.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionWitness {
+    /// The RLP encoded account and storage trie nodes, including the siblings needed to apply the
+    /// state changes of the block.
+    pub state: Vec<Bytes>,
+    /// The bytecode of all contracts accessed during execution.
+    pub codes: Vec<Bytes>,
+    /// The ancestor headers from the oldest accessed block hash up to the parent of the block.
+    ///
+    /// Always contains at least the parent header, which commits to the pre-state root.
+    pub headers: Vec<Header>,
+}
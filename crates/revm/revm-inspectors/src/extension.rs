@@ -0,0 +1,556 @@
+use reth_primitives::{bytes::Bytes, Address, Head, H256, U256};
+use revm::{
+    interpreter::{CallInputs, CreateInputs, Gas, InstructionResult, Interpreter},
+    primitives::{Env, SpecId},
+    Database, EVMData, Inspector,
+};
+use std::{collections::HashMap, fmt::Debug, sync::Arc};
+
+/// The function that executes a custom precompile.
+///
+/// It receives the call data and the gas limit of the call and returns the gas used and the
+/// output of the precompile.
+pub type CustomPrecompileFn =
+    Arc<dyn Fn(&[u8], u64) -> Result<(u64, Bytes), CustomPrecompileError> + Send + Sync>;
+
+/// Errors a [CustomPrecompileFn] can return.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CustomPrecompileError {
+    /// The call ran out of gas.
+    OutOfGas,
+    /// The precompile failed for any other reason.
+    Other(String),
+}
+
+/// An extension of the EVM configuration used by the node.
+///
+/// The node configures revm for mainnet by default. This lets embedders, for example appchains,
+/// register custom precompiles, override the spec selected for a block and install a custom
+/// [ExtensionInspector]. Once configured on the executor factory, the payload builder and the `eth`
+/// API, it applies to every transaction executed by the node.
+///
+/// Custom precompiles are implemented on top of revm's inspector hooks, which has a few
+/// consequences:
+///  - calls to a custom precompile that transfer value revert, because the inspector is invoked
+///    before the value is transferred.
+///  - custom precompiles are not part of the warm set of a transaction, so the first call pays for
+///    a cold account access.
+///  - a custom precompile takes precedence over a builtin precompile at the same address.
+pub trait EvmExtension: Debug + Send + Sync + 'static {
+    /// Returns the custom precompiles and the addresses they are installed at.
+    fn precompiles(&self) -> Vec<(Address, CustomPrecompileFn)> {
+        Vec::new()
+    }
+
+    /// Returns the spec that should be used to execute the block at the given head.
+    ///
+    /// `spec_id` is the spec selected by the chain spec.
+    fn spec_id(&self, head: &Head, spec_id: SpecId) -> SpecId {
+        let _ = head;
+        spec_id
+    }
+
+    /// Returns a new instance of the custom inspector, if any.
+    ///
+    /// This is called for every block that is executed or built and for every `eth` call.
+    fn inspector(&self) -> Option<Box<dyn ExtensionInspector>> {
+        None
+    }
+}
+
+/// A database agnostic [Inspector].
+///
+/// revm inspectors are generic over the database, which differs between the executor, the payload
+/// builder and the `eth` API. This exposes the hooks of [Inspector] without the database so the
+/// same inspector can be installed everywhere.
+#[allow(unused_variables)]
+pub trait ExtensionInspector: Send + Sync {
+    /// See [Inspector::initialize_interp].
+    fn initialize_interp(
+        &mut self,
+        interp: &mut Interpreter,
+        env: &Env,
+        is_static: bool,
+    ) -> InstructionResult {
+        InstructionResult::Continue
+    }
+
+    /// See [Inspector::step].
+    fn step(&mut self, interp: &mut Interpreter, env: &Env, is_static: bool) -> InstructionResult {
+        InstructionResult::Continue
+    }
+
+    /// See [Inspector::log].
+    fn log(&mut self, env: &Env, address: &Address, topics: &[H256], data: &Bytes) {}
+
+    /// See [Inspector::step_end].
+    fn step_end(
+        &mut self,
+        interp: &mut Interpreter,
+        env: &Env,
+        is_static: bool,
+        eval: InstructionResult,
+    ) -> InstructionResult {
+        InstructionResult::Continue
+    }
+
+    /// See [Inspector::call].
+    fn call(
+        &mut self,
+        env: &Env,
+        inputs: &mut CallInputs,
+        is_static: bool,
+    ) -> (InstructionResult, Gas, Bytes) {
+        (InstructionResult::Continue, Gas::new(0), Bytes::new())
+    }
+
+    /// See [Inspector::call_end].
+    fn call_end(
+        &mut self,
+        env: &Env,
+        inputs: &CallInputs,
+        remaining_gas: Gas,
+        ret: InstructionResult,
+        out: Bytes,
+        is_static: bool,
+    ) -> (InstructionResult, Gas, Bytes) {
+        (ret, remaining_gas, out)
+    }
+
+    /// See [Inspector::create].
+    fn create(
+        &mut self,
+        env: &Env,
+        inputs: &mut CreateInputs,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        (InstructionResult::Continue, None, Gas::new(0), Bytes::new())
+    }
+
+    /// See [Inspector::create_end].
+    fn create_end(
+        &mut self,
+        env: &Env,
+        inputs: &CreateInputs,
+        ret: InstructionResult,
+        address: Option<Address>,
+        remaining_gas: Gas,
+        out: Bytes,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        (ret, address, remaining_gas, out)
+    }
+
+    /// See [Inspector::selfdestruct].
+    fn selfdestruct(&mut self, contract: Address, target: Address) {}
+}
+
+/// The [Inspector] that applies an [EvmExtension]: it runs the custom precompiles and forwards all
+/// hooks to the custom inspector of the extension.
+pub struct ExtensionStack {
+    extension: Arc<dyn EvmExtension>,
+    precompiles: HashMap<Address, CustomPrecompileFn>,
+    inspector: Option<Box<dyn ExtensionInspector>>,
+}
+
+impl ExtensionStack {
+    /// Creates a new stack for the given extension.
+    pub fn new(extension: Arc<dyn EvmExtension>) -> Self {
+        let precompiles = extension.precompiles().into_iter().collect();
+        let inspector = extension.inspector();
+        Self { extension, precompiles, inspector }
+    }
+
+    /// Returns the extension this stack applies.
+    pub fn extension(&self) -> &Arc<dyn EvmExtension> {
+        &self.extension
+    }
+
+    /// Returns the spec to use for the given head, see [EvmExtension::spec_id].
+    pub fn spec_id(&self, head: &Head, spec_id: SpecId) -> SpecId {
+        self.extension.spec_id(head, spec_id)
+    }
+
+    /// Runs the custom precompile at the called address, if any.
+    fn run_precompile(&self, inputs: &CallInputs) -> Option<(InstructionResult, Gas, Bytes)> {
+        let precompile = self.precompiles.get(&inputs.contract)?;
+
+        if inputs.transfer.value != U256::ZERO {
+            return Some((InstructionResult::Revert, Gas::new(inputs.gas_limit), Bytes::new()))
+        }
+
+        let mut gas = Gas::new(inputs.gas_limit);
+        let res = match precompile(&inputs.input, inputs.gas_limit) {
+            Ok((gas_used, out)) => {
+                if gas.record_cost(gas_used) {
+                    (InstructionResult::Return, gas, out)
+                } else {
+                    (InstructionResult::PrecompileOOG, gas, Bytes::new())
+                }
+            }
+            Err(CustomPrecompileError::OutOfGas) => {
+                (InstructionResult::PrecompileOOG, gas, Bytes::new())
+            }
+            Err(CustomPrecompileError::Other(_)) => {
+                (InstructionResult::PrecompileError, gas, Bytes::new())
+            }
+        };
+        Some(res)
+    }
+}
+
+impl Clone for ExtensionStack {
+    /// Clones the stack with a new instance of the custom inspector.
+    fn clone(&self) -> Self {
+        Self {
+            extension: Arc::clone(&self.extension),
+            precompiles: self.precompiles.clone(),
+            inspector: self.extension.inspector(),
+        }
+    }
+}
+
+impl Debug for ExtensionStack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExtensionStack")
+            .field("extension", &self.extension)
+            .field("precompiles", &self.precompiles.keys().collect::<Vec<_>>())
+            .field("inspector", &self.inspector.is_some())
+            .finish()
+    }
+}
+
+impl<DB> Inspector<DB> for ExtensionStack
+where
+    DB: Database,
+{
+    fn initialize_interp(
+        &mut self,
+        interp: &mut Interpreter,
+        data: &mut EVMData<'_, DB>,
+        is_static: bool,
+    ) -> InstructionResult {
+        match self.inspector.as_mut() {
+            Some(inspector) => inspector.initialize_interp(interp, data.env, is_static),
+            None => InstructionResult::Continue,
+        }
+    }
+
+    fn step(
+        &mut self,
+        interp: &mut Interpreter,
+        data: &mut EVMData<'_, DB>,
+        is_static: bool,
+    ) -> InstructionResult {
+        match self.inspector.as_mut() {
+            Some(inspector) => inspector.step(interp, data.env, is_static),
+            None => InstructionResult::Continue,
+        }
+    }
+
+    fn log(
+        &mut self,
+        evm_data: &mut EVMData<'_, DB>,
+        address: &Address,
+        topics: &[H256],
+        data: &Bytes,
+    ) {
+        if let Some(inspector) = self.inspector.as_mut() {
+            inspector.log(evm_data.env, address, topics, data);
+        }
+    }
+
+    fn step_end(
+        &mut self,
+        interp: &mut Interpreter,
+        data: &mut EVMData<'_, DB>,
+        is_static: bool,
+        eval: InstructionResult,
+    ) -> InstructionResult {
+        match self.inspector.as_mut() {
+            Some(inspector) => inspector.step_end(interp, data.env, is_static, eval),
+            None => InstructionResult::Continue,
+        }
+    }
+
+    fn call(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        inputs: &mut CallInputs,
+        is_static: bool,
+    ) -> (InstructionResult, Gas, Bytes) {
+        if let Some(inspector) = self.inspector.as_mut() {
+            let (status, gas, retdata) = inspector.call(data.env, inputs, is_static);
+
+            // Allow the inspector to exit early
+            if status != InstructionResult::Continue {
+                return (status, gas, retdata)
+            }
+        }
+
+        if let Some(res) = self.run_precompile(inputs) {
+            return res
+        }
+
+        (InstructionResult::Continue, Gas::new(inputs.gas_limit), Bytes::new())
+    }
+
+    fn call_end(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        inputs: &CallInputs,
+        remaining_gas: Gas,
+        ret: InstructionResult,
+        out: Bytes,
+        is_static: bool,
+    ) -> (InstructionResult, Gas, Bytes) {
+        match self.inspector.as_mut() {
+            Some(inspector) => {
+                inspector.call_end(data.env, inputs, remaining_gas, ret, out, is_static)
+            }
+            None => (ret, remaining_gas, out),
+        }
+    }
+
+    fn create(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        inputs: &mut CreateInputs,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        match self.inspector.as_mut() {
+            Some(inspector) => inspector.create(data.env, inputs),
+            None => (InstructionResult::Continue, None, Gas::new(inputs.gas_limit), Bytes::new()),
+        }
+    }
+
+    fn create_end(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        inputs: &CreateInputs,
+        ret: InstructionResult,
+        address: Option<Address>,
+        remaining_gas: Gas,
+        out: Bytes,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        match self.inspector.as_mut() {
+            Some(inspector) => {
+                inspector.create_end(data.env, inputs, ret, address, remaining_gas, out)
+            }
+            None => (ret, address, remaining_gas, out),
+        }
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address) {
+        if let Some(inspector) = self.inspector.as_mut() {
+            inspector.selfdestruct(contract, target);
+        }
+    }
+}
+
+/// Wraps an [Inspector] so that the [EvmExtension], if any, is applied while it runs.
+///
+/// The wrapped inspector observes calls to custom precompiles like any other call.
+#[derive(Debug)]
+pub struct WithExtension<I> {
+    extension: Option<ExtensionStack>,
+    inner: I,
+}
+
+impl<I> WithExtension<I> {
+    /// Wraps the given inspector.
+    pub fn new(extension: Option<ExtensionStack>, inner: I) -> Self {
+        Self { extension, inner }
+    }
+
+    /// Returns the wrapped inspector.
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+}
+
+impl<DB, I> Inspector<DB> for WithExtension<I>
+where
+    DB: Database,
+    I: Inspector<DB>,
+{
+    fn initialize_interp(
+        &mut self,
+        interp: &mut Interpreter,
+        data: &mut EVMData<'_, DB>,
+        is_static: bool,
+    ) -> InstructionResult {
+        let status = self.inner.initialize_interp(interp, data, is_static);
+        if status != InstructionResult::Continue {
+            return status
+        }
+        match self.extension.as_mut() {
+            Some(extension) => extension.initialize_interp(interp, data, is_static),
+            None => InstructionResult::Continue,
+        }
+    }
+
+    fn step(
+        &mut self,
+        interp: &mut Interpreter,
+        data: &mut EVMData<'_, DB>,
+        is_static: bool,
+    ) -> InstructionResult {
+        let status = self.inner.step(interp, data, is_static);
+        if status != InstructionResult::Continue {
+            return status
+        }
+        match self.extension.as_mut() {
+            Some(extension) => extension.step(interp, data, is_static),
+            None => InstructionResult::Continue,
+        }
+    }
+
+    fn log(
+        &mut self,
+        evm_data: &mut EVMData<'_, DB>,
+        address: &Address,
+        topics: &[H256],
+        data: &Bytes,
+    ) {
+        self.inner.log(evm_data, address, topics, data);
+        if let Some(extension) = self.extension.as_mut() {
+            extension.log(evm_data, address, topics, data);
+        }
+    }
+
+    fn step_end(
+        &mut self,
+        interp: &mut Interpreter,
+        data: &mut EVMData<'_, DB>,
+        is_static: bool,
+        eval: InstructionResult,
+    ) -> InstructionResult {
+        let status = self.inner.step_end(interp, data, is_static, eval);
+        if status != InstructionResult::Continue {
+            return status
+        }
+        match self.extension.as_mut() {
+            Some(extension) => extension.step_end(interp, data, is_static, eval),
+            None => InstructionResult::Continue,
+        }
+    }
+
+    fn call(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        inputs: &mut CallInputs,
+        is_static: bool,
+    ) -> (InstructionResult, Gas, Bytes) {
+        let (status, gas, retdata) = self.inner.call(data, inputs, is_static);
+        if status != InstructionResult::Continue {
+            return (status, gas, retdata)
+        }
+        match self.extension.as_mut() {
+            Some(extension) => extension.call(data, inputs, is_static),
+            None => (InstructionResult::Continue, Gas::new(inputs.gas_limit), Bytes::new()),
+        }
+    }
+
+    fn call_end(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        inputs: &CallInputs,
+        remaining_gas: Gas,
+        ret: InstructionResult,
+        out: Bytes,
+        is_static: bool,
+    ) -> (InstructionResult, Gas, Bytes) {
+        let (ret, remaining_gas, out) = match self.extension.as_mut() {
+            Some(extension) => extension.call_end(data, inputs, remaining_gas, ret, out, is_static),
+            None => (ret, remaining_gas, out),
+        };
+        self.inner.call_end(data, inputs, remaining_gas, ret, out, is_static)
+    }
+
+    fn create(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        inputs: &mut CreateInputs,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        let (status, addr, gas, retdata) = self.inner.create(data, inputs);
+        if status != InstructionResult::Continue {
+            return (status, addr, gas, retdata)
+        }
+        match self.extension.as_mut() {
+            Some(extension) => extension.create(data, inputs),
+            None => (InstructionResult::Continue, None, Gas::new(inputs.gas_limit), Bytes::new()),
+        }
+    }
+
+    fn create_end(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        inputs: &CreateInputs,
+        ret: InstructionResult,
+        address: Option<Address>,
+        remaining_gas: Gas,
+        out: Bytes,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        let (ret, address, remaining_gas, out) = match self.extension.as_mut() {
+            Some(extension) => extension.create_end(data, inputs, ret, address, remaining_gas, out),
+            None => (ret, address, remaining_gas, out),
+        };
+        self.inner.create_end(data, inputs, ret, address, remaining_gas, out)
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address) {
+        self.inner.selfdestruct(contract, target);
+        if let Some(extension) = self.extension.as_mut() {
+            Inspector::<DB>::selfdestruct(extension, contract, target);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::{
+        db::{CacheDB, EmptyDB},
+        primitives::{ExecutionResult, Output, TransactTo},
+        EVM,
+    };
+
+    #[derive(Debug)]
+    struct Echo;
+
+    impl EvmExtension for Echo {
+        fn precompiles(&self) -> Vec<(Address, CustomPrecompileFn)> {
+            let echo: CustomPrecompileFn = Arc::new(|input, gas_limit| {
+                if gas_limit < 100 {
+                    return Err(CustomPrecompileError::OutOfGas)
+                }
+                Ok((100, Bytes::copy_from_slice(input)))
+            });
+            vec![(Address::from_low_u64_be(0x100), echo)]
+        }
+    }
+
+    fn call_echo(gas_limit: u64) -> ExecutionResult {
+        let mut evm = EVM::new();
+        evm.database(CacheDB::new(EmptyDB::default()));
+        evm.env.tx.gas_limit = gas_limit;
+        evm.env.tx.transact_to = TransactTo::Call(Address::from_low_u64_be(0x100));
+        evm.env.tx.data = Bytes::from_static(b"hello");
+        evm.inspect(ExtensionStack::new(Arc::new(Echo))).unwrap().result
+    }
+
+    #[test]
+    fn runs_custom_precompile() {
+        match call_echo(50_000) {
+            ExecutionResult::Success { output: Output::Call(out), gas_used, .. } => {
+                assert_eq!(out, Bytes::from_static(b"hello"));
+                // intrinsic gas, 5 non zero bytes of call data and the precompile itself
+                assert_eq!(gas_used, 21_000 + 5 * 16 + 100);
+            }
+            res => panic!("unexpected result {res:?}"),
+        }
+    }
+
+    #[test]
+    fn custom_precompile_out_of_gas() {
+        assert!(matches!(call_echo(21_000 + 5 * 16 + 50), ExecutionResult::Halt { .. }));
+    }
+}
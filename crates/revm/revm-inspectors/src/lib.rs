@@ -10,6 +10,9 @@
 /// An inspector implementation for an EIP2930 Accesslist
 pub mod access_list;
 
+/// Extension point for custom precompiles, spec overrides and inspectors
+pub mod extension;
+
 /// An inspector stack abstracting the implementation details of
 /// each inspector and allowing to hook on block/transaction execution,
 /// used in the main RETH executor.
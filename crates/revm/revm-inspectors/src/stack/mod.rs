@@ -1,5 +1,6 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, sync::Arc};
 
+use crate::extension::{EvmExtension, ExtensionStack};
 use reth_primitives::{bytes::Bytes, Address, TxHash, H256};
 use revm::{
     inspectors::CustomPrintTracer,
@@ -37,6 +38,8 @@ pub enum Hook {
 pub struct InspectorStack {
     /// An inspector that prints the opcode traces to the console.
     pub custom_print_tracer: Option<CustomPrintTracer>,
+    /// The custom precompiles and inspector of the configured [EvmExtension].
+    pub extension: Option<ExtensionStack>,
     /// The provided hook
     pub hook: Hook,
 }
@@ -45,6 +48,7 @@ impl Debug for InspectorStack {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("InspectorStack")
             .field("custom_print_tracer", &self.custom_print_tracer.is_some())
+            .field("extension", &self.extension)
             .field("hook", &self.hook)
             .finish()
     }
@@ -59,11 +63,26 @@ impl InspectorStack {
             stack.custom_print_tracer = Some(CustomPrintTracer::default());
         }
 
+        if let Some(extension) = config.extension {
+            stack = stack.with_extension(extension);
+        }
+
         stack
     }
 
+    /// Applies the given [EvmExtension] to all executions.
+    pub fn with_extension(mut self, extension: Arc<dyn EvmExtension>) -> Self {
+        self.extension = Some(ExtensionStack::new(extension));
+        self
+    }
+
     /// Check if the inspector should be used.
+    ///
+    /// This is always the case if an [EvmExtension] is configured.
     pub fn should_inspect(&self, env: &Env, tx_hash: TxHash) -> bool {
+        if self.extension.is_some() {
+            return true
+        }
         match self.hook {
             Hook::None => false,
             Hook::Block(block) => env.block.number.to::<u64>() == block,
@@ -82,6 +101,9 @@ pub struct InspectorStackConfig {
 
     /// Hook on a specific block or transaction.
     pub hook: Hook,
+
+    /// The extension to apply to all executions.
+    pub extension: Option<Arc<dyn EvmExtension>>,
 }
 
 /// Helper macro to call the same method on multiple inspectors without resorting to dynamic
@@ -107,7 +129,7 @@ where
         data: &mut EVMData<'_, DB>,
         is_static: bool,
     ) -> InstructionResult {
-        call_inspectors!(inspector, [&mut self.custom_print_tracer], {
+        call_inspectors!(inspector, [&mut self.custom_print_tracer, &mut self.extension], {
             let status = inspector.initialize_interp(interpreter, data, is_static);
 
             // Allow inspectors to exit early
@@ -125,7 +147,7 @@ where
         data: &mut EVMData<'_, DB>,
         is_static: bool,
     ) -> InstructionResult {
-        call_inspectors!(inspector, [&mut self.custom_print_tracer], {
+        call_inspectors!(inspector, [&mut self.custom_print_tracer, &mut self.extension], {
             let status = inspector.step(interpreter, data, is_static);
 
             // Allow inspectors to exit early
@@ -144,7 +166,7 @@ where
         topics: &[H256],
         data: &Bytes,
     ) {
-        call_inspectors!(inspector, [&mut self.custom_print_tracer], {
+        call_inspectors!(inspector, [&mut self.custom_print_tracer, &mut self.extension], {
             inspector.log(evm_data, address, topics, data);
         });
     }
@@ -156,7 +178,7 @@ where
         is_static: bool,
         eval: InstructionResult,
     ) -> InstructionResult {
-        call_inspectors!(inspector, [&mut self.custom_print_tracer], {
+        call_inspectors!(inspector, [&mut self.custom_print_tracer, &mut self.extension], {
             let status = inspector.step_end(interpreter, data, is_static, eval);
 
             // Allow inspectors to exit early
@@ -174,7 +196,7 @@ where
         inputs: &mut CallInputs,
         is_static: bool,
     ) -> (InstructionResult, Gas, Bytes) {
-        call_inspectors!(inspector, [&mut self.custom_print_tracer], {
+        call_inspectors!(inspector, [&mut self.custom_print_tracer, &mut self.extension], {
             let (status, gas, retdata) = inspector.call(data, inputs, is_static);
 
             // Allow inspectors to exit early
@@ -195,7 +217,7 @@ where
         out: Bytes,
         is_static: bool,
     ) -> (InstructionResult, Gas, Bytes) {
-        call_inspectors!(inspector, [&mut self.custom_print_tracer], {
+        call_inspectors!(inspector, [&mut self.custom_print_tracer, &mut self.extension], {
             let (new_ret, new_gas, new_out) =
                 inspector.call_end(data, inputs, remaining_gas, ret, out.clone(), is_static);
 
@@ -214,7 +236,7 @@ where
         data: &mut EVMData<'_, DB>,
         inputs: &mut CreateInputs,
     ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
-        call_inspectors!(inspector, [&mut self.custom_print_tracer], {
+        call_inspectors!(inspector, [&mut self.custom_print_tracer, &mut self.extension], {
             let (status, addr, gas, retdata) = inspector.create(data, inputs);
 
             // Allow inspectors to exit early
@@ -235,7 +257,7 @@ where
         remaining_gas: Gas,
         out: Bytes,
     ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
-        call_inspectors!(inspector, [&mut self.custom_print_tracer], {
+        call_inspectors!(inspector, [&mut self.custom_print_tracer, &mut self.extension], {
             let (new_ret, new_address, new_gas, new_retdata) =
                 inspector.create_end(data, inputs, ret, address, remaining_gas, out.clone());
 
@@ -248,7 +270,7 @@ where
     }
 
     fn selfdestruct(&mut self, contract: Address, target: Address) {
-        call_inspectors!(inspector, [&mut self.custom_print_tracer], {
+        call_inspectors!(inspector, [&mut self.custom_print_tracer, &mut self.extension], {
             Inspector::<DB>::selfdestruct(inspector, contract, target);
         });
     }
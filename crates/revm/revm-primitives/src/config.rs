@@ -19,6 +19,8 @@ pub fn revm_spec_by_timestamp_after_merge(
 
 /// return revm_spec from spec configuration.
 pub fn revm_spec(chain_spec: &ChainSpec, block: Head) -> revm::primitives::SpecId {
+    // TODO: revm doesn't support Cancun yet, the executor refuses to execute Cancun blocks until
+    // Cancun can be mapped to its own spec
     if chain_spec.fork(Hardfork::Shanghai).active_at_head(&block) {
         revm::primitives::SHANGHAI
     } else if chain_spec.fork(Hardfork::Paris).active_at_head(&block) {
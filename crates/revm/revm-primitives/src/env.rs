@@ -1,7 +1,7 @@
 use crate::config::revm_spec;
 use reth_primitives::{
-    recover_signer, Address, Bytes, Chain, ChainSpec, Head, Header, Transaction, TransactionKind,
-    TransactionSignedEcRecovered, TxEip1559, TxEip2930, TxLegacy, U256,
+    recover_signer, Address, Bytes, ChainSpec, Head, Header, Transaction, TransactionKind,
+    TransactionSignedEcRecovered, TxEip1559, TxEip2930, TxEip4844, TxLegacy, U256,
 };
 use revm::primitives::{AnalysisKind, BlockEnv, CfgEnv, SpecId, TransactTo, TxEnv};
 
@@ -77,7 +77,7 @@ pub fn fill_block_env_with_coinbase(
 
 /// Return the coinbase address for the given header and chain spec.
 pub fn block_coinbase(chain_spec: &ChainSpec, header: &Header, after_merge: bool) -> Address {
-    if chain_spec.chain == Chain::goerli() && !after_merge {
+    if chain_spec.clique.is_some() && !after_merge {
         recover_header_signer(header).expect("failed to recover signer")
     } else {
         header.beneficiary
@@ -90,7 +90,7 @@ pub fn recover_header_signer(header: &Header) -> Option<Address> {
     // Fixed number of extra-data suffix bytes reserved for signer signature.
     // 65 bytes fixed as signatures are based on the standard secp256k1 curve.
     // Filled with zeros on genesis block.
-    let signature_start_byte = extra_data_len - 65;
+    let signature_start_byte = extra_data_len.checked_sub(65)?;
     let signature: [u8; 65] = header.extra_data[signature_start_byte..].try_into().ok()?;
     let seal_hash = {
         let mut header_to_seal = header.clone();
@@ -212,5 +212,44 @@ where
                 })
                 .collect();
         }
+        Transaction::Eip4844(TxEip4844 {
+            nonce,
+            chain_id,
+            gas_limit,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            to,
+            value,
+            access_list,
+            blob_versioned_hashes: _,
+            max_fee_per_blob_gas: _,
+            input,
+        }) => {
+            // revm is not aware of blob gas, the blob fee is charged by the executor
+            tx_env.gas_limit = *gas_limit;
+            tx_env.gas_price = U256::from(*max_fee_per_gas);
+            tx_env.gas_priority_fee = Some(U256::from(*max_priority_fee_per_gas));
+            tx_env.transact_to = match to {
+                TransactionKind::Call(to) => TransactTo::Call(*to),
+                TransactionKind::Create => TransactTo::create(),
+            };
+            tx_env.value = U256::from(*value);
+            tx_env.data = input.0.clone();
+            tx_env.chain_id = Some(*chain_id);
+            tx_env.nonce = Some(*nonce);
+            tx_env.access_list = access_list
+                .0
+                .iter()
+                .map(|l| {
+                    (
+                        l.address,
+                        l.storage_keys
+                            .iter()
+                            .map(|k| U256::from_be_bytes(k.to_fixed_bytes()))
+                            .collect(),
+                    )
+                })
+                .collect();
+        }
     }
 }
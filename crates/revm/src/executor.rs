@@ -2,15 +2,18 @@ use crate::{
     database::SubState,
     env::{fill_cfg_and_block_env, fill_tx_env},
     eth_dao_fork::{DAO_HARDFORK_BENEFICIARY, DAO_HARDKFORK_ACCOUNTS},
+    extension::{EvmExtension, ExtensionStack},
     into_reth_log,
+    parallel::{self, ParallelExecutionConfig},
     stack::{InspectorStack, InspectorStackConfig},
     to_reth_acc,
 };
 use reth_consensus_common::calc;
 use reth_interfaces::executor::BlockExecutionError;
 use reth_primitives::{
-    Account, Address, Block, BlockNumber, Bloom, Bytecode, ChainSpec, Hardfork, Header, Receipt,
-    ReceiptWithBloom, TransactionSigned, Withdrawal, H256, U256,
+    constants::MAX_DATA_GAS_PER_BLOCK, Account, Address, Block, BlockNumber, Bloom, Bytecode,
+    ChainSpec, Hardfork, Head, Header, Receipt, ReceiptWithBloom, TransactionSigned, Withdrawal,
+    H256, U256,
 };
 use reth_provider::{BlockExecutor, PostState, StateProvider};
 use revm::{
@@ -35,6 +38,8 @@ where
     pub chain_spec: Arc<ChainSpec>,
     evm: EVM<SubState<DB>>,
     stack: InspectorStack,
+    /// If set, the transactions of a block are speculatively executed in parallel.
+    parallel: Option<ParallelExecutionConfig>,
 }
 
 impl<DB> From<Arc<ChainSpec>> for Executor<DB>
@@ -45,7 +50,12 @@ where
     /// `with_db` to set the database before executing.
     fn from(chain_spec: Arc<ChainSpec>) -> Self {
         let evm = EVM::new();
-        Executor { chain_spec, evm, stack: InspectorStack::new(InspectorStackConfig::default()) }
+        Executor {
+            chain_spec,
+            evm,
+            stack: InspectorStack::new(InspectorStackConfig::default()),
+            parallel: None,
+        }
     }
 }
 
@@ -58,7 +68,12 @@ where
         let mut evm = EVM::new();
         evm.database(db);
 
-        Executor { chain_spec, evm, stack: InspectorStack::new(InspectorStackConfig::default()) }
+        Executor {
+            chain_spec,
+            evm,
+            stack: InspectorStack::new(InspectorStackConfig::default()),
+            parallel: None,
+        }
     }
 
     /// Configures the executor with the given inspectors.
@@ -67,6 +82,20 @@ where
         self
     }
 
+    /// Applies the given [EvmExtension] to all transactions executed by this executor.
+    pub fn with_evm_extension(mut self, extension: Arc<dyn EvmExtension>) -> Self {
+        self.stack.extension = Some(ExtensionStack::new(extension));
+        self
+    }
+
+    /// Configures the executor to speculatively execute the transactions of a block in parallel.
+    ///
+    /// See [parallel](crate::parallel) for details.
+    pub fn with_parallel_execution(mut self, config: ParallelExecutionConfig) -> Self {
+        self.parallel = Some(config);
+        self
+    }
+
     /// Gives a reference to the database
     pub fn db(&mut self) -> &mut SubState<DB> {
         self.evm.db().expect("db to not be moved")
@@ -99,6 +128,17 @@ where
             header,
             total_difficulty,
         );
+
+        if let Some(extension) = &self.stack.extension {
+            let head = Head {
+                number: header.number,
+                hash: Default::default(),
+                difficulty: header.difficulty,
+                total_difficulty,
+                timestamp: header.timestamp,
+            };
+            self.evm.env.cfg.spec_id = extension.spec_id(&head, self.evm.env.cfg.spec_id);
+        }
     }
 
     /// Commit change to the run-time database, and update the given [PostState] with the changes
@@ -172,6 +212,59 @@ where
             .map_err(|_| BlockExecutionError::ProviderError)
     }
 
+    /// Charges the blob fee of an EIP-4844 transaction to its sender.
+    ///
+    /// revm is not aware of blob gas, so the fee is deducted (and burned) before the transaction
+    /// is executed.
+    ///
+    /// As required by EIP-4844, the sender must be able to afford the transaction at its maximum
+    /// fees (`gas_limit * max_fee_per_gas + value + blob_gas * max_fee_per_blob_gas`), even though
+    /// only the blob fee at the block's blob gas price is charged.
+    fn charge_blob_fee(
+        &mut self,
+        block_number: BlockNumber,
+        transaction: &TransactionSigned,
+        sender: Address,
+        blob_gasprice: Option<u128>,
+        post_state: &mut PostState,
+    ) -> Result<(), BlockExecutionError> {
+        let hash = transaction.hash();
+        let Some(blob_gasprice) = blob_gasprice else {
+            return Err(BlockExecutionError::MissingBlobGasPrice { hash })
+        };
+        let max_fee_per_blob_gas = transaction.max_fee_per_blob_gas().unwrap_or_default();
+        if max_fee_per_blob_gas < blob_gasprice {
+            return Err(BlockExecutionError::BlobFeeCapTooLow {
+                hash,
+                max_fee_per_blob_gas,
+                blob_gasprice,
+            })
+        }
+
+        let blob_gas = U256::from(transaction.blob_gas());
+        let max_cost = U256::from(transaction.gas_limit()) *
+            U256::from(transaction.max_fee_per_gas()) +
+            U256::from(transaction.value()) +
+            blob_gas * U256::from(max_fee_per_blob_gas);
+        let balance = self
+            .db()
+            .load_account(sender)
+            .map_err(|_| BlockExecutionError::ProviderError)?
+            .info
+            .balance;
+        if balance < max_cost {
+            return Err(BlockExecutionError::InsufficientFundsForMaxCost { hash, balance, max_cost })
+        }
+
+        let fee = blob_gas * U256::from(blob_gasprice);
+        let charged = decrement_account_balance(self.db(), post_state, block_number, sender, fee)
+            .map_err(|_| BlockExecutionError::ProviderError)?;
+        if !charged {
+            return Err(BlockExecutionError::InsufficientFundsForBlobFee { hash })
+        }
+        Ok(())
+    }
+
     /// Runs a single transaction in the configured environment and proceeds
     /// to return the result and state diff (without applying it).
     ///
@@ -217,6 +310,12 @@ where
         total_difficulty: U256,
         senders: Option<Vec<Address>>,
     ) -> Result<(PostState, u64), BlockExecutionError> {
+        // revm doesn't support Cancun yet, executing Cancun blocks with the Shanghai spec would
+        // silently produce a wrong state
+        if self.chain_spec.is_cancun_activated_at_timestamp(block.timestamp) {
+            return Err(BlockExecutionError::CancunUnsupported { number: block.number })
+        }
+
         // perf: do not execute empty blocks
         if block.body.is_empty() {
             return Ok((PostState::default(), 0))
@@ -225,9 +324,24 @@ where
 
         self.init_env(&block.header, total_difficulty);
 
+        // speculatively execute all transactions against the state at the start of the block, the
+        // speculative execution does not apply the extension
+        let mut speculative = match self.parallel {
+            Some(config)
+                if config.is_enabled_for(block.body.len()) && self.stack.extension.is_none() =>
+            {
+                let env = self.evm.env.clone();
+                parallel::speculate(&*self.db(), &env, &block.body, &senders, config.workers)
+            }
+            _ => Vec::new(),
+        };
+        let mut reexecuted = 0usize;
+
         let mut cumulative_gas_used = 0;
+        let mut cumulative_blob_gas_used = 0;
+        let blob_gasprice = block.header.blob_gasprice();
         let mut post_state = PostState::with_tx_capacity(block.number, block.body.len());
-        for (transaction, sender) in block.body.iter().zip(senders.into_iter()) {
+        for (idx, (transaction, sender)) in block.body.iter().zip(senders.into_iter()).enumerate() {
             // The sum of the transaction’s gas limit, Tg, and the gas utilised in this block prior,
             // must be no greater than the block’s gasLimit.
             let block_available_gas = block.header.gas_limit - cumulative_gas_used;
@@ -237,8 +351,47 @@ where
                     block_available_gas,
                 })
             }
-            // Execute transaction.
-            let ResultAndState { result, state } = self.transact(transaction, sender)?;
+            if transaction.is_eip4844() {
+                cumulative_blob_gas_used += transaction.blob_gas();
+                if cumulative_blob_gas_used > MAX_DATA_GAS_PER_BLOCK {
+                    return Err(BlockExecutionError::BlockBlobGasExceedsMax {
+                        got: cumulative_blob_gas_used,
+                        max: MAX_DATA_GAS_PER_BLOCK,
+                    })
+                }
+                self.charge_blob_fee(
+                    block.number,
+                    transaction,
+                    sender,
+                    blob_gasprice,
+                    &mut post_state,
+                )?;
+            }
+
+            // Execute transaction, or use its speculative result if it is still valid.
+            //
+            // Blob transactions are always re-executed, because the speculative run didn't account
+            // for the blob fee.
+            let speculated = speculative
+                .get_mut(idx)
+                .and_then(Option::take)
+                .filter(|_| !transaction.is_eip4844())
+                .filter(|_| !self.stack.should_inspect(&self.evm.env, transaction.hash()));
+            let ResultAndState { result, state } = match speculated {
+                Some(speculated) => {
+                    let beneficiary = self.evm.env.block.coinbase;
+                    match parallel::validate(self.db(), beneficiary, speculated)
+                        .map_err(|_| BlockExecutionError::ProviderError)?
+                    {
+                        Some(result_and_state) => result_and_state,
+                        None => {
+                            reexecuted += 1;
+                            self.transact(transaction, sender)?
+                        }
+                    }
+                }
+                None => self.transact(transaction, sender)?,
+            };
 
             // commit changes
             self.commit_changes(
@@ -266,6 +419,16 @@ where
             );
         }
 
+        if !speculative.is_empty() {
+            tracing::trace!(
+                target: "evm",
+                number = block.number,
+                transactions = block.body.len(),
+                reexecuted,
+                "Executed block transactions in parallel"
+            );
+        }
+
         Ok((post_state, cumulative_gas_used))
     }
 }
@@ -291,6 +454,14 @@ where
             })
         }
 
+        // Check if blob gas used matches the value set in header.
+        if let Some(expected) = block.blob_gas_used {
+            let got: u64 = block.body.iter().map(|tx| tx.blob_gas()).sum();
+            if got != expected {
+                return Err(BlockExecutionError::BlockBlobGasUsed { got, expected })
+            }
+        }
+
         // Add block rewards
         let balance_increments = self.post_block_balance_increments(block, total_difficulty);
         for (address, increment) in balance_increments.into_iter() {
@@ -375,6 +546,40 @@ where
     Ok(())
 }
 
+/// Decrement the balance for the given account in the [PostState].
+///
+/// Returns `false` and leaves the account untouched if its balance is lower than the decrement.
+/// Returns an error if the database encountered an error while loading the account.
+pub fn decrement_account_balance<DB>(
+    db: &mut CacheDB<DB>,
+    post_state: &mut PostState,
+    block_number: BlockNumber,
+    address: Address,
+    decrement: U256,
+) -> Result<bool, <DB as DatabaseRef>::Error>
+where
+    DB: DatabaseRef,
+{
+    let account = db.load_account(address)?;
+    if account.info.balance < decrement {
+        return Ok(false)
+    }
+    if decrement.is_zero() {
+        return Ok(true)
+    }
+
+    let old = to_reth_acc(&account.info);
+    account.info.balance -= decrement;
+    let new = to_reth_acc(&account.info);
+    // an account with a sufficient balance exists, so we only need to mark it as touched
+    if account.account_state == AccountState::None {
+        account.account_state = AccountState::Touched;
+    }
+    post_state.change_account(block_number, address, old, new);
+
+    Ok(true)
+}
+
 /// Commit change to the _run-time_ database [CacheDB], and update the given [PostState] with the
 /// changes made in the transaction, which can be persisted to the database.
 ///
@@ -632,15 +837,20 @@ mod tests {
     use once_cell::sync::Lazy;
     use reth_consensus_common::calc;
     use reth_primitives::{
-        constants::ETH_TO_WEI, hex_literal::hex, keccak256, Account, Address, BlockNumber,
-        Bytecode, Bytes, ChainSpecBuilder, ForkCondition, StorageKey, H256, MAINNET, U256,
+        constants::{DATA_GAS_PER_BLOB, ETH_TO_WEI},
+        hex_literal::hex,
+        keccak256, Account, Address, BlockNumber, Bytecode, Bytes, ChainSpecBuilder, ForkCondition,
+        Signature, StorageKey, Transaction, TransactionKind, TxEip4844, H256, MAINNET, U256,
     };
     use reth_provider::{
         post_state::{AccountChanges, Storage, StorageTransition, StorageWipe},
         AccountProvider, BlockHashProvider, StateProvider, StateRootProvider,
     };
     use reth_rlp::Decodable;
-    use std::{collections::HashMap, str::FromStr};
+    use std::{
+        collections::{BTreeSet, HashMap},
+        str::FromStr,
+    };
 
     static DEFAULT_REVM_ACCOUNT: Lazy<RevmAccount> = Lazy::new(|| RevmAccount {
         info: AccountInfo::default(),
@@ -731,6 +941,13 @@ mod tests {
         ) -> reth_interfaces::Result<(Vec<Bytes>, H256, Vec<Vec<Bytes>>)> {
             todo!()
         }
+
+        fn multiproof(
+            &self,
+            _targets: &BTreeMap<Address, BTreeSet<H256>>,
+        ) -> reth_interfaces::Result<Vec<Bytes>> {
+            todo!()
+        }
     }
 
     #[test]
@@ -1091,6 +1308,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_cancun_block_unsupported() {
+        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().cancun_activated().build());
+        let db = SubState::new(State::new(StateProviderTest::default()));
+        let mut executor = Executor::new(chain_spec, db);
+
+        let block =
+            Block { header: Header { number: 1, ..Default::default() }, ..Default::default() };
+        assert_eq!(
+            executor.execute(&block, U256::ZERO, None),
+            Err(BlockExecutionError::CancunUnsupported { number: 1 })
+        );
+    }
+
     #[test]
     fn test_account_state_preserved() {
         let account = Address::from_str("c94f5374fce5edbc8e2a8697c15331677e6ebf0b").unwrap();
@@ -1151,6 +1382,101 @@ mod tests {
         assert_eq!(account.account_state, AccountState::StorageCleared);
     }
 
+    #[test]
+    fn test_decrement_account_balance() {
+        let address = Address::random();
+        let balance = U256::from(1_000);
+
+        let mut db = StateProviderTest::default();
+        db.insert_account(
+            address,
+            Account { nonce: 1, balance, bytecode_hash: None },
+            None,
+            HashMap::default(),
+        );
+        let mut db = SubState::new(State::new(db));
+        let mut post_state = PostState::default();
+
+        // insufficient balance leaves the account untouched
+        assert!(!decrement_account_balance(
+            &mut db,
+            &mut post_state,
+            1,
+            address,
+            balance + U256::from(1)
+        )
+        .unwrap());
+        assert!(post_state.accounts().is_empty());
+
+        assert!(decrement_account_balance(&mut db, &mut post_state, 1, address, U256::from(400))
+            .unwrap());
+        assert_eq!(
+            post_state.accounts().get(&address).unwrap(),
+            &Some(Account { nonce: 1, balance: U256::from(600), bytecode_hash: None })
+        );
+        let account = db.load_account(address).unwrap();
+        assert_eq!(account.info.balance, U256::from(600));
+        assert_eq!(account.account_state, AccountState::Touched);
+    }
+
+    #[test]
+    fn test_blob_fee_requires_max_cost() {
+        let sender = Address::random();
+        let transaction = TransactionSigned::from_transaction_and_signature(
+            Transaction::Eip4844(TxEip4844 {
+                gas_limit: 21_000,
+                max_fee_per_gas: 10,
+                to: TransactionKind::Call(Address::random()),
+                value: 1,
+                blob_versioned_hashes: vec![H256::random()],
+                max_fee_per_blob_gas: 10,
+                ..Default::default()
+            }),
+            Signature::default(),
+        );
+        let blob_gasprice = 1;
+        let blob_fee = U256::from(DATA_GAS_PER_BLOB * blob_gasprice);
+        let max_cost = U256::from(21_000 * 10 + 1 + DATA_GAS_PER_BLOB * 10);
+
+        let executor = |balance| {
+            let mut db = StateProviderTest::default();
+            db.insert_account(
+                sender,
+                Account { nonce: 0, balance, bytecode_hash: None },
+                None,
+                HashMap::default(),
+            );
+            Executor::new(MAINNET.clone(), SubState::new(State::new(db)))
+        };
+
+        // the sender can pay for the actual blob fee, but not for the transaction at its max fees
+        let balance = max_cost - U256::from(1);
+        assert!(balance > blob_fee);
+        let mut post_state = PostState::default();
+        assert_eq!(
+            executor(balance).charge_blob_fee(
+                1,
+                &transaction,
+                sender,
+                Some(blob_gasprice as u128),
+                &mut post_state
+            ),
+            Err(BlockExecutionError::InsufficientFundsForMaxCost {
+                hash: transaction.hash(),
+                balance,
+                max_cost
+            })
+        );
+        assert!(post_state.accounts().is_empty());
+
+        // only the actual blob fee is charged
+        let mut executor = executor(max_cost);
+        executor
+            .charge_blob_fee(1, &transaction, sender, Some(blob_gasprice as u128), &mut post_state)
+            .unwrap();
+        assert_eq!(executor.db().load_account(sender).unwrap().info.balance, max_cost - blob_fee);
+    }
+
     /// If the account is created and destroyed within the same transaction, we shouldn't generate
     /// the changeset.
     #[test]
@@ -1,5 +1,7 @@
 use crate::{
     database::{State, SubState},
+    extension::EvmExtension,
+    parallel::ParallelExecutionConfig,
     stack::{InspectorStack, InspectorStackConfig},
 };
 use reth_primitives::ChainSpec;
@@ -13,12 +15,14 @@ use std::sync::Arc;
 pub struct Factory {
     chain_spec: Arc<ChainSpec>,
     stack: Option<InspectorStack>,
+    parallel: Option<ParallelExecutionConfig>,
+    extension: Option<Arc<dyn EvmExtension>>,
 }
 
 impl Factory {
     /// Create new factory
     pub fn new(chain_spec: Arc<ChainSpec>) -> Self {
-        Self { chain_spec, stack: None }
+        Self { chain_spec, stack: None, parallel: None, extension: None }
     }
 
     /// Sets the inspector stack for all generated executors.
@@ -32,6 +36,23 @@ impl Factory {
         self.stack = Some(InspectorStack::new(config));
         self
     }
+
+    /// Applies the given [EvmExtension] in all generated executors.
+    pub fn with_evm_extension(mut self, extension: Arc<dyn EvmExtension>) -> Self {
+        self.extension = Some(extension);
+        self
+    }
+
+    /// Returns the configured [EvmExtension], if any.
+    pub fn evm_extension(&self) -> Option<&Arc<dyn EvmExtension>> {
+        self.extension.as_ref()
+    }
+
+    /// Speculatively executes the transactions of a block in parallel in all generated executors.
+    pub fn with_parallel_execution(mut self, config: ParallelExecutionConfig) -> Self {
+        self.parallel = Some(config);
+        self
+    }
 }
 
 impl ExecutorFactory for Factory {
@@ -45,6 +66,12 @@ impl ExecutorFactory for Factory {
         if let Some(ref stack) = self.stack {
             executor = executor.with_stack(stack.clone());
         }
+        if let Some(ref extension) = self.extension {
+            executor = executor.with_evm_extension(Arc::clone(extension));
+        }
+        if let Some(parallel) = self.parallel {
+            executor = executor.with_parallel_execution(parallel);
+        }
         executor
     }
 
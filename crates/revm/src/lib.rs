@@ -14,6 +14,9 @@ pub mod database;
 pub mod executor;
 mod factory;
 
+/// Speculative parallel execution of the transactions of a block.
+pub mod parallel;
+
 /// revm executor factory.
 pub use factory::Factory;
 
@@ -25,5 +28,8 @@ pub use reth_revm_primitives::*;
 /// Re-export everything
 pub use revm;
 
+/// Execution witness recording and stateless block verification.
+pub mod witness;
+
 /// Etereum DAO hardfork state change data.
 pub mod eth_dao_fork;
@@ -0,0 +1,643 @@
+//! Speculative parallel execution of the transactions of a block.
+//!
+//! All transactions of a block are first executed concurrently against the state at the start of
+//! the block, recording every value they read through a [ReadRecorder]. The speculative results
+//! are then committed in block order: a result is only used if every value the transaction read is
+//! unchanged in the state produced by the transactions before it, otherwise the transaction is
+//! re-executed on top of that state. Since execution is a pure function of the environment and the
+//! values read, a validated result is exactly the result sequential execution would have produced.
+//!
+//! Every transaction pays its fees to the block's beneficiary, which would make all transactions of
+//! a block conflict with each other. The beneficiary's balance is therefore treated separately: if
+//! a transaction never observes it (see [BeneficiaryBalanceInspector]), a balance change made by
+//! the transactions before it does not invalidate the speculative result, and the balance
+//! difference the transaction produced is applied on top of the committed balance instead.
+
+use crate::env::fill_tx_env;
+use reth_primitives::{bytes::Bytes, Address, TransactionSigned, H256, U256};
+use revm::{
+    db::{CacheDB, DatabaseRef},
+    interpreter::{opcode, CallInputs, CreateInputs, Gas, InstructionResult, Interpreter},
+    primitives::{AccountInfo, Bytecode, Env, ResultAndState},
+    Database, EVMData, Inspector, EVM,
+};
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// The default minimum number of transactions a block must have to be executed in parallel.
+pub const DEFAULT_MIN_PARALLEL_TRANSACTIONS: usize = 8;
+
+/// The size of the stack of the speculative execution threads.
+///
+/// Same as the stack of the execution stage, see <https://github.com/bluealloy/revm/issues/305>.
+const WORKER_STACK_SIZE: usize = 64 * 1024 * 1024;
+
+/// Configures speculative parallel execution of the transactions within a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParallelExecutionConfig {
+    /// The number of threads transactions are speculatively executed on.
+    pub workers: usize,
+    /// Blocks with fewer transactions are executed sequentially.
+    pub min_transactions: usize,
+}
+
+impl ParallelExecutionConfig {
+    /// Creates a new config with the given number of worker threads.
+    pub fn new(workers: usize) -> Self {
+        Self { workers: workers.max(1), ..Default::default() }
+    }
+
+    /// Sets the minimum number of transactions a block must have to be executed in parallel.
+    pub fn with_min_transactions(mut self, min_transactions: usize) -> Self {
+        self.min_transactions = min_transactions;
+        self
+    }
+
+    /// Returns true if a block with the given number of transactions should be executed in
+    /// parallel.
+    pub fn is_enabled_for(&self, transactions: usize) -> bool {
+        self.workers > 1 && transactions > 1 && transactions >= self.min_transactions
+    }
+}
+
+impl Default for ParallelExecutionConfig {
+    fn default() -> Self {
+        Self {
+            workers: std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+            min_transactions: DEFAULT_MIN_PARALLEL_TRANSACTIONS,
+        }
+    }
+}
+
+/// All values a transaction read from the database.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ReadSet {
+    /// The accounts read, and the value they had.
+    pub accounts: HashMap<Address, Option<AccountInfo>>,
+    /// The storage slots read, and the value they had.
+    pub storage: HashMap<(Address, U256), U256>,
+    /// The bytecodes read, and whether they were empty.
+    pub contracts: HashMap<H256, bool>,
+}
+
+/// A [Database] that reads from a shared [DatabaseRef] and records every value it returns.
+///
+/// Block hashes are not recorded since they cannot change during the execution of a block.
+#[derive(Debug)]
+pub struct ReadRecorder<'a, DB> {
+    db: &'a DB,
+    reads: ReadSet,
+}
+
+impl<'a, DB> ReadRecorder<'a, DB> {
+    /// Creates a new recorder on top of the given database.
+    pub fn new(db: &'a DB) -> Self {
+        Self { db, reads: ReadSet::default() }
+    }
+
+    /// Returns the values read so far and resets the recorder.
+    pub fn take_reads(&mut self) -> ReadSet {
+        std::mem::take(&mut self.reads)
+    }
+}
+
+impl<'a, DB: DatabaseRef> Database for ReadRecorder<'a, DB> {
+    type Error = <DB as DatabaseRef>::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        if let Some(info) = self.reads.accounts.get(&address) {
+            return Ok(info.clone())
+        }
+        let info = self.db.basic(address)?;
+        self.reads.accounts.insert(address, info.clone());
+        Ok(info)
+    }
+
+    fn code_by_hash(&mut self, code_hash: H256) -> Result<Bytecode, Self::Error> {
+        let code = self.db.code_by_hash(code_hash)?;
+        self.reads.contracts.entry(code_hash).or_insert_with(|| code.is_empty());
+        Ok(code)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        if let Some(value) = self.reads.storage.get(&(address, index)) {
+            return Ok(*value)
+        }
+        let value = self.db.storage(address, index)?;
+        self.reads.storage.insert((address, index), value);
+        Ok(value)
+    }
+
+    fn block_hash(&mut self, number: U256) -> Result<H256, Self::Error> {
+        self.db.block_hash(number)
+    }
+}
+
+/// An [Inspector] that detects whether a transaction can observe the balance of the block's
+/// beneficiary.
+///
+/// This is conservative: besides reading the balance via `BALANCE` or `SELFBALANCE`, any code
+/// executed on behalf of the beneficiary counts as observing it, since sending value requires a
+/// balance check.
+#[derive(Debug)]
+pub struct BeneficiaryBalanceInspector {
+    beneficiary: Address,
+    observed: bool,
+}
+
+impl BeneficiaryBalanceInspector {
+    /// Creates a new inspector for the given beneficiary.
+    pub fn new(beneficiary: Address) -> Self {
+        Self { beneficiary, observed: false }
+    }
+
+    /// Returns true if the beneficiary's balance was observed.
+    pub fn observed(&self) -> bool {
+        self.observed
+    }
+}
+
+impl<DB> Inspector<DB> for BeneficiaryBalanceInspector
+where
+    DB: Database,
+{
+    fn step(
+        &mut self,
+        interpreter: &mut Interpreter,
+        _data: &mut EVMData<'_, DB>,
+        _is_static: bool,
+    ) -> InstructionResult {
+        let pc = interpreter.program_counter();
+        let op = interpreter.contract.bytecode.bytecode()[pc];
+
+        match op {
+            opcode::BALANCE => {
+                if let Ok(slot) = interpreter.stack().peek(0) {
+                    let address: Address = H256::from(slot.to_be_bytes()).into();
+                    self.observed |= address == self.beneficiary;
+                }
+            }
+            opcode::SELFBALANCE | opcode::SELFDESTRUCT => {
+                self.observed |= interpreter.contract.address == self.beneficiary;
+            }
+            _ => (),
+        }
+
+        InstructionResult::Continue
+    }
+
+    fn call(
+        &mut self,
+        _data: &mut EVMData<'_, DB>,
+        inputs: &mut CallInputs,
+        _is_static: bool,
+    ) -> (InstructionResult, Gas, Bytes) {
+        self.observed |=
+            inputs.context.caller == self.beneficiary || inputs.transfer.source == self.beneficiary;
+
+        (InstructionResult::Continue, Gas::new(0), Bytes::new())
+    }
+
+    fn create(
+        &mut self,
+        _data: &mut EVMData<'_, DB>,
+        inputs: &mut CreateInputs,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        self.observed |= inputs.caller == self.beneficiary;
+
+        (InstructionResult::Continue, None, Gas::new(0), Bytes::new())
+    }
+}
+
+/// The result of executing a transaction against the state at the start of its block.
+#[derive(Debug)]
+pub struct SpeculativeResult {
+    /// The result and state changes of the transaction.
+    pub result_and_state: ResultAndState,
+    /// The values the transaction read.
+    pub reads: ReadSet,
+    /// Whether the transaction observed the balance of the block's beneficiary.
+    pub observed_beneficiary_balance: bool,
+}
+
+/// Speculatively executes all `transactions` concurrently against `db` on `workers` threads.
+///
+/// The returned vector has one entry per transaction, `None` if the speculative execution failed.
+/// `env` must be configured for the block the transactions belong to.
+pub fn speculate<DB>(
+    db: &DB,
+    env: &Env,
+    transactions: &[TransactionSigned],
+    senders: &[Address],
+    workers: usize,
+) -> Vec<Option<SpeculativeResult>>
+where
+    DB: DatabaseRef + Sync,
+{
+    let mut results = Vec::with_capacity(transactions.len());
+    results.resize_with(transactions.len(), || None);
+
+    let next = AtomicUsize::new(0);
+    std::thread::scope(|scope| {
+        let handles = (0..workers.clamp(1, transactions.len().max(1)))
+            .map(|_| {
+                std::thread::Builder::new()
+                    .stack_size(WORKER_STACK_SIZE)
+                    .spawn_scoped(scope, || {
+                        let mut evm = EVM::new();
+                        evm.env = env.clone();
+                        evm.database(ReadRecorder::new(db));
+
+                        let mut executed = Vec::new();
+                        loop {
+                            let idx = next.fetch_add(1, Ordering::Relaxed);
+                            let (Some(transaction), Some(sender)) =
+                                (transactions.get(idx), senders.get(idx))
+                            else {
+                                break
+                            };
+                            executed
+                                .push((idx, speculate_transaction(&mut evm, transaction, *sender)));
+                        }
+                        executed
+                    })
+                    .expect("Expects that thread name is not null")
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            for (idx, result) in handle.join().expect("Expects for thread to not panic") {
+                results[idx] = result;
+            }
+        }
+    });
+
+    results
+}
+
+/// Executes a single transaction against the recorder's database.
+fn speculate_transaction<DB: DatabaseRef>(
+    evm: &mut EVM<ReadRecorder<'_, DB>>,
+    transaction: &TransactionSigned,
+    sender: Address,
+) -> Option<SpeculativeResult> {
+    fill_tx_env(&mut evm.env.tx, transaction, sender);
+
+    let beneficiary = evm.env.block.coinbase;
+    let mut inspector = BeneficiaryBalanceInspector::new(beneficiary);
+    let out = evm.inspect(&mut inspector);
+    let reads = evm.db().expect("db to not be moved").take_reads();
+
+    Some(SpeculativeResult {
+        result_and_state: out.ok()?,
+        reads,
+        // the sender's balance is checked and charged before execution
+        observed_beneficiary_balance: inspector.observed() || sender == beneficiary,
+    })
+}
+
+/// Validates a speculative result against the current state of the block.
+///
+/// Returns the result of the transaction if it is identical to the result of executing the
+/// transaction on top of `db`, or `None` if the transaction has to be re-executed.
+///
+/// If the result is valid, all values the transaction read are loaded into `db`, exactly like
+/// sequential execution would have done, so that committing the returned state changes yields the
+/// same [PostState](reth_provider::PostState).
+pub fn validate<DB>(
+    db: &mut CacheDB<DB>,
+    beneficiary: Address,
+    speculative: SpeculativeResult,
+) -> Result<Option<ResultAndState>, <DB as DatabaseRef>::Error>
+where
+    DB: DatabaseRef,
+{
+    let SpeculativeResult { mut result_and_state, reads, observed_beneficiary_balance } =
+        speculative;
+
+    // check all reads without touching the cache, a rejected result must not leave any trace
+    let mut beneficiary_balance = None;
+    for (address, read) in &reads.accounts {
+        let current = DatabaseRef::basic(&*db, *address)?;
+        if *address == beneficiary && !observed_beneficiary_balance {
+            if !is_same_account_ignoring_balance(read, &current) {
+                return Ok(None)
+            }
+            beneficiary_balance = Some((
+                read.as_ref().map(|info| info.balance).unwrap_or_default(),
+                current.map(|info| info.balance).unwrap_or_default(),
+            ));
+        } else if !is_same_account(read, &current) {
+            return Ok(None)
+        }
+    }
+    for ((address, index), value) in &reads.storage {
+        if DatabaseRef::storage(&*db, *address, *index)? != *value {
+            return Ok(None)
+        }
+    }
+    for (code_hash, is_empty) in &reads.contracts {
+        if DatabaseRef::code_by_hash(&*db, *code_hash)?.is_empty() != *is_empty {
+            return Ok(None)
+        }
+    }
+
+    // rebase the beneficiary's balance onto the committed balance
+    if let Some((read, current)) = beneficiary_balance {
+        if let Some(account) = result_and_state.state.get_mut(&beneficiary) {
+            if account.info.balance < read {
+                return Ok(None)
+            }
+            account.info.balance = current + (account.info.balance - read);
+        }
+    }
+
+    // load everything the transaction read, like sequential execution would have
+    for address in reads.accounts.keys() {
+        Database::basic(db, *address)?;
+    }
+    for (address, index) in reads.storage.keys() {
+        Database::storage(db, *address, *index)?;
+    }
+    for code_hash in reads.contracts.keys() {
+        Database::code_by_hash(db, *code_hash)?;
+    }
+
+    Ok(Some(result_and_state))
+}
+
+/// Returns true if both accounts exist and are equal, or if neither exists.
+fn is_same_account(a: &Option<AccountInfo>, b: &Option<AccountInfo>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            a.balance == b.balance && is_same_account_ignoring_balance_inner(a, b)
+        }
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Like [is_same_account], but only compares whether the balances are zero, which determines if
+/// the account is empty.
+fn is_same_account_ignoring_balance(a: &Option<AccountInfo>, b: &Option<AccountInfo>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            a.balance.is_zero() == b.balance.is_zero() &&
+                is_same_account_ignoring_balance_inner(a, b)
+        }
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+fn is_same_account_ignoring_balance_inner(a: &AccountInfo, b: &AccountInfo) -> bool {
+    a.nonce == b.nonce && a.code_hash == b.code_hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        database::{State, SubState},
+        env::fill_cfg_and_block_env,
+        executor::{commit_state_changes, Executor},
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use reth_primitives::{
+        hex_literal::hex, Block, ChainSpec, ChainSpecBuilder, Header, Signature, Transaction,
+        TransactionKind, TxLegacy,
+    };
+    use reth_provider::{
+        test_utils::{ExtendedAccount, MockEthProvider},
+        PostState,
+    };
+    use std::sync::Arc;
+
+    /// Increments storage slot zero.
+    const COUNTER: [u8; 10] = hex!("60005460010160005500");
+    /// Stores the balance of the beneficiary in storage slot zero.
+    const BENEFICIARY_BALANCE: [u8; 6] = hex!("413160005500");
+    /// Stores the call value in the storage slot of the caller.
+    const DEPOSIT: [u8; 4] = hex!("34335500");
+
+    const BASE_FEE: u64 = 7;
+
+    /// A randomly generated chain of blocks.
+    struct TestChain {
+        rng: StdRng,
+        provider: MockEthProvider,
+        accounts: Vec<(Address, u64)>,
+        contracts: Vec<Address>,
+        beneficiary: Address,
+        number: u64,
+    }
+
+    impl TestChain {
+        fn new(seed: u64, accounts: usize) -> Self {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let provider = MockEthProvider::default();
+
+            let accounts = (0..accounts)
+                .map(|_| {
+                    let address = Address::from(rng.gen::<[u8; 20]>());
+                    provider
+                        .add_account(address, ExtendedAccount::new(0, U256::from(10u128.pow(20))));
+                    (address, 0)
+                })
+                .collect();
+
+            let contracts = [&COUNTER[..], &BENEFICIARY_BALANCE[..], &DEPOSIT[..]]
+                .into_iter()
+                .map(|code| {
+                    let address = Address::from(rng.gen::<[u8; 20]>());
+                    provider.add_account(
+                        address,
+                        ExtendedAccount::new(1, U256::ZERO).with_bytecode(code.to_vec().into()),
+                    );
+                    address
+                })
+                .collect();
+
+            // an existing beneficiary, otherwise its creation by the first fee payment conflicts
+            let beneficiary = Address::from(rng.gen::<[u8; 20]>());
+            provider.add_account(beneficiary, ExtendedAccount::new(0, U256::from(1)));
+
+            Self { rng, provider, accounts, contracts, beneficiary, number: 0 }
+        }
+
+        /// Generates the next block with random transactions.
+        fn next_block(&mut self, transactions: usize) -> (Block, Vec<Address>) {
+            let calls = (0..transactions)
+                .map(|_| {
+                    let sender = self.rng.gen_range(0..self.accounts.len());
+                    let to = match self.rng.gen_range(0..5) {
+                        0 => self.accounts[self.rng.gen_range(0..self.accounts.len())].0,
+                        1 => Address::from(self.rng.gen::<[u8; 20]>()),
+                        2 => self.beneficiary,
+                        _ => self.contracts[self.rng.gen_range(0..self.contracts.len())],
+                    };
+                    (sender, to)
+                })
+                .collect();
+            self.block(calls)
+        }
+
+        /// Creates the next block with a transaction for each `(sender index, recipient)` pair.
+        fn block(&mut self, calls: Vec<(usize, Address)>) -> (Block, Vec<Address>) {
+            self.number += 1;
+
+            let mut body = Vec::with_capacity(calls.len());
+            let mut senders = Vec::with_capacity(calls.len());
+            for (idx, to) in calls {
+                let (sender, nonce) = self.accounts[idx];
+                self.accounts[idx].1 += 1;
+
+                let transaction = Transaction::Legacy(TxLegacy {
+                    chain_id: Some(1),
+                    nonce,
+                    gas_price: (BASE_FEE + self.rng.gen_range(0..10)) as u128,
+                    gas_limit: 100_000,
+                    to: TransactionKind::Call(to),
+                    value: self.rng.gen_range(0..1_000),
+                    input: Default::default(),
+                });
+                body.push(TransactionSigned::from_transaction_and_signature(
+                    transaction,
+                    Signature::default(),
+                ));
+                senders.push(sender);
+            }
+
+            let header = Header {
+                number: self.number,
+                beneficiary: self.beneficiary,
+                gas_limit: 100_000 * body.len() as u64,
+                base_fee_per_gas: Some(BASE_FEE),
+                timestamp: self.number,
+                ..Default::default()
+            };
+            (Block { header, body, ommers: vec![], withdrawals: None }, senders)
+        }
+    }
+
+    fn chain_spec() -> Arc<ChainSpec> {
+        Arc::new(ChainSpecBuilder::mainnet().shanghai_activated().build())
+    }
+
+    fn executor(
+        provider: &MockEthProvider,
+        parallel: Option<ParallelExecutionConfig>,
+    ) -> Executor<MockEthProvider> {
+        let executor = Executor::new(chain_spec(), SubState::new(State::new(provider.clone())));
+        match parallel {
+            Some(config) => executor.with_parallel_execution(config),
+            None => executor,
+        }
+    }
+
+    /// Executes the same random blocks sequentially and in parallel and checks that the resulting
+    /// [PostState]s are identical.
+    fn assert_parallel_matches_sequential(seed: u64, accounts: usize, blocks: usize, txs: usize) {
+        let mut chain = TestChain::new(seed, accounts);
+        let config = ParallelExecutionConfig::new(4).with_min_transactions(2);
+        let mut sequential = executor(&chain.provider, None);
+        let mut parallel = executor(&chain.provider, Some(config));
+
+        for _ in 0..blocks {
+            let (block, senders) = chain.next_block(txs);
+            let expected =
+                sequential.execute_transactions(&block, U256::ZERO, Some(senders.clone())).unwrap();
+            let got = parallel.execute_transactions(&block, U256::ZERO, Some(senders)).unwrap();
+            assert_eq!(got, expected, "seed {seed}, block {}", block.number);
+        }
+    }
+
+    #[test]
+    fn parallel_matches_sequential_with_few_conflicts() {
+        for seed in 0..8 {
+            assert_parallel_matches_sequential(seed, 64, 4, 32);
+        }
+    }
+
+    #[test]
+    fn parallel_matches_sequential_with_many_conflicts() {
+        for seed in 0..8 {
+            assert_parallel_matches_sequential(seed, 3, 4, 32);
+        }
+    }
+
+    /// Speculatively executes the block and validates all results in order, returning which ones
+    /// could be used.
+    fn validated(provider: &MockEthProvider, block: &Block, senders: &[Address]) -> Vec<bool> {
+        let mut evm = EVM::new();
+        fill_cfg_and_block_env(
+            &mut evm.env.cfg,
+            &mut evm.env.block,
+            &chain_spec(),
+            &block.header,
+            U256::ZERO,
+        );
+        evm.database(SubState::new(State::new(provider.clone())));
+
+        let env = evm.env.clone();
+        let speculated = speculate(&*evm.db().unwrap(), &env, &block.body, senders, 4);
+
+        let mut post_state = PostState::default();
+        speculated
+            .into_iter()
+            .zip(block.body.iter().zip(senders))
+            .map(|(speculated, (transaction, sender))| {
+                let speculated = speculated.expect("speculative execution succeeds");
+                let validated =
+                    validate(evm.db().unwrap(), env.block.coinbase, speculated).unwrap();
+                let valid = validated.is_some();
+                let changes = match validated {
+                    Some(result_and_state) => result_and_state.state,
+                    None => {
+                        fill_tx_env(&mut evm.env.tx, transaction, *sender);
+                        evm.transact().unwrap().state
+                    }
+                };
+                commit_state_changes(
+                    evm.db().unwrap(),
+                    &mut post_state,
+                    block.number,
+                    changes,
+                    true,
+                );
+                valid
+            })
+            .collect()
+    }
+
+    #[test]
+    fn beneficiary_fees_do_not_conflict() {
+        let mut chain = TestChain::new(1, 8);
+        let (block, senders) = chain.block(
+            (0..8).map(|idx| (idx, Address::from_low_u64_be(0x1000 + idx as u64))).collect(),
+        );
+
+        assert_eq!(validated(&chain.provider, &block, &senders), vec![true; 8]);
+    }
+
+    #[test]
+    fn storage_conflicts_are_reexecuted() {
+        let mut chain = TestChain::new(1, 4);
+        let counter = chain.contracts[0];
+        let (block, senders) = chain.block((0..4).map(|idx| (idx, counter)).collect());
+
+        assert_eq!(validated(&chain.provider, &block, &senders), vec![true, false, false, false]);
+    }
+
+    #[test]
+    fn observed_beneficiary_balance_is_reexecuted() {
+        let mut chain = TestChain::new(1, 2);
+        let observer = chain.contracts[1];
+        let (block, senders) =
+            chain.block(vec![(0, Address::from_low_u64_be(0x1000)), (1, observer)]);
+
+        assert_eq!(validated(&chain.provider, &block, &senders), vec![true, false]);
+    }
+}
@@ -0,0 +1,366 @@
+use crate::{
+    database::{State, SubState},
+    executor::Executor,
+};
+use parking_lot::Mutex;
+use reth_interfaces::{executor::BlockExecutionError, provider::ProviderError, Result};
+use reth_primitives::{
+    keccak256, Account, Address, Block, BlockNumber, Bytecode, Bytes, ChainSpec, ExecutionWitness,
+    Header, StorageKey, StorageValue, H256, U256,
+};
+use reth_provider::{
+    AccountProvider, BlockExecutor, BlockHashProvider, PostState, StateProvider, StateRootProvider,
+};
+use reth_trie::{SparseStateTrie, SparseTrieError};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::Arc,
+};
+
+/// The state accessed during execution.
+#[derive(Debug, Default)]
+struct AccessedState {
+    /// The accessed accounts and their accessed storage slots.
+    accounts: BTreeMap<Address, BTreeSet<H256>>,
+    /// The bytecode of the accessed contracts, keyed by the code hash.
+    codes: BTreeMap<H256, Bytes>,
+    /// The oldest block number whose hash was accessed.
+    oldest_block_hash: Option<BlockNumber>,
+}
+
+/// A [StateProvider] that records all state accessed through it.
+///
+/// Wrap the state provider of the parent block in it and execute the block with
+/// [SubState::new], afterwards [WitnessRecorder::witness] returns the [ExecutionWitness] of the
+/// block.
+pub struct WitnessRecorder<SP> {
+    /// The state provider of the parent block.
+    inner: SP,
+    /// The state accessed so far.
+    accessed: Mutex<AccessedState>,
+}
+
+impl<SP: StateProvider> WitnessRecorder<SP> {
+    /// Creates a new recorder around the given state provider.
+    pub fn new(inner: SP) -> Self {
+        Self { inner, accessed: Mutex::new(AccessedState::default()) }
+    }
+
+    /// Returns the oldest block number whose hash was accessed.
+    pub fn oldest_block_hash(&self) -> Option<BlockNumber> {
+        self.accessed.lock().oldest_block_hash
+    }
+
+    /// Returns the execution witness of all state accessed so far.
+    ///
+    /// The trie nodes also cover the accounts and storage slots changed in the given post state,
+    /// so the state root after the block can be computed from the witness.
+    ///
+    /// The headers must range from the [oldest accessed block
+    /// hash](WitnessRecorder::oldest_block_hash) up to the parent of the executed block.
+    pub fn witness(
+        &self,
+        post_state: &PostState,
+        headers: Vec<Header>,
+    ) -> Result<ExecutionWitness> {
+        let accessed = self.accessed.lock();
+
+        let mut targets = accessed.accounts.clone();
+        for address in post_state.accounts().keys() {
+            targets.entry(*address).or_default();
+        }
+        for (address, storage) in post_state.storage() {
+            targets
+                .entry(*address)
+                .or_default()
+                .extend(storage.storage.keys().map(|slot| H256(slot.to_be_bytes())));
+        }
+
+        Ok(ExecutionWitness {
+            state: self.inner.multiproof(&targets)?,
+            codes: accessed.codes.values().cloned().collect(),
+            headers,
+        })
+    }
+}
+
+impl<SP: StateProvider> AccountProvider for WitnessRecorder<SP> {
+    fn basic_account(&self, address: Address) -> Result<Option<Account>> {
+        self.accessed.lock().accounts.entry(address).or_default();
+        self.inner.basic_account(address)
+    }
+}
+
+impl<SP: StateProvider> BlockHashProvider for WitnessRecorder<SP> {
+    fn block_hash(&self, number: u64) -> Result<Option<H256>> {
+        let mut accessed = self.accessed.lock();
+        accessed.oldest_block_hash =
+            Some(accessed.oldest_block_hash.map_or(number, |oldest| oldest.min(number)));
+        drop(accessed);
+        self.inner.block_hash(number)
+    }
+
+    fn canonical_hashes_range(&self, start: BlockNumber, end: BlockNumber) -> Result<Vec<H256>> {
+        self.inner.canonical_hashes_range(start, end)
+    }
+}
+
+impl<SP: StateProvider> StateRootProvider for WitnessRecorder<SP> {
+    fn state_root(&self, post_state: PostState) -> Result<H256> {
+        self.inner.state_root(post_state)
+    }
+}
+
+impl<SP: StateProvider> StateProvider for WitnessRecorder<SP> {
+    fn storage(&self, account: Address, storage_key: StorageKey) -> Result<Option<StorageValue>> {
+        self.accessed.lock().accounts.entry(account).or_default().insert(storage_key);
+        self.inner.storage(account, storage_key)
+    }
+
+    fn bytecode_by_hash(&self, code_hash: H256) -> Result<Option<Bytecode>> {
+        let bytecode = self.inner.bytecode_by_hash(code_hash)?;
+        if let Some(ref bytecode) = bytecode {
+            self.accessed.lock().codes.insert(code_hash, bytecode.original_bytes().into());
+        }
+        Ok(bytecode)
+    }
+
+    fn proof(
+        &self,
+        address: Address,
+        keys: &[H256],
+    ) -> Result<(Vec<Bytes>, H256, Vec<Vec<Bytes>>)> {
+        self.inner.proof(address, keys)
+    }
+
+    fn multiproof(&self, targets: &BTreeMap<Address, BTreeSet<H256>>) -> Result<Vec<Bytes>> {
+        self.inner.multiproof(targets)
+    }
+}
+
+/// A [StateProvider] that serves the state from an [ExecutionWitness].
+///
+/// Any access to state that is not part of the witness fails with
+/// [ProviderError::ExecutionWitness].
+pub struct WitnessState {
+    /// The state trie of the parent block.
+    trie: Mutex<SparseStateTrie>,
+    /// The bytecode of the accessed contracts, keyed by the code hash.
+    codes: HashMap<H256, Bytecode>,
+    /// The hashes of the ancestor blocks, keyed by their number.
+    block_hashes: BTreeMap<BlockNumber, H256>,
+}
+
+impl WitnessState {
+    /// Creates the state from the witness, rooted at the state root of the given parent header.
+    pub fn new(parent: &Header, witness: &ExecutionWitness) -> Self {
+        Self {
+            trie: Mutex::new(SparseStateTrie::new(parent.state_root, witness.state.clone())),
+            codes: witness
+                .codes
+                .iter()
+                .map(|code| (keccak256(code), Bytecode::new_raw(code.clone().into())))
+                .collect(),
+            block_hashes: witness
+                .headers
+                .iter()
+                .map(|header| (header.number, header.hash_slow()))
+                .collect(),
+        }
+    }
+
+    /// Applies the changes of the post state to the state trie and returns the new state root.
+    pub fn apply(&self, post_state: &PostState) -> std::result::Result<H256, SparseTrieError> {
+        self.trie.lock().update(&post_state.hash_state_slow())
+    }
+}
+
+impl AccountProvider for WitnessState {
+    fn basic_account(&self, address: Address) -> Result<Option<Account>> {
+        self.trie.lock().account(address).map_err(witness_error)
+    }
+}
+
+impl BlockHashProvider for WitnessState {
+    fn block_hash(&self, number: u64) -> Result<Option<H256>> {
+        match self.block_hashes.get(&number) {
+            Some(hash) => Ok(Some(*hash)),
+            None => Err(witness_error(format!("missing header of block {number}"))),
+        }
+    }
+
+    fn canonical_hashes_range(&self, start: BlockNumber, end: BlockNumber) -> Result<Vec<H256>> {
+        Ok(self.block_hashes.range(start..end).map(|(_, hash)| *hash).collect())
+    }
+}
+
+impl StateRootProvider for WitnessState {
+    fn state_root(&self, post_state: PostState) -> Result<H256> {
+        let mut trie = self.trie.lock().clone();
+        trie.update(&post_state.hash_state_slow()).map_err(witness_error)
+    }
+}
+
+impl StateProvider for WitnessState {
+    fn storage(&self, account: Address, storage_key: StorageKey) -> Result<Option<StorageValue>> {
+        Ok(Some(self.trie.lock().storage(account, storage_key).map_err(witness_error)?))
+    }
+
+    fn bytecode_by_hash(&self, code_hash: H256) -> Result<Option<Bytecode>> {
+        match self.codes.get(&code_hash) {
+            Some(bytecode) => Ok(Some(bytecode.clone())),
+            None => Err(witness_error(format!("missing bytecode {code_hash:?}"))),
+        }
+    }
+
+    fn proof(
+        &self,
+        _address: Address,
+        _keys: &[H256],
+    ) -> Result<(Vec<Bytes>, H256, Vec<Vec<Bytes>>)> {
+        Err(witness_error("proofs are not available"))
+    }
+
+    fn multiproof(&self, _targets: &BTreeMap<Address, BTreeSet<H256>>) -> Result<Vec<Bytes>> {
+        Err(witness_error("proofs are not available"))
+    }
+}
+
+fn witness_error(err: impl ToString) -> reth_interfaces::Error {
+    ProviderError::ExecutionWitness(err.to_string()).into()
+}
+
+/// Errors of the stateless verification of a block.
+#[derive(Debug, thiserror::Error)]
+pub enum StatelessError {
+    /// The witness does not contain the parent header.
+    #[error("Execution witness does not contain the parent header of the block")]
+    MissingParentHeader,
+    /// The witness headers do not form a chain up to the parent of the block.
+    #[error("Execution witness header {number} does not connect to its child")]
+    InvalidHeaderChain {
+        /// The number of the disconnected header.
+        number: BlockNumber,
+    },
+    /// The block failed to execute.
+    #[error(transparent)]
+    Execution(#[from] BlockExecutionError),
+    /// The witness does not contain all trie nodes needed to compute the new state root.
+    #[error(transparent)]
+    Trie(#[from] SparseTrieError),
+    /// The computed state root does not match the state root of the block.
+    #[error("State root {got:?} is different than expected {expected:?}")]
+    StateRootMismatch {
+        /// The computed state root.
+        got: H256,
+        /// The state root of the block.
+        expected: H256,
+    },
+}
+
+/// Executes the block on the state of its parent, and returns the witness of the execution.
+///
+/// `headers` is called with the oldest accessed block number and must return the headers from that
+/// block up to the parent of the block.
+pub fn execution_witness<SP, F>(
+    chain_spec: Arc<ChainSpec>,
+    block: &Block,
+    total_difficulty: U256,
+    state: SP,
+    headers: F,
+) -> Result<ExecutionWitness>
+where
+    SP: StateProvider,
+    F: FnOnce(BlockNumber) -> Result<Vec<Header>>,
+{
+    let recorder = WitnessRecorder::new(state);
+    let mut executor = Executor::new(chain_spec, SubState::new(State::new(&recorder)));
+    let post_state = executor.execute_and_verify_receipt(block, total_difficulty, None)?;
+    drop(executor);
+
+    let parent = block.number.saturating_sub(1);
+    let oldest = recorder.oldest_block_hash().map_or(parent, |oldest| oldest.min(parent));
+    recorder.witness(&post_state, headers(oldest)?)
+}
+
+/// Re-executes the block using only the state of the witness, and checks that the resulting state
+/// root matches the state root of the block.
+///
+/// Returns the verified state root.
+pub fn verify_stateless(
+    chain_spec: Arc<ChainSpec>,
+    block: &Block,
+    total_difficulty: U256,
+    witness: &ExecutionWitness,
+) -> std::result::Result<H256, StatelessError> {
+    let Some(parent) = witness.headers.last() else {
+        return Err(StatelessError::MissingParentHeader)
+    };
+    if parent.number + 1 != block.number || parent.hash_slow() != block.parent_hash {
+        return Err(StatelessError::MissingParentHeader)
+    }
+    for pair in witness.headers.windows(2) {
+        if pair[1].parent_hash != pair[0].hash_slow() || pair[1].number != pair[0].number + 1 {
+            return Err(StatelessError::InvalidHeaderChain { number: pair[0].number })
+        }
+    }
+
+    let state = WitnessState::new(parent, witness);
+    let mut executor = Executor::new(chain_spec, SubState::new(State::new(&state)));
+    let post_state = executor.execute_and_verify_receipt(block, total_difficulty, None)?;
+    drop(executor);
+
+    let state_root = state.apply(&post_state)?;
+    if state_root != block.state_root {
+        return Err(StatelessError::StateRootMismatch {
+            got: state_root,
+            expected: block.state_root,
+        })
+    }
+    Ok(state_root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{proofs::EMPTY_ROOT, ChainSpecBuilder, MAINNET};
+
+    #[test]
+    fn rejects_witness_without_parent() {
+        let block =
+            Block { header: Header { number: 1, ..Default::default() }, ..Default::default() };
+        let witness = ExecutionWitness::default();
+        assert!(matches!(
+            verify_stateless(MAINNET.clone(), &block, U256::ZERO, &witness),
+            Err(StatelessError::MissingParentHeader)
+        ));
+    }
+
+    #[test]
+    fn verifies_empty_block() {
+        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().paris_activated().build());
+        let parent = Header { state_root: EMPTY_ROOT, ..Default::default() };
+        let block = Block {
+            header: Header {
+                number: 1,
+                parent_hash: parent.hash_slow(),
+                state_root: EMPTY_ROOT,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let witness = ExecutionWitness { headers: vec![parent], ..Default::default() };
+        let state_root =
+            verify_stateless(chain_spec.clone(), &block, U256::ZERO, &witness).unwrap();
+        assert_eq!(state_root, EMPTY_ROOT);
+
+        let block = Block {
+            header: Header { state_root: H256::random(), ..block.header },
+            ..Default::default()
+        };
+        assert!(matches!(
+            verify_stateless(chain_spec, &block, U256::ZERO, &witness),
+            Err(StatelessError::StateRootMismatch { .. })
+        ));
+    }
+}
@@ -1,5 +1,5 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use reth_primitives::{BlockId, BlockNumberOrTag, Bytes, H256};
+use reth_primitives::{BlockId, BlockNumberOrTag, BlockStateDiff, Bytes, ExecutionWitness, H256};
 use reth_rpc_types::{
     trace::geth::{
         BlockTraceResult, GethDebugTracingCallOptions, GethDebugTracingOptions, GethTraceFrame,
@@ -102,4 +102,17 @@ pub trait DebugApi {
         block_number: Option<BlockId>,
         opts: GethDebugTracingCallOptions,
     ) -> RpcResult<GethTraceFrame>;
+
+    /// Re-executes the block on top of its parent's state and returns the execution witness of
+    /// the block: all trie nodes, bytecode and ancestor headers accessed during execution.
+    ///
+    /// The witness is sufficient to re-execute the block and compute its state root without
+    /// access to the state database.
+    #[method(name = "debug_executionWitness")]
+    async fn debug_execution_witness(&self, block: BlockId) -> RpcResult<ExecutionWitness>;
+
+    /// Re-executes the block on top of its parent's state and returns the accounts, storage slots
+    /// and code it changed, with their values before and after the block.
+    #[method(name = "debug_getBlockStateDiff")]
+    async fn debug_block_state_diff(&self, block: BlockId) -> RpcResult<BlockStateDiff>;
 }
@@ -232,6 +232,17 @@ pub trait EthApi {
     #[method(name = "eth_sendRawTransaction")]
     async fn send_raw_transaction(&self, bytes: Bytes) -> RpcResult<H256>;
 
+    /// Sends signed transaction privately, returning its hash.
+    ///
+    /// The transaction is only included in blocks built by this node and never propagated to
+    /// peers. It's dropped if it's not included until the optional max block number.
+    #[method(name = "eth_sendPrivateRawTransaction")]
+    async fn send_private_raw_transaction(
+        &self,
+        bytes: Bytes,
+        max_block_number: Option<U64>,
+    ) -> RpcResult<H256>;
+
     /// Returns an Ethereum specific signature with: sign(keccak256("\x19Ethereum Signed Message:\n"
     /// + len(message) + message))).
     #[method(name = "eth_sign")]
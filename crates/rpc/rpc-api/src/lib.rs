@@ -15,6 +15,7 @@ mod engine;
 mod eth;
 mod eth_filter;
 mod eth_pubsub;
+mod mev;
 mod net;
 mod trace;
 mod txpool;
@@ -32,6 +33,7 @@ pub mod servers {
         eth::EthApiServer,
         eth_filter::EthFilterApiServer,
         eth_pubsub::EthPubSubApiServer,
+        mev::MevApiServer,
         net::NetApiServer,
         trace::TraceApiServer,
         txpool::TxPoolApiServer,
@@ -51,6 +53,7 @@ pub mod clients {
         debug::DebugApiClient,
         engine::{EngineApiClient, EngineEthApiClient},
         eth::EthApiClient,
+        mev::MevApiClient,
         net::NetApiClient,
         trace::TraceApiClient,
         txpool::TxPoolApiClient,
@@ -0,0 +1,15 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use reth_rpc_types::{SendBundleRequest, SendBundleResponse};
+
+/// Mev rpc interface.
+#[cfg_attr(not(feature = "client"), rpc(server))]
+#[cfg_attr(feature = "client", rpc(server, client))]
+#[async_trait::async_trait]
+pub trait MevApi {
+    /// Submits a bundle of transactions that the local payload builder tries to include at the
+    /// top of the targeted block.
+    ///
+    /// Returns the hash of the bundle.
+    #[method(name = "mev_sendBundle")]
+    async fn send_bundle(&self, request: SendBundleRequest) -> RpcResult<SendBundleResponse>;
+}
@@ -1,6 +1,8 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use reth_primitives::Address;
-use reth_rpc_types::txpool::{TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolStatus};
+use reth_rpc_types::txpool::{
+    TxpoolContent, TxpoolContentFrom, TxpoolContentFromWithReasons, TxpoolInspect, TxpoolStatus,
+};
 
 /// Txpool rpc interface.
 #[cfg_attr(not(feature = "client"), rpc(server))]
@@ -27,6 +29,14 @@ pub trait TxPoolApi {
     #[method(name = "txpool_contentFrom")]
     async fn txpool_content_from(&self, from: Address) -> RpcResult<TxpoolContentFrom>;
 
+    /// Same as `txpool_contentFrom`, but queued transactions also include the reasons why they're
+    /// parked: for example a nonce gap, insufficient balance or a fee cap below the base fee.
+    #[method(name = "txpool_contentFromWithReasons")]
+    async fn txpool_content_from_with_reasons(
+        &self,
+        from: Address,
+    ) -> RpcResult<TxpoolContentFromWithReasons>;
+
     /// Returns the details of all transactions currently pending for inclusion in the next
     /// block(s), as well as the ones that are being scheduled for future execution only.
     ///
@@ -25,13 +25,13 @@
 //!
 //! ```
 //! use reth_network_api::{NetworkInfo, Peers};
-//! use reth_provider::{BlockProviderIdExt, CanonStateSubscriptions, StateProviderFactory, EvmEnvProvider};
+//! use reth_provider::{BlockProviderIdExt, CanonStateSubscriptions, ChainSpecProvider, StateProviderFactory, EvmEnvProvider};
 //! use reth_rpc_builder::{RethRpcModule, RpcModuleBuilder, RpcServerConfig, ServerBuilder, TransportRpcModuleConfig};
 //! use reth_tasks::TokioTaskExecutor;
 //! use reth_transaction_pool::TransactionPool;
 //! pub async fn launch<Client, Pool, Network, Events>(client: Client, pool: Pool, network: Network, events: Events)
 //! where
-//!     Client: BlockProviderIdExt + StateProviderFactory + EvmEnvProvider + Clone + Unpin + 'static,
+//!     Client: BlockProviderIdExt + StateProviderFactory + EvmEnvProvider + ChainSpecProvider + Clone + Unpin + 'static,
 //!     Pool: TransactionPool + Clone + 'static,
 //!     Network: NetworkInfo + Peers + Clone + 'static,
 //!     Events: CanonStateSubscriptions +  Clone + 'static,
@@ -58,7 +58,7 @@
 //! ```
 //! use tokio::try_join;
 //! use reth_network_api::{NetworkInfo, Peers};
-//! use reth_provider::{BlockProviderIdExt, CanonStateSubscriptions, StateProviderFactory, EvmEnvProvider};
+//! use reth_provider::{BlockProviderIdExt, CanonStateSubscriptions, ChainSpecProvider, StateProviderFactory, EvmEnvProvider};
 //! use reth_rpc::JwtSecret;
 //! use reth_rpc_builder::{RethRpcModule, RpcModuleBuilder, RpcServerConfig, TransportRpcModuleConfig};
 //! use reth_tasks::TokioTaskExecutor;
@@ -67,7 +67,7 @@
 //! use reth_rpc_builder::auth::AuthServerConfig;
 //! pub async fn launch<Client, Pool, Network, Events, EngineApi>(client: Client, pool: Pool, network: Network, events: Events, engine_api: EngineApi)
 //! where
-//!     Client: BlockProviderIdExt + StateProviderFactory + EvmEnvProvider + Clone + Unpin + 'static,
+//!     Client: BlockProviderIdExt + StateProviderFactory + EvmEnvProvider + ChainSpecProvider + Clone + Unpin + 'static,
 //!     Pool: TransactionPool + Clone + 'static,
 //!     Network: NetworkInfo + Peers + Clone + 'static,
 //!     Events: CanonStateSubscriptions +  Clone + 'static,
@@ -106,17 +106,19 @@ use jsonrpsee::{
 };
 use reth_ipc::server::IpcServer;
 use reth_network_api::{NetworkInfo, Peers};
+use reth_payload_builder::BundleStore;
 use reth_provider::{
-    BlockProvider, BlockProviderIdExt, CanonStateSubscriptions, EvmEnvProvider,
+    BlockProvider, BlockProviderIdExt, CanonStateSubscriptions, ChainSpecProvider, EvmEnvProvider,
     StateProviderFactory,
 };
+use reth_revm::extension::EvmExtension;
 use reth_rpc::{
     eth::{
         cache::{cache_new_blocks_task, EthStateCache},
         gas_oracle::GasPriceOracle,
     },
     AdminApi, DebugApi, EngineEthApi, EthApi, EthFilter, EthPubSub, EthSubscriptionIdProvider,
-    NetApi, TraceApi, TracingCallGuard, TxPoolApi, Web3Api,
+    MevApi, NetApi, TraceApi, TracingCallGuard, TxPoolApi, Web3Api,
 };
 use reth_rpc_api::{servers::*, EngineApiServer};
 use reth_tasks::TaskSpawner;
@@ -127,6 +129,7 @@ use std::{
     fmt,
     net::{Ipv4Addr, SocketAddr, SocketAddrV4},
     str::FromStr,
+    sync::Arc,
 };
 use strum::{AsRefStr, EnumString, EnumVariantNames, ParseError, VariantNames};
 use tower::layer::util::{Identity, Stack};
@@ -164,7 +167,13 @@ pub async fn launch<Client, Pool, Network, Tasks, Events>(
     events: Events,
 ) -> Result<RpcServerHandle, RpcError>
 where
-    Client: BlockProviderIdExt + StateProviderFactory + EvmEnvProvider + Clone + Unpin + 'static,
+    Client: BlockProviderIdExt
+        + StateProviderFactory
+        + EvmEnvProvider
+        + ChainSpecProvider
+        + Clone
+        + Unpin
+        + 'static,
     Pool: TransactionPool + Clone + 'static,
     Network: NetworkInfo + Peers + Clone + 'static,
     Tasks: TaskSpawner + Clone + 'static,
@@ -193,6 +202,10 @@ pub struct RpcModuleBuilder<Client, Pool, Network, Tasks, Events> {
     executor: Tasks,
     /// Provides access to chain events, such as new blocks, required by pubsub.
     events: Events,
+    /// The extension applied to all executions of the `eth` handlers.
+    evm_extension: Option<Arc<dyn EvmExtension>>,
+    /// The store bundles submitted via the `mev` namespace are added to.
+    bundle_store: BundleStore,
 }
 
 // === impl RpcBuilder ===
@@ -206,7 +219,15 @@ impl<Client, Pool, Network, Tasks, Events> RpcModuleBuilder<Client, Pool, Networ
         executor: Tasks,
         events: Events,
     ) -> Self {
-        Self { client, pool, network, executor, events }
+        Self {
+            client,
+            pool,
+            network,
+            executor,
+            events,
+            evm_extension: None,
+            bundle_store: Default::default(),
+        }
     }
 
     /// Configure the client instance.
@@ -214,8 +235,8 @@ impl<Client, Pool, Network, Tasks, Events> RpcModuleBuilder<Client, Pool, Networ
     where
         C: BlockProvider + StateProviderFactory + EvmEnvProvider + 'static,
     {
-        let Self { pool, network, executor, events, .. } = self;
-        RpcModuleBuilder { client, network, pool, executor, events }
+        let Self { pool, network, executor, events, evm_extension, bundle_store, .. } = self;
+        RpcModuleBuilder { client, network, pool, executor, events, evm_extension, bundle_store }
     }
 
     /// Configure the transaction pool instance.
@@ -223,8 +244,8 @@ impl<Client, Pool, Network, Tasks, Events> RpcModuleBuilder<Client, Pool, Networ
     where
         P: TransactionPool + 'static,
     {
-        let Self { client, network, executor, events, .. } = self;
-        RpcModuleBuilder { client, network, pool, executor, events }
+        let Self { client, network, executor, events, evm_extension, bundle_store, .. } = self;
+        RpcModuleBuilder { client, network, pool, executor, events, evm_extension, bundle_store }
     }
 
     /// Configure the network instance.
@@ -232,8 +253,8 @@ impl<Client, Pool, Network, Tasks, Events> RpcModuleBuilder<Client, Pool, Networ
     where
         N: NetworkInfo + Peers + 'static,
     {
-        let Self { client, pool, executor, events, .. } = self;
-        RpcModuleBuilder { client, network, pool, executor, events }
+        let Self { client, pool, executor, events, evm_extension, bundle_store, .. } = self;
+        RpcModuleBuilder { client, network, pool, executor, events, evm_extension, bundle_store }
     }
 
     /// Configure the task executor to use for additional tasks.
@@ -241,8 +262,8 @@ impl<Client, Pool, Network, Tasks, Events> RpcModuleBuilder<Client, Pool, Networ
     where
         T: TaskSpawner + 'static,
     {
-        let Self { pool, network, client, events, .. } = self;
-        RpcModuleBuilder { client, network, pool, executor, events }
+        let Self { pool, network, client, events, evm_extension, bundle_store, .. } = self;
+        RpcModuleBuilder { client, network, pool, executor, events, evm_extension, bundle_store }
     }
 
     /// Configure the event subscriber instance
@@ -250,14 +271,34 @@ impl<Client, Pool, Network, Tasks, Events> RpcModuleBuilder<Client, Pool, Networ
     where
         E: CanonStateSubscriptions + 'static,
     {
-        let Self { client, pool, executor, network, .. } = self;
-        RpcModuleBuilder { client, network, pool, executor, events }
+        let Self { client, pool, executor, network, evm_extension, bundle_store, .. } = self;
+        RpcModuleBuilder { client, network, pool, executor, events, evm_extension, bundle_store }
+    }
+
+    /// Applies the given [EvmExtension] to all calls of the `eth` handlers.
+    pub fn with_evm_extension(mut self, extension: Arc<dyn EvmExtension>) -> Self {
+        self.evm_extension = Some(extension);
+        self
+    }
+
+    /// Configures the [BundleStore] that bundles submitted via the `mev` namespace are added to.
+    ///
+    /// This should be the store of the payload builder.
+    pub fn with_bundle_store(mut self, bundle_store: BundleStore) -> Self {
+        self.bundle_store = bundle_store;
+        self
     }
 }
 
 impl<Client, Pool, Network, Tasks, Events> RpcModuleBuilder<Client, Pool, Network, Tasks, Events>
 where
-    Client: BlockProviderIdExt + StateProviderFactory + EvmEnvProvider + Clone + Unpin + 'static,
+    Client: BlockProviderIdExt
+        + StateProviderFactory
+        + EvmEnvProvider
+        + ChainSpecProvider
+        + Clone
+        + Unpin
+        + 'static,
     Pool: TransactionPool + Clone + 'static,
     Network: NetworkInfo + Peers + Clone + 'static,
     Tasks: TaskSpawner + Clone + 'static,
@@ -278,7 +319,7 @@ where
     {
         let mut modules = TransportRpcModules::default();
 
-        let Self { client, pool, network, executor, events } = self;
+        let Self { client, pool, network, executor, events, evm_extension, bundle_store } = self;
 
         let TransportRpcModuleConfig { http, ws, ipc, config } = module_config.clone();
 
@@ -290,6 +331,8 @@ where
             events,
             config.unwrap_or_default(),
         );
+        registry.evm_extension = evm_extension;
+        registry.bundle_store = bundle_store;
 
         modules.config = module_config;
         modules.http = registry.maybe_module(http.as_ref());
@@ -308,7 +351,7 @@ where
     pub fn build(self, module_config: TransportRpcModuleConfig) -> TransportRpcModules<()> {
         let mut modules = TransportRpcModules::default();
 
-        let Self { client, pool, network, executor, events } = self;
+        let Self { client, pool, network, executor, events, evm_extension, bundle_store } = self;
 
         if !module_config.is_empty() {
             let TransportRpcModuleConfig { http, ws, ipc, config } = module_config.clone();
@@ -321,6 +364,8 @@ where
                 events,
                 config.unwrap_or_default(),
             );
+            registry.evm_extension = evm_extension;
+            registry.bundle_store = bundle_store;
 
             modules.config = module_config;
             modules.http = registry.maybe_module(http.as_ref());
@@ -460,8 +505,13 @@ impl RpcModuleSelection {
         config: RpcModuleConfig,
     ) -> RpcModule<()>
     where
-        Client:
-            BlockProviderIdExt + StateProviderFactory + EvmEnvProvider + Clone + Unpin + 'static,
+        Client: BlockProviderIdExt
+            + StateProviderFactory
+            + EvmEnvProvider
+            + ChainSpecProvider
+            + Clone
+            + Unpin
+            + 'static,
         Pool: TransactionPool + Clone + 'static,
         Network: NetworkInfo + Peers + Clone + 'static,
         Tasks: TaskSpawner + Clone + 'static,
@@ -548,6 +598,8 @@ pub enum RethRpcModule {
     Debug,
     /// `eth_` module
     Eth,
+    /// `mev_` module
+    Mev,
     /// `net_` module
     Net,
     /// `trace_` module
@@ -597,6 +649,10 @@ pub struct RethModuleRegistry<Client, Pool, Network, Tasks, Events> {
     tracing_call_guard: TracingCallGuard,
     /// Contains the [Methods] of a module
     modules: HashMap<RethRpcModule, Methods>,
+    /// The extension applied to all executions of the `eth` handlers.
+    evm_extension: Option<Arc<dyn EvmExtension>>,
+    /// The store bundles submitted via the `mev` namespace are added to.
+    bundle_store: BundleStore,
 }
 
 // === impl RethModuleRegistry ===
@@ -623,9 +679,27 @@ impl<Client, Pool, Network, Tasks, Events>
             tracing_call_guard: TracingCallGuard::new(config.eth.max_tracing_requests),
             config,
             events,
+            evm_extension: None,
+            bundle_store: Default::default(),
         }
     }
 
+    /// Applies the given [EvmExtension] to all calls of the `eth` handlers.
+    ///
+    /// This must be configured before any handler is created.
+    pub fn with_evm_extension(mut self, extension: Arc<dyn EvmExtension>) -> Self {
+        self.evm_extension = Some(extension);
+        self
+    }
+
+    /// Configures the [BundleStore] that bundles submitted via the `mev` namespace are added to.
+    ///
+    /// This must be configured before the `mev` handler is created.
+    pub fn with_bundle_store(mut self, bundle_store: BundleStore) -> Self {
+        self.bundle_store = bundle_store;
+        self
+    }
+
     /// Returns all installed methods
     pub fn methods(&self) -> Vec<Methods> {
         self.modules.values().cloned().collect()
@@ -662,7 +736,13 @@ where
 
 impl<Client, Pool, Network, Tasks, Events> RethModuleRegistry<Client, Pool, Network, Tasks, Events>
 where
-    Client: BlockProviderIdExt + StateProviderFactory + EvmEnvProvider + Clone + Unpin + 'static,
+    Client: BlockProviderIdExt
+        + StateProviderFactory
+        + EvmEnvProvider
+        + ChainSpecProvider
+        + Clone
+        + Unpin
+        + 'static,
     Pool: TransactionPool + Clone + 'static,
     Network: NetworkInfo + Peers + Clone + 'static,
     Tasks: TaskSpawner + Clone + 'static,
@@ -793,6 +873,11 @@ where
 
                             module.into()
                         }
+                        RethRpcModule::Mev => {
+                            MevApi::new(self.client.clone(), self.bundle_store.clone())
+                                .into_rpc()
+                                .into()
+                        }
                         RethRpcModule::Net => {
                             NetApi::new(self.network.clone(), eth_api.clone()).into_rpc().into()
                         }
@@ -849,13 +934,14 @@ where
             );
 
             let executor = Box::new(self.executor.clone());
-            let api = EthApi::with_spawner(
+            let api = EthApi::with_evm_extension(
                 self.client.clone(),
                 self.pool.clone(),
                 self.network.clone(),
                 cache.clone(),
                 gas_oracle,
                 executor.clone(),
+                self.evm_extension.clone(),
             );
             let filter = EthFilter::new(
                 self.client.clone(),
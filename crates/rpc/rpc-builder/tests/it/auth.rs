@@ -2,7 +2,7 @@
 
 use crate::utils::launch_auth;
 use jsonrpsee::core::client::{ClientT, SubscriptionClientT};
-use reth_primitives::Block;
+use reth_primitives::{Block, H256};
 use reth_rpc::JwtSecret;
 use reth_rpc_api::clients::EngineApiClient;
 use reth_rpc_types::engine::{ForkchoiceState, PayloadId, TransitionConfiguration};
@@ -14,10 +14,13 @@ where
 {
     let block = Block::default().seal_slow();
     EngineApiClient::new_payload_v1(client, block.clone().into()).await;
-    EngineApiClient::new_payload_v2(client, block.into()).await;
+    EngineApiClient::new_payload_v2(client, block.clone().into()).await;
+    EngineApiClient::new_payload_v3(client, block.into(), vec![], H256::zero()).await;
     EngineApiClient::fork_choice_updated_v1(client, ForkchoiceState::default(), None).await;
+    EngineApiClient::fork_choice_updated_v3(client, ForkchoiceState::default(), None).await;
     EngineApiClient::get_payload_v1(client, PayloadId::new([0, 0, 0, 0, 0, 0, 0, 0])).await;
     EngineApiClient::get_payload_v2(client, PayloadId::new([0, 0, 0, 0, 0, 0, 0, 0])).await;
+    EngineApiClient::get_payload_v3(client, PayloadId::new([0, 0, 0, 0, 0, 0, 0, 0])).await;
     EngineApiClient::get_payload_bodies_by_hash_v1(client, vec![]).await;
     EngineApiClient::get_payload_bodies_by_range_v1(client, 0u64.into(), 1u64.into()).await;
     EngineApiClient::exchange_transition_configuration(client, TransitionConfiguration::default())
@@ -14,10 +14,12 @@ use reth_primitives::{
 };
 use reth_rpc_api::{
     clients::{AdminApiClient, EthApiClient},
-    DebugApiClient, NetApiClient, TraceApiClient, Web3ApiClient,
+    DebugApiClient, MevApiClient, NetApiClient, TraceApiClient, Web3ApiClient,
 };
 use reth_rpc_builder::RethRpcModule;
-use reth_rpc_types::{trace::filter::TraceFilter, CallRequest, Index, TransactionRequest};
+use reth_rpc_types::{
+    trace::filter::TraceFilter, CallRequest, Index, SendBundleRequest, TransactionRequest,
+};
 use std::collections::HashSet;
 
 fn is_unimplemented(err: Error) -> bool {
@@ -178,6 +180,25 @@ where
     Web3ApiClient::sha3(client, Bytes::default()).await.unwrap();
 }
 
+async fn test_basic_mev_calls<C>(client: &C)
+where
+    C: ClientT + SubscriptionClientT + Sync,
+{
+    let tx = Bytes::from(hex!("02f871018303579880850555633d1b82520894eee27662c2b8eba3cd936a23f039f3189633e4c887ad591c62bdaeb180c080a07ea72c68abfb8fca1bd964f0f99132ed9280261bdca3e549546c0205e800f7d0a05b4ef3039e9c9b9babc179a1878fb825b5aaf5aed2fa8744854150157b08d6f3"));
+    let request = SendBundleRequest {
+        txs: vec![tx],
+        block_number: 1u64.into(),
+        min_timestamp: None,
+        max_timestamp: None,
+        reverting_tx_hashes: vec![],
+        atomic: None,
+    };
+    MevApiClient::send_bundle(client, request.clone()).await.unwrap();
+
+    let empty = SendBundleRequest { txs: vec![], ..request };
+    assert!(MevApiClient::send_bundle(client, empty).await.is_err());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_call_admin_functions_http() {
     reth_tracing::init_test_tracing();
@@ -313,6 +334,15 @@ async fn test_call_trace_functions_http_and_ws() {
     test_basic_trace_calls(&client).await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_call_mev_functions_http() {
+    reth_tracing::init_test_tracing();
+
+    let handle = launch_http(vec![RethRpcModule::Mev]).await;
+    let client = handle.http_client().unwrap();
+    test_basic_mev_calls(&client).await;
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_call_web3_functions_http() {
     reth_tracing::init_test_tracing();
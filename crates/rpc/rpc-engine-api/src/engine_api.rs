@@ -4,12 +4,13 @@ use jsonrpsee_core::RpcResult;
 use reth_beacon_consensus::BeaconConsensusEngineHandle;
 use reth_interfaces::consensus::ForkchoiceState;
 use reth_payload_builder::PayloadStore;
-use reth_primitives::{BlockHash, BlockHashOrNumber, BlockNumber, ChainSpec, Hardfork, U64};
+use reth_primitives::{BlockHash, BlockHashOrNumber, BlockNumber, ChainSpec, Hardfork, H256, U64};
 use reth_provider::{BlockProvider, EvmEnvProvider, HeaderProvider, StateProviderFactory};
 use reth_rpc_api::EngineApiServer;
 use reth_rpc_types::engine::{
-    ExecutionPayload, ExecutionPayloadBodies, ExecutionPayloadEnvelope, ForkchoiceUpdated,
-    PayloadAttributes, PayloadId, PayloadStatus, TransitionConfiguration, CAPABILITIES,
+    CancunPayloadFields, ExecutionPayload, ExecutionPayloadBodies, ExecutionPayloadEnvelope,
+    ForkchoiceUpdated, PayloadAttributes, PayloadId, PayloadStatus, TransitionConfiguration,
+    CAPABILITIES,
 };
 use std::sync::Arc;
 use tokio::sync::oneshot;
@@ -59,7 +60,8 @@ where
             payload.timestamp.as_u64(),
             payload.withdrawals.is_some(),
         )?;
-        Ok(self.beacon_consensus.new_payload(payload).await?)
+        self.validate_blob_gas_fields_presence(EngineApiMessageVersion::V1, &payload)?;
+        Ok(self.beacon_consensus.new_payload(payload, None).await?)
     }
 
     /// See also <https://github.com/ethereum/execution-apis/blob/8db51dcd2f4bdfbd9ad6e4a7560aac97010ad063/src/engine/specification.md#engine_newpayloadv1>
@@ -72,7 +74,28 @@ where
             payload.timestamp.as_u64(),
             payload.withdrawals.is_some(),
         )?;
-        Ok(self.beacon_consensus.new_payload(payload).await?)
+        self.validate_blob_gas_fields_presence(EngineApiMessageVersion::V2, &payload)?;
+        Ok(self.beacon_consensus.new_payload(payload, None).await?)
+    }
+
+    /// See also <https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md#engine_newpayloadv3>
+    ///
+    /// The versioned hashes of the payload's blob transactions are checked against the
+    /// `versioned_hashes`, a mismatch results in an `INVALID` status.
+    pub async fn new_payload_v3(
+        &self,
+        payload: ExecutionPayload,
+        versioned_hashes: Vec<H256>,
+        parent_beacon_block_root: H256,
+    ) -> EngineApiResult<PayloadStatus> {
+        self.validate_withdrawals_presence(
+            EngineApiMessageVersion::V3,
+            payload.timestamp.as_u64(),
+            payload.withdrawals.is_some(),
+        )?;
+        self.validate_blob_gas_fields_presence(EngineApiMessageVersion::V3, &payload)?;
+        let cancun_fields = CancunPayloadFields { versioned_hashes, parent_beacon_block_root };
+        Ok(self.beacon_consensus.new_payload(payload, Some(cancun_fields)).await?)
     }
 
     /// Sends a message to the beacon consensus engine to update the fork choice _without_
@@ -87,11 +110,7 @@ where
         payload_attrs: Option<PayloadAttributes>,
     ) -> EngineApiResult<ForkchoiceUpdated> {
         if let Some(ref attrs) = payload_attrs {
-            self.validate_withdrawals_presence(
-                EngineApiMessageVersion::V1,
-                attrs.timestamp.as_u64(),
-                attrs.withdrawals.is_some(),
-            )?;
+            self.validate_payload_attributes(EngineApiMessageVersion::V1, attrs)?;
         }
         Ok(self.beacon_consensus.fork_choice_updated(state, payload_attrs).await?)
     }
@@ -106,11 +125,22 @@ where
         payload_attrs: Option<PayloadAttributes>,
     ) -> EngineApiResult<ForkchoiceUpdated> {
         if let Some(ref attrs) = payload_attrs {
-            self.validate_withdrawals_presence(
-                EngineApiMessageVersion::V2,
-                attrs.timestamp.as_u64(),
-                attrs.withdrawals.is_some(),
-            )?;
+            self.validate_payload_attributes(EngineApiMessageVersion::V2, attrs)?;
+        }
+        Ok(self.beacon_consensus.fork_choice_updated(state, payload_attrs).await?)
+    }
+
+    /// Sends a message to the beacon consensus engine to update the fork choice _with_ withdrawals
+    /// and the parent beacon block root, but only _after_ cancun.
+    ///
+    /// See also <https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md#engine_forkchoiceupdatedv3>
+    pub async fn fork_choice_updated_v3(
+        &self,
+        state: ForkchoiceState,
+        payload_attrs: Option<PayloadAttributes>,
+    ) -> EngineApiResult<ForkchoiceUpdated> {
+        if let Some(ref attrs) = payload_attrs {
+            self.validate_payload_attributes(EngineApiMessageVersion::V3, attrs)?;
         }
         Ok(self.beacon_consensus.fork_choice_updated(state, payload_attrs).await?)
     }
@@ -144,12 +174,34 @@ where
         &self,
         payload_id: PayloadId,
     ) -> EngineApiResult<ExecutionPayloadEnvelope> {
-        Ok(self
+        let payload = self
             .payload_store
             .resolve(payload_id)
             .await
-            .ok_or(EngineApiError::UnknownPayload)?
-            .map(|payload| (*payload).clone().into_v2_payload())?)
+            .ok_or(EngineApiError::UnknownPayload)??;
+        self.validate_payload_fork(EngineApiMessageVersion::V2, payload.block().timestamp)?;
+        Ok((*payload).clone().into_v2_payload())
+    }
+
+    /// Returns the most recent version of the payload that is available in the corresponding
+    /// payload build process at the time of receiving this call, together with the blobs bundle
+    /// of its blob transactions.
+    ///
+    /// See also <https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md#engine_getpayloadv3>
+    ///
+    /// Note:
+    /// > Client software MAY stop the corresponding build process after serving this call.
+    pub async fn get_payload_v3(
+        &self,
+        payload_id: PayloadId,
+    ) -> EngineApiResult<ExecutionPayloadEnvelope> {
+        let payload = self
+            .payload_store
+            .resolve(payload_id)
+            .await
+            .ok_or(EngineApiError::UnknownPayload)??;
+        self.validate_payload_fork(EngineApiMessageVersion::V3, payload.block().timestamp)?;
+        Ok((*payload).clone().into_v3_payload())
     }
 
     /// Returns the execution payload bodies by the range starting at `start`, containing `count`
@@ -268,12 +320,17 @@ where
     /// Validates the presence of the `withdrawals` field according to the payload timestamp.
     /// After Shanghai, withdrawals field must be [Some].
     /// Before Shanghai, withdrawals field must be [None];
+    ///
+    /// This also ensures that the method version is supported by the fork that is active at the
+    /// payload timestamp, see [EngineApi::validate_payload_fork].
     fn validate_withdrawals_presence(
         &self,
         version: EngineApiMessageVersion,
         timestamp: u64,
         has_withdrawals: bool,
     ) -> EngineApiResult<()> {
+        self.validate_payload_fork(version, timestamp)?;
+
         let is_shanghai = self.chain_spec.fork(Hardfork::Shanghai).active_at_timestamp(timestamp);
 
         match version {
@@ -285,7 +342,7 @@ where
                     return Err(EngineApiError::NoWithdrawalsPostShanghai)
                 }
             }
-            EngineApiMessageVersion::V2 => {
+            EngineApiMessageVersion::V2 | EngineApiMessageVersion::V3 => {
                 if is_shanghai && !has_withdrawals {
                     return Err(EngineApiError::NoWithdrawalsPostShanghai)
                 }
@@ -297,6 +354,88 @@ where
 
         Ok(())
     }
+
+    /// Validates that the method version is supported by the fork that is active at the given
+    /// timestamp.
+    /// After Cancun, only V3 is supported.
+    /// Before Cancun, V3 is not supported.
+    fn validate_payload_fork(
+        &self,
+        version: EngineApiMessageVersion,
+        timestamp: u64,
+    ) -> EngineApiResult<()> {
+        let is_cancun = self.chain_spec.is_cancun_activated_at_timestamp(timestamp);
+
+        match version {
+            EngineApiMessageVersion::V1 | EngineApiMessageVersion::V2 => {
+                if is_cancun {
+                    return Err(EngineApiError::UnsupportedFork)
+                }
+            }
+            EngineApiMessageVersion::V3 => {
+                if !is_cancun {
+                    return Err(EngineApiError::UnsupportedFork)
+                }
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Validates the presence of the `blobGasUsed` and `excessBlobGas` fields of the payload.
+    /// V3 payloads must have both fields, V1 and V2 payloads must have neither.
+    fn validate_blob_gas_fields_presence(
+        &self,
+        version: EngineApiMessageVersion,
+        payload: &ExecutionPayload,
+    ) -> EngineApiResult<()> {
+        match version {
+            EngineApiMessageVersion::V1 | EngineApiMessageVersion::V2 => {
+                if payload.blob_gas_used.is_some() || payload.excess_blob_gas.is_some() {
+                    return Err(EngineApiError::BlobGasFieldsNotSupportedBeforeV3)
+                }
+            }
+            EngineApiMessageVersion::V3 => {
+                if payload.blob_gas_used.is_none() || payload.excess_blob_gas.is_none() {
+                    return Err(EngineApiError::NoBlobGasFieldsPostCancun)
+                }
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Validates the payload attributes of a fork choice update for the given method version.
+    ///
+    /// In addition to the withdrawals, see [EngineApi::validate_withdrawals_presence], the
+    /// `parentBeaconBlockRoot` must be [Some] for V3 and [None] for V1 and V2.
+    fn validate_payload_attributes(
+        &self,
+        version: EngineApiMessageVersion,
+        attrs: &PayloadAttributes,
+    ) -> EngineApiResult<()> {
+        self.validate_withdrawals_presence(
+            version,
+            attrs.timestamp.as_u64(),
+            attrs.withdrawals.is_some(),
+        )?;
+
+        let has_parent_beacon_block_root = attrs.parent_beacon_block_root.is_some();
+        match version {
+            EngineApiMessageVersion::V1 | EngineApiMessageVersion::V2 => {
+                if has_parent_beacon_block_root {
+                    return Err(EngineApiError::ParentBeaconBlockRootNotSupportedBeforeV3)
+                }
+            }
+            EngineApiMessageVersion::V3 => {
+                if !has_parent_beacon_block_root {
+                    return Err(EngineApiError::NoParentBeaconBlockRootPostCancun)
+                }
+            }
+        };
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -319,6 +458,19 @@ where
         Ok(EngineApi::new_payload_v2(self, payload).await?)
     }
 
+    /// Handler for `engine_newPayloadV3`
+    /// See also <https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md#engine_newpayloadv3>
+    async fn new_payload_v3(
+        &self,
+        payload: ExecutionPayload,
+        versioned_hashes: Vec<H256>,
+        parent_beacon_block_root: H256,
+    ) -> RpcResult<PayloadStatus> {
+        trace!(target: "rpc::eth", "Serving engine_newPayloadV3");
+        Ok(EngineApi::new_payload_v3(self, payload, versioned_hashes, parent_beacon_block_root)
+            .await?)
+    }
+
     /// Handler for `engine_forkchoiceUpdatedV1`
     /// See also <https://github.com/ethereum/execution-apis/blob/8db51dcd2f4bdfbd9ad6e4a7560aac97010ad063/src/engine/specification.md#engine_forkchoiceUpdatedV1>
     ///
@@ -343,6 +495,17 @@ where
         Ok(EngineApi::fork_choice_updated_v2(self, fork_choice_state, payload_attributes).await?)
     }
 
+    /// Handler for `engine_forkchoiceUpdatedV3`
+    /// See also <https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md#engine_forkchoiceupdatedv3>
+    async fn fork_choice_updated_v3(
+        &self,
+        fork_choice_state: ForkchoiceState,
+        payload_attributes: Option<PayloadAttributes>,
+    ) -> RpcResult<ForkchoiceUpdated> {
+        trace!(target: "rpc::eth", "Serving engine_forkchoiceUpdatedV3");
+        Ok(EngineApi::fork_choice_updated_v3(self, fork_choice_state, payload_attributes).await?)
+    }
+
     /// Handler for `engine_getPayloadV1`
     ///
     /// Returns the most recent version of the payload that is available in the corresponding
@@ -373,6 +536,20 @@ where
         Ok(EngineApi::get_payload_v2(self, payload_id).await?)
     }
 
+    /// Handler for `engine_getPayloadV3`
+    ///
+    /// Returns the most recent version of the payload that is available in the corresponding
+    /// payload build process at the time of receiving this call, together with the blobs bundle.
+    ///
+    /// See also <https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md#engine_getpayloadv3>
+    ///
+    /// Note:
+    /// > Client software MAY stop the corresponding build process after serving this call.
+    async fn get_payload_v3(&self, payload_id: PayloadId) -> RpcResult<ExecutionPayloadEnvelope> {
+        trace!(target: "rpc::eth", "Serving engine_getPayloadV3");
+        Ok(EngineApi::get_payload_v3(self, payload_id).await?)
+    }
+
     /// Handler for `engine_getPayloadBodiesByHashV1`
     /// See also <https://github.com/ethereum/execution-apis/blob/6452a6b194d7db269bf1dbd087a267251d3cc7f8/src/engine/shanghai.md#engine_getpayloadbodiesbyhashv1>
     async fn get_payload_bodies_by_hash_v1(
@@ -441,7 +618,12 @@ mod tests {
     use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
 
     fn setup_engine_api() -> (EngineApiTestHandle, EngineApi<Arc<MockEthProvider>>) {
-        let chain_spec = Arc::new(MAINNET.clone());
+        setup_engine_api_with_chain_spec(Arc::new(MAINNET.clone()))
+    }
+
+    fn setup_engine_api_with_chain_spec(
+        chain_spec: Arc<ChainSpec>,
+    ) -> (EngineApiTestHandle, EngineApi<Arc<MockEthProvider>>) {
         let client = Arc::new(MockEthProvider::default());
         let payload_store = spawn_test_payload_service();
         let (to_engine, engine_rx) = unbounded_channel();
@@ -471,6 +653,76 @@ mod tests {
         assert_matches!(handle.from_api.recv().await, Some(BeaconEngineMessage::NewPayload { .. }));
     }
 
+    // tests covering the fork based gating of the method versions
+    mod version_gating {
+        use super::*;
+        use reth_primitives::ChainSpecBuilder;
+
+        fn payload_attributes(
+            withdrawals: bool,
+            parent_beacon_block_root: bool,
+        ) -> PayloadAttributes {
+            PayloadAttributes {
+                timestamp: 1u64.into(),
+                prev_randao: H256::random(),
+                suggested_fee_recipient: Default::default(),
+                withdrawals: withdrawals.then(Vec::new),
+                parent_beacon_block_root: parent_beacon_block_root.then(H256::random),
+            }
+        }
+
+        #[tokio::test]
+        async fn v3_before_cancun() {
+            let (_, api) = setup_engine_api();
+
+            let mut payload: ExecutionPayload = SealedBlock::default().into();
+            payload.blob_gas_used = Some(0u64.into());
+            payload.excess_blob_gas = Some(0u64.into());
+            let res = api.new_payload_v3(payload.clone(), vec![], H256::zero()).await;
+            assert_matches!(res, Err(EngineApiError::UnsupportedFork));
+
+            let res = api.new_payload_v2(payload).await;
+            assert_matches!(res, Err(EngineApiError::BlobGasFieldsNotSupportedBeforeV3));
+
+            let attrs = payload_attributes(false, true);
+            let res = api.fork_choice_updated_v2(ForkchoiceState::default(), Some(attrs)).await;
+            assert_matches!(res, Err(EngineApiError::ParentBeaconBlockRootNotSupportedBeforeV3));
+        }
+
+        #[tokio::test]
+        async fn v3_after_cancun() {
+            let chain_spec = Arc::new(ChainSpecBuilder::mainnet().cancun_activated().build());
+            let (mut handle, api) = setup_engine_api_with_chain_spec(chain_spec);
+
+            let payload: ExecutionPayload = SealedBlock::default().into();
+            let res = api.new_payload_v2(payload.clone()).await;
+            assert_matches!(res, Err(EngineApiError::UnsupportedFork));
+
+            let res = api.new_payload_v3(payload, vec![], H256::zero()).await;
+            assert_matches!(res, Err(EngineApiError::NoWithdrawalsPostShanghai));
+
+            let attrs = payload_attributes(true, false);
+            let res = api.fork_choice_updated_v3(ForkchoiceState::default(), Some(attrs)).await;
+            assert_matches!(res, Err(EngineApiError::NoParentBeaconBlockRootPostCancun));
+
+            let attrs = payload_attributes(true, true);
+            let res = api.fork_choice_updated_v2(ForkchoiceState::default(), Some(attrs)).await;
+            assert_matches!(res, Err(EngineApiError::UnsupportedFork));
+
+            let mut payload: ExecutionPayload = SealedBlock::default().into();
+            payload.withdrawals = Some(vec![]);
+            payload.blob_gas_used = Some(0u64.into());
+            payload.excess_blob_gas = Some(0u64.into());
+            tokio::spawn(async move {
+                api.new_payload_v3(payload, vec![], H256::zero()).await.unwrap();
+            });
+            assert_matches!(
+                handle.from_api.recv().await,
+                Some(BeaconEngineMessage::NewPayload { cancun_fields: Some(_), .. })
+            );
+        }
+    }
+
     // tests covering `engine_getPayloadBodiesByRange` and `engine_getPayloadBodiesByHash`
     mod get_payload_bodies {
         use super::*;
@@ -11,6 +11,8 @@ pub type EngineApiResult<Ok> = Result<Ok, EngineApiError>;
 pub const UNKNOWN_PAYLOAD_CODE: i32 = -38001;
 /// Request too large error code.
 pub const REQUEST_TOO_LARGE_CODE: i32 = -38004;
+/// Unsupported fork error code.
+pub const UNSUPPORTED_FORK_CODE: i32 = -38005;
 
 /// Error returned by [`EngineApi`][crate::EngineApi]
 ///
@@ -43,6 +45,22 @@ pub enum EngineApiError {
     /// Thrown if engine_forkchoiceUpdated contains withdrawals before Shanghai
     #[error("withdrawals pre-shanghai")]
     HasWithdrawalsPreShanghai,
+    /// Thrown if the method version doesn't match the fork that is active at the payload's
+    /// timestamp, e.g. a V3 method before Cancun or a V2 method after Cancun
+    #[error("unsupported fork")]
+    UnsupportedFork,
+    /// Thrown if engine_forkchoiceUpdatedV3 payload attributes contain no parent beacon block root
+    #[error("no parent beacon block root post-cancun")]
+    NoParentBeaconBlockRootPostCancun,
+    /// Thrown if the payload attributes of a V1 or V2 method contain a parent beacon block root
+    #[error("parent beacon block root not supported before V3")]
+    ParentBeaconBlockRootNotSupportedBeforeV3,
+    /// Thrown if engine_newPayloadV3 contains no blob gas fields
+    #[error("no blob gas fields post-cancun")]
+    NoBlobGasFieldsPostCancun,
+    /// Thrown if the payload of a V1 or V2 method contains blob gas fields
+    #[error("blob gas fields not supported before V3")]
+    BlobGasFieldsNotSupportedBeforeV3,
     /// Terminal total difficulty mismatch during transition configuration exchange.
     #[error(
         "Invalid transition terminal total difficulty. Execution: {execution}. Consensus: {consensus}"
@@ -83,7 +101,12 @@ impl From<EngineApiError> for jsonrpsee_types::error::ErrorObject<'static> {
             EngineApiError::InvalidBodiesRange { .. } |
             EngineApiError::WithdrawalsNotSupportedInV1 |
             EngineApiError::NoWithdrawalsPostShanghai |
-            EngineApiError::HasWithdrawalsPreShanghai => INVALID_PARAMS_CODE,
+            EngineApiError::HasWithdrawalsPreShanghai |
+            EngineApiError::NoParentBeaconBlockRootPostCancun |
+            EngineApiError::ParentBeaconBlockRootNotSupportedBeforeV3 |
+            EngineApiError::NoBlobGasFieldsPostCancun |
+            EngineApiError::BlobGasFieldsNotSupportedBeforeV3 => INVALID_PARAMS_CODE,
+            EngineApiError::UnsupportedFork => UNSUPPORTED_FORK_CODE,
             EngineApiError::UnknownPayload => UNKNOWN_PAYLOAD_CODE,
             EngineApiError::PayloadRequestTooLarge { .. } => REQUEST_TOO_LARGE_CODE,
 
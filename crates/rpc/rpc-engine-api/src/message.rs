@@ -5,4 +5,6 @@ pub enum EngineApiMessageVersion {
     V1,
     /// Version 2
     V2,
+    /// Version 3
+    V3,
 }
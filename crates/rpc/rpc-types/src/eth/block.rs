@@ -238,6 +238,15 @@ pub struct Header {
     /// Withdrawals root hash added by EIP-4895 and is ignored in legacy headers.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub withdrawals_root: Option<H256>,
+    /// Blob gas used, added by EIP-4844 and is ignored in legacy headers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blob_gas_used: Option<U64>,
+    /// Excess blob gas, added by EIP-4844 and is ignored in legacy headers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub excess_blob_gas: Option<U64>,
+    /// Parent beacon block root, added by EIP-4788 and is ignored in legacy headers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_beacon_block_root: Option<H256>,
 }
 
 // === impl Header ===
@@ -267,6 +276,9 @@ impl Header {
                     base_fee_per_gas,
                     extra_data,
                     withdrawals_root,
+                    blob_gas_used,
+                    excess_blob_gas,
+                    parent_beacon_block_root,
                 },
             hash,
         } = primitive_header;
@@ -290,6 +302,9 @@ impl Header {
             mix_hash,
             nonce: Some(nonce.to_be_bytes().into()),
             base_fee_per_gas: base_fee_per_gas.map(U256::from),
+            blob_gas_used: blob_gas_used.map(U64::from),
+            excess_blob_gas: excess_blob_gas.map(U64::from),
+            parent_beacon_block_root,
         }
     }
 }
@@ -418,6 +433,9 @@ mod tests {
                 mix_hash: H256::from_low_u64_be(14),
                 nonce: Some(H64::from_low_u64_be(15)),
                 base_fee_per_gas: Some(U256::from(20)),
+                blob_gas_used: None,
+                excess_blob_gas: None,
+                parent_beacon_block_root: None,
             },
             total_difficulty: Some(U256::from(100000)),
             uncles: vec![H256::from_low_u64_be(17)],
@@ -456,6 +474,9 @@ mod tests {
                 mix_hash: H256::from_low_u64_be(14),
                 nonce: Some(H64::from_low_u64_be(15)),
                 base_fee_per_gas: Some(U256::from(20)),
+                blob_gas_used: None,
+                excess_blob_gas: None,
+                parent_beacon_block_root: None,
             },
             total_difficulty: Some(U256::from(100000)),
             uncles: vec![H256::from_low_u64_be(17)],
@@ -1,8 +1,8 @@
 use reth_primitives::{
     constants::{MAXIMUM_EXTRA_DATA_SIZE, MIN_PROTOCOL_BASE_FEE_U256},
     proofs::{self, EMPTY_LIST_HASH},
-    Address, Block, Bloom, Bytes, Header, SealedBlock, TransactionSigned, UintTryTo, Withdrawal,
-    H256, H64, U256, U64,
+    Address, BlobTransactionSidecar, Block, Bloom, Bytes, Header, SealedBlock, TransactionSigned,
+    UintTryTo, Withdrawal, H256, H64, U256, U64,
 };
 use reth_rlp::{Decodable, Encodable};
 use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
@@ -29,9 +29,11 @@ impl std::fmt::Display for PayloadId {
     }
 }
 
-/// This structure maps for the return value of `engine_getPayloadV2` of the beacon chain spec.
+/// This structure maps for the return value of `engine_getPayloadV2` and `engine_getPayloadV3` of
+/// the beacon chain spec.
 ///
 /// See also: <https://github.com/ethereum/execution-apis/blob/main/src/engine/shanghai.md#engine_getpayloadv2>
+/// and <https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md#engine_getpayloadv3>
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ExecutionPayloadEnvelope {
     /// Execution payload, which could be either V1 or V2
@@ -46,15 +48,62 @@ pub struct ExecutionPayloadEnvelope {
     /// The expected value to be received by the feeRecipient in wei
     #[serde(rename = "blockValue")]
     pub block_value: U256,
+    /// The blobs, commitments, and proofs associated with the executed payload, enabled with V3
+    #[serde(rename = "blobsBundle", default, skip_serializing_if = "Option::is_none")]
+    pub blobs_bundle: Option<BlobsBundleV1>,
+    /// Suggestion to the consensus layer to use this payload over a builder's payload, enabled
+    /// with V3
+    #[serde(rename = "shouldOverrideBuilder", default, skip_serializing_if = "Option::is_none")]
+    pub should_override_builder: Option<bool>,
 }
 
 impl ExecutionPayloadEnvelope {
     /// Returns the [ExecutionPayload] for the `engine_getPayloadV1` endpoint
     pub fn into_v1_payload(mut self) -> ExecutionPayload {
-        // ensure withdrawals are removed
+        // ensure withdrawals and blob fields are removed
         self.payload.withdrawals.take();
+        self.payload.blob_gas_used.take();
+        self.payload.excess_blob_gas.take();
         self.payload
     }
+
+    /// Returns the envelope for the `engine_getPayloadV2` endpoint
+    pub fn into_v2_envelope(mut self) -> Self {
+        // ensure the V3 fields are removed
+        self.payload.blob_gas_used.take();
+        self.payload.excess_blob_gas.take();
+        self.blobs_bundle.take();
+        self.should_override_builder.take();
+        self
+    }
+}
+
+/// This structure contains the blobs, commitments, and proofs of all blob transactions in an
+/// execution payload.
+///
+/// See also: <https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md#blobsbundlev1>
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobsBundleV1 {
+    /// The KZG commitments of the blobs
+    pub commitments: Vec<Bytes>,
+    /// The KZG proofs of the blobs
+    pub proofs: Vec<Bytes>,
+    /// The blobs
+    pub blobs: Vec<Bytes>,
+}
+
+impl BlobsBundleV1 {
+    /// Creates the bundle from the sidecars of the payload's blob transactions, in transaction
+    /// order.
+    pub fn new(sidecars: impl IntoIterator<Item = BlobTransactionSidecar>) -> Self {
+        let mut bundle = Self::default();
+        for BlobTransactionSidecar { blobs, commitments, proofs } in sidecars {
+            bundle.commitments.extend(commitments);
+            bundle.proofs.extend(proofs);
+            bundle.blobs.extend(blobs);
+        }
+        bundle
+    }
 }
 
 /// This structure maps on the ExecutionPayload structure of the beacon chain spec.
@@ -81,6 +130,87 @@ pub struct ExecutionPayload {
     /// See <https://github.com/ethereum/execution-apis/blob/6709c2a795b707202e93c4f2867fa0bf2640a84f/src/engine/shanghai.md#executionpayloadv2>
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub withdrawals: Option<Vec<Withdrawal>>,
+    /// The total amount of blob gas consumed by the payload's transactions, enabled with V3
+    /// See <https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md#executionpayloadv3>
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_gas_used: Option<U64>,
+    /// The running total of blob gas consumed in excess of the target, enabled with V3
+    /// See <https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md#executionpayloadv3>
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub excess_blob_gas: Option<U64>,
+}
+
+impl ExecutionPayload {
+    /// Try to construct a block from the payload, see also the [`TryFrom`] impl for
+    /// [`SealedBlock`].
+    ///
+    /// The `parent_beacon_block_root` isn't part of the payload itself, it's provided separately
+    /// by `engine_newPayloadV3`.
+    pub fn try_into_sealed_block(
+        self,
+        parent_beacon_block_root: Option<H256>,
+    ) -> Result<SealedBlock, PayloadError> {
+        if self.extra_data.len() > MAXIMUM_EXTRA_DATA_SIZE {
+            return Err(PayloadError::ExtraData(self.extra_data))
+        }
+
+        if self.base_fee_per_gas < MIN_PROTOCOL_BASE_FEE_U256 {
+            return Err(PayloadError::BaseFee(self.base_fee_per_gas))
+        }
+
+        let transactions = self
+            .transactions
+            .iter()
+            .map(|tx| TransactionSigned::decode(&mut tx.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let transactions_root = proofs::calculate_transaction_root(&transactions);
+
+        let withdrawals_root =
+            self.withdrawals.as_ref().map(|w| proofs::calculate_withdrawals_root(w));
+
+        let header = Header {
+            parent_hash: self.parent_hash,
+            beneficiary: self.fee_recipient,
+            state_root: self.state_root,
+            transactions_root,
+            receipts_root: self.receipts_root,
+            withdrawals_root,
+            logs_bloom: self.logs_bloom,
+            number: self.block_number.as_u64(),
+            gas_limit: self.gas_limit.as_u64(),
+            gas_used: self.gas_used.as_u64(),
+            timestamp: self.timestamp.as_u64(),
+            mix_hash: self.prev_randao,
+            base_fee_per_gas: Some(
+                self.base_fee_per_gas
+                    .uint_try_to()
+                    .map_err(|_| PayloadError::BaseFee(self.base_fee_per_gas))?,
+            ),
+            blob_gas_used: self.blob_gas_used.map(|gas| gas.as_u64()),
+            excess_blob_gas: self.excess_blob_gas.map(|gas| gas.as_u64()),
+            parent_beacon_block_root,
+            extra_data: self.extra_data,
+            // Defaults
+            ommers_hash: EMPTY_LIST_HASH,
+            difficulty: Default::default(),
+            nonce: Default::default(),
+        }
+        .seal_slow();
+
+        if self.block_hash != header.hash() {
+            return Err(PayloadError::BlockHash {
+                execution: header.hash(),
+                consensus: self.block_hash,
+            })
+        }
+
+        Ok(SealedBlock {
+            header,
+            body: transactions,
+            withdrawals: self.withdrawals,
+            ommers: Default::default(),
+        })
+    }
 }
 
 impl From<SealedBlock> for ExecutionPayload {
@@ -110,6 +240,8 @@ impl From<SealedBlock> for ExecutionPayload {
             block_hash: value.hash(),
             transactions,
             withdrawals: value.withdrawals,
+            blob_gas_used: value.blob_gas_used.map(U64::from),
+            excess_blob_gas: value.excess_blob_gas.map(U64::from),
         }
     }
 }
@@ -126,64 +258,7 @@ impl TryFrom<ExecutionPayload> for SealedBlock {
     type Error = PayloadError;
 
     fn try_from(payload: ExecutionPayload) -> Result<Self, Self::Error> {
-        if payload.extra_data.len() > MAXIMUM_EXTRA_DATA_SIZE {
-            return Err(PayloadError::ExtraData(payload.extra_data))
-        }
-
-        if payload.base_fee_per_gas < MIN_PROTOCOL_BASE_FEE_U256 {
-            return Err(PayloadError::BaseFee(payload.base_fee_per_gas))
-        }
-
-        let transactions = payload
-            .transactions
-            .iter()
-            .map(|tx| TransactionSigned::decode(&mut tx.as_ref()))
-            .collect::<Result<Vec<_>, _>>()?;
-        let transactions_root = proofs::calculate_transaction_root(&transactions);
-
-        let withdrawals_root =
-            payload.withdrawals.as_ref().map(|w| proofs::calculate_withdrawals_root(w));
-
-        let header = Header {
-            parent_hash: payload.parent_hash,
-            beneficiary: payload.fee_recipient,
-            state_root: payload.state_root,
-            transactions_root,
-            receipts_root: payload.receipts_root,
-            withdrawals_root,
-            logs_bloom: payload.logs_bloom,
-            number: payload.block_number.as_u64(),
-            gas_limit: payload.gas_limit.as_u64(),
-            gas_used: payload.gas_used.as_u64(),
-            timestamp: payload.timestamp.as_u64(),
-            mix_hash: payload.prev_randao,
-            base_fee_per_gas: Some(
-                payload
-                    .base_fee_per_gas
-                    .uint_try_to()
-                    .map_err(|_| PayloadError::BaseFee(payload.base_fee_per_gas))?,
-            ),
-            extra_data: payload.extra_data,
-            // Defaults
-            ommers_hash: EMPTY_LIST_HASH,
-            difficulty: Default::default(),
-            nonce: Default::default(),
-        }
-        .seal_slow();
-
-        if payload.block_hash != header.hash() {
-            return Err(PayloadError::BlockHash {
-                execution: header.hash(),
-                consensus: payload.block_hash,
-            })
-        }
-
-        Ok(SealedBlock {
-            header,
-            body: transactions,
-            withdrawals: payload.withdrawals,
-            ommers: Default::default(),
-        })
+        payload.try_into_sealed_block(None)
     }
 }
 
@@ -204,16 +279,40 @@ pub enum PayloadError {
         /// The block hash provided with the payload.
         consensus: H256,
     },
+    /// The versioned hashes of the payload's blob transactions don't match the expected ones.
+    #[error("versioned hashes mismatch, want {expected:?}, got {got:?}")]
+    InvalidVersionedHashes {
+        /// The versioned hashes provided with the payload.
+        expected: Vec<H256>,
+        /// The versioned hashes of the payload's transactions.
+        got: Vec<H256>,
+    },
     /// Encountered decoding error.
     #[error(transparent)]
     Decode(#[from] reth_rlp::DecodeError),
 }
 
 impl PayloadError {
-    /// Returns `true` if the error is caused by invalid extra data.
+    /// Returns `true` if the error is caused by a block hash mismatch.
     pub fn is_block_hash_mismatch(&self) -> bool {
         matches!(self, PayloadError::BlockHash { .. })
     }
+
+    /// Returns `true` if the error is caused by a versioned hashes mismatch.
+    pub fn is_versioned_hashes_mismatch(&self) -> bool {
+        matches!(self, PayloadError::InvalidVersionedHashes { .. })
+    }
+}
+
+/// The fields of `engine_newPayloadV3` that aren't part of the [ExecutionPayload] itself.
+///
+/// See also: <https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md#engine_newpayloadv3>
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CancunPayloadFields {
+    /// The expected versioned hashes of the payload's blob transactions, in order.
+    pub versioned_hashes: Vec<H256>,
+    /// The root of the parent beacon block.
+    pub parent_beacon_block_root: H256,
 }
 
 /// This structure contains a body of an execution payload.
@@ -251,6 +350,10 @@ pub struct PayloadAttributes {
     /// See <https://github.com/ethereum/execution-apis/blob/6452a6b194d7db269bf1dbd087a267251d3cc7f8/src/engine/shanghai.md#payloadattributesv2>
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub withdrawals: Option<Vec<Withdrawal>>,
+    /// Root of the parent beacon block enabled with V3
+    /// See <https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md#payloadattributesv3>
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_beacon_block_root: Option<H256>,
 }
 
 /// This structure contains the result of processing a payload or fork choice update.
@@ -602,4 +705,40 @@ mod tests {
         let payload: ExecutionPayload = serde_json::from_str(s).unwrap();
         assert_eq!(serde_json::to_string(&payload).unwrap(), s);
     }
+
+    #[test]
+    fn serde_roundtrip_v3_payload() {
+        let s = r#"{"parentHash":"0x67ead97eb79b47a1638659942384143f36ed44275d4182799875ab5a87324055","feeRecipient":"0x0000000000000000000000000000000000000000","stateRoot":"0x76a03cbcb7adce07fd284c61e4fa31e5e786175cefac54a29e46ec8efa28ea41","receiptsRoot":"0x4e3c608a9f2e129fccb91a1dae7472e78013b8e654bccc8d224ce3d63ae17006","logsBloom":"0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000","prevRandao":"0x028111cb7d25918386a69656b3d17b2febe95fd0f11572c1a55c14f99fdfe3df","blockNumber":"0x1","gasLimit":"0x2fefd8","gasUsed":"0xa860","timestamp":"0x1235","extraData":"0x8b726574682f76302e312e30","baseFeePerGas":"0x342770c0","blockHash":"0xa6f40ed042e61e88e76125dede8fff8026751ea14454b68fb534cea99f2b2a77","transactions":[],"withdrawals":[],"blobGasUsed":"0x20000","excessBlobGas":"0x0"}"#;
+        let payload: ExecutionPayload = serde_json::from_str(s).unwrap();
+        assert_eq!(payload.blob_gas_used, Some(U64::from(0x20000)));
+        assert_eq!(payload.excess_blob_gas, Some(U64::zero()));
+        assert_eq!(serde_json::to_string(&payload).unwrap(), s);
+
+        let envelope = ExecutionPayloadEnvelope {
+            payload,
+            block_value: U256::ZERO,
+            blobs_bundle: Some(BlobsBundleV1::default()),
+            should_override_builder: Some(false),
+        };
+        let json = serde_json::to_value(&envelope).unwrap();
+        assert!(json.get("blobsBundle").is_some());
+        assert!(json.get("shouldOverrideBuilder").is_some());
+
+        let v2 = serde_json::to_value(envelope.into_v2_envelope()).unwrap();
+        assert!(v2.get("blobsBundle").is_none());
+        assert!(v2["executionPayload"].get("blobGasUsed").is_none());
+    }
+
+    #[test]
+    fn blobs_bundle_from_sidecars() {
+        let sidecar = |byte: u8| BlobTransactionSidecar {
+            blobs: vec![Bytes::from(vec![byte]).into()],
+            commitments: vec![Bytes::from(vec![byte + 1]).into()],
+            proofs: vec![Bytes::from(vec![byte + 2]).into()],
+        };
+        let bundle = BlobsBundleV1::new([sidecar(1), sidecar(10)]);
+        assert_eq!(bundle.blobs.len(), 2);
+        assert_eq!(bundle.commitments[1], sidecar(10).commitments[0]);
+        assert_eq!(bundle.proofs[0], sidecar(1).proofs[0]);
+    }
 }
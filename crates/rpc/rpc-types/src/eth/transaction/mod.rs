@@ -62,6 +62,16 @@ pub struct Transaction {
     /// Pre-pay to warm storage access.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub access_list: Option<Vec<AccessListItem>>,
+    /// EIP4844
+    ///
+    /// Max fee per blob gas the sender is willing to pay.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_fee_per_blob_gas: Option<U128>,
+    /// EIP4844
+    ///
+    /// Versioned hashes of the blobs carried by the transaction.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blob_versioned_hashes: Vec<H256>,
     /// EIP2718
     ///
     /// Transaction type, Some(2) for EIP-1559 transaction,
@@ -112,7 +122,7 @@ impl Transaction {
         let (gas_price, max_fee_per_gas) = match signed_tx.tx_type() {
             TxType::Legacy => (Some(U128::from(signed_tx.max_fee_per_gas())), None),
             TxType::EIP2930 => (None, Some(U128::from(signed_tx.max_fee_per_gas()))),
-            TxType::EIP1559 => {
+            TxType::EIP1559 | TxType::EIP4844 => {
                 // the gas price field for EIP1559 is set to `min(tip, gasFeeCap - baseFee) +
                 // baseFee`
                 let gas_price = base_fee
@@ -148,6 +158,16 @@ impl Transaction {
                     })
                     .collect(),
             ),
+            PrimitiveTransaction::Eip4844(tx) => Some(
+                tx.access_list
+                    .0
+                    .iter()
+                    .map(|item| AccessListItem {
+                        address: item.address.0.into(),
+                        storage_keys: item.storage_keys.iter().map(|key| key.0.into()).collect(),
+                    })
+                    .collect(),
+            ),
         };
 
         let signature = Signature::from_primitive_signature(
@@ -170,6 +190,11 @@ impl Transaction {
             input: signed_tx.input().clone(),
             chain_id,
             access_list,
+            max_fee_per_blob_gas: signed_tx.max_fee_per_blob_gas().map(U128::from),
+            blob_versioned_hashes: signed_tx
+                .blob_versioned_hashes()
+                .map(|hashes| hashes.to_vec())
+                .unwrap_or_default(),
             transaction_type: Some(U64::from(signed_tx.tx_type() as u8)),
 
             // These fields are set to None because they are not stored as part of the transaction
@@ -201,6 +226,8 @@ mod tests {
             signature: Some(Signature { v: U256::from(14), r: U256::from(14), s: U256::from(14) }),
             chain_id: Some(U64::from(17)),
             access_list: None,
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: vec![],
             transaction_type: Some(U64::from(20)),
             max_fee_per_gas: Some(U128::from(21)),
             max_priority_fee_per_gas: Some(U128::from(22)),
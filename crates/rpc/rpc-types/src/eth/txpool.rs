@@ -144,6 +144,46 @@ pub struct TxpoolContentFrom {
     pub queued: BTreeMap<String, Transaction>,
 }
 
+/// Transaction Pool Content From, including the reasons why queued transactions are parked.
+///
+/// Same as [TxpoolContentFrom], but each queued transaction reports why it can't be included in
+/// the next block.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxpoolContentFromWithReasons {
+    /// pending tx
+    pub pending: BTreeMap<String, Transaction>,
+    /// queued tx and the reasons why they're parked
+    pub queued: BTreeMap<String, TxpoolParkedTransaction>,
+}
+
+/// A queued transaction and the reasons why it is parked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxpoolParkedTransaction {
+    /// The parked transaction
+    pub transaction: Transaction,
+    /// All reasons why the transaction is parked
+    pub parked_reasons: Vec<TxpoolParkedReason>,
+}
+
+/// The reason why a transaction is parked in the pool and can't be included in the next block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TxpoolParkedReason {
+    /// Transactions with lower nonces of the same sender are missing.
+    NonceGap,
+    /// A transaction with a lower nonce of the same sender is parked.
+    ParkedAncestor,
+    /// The sender's balance doesn't cover the cost of this and all prior transactions.
+    InsufficientBalance,
+    /// The gas limit exceeds the block gas limit.
+    ExceedsBlockGasLimit,
+    /// The fee cap is below the base fee of the pending block.
+    FeeCapBelowBaseFee,
+    /// The blob fee cap is below the blob fee of the pending block.
+    BlobFeeCapBelowBlobFee,
+}
+
 /// Transaction Pool Inspect
 ///
 /// The inspect inspection property can be queried to list a textual summary
@@ -405,6 +445,15 @@ mod tests {
         assert_eq!(txpool_status_json.trim(), serialized);
     }
 
+    #[test]
+    fn serde_txpool_parked_reasons() {
+        let reasons = vec![TxpoolParkedReason::NonceGap, TxpoolParkedReason::FeeCapBelowBaseFee];
+        let serialized = serde_json::to_string(&reasons).unwrap();
+        assert_eq!(serialized, r#"["nonceGap","feeCapBelowBaseFee"]"#);
+        let deserialized: Vec<TxpoolParkedReason> = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, reasons);
+    }
+
     fn expected_txpool_inspect() -> TxpoolInspect {
         let mut pending_map = BTreeMap::new();
         let mut pending_map_inner = BTreeMap::new();
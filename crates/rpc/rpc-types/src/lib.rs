@@ -11,6 +11,8 @@
 
 mod admin;
 mod eth;
+mod mev;
 
 pub use admin::*;
 pub use eth::*;
+pub use mev::*;
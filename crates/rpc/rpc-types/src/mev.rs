@@ -0,0 +1,54 @@
+use reth_primitives::{Bytes, H256, U64};
+use serde::{Deserialize, Serialize};
+
+/// A bundle of transactions submitted via `mev_sendBundle` that should be included at the top of
+/// the block with the given number.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendBundleRequest {
+    /// The raw signed transactions of the bundle, in execution order.
+    pub txs: Vec<Bytes>,
+    /// The number of the block the bundle targets.
+    pub block_number: U64,
+    /// The minimum timestamp of the block the bundle can be included in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_timestamp: Option<u64>,
+    /// The maximum timestamp of the block the bundle can be included in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_timestamp: Option<u64>,
+    /// Hashes of the transactions that are allowed to revert.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reverting_tx_hashes: Vec<H256>,
+    /// Whether the bundle must be included as a whole or not at all.
+    ///
+    /// If disabled, transactions that fail or revert without being allowed to are dropped from
+    /// the bundle instead. Defaults to `true`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub atomic: Option<bool>,
+}
+
+/// Response to a `mev_sendBundle` request.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendBundleResponse {
+    /// The hash of the accepted bundle.
+    pub bundle_hash: H256,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_send_bundle_request() {
+        let s = r#"{"txs":["0x01","0x02"],"blockNumber":"0x10","maxTimestamp":1700000000,"revertingTxHashes":["0x0000000000000000000000000000000000000000000000000000000000000001"]}"#;
+        let request: SendBundleRequest = serde_json::from_str(s).unwrap();
+        assert_eq!(request.txs.len(), 2);
+        assert_eq!(request.block_number, U64::from(16));
+        assert_eq!(request.min_timestamp, None);
+        assert_eq!(request.max_timestamp, Some(1700000000));
+        assert_eq!(request.reverting_tx_hashes, vec![H256::from_low_u64_be(1)]);
+        assert_eq!(request.atomic, None);
+        assert_eq!(serde_json::to_string(&request).unwrap(), s);
+    }
+}
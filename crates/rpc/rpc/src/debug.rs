@@ -9,12 +9,21 @@ use crate::{
 };
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
-use reth_primitives::{Block, BlockId, BlockNumberOrTag, Bytes, TransactionSigned, H256, U256};
-use reth_provider::{BlockProviderIdExt, HeaderProvider, ReceiptProviderIdExt, StateProviderBox};
+use reth_primitives::{
+    Block, BlockId, BlockNumberOrTag, BlockStateDiff, Bytes, ExecutionWitness, SealedBlock,
+    TransactionSigned, H256, U256,
+};
+use reth_provider::{
+    BlockExecutor, BlockProviderIdExt, ChainSpecProvider, HeaderProvider, ReceiptProviderIdExt,
+    StateProviderBox,
+};
 use reth_revm::{
     database::{State, SubState},
     env::tx_env_with_recovered,
+    executor::Executor,
+    extension::EvmExtension,
     tracing::{FourByteInspector, TracingInspector, TracingInspectorConfig},
+    witness::execution_witness,
 };
 use reth_rlp::{Decodable, Encodable};
 use reth_rpc_api::DebugApiServer;
@@ -59,7 +68,7 @@ impl<Client, Eth> DebugApi<Client, Eth> {
 
 impl<Client, Eth> DebugApi<Client, Eth>
 where
-    Client: BlockProviderIdExt + HeaderProvider + 'static,
+    Client: BlockProviderIdExt + HeaderProvider + ChainSpecProvider + 'static,
     Eth: EthTransactions + 'static,
 {
     /// Executes the future on a new blocking task.
@@ -93,6 +102,7 @@ where
         block_env: BlockEnv,
         opts: GethDebugTracingOptions,
     ) -> EthResult<Vec<TraceResult>> {
+        let extension = self.inner.eth_api.evm_extension().cloned();
         // replay all transactions of the block
         self.inner.eth_api.with_state_at_block(at, move |state| {
             let mut results = Vec::with_capacity(transactions.len());
@@ -103,7 +113,8 @@ where
                 let tx = tx.into_ecrecovered().ok_or(BlockError::InvalidSignature)?;
                 let tx = tx_env_with_recovered(&tx);
                 let env = Env { cfg: cfg.clone(), block: block_env.clone(), tx };
-                let (result, state_changes) = trace_transaction(opts.clone(), env, &mut db)?;
+                let (result, state_changes) =
+                    trace_transaction(opts.clone(), env, &mut db, extension.as_ref())?;
                 results.push(TraceResult::Success { result });
 
                 if transactions.peek().is_some() {
@@ -188,6 +199,69 @@ where
         self.trace_block_with_sync(state_at.into(), block.body, cfg, block_env, opts)
     }
 
+    /// Re-executes the block on top of its parent's state and returns the execution witness of
+    /// the block.
+    pub async fn debug_execution_witness(&self, block_id: BlockId) -> EthResult<ExecutionWitness> {
+        let block = self
+            .inner
+            .eth_api
+            .block_by_id(block_id)
+            .await?
+            .ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+
+        self.on_blocking_task(|this| async move { this.try_execution_witness(block) }).await
+    }
+
+    fn try_execution_witness(&self, block: SealedBlock) -> EthResult<ExecutionWitness> {
+        let client = &self.inner.client;
+        let total_difficulty =
+            client.header_td(&block.hash)?.ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+        let block = block.unseal();
+
+        self.inner.eth_api.with_state_at_block(block.parent_hash.into(), |state| {
+            let witness = execution_witness(
+                client.chain_spec(),
+                &block,
+                total_difficulty,
+                state,
+                |oldest| client.headers_range(oldest..block.number),
+            )?;
+            Ok(witness)
+        })
+    }
+
+    /// Re-executes the block on top of its parent's state and returns the state changes of the
+    /// block.
+    pub async fn debug_block_state_diff(&self, block_id: BlockId) -> EthResult<BlockStateDiff> {
+        let block = self
+            .inner
+            .eth_api
+            .block_by_id(block_id)
+            .await?
+            .ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+
+        self.on_blocking_task(|this| async move { this.try_block_state_diff(block) }).await
+    }
+
+    fn try_block_state_diff(&self, block: SealedBlock) -> EthResult<BlockStateDiff> {
+        let client = &self.inner.client;
+        let total_difficulty =
+            client.header_td(&block.hash)?.ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+        let num_hash = block.header.num_hash();
+        let block = block.unseal();
+
+        self.inner.eth_api.with_state_at_block(block.parent_hash.into(), |state| {
+            let mut executor = Executor::new(client.chain_spec(), SubState::new(State::new(state)));
+            if let Some(extension) = self.inner.eth_api.evm_extension() {
+                executor = executor.with_evm_extension(Arc::clone(extension));
+            }
+            let post_state = executor
+                .execute_and_verify_receipt(&block, total_difficulty, None)
+                .map_err(reth_interfaces::Error::from)?;
+            Ok(post_state.state_diff(num_hash))
+        })
+    }
+
     /// Trace the transaction according to the provided options.
     ///
     /// Ref: <https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers>
@@ -220,10 +294,12 @@ where
                     block_env.clone(),
                     block_txs,
                     tx.hash,
+                    this.inner.eth_api.evm_extension(),
                 )?;
 
                 let env = Env { cfg, block: block_env, tx: tx_env_with_recovered(&tx) };
-                trace_transaction(opts, env, &mut db).map(|(trace, _)| trace)
+                trace_transaction(opts, env, &mut db, this.inner.eth_api.evm_extension())
+                    .map(|(trace, _)| trace)
             })
         })
         .await
@@ -326,7 +402,7 @@ where
 #[async_trait]
 impl<Client, Eth> DebugApiServer for DebugApi<Client, Eth>
 where
-    Client: BlockProviderIdExt + HeaderProvider + 'static,
+    Client: BlockProviderIdExt + HeaderProvider + ChainSpecProvider + 'static,
     Eth: EthApiSpec + 'static,
 {
     /// Handler for `debug_getRawHeader`
@@ -461,6 +537,18 @@ where
         let _permit = self.acquire_trace_permit().await;
         Ok(DebugApi::debug_trace_call(self, request, block_number, opts).await?)
     }
+
+    /// Handler for `debug_getBlockStateDiff`
+    async fn debug_block_state_diff(&self, block: BlockId) -> RpcResult<BlockStateDiff> {
+        let _permit = self.acquire_trace_permit().await;
+        Ok(DebugApi::debug_block_state_diff(self, block).await?)
+    }
+
+    /// Handler for `debug_executionWitness`
+    async fn debug_execution_witness(&self, block: BlockId) -> RpcResult<ExecutionWitness> {
+        let _permit = self.acquire_trace_permit().await;
+        Ok(DebugApi::debug_execution_witness(self, block).await?)
+    }
 }
 
 impl<Client, Eth> std::fmt::Debug for DebugApi<Client, Eth> {
@@ -495,6 +583,7 @@ fn trace_transaction(
     opts: GethDebugTracingOptions,
     env: Env,
     db: &mut SubState<StateProviderBox<'_>>,
+    extension: Option<&Arc<dyn EvmExtension>>,
 ) -> EthResult<(GethTraceFrame, revm_primitives::State)> {
     let GethDebugTracingOptions { config, tracer, tracer_config, .. } = opts;
 
@@ -510,7 +599,7 @@ fn trace_transaction(
             GethDebugTracerType::BuiltInTracer(tracer) => match tracer {
                 GethDebugBuiltInTracerType::FourByteTracer => {
                     let mut inspector = FourByteInspector::default();
-                    let (res, _) = inspect(db, env, &mut inspector)?;
+                    let (res, _) = inspect(db, env, &mut inspector, extension)?;
                     return Ok((FourByteFrame::from(inspector).into(), res.state))
                 }
                 GethDebugBuiltInTracerType::CallTracer => {
@@ -521,7 +610,7 @@ fn trace_transaction(
                     let mut inspector =
                         TracingInspector::new(TracingInspectorConfig::from_geth_config(&config));
 
-                    let (res, _) = inspect(db, env, &mut inspector)?;
+                    let (res, _) = inspect(db, env, &mut inspector, extension)?;
 
                     let frame = inspector.into_geth_builder().geth_call_traces(call_config);
 
@@ -545,7 +634,7 @@ fn trace_transaction(
 
     let mut inspector = TracingInspector::new(inspector_config);
 
-    let (res, _) = inspect(db, env, &mut inspector)?;
+    let (res, _) = inspect(db, env, &mut inspector, extension)?;
     let gas_used = res.result.gas_used();
 
     let frame = inspector.into_geth_builder().geth_traces(U256::from(gas_used), config);
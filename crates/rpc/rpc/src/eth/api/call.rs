@@ -18,6 +18,7 @@ use reth_provider::{BlockProviderIdExt, EvmEnvProvider, StateProvider, StateProv
 use reth_revm::{
     access_list::AccessListInspector,
     database::{State, SubState},
+    extension::EvmExtension,
 };
 use reth_rpc_types::{state::StateOverride, CallRequest};
 use reth_transaction_pool::TransactionPool;
@@ -25,6 +26,7 @@ use revm::{
     db::{CacheDB, DatabaseRef},
     primitives::{BlockEnv, CfgEnv, Env, ExecutionResult, Halt, TransactTo},
 };
+use std::sync::Arc;
 use tracing::trace;
 
 // Gas per transaction not creating a contract.
@@ -100,6 +102,7 @@ where
         // Configure the evm env
         let mut env = build_call_evm_env(cfg, block, request)?;
         let mut db = SubState::new(State::new(state));
+        let extension = self.evm_extension();
 
         // if the request is a simple transfer we can optimize
         if env.tx.data.is_empty() {
@@ -149,7 +152,7 @@ where
         trace!(target: "rpc::eth::estimate", ?env, "Starting gas estimation");
 
         // execute the call without writing to db
-        let ethres = transact(&mut db, env.clone());
+        let ethres = transact(&mut db, env.clone(), extension);
 
         // Exceptional case: init used too much gas, we need to increase the gas limit and try
         // again
@@ -158,7 +161,7 @@ where
             // if price or limit was included in the request then we can execute the request
             // again with the block's gas limit to check if revert is gas related or not
             if request_gas.is_some() || request_gas_price.is_some() {
-                return Err(map_out_of_gas_err(env_gas_limit, env, &mut db))
+                return Err(map_out_of_gas_err(env_gas_limit, env, &mut db, extension))
             }
         }
 
@@ -174,7 +177,7 @@ where
                 // if price or limit was included in the request then we can execute the request
                 // again with the block's gas limit to check if revert is gas related or not
                 return if request_gas.is_some() || request_gas_price.is_some() {
-                    Err(map_out_of_gas_err(env_gas_limit, env, &mut db))
+                    Err(map_out_of_gas_err(env_gas_limit, env, &mut db, extension))
                 } else {
                     // the transaction did revert
                     Err(RpcInvalidTransactionError::Revert(RevertError::new(output)).into())
@@ -203,7 +206,7 @@ where
         while (highest_gas_limit - lowest_gas_limit) > 1 {
             let mut env = env.clone();
             env.tx.gas_limit = mid_gas_limit;
-            let ethres = transact(&mut db, env);
+            let ethres = transact(&mut db, env, extension);
 
             // Exceptional case: init used too much gas, we need to increase the gas limit and try
             // again
@@ -286,9 +289,12 @@ where
 
         let initial = request.access_list.clone().unwrap_or_default();
 
-        let precompiles = get_precompiles(&env.cfg.spec_id);
+        let mut precompiles = get_precompiles(&env.cfg.spec_id);
+        if let Some(extension) = self.evm_extension() {
+            precompiles.extend(extension.precompiles().into_iter().map(|(address, _)| address));
+        }
         let mut inspector = AccessListInspector::new(initial, from, to, precompiles);
-        let (result, _env) = inspect(&mut db, env, &mut inspector)?;
+        let (result, _env) = inspect(&mut db, env, &mut inspector, self.evm_extension())?;
 
         match result.result {
             ExecutionResult::Halt { reason, .. } => Err(match reason {
@@ -311,13 +317,14 @@ fn map_out_of_gas_err<S>(
     env_gas_limit: U256,
     mut env: Env,
     mut db: &mut CacheDB<State<S>>,
+    extension: Option<&Arc<dyn EvmExtension>>,
 ) -> EthApiError
 where
     S: StateProvider,
 {
     let req_gas_limit = env.tx.gas_limit;
     env.tx.gas_limit = env_gas_limit.try_into().unwrap_or(u64::MAX);
-    let (res, _) = match transact(&mut db, env) {
+    let (res, _) = match transact(&mut db, env, extension) {
         Ok(res) => res,
         Err(err) => return err,
     };
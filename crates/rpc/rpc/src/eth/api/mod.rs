@@ -14,6 +14,7 @@ use reth_interfaces::Result;
 use reth_network_api::NetworkInfo;
 use reth_primitives::{Address, BlockId, BlockNumberOrTag, ChainInfo, H256, U256, U64};
 use reth_provider::{BlockProviderIdExt, EvmEnvProvider, StateProviderBox, StateProviderFactory};
+use reth_revm::extension::EvmExtension;
 use reth_rpc_types::{FeeHistoryCache, SyncInfo, SyncStatus};
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
 use reth_transaction_pool::TransactionPool;
@@ -28,7 +29,9 @@ mod sign;
 mod state;
 mod transactions;
 
-pub use transactions::{EthTransactions, TransactionSource};
+pub use transactions::{
+    build_transaction_receipt_with_block_receipts, EthTransactions, TransactionSource,
+};
 
 /// Cache limit of block-level fee history for `eth_feeHistory` RPC method.
 const FEE_HISTORY_CACHE_LIMIT: usize = 2048;
@@ -100,6 +103,19 @@ where
         eth_cache: EthStateCache,
         gas_oracle: GasPriceOracle<Client>,
         task_spawner: Box<dyn TaskSpawner>,
+    ) -> Self {
+        Self::with_evm_extension(client, pool, network, eth_cache, gas_oracle, task_spawner, None)
+    }
+
+    /// Creates a new, shareable instance that applies the given [EvmExtension] to all calls.
+    pub fn with_evm_extension(
+        client: Client,
+        pool: Pool,
+        network: Network,
+        eth_cache: EthStateCache,
+        gas_oracle: GasPriceOracle<Client>,
+        task_spawner: Box<dyn TaskSpawner>,
+        evm_extension: Option<Arc<dyn EvmExtension>>,
     ) -> Self {
         // get the block number of the latest block
         let latest_block = client
@@ -121,6 +137,7 @@ where
             fee_history_cache: FeeHistoryCache::new(
                 NonZeroUsize::new(FEE_HISTORY_CACHE_LIMIT).unwrap(),
             ),
+            evm_extension,
         };
         Self { inner: Arc::new(inner) }
     }
@@ -321,4 +338,6 @@ struct EthApiInner<Client, Pool, Network> {
     task_spawner: Box<dyn TaskSpawner>,
     /// The cache for fee history entries,
     fee_history_cache: FeeHistoryCache,
+    /// The extension applied to all calls.
+    evm_extension: Option<Arc<dyn EvmExtension>>,
 }
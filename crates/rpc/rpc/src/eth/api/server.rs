@@ -332,6 +332,17 @@ where
         Ok(EthTransactions::send_raw_transaction(self, tx).await?)
     }
 
+    /// Handler for: `eth_sendPrivateRawTransaction`
+    async fn send_private_raw_transaction(
+        &self,
+        tx: Bytes,
+        max_block_number: Option<U64>,
+    ) -> Result<H256> {
+        trace!(target: "rpc::eth", ?tx, ?max_block_number, "Serving eth_sendPrivateRawTransaction");
+        let max_block_number = max_block_number.map(|number| number.as_u64());
+        Ok(EthTransactions::send_private_raw_transaction(self, tx, max_block_number).await?)
+    }
+
     /// Handler for: `eth_sign`
     async fn sign(&self, address: Address, message: Bytes) -> Result<Bytes> {
         trace!(target: "rpc::eth", ?address, ?message, "Serving eth_sign");
@@ -3,7 +3,7 @@ use crate::{
     eth::{
         error::{EthApiError, EthResult, SignError},
         revm_utils::{inspect, prepare_call_env, replay_transactions_until, transact},
-        utils::recover_raw_transaction,
+        utils::recover_raw_pooled_transaction,
     },
     EthApi, EthApiSpec,
 };
@@ -11,7 +11,7 @@ use async_trait::async_trait;
 
 use reth_network_api::NetworkInfo;
 use reth_primitives::{
-    Address, BlockId, BlockNumberOrTag, Bytes, FromRecoveredTransaction, Header,
+    Address, BlockId, BlockNumberOrTag, Bytes, FromRecoveredTransaction, Head, Header,
     IntoRecoveredTransaction, Receipt, SealedBlock,
     TransactionKind::{Call, Create},
     TransactionMeta, TransactionSigned, TransactionSignedEcRecovered, H256, U128, U256, U64,
@@ -20,19 +20,21 @@ use reth_provider::{BlockProviderIdExt, EvmEnvProvider, StateProviderBox, StateP
 use reth_revm::{
     database::{State, SubState},
     env::{fill_block_env_with_coinbase, tx_env_with_recovered},
+    extension::EvmExtension,
     tracing::{TracingInspector, TracingInspectorConfig},
 };
 use reth_rpc_types::{
     state::StateOverride, CallRequest, Index, Log, Transaction, TransactionInfo,
     TransactionReceipt, TransactionRequest, TypedTransactionRequest,
 };
-use reth_transaction_pool::{TransactionOrigin, TransactionPool};
+use reth_transaction_pool::{PoolTransaction, TransactionOrigin, TransactionPool};
 use revm::{
     db::CacheDB,
     primitives::{BlockEnv, CfgEnv},
     Inspector,
 };
 use revm_primitives::{utilities::create_address, Env, ResultAndState, SpecId};
+use std::sync::Arc;
 
 /// Commonly used transaction related functions for the [EthApi] type in the `eth_` namespace
 #[async_trait::async_trait]
@@ -45,6 +47,9 @@ pub trait EthTransactions: Send + Sync {
     where
         F: FnOnce(StateProviderBox<'_>) -> EthResult<T>;
 
+    /// Returns the [EvmExtension] that is applied to all executions, if any.
+    fn evm_extension(&self) -> Option<&Arc<dyn EvmExtension>>;
+
     /// Returns the revm evm env for the requested [BlockId]
     ///
     /// If the [BlockId] this will return the [BlockId::Hash] of the block the env was configured
@@ -107,6 +112,19 @@ pub trait EthTransactions: Send + Sync {
     /// Returns the hash of the transaction.
     async fn send_raw_transaction(&self, tx: Bytes) -> EthResult<H256>;
 
+    /// Decodes and recovers the transaction and submits it to the pool as a private transaction,
+    /// which is only included in locally built blocks and never propagated to peers.
+    ///
+    /// If a max block number is given, the transaction is discarded if it's not included until
+    /// that block.
+    ///
+    /// Returns the hash of the transaction.
+    async fn send_private_raw_transaction(
+        &self,
+        tx: Bytes,
+        max_block_number: Option<u64>,
+    ) -> EthResult<H256>;
+
     /// Signs transaction with a matching signer, if any and submits the transaction to the pool.
     /// Returns the hash of the signed transaction.
     async fn send_transaction(&self, request: TransactionRequest) -> EthResult<H256>;
@@ -196,6 +214,10 @@ where
         f(state)
     }
 
+    fn evm_extension(&self) -> Option<&Arc<dyn EvmExtension>> {
+        self.inner.evm_extension.as_ref()
+    }
+
     async fn evm_env_at(&self, at: BlockId) -> EthResult<(CfgEnv, BlockEnv, BlockId)> {
         // TODO handle Pending state's env
         match at {
@@ -209,7 +231,20 @@ where
                     .client()
                     .block_hash_for_id(hash_or_num)?
                     .ok_or_else(|| EthApiError::UnknownBlockNumber)?;
-                let (cfg, env) = self.cache().get_evm_env(block_hash).await?;
+                let (mut cfg, env) = self.cache().get_evm_env(block_hash).await?;
+                if let Some(extension) = self.evm_extension() {
+                    let head = Head {
+                        number: env.number.to::<u64>(),
+                        hash: block_hash,
+                        difficulty: env.difficulty,
+                        total_difficulty: self
+                            .client()
+                            .header_td(&block_hash)?
+                            .ok_or_else(|| EthApiError::UnknownBlockNumber)?,
+                        timestamp: env.timestamp.to::<u64>(),
+                    };
+                    cfg.spec_id = extension.spec_id(&head, cfg.spec_id);
+                }
                 Ok((cfg, env, block_hash.into()))
             }
         }
@@ -244,7 +279,12 @@ where
     }
 
     async fn transaction_by_hash(&self, hash: H256) -> EthResult<Option<TransactionSource>> {
-        if let Some(tx) = self.pool().get(&hash).map(|tx| tx.transaction.to_recovered_transaction())
+        // private transactions are never revealed
+        if let Some(tx) = self
+            .pool()
+            .get(&hash)
+            .filter(|tx| tx.propagate)
+            .map(|tx| tx.transaction.to_recovered_transaction())
         {
             return Ok(Some(TransactionSource::Pool(tx)))
         }
@@ -332,9 +372,11 @@ where
     }
 
     async fn send_raw_transaction(&self, tx: Bytes) -> EthResult<H256> {
-        let recovered = recover_raw_transaction(tx)?;
+        // blob transactions are submitted together with their sidecar
+        let (recovered, sidecar) = recover_raw_pooled_transaction(tx)?;
 
-        let pool_transaction = <Pool::Transaction>::from_recovered_transaction(recovered);
+        let pool_transaction =
+            <Pool::Transaction>::from_recovered_pooled_transaction(recovered, sidecar);
 
         // submit the transaction to the pool with a `Local` origin
         let hash = self.pool().add_transaction(TransactionOrigin::Local, pool_transaction).await?;
@@ -342,6 +384,31 @@ where
         Ok(hash)
     }
 
+    async fn send_private_raw_transaction(
+        &self,
+        tx: Bytes,
+        max_block_number: Option<u64>,
+    ) -> EthResult<H256> {
+        if let Some(max_block_number) = max_block_number {
+            // the transaction must at least be includable in the next block
+            let best_number = self.client().chain_info()?.best_number;
+            if max_block_number <= best_number {
+                return Err(EthApiError::MaxBlockNumberInPast(max_block_number))
+            }
+        }
+
+        let (recovered, sidecar) = recover_raw_pooled_transaction(tx)?;
+
+        let pool_transaction =
+            <Pool::Transaction>::from_recovered_pooled_transaction(recovered, sidecar);
+
+        // submit the transaction to the pool with a `Private` origin, so it's never propagated
+        let origin = TransactionOrigin::Private { max_block_number };
+        let hash = self.pool().add_transaction(origin, pool_transaction).await?;
+
+        Ok(hash)
+    }
+
     async fn send_transaction(&self, mut request: TransactionRequest) -> EthResult<H256> {
         let from = match request.from {
             Some(from) => from,
@@ -443,7 +510,11 @@ where
         at: BlockId,
         state_overrides: Option<StateOverride>,
     ) -> EthResult<(ResultAndState, Env)> {
-        self.with_call_at(request, at, state_overrides, |mut db, env| transact(&mut db, env)).await
+        let extension = self.evm_extension().cloned();
+        self.with_call_at(request, at, state_overrides, |mut db, env| {
+            transact(&mut db, env, extension.as_ref())
+        })
+        .await
     }
 
     async fn inspect_call_at<I>(
@@ -456,7 +527,11 @@ where
     where
         I: for<'r> Inspector<CacheDB<State<StateProviderBox<'r>>>> + Send,
     {
-        self.with_call_at(request, at, state_overrides, |db, env| inspect(db, env, inspector)).await
+        let extension = self.evm_extension().cloned();
+        self.with_call_at(request, at, state_overrides, |db, env| {
+            inspect(db, env, inspector, extension.as_ref())
+        })
+        .await
     }
 
     fn trace_at<F, R>(
@@ -473,7 +548,7 @@ where
             let db = SubState::new(State::new(state));
 
             let mut inspector = TracingInspector::new(config);
-            let (res, _) = inspect(db, env, &mut inspector)?;
+            let (res, _) = inspect(db, env, &mut inspector, self.evm_extension())?;
 
             f(inspector, res)
         })
@@ -523,12 +598,19 @@ where
             let mut db = SubState::new(State::new(state));
 
             // replay all transactions prior to the targeted transaction
-            replay_transactions_until(&mut db, cfg.clone(), block_env.clone(), block_txs, tx.hash)?;
+            replay_transactions_until(
+                &mut db,
+                cfg.clone(),
+                block_env.clone(),
+                block_txs,
+                tx.hash,
+                self.evm_extension(),
+            )?;
 
             let env = Env { cfg, block: block_env, tx: tx_env_with_recovered(&tx) };
 
             let mut inspector = TracingInspector::new(config);
-            let (res, _) = inspect(db, env, &mut inspector)?;
+            let (res, _) = inspect(db, env, &mut inspector, self.evm_extension())?;
             f(tx_info, inspector, res)
         })
         .map(Some)
@@ -597,79 +679,94 @@ where
         meta: TransactionMeta,
         receipt: Receipt,
     ) -> EthResult<TransactionReceipt> {
-        let transaction =
-            tx.clone().into_ecrecovered().ok_or(EthApiError::InvalidTransactionSignature)?;
-
         // get all receipts for the block
         let all_receipts = match self.cache().get_receipts(meta.block_hash).await? {
             Some(recpts) => recpts,
             None => return Err(EthApiError::UnknownBlockNumber),
         };
 
-        // get the previous transaction cumulative gas used
-        let gas_used = if meta.index == 0 {
-            receipt.cumulative_gas_used
-        } else {
-            let prev_tx_idx = (meta.index - 1) as usize;
-            all_receipts
-                .get(prev_tx_idx)
-                .map(|prev_receipt| receipt.cumulative_gas_used - prev_receipt.cumulative_gas_used)
-                .unwrap_or_default()
-        };
+        build_transaction_receipt_with_block_receipts(tx, meta, receipt, &all_receipts)
+    }
+}
 
-        let mut res_receipt = TransactionReceipt {
-            transaction_hash: Some(meta.tx_hash),
-            transaction_index: Some(U256::from(meta.index)),
-            block_hash: Some(meta.block_hash),
-            block_number: Some(U256::from(meta.block_number)),
-            from: transaction.signer(),
-            to: None,
-            cumulative_gas_used: U256::from(receipt.cumulative_gas_used),
-            gas_used: Some(U256::from(gas_used)),
-            contract_address: None,
-            logs: Vec::with_capacity(receipt.logs.len()),
-            effective_gas_price: U128::from(transaction.effective_gas_price(meta.base_fee)),
-            transaction_type: tx.transaction.tx_type().into(),
-            // TODO pre-byzantium receipts have a post-transaction state root
-            state_root: None,
-            logs_bloom: receipt.bloom_slow(),
-            status_code: if receipt.success { Some(U64::from(1)) } else { Some(U64::from(0)) },
-        };
+/// Builds the [TransactionReceipt] response of a transaction, given the receipts of all
+/// transactions in its block.
+///
+/// The receipts of the block are required to determine the gas used by the transaction and the
+/// index of its logs in the block.
+pub fn build_transaction_receipt_with_block_receipts(
+    tx: TransactionSigned,
+    meta: TransactionMeta,
+    receipt: Receipt,
+    all_receipts: &[Receipt],
+) -> EthResult<TransactionReceipt> {
+    let transaction =
+        tx.clone().into_ecrecovered().ok_or(EthApiError::InvalidTransactionSignature)?;
+
+    // get the previous transaction cumulative gas used
+    let gas_used = if meta.index == 0 {
+        receipt.cumulative_gas_used
+    } else {
+        let prev_tx_idx = (meta.index - 1) as usize;
+        all_receipts
+            .get(prev_tx_idx)
+            .map(|prev_receipt| receipt.cumulative_gas_used - prev_receipt.cumulative_gas_used)
+            .unwrap_or_default()
+    };
 
-        match tx.transaction.kind() {
-            Create => {
-                res_receipt.contract_address =
-                    Some(create_address(transaction.signer(), tx.transaction.nonce()));
-            }
-            Call(addr) => {
-                res_receipt.to = Some(*addr);
-            }
-        }
+    let mut res_receipt = TransactionReceipt {
+        transaction_hash: Some(meta.tx_hash),
+        transaction_index: Some(U256::from(meta.index)),
+        block_hash: Some(meta.block_hash),
+        block_number: Some(U256::from(meta.block_number)),
+        from: transaction.signer(),
+        to: None,
+        cumulative_gas_used: U256::from(receipt.cumulative_gas_used),
+        gas_used: Some(U256::from(gas_used)),
+        contract_address: None,
+        logs: Vec::with_capacity(receipt.logs.len()),
+        effective_gas_price: U128::from(transaction.effective_gas_price(meta.base_fee)),
+        transaction_type: tx.transaction.tx_type().into(),
+        // TODO pre-byzantium receipts have a post-transaction state root
+        state_root: None,
+        logs_bloom: receipt.bloom_slow(),
+        status_code: if receipt.success { Some(U64::from(1)) } else { Some(U64::from(0)) },
+    };
 
-        // get number of logs in the block
-        let mut num_logs = 0;
-        for prev_receipt in all_receipts.iter().take(meta.index as usize) {
-            num_logs += prev_receipt.logs.len();
+    match tx.transaction.kind() {
+        Create => {
+            res_receipt.contract_address =
+                Some(create_address(transaction.signer(), tx.transaction.nonce()));
         }
-
-        for (tx_log_idx, log) in receipt.logs.into_iter().enumerate() {
-            let rpclog = Log {
-                address: log.address,
-                topics: log.topics,
-                data: log.data,
-                block_hash: Some(meta.block_hash),
-                block_number: Some(U256::from(meta.block_number)),
-                transaction_hash: Some(meta.tx_hash),
-                transaction_index: Some(U256::from(meta.index)),
-                log_index: Some(U256::from(num_logs + tx_log_idx)),
-                removed: false,
-            };
-            res_receipt.logs.push(rpclog);
+        Call(addr) => {
+            res_receipt.to = Some(*addr);
         }
+    }
 
-        Ok(res_receipt)
+    // get number of logs in the block
+    let mut num_logs = 0;
+    for prev_receipt in all_receipts.iter().take(meta.index as usize) {
+        num_logs += prev_receipt.logs.len();
     }
+
+    for (tx_log_idx, log) in receipt.logs.into_iter().enumerate() {
+        let rpclog = Log {
+            address: log.address,
+            topics: log.topics,
+            data: log.data,
+            block_hash: Some(meta.block_hash),
+            block_number: Some(U256::from(meta.block_number)),
+            transaction_hash: Some(meta.tx_hash),
+            transaction_index: Some(U256::from(meta.index)),
+            log_index: Some(U256::from(num_logs + tx_log_idx)),
+            removed: false,
+        };
+        res_receipt.logs.push(rpclog);
+    }
+
+    Ok(res_receipt)
 }
+
 /// Represents from where a transaction was fetched.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum TransactionSource {
@@ -812,4 +909,35 @@ mod tests {
         assert!(pool.get(&tx_1_result).is_some(), "tx1 not found in the pool");
         assert!(pool.get(&tx_2_result).is_some(), "tx2 not found in the pool");
     }
+
+    #[tokio::test]
+    async fn send_private_raw_transaction() {
+        let noop_provider = NoopProvider::default();
+        let pool = testing_pool();
+
+        let cache = EthStateCache::spawn(noop_provider, Default::default());
+        let eth_api = EthApi::new(
+            noop_provider,
+            pool.clone(),
+            NoopNetwork,
+            cache.clone(),
+            GasPriceOracle::new(noop_provider, Default::default(), cache),
+        );
+
+        // https://etherscan.io/tx/0xa694b71e6c128a2ed8e2e0f6770bddbe52e3bb8f10e8472f9a79ab81497a8b5d
+        let tx = Bytes::from(hex!("02f871018303579880850555633d1b82520894eee27662c2b8eba3cd936a23f039f3189633e4c887ad591c62bdaeb180c080a07ea72c68abfb8fca1bd964f0f99132ed9280261bdca3e549546c0205e800f7d0a05b4ef3039e9c9b9babc179a1878fb825b5aaf5aed2fa8744854150157b08d6f3"));
+
+        // the max block number must be in the future
+        let err = eth_api.send_private_raw_transaction(tx.clone(), Some(0)).await.unwrap_err();
+        assert!(matches!(err, EthApiError::MaxBlockNumberInPast(0)));
+        assert!(pool.is_empty());
+
+        let hash = eth_api.send_private_raw_transaction(tx, Some(10)).await.unwrap();
+        let pooled = pool.get(&hash).expect("tx not found in the pool");
+        assert_eq!(pooled.origin, TransactionOrigin::Private { max_block_number: Some(10) });
+        assert!(!pooled.propagate);
+
+        // private transactions are not revealed to rpc callers
+        assert!(eth_api.transaction_by_hash(hash).await.unwrap().is_none());
+    }
 }
@@ -2,7 +2,9 @@
 
 use crate::result::{internal_rpc_err, invalid_params_rpc_err, rpc_err, rpc_error_with_code};
 use jsonrpsee::{core::Error as RpcError, types::ErrorObject};
-use reth_primitives::{abi::decode_revert_reason, Address, Bytes, U256};
+use reth_primitives::{
+    abi::decode_revert_reason, Address, BlobTransactionValidationError, Bytes, U256,
+};
 use reth_rpc_types::{error::EthRpcErrorCode, BlockError};
 use reth_transaction_pool::error::{InvalidPoolTransactionError, PoolError};
 use revm::primitives::{EVMError, ExecutionResult, Halt, OutOfGasError};
@@ -29,6 +31,9 @@ pub enum EthApiError {
     UnknownBlockOrTxIndex,
     #[error("Invalid block range")]
     InvalidBlockRange,
+    /// Thrown when the max block number of a private transaction is not after the current block
+    #[error("max block number {0} is not in the future")]
+    MaxBlockNumberInPast(u64),
     /// An internal error where prevrandao is not set in the evm's environment
     #[error("Prevrandao not in th EVM's environment after merge")]
     PrevrandaoNotSet,
@@ -78,6 +83,7 @@ impl From<EthApiError> for ErrorObject<'static> {
             EthApiError::InvalidTransactionSignature |
             EthApiError::EmptyRawTransactionData |
             EthApiError::InvalidBlockRange |
+            EthApiError::MaxBlockNumberInPast(_) |
             EthApiError::ConflictingFeeFieldsInRequest |
             EthApiError::Signing(_) |
             EthApiError::BothStateAndStateDiffInOverride(_) |
@@ -207,6 +213,19 @@ pub enum RpcInvalidTransactionError {
     /// The transaction is before Spurious Dragon and has a chain ID
     #[error("Transactions before Spurious Dragon should not have a chain ID.")]
     OldLegacyChainId,
+    /// Thrown post Cancun if the transaction's blob fee is less than the blob gas price of the
+    /// block
+    #[error("max fee per blob gas less than block blob gas fee")]
+    BlobFeeCapTooLow,
+    /// Thrown if a blob transaction does not carry any blobs
+    #[error("blobless blob transaction")]
+    BlobTransactionMissingBlobHashes,
+    /// Thrown if a blob transaction tries to create a contract
+    #[error("blob transaction is a create transaction")]
+    BlobTransactionIsCreate,
+    /// Thrown if a blob versioned hash has an unsupported version
+    #[error("blob hash version mismatch")]
+    BlobVersionedHashVersionInvalid,
 }
 
 impl RpcInvalidTransactionError {
@@ -318,6 +337,21 @@ impl From<reth_primitives::InvalidTransactionError> for RpcInvalidTransactionErr
             InvalidTransactionError::Eip1559Disabled => {
                 RpcInvalidTransactionError::TxTypeNotSupported
             }
+            InvalidTransactionError::Eip4844Disabled => {
+                RpcInvalidTransactionError::TxTypeNotSupported
+            }
+            InvalidTransactionError::BlobTransactionMissingBlobHashes => {
+                RpcInvalidTransactionError::BlobTransactionMissingBlobHashes
+            }
+            InvalidTransactionError::BlobTransactionIsCreate => {
+                RpcInvalidTransactionError::BlobTransactionIsCreate
+            }
+            InvalidTransactionError::BlobVersionedHashVersionInvalid => {
+                RpcInvalidTransactionError::BlobVersionedHashVersionInvalid
+            }
+            InvalidTransactionError::BlobFeeCapTooLow => {
+                RpcInvalidTransactionError::BlobFeeCapTooLow
+            }
             InvalidTransactionError::TxTypeNotSupported => {
                 RpcInvalidTransactionError::TxTypeNotSupported
             }
@@ -395,8 +429,16 @@ pub enum RpcPoolError {
     NegativeValue,
     #[error("oversized data")]
     OversizedData,
+    #[error("oversized data: transaction size {size}, limit {limit}")]
+    ExceedsMaxTransactionSize { size: usize, limit: usize },
+    #[error("transaction underpriced: gas tip cap below minimum needed {0}")]
+    PriorityFeeBelowMinimum(u128),
     #[error("max initcode size exceeded")]
     ExceedsMaxInitCodeSize,
+    #[error("missing blob sidecar")]
+    MissingBlobSidecar,
+    #[error(transparent)]
+    InvalidBlobSidecar(#[from] BlobTransactionValidationError),
     #[error(transparent)]
     Invalid(#[from] RpcInvalidTransactionError),
     #[error(transparent)]
@@ -418,6 +460,12 @@ impl From<PoolError> for RpcPoolError {
             PoolError::ReplacementUnderpriced(_) => RpcPoolError::ReplaceUnderpriced,
             PoolError::FeeCapBelowMinimumProtocolFeeCap(_, _) => RpcPoolError::Underpriced,
             PoolError::SpammerExceededCapacity(_, _) => RpcPoolError::TxPoolOverflow,
+            PoolError::PriorityFeeBelowMinimum(_, minimum) => {
+                RpcPoolError::PriorityFeeBelowMinimum(minimum)
+            }
+            PoolError::ExceededMaxTransactionSize(_, size, limit) => {
+                RpcPoolError::ExceedsMaxTransactionSize { size, limit }
+            }
             PoolError::DiscardedOnInsert(_) => RpcPoolError::TxPoolOverflow,
             PoolError::InvalidTransaction(_, err) => err.into(),
             PoolError::Other(_, err) => RpcPoolError::Other(err),
@@ -436,6 +484,8 @@ impl From<InvalidPoolTransactionError> for RpcPoolError {
             }
             InvalidPoolTransactionError::OversizedData(_, _) => RpcPoolError::OversizedData,
             InvalidPoolTransactionError::Underpriced => RpcPoolError::Underpriced,
+            InvalidPoolTransactionError::MissingBlobSidecar => RpcPoolError::MissingBlobSidecar,
+            InvalidPoolTransactionError::InvalidBlobSidecar(err) => err.into(),
         }
     }
 }
@@ -12,7 +12,10 @@ pub(crate) mod revm_utils;
 mod signer;
 pub(crate) mod utils;
 
-pub use api::{EthApi, EthApiSpec, EthTransactions, TransactionSource};
+pub use api::{
+    build_transaction_receipt_with_block_receipts, EthApi, EthApiSpec, EthTransactions,
+    TransactionSource,
+};
 pub use filter::EthFilter;
 pub use id_provider::EthSubscriptionIdProvider;
 pub use pubsub::EthPubSub;
@@ -4,7 +4,10 @@ use crate::eth::error::{EthApiError, EthResult, RpcInvalidTransactionError};
 use reth_primitives::{
     AccessList, Address, TransactionSigned, TransactionSignedEcRecovered, TxHash, H256, U256,
 };
-use reth_revm::env::{fill_tx_env, fill_tx_env_with_recovered};
+use reth_revm::{
+    env::{fill_tx_env, fill_tx_env_with_recovered},
+    extension::{EvmExtension, ExtensionStack, WithExtension},
+};
 use reth_rpc_types::{
     state::{AccountOverride, StateOverride},
     CallRequest,
@@ -19,6 +22,7 @@ use revm_primitives::{
     db::{DatabaseCommit, DatabaseRef},
     Bytecode,
 };
+use std::sync::Arc;
 use tracing::trace;
 
 /// Helper type to work with different transaction types when configuring the EVM env.
@@ -78,19 +82,35 @@ pub(crate) fn get_precompiles(spec_id: &SpecId) -> Vec<reth_primitives::H160> {
 }
 
 /// Executes the [Env] against the given [Database] without committing state changes.
-pub(crate) fn transact<DB>(db: DB, env: Env) -> EthResult<(ResultAndState, Env)>
+///
+/// The [EvmExtension], if any, is applied to the execution.
+pub(crate) fn transact<DB>(
+    db: DB,
+    env: Env,
+    extension: Option<&Arc<dyn EvmExtension>>,
+) -> EthResult<(ResultAndState, Env)>
 where
     DB: Database,
     <DB as Database>::Error: Into<EthApiError>,
 {
     let mut evm = revm::EVM::with_env(env);
     evm.database(db);
-    let res = evm.transact()?;
+    let res = match extension {
+        Some(extension) => evm.inspect(ExtensionStack::new(Arc::clone(extension)))?,
+        None => evm.transact()?,
+    };
     Ok((res, evm.env))
 }
 
 /// Executes the [Env] against the given [Database] without committing state changes.
-pub(crate) fn inspect<S, I>(db: S, env: Env, inspector: I) -> EthResult<(ResultAndState, Env)>
+///
+/// The [EvmExtension], if any, is applied to the execution.
+pub(crate) fn inspect<S, I>(
+    db: S,
+    env: Env,
+    inspector: I,
+    extension: Option<&Arc<dyn EvmExtension>>,
+) -> EthResult<(ResultAndState, Env)>
 where
     S: Database,
     <S as Database>::Error: Into<EthApiError>,
@@ -98,7 +118,8 @@ where
 {
     let mut evm = revm::EVM::with_env(env);
     evm.database(db);
-    let res = evm.inspect(inspector)?;
+    let extension = extension.map(|extension| ExtensionStack::new(Arc::clone(extension)));
+    let res = evm.inspect(WithExtension::new(extension, inspector))?;
     Ok((res, evm.env))
 }
 
@@ -114,6 +135,7 @@ pub(crate) fn replay_transactions_until<DB, I, Tx>(
     block_env: BlockEnv,
     transactions: I,
     target_tx_hash: H256,
+    extension: Option<&Arc<dyn EvmExtension>>,
 ) -> EthResult<()>
 where
     DB: DatabaseRef,
@@ -124,6 +146,7 @@ where
     let env = Env { cfg, block: block_env, tx: TxEnv::default() };
     let mut evm = revm::EVM::with_env(env);
     evm.database(db);
+    let mut extension = extension.map(|extension| ExtensionStack::new(Arc::clone(extension)));
     for tx in transactions.into_iter() {
         if tx.hash() == target_tx_hash {
             // reached the target transaction
@@ -131,7 +154,10 @@ where
         }
 
         tx.try_fill_tx_env(&mut evm.env.tx)?;
-        let res = evm.transact()?;
+        let res = match extension.as_mut() {
+            Some(extension) => evm.inspect(extension)?,
+            None => evm.transact()?,
+        };
         evm.db.as_mut().expect("is set").commit(res.state)
     }
     Ok(())
@@ -1,7 +1,10 @@
 //! Commonly used code snippets
 
 use crate::eth::error::{EthApiError, EthResult};
-use reth_primitives::{Bytes, TransactionSigned, TransactionSignedEcRecovered};
+use reth_primitives::{
+    BlobTransactionSidecar, Bytes, PooledTransactionsElement, TransactionSigned,
+    TransactionSignedEcRecovered,
+};
 
 /// Recovers a [TransactionSignedEcRecovered] from an enveloped encoded byte stream.
 ///
@@ -16,3 +19,24 @@ pub(crate) fn recover_raw_transaction(data: Bytes) -> EthResult<TransactionSigne
 
     transaction.into_ecrecovered().ok_or(EthApiError::InvalidTransactionSignature)
 }
+
+/// Recovers a [TransactionSignedEcRecovered] and the sidecar of a blob transaction from an
+/// enveloped encoded byte stream.
+///
+/// Unlike [recover_raw_transaction], blob transactions are expected to include their sidecar, see
+/// [PooledTransactionsElement::decode_enveloped]
+pub(crate) fn recover_raw_pooled_transaction(
+    data: Bytes,
+) -> EthResult<(TransactionSignedEcRecovered, Option<BlobTransactionSidecar>)> {
+    if data.is_empty() {
+        return Err(EthApiError::EmptyRawTransactionData)
+    }
+
+    let (transaction, sidecar) = PooledTransactionsElement::decode_enveloped(data)
+        .map_err(|_| EthApiError::FailedToDecodeSignedTransaction)?
+        .into_parts();
+
+    let transaction =
+        transaction.into_ecrecovered().ok_or(EthApiError::InvalidTransactionSignature)?;
+    Ok((transaction, sidecar))
+}
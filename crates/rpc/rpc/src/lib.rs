@@ -30,6 +30,7 @@ mod debug;
 mod engine;
 pub mod eth;
 mod layers;
+mod mev;
 mod net;
 mod trace;
 mod txpool;
@@ -41,6 +42,7 @@ pub use debug::DebugApi;
 pub use engine::{EngineApi, EngineEthApi};
 pub use eth::{EthApi, EthApiSpec, EthFilter, EthPubSub, EthSubscriptionIdProvider};
 pub use layers::{AuthLayer, AuthValidator, Claims, JwtAuthValidator, JwtError, JwtSecret};
+pub use mev::MevApi;
 pub use net::NetApi;
 pub use trace::TraceApi;
 pub use txpool::TxPoolApi;
@@ -0,0 +1,110 @@
+use crate::{
+    eth::{
+        error::{EthApiError, EthResult},
+        utils::recover_raw_transaction,
+    },
+    result::invalid_params_rpc_err,
+};
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use reth_payload_builder::{Bundle, BundleStore};
+use reth_provider::BlockNumProvider;
+use reth_rpc_api::MevApiServer;
+use reth_rpc_types::{SendBundleRequest, SendBundleResponse};
+use tracing::trace;
+
+/// `mev` API implementation.
+///
+/// This type provides the functionality for handling `mev` related requests: submitted bundles are
+/// added to the [BundleStore] of the payload builder.
+#[derive(Debug, Clone)]
+pub struct MevApi<Client> {
+    /// The client that can interact with the chain.
+    client: Client,
+    /// The store submitted bundles are added to.
+    bundles: BundleStore,
+}
+
+impl<Client> MevApi<Client> {
+    /// Creates a new instance of `MevApi`.
+    pub fn new(client: Client, bundles: BundleStore) -> Self {
+        MevApi { client, bundles }
+    }
+
+    /// Decodes and recovers all transactions of the request.
+    fn to_bundle(request: SendBundleRequest) -> EthResult<Bundle> {
+        let SendBundleRequest {
+            txs,
+            block_number,
+            min_timestamp,
+            max_timestamp,
+            reverting_tx_hashes,
+            atomic,
+        } = request;
+        let transactions =
+            txs.into_iter().map(recover_raw_transaction).collect::<EthResult<Vec<_>>>()?;
+        Ok(Bundle::new(transactions, block_number.as_u64())
+            .with_min_timestamp(min_timestamp)
+            .with_max_timestamp(max_timestamp)
+            .with_reverting_tx_hashes(reverting_tx_hashes)
+            .with_atomic(atomic.unwrap_or(true)))
+    }
+}
+
+#[async_trait]
+impl<Client> MevApiServer for MevApi<Client>
+where
+    Client: BlockNumProvider + 'static,
+{
+    /// Handler for `mev_sendBundle`
+    async fn send_bundle(&self, request: SendBundleRequest) -> RpcResult<SendBundleResponse> {
+        trace!(target: "rpc::mev", block_number = ?request.block_number, "Serving mev_sendBundle");
+        let bundle = Self::to_bundle(request)?;
+        let head = self.client.best_block_number().map_err(EthApiError::from)?;
+        let bundle_hash = self
+            .bundles
+            .insert(bundle, head)
+            .map_err(|err| invalid_params_rpc_err(err.to_string()))?;
+        Ok(SendBundleResponse { bundle_hash })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{hex_literal::hex, Bytes, U64};
+    use reth_provider::test_utils::NoopProvider;
+
+    fn request(txs: Vec<Bytes>) -> SendBundleRequest {
+        SendBundleRequest {
+            txs,
+            block_number: U64::from(1),
+            min_timestamp: None,
+            max_timestamp: None,
+            reverting_tx_hashes: vec![],
+            atomic: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn send_bundle() {
+        let store = BundleStore::default();
+        let api = MevApi::new(NoopProvider::default(), store.clone());
+
+        // random tx: <https://etherscan.io/getRawTx?tx=0x9448608d36e721ef403c53b00546068a6474d6cbab6816c3926de449898e7bce>
+        let tx = Bytes::from(hex!("02f871018302a90f808504890aef60826b6c94ddf4c5025d1a5742cf12f74eec246d4432c295e487e09c3bbcc12b2b80c080a0f21a4eacd0bf8fea9c5105c543be5a1d8c796516875710fafafdf16d16d8ee23a001280915021bb446d1973501a67f93d2b38894a514b976e7b46dc2fe54598d76"));
+        let response = api.send_bundle(request(vec![tx.clone()])).await.unwrap();
+        let bundles = store.bundles_for(1, 0);
+        assert_eq!(bundles.len(), 1);
+        assert_eq!(bundles[0].hash, response.bundle_hash);
+        assert!(bundles[0].atomic);
+
+        assert!(api.send_bundle(request(vec![])).await.is_err());
+        assert!(api.send_bundle(request(vec![Bytes::from(vec![0x02])])).await.is_err());
+
+        // the head of the noop provider is block 0, so block 0 was already mined
+        let stale = SendBundleRequest { block_number: U64::from(0), ..request(vec![tx]) };
+        assert!(api.send_bundle(stale).await.is_err());
+        assert_eq!(store.len(), 1);
+    }
+}
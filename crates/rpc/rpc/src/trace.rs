@@ -166,6 +166,7 @@ where
         let (cfg, block_env, at) = self.inner.eth_api.evm_env_at(at).await?;
 
         self.on_blocking_task(|this| async move {
+            let extension = this.inner.eth_api.evm_extension().cloned();
             // execute all transactions on top of each other and record the traces
             this.inner.eth_api.with_state_at_block(at, move |state| {
                 let mut results = Vec::with_capacity(calls.len());
@@ -176,7 +177,7 @@ where
                         prepare_call_env(cfg.clone(), block_env.clone(), call, &mut db, None)?;
                     let config = tracing_config(&trace_types);
                     let mut inspector = TracingInspector::new(config);
-                    let (res, _) = inspect(&mut db, env, &mut inspector)?;
+                    let (res, _) = inspect(&mut db, env, &mut inspector, extension.as_ref())?;
                     let trace_res = inspector
                         .into_parity_builder()
                         .into_trace_results(res.result, &trace_types);
@@ -280,6 +281,7 @@ where
         let transactions = block.body;
 
         self.on_blocking_task(|this| async move {
+            let extension = this.inner.eth_api.evm_extension().cloned();
             // replay all transactions of the block
             this.inner
                 .eth_api
@@ -303,7 +305,7 @@ where
                         let env = Env { cfg: cfg.clone(), block: block_env.clone(), tx };
 
                         let mut inspector = TracingInspector::new(config);
-                        let (res, _) = inspect(&mut db, env, &mut inspector)?;
+                        let (res, _) = inspect(&mut db, env, &mut inspector, extension.as_ref())?;
                         results.push(f(tx_info, inspector, res.result)?);
 
                         // need to apply the state changes of this transaction before executing the
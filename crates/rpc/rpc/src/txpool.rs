@@ -3,10 +3,13 @@ use jsonrpsee::core::RpcResult as Result;
 use reth_primitives::{Address, U256, U64};
 use reth_rpc_api::TxPoolApiServer;
 use reth_rpc_types::{
-    txpool::{TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolInspectSummary, TxpoolStatus},
+    txpool::{
+        TxpoolContent, TxpoolContentFrom, TxpoolContentFromWithReasons, TxpoolInspect,
+        TxpoolInspectSummary, TxpoolParkedReason, TxpoolParkedTransaction, TxpoolStatus,
+    },
     Transaction,
 };
-use reth_transaction_pool::{AllPoolTransactions, PoolTransaction, TransactionPool};
+use reth_transaction_pool::{AllPoolTransactions, ParkedReason, PoolTransaction, TransactionPool};
 use std::collections::BTreeMap;
 use tracing::trace;
 
@@ -43,7 +46,7 @@ where
             entry.insert(key, tx);
         }
 
-        let AllPoolTransactions { pending, queued } = self.pool.all_transactions();
+        let AllPoolTransactions { pending, queued } = self.all_transactions();
 
         let mut content = TxpoolContent::default();
         for pending in pending {
@@ -55,6 +58,50 @@ where
 
         content
     }
+
+    /// Returns all transactions in the pool, excluding private transactions which must never be
+    /// revealed to RPC callers.
+    fn all_transactions(&self) -> AllPoolTransactions<Pool::Transaction> {
+        let AllPoolTransactions { mut pending, mut queued } = self.pool.all_transactions();
+        pending.retain(|tx| tx.propagate);
+        queued.retain(|tx| tx.propagate);
+        AllPoolTransactions { pending, queued }
+    }
+
+    fn content_from_with_reasons(&self, from: Address) -> TxpoolContentFromWithReasons {
+        let mut content = TxpoolContentFromWithReasons::default();
+        for status in self.pool.get_transactions_by_sender_with_status(from) {
+            if !status.transaction.propagate {
+                // private transactions are never revealed
+                continue
+            }
+            let key = status.transaction.nonce().to_string();
+            let tx = status.transaction.transaction.to_recovered_transaction();
+            let tx = Transaction::from_recovered(tx);
+            if status.is_pending() {
+                content.pending.insert(key, tx);
+            } else {
+                let parked_reasons =
+                    status.parked_reasons.into_iter().map(to_rpc_parked_reason).collect();
+                content
+                    .queued
+                    .insert(key, TxpoolParkedTransaction { transaction: tx, parked_reasons });
+            }
+        }
+        content
+    }
+}
+
+/// Converts the pool's [ParkedReason] into its rpc representation.
+fn to_rpc_parked_reason(reason: ParkedReason) -> TxpoolParkedReason {
+    match reason {
+        ParkedReason::NonceGap => TxpoolParkedReason::NonceGap,
+        ParkedReason::ParkedAncestor => TxpoolParkedReason::ParkedAncestor,
+        ParkedReason::InsufficientBalance => TxpoolParkedReason::InsufficientBalance,
+        ParkedReason::ExceedsBlockGasLimit => TxpoolParkedReason::ExceedsBlockGasLimit,
+        ParkedReason::FeeCapBelowBaseFee => TxpoolParkedReason::FeeCapBelowBaseFee,
+        ParkedReason::BlobFeeCapBelowBlobFee => TxpoolParkedReason::BlobFeeCapBelowBlobFee,
+    }
 }
 
 #[async_trait]
@@ -69,7 +116,7 @@ where
     /// Handler for `txpool_status`
     async fn txpool_status(&self) -> Result<TxpoolStatus> {
         trace!(target: "rpc::eth", "Serving txpool_status");
-        let all = self.pool.all_transactions();
+        let all = self.all_transactions();
         Ok(TxpoolStatus {
             pending: U64::from(all.pending.len()),
             queued: U64::from(all.queued.len()),
@@ -107,7 +154,7 @@ where
         }
 
         let mut inspect = TxpoolInspect::default();
-        let AllPoolTransactions { pending, queued } = self.pool.all_transactions();
+        let AllPoolTransactions { pending, queued } = self.all_transactions();
 
         for pending in pending {
             insert(&pending.transaction, &mut inspect.pending);
@@ -129,6 +176,18 @@ where
         Ok(self.content().remove_from(&from))
     }
 
+    /// Same as `txpool_contentFrom`, but queued transactions also include the reasons why they're
+    /// parked.
+    ///
+    /// Handler for `txpool_contentFromWithReasons`
+    async fn txpool_content_from_with_reasons(
+        &self,
+        from: Address,
+    ) -> Result<TxpoolContentFromWithReasons> {
+        trace!(target: "rpc::eth", ?from, "Serving txpool_contentFromWithReasons");
+        Ok(self.content_from_with_reasons(from))
+    }
+
     /// Returns the details of all transactions currently pending for inclusion in the next
     /// block(s), as well as the ones that are being scheduled for future execution only.
     ///
@@ -145,3 +204,43 @@ impl<Pool> std::fmt::Debug for TxPoolApi<Pool> {
         f.debug_struct("TxpoolApi").finish_non_exhaustive()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_transaction_pool::{
+        test_utils::{testing_pool, MockTransaction},
+        TransactionOrigin,
+    };
+
+    #[tokio::test]
+    async fn private_transactions_are_not_revealed() {
+        let pool = testing_pool();
+        let api = TxPoolApi::new(pool.clone());
+
+        let public = MockTransaction::eip1559();
+        let private = MockTransaction::eip1559();
+        let (public_sender, private_sender) = (public.sender(), private.sender());
+        pool.add_transaction(TransactionOrigin::External, public).await.unwrap();
+        pool.add_transaction(TransactionOrigin::Private { max_block_number: None }, private)
+            .await
+            .unwrap();
+        assert_eq!(pool.len(), 2);
+
+        let status = api.txpool_status().await.unwrap();
+        assert_eq!(status.pending + status.queued, U64::from(1));
+
+        let content = api.txpool_content().await.unwrap();
+        let senders = content.pending.keys().chain(content.queued.keys()).collect::<Vec<_>>();
+        assert_eq!(senders, vec![&public_sender]);
+
+        let inspect = api.txpool_inspect().await.unwrap();
+        assert!(!inspect.pending.contains_key(&private_sender));
+        assert!(!inspect.queued.contains_key(&private_sender));
+
+        let content = api.txpool_content_from(private_sender).await.unwrap();
+        assert!(content.pending.is_empty() && content.queued.is_empty());
+        let content = api.txpool_content_from_with_reasons(private_sender).await.unwrap();
+        assert!(content.pending.is_empty() && content.queued.is_empty());
+    }
+}
@@ -1,6 +1,17 @@
-use reth_primitives::{AllGenesisFormats, ChainSpec, GOERLI, MAINNET, SEPOLIA};
+use reth_primitives::{
+    AllGenesisFormats, ChainSpec, DevnetConfig, GOERLI, HOLESKY, MAINNET, SEPOLIA,
+};
 use std::{path::PathBuf, sync::Arc};
 
+/// The prefix of generated devnet chains, see [DevnetConfig].
+const DEVNET_PREFIX: &str = "devnet:";
+
+/// Generates the chain spec of a devnet described as `devnet:<seed>[,<option>=<value>]*`.
+fn devnet_chain_spec(s: &str) -> Result<ChainSpec, eyre::Error> {
+    let config: DevnetConfig = s[DEVNET_PREFIX.len()..].parse()?;
+    Ok(config.chain_spec())
+}
+
 /// Clap value parser for [ChainSpec]s that takes either a built-in chainspec or the path
 /// to a custom one.
 pub fn chain_spec_value_parser(s: &str) -> Result<Arc<ChainSpec>, eyre::Error> {
@@ -8,6 +19,8 @@ pub fn chain_spec_value_parser(s: &str) -> Result<Arc<ChainSpec>, eyre::Error> {
         "mainnet" => MAINNET.clone(),
         "goerli" => GOERLI.clone(),
         "sepolia" => SEPOLIA.clone(),
+        "holesky" => HOLESKY.clone(),
+        _ if s.starts_with(DEVNET_PREFIX) => devnet_chain_spec(s)?,
         _ => {
             let raw = std::fs::read_to_string(PathBuf::from(shellexpand::full(s)?.into_owned()))?;
             serde_json::from_str(&raw)?
@@ -22,6 +35,8 @@ pub fn genesis_value_parser(s: &str) -> Result<Arc<ChainSpec>, eyre::Error> {
         "mainnet" => MAINNET.clone(),
         "goerli" => GOERLI.clone(),
         "sepolia" => SEPOLIA.clone(),
+        "holesky" => HOLESKY.clone(),
+        _ if s.starts_with(DEVNET_PREFIX) => devnet_chain_spec(s)?,
         _ => {
             let raw = std::fs::read_to_string(PathBuf::from(shellexpand::full(s)?.into_owned()))?;
             let genesis: AllGenesisFormats = serde_json::from_str(&raw)?;
@@ -36,9 +51,22 @@ mod tests {
 
     #[test]
     fn parse_chain_spec() {
-        for chain in ["mainnet", "sepolia", "goerli"] {
+        for chain in ["mainnet", "sepolia", "goerli", "holesky", "devnet:ci,accounts=2"] {
             chain_spec_value_parser(chain).unwrap();
             genesis_value_parser(chain).unwrap();
         }
     }
+
+    #[test]
+    fn parse_devnet_chain_spec() {
+        let spec = genesis_value_parser("devnet:ci,accounts=2,contracts=0").unwrap();
+        assert_eq!(spec.genesis().alloc.len(), 2);
+        assert_eq!(
+            spec.genesis_hash(),
+            chain_spec_value_parser("devnet:ci,accounts=2,contracts=0").unwrap().genesis_hash()
+        );
+
+        assert!(genesis_value_parser("devnet:").is_err());
+        assert!(genesis_value_parser("devnet:ci,accounts=many").is_err());
+    }
 }
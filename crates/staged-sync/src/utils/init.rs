@@ -31,6 +31,15 @@ pub enum InitDatabaseError {
         actual: H256,
     },
 
+    /// The genesis block of the chain spec doesn't hash to the chain's known genesis hash.
+    #[error("Invalid genesis block: expected {expected}, computed {computed}")]
+    InvalidGenesisBlock {
+        /// The genesis hash of the chain spec.
+        expected: H256,
+        /// The hash of the genesis block built from the chain spec.
+        computed: H256,
+    },
+
     /// Higher level error encountered when using a Transaction.
     #[error(transparent)]
     TransactionError(#[from] TransactionError),
@@ -51,6 +60,13 @@ pub fn init_genesis<DB: Database>(
     let header = chain.genesis_header();
     let hash = header.hash_slow();
 
+    // never write a genesis state that doesn't belong to the chain, e.g. an incomplete allocation
+    if let Some(expected) = chain.genesis_hash {
+        if expected != hash {
+            return Err(InitDatabaseError::InvalidGenesisBlock { expected, computed: hash })
+        }
+    }
+
     let tx = db.tx()?;
     if let Some((_, db_hash)) = tx.cursor_read::<tables::CanonicalHeaders>()?.first()? {
         if db_hash == hash {
@@ -146,7 +162,8 @@ mod tests {
     use super::{init_genesis, InitDatabaseError};
     use reth_db::mdbx::test_utils::create_test_rw_db;
     use reth_primitives::{
-        GOERLI, GOERLI_GENESIS, MAINNET, MAINNET_GENESIS, SEPOLIA, SEPOLIA_GENESIS,
+        GOERLI, GOERLI_GENESIS, HOLESKY, HOLESKY_GENESIS, MAINNET, MAINNET_GENESIS, SEPOLIA,
+        SEPOLIA_GENESIS,
     };
     use std::sync::Arc;
 
@@ -177,6 +194,31 @@ mod tests {
         assert_eq!(genesis_hash, SEPOLIA_GENESIS);
     }
 
+    #[test]
+    #[ignore = "the Holesky genesis allocation is not vendored yet"]
+    fn success_init_genesis_holesky() {
+        let db = create_test_rw_db();
+        let genesis_hash = init_genesis(db, Arc::new(HOLESKY.clone())).unwrap();
+
+        // actual, expected
+        assert_eq!(genesis_hash, HOLESKY_GENESIS);
+    }
+
+    #[test]
+    fn fail_init_invalid_genesis_block() {
+        let db = create_test_rw_db();
+        let mut chain = MAINNET.clone();
+        chain.genesis_hash = Some(SEPOLIA_GENESIS);
+
+        assert_eq!(
+            init_genesis(db, Arc::new(chain)).unwrap_err(),
+            InitDatabaseError::InvalidGenesisBlock {
+                expected: SEPOLIA_GENESIS,
+                computed: MAINNET_GENESIS
+            }
+        )
+    }
+
     #[test]
     fn fail_init_inconsistent_db() {
         let db = create_test_rw_db();
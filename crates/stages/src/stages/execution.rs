@@ -57,6 +57,10 @@ pub struct ExecutionStageMetrics {
 /// - [tables::AccountHistory] to remove change set and apply old values to
 /// - [tables::PlainAccountState] [tables::StorageHistory] to remove change set and apply old values
 /// to [tables::PlainStorageState]
+///
+/// Blocks are executed one after another, but the executor may execute the transactions within a
+/// block in parallel, e.g. `reth_revm::Factory::with_parallel_execution`. The resulting state
+/// changes are identical either way.
 // false positive, we cannot derive it if !DB: Debug.
 #[allow(missing_debug_implementations)]
 pub struct ExecutionStage<EF: ExecutorFactory> {
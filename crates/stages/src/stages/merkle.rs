@@ -13,7 +13,7 @@ use reth_primitives::{
     BlockNumber, H256,
 };
 use reth_provider::Transaction;
-use reth_trie::{IntermediateStateRootState, StateRoot, StateRootProgress};
+use reth_trie::{IntermediateStateRootState, ParallelStateRoot, StateRoot, StateRootProgress};
 use std::{fmt::Debug, ops::DerefMut};
 use tracing::*;
 
@@ -26,6 +26,13 @@ use tracing::*;
 ///
 /// This stage is split in two: one for calculating hashes and one for unwinding.
 ///
+/// Incremental execution computes the storage roots of changed accounts in parallel, each in its
+/// own read-only transaction, so the hashed state they are based on must already be committed.
+///
+/// Unwinding updates the trie incrementally as well, but returns intermediate progress every
+/// `commit_threshold` trie updates. The progress is saved as a [`MerkleCheckpoint`] so that deep
+/// unwinds are committed in steps and can be resumed if interrupted.
+///
 /// When run in execution, it's going to be executed AFTER the hashing stages, to generate
 /// the state root. When run in unwind mode, it's going to be executed BEFORE the hashing stages,
 /// so that it unwinds the intermediate hashes based on the unwound hashed state from the hashing
@@ -47,12 +54,16 @@ pub enum MerkleStage {
         clean_threshold: u64,
     },
     /// The unwind portion of the merkle stage.
-    Unwind,
+    Unwind {
+        /// The number of trie updates after which the unwind progress is checkpointed and
+        /// committed.
+        commit_threshold: u64,
+    },
 
     /// Able to execute and unwind. Used for tests
     #[cfg(any(test, feature = "test-utils"))]
     #[allow(missing_docs)]
-    Both { clean_threshold: u64 },
+    Both { clean_threshold: u64, commit_threshold: u64 },
 }
 
 impl MerkleStage {
@@ -63,7 +74,7 @@ impl MerkleStage {
 
     /// Stage default for the Unwind variant.
     pub fn default_unwind() -> Self {
-        Self::Unwind
+        Self::Unwind { commit_threshold: 100_000 }
     }
 
     /// Check that the computed state root matches the expected.
@@ -89,9 +100,40 @@ impl MerkleStage {
         &self,
         tx: &Transaction<'_, DB>,
     ) -> Result<Option<MerkleCheckpoint>, StageError> {
-        let buf = tx
-            .get::<tables::SyncStageProgress>(StageId::MerkleExecute.to_string())?
-            .unwrap_or_default();
+        Self::get_checkpoint(tx, StageId::MerkleExecute)
+    }
+
+    /// Saves the hashing progress
+    pub fn save_execution_checkpoint<DB: Database>(
+        &mut self,
+        tx: &Transaction<'_, DB>,
+        checkpoint: Option<MerkleCheckpoint>,
+    ) -> Result<(), StageError> {
+        Self::save_checkpoint(tx, StageId::MerkleExecute, checkpoint)
+    }
+
+    /// Gets the unwind progress
+    pub fn get_unwind_checkpoint<DB: Database>(
+        &self,
+        tx: &Transaction<'_, DB>,
+    ) -> Result<Option<MerkleCheckpoint>, StageError> {
+        Self::get_checkpoint(tx, StageId::MerkleUnwind)
+    }
+
+    /// Saves the unwind progress
+    pub fn save_unwind_checkpoint<DB: Database>(
+        &mut self,
+        tx: &Transaction<'_, DB>,
+        checkpoint: Option<MerkleCheckpoint>,
+    ) -> Result<(), StageError> {
+        Self::save_checkpoint(tx, StageId::MerkleUnwind, checkpoint)
+    }
+
+    fn get_checkpoint<DB: Database>(
+        tx: &Transaction<'_, DB>,
+        id: StageId,
+    ) -> Result<Option<MerkleCheckpoint>, StageError> {
+        let buf = tx.get::<tables::SyncStageProgress>(id.to_string())?.unwrap_or_default();
 
         if buf.is_empty() {
             return Ok(None)
@@ -101,23 +143,24 @@ impl MerkleStage {
         Ok(Some(checkpoint))
     }
 
-    /// Saves the hashing progress
-    pub fn save_execution_checkpoint<DB: Database>(
-        &mut self,
+    fn save_checkpoint<DB: Database>(
         tx: &Transaction<'_, DB>,
+        id: StageId,
         checkpoint: Option<MerkleCheckpoint>,
     ) -> Result<(), StageError> {
         let mut buf = vec![];
         if let Some(checkpoint) = checkpoint {
             debug!(
-                target: "sync::stages::merkle::exec",
+                target: "sync::stages::merkle",
+                stage = %id,
+                target_block = checkpoint.target_block,
                 last_account_key = ?checkpoint.last_account_key,
                 last_walker_key = ?hex::encode(&checkpoint.last_walker_key),
                 "Saving inner merkle checkpoint"
             );
             checkpoint.to_compact(&mut buf);
         }
-        tx.put::<tables::SyncStageProgress>(StageId::MerkleExecute.to_string(), buf)?;
+        tx.put::<tables::SyncStageProgress>(id.to_string(), buf)?;
         Ok(())
     }
 }
@@ -128,7 +171,7 @@ impl<DB: Database> Stage<DB> for MerkleStage {
     fn id(&self) -> StageId {
         match self {
             MerkleStage::Execution { .. } => StageId::MerkleExecute,
-            MerkleStage::Unwind => StageId::MerkleUnwind,
+            MerkleStage::Unwind { .. } => StageId::MerkleUnwind,
             #[cfg(any(test, feature = "test-utils"))]
             MerkleStage::Both { .. } => StageId::Other("MerkleBoth"),
         }
@@ -141,13 +184,13 @@ impl<DB: Database> Stage<DB> for MerkleStage {
         input: ExecInput,
     ) -> Result<ExecOutput, StageError> {
         let threshold = match self {
-            MerkleStage::Unwind => {
+            MerkleStage::Unwind { .. } => {
                 info!(target: "sync::stages::merkle::unwind", "Stage is always skipped");
                 return Ok(ExecOutput { checkpoint: input.previous_stage_checkpoint(), done: true })
             }
             MerkleStage::Execution { clean_threshold } => *clean_threshold,
             #[cfg(any(test, feature = "test-utils"))]
-            MerkleStage::Both { clean_threshold } => *clean_threshold,
+            MerkleStage::Both { clean_threshold, .. } => *clean_threshold,
         };
 
         let range = input.next_block_range();
@@ -210,8 +253,9 @@ impl<DB: Database> Stage<DB> for MerkleStage {
             }
         } else {
             debug!(target: "sync::stages::merkle::exec", current = ?current_block, target = ?to_block, "Updating trie");
-            let (root, updates) = StateRoot::incremental_root_with_updates(tx.deref_mut(), range)
-                .map_err(|e| StageError::Fatal(Box::new(e)))?;
+            let (root, updates) =
+                ParallelStateRoot::incremental_root_with_updates(tx.inner(), range)
+                    .map_err(|e| StageError::Fatal(Box::new(e)))?;
             updates.flush(tx.deref_mut())?;
             root
         };
@@ -231,33 +275,106 @@ impl<DB: Database> Stage<DB> for MerkleStage {
         tx: &mut Transaction<'_, DB>,
         input: UnwindInput,
     ) -> Result<UnwindOutput, StageError> {
-        let range = input.unwind_block_range();
-        if matches!(self, MerkleStage::Execution { .. }) {
-            info!(target: "sync::stages::merkle::unwind", "Stage is always skipped");
-            return Ok(UnwindOutput { checkpoint: StageCheckpoint::new(input.unwind_to) })
-        }
+        let commit_threshold = match self {
+            MerkleStage::Execution { .. } => {
+                info!(target: "sync::stages::merkle::unwind", "Stage is always skipped");
+                return Ok(UnwindOutput { checkpoint: StageCheckpoint::new(input.unwind_to) })
+            }
+            MerkleStage::Unwind { commit_threshold } => *commit_threshold,
+            #[cfg(any(test, feature = "test-utils"))]
+            MerkleStage::Both { commit_threshold, .. } => *commit_threshold,
+        };
 
         if input.unwind_to == 0 {
             tx.clear::<tables::AccountsTrie>()?;
             tx.clear::<tables::StoragesTrie>()?;
+            self.save_unwind_checkpoint(tx, None)?;
             info!(target: "sync::stages::merkle::unwind", stage_progress = input.unwind_to, is_final_range = true, "Unwind iteration finished");
             return Ok(UnwindOutput { checkpoint: StageCheckpoint::new(input.unwind_to) })
         }
 
         // Unwind trie only if there are transitions
-        if !range.is_empty() {
-            let (block_root, updates) =
-                StateRoot::incremental_root_with_updates(tx.deref_mut(), range)
-                    .map_err(|e| StageError::Fatal(Box::new(e)))?;
+        let range = input.unwind_block_range();
+        if range.is_empty() {
+            info!(target: "sync::stages::merkle::unwind", "Nothing to unwind");
+            info!(target: "sync::stages::merkle::unwind", stage_progress = input.unwind_to, is_final_range = true, "Unwind iteration finished");
+            return Ok(UnwindOutput { checkpoint: StageCheckpoint::new(input.unwind_to) })
+        }
+
+        let mut checkpoint = self.get_unwind_checkpoint(tx)?;
+        if let Some(previous_target) =
+            checkpoint.as_ref().map(|c| c.target_block).filter(|target| *target != input.unwind_to)
+        {
+            // The trie was partially unwound towards a different block and can no longer be
+            // updated incrementally. Updating an empty trie is the same as rebuilding it.
+            warn!(
+                target: "sync::stages::merkle::unwind",
+                target = ?input.unwind_to,
+                ?previous_target,
+                "Discarding unwind checkpoint, rebuilding trie"
+            );
+            tx.clear::<tables::AccountsTrie>()?;
+            tx.clear::<tables::StoragesTrie>()?;
+            self.save_unwind_checkpoint(tx, None)?;
+            checkpoint = None;
+        } else if let Some(checkpoint) = checkpoint.as_ref() {
+            debug!(
+                target: "sync::stages::merkle::unwind",
+                target = ?input.unwind_to,
+                last_account_key = ?checkpoint.last_account_key,
+                last_walker_key = ?hex::encode(&checkpoint.last_walker_key),
+                "Continuing inner merkle checkpoint"
+            );
+        }
+        let is_resumed = checkpoint.is_some();
+
+        let progress = StateRoot::incremental_root_calculator(tx.deref_mut(), range)
+            .map_err(|e| StageError::Fatal(Box::new(e)))?
+            .with_threshold(commit_threshold)
+            .with_intermediate_state(checkpoint.map(IntermediateStateRootState::from))
+            .root_with_progress()
+            .map_err(|e| StageError::Fatal(Box::new(e)))?;
+        match progress {
+            StateRootProgress::Progress(state, updates) => {
+                updates.flush(tx.deref_mut())?;
+                let checkpoint = MerkleCheckpoint::new(
+                    input.unwind_to,
+                    state.last_account_key,
+                    state.last_walker_key.hex_data,
+                    state.walker_stack.into_iter().map(StoredSubNode::from).collect(),
+                    state.hash_builder.into(),
+                );
+                self.save_unwind_checkpoint(tx, Some(checkpoint))?;
 
-            // Validate the calulated state root
-            let target_root = tx.get_header(input.unwind_to)?.state_root;
-            self.validate_state_root(block_root, target_root, input.unwind_to)?;
+                info!(target: "sync::stages::merkle::unwind", stage_progress = input.checkpoint.block_number, is_final_range = false, "Unwind iteration finished");
+                return Ok(UnwindOutput { checkpoint: input.checkpoint })
+            }
+            StateRootProgress::Complete(block_root, updates) => {
+                // Validate the calulated state root. If the unwind was resumed, the progress up
+                // to here has already been committed.
+                let target_root = tx.get_header(input.unwind_to)?.state_root;
+                if let Err(err) = self.validate_state_root(block_root, target_root, input.unwind_to)
+                {
+                    if is_resumed {
+                        // The committed intermediate updates can't be reverted, roll back to an
+                        // empty trie instead and reset the execution progress, so the trie is
+                        // rebuilt from scratch. This has to be committed here, the transaction
+                        // is discarded when the unwind fails.
+                        warn!(target: "sync::stages::merkle::unwind", target = ?input.unwind_to, "Trie was partially unwound before validation failed, discarding trie");
+                        tx.clear::<tables::AccountsTrie>()?;
+                        tx.clear::<tables::StoragesTrie>()?;
+                        self.save_unwind_checkpoint(tx, None)?;
+                        self.save_execution_checkpoint(tx, None)?;
+                        tx.save_stage_checkpoint(StageId::MerkleExecute, StageCheckpoint::new(0))?;
+                        tx.commit()?;
+                    }
+                    return Err(err)
+                }
 
-            // Validation passed, apply unwind changes to the database.
-            updates.flush(tx.deref_mut())?;
-        } else {
-            info!(target: "sync::stages::merkle::unwind", "Nothing to unwind");
+                // Validation passed, apply unwind changes to the database.
+                updates.flush(tx.deref_mut())?;
+                self.save_unwind_checkpoint(tx, None)?;
+            }
         }
 
         info!(target: "sync::stages::merkle::unwind", stage_progress = input.unwind_to, is_final_range = true, "Unwind iteration finished");
@@ -273,6 +390,7 @@ mod tests {
         TestTransaction, UnwindStageTestRunner, PREV_STAGE_ID,
     };
     use assert_matches::assert_matches;
+    use rand::Rng;
     use reth_db::{
         cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO},
         tables,
@@ -344,14 +462,157 @@ mod tests {
         assert!(runner.validate_execution(input, result.ok()).is_ok(), "execution validation");
     }
 
+    /// Unwind to random blocks in small checkpointed steps and compare the resulting trie with a
+    /// full rebuild.
+    #[tokio::test]
+    async fn unwind_checkpointed_matches_rebuild() {
+        let (previous_stage, stage_progress) = (50, 0);
+
+        for _ in 0..3 {
+            let mut runner = MerkleTestRunner::default();
+            runner.commit_threshold = 10;
+            let input = ExecInput {
+                previous_stage: Some((PREV_STAGE_ID, StageCheckpoint::new(previous_stage))),
+                checkpoint: Some(StageCheckpoint::new(stage_progress)),
+            };
+            runner.seed_execution(input).expect("failed to seed execution");
+            let result = runner.execute(input).await.unwrap();
+            assert_matches!(result, Ok(ExecOutput { done: true, .. }));
+
+            let unwind_to = rand::thread_rng().gen_range(1..previous_stage);
+            let mut input = UnwindInput {
+                checkpoint: StageCheckpoint::new(previous_stage),
+                unwind_to,
+                bad_block: None,
+            };
+            runner.before_unwind(input).expect("failed to unwind hashed state");
+
+            // The headers are random, set the expected root of the unwind target.
+            let expected_root = runner.state_root().expect("failed to calculate state root");
+            runner
+                .tx
+                .commit(|tx| {
+                    let mut header = tx.get::<tables::Headers>(unwind_to)?.unwrap();
+                    header.state_root = expected_root;
+                    tx.put::<tables::Headers>(unwind_to, header)
+                })
+                .unwrap();
+
+            // Unwind until done, same as the pipeline does.
+            let mut iterations = 0;
+            while input.checkpoint.block_number > unwind_to {
+                input.checkpoint = runner.unwind(input).await.unwrap().checkpoint;
+                iterations += 1;
+            }
+            assert!(iterations > 1, "unwind was not checkpointed");
+            assert_eq!(runner.stage().get_unwind_checkpoint(&runner.tx.inner()).unwrap(), None);
+
+            let unwound = runner.tx.table::<tables::AccountsTrie>().unwrap();
+            runner
+                .tx
+                .commit(|tx| {
+                    tx.clear::<tables::AccountsTrie>()?;
+                    tx.clear::<tables::StoragesTrie>()?;
+                    let (root, updates) = StateRoot::new(tx).root_with_updates().unwrap();
+                    assert_eq!(root, expected_root);
+                    updates.flush(tx)
+                })
+                .unwrap();
+            assert_eq!(unwound, runner.tx.table::<tables::AccountsTrie>().unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn unwind_checkpointed_invalid_root_discards_trie() {
+        let (previous_stage, stage_progress) = (50, 0);
+
+        let mut runner = MerkleTestRunner::default();
+        runner.commit_threshold = 10;
+        let input = ExecInput {
+            previous_stage: Some((PREV_STAGE_ID, StageCheckpoint::new(previous_stage))),
+            checkpoint: Some(StageCheckpoint::new(stage_progress)),
+        };
+        runner.seed_execution(input).expect("failed to seed execution");
+        let result = runner.execute(input).await.unwrap();
+        assert_matches!(result, Ok(ExecOutput { done: true, .. }));
+        runner
+            .tx
+            .commit(|tx| {
+                tx.put::<tables::SyncStage>(
+                    StageId::MerkleExecute.to_string(),
+                    StageCheckpoint::new(previous_stage),
+                )
+            })
+            .unwrap();
+
+        let unwind_to = 1;
+        let mut input = UnwindInput {
+            checkpoint: StageCheckpoint::new(previous_stage),
+            unwind_to,
+            bad_block: None,
+        };
+        runner.before_unwind(input).expect("failed to unwind hashed state");
+
+        // The random header root doesn't match the unwound trie.
+        let result = loop {
+            match runner.unwind(input).await {
+                Ok(output) => input.checkpoint = output.checkpoint,
+                Err(err) => break err,
+            }
+        };
+        assert_matches!(result, StageError::Validation { block, .. } if block == unwind_to);
+
+        // The partially unwound trie is discarded and rebuilt on the next execution.
+        assert!(runner.tx.table_is_empty::<tables::AccountsTrie>().unwrap());
+        assert!(runner.tx.table_is_empty::<tables::StoragesTrie>().unwrap());
+        assert_eq!(runner.stage().get_unwind_checkpoint(&runner.tx.inner()).unwrap(), None);
+        assert_eq!(
+            runner.tx.inner().get_stage_checkpoint(StageId::MerkleExecute).unwrap(),
+            Some(StageCheckpoint::new(0))
+        );
+    }
+
     struct MerkleTestRunner {
         tx: TestTransaction,
         clean_threshold: u64,
+        commit_threshold: u64,
     }
 
     impl Default for MerkleTestRunner {
         fn default() -> Self {
-            Self { tx: TestTransaction::default(), clean_threshold: 10000 }
+            Self {
+                tx: TestTransaction::default(),
+                clean_threshold: 10000,
+                commit_threshold: 100_000,
+            }
+        }
+    }
+
+    impl MerkleTestRunner {
+        /// Calculates the state root of the hashed state from scratch.
+        fn state_root(&self) -> Result<H256, TestRunnerError> {
+            Ok(self.tx.query(|tx| {
+                let mut accounts = BTreeMap::default();
+                let mut accounts_cursor = tx.cursor_read::<tables::HashedAccount>()?;
+                let mut storage_cursor = tx.cursor_dup_read::<tables::HashedStorage>()?;
+                for entry in accounts_cursor.walk_range(..)? {
+                    let (key, account) = entry?;
+                    let mut storage_entries = Vec::new();
+                    let mut entry = storage_cursor.seek_exact(key)?;
+                    while let Some((_, storage)) = entry {
+                        storage_entries.push(storage);
+                        entry = storage_cursor.next_dup()?;
+                    }
+                    let storage = storage_entries
+                        .into_iter()
+                        .filter(|v| v.value != U256::ZERO)
+                        .map(|v| (v.key, v.value))
+                        .collect::<Vec<_>>();
+                    accounts.insert(key, (account, storage));
+                }
+
+                Ok(state_root_prehashed(accounts.into_iter()))
+            })?)
         }
     }
 
@@ -363,7 +624,10 @@ mod tests {
         }
 
         fn stage(&self) -> Self::S {
-            Self::S::Both { clean_threshold: self.clean_threshold }
+            Self::S::Both {
+                clean_threshold: self.clean_threshold,
+                commit_threshold: self.commit_threshold,
+            }
         }
     }
 
@@ -413,28 +677,7 @@ mod tests {
             self.tx.insert_accounts_and_storages(final_state)?;
 
             // Calculate state root
-            let root = self.tx.query(|tx| {
-                let mut accounts = BTreeMap::default();
-                let mut accounts_cursor = tx.cursor_read::<tables::HashedAccount>()?;
-                let mut storage_cursor = tx.cursor_dup_read::<tables::HashedStorage>()?;
-                for entry in accounts_cursor.walk_range(..)? {
-                    let (key, account) = entry?;
-                    let mut storage_entries = Vec::new();
-                    let mut entry = storage_cursor.seek_exact(key)?;
-                    while let Some((_, storage)) = entry {
-                        storage_entries.push(storage);
-                        entry = storage_cursor.next_dup()?;
-                    }
-                    let storage = storage_entries
-                        .into_iter()
-                        .filter(|v| v.value != U256::ZERO)
-                        .map(|v| (v.key, v.value))
-                        .collect::<Vec<_>>();
-                    accounts.insert(key, (account, storage));
-                }
-
-                Ok(state_root_prehashed(accounts.into_iter()))
-            })?;
+            let root = self.state_root()?;
 
             let last_block_number = end;
             self.tx.commit(|tx| {
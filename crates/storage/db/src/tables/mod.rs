@@ -27,6 +27,7 @@ use crate::{
         models::{
             accounts::{AccountBeforeTx, BlockNumberAddress},
             blocks::{HeaderHash, StoredBlockOmmers},
+            clique::StoredCliqueSnapshot,
             storage_sharded_key::StorageShardedKey,
             ShardedKey, StoredBlockBodyIndices, StoredBlockWithdrawals,
         },
@@ -49,7 +50,7 @@ pub enum TableType {
 }
 
 /// Number of tables that should be present inside database.
-pub const NUM_TABLES: usize = 25;
+pub const NUM_TABLES: usize = 26;
 
 /// Default tables that should be present inside database.
 pub const TABLES: [(TableType, &str); NUM_TABLES] = [
@@ -78,6 +79,7 @@ pub const TABLES: [(TableType, &str); NUM_TABLES] = [
     (TableType::Table, TxSenders::const_name()),
     (TableType::Table, SyncStage::const_name()),
     (TableType::Table, SyncStageProgress::const_name()),
+    (TableType::Table, CliqueSnapshots::const_name()),
 ];
 
 #[macro_export]
@@ -309,6 +311,14 @@ table!(
     ( SyncStageProgress ) StageId | Vec<u8>
 );
 
+table!(
+    /// Stores the clique vote snapshots at checkpoint blocks.
+    ///
+    /// Only one snapshot is stored per block number, so the snapshot hash has to be compared to the
+    /// requested block hash.
+    ( CliqueSnapshots ) BlockNumber | StoredCliqueSnapshot
+);
+
 /// Alias Types
 
 /// List with transaction numbers.
@@ -0,0 +1,68 @@
+//! Clique related models and types.
+use reth_codecs::{main_codec, Compact};
+use reth_primitives::{Address, BlockNumber, H256};
+
+/// The storage representation of a clique vote snapshot.
+///
+/// The vote tally is not stored, since it can be recomputed from the pending votes.
+#[main_codec]
+#[derive(Debug, Default, Eq, PartialEq, Clone)]
+pub struct StoredCliqueSnapshot {
+    /// Hash of the block this snapshot was created at.
+    pub hash: H256,
+    /// The authorized signers at this block.
+    pub signers: Vec<Address>,
+    /// The signers of the most recent blocks, used for spam protection.
+    pub recents: Vec<StoredCliqueRecent>,
+    /// The pending votes in chronological order.
+    pub votes: Vec<StoredCliqueVote>,
+}
+
+/// A recently sealed block and its signer.
+#[main_codec]
+#[derive(Debug, Default, Eq, PartialEq, Clone)]
+pub struct StoredCliqueRecent {
+    /// The number of the sealed block.
+    pub block_number: BlockNumber,
+    /// The signer of the block.
+    pub signer: Address,
+}
+
+/// A single vote that an authorized signer made to modify the list of authorizations.
+#[main_codec]
+#[derive(Debug, Default, Eq, PartialEq, Clone)]
+pub struct StoredCliqueVote {
+    /// The authorized signer that cast this vote.
+    pub signer: Address,
+    /// The block number the vote was cast in.
+    pub block_number: BlockNumber,
+    /// The account being voted on.
+    pub address: Address,
+    /// Whether to authorize or deauthorize the voted account.
+    pub authorize: bool,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::table::{Compress, Decompress};
+
+    #[test]
+    fn clique_snapshot_roundtrip() {
+        let snapshot = StoredCliqueSnapshot {
+            hash: H256::random(),
+            signers: vec![Address::random(), Address::random()],
+            recents: vec![StoredCliqueRecent { block_number: 7, signer: Address::random() }],
+            votes: vec![StoredCliqueVote {
+                signer: Address::random(),
+                block_number: 6,
+                address: Address::random(),
+                authorize: true,
+            }],
+        };
+        assert_eq!(
+            snapshot.clone(),
+            StoredCliqueSnapshot::decompress::<Vec<_>>(snapshot.compress()).unwrap()
+        );
+    }
+}
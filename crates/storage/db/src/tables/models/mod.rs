@@ -11,12 +11,14 @@ use reth_primitives::{
 
 pub mod accounts;
 pub mod blocks;
+pub mod clique;
 pub mod integer_list;
 pub mod sharded_key;
 pub mod storage_sharded_key;
 
 pub use accounts::*;
 pub use blocks::*;
+pub use clique::*;
 pub use sharded_key::ShardedKey;
 
 /// Macro that implements [`Encode`] and [`Decode`] for uint types.
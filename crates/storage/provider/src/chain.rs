@@ -3,8 +3,8 @@
 use crate::PostState;
 use reth_interfaces::{executor::BlockExecutionError, Error};
 use reth_primitives::{
-    BlockHash, BlockNumHash, BlockNumber, ForkBlock, Receipt, SealedBlock, SealedBlockWithSenders,
-    TransactionSigned, TxHash,
+    BlockHash, BlockNumHash, BlockNumber, BlockStateDiff, ForkBlock, Receipt, SealedBlock,
+    SealedBlockWithSenders, TransactionSigned, TxHash,
 };
 use std::{borrow::Cow, collections::BTreeMap};
 
@@ -145,6 +145,14 @@ impl Chain {
         receipt_attch
     }
 
+    /// Get the state changes of every block in this chain, see [PostState::state_diff].
+    pub fn state_diffs(&self) -> Vec<BlockStateDiff> {
+        self.blocks()
+            .iter()
+            .map(|(number, block)| self.state.state_diff(BlockNumHash::new(*number, block.hash())))
+            .collect()
+    }
+
     /// Merge two chains by appending the given chain into the current one.
     ///
     /// The state of accounts for this chain is set to the state of the newest chain.
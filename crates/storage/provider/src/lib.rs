@@ -14,8 +14,8 @@ pub use traits::{
     AccountProvider, BlockExecutor, BlockHashProvider, BlockIdProvider, BlockNumProvider,
     BlockProvider, BlockProviderIdExt, BlockSource, BlockchainTreePendingStateProvider,
     CanonChainTracker, CanonStateNotification, CanonStateNotificationSender,
-    CanonStateNotifications, CanonStateSubscriptions, EvmEnvProvider, ExecutorFactory,
-    HeaderProvider, PostStateDataProvider, ReceiptProvider, ReceiptProviderIdExt,
+    CanonStateNotifications, CanonStateSubscriptions, ChainSpecProvider, EvmEnvProvider,
+    ExecutorFactory, HeaderProvider, PostStateDataProvider, ReceiptProvider, ReceiptProviderIdExt,
     StageCheckpointProvider, StateProvider, StateProviderBox, StateProviderFactory,
     StateRootProvider, TransactionsProvider, WithdrawalsProvider,
 };
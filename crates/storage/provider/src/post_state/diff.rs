@@ -0,0 +1,133 @@
+use super::PostState;
+use reth_primitives::{AccountDiff, BlockNumHash, BlockStateDiff, ValueChange, H256};
+
+impl PostState {
+    /// Returns the state changes of the given block.
+    ///
+    /// The values before the block are taken from the changesets of the block. The values after
+    /// the block are taken from the changesets of the next block that changed them again, or from
+    /// the latest state if they were not changed again.
+    ///
+    /// Storage slots that were changed and restored within the block are omitted.
+    pub fn state_diff(&self, block: BlockNumHash) -> BlockStateDiff {
+        let mut diff =
+            BlockStateDiff { number: block.number, hash: block.hash, ..Default::default() };
+
+        for (address, pre) in self.account_changes.get(&block.number).into_iter().flatten() {
+            let post = self
+                .account_changes
+                .range(block.number + 1..)
+                .find_map(|(_, changes)| changes.get(address))
+                .copied()
+                .unwrap_or_else(|| self.accounts.get(address).copied().flatten());
+
+            let pre_code = pre.and_then(|account| account.bytecode_hash);
+            if let Some(code_hash) = post.and_then(|account| account.bytecode_hash) {
+                if Some(code_hash) != pre_code {
+                    if let Some(bytecode) = self.bytecode.get(&code_hash) {
+                        diff.codes.insert(code_hash, bytecode.original_bytes().into());
+                    }
+                }
+            }
+
+            diff.accounts.entry(*address).or_default().info = Some(ValueChange::new(*pre, post));
+        }
+
+        for (address, transition) in self.storage_changes.get(&block.number).into_iter().flatten() {
+            let mut account =
+                AccountDiff { storage_wiped: transition.wipe.is_wiped(), ..Default::default() };
+
+            for (slot, pre) in &transition.storage {
+                let post = self
+                    .storage_changes
+                    .range(block.number + 1..)
+                    .find_map(|(_, changes)| {
+                        changes.get(address).and_then(|transition| transition.storage.get(slot))
+                    })
+                    .or_else(|| {
+                        self.storage.get(address).and_then(|storage| storage.storage.get(slot))
+                    })
+                    .copied()
+                    .unwrap_or_default();
+                if *pre != post {
+                    account.storage.insert(H256(slot.to_be_bytes()), ValueChange::new(*pre, post));
+                }
+            }
+
+            if account.storage_wiped || !account.storage.is_empty() {
+                let entry = diff.accounts.entry(*address).or_default();
+                entry.storage_wiped = account.storage_wiped;
+                entry.storage = account.storage;
+            }
+        }
+
+        diff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::post_state::StorageChangeset;
+    use reth_primitives::{Account, Address, Bytecode, Bytes, U256};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn state_diff_of_each_block() {
+        let address = Address::random();
+        let code = Bytes::from(vec![0x60, 0x00]);
+        let code_hash = reth_primitives::keccak256(&code);
+        let first = Account { nonce: 1, ..Default::default() };
+        let second = Account { nonce: 2, bytecode_hash: Some(code_hash), ..Default::default() };
+        let third = Account { nonce: 3, bytecode_hash: Some(code_hash), ..Default::default() };
+
+        let mut state = PostState::new();
+        state.create_account(1, address, first);
+        state.change_storage(
+            1,
+            address,
+            StorageChangeset::from([(U256::from(1), (U256::ZERO, U256::from(10)))]),
+        );
+        state.change_account(2, address, first, second);
+        state.add_bytecode(code_hash, Bytecode::new_raw(code.clone().into()));
+        state.change_storage(
+            2,
+            address,
+            StorageChangeset::from([
+                (U256::from(1), (U256::from(10), U256::from(20))),
+                (U256::from(2), (U256::ZERO, U256::ZERO)),
+            ]),
+        );
+        state.change_account(3, address, second, third);
+
+        let diff = state.state_diff(BlockNumHash::new(1, H256::zero()));
+        let account = &diff.accounts[&address];
+        assert_eq!(account.info, Some(ValueChange::new(None, Some(first))));
+        assert_eq!(
+            account.storage,
+            BTreeMap::from([(
+                H256::from_low_u64_be(1),
+                ValueChange::new(U256::ZERO, U256::from(10))
+            )])
+        );
+        assert!(diff.codes.is_empty());
+
+        let diff = state.state_diff(BlockNumHash::new(2, H256::zero()));
+        let account = &diff.accounts[&address];
+        assert_eq!(account.info, Some(ValueChange::new(Some(first), Some(second))));
+        assert_eq!(
+            account.storage,
+            BTreeMap::from([(
+                H256::from_low_u64_be(1),
+                ValueChange::new(U256::from(10), U256::from(20))
+            )])
+        );
+        assert_eq!(diff.codes, BTreeMap::from([(code_hash, code)]));
+
+        let diff = state.state_diff(BlockNumHash::new(3, H256::zero()));
+        let account = &diff.accounts[&address];
+        assert_eq!(account.info, Some(ValueChange::new(Some(second), Some(third))));
+        assert!(account.storage.is_empty());
+        assert!(diff.codes.is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 //! Output of execution.
 use reth_db::{
     cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW},
+    database::Database,
     models::{AccountBeforeTx, BlockNumberAddress},
     tables,
     transaction::{DbTx, DbTxMut},
@@ -12,13 +13,15 @@ use reth_primitives::{
 };
 use reth_trie::{
     hashed_cursor::{HashedPostState, HashedPostStateCursorFactory, HashedStorage},
-    StateRoot, StateRootError,
+    ParallelStateRoot, StateRoot, StateRootError,
 };
 use std::collections::{BTreeMap, BTreeSet};
 
 mod account;
 pub use account::AccountChanges;
 
+mod diff;
+
 mod storage;
 pub use storage::{Storage, StorageChanges, StorageChangeset, StorageTransition, StorageWipe};
 
@@ -258,6 +261,21 @@ impl PostState {
             .root()
     }
 
+    /// Calculate the state root for this [PostState] on top of the committed state of the given
+    /// database.
+    ///
+    /// Same as [Self::state_root_slow], except that the storage roots of all accounts with changed
+    /// storage are computed in parallel, see [ParallelStateRoot].
+    pub fn state_root_parallel<DB: Database>(&self, db: &DB) -> Result<H256, StateRootError> {
+        let hashed_post_state = self.hash_state_slow();
+        let (account_prefix_set, storage_prefix_set) = hashed_post_state.construct_prefix_sets();
+        ParallelStateRoot::new(db)
+            .with_hashed_state(hashed_post_state)
+            .with_changed_account_prefixes(account_prefix_set)
+            .with_changed_storage_prefixes(storage_prefix_set)
+            .root()
+    }
+
     // todo: note overwrite behavior, i.e. changes in `other` take precedent
     /// Extend this [PostState] with the changes in another [PostState].
     pub fn extend(&mut self, mut other: PostState) {
@@ -1,9 +1,9 @@
 use crate::{
     providers::state::{historical::HistoricalStateProvider, latest::LatestStateProvider},
     traits::{BlockSource, ReceiptProvider},
-    BlockHashProvider, BlockNumProvider, BlockProvider, EvmEnvProvider, HeaderProvider,
-    ProviderError, StageCheckpointProvider, StateProviderBox, TransactionsProvider,
-    WithdrawalsProvider,
+    BlockHashProvider, BlockNumProvider, BlockProvider, ChainSpecProvider, EvmEnvProvider,
+    HeaderProvider, PostState, ProviderError, StageCheckpointProvider, StateProviderBox,
+    TransactionsProvider, WithdrawalsProvider,
 };
 use reth_db::{cursor::DbCursorRO, database::Database, tables, transaction::DbTx};
 use reth_interfaces::Result;
@@ -70,6 +70,27 @@ impl<DB: Database> ShareableDatabase<DB> {
         Ok(Box::new(HistoricalStateProvider::new(tx, block_number)))
     }
 
+    /// Calculates the state root of the given [PostState] on top of the state at the given block,
+    /// computing storage roots in parallel.
+    ///
+    /// This is only possible if the block is the latest block, otherwise
+    /// [ProviderError::StateRootNotAvailableForHistoricalBlock] is returned.
+    pub fn state_root_parallel(
+        &self,
+        block_number: BlockNumber,
+        post_state: &PostState,
+    ) -> Result<H256> {
+        let tx = self.db.tx()?;
+        if !is_latest_block_number(&tx, block_number)? {
+            return Err(ProviderError::StateRootNotAvailableForHistoricalBlock.into())
+        }
+        drop(tx);
+
+        post_state
+            .state_root_parallel(&self.db)
+            .map_err(|err| reth_interfaces::Error::Database(err.into()))
+    }
+
     /// Storage provider for state at that given block hash
     pub fn history_by_block_hash(&self, block_hash: BlockHash) -> Result<StateProviderBox<'_>> {
         let tx = self.db.tx()?;
@@ -463,6 +484,12 @@ impl<DB: Database> StageCheckpointProvider for ShareableDatabase<DB> {
     }
 }
 
+impl<DB: Send + Sync> ChainSpecProvider for ShareableDatabase<DB> {
+    fn chain_spec(&self) -> Arc<ChainSpec> {
+        self.chain_spec.clone()
+    }
+}
+
 impl<DB: Database> EvmEnvProvider for ShareableDatabase<DB> {
     fn fill_env_at(
         &self,
@@ -4,6 +4,7 @@ use crate::{
 };
 use reth_interfaces::{provider::ProviderError, Result};
 use reth_primitives::{Account, Address, BlockNumber, Bytecode, Bytes, H256, U256};
+use std::collections::{BTreeMap, BTreeSet};
 
 /// A state provider that either resolves to data in a wrapped [`crate::PostState`], or an
 /// underlying state provider.
@@ -96,4 +97,8 @@ impl<SP: StateProvider, PSDP: PostStateDataProvider> StateProvider for PostState
     ) -> Result<(Vec<Bytes>, H256, Vec<Vec<Bytes>>)> {
         Err(ProviderError::StateRootNotAvailableForHistoricalBlock.into())
     }
+
+    fn multiproof(&self, _targets: &BTreeMap<Address, BTreeSet<H256>>) -> Result<Vec<Bytes>> {
+        Err(ProviderError::StateRootNotAvailableForHistoricalBlock.into())
+    }
 }
@@ -4,15 +4,25 @@ use crate::{
 };
 use reth_db::{
     cursor::{DbCursorRO, DbDupCursorRO},
-    models::{storage_sharded_key::StorageShardedKey, ShardedKey},
+    models::{
+        storage_sharded_key::StorageShardedKey, AccountBeforeTx, BlockNumberAddress, ShardedKey,
+    },
     tables,
     transaction::DbTx,
 };
 use reth_interfaces::Result;
 use reth_primitives::{
-    Account, Address, BlockNumber, Bytecode, Bytes, StorageKey, StorageValue, H256,
+    keccak256, Account, Address, BlockNumber, Bytecode, Bytes, StorageEntry, StorageKey,
+    StorageValue, H256,
+};
+use reth_trie::{
+    hashed_cursor::{HashedPostState, HashedPostStateCursorFactory, HashedStorage},
+    Proof,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    marker::PhantomData,
 };
-use std::marker::PhantomData;
 
 /// State provider for a given transition id which takes a tx reference.
 ///
@@ -36,7 +46,36 @@ impl<'a, 'b, TX: DbTx<'a>> HistoricalStateProviderRef<'a, 'b, TX> {
     pub fn new(tx: &'b TX, block_number: BlockNumber) -> Self {
         Self { tx, block_number, _phantom: PhantomData {} }
     }
+
+    /// Collects the changes that revert the latest state to the state at the block number.
+    ///
+    /// The changesets store the value before the change, so the first entry of every account and
+    /// storage slot at or after the block number holds its value at that block.
+    fn revert_state(&self) -> Result<HashedPostState> {
+        let mut accounts = BTreeMap::default();
+        for entry in
+            self.tx.cursor_read::<tables::AccountChangeSet>()?.walk(Some(self.block_number))?
+        {
+            let (_, AccountBeforeTx { address, info }) = entry?;
+            accounts.entry(keccak256(address)).or_insert(info);
+        }
+
+        let mut storages = BTreeMap::<H256, HashedStorage>::default();
+        let start = BlockNumberAddress((self.block_number, Address::zero()));
+        for entry in self.tx.cursor_read::<tables::StorageChangeSet>()?.walk(Some(start))? {
+            let (key, StorageEntry { key: slot, value }) = entry?;
+            storages
+                .entry(keccak256(key.address()))
+                .or_default()
+                .storage
+                .entry(keccak256(slot))
+                .or_insert(value);
+        }
+
+        Ok(HashedPostState { accounts, storages })
+    }
 }
+
 impl<'a, 'b, TX: DbTx<'a>> AccountProvider for HistoricalStateProviderRef<'a, 'b, TX> {
     /// Get basic account information.
     fn basic_account(&self, address: Address) -> Result<Option<Account>> {
@@ -151,6 +190,20 @@ impl<'a, 'b, TX: DbTx<'a>> StateProvider for HistoricalStateProviderRef<'a, 'b,
     ) -> Result<(Vec<Bytes>, H256, Vec<Vec<Bytes>>)> {
         Err(ProviderError::StateRootNotAvailableForHistoricalBlock.into())
     }
+
+    fn multiproof(&self, targets: &BTreeMap<Address, BTreeSet<H256>>) -> Result<Vec<Bytes>> {
+        let revert_state = self.revert_state()?;
+        let (account_prefix_set, storage_prefix_set) = revert_state.construct_prefix_sets();
+        let hashed_cursor_factory = HashedPostStateCursorFactory::new(self.tx, &revert_state);
+        let multiproof = Proof::new(self.tx)
+            .with_hashed_cursor_factory(&hashed_cursor_factory)
+            .with_changed_account_prefixes(account_prefix_set)
+            .with_changed_storage_prefixes(storage_prefix_set)
+            .with_siblings(true)
+            .multiproof(targets)
+            .map_err(|err| reth_interfaces::Error::Database(err.into()))?;
+        Ok(multiproof.nodes().cloned().collect())
+    }
 }
 
 /// State provider for a given transition
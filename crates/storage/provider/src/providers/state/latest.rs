@@ -7,11 +7,15 @@ use reth_db::{
     tables,
     transaction::DbTx,
 };
-use reth_interfaces::{provider::ProviderError, Result};
+use reth_interfaces::Result;
 use reth_primitives::{
-    keccak256, Account, Address, BlockNumber, Bytecode, Bytes, StorageKey, StorageValue, H256,
+    Account, Address, BlockNumber, Bytecode, Bytes, StorageKey, StorageValue, H256,
+};
+use reth_trie::{AccountProof, Proof};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    marker::PhantomData,
 };
-use std::marker::PhantomData;
 
 /// State provider over latest state that takes tx reference.
 pub struct LatestStateProviderRef<'a, 'b, TX: DbTx<'a>> {
@@ -83,18 +87,20 @@ impl<'a, 'b, TX: DbTx<'a>> StateProvider for LatestStateProviderRef<'a, 'b, TX>
     fn proof(
         &self,
         address: Address,
-        _keys: &[H256],
+        keys: &[H256],
     ) -> Result<(Vec<Bytes>, H256, Vec<Vec<Bytes>>)> {
-        let _hashed_address = keccak256(address);
-        let _root = self
-            .db
-            .cursor_read::<tables::Headers>()?
-            .last()?
-            .ok_or_else(|| ProviderError::HeaderNotFound(0.into()))?
-            .1
-            .state_root;
-
-        unimplemented!()
+        let AccountProof { proof, storage_root, storage_proofs } = Proof::new(self.db)
+            .account_proof(address, keys)
+            .map_err(|err| reth_interfaces::Error::Database(err.into()))?;
+        Ok((proof, storage_root, storage_proofs))
+    }
+
+    fn multiproof(&self, targets: &BTreeMap<Address, BTreeSet<H256>>) -> Result<Vec<Bytes>> {
+        let multiproof = Proof::new(self.db)
+            .with_siblings(true)
+            .multiproof(targets)
+            .map_err(|err| reth_interfaces::Error::Database(err.into()))?;
+        Ok(multiproof.nodes().cloned().collect())
     }
 }
 
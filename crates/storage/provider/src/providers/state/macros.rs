@@ -44,6 +44,7 @@ macro_rules! delegate_provider_impls {
                 fn storage(&self, account: reth_primitives::Address, storage_key: reth_primitives::StorageKey) -> reth_interfaces::Result<Option<reth_primitives::StorageValue>>;
                 fn proof(&self, address: reth_primitives::Address, keys: &[reth_primitives::H256]) -> reth_interfaces::Result<(Vec<reth_primitives::Bytes>, reth_primitives::H256, Vec<Vec<reth_primitives::Bytes>>)>;
                 fn bytecode_by_hash(&self, code_hash: reth_primitives::H256) -> reth_interfaces::Result<Option<reth_primitives::Bytecode>>;
+                fn multiproof(&self, targets: &std::collections::BTreeMap<reth_primitives::Address, std::collections::BTreeSet<reth_primitives::H256>>) -> reth_interfaces::Result<Vec<reth_primitives::Bytes>>;
             }
         );
     }
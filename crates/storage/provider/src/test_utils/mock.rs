@@ -1,19 +1,20 @@
 use crate::{
     traits::{BlockSource, ReceiptProvider},
     AccountProvider, BlockHashProvider, BlockIdProvider, BlockNumProvider, BlockProvider,
-    BlockProviderIdExt, EvmEnvProvider, HeaderProvider, PostState, PostStateDataProvider,
-    StateProvider, StateProviderBox, StateProviderFactory, StateRootProvider, TransactionsProvider,
+    BlockProviderIdExt, ChainSpecProvider, EvmEnvProvider, HeaderProvider, PostState,
+    PostStateDataProvider, StateProvider, StateProviderBox, StateProviderFactory,
+    StateRootProvider, TransactionsProvider,
 };
 use parking_lot::Mutex;
 use reth_interfaces::{provider::ProviderError, Result};
 use reth_primitives::{
     keccak256, Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId, BlockNumber,
-    Bytecode, Bytes, ChainInfo, Header, Receipt, SealedBlock, SealedHeader, StorageKey,
-    StorageValue, TransactionMeta, TransactionSigned, TxHash, TxNumber, H256, U256,
+    Bytecode, Bytes, ChainInfo, ChainSpec, Header, Receipt, SealedBlock, SealedHeader, StorageKey,
+    StorageValue, TransactionMeta, TransactionSigned, TxHash, TxNumber, H256, MAINNET, U256,
 };
 use reth_revm_primitives::primitives::{BlockEnv, CfgEnv};
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
     ops::RangeBounds,
     sync::Arc,
 };
@@ -370,6 +371,16 @@ impl StateProvider for MockEthProvider {
     ) -> Result<(Vec<Bytes>, H256, Vec<Vec<Bytes>>)> {
         todo!()
     }
+
+    fn multiproof(&self, _targets: &BTreeMap<Address, BTreeSet<H256>>) -> Result<Vec<Bytes>> {
+        Ok(vec![])
+    }
+}
+
+impl ChainSpecProvider for MockEthProvider {
+    fn chain_spec(&self) -> Arc<ChainSpec> {
+        MAINNET.clone()
+    }
 }
 
 impl EvmEnvProvider for MockEthProvider {
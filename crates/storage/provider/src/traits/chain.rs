@@ -1,7 +1,7 @@
 //! Canonical chain state notification trait and types.
 use crate::{chain::BlockReceipts, Chain};
 use auto_impl::auto_impl;
-use reth_primitives::SealedBlockWithSenders;
+use reth_primitives::{BlockStateDiff, SealedBlockWithSenders};
 use std::{
     pin::Pin,
     sync::Arc,
@@ -143,4 +143,21 @@ impl CanonStateNotification {
         }
         receipts
     }
+
+    /// Return the state changes of every block, see [Chain::state_diffs].
+    ///
+    /// Last boolean is true if the state changes are from a reverted block.
+    pub fn state_diffs(&self) -> Vec<(BlockStateDiff, bool)> {
+        let mut diffs = Vec::new();
+
+        // get old state changes
+        if let Some(old) = self.reverted() {
+            diffs.extend(old.state_diffs().into_iter().map(|diff| (diff, true)));
+        }
+        // get new state changes
+        if let Some(new) = self.committed() {
+            diffs.extend(new.state_diffs().into_iter().map(|diff| (diff, false)));
+        }
+        diffs
+    }
 }
@@ -15,6 +15,9 @@ pub use block_id::{BlockIdProvider, BlockNumProvider};
 mod evm_env;
 pub use evm_env::EvmEnvProvider;
 
+mod chain_spec;
+pub use chain_spec::ChainSpecProvider;
+
 mod chain_info;
 pub use chain_info::CanonChainTracker;
 
@@ -6,6 +6,7 @@ use reth_primitives::{
     Address, BlockHash, BlockId, BlockNumHash, BlockNumber, BlockNumberOrTag, Bytecode, Bytes,
     StorageKey, StorageValue, H256, KECCAK_EMPTY, U256,
 };
+use std::collections::{BTreeMap, BTreeSet};
 
 /// Type alias of boxed [StateProvider].
 pub type StateProviderBox<'a> = Box<dyn StateProvider + 'a>;
@@ -25,6 +26,12 @@ pub trait StateProvider:
     fn proof(&self, address: Address, keys: &[H256])
         -> Result<(Vec<Bytes>, H256, Vec<Vec<Bytes>>)>;
 
+    /// Get the account and storage trie nodes proving the given accounts and storage slots.
+    ///
+    /// The returned nodes also contain the siblings of all nodes on the proof paths, so the tries
+    /// can be updated with changes to the targets without access to the database.
+    fn multiproof(&self, targets: &BTreeMap<Address, BTreeSet<H256>>) -> Result<Vec<Bytes>>;
+
     /// Get account code by its address.
     ///
     /// Returns `None` if the account doesn't exist or account is not a contract
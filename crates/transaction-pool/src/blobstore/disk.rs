@@ -0,0 +1,202 @@
+use crate::blobstore::{BlobAndProof, BlobStore, BlobStoreError, BlobStoreIndex};
+use parking_lot::RwLock;
+use reth_primitives::{hex, BlobTransactionSidecar, TxHash, H256};
+use reth_rlp::{Decodable, Encodable};
+use std::{fs, io, path::PathBuf, sync::Arc};
+use tracing::debug;
+
+/// A [BlobStore] that stores every sidecar in a separate file in the given directory.
+///
+/// The files are named after the hash of the transaction, the index of the stored sidecars is kept
+/// in memory.
+///
+/// The pool itself is not persisted, so the directory is cleared when the store is opened.
+#[derive(Clone, Debug)]
+pub struct DiskFileBlobStore {
+    inner: Arc<DiskFileBlobStoreInner>,
+}
+
+#[derive(Debug)]
+struct DiskFileBlobStoreInner {
+    /// The directory that contains the sidecar files.
+    blob_dir: PathBuf,
+    /// Tracks the versioned hashes and sizes of the stored sidecars.
+    index: RwLock<BlobStoreIndex>,
+}
+
+// === impl DiskFileBlobStore ===
+
+impl DiskFileBlobStore {
+    /// Opens the store in the given directory and removes all sidecars of a previous run.
+    pub fn open(blob_dir: impl Into<PathBuf>) -> Result<Self, BlobStoreError> {
+        let blob_dir = blob_dir.into();
+        match fs::remove_dir_all(&blob_dir) {
+            Ok(()) => {
+                debug!(target: "txpool::blob", ?blob_dir, "removed stale blob sidecars");
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err.into()),
+        }
+        fs::create_dir_all(&blob_dir)?;
+
+        Ok(Self { inner: Arc::new(DiskFileBlobStoreInner { blob_dir, index: Default::default() }) })
+    }
+
+    /// Returns the path of the file that stores the sidecar of the transaction.
+    fn blob_disk_file(&self, tx: TxHash) -> PathBuf {
+        self.inner.blob_dir.join(hex::encode(tx))
+    }
+
+    /// Reads and decodes the sidecar of the transaction from disk.
+    fn read_one(&self, tx: TxHash) -> Result<BlobTransactionSidecar, BlobStoreError> {
+        let data = match fs::read(self.blob_disk_file(tx)) {
+            Ok(data) => data,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Err(BlobStoreError::MissingSidecar(tx))
+            }
+            Err(err) => return Err(err.into()),
+        };
+        Ok(BlobTransactionSidecar::decode(&mut data.as_slice())?)
+    }
+
+    /// Removes the sidecar file of the transaction, if it's tracked.
+    ///
+    /// Caution: this expects that the index is locked by the caller.
+    fn remove_one(&self, index: &mut BlobStoreIndex, tx: TxHash) -> Result<(), BlobStoreError> {
+        if !index.remove(&tx) {
+            return Ok(())
+        }
+        match fs::remove_file(self.blob_disk_file(tx)) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+impl BlobStore for DiskFileBlobStore {
+    fn insert(&self, tx: TxHash, data: BlobTransactionSidecar) -> Result<(), BlobStoreError> {
+        let mut index = self.inner.index.write();
+        if index.contains(&tx) {
+            return Ok(())
+        }
+
+        let mut buf = Vec::with_capacity(data.length());
+        data.encode(&mut buf);
+        fs::write(self.blob_disk_file(tx), buf)?;
+
+        index.insert(tx, &data);
+        Ok(())
+    }
+
+    fn delete(&self, tx: TxHash) -> Result<(), BlobStoreError> {
+        let mut index = self.inner.index.write();
+        self.remove_one(&mut index, tx)
+    }
+
+    fn delete_all(&self, txs: Vec<TxHash>) -> Result<(), BlobStoreError> {
+        let mut index = self.inner.index.write();
+        for tx in txs {
+            self.remove_one(&mut index, tx)?;
+        }
+        Ok(())
+    }
+
+    fn get(&self, tx: TxHash) -> Result<Option<BlobTransactionSidecar>, BlobStoreError> {
+        let index = self.inner.index.read();
+        if !index.contains(&tx) {
+            return Ok(None)
+        }
+        self.read_one(tx).map(Some)
+    }
+
+    fn get_all(
+        &self,
+        txs: Vec<TxHash>,
+    ) -> Result<Vec<(TxHash, BlobTransactionSidecar)>, BlobStoreError> {
+        let index = self.inner.index.read();
+        txs.into_iter()
+            .filter(|tx| index.contains(tx))
+            .map(|tx| Ok((tx, self.read_one(tx)?)))
+            .collect()
+    }
+
+    fn get_by_versioned_hash(
+        &self,
+        versioned_hash: H256,
+    ) -> Result<Option<BlobAndProof>, BlobStoreError> {
+        let index = self.inner.index.read();
+        let Some((tx, blob_index)) = index.get_by_versioned_hash(&versioned_hash) else {
+            return Ok(None)
+        };
+        let sidecar = self.read_one(tx)?;
+        Ok(BlobAndProof::from_sidecar(&sidecar, blob_index))
+    }
+
+    fn data_size_hint(&self) -> usize {
+        self.inner.index.read().data_size
+    }
+
+    fn blobs_len(&self) -> usize {
+        self.inner.index.read().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{
+        constants::{BYTES_PER_BLOB, BYTES_PER_COMMITMENT, BYTES_PER_PROOF},
+        Bytes,
+    };
+
+    fn sidecar(seed: u8) -> BlobTransactionSidecar {
+        BlobTransactionSidecar {
+            blobs: vec![Bytes::from(vec![seed; BYTES_PER_BLOB]); 2],
+            commitments: vec![
+                Bytes::from(vec![seed; BYTES_PER_COMMITMENT]),
+                Bytes::from(vec![seed.wrapping_add(1); BYTES_PER_COMMITMENT]),
+            ],
+            proofs: vec![Bytes::from(vec![seed; BYTES_PER_PROOF]); 2],
+        }
+    }
+
+    #[test]
+    fn disk_insert_get_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = DiskFileBlobStore::open(dir.path().join("blobstore")).unwrap();
+
+        let tx = H256::random();
+        let data = sidecar(1);
+        store.insert(tx, data.clone()).unwrap();
+        assert_eq!(store.blobs_len(), 1);
+        assert_eq!(store.data_size_hint(), data.size());
+        assert_eq!(store.get(tx).unwrap(), Some(data.clone()));
+        assert_eq!(store.get(H256::random()).unwrap(), None);
+
+        let versioned_hash = data.versioned_hashes().nth(1).unwrap();
+        let blob = store.get_by_versioned_hash(versioned_hash).unwrap().unwrap();
+        assert_eq!(blob.commitment, data.commitments[1]);
+
+        store.delete(tx).unwrap();
+        assert_eq!(store.blobs_len(), 0);
+        assert_eq!(store.get(tx).unwrap(), None);
+        assert!(!store.blob_disk_file(tx).exists());
+    }
+
+    #[test]
+    fn disk_open_clears_stale_sidecars() {
+        let dir = tempfile::tempdir().unwrap();
+        let blob_dir = dir.path().join("blobstore");
+
+        let tx = H256::random();
+        let store = DiskFileBlobStore::open(&blob_dir).unwrap();
+        store.insert(tx, sidecar(2)).unwrap();
+        assert!(store.blob_disk_file(tx).exists());
+        drop(store);
+
+        let store = DiskFileBlobStore::open(&blob_dir).unwrap();
+        assert_eq!(store.blobs_len(), 0);
+        assert!(!store.blob_disk_file(tx).exists());
+    }
+}
@@ -0,0 +1,123 @@
+use crate::blobstore::{BlobAndProof, BlobStore, BlobStoreError, BlobStoreIndex};
+use parking_lot::RwLock;
+use reth_primitives::{BlobTransactionSidecar, TxHash, H256};
+use std::{collections::HashMap, sync::Arc};
+
+/// An in-memory [BlobStore].
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryBlobStore {
+    inner: Arc<RwLock<InMemoryBlobStoreInner>>,
+}
+
+#[derive(Debug, Default)]
+struct InMemoryBlobStoreInner {
+    /// Storage for all blob data.
+    store: HashMap<TxHash, BlobTransactionSidecar>,
+    /// Tracks the versioned hashes and sizes of the stored sidecars.
+    index: BlobStoreIndex,
+}
+
+impl BlobStore for InMemoryBlobStore {
+    fn insert(&self, tx: TxHash, data: BlobTransactionSidecar) -> Result<(), BlobStoreError> {
+        let mut inner = self.inner.write();
+        if inner.index.insert(tx, &data) {
+            inner.store.insert(tx, data);
+        }
+        Ok(())
+    }
+
+    fn delete(&self, tx: TxHash) -> Result<(), BlobStoreError> {
+        let mut inner = self.inner.write();
+        if inner.index.remove(&tx) {
+            inner.store.remove(&tx);
+        }
+        Ok(())
+    }
+
+    fn delete_all(&self, txs: Vec<TxHash>) -> Result<(), BlobStoreError> {
+        let mut inner = self.inner.write();
+        for tx in txs {
+            if inner.index.remove(&tx) {
+                inner.store.remove(&tx);
+            }
+        }
+        Ok(())
+    }
+
+    fn get(&self, tx: TxHash) -> Result<Option<BlobTransactionSidecar>, BlobStoreError> {
+        Ok(self.inner.read().store.get(&tx).cloned())
+    }
+
+    fn get_all(
+        &self,
+        txs: Vec<TxHash>,
+    ) -> Result<Vec<(TxHash, BlobTransactionSidecar)>, BlobStoreError> {
+        let inner = self.inner.read();
+        Ok(txs
+            .into_iter()
+            .filter_map(|tx| inner.store.get(&tx).map(|sidecar| (tx, sidecar.clone())))
+            .collect())
+    }
+
+    fn get_by_versioned_hash(
+        &self,
+        versioned_hash: H256,
+    ) -> Result<Option<BlobAndProof>, BlobStoreError> {
+        let inner = self.inner.read();
+        let Some((tx, index)) = inner.index.get_by_versioned_hash(&versioned_hash) else {
+            return Ok(None)
+        };
+        let sidecar = inner.store.get(&tx).ok_or(BlobStoreError::MissingSidecar(tx))?;
+        Ok(BlobAndProof::from_sidecar(sidecar, index))
+    }
+
+    fn data_size_hint(&self) -> usize {
+        self.inner.read().index.data_size
+    }
+
+    fn blobs_len(&self) -> usize {
+        self.inner.read().index.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{
+        constants::{BYTES_PER_BLOB, BYTES_PER_COMMITMENT, BYTES_PER_PROOF},
+        Bytes,
+    };
+
+    fn sidecar(seed: u8) -> BlobTransactionSidecar {
+        BlobTransactionSidecar {
+            blobs: vec![Bytes::from(vec![seed; BYTES_PER_BLOB])],
+            commitments: vec![Bytes::from(vec![seed; BYTES_PER_COMMITMENT])],
+            proofs: vec![Bytes::from(vec![seed; BYTES_PER_PROOF])],
+        }
+    }
+
+    #[test]
+    fn insert_get_delete() {
+        let store = InMemoryBlobStore::default();
+        let tx = H256::random();
+        let data = sidecar(1);
+        let versioned_hash = data.versioned_hashes().next().unwrap();
+
+        store.insert(tx, data.clone()).unwrap();
+        assert_eq!(store.blobs_len(), 1);
+        assert_eq!(store.data_size_hint(), data.size());
+        assert_eq!(store.get(tx).unwrap(), Some(data.clone()));
+        assert_eq!(store.get_all(vec![tx, H256::random()]).unwrap(), vec![(tx, data.clone())]);
+
+        let blob = store.get_by_versioned_hash(versioned_hash).unwrap().unwrap();
+        assert_eq!(blob.blob, data.blobs[0]);
+        assert_eq!(blob.commitment, data.commitments[0]);
+        assert_eq!(blob.proof, data.proofs[0]);
+
+        store.delete_all(vec![tx, H256::random()]).unwrap();
+        assert_eq!(store.blobs_len(), 0);
+        assert_eq!(store.data_size_hint(), 0);
+        assert_eq!(store.get(tx).unwrap(), None);
+        assert_eq!(store.get_by_versioned_hash(versioned_hash).unwrap(), None);
+    }
+}
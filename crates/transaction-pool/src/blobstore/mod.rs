@@ -0,0 +1,157 @@
+//! Storage for blob transaction sidecars.
+//!
+//! Sidecars of EIP-4844 transactions are large (up to ~768KiB per transaction) and are not needed
+//! for ordering or validation once the transaction is in the pool, so they're kept in a separate
+//! [BlobStore] keyed by transaction hash.
+
+use reth_primitives::{BlobTransactionSidecar, Bytes, TxHash, H256};
+use std::{collections::HashMap, fmt};
+
+pub use disk::DiskFileBlobStore;
+pub use mem::InMemoryBlobStore;
+
+mod disk;
+mod mem;
+
+/// A storage for blob transaction sidecars.
+///
+/// Besides the lookup by transaction hash, the store also indexes all blobs by their versioned
+/// hash, so that payload building can resolve the blobs of a block.
+pub trait BlobStore: fmt::Debug + Send + Sync + 'static {
+    /// Inserts the sidecar of the blob transaction into the store.
+    fn insert(&self, tx: TxHash, data: BlobTransactionSidecar) -> Result<(), BlobStoreError>;
+
+    /// Deletes the sidecar of the blob transaction from the store.
+    ///
+    /// This is a no-op if the store has no sidecar for the transaction.
+    fn delete(&self, tx: TxHash) -> Result<(), BlobStoreError>;
+
+    /// Deletes the sidecars of all given transactions from the store.
+    ///
+    /// Transactions that the store has no sidecar for are skipped.
+    fn delete_all(&self, txs: Vec<TxHash>) -> Result<(), BlobStoreError>;
+
+    /// Returns the sidecar of the blob transaction.
+    fn get(&self, tx: TxHash) -> Result<Option<BlobTransactionSidecar>, BlobStoreError>;
+
+    /// Returns the sidecars of all given transactions that are in the store.
+    fn get_all(
+        &self,
+        txs: Vec<TxHash>,
+    ) -> Result<Vec<(TxHash, BlobTransactionSidecar)>, BlobStoreError>;
+
+    /// Returns the blob with its commitment and proof for the given versioned hash.
+    fn get_by_versioned_hash(
+        &self,
+        versioned_hash: H256,
+    ) -> Result<Option<BlobAndProof>, BlobStoreError>;
+
+    /// Returns the total size of all stored sidecars in bytes, see
+    /// [BlobTransactionSidecar::size].
+    fn data_size_hint(&self) -> usize;
+
+    /// Returns the number of transactions with a stored sidecar.
+    fn blobs_len(&self) -> usize;
+}
+
+/// A single blob with its KZG commitment and proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobAndProof {
+    /// The blob data.
+    pub blob: Bytes,
+    /// The blob commitment.
+    pub commitment: Bytes,
+    /// The blob proof.
+    pub proof: Bytes,
+}
+
+impl BlobAndProof {
+    /// Extracts the blob at the given index of the sidecar.
+    pub(crate) fn from_sidecar(sidecar: &BlobTransactionSidecar, index: usize) -> Option<Self> {
+        Some(Self {
+            blob: sidecar.blobs.get(index)?.clone(),
+            commitment: sidecar.commitments.get(index)?.clone(),
+            proof: sidecar.proofs.get(index)?.clone(),
+        })
+    }
+}
+
+/// Errors that can occur when interacting with a [BlobStore].
+#[derive(Debug, thiserror::Error)]
+pub enum BlobStoreError {
+    /// The sidecar of the transaction is indexed but could not be found.
+    #[error("Blob sidecar for transaction {0:?} not found.")]
+    MissingSidecar(TxHash),
+    /// Failed to decode the stored sidecar.
+    #[error("Failed to decode blob sidecar: {0}")]
+    DecodeError(#[from] reth_rlp::DecodeError),
+    /// Failed to access the underlying storage.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Keeps track of the stored sidecars: their size and the versioned hashes of their blobs.
+///
+/// This is shared by all [BlobStore] implementations so that lookups by versioned hash never
+/// have to scan the stored sidecars.
+#[derive(Debug, Default)]
+struct BlobStoreIndex {
+    /// The size and versioned hashes of all stored sidecars.
+    sidecars: HashMap<TxHash, (usize, Vec<H256>)>,
+    /// Maps a versioned hash to the transaction that carries the blob and its index in the
+    /// sidecar.
+    by_versioned_hash: HashMap<H256, (TxHash, usize)>,
+    /// The total size of all stored sidecars.
+    data_size: usize,
+}
+
+// === impl BlobStoreIndex ===
+
+impl BlobStoreIndex {
+    /// Tracks the sidecar of the transaction.
+    ///
+    /// Returns `false` if the sidecar is already tracked.
+    fn insert(&mut self, tx: TxHash, sidecar: &BlobTransactionSidecar) -> bool {
+        if self.sidecars.contains_key(&tx) {
+            return false
+        }
+        let versioned_hashes = sidecar.versioned_hashes().collect::<Vec<_>>();
+        for (index, versioned_hash) in versioned_hashes.iter().enumerate() {
+            self.by_versioned_hash.insert(*versioned_hash, (tx, index));
+        }
+        let size = sidecar.size();
+        self.data_size += size;
+        self.sidecars.insert(tx, (size, versioned_hashes));
+        true
+    }
+
+    /// Stops tracking the sidecar of the transaction.
+    ///
+    /// Returns `false` if the sidecar is not tracked.
+    fn remove(&mut self, tx: &TxHash) -> bool {
+        let Some((size, versioned_hashes)) = self.sidecars.remove(tx) else { return false };
+        for versioned_hash in versioned_hashes {
+            // the same blob can be carried by multiple transactions, only remove our own entry
+            if self.by_versioned_hash.get(&versioned_hash).map(|(hash, _)| hash) == Some(tx) {
+                self.by_versioned_hash.remove(&versioned_hash);
+            }
+        }
+        self.data_size -= size;
+        true
+    }
+
+    /// Returns `true` if the sidecar of the transaction is tracked.
+    fn contains(&self, tx: &TxHash) -> bool {
+        self.sidecars.contains_key(tx)
+    }
+
+    /// Returns the transaction that carries the blob and the blob's index in the sidecar.
+    fn get_by_versioned_hash(&self, versioned_hash: &H256) -> Option<(TxHash, usize)> {
+        self.by_versioned_hash.get(versioned_hash).copied()
+    }
+
+    /// Number of tracked sidecars.
+    fn len(&self) -> usize {
+        self.sidecars.len()
+    }
+}
@@ -1,6 +1,19 @@
+use crate::{PoolTransaction, TransactionOrigin, ValidPoolTransaction, TX_MAX_SIZE};
+use reth_primitives::Address;
+use std::{collections::HashSet, path::PathBuf, time::Duration};
+
 /// Guarantees max transactions for one sender, compatible with geth/erigon
 pub(crate) const MAX_ACCOUNT_SLOTS_PER_SENDER: usize = 16;
 
+/// Default price bump (in %) for the transaction pool underpriced check, compatible with geth
+pub const DEFAULT_PRICE_BUMP: u128 = 10;
+
+/// Default maximum number of transactions in the entire pool, across all sub-pools
+pub const DEFAULT_MAX_TOTAL_TRANSACTIONS: usize = 30_000;
+
+/// How often the journal of local transactions is rotated by default, compatible with geth
+pub const DEFAULT_JOURNAL_ROTATION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
 /// Configuration options for the Transaction pool.
 #[derive(Debug, Clone)]
 pub struct PoolConfig {
@@ -10,8 +23,27 @@ pub struct PoolConfig {
     pub basefee_limit: SubPoolLimit,
     /// Max number of transaction in the queued sub-pool
     pub queued_limit: SubPoolLimit,
+    /// Max number of transaction in the blob sub-pool
+    pub blob_limit: SubPoolLimit,
     /// Max number of executable transaction slots guaranteed per account
     pub max_account_slots: usize,
+    /// Max number of transactions in the entire pool, the least valuable transactions are evicted
+    /// first once this is exceeded.
+    pub max_total_transactions: usize,
+    /// Price bump (in %) a replacement transaction needs to pay over the replaced transaction.
+    pub price_bumps: PriceBumpConfig,
+    /// Minimum priority fee (gas price for legacy transactions) required for acceptance into the
+    /// pool.
+    pub minimal_priority_fee: u128,
+    /// Max size (in bytes) of a transaction in its network encoding.
+    ///
+    /// Validators should reject larger transactions before fetching any state, see
+    /// `EthTransactionValidator::with_max_tx_size`.
+    pub max_tx_size: usize,
+    /// How local transactions are treated by the pool.
+    pub local_transactions: LocalTransactionConfig,
+    /// The journal that persists local transactions across restarts, disabled if `None`.
+    pub journal: Option<JournalConfig>,
 }
 
 impl Default for PoolConfig {
@@ -20,11 +52,114 @@ impl Default for PoolConfig {
             pending_limit: Default::default(),
             basefee_limit: Default::default(),
             queued_limit: Default::default(),
+            blob_limit: SubPoolLimit::blob_default(),
             max_account_slots: MAX_ACCOUNT_SLOTS_PER_SENDER,
+            max_total_transactions: DEFAULT_MAX_TOTAL_TRANSACTIONS,
+            price_bumps: Default::default(),
+            minimal_priority_fee: 0,
+            max_tx_size: TX_MAX_SIZE,
+            local_transactions: Default::default(),
+            journal: None,
         }
     }
 }
 
+/// Price bumps (in %) a replacement transaction needs to pay over the transaction it replaces.
+///
+/// A replacement must increase both its fee cap and its priority fee, for legacy transactions both
+/// are the gas price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriceBumpConfig {
+    /// Minimum bump of the fee cap (`max_fee_per_gas`).
+    pub fee_cap_bump: u128,
+    /// Minimum bump of the priority fee (`max_priority_fee_per_gas`).
+    pub priority_fee_bump: u128,
+}
+
+impl PriceBumpConfig {
+    /// Returns `true` if the replacement's fee cap and priority fee are not sufficiently bumped
+    /// over the existing transaction's.
+    pub fn is_underpriced<T: PoolTransaction>(
+        &self,
+        existing: &ValidPoolTransaction<T>,
+        replacement: &ValidPoolTransaction<T>,
+    ) -> bool {
+        is_bump_insufficient(
+            existing.max_fee_per_gas(),
+            replacement.max_fee_per_gas(),
+            self.fee_cap_bump,
+        ) || is_bump_insufficient(
+            existing.priority_fee_or_price(),
+            replacement.priority_fee_or_price(),
+            self.priority_fee_bump,
+        )
+    }
+}
+
+impl Default for PriceBumpConfig {
+    fn default() -> Self {
+        Self { fee_cap_bump: DEFAULT_PRICE_BUMP, priority_fee_bump: DEFAULT_PRICE_BUMP }
+    }
+}
+
+/// Returns `true` if `new` is not strictly greater than `old` or below `old` bumped by `bump` %.
+fn is_bump_insufficient(old: u128, new: u128, bump: u128) -> bool {
+    let threshold = old.saturating_mul(100 + bump) / 100;
+    new <= old || new < threshold
+}
+
+/// Configuration for local transactions.
+///
+/// Local transactions are exempt from the per account slot limit, the minimal priority fee and
+/// are never evicted to respect the size limits of the pool. Private transactions are submitted
+/// locally as well and are therefore treated as local transactions.
+///
+/// Note: only transactions of local origin are persisted in the journal, private transactions and
+/// transactions of [local addresses](Self::local_addresses) are not, see
+/// [LocalTransactionJournal](crate::journal::LocalTransactionJournal).
+#[derive(Debug, Clone, Default)]
+pub struct LocalTransactionConfig {
+    /// Treat all transactions as remote transactions, disabling all exemptions.
+    pub no_exemptions: bool,
+    /// Additional senders whose transactions are treated as local, regardless of their origin.
+    pub local_addresses: HashSet<Address>,
+}
+
+impl LocalTransactionConfig {
+    /// Returns `true` if a transaction of the given origin and sender is considered local and
+    /// exempt from the pool's limits.
+    #[inline]
+    pub fn is_local(&self, origin: TransactionOrigin, sender: Address) -> bool {
+        !self.no_exemptions &&
+            (origin.is_local() || origin.is_private() || self.local_addresses.contains(&sender))
+    }
+
+    /// Returns `true` if the given transaction is considered local.
+    #[inline]
+    pub fn is_exempt<T: PoolTransaction>(&self, tx: &ValidPoolTransaction<T>) -> bool {
+        self.is_local(tx.origin, tx.sender())
+    }
+}
+
+/// Configuration for the journal of local transactions.
+///
+/// See [LocalTransactionJournal](crate::journal::LocalTransactionJournal).
+#[derive(Debug, Clone)]
+pub struct JournalConfig {
+    /// The path of the journal file.
+    pub path: PathBuf,
+    /// How often the journal is compacted to the local transactions that are still in the pool.
+    pub rotation_interval: Duration,
+}
+
+impl JournalConfig {
+    /// Creates a new config for a journal at the given path that is rotated at the default
+    /// interval.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), rotation_interval: DEFAULT_JOURNAL_ROTATION_INTERVAL }
+    }
+}
+
 /// Size limits for a sub-pool.
 #[derive(Debug, Clone)]
 pub struct SubPoolLimit {
@@ -40,6 +175,15 @@ impl SubPoolLimit {
     pub fn is_exceeded(&self, txs: usize, size: usize) -> bool {
         self.max_txs < txs || self.max_size < size
     }
+
+    /// The default limits for the blob sub-pool.
+    ///
+    /// The sidecars live in the blob store and aren't counted towards the size, so this mainly
+    /// bounds the number of sidecars the store needs to hold.
+    pub fn blob_default() -> Self {
+        // either 1k transactions or 20MB
+        Self { max_txs: 1_000, max_size: 20 * 1024 * 1024 }
+    }
 }
 
 impl Default for SubPoolLimit {
@@ -1,6 +1,6 @@
 //! Transaction pool errors
 
-use reth_primitives::{Address, InvalidTransactionError, TxHash};
+use reth_primitives::{Address, BlobTransactionValidationError, InvalidTransactionError, TxHash};
 
 /// Transaction pool result type.
 pub type PoolResult<T> = Result<T, PoolError>;
@@ -20,6 +20,13 @@ pub enum PoolError {
     /// Thrown when the number of unique transactions of a sender exceeded the slot capacity.
     #[error("{0:?} identified as spammer. Transaction {1:?} rejected.")]
     SpammerExceededCapacity(Address, TxHash),
+    /// Thrown when the priority fee (gas price for legacy transactions) of a non-local transaction
+    /// is below the minimum configured for the pool.
+    #[error("[{0:?}] Transaction priority fee below configured minimum {1}.")]
+    PriorityFeeBelowMinimum(TxHash, u128),
+    /// Thrown when the encoded size of a transaction exceeds the maximum configured for the pool.
+    #[error("[{0:?}] Transaction size {1} exceeds configured maximum {2}.")]
+    ExceededMaxTransactionSize(TxHash, usize, usize),
     /// Thrown when a new transaction is added to the pool, but then immediately discarded to
     /// respect the size limits of the pool.
     #[error("[{0:?}] Transaction discarded outright due to pool size constraints.")]
@@ -43,6 +50,8 @@ impl PoolError {
             PoolError::ReplacementUnderpriced(hash) => hash,
             PoolError::FeeCapBelowMinimumProtocolFeeCap(hash, _) => hash,
             PoolError::SpammerExceededCapacity(_, hash) => hash,
+            PoolError::PriorityFeeBelowMinimum(hash, _) => hash,
+            PoolError::ExceededMaxTransactionSize(hash, _, _) => hash,
             PoolError::DiscardedOnInsert(hash) => hash,
             PoolError::InvalidTransaction(hash, _) => hash,
             PoolError::Other(hash, _) => hash,
@@ -86,6 +95,14 @@ impl PoolError {
                 // (pool lags behind) and old transaction still occupy a slot in the pool
                 false
             }
+            PoolError::PriorityFeeBelowMinimum(_, _) => {
+                // local setting
+                false
+            }
+            PoolError::ExceededMaxTransactionSize(_, _, _) => {
+                // local setting, the transaction could be valid for peers with a higher limit
+                false
+            }
             PoolError::DiscardedOnInsert(_) => {
                 // valid tx but dropped due to size constraints
                 false
@@ -126,6 +143,12 @@ pub enum InvalidPoolTransactionError {
     /// Thrown if the transaction's fee is below the minimum fee
     #[error("transaction underpriced")]
     Underpriced,
+    /// Thrown if a blob transaction was received without its sidecar.
+    #[error("blob transaction is missing its sidecar")]
+    MissingBlobSidecar,
+    /// Thrown if the sidecar of a blob transaction does not match the transaction.
+    #[error(transparent)]
+    InvalidBlobSidecar(BlobTransactionValidationError),
 }
 
 // === impl InvalidPoolTransactionError ===
@@ -155,12 +178,14 @@ impl InvalidPoolTransactionError {
                         // these are technically not invalid
                         false
                     }
-                    InvalidTransactionError::FeeCapTooLow => {
+                    InvalidTransactionError::FeeCapTooLow |
+                    InvalidTransactionError::BlobFeeCapTooLow => {
                         // dynamic, but not used during validation
                         false
                     }
                     InvalidTransactionError::Eip2930Disabled |
-                    InvalidTransactionError::Eip1559Disabled => {
+                    InvalidTransactionError::Eip1559Disabled |
+                    InvalidTransactionError::Eip4844Disabled => {
                         // settings
                         false
                     }
@@ -169,6 +194,9 @@ impl InvalidPoolTransactionError {
                     InvalidTransactionError::GasUintOverflow => true,
                     InvalidTransactionError::TxTypeNotSupported => true,
                     InvalidTransactionError::SignerAccountHasBytecode => true,
+                    InvalidTransactionError::BlobTransactionMissingBlobHashes => true,
+                    InvalidTransactionError::BlobTransactionIsCreate => true,
+                    InvalidTransactionError::BlobVersionedHashVersionInvalid => true,
                 }
             }
             InvalidPoolTransactionError::ExceedsGasLimit(_, _) => true,
@@ -178,6 +206,8 @@ impl InvalidPoolTransactionError {
                 // local setting
                 false
             }
+            InvalidPoolTransactionError::MissingBlobSidecar => true,
+            InvalidPoolTransactionError::InvalidBlobSidecar(_) => true,
         }
     }
 }
@@ -0,0 +1,230 @@
+//! A journal of local transactions that survives restarts of the node.
+//!
+//! The pool is kept in memory, so all transactions are lost when the node shuts down. Transactions
+//! of local origin are appended to the journal as they are accepted by the pool and the journal is
+//! periodically rotated: it is replaced by a compacted version that only contains the local
+//! transactions that are still in the pool.
+//!
+//! The journal is a file of rlp encoded transactions in their network encoding, blob transactions
+//! are never journaled because their sidecars aren't persisted.
+//!
+//! Only transactions of [Local](crate::TransactionOrigin::Local) origin are journaled. Private
+//! transactions are not persisted, even though the pool exempts them from its limits like local
+//! transactions (see [LocalTransactionConfig](crate::LocalTransactionConfig)): the journal doesn't
+//! record the origin and expiry of a transaction, and replaying a private transaction as a local
+//! one would announce it to peers. The same applies to transactions that are only treated as
+//! local because of their sender.
+
+use crate::{traits::PoolTransaction, ValidPoolTransaction};
+use parking_lot::Mutex;
+use reth_primitives::{TransactionSigned, TransactionSignedEcRecovered};
+use reth_rlp::{Decodable, Encodable};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::{debug, trace};
+
+/// Errors that can occur while accessing the journal.
+#[derive(Debug, thiserror::Error)]
+pub enum JournalError {
+    /// Failed to access the journal file.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// The journal of local transactions.
+#[derive(Debug)]
+pub struct LocalTransactionJournal {
+    /// The path of the journal file.
+    path: PathBuf,
+    /// The writer that appends new transactions to the journal, opened lazily.
+    writer: Mutex<Option<BufWriter<File>>>,
+}
+
+// === impl LocalTransactionJournal ===
+
+impl LocalTransactionJournal {
+    /// Creates a new journal that is stored at the given path.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), writer: Mutex::new(None) }
+    }
+
+    /// Returns the path of the journal file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Loads all transactions from the journal.
+    ///
+    /// Entries that can't be decoded or recovered are skipped, if an entry is truncated, for
+    /// example because the node crashed while writing it, all following entries are dropped.
+    pub fn load<T: PoolTransaction>(&self) -> Result<Vec<T>, JournalError> {
+        let data = match fs::read(&self.path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut buf = data.as_slice();
+        let mut transactions = Vec::new();
+        let mut dropped = 0usize;
+        while !buf.is_empty() {
+            let tx = match TransactionSigned::decode(&mut buf) {
+                Ok(tx) => tx,
+                Err(err) => {
+                    debug!(target: "txpool::journal", ?err, "truncated transaction journal");
+                    break
+                }
+            };
+            match tx.into_ecrecovered() {
+                Some(tx) => transactions.push(T::from_recovered_transaction(tx)),
+                None => dropped += 1,
+            }
+        }
+
+        debug!(target: "txpool::journal", loaded = transactions.len(), dropped, path = ?self.path, "loaded transaction journal");
+        Ok(transactions)
+    }
+
+    /// Appends the transaction to the journal.
+    pub fn insert<T: PoolTransaction>(&self, tx: &T) -> Result<(), JournalError> {
+        if tx.is_eip4844() {
+            return Ok(())
+        }
+
+        let mut writer = self.writer.lock();
+        if writer.is_none() {
+            let file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+            *writer = Some(BufWriter::new(file));
+        }
+        let writer = writer.as_mut().expect("is set");
+
+        writer.write_all(&encode(tx.to_recovered_transaction()))?;
+        writer.flush()?;
+        trace!(target: "txpool::journal", hash = ?tx.hash(), "journaled transaction");
+        Ok(())
+    }
+
+    /// Replaces the journal with one that only contains the given transactions.
+    ///
+    /// The new journal is written to a temporary file first that replaces the journal once it's
+    /// complete.
+    pub fn rotate<T: PoolTransaction>(
+        &self,
+        transactions: impl IntoIterator<Item = Arc<ValidPoolTransaction<T>>>,
+    ) -> Result<usize, JournalError> {
+        // hold the lock so nothing is appended to the replaced file
+        let mut writer = self.writer.lock();
+        writer.take();
+
+        let tmp = self.path.with_extension("new");
+        let mut count = 0;
+        {
+            let mut file = BufWriter::new(File::create(&tmp)?);
+            for tx in transactions {
+                if tx.is_eip4844() {
+                    continue
+                }
+                file.write_all(&encode(tx.transaction.to_recovered_transaction()))?;
+                count += 1;
+            }
+            file.flush()?;
+        }
+        fs::rename(&tmp, &self.path)?;
+
+        debug!(target: "txpool::journal", transactions = count, path = ?self.path, "rotated transaction journal");
+        Ok(count)
+    }
+}
+
+/// Encodes the transaction the way it is stored in the journal.
+fn encode(tx: TransactionSignedEcRecovered) -> Vec<u8> {
+    let tx = tx.into_signed();
+    let mut buf = Vec::with_capacity(tx.length());
+    tx.encode(&mut buf);
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{identifier::TransactionId, PooledTransaction, TransactionOrigin};
+    use reth_primitives::{
+        sign_message, FromRecoveredTransaction, IntoRecoveredTransaction, Signature, Transaction,
+        TransactionKind, TxEip1559, H256, U256,
+    };
+    use std::time::Instant;
+
+    fn signed_tx(nonce: u64) -> PooledTransaction {
+        let tx = Transaction::Eip1559(TxEip1559 {
+            chain_id: 1,
+            nonce,
+            gas_limit: 21_000,
+            max_fee_per_gas: 100,
+            max_priority_fee_per_gas: 1,
+            to: TransactionKind::Call(Default::default()),
+            value: 1,
+            ..Default::default()
+        });
+        let signature: Signature =
+            sign_message(H256::from_low_u64_be(1), tx.signature_hash()).unwrap();
+        let tx = TransactionSigned::from_transaction_and_signature(tx, signature);
+        PooledTransaction::from_recovered_transaction(tx.into_ecrecovered().unwrap())
+    }
+
+    fn valid(transaction: PooledTransaction) -> Arc<ValidPoolTransaction<PooledTransaction>> {
+        Arc::new(ValidPoolTransaction {
+            transaction_id: TransactionId::new(0u64.into(), transaction.nonce()),
+            propagate: true,
+            cost: transaction.cost(),
+            timestamp: Instant::now(),
+            origin: TransactionOrigin::Local,
+            encoded_length: transaction.encoded_length(),
+            transaction,
+        })
+    }
+
+    fn hashes(txs: &[PooledTransaction]) -> Vec<H256> {
+        txs.iter().map(|tx| *tx.hash()).collect()
+    }
+
+    #[test]
+    fn insert_rotate_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = LocalTransactionJournal::new(dir.path().join("transactions.rlp"));
+        assert!(journal.load::<PooledTransaction>().unwrap().is_empty());
+
+        let txs = (0..3).map(signed_tx).collect::<Vec<_>>();
+        for tx in &txs {
+            journal.insert(tx).unwrap();
+        }
+        assert_eq!(hashes(&journal.load::<PooledTransaction>().unwrap()), hashes(&txs));
+
+        // compact to the last transaction and keep appending
+        assert_eq!(journal.rotate(vec![valid(txs[2].clone())]).unwrap(), 1);
+        journal.insert(&txs[0]).unwrap();
+        let loaded = journal.load::<PooledTransaction>().unwrap();
+        assert_eq!(hashes(&loaded), vec![*txs[2].hash(), *txs[0].hash()]);
+        assert_eq!(loaded[0].cost(), txs[2].cost());
+        assert_eq!(loaded[0].cost(), U256::from(21_000 * 100 + 1));
+    }
+
+    #[test]
+    fn drops_truncated_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("transactions.rlp");
+        let journal = LocalTransactionJournal::new(&path);
+        journal.insert(&signed_tx(0)).unwrap();
+
+        let mut data = fs::read(&path).unwrap();
+        let mut truncated = encode(signed_tx(1).to_recovered_transaction());
+        truncated.truncate(truncated.len() / 2);
+        data.extend(truncated);
+        fs::write(&path, data).unwrap();
+
+        assert_eq!(journal.load::<PooledTransaction>().unwrap().len(), 1);
+    }
+}
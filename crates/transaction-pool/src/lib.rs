@@ -38,10 +38,20 @@
 //!
 //! The pending pool contains transactions that can be mined on the current state.
 //! The order in which they're returned are determined by a `Priority` value returned by the
-//! `TransactionOrdering` type this pool is configured with.
+//! `TransactionOrdering` type this pool is configured with. Besides the default `CostOrdering`,
+//! the `EffectiveTipOrdering` orders transactions by the tip the block author receives at the
+//! current base fee and the `ScoreOrdering` allows plugging in external scores. Since priorities
+//! may depend on the base fee, the pending pool is re-sorted whenever the base fee changes.
 //!
 //! This is only used in the _pending_ pool to yield the best transactions for block production. The
-//! _base pool_ is ordered by base fee, and the _queued pool_ by current distance.
+//! _base pool_ is ordered by base fee, the _queued pool_ by current distance and the _blob pool_
+//! by blob fee.
+//!
+//! ### Blob transactions
+//!
+//! EIP-4844 blob transactions are pooled like any other transaction, but their sidecars are kept
+//! separately in a [`BlobStore`](crate::blobstore::BlobStore). Blob transactions that can't be
+//! included in the next block are parked in a separate _blob pool_ with its own limits.
 //!
 //! ### Validation
 //!
@@ -80,35 +90,47 @@
 //! [`Pool`](crate::Pool) type is just an `Arc` wrapper around `PoolInner`. This is the usable type
 //! that provides the `TransactionPool` interface.
 
+use crate::{
+    blobstore::BlobAndProof,
+    error::PoolResult,
+    pool::PoolInner,
+    traits::{NewTransactionEvent, PoolSize},
+};
 pub use crate::{
-    config::PoolConfig,
-    ordering::{CostOrdering, TransactionOrdering},
+    blobstore::{BlobStore, BlobStoreError, DiskFileBlobStore, InMemoryBlobStore},
+    config::{
+        JournalConfig, LocalTransactionConfig, PoolConfig, PriceBumpConfig,
+        DEFAULT_JOURNAL_ROTATION_INTERVAL, DEFAULT_MAX_TOTAL_TRANSACTIONS, DEFAULT_PRICE_BUMP,
+    },
+    ordering::{
+        CostOrdering, EffectiveTipOrdering, ScoreOrdering, ScorePriority, TransactionOrdering,
+        TransactionScorer,
+    },
     pool::TransactionEvents,
     traits::{
         AllPoolTransactions, BestTransactions, BlockInfo, CanonicalStateUpdate, ChangedAccount,
-        PoolTransaction, PooledTransaction, PropagateKind, PropagatedTransactions,
-        TransactionOrigin, TransactionPool,
+        ParkedReason, PoolTransaction, PoolTransactionStatus, PooledTransaction, PropagateKind,
+        PropagatedTransactions, TransactionOrigin, TransactionPool,
     },
     validate::{
         EthTransactionValidator, TransactionValidationOutcome, TransactionValidator,
         ValidPoolTransaction,
     },
 };
-use crate::{
-    error::PoolResult,
-    pool::PoolInner,
-    traits::{NewTransactionEvent, PoolSize},
-};
 use aquamarine as _;
-use reth_primitives::{Address, TxHash, U256};
+use reth_primitives::{
+    Address, BlobTransactionSidecar, PooledTransactionsElement, TxHash, H256, U256,
+};
 use reth_provider::StateProviderFactory;
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Instant};
 use tokio::sync::mpsc::Receiver;
 use tracing::{instrument, trace};
 
+pub mod blobstore;
 mod config;
 pub mod error;
 mod identifier;
+pub mod journal;
 pub mod maintain;
 pub mod metrics;
 mod ordering;
@@ -153,8 +175,15 @@ where
     T: TransactionOrdering<Transaction = <V as TransactionValidator>::Transaction>,
 {
     /// Create a new transaction pool instance.
-    pub fn new(validator: V, ordering: T, config: PoolConfig) -> Self {
-        Self { pool: Arc::new(PoolInner::new(validator, ordering, config)) }
+    ///
+    /// The sidecars of blob transactions are stored in the given [BlobStore].
+    pub fn new(
+        validator: V,
+        ordering: T,
+        blob_store: Arc<dyn BlobStore>,
+        config: PoolConfig,
+    ) -> Self {
+        Self { pool: Arc::new(PoolInner::new(validator, ordering, blob_store, config)) }
     }
 
     /// Returns the wrapped pool.
@@ -180,12 +209,17 @@ where
         origin: TransactionOrigin,
         transactions: impl IntoIterator<Item = V::Transaction>,
     ) -> PoolResult<HashMap<TxHash, TransactionValidationOutcome<V::Transaction>>> {
+        let start = Instant::now();
         let outcome = futures_util::future::join_all(
             transactions.into_iter().map(|tx| self.validate(origin, tx)),
         )
         .await
         .into_iter()
         .collect::<HashMap<_, _>>();
+        self.pool
+            .validation_metrics()
+            .batch_validation_duration
+            .record(start.elapsed().as_secs_f64());
 
         Ok(outcome)
     }
@@ -198,7 +232,9 @@ where
     ) -> (TxHash, TransactionValidationOutcome<V::Transaction>) {
         let hash = *transaction.hash();
 
+        let start = Instant::now();
         let outcome = self.pool.validator().validate_transaction(origin, transaction).await;
+        self.pool.validation_metrics().validation_duration.record(start.elapsed().as_secs_f64());
 
         (hash, outcome)
     }
@@ -223,9 +259,10 @@ where
     /// [PooledTransaction]s and ords via [CostOrdering]
     pub fn eth_pool(
         validator: EthTransactionValidator<Client, PooledTransaction>,
+        blob_store: Arc<dyn BlobStore>,
         config: PoolConfig,
     ) -> Self {
-        Self::new(validator, CostOrdering::default(), config)
+        Self::new(validator, CostOrdering::default(), blob_store, config)
     }
 }
 
@@ -346,6 +383,31 @@ where
         self.inner().get_all(txs)
     }
 
+    fn get_pooled_transaction_elements(
+        &self,
+        tx_hashes: Vec<TxHash>,
+    ) -> Vec<PooledTransactionsElement> {
+        self.inner().get_pooled_transaction_elements(tx_hashes)
+    }
+
+    fn get_blob(&self, tx_hash: TxHash) -> Result<Option<BlobTransactionSidecar>, BlobStoreError> {
+        self.inner().get_blob(tx_hash)
+    }
+
+    fn get_all_blobs(
+        &self,
+        tx_hashes: Vec<TxHash>,
+    ) -> Result<Vec<(TxHash, BlobTransactionSidecar)>, BlobStoreError> {
+        self.inner().get_all_blobs(tx_hashes)
+    }
+
+    fn get_blob_by_versioned_hash(
+        &self,
+        versioned_hash: H256,
+    ) -> Result<Option<BlobAndProof>, BlobStoreError> {
+        self.inner().get_blob_by_versioned_hash(versioned_hash)
+    }
+
     fn on_propagated(&self, txs: PropagatedTransactions) {
         self.inner().on_propagated(txs)
     }
@@ -356,6 +418,13 @@ where
     ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
         self.pool.get_transactions_by_sender(sender)
     }
+
+    fn get_transactions_by_sender_with_status(
+        &self,
+        sender: Address,
+    ) -> Vec<PoolTransactionStatus<Self::Transaction>> {
+        self.pool.get_transactions_by_sender_with_status(sender)
+    }
 }
 
 impl<V: TransactionValidator, T: TransactionOrdering> Clone for Pool<V, T> {
@@ -1,8 +1,11 @@
 //! Support for maintaining the state of the transaction pool
 
 use crate::{
+    journal::LocalTransactionJournal,
+    metrics::MaintainPoolMetrics,
     traits::{CanonicalStateUpdate, ChangedAccount},
-    BlockInfo, Pool, TransactionOrdering, TransactionPool, TransactionValidator,
+    BlockInfo, JournalConfig, Pool, TransactionOrdering, TransactionOrigin, TransactionPool,
+    TransactionValidator,
 };
 use futures_util::{Stream, StreamExt};
 use reth_primitives::{Address, BlockHash, BlockNumberOrTag, FromRecoveredTransaction};
@@ -11,21 +14,54 @@ use std::{
     borrow::Borrow,
     collections::HashSet,
     hash::{Hash, Hasher},
+    time::Duration,
 };
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
 
 /// Maximum (reorg) depth we handle when updating the transaction pool: `new.number -
 /// last_seen.number`
 const MAX_UPDATE_DEPTH: u64 = 64;
 
+/// Maximum number of accounts that are reloaded from the state at once.
+const MAX_RELOAD_ACCOUNTS: usize = 256;
+
+/// How often all senders of the pool are reloaded from the state by default.
+const RESYNC_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Additional settings for maintaining the transaction pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaintainPoolConfig {
+    /// Maximum (reorg) depth we handle when updating the transaction pool: `new.number -
+    /// last_seen.number`
+    pub max_update_depth: u64,
+    /// Maximum number of accounts that are reloaded from the state at once.
+    pub max_reload_accounts: usize,
+    /// How often the state of all senders in the pool is reloaded to heal drift between the pool
+    /// and the chain.
+    pub resync_interval: Duration,
+}
+
+impl Default for MaintainPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_update_depth: MAX_UPDATE_DEPTH,
+            max_reload_accounts: MAX_RELOAD_ACCOUNTS,
+            resync_interval: RESYNC_INTERVAL,
+        }
+    }
+}
+
 /// Maintains the state of the transaction pool by handling new blocks and reorgs.
 ///
-/// This listens for any new blocks and reorgs and updates the transaction pool's state accordingly
-#[allow(unused)]
+/// This listens for any new blocks and reorgs and updates the transaction pool's state
+/// accordingly. Periodically, and whenever the pool is found to be out of sync with the chain, the
+/// nonce and balance of all senders in the pool are reloaded from the state, which moves
+/// transactions between the sub-pools and discards stale transactions.
 pub async fn maintain_transaction_pool<Client, V, T, St>(
     client: Client,
     pool: Pool<V, T>,
     mut events: St,
+    config: MaintainPoolConfig,
 ) where
     Client: StateProviderFactory + BlockProviderIdExt,
     V: TransactionValidator,
@@ -39,6 +75,7 @@ pub async fn maintain_transaction_pool<Client, V, T, St>(
             last_seen_block_hash: latest.hash,
             last_seen_block_number: latest.number,
             pending_basefee: latest.next_block_base_fee().unwrap_or_default() as u128,
+            pending_blob_fee: latest.next_block_blob_fee(),
         };
         pool.set_block_info(info);
     }
@@ -49,12 +86,46 @@ pub async fn maintain_transaction_pool<Client, V, T, St>(
     // keeps track of the state of the pool wrt to blocks
     let mut maintained_state = MaintainedPoolState::InSync;
 
-    // Listen for new chain events and derive the update action for the pool
-    while let Some(event) = events.next().await {
-        let pool_info = pool.block_info();
+    let metrics = MaintainPoolMetrics::default();
+
+    // the first tick completes immediately, but the pool was just initialized
+    let mut resync = tokio::time::interval(config.resync_interval);
+    resync.tick().await;
+
+    loop {
+        if maintained_state == MaintainedPoolState::Drift {
+            // the pool could be out of sync with the chain, reload all senders
+            metrics.drift_count.increment(1);
+            debug!(target: "txpool", "pool drifted from the chain, reloading all senders");
+            let senders = pool.inner().unique_senders();
+            reload_accounts(&client, &pool, senders, &mut dirty_addresses, &config, &metrics);
+            maintained_state = MaintainedPoolState::InSync;
+        } else if !dirty_addresses.is_empty() {
+            // retry some of the accounts that previously failed to load
+            let dirty = dirty_addresses
+                .iter()
+                .take(config.max_reload_accounts)
+                .copied()
+                .collect::<Vec<_>>();
+            reload_accounts(&client, &pool, dirty, &mut dirty_addresses, &config, &metrics);
+        }
 
-        // TODO from time to time re-check the unique accounts in the pool and remove and resync
-        // based on the tracked state
+        // Listen for new chain events and derive the update action for the pool
+        let event = tokio::select! {
+            event = events.next() => {
+                let Some(event) = event else { break };
+                event
+            }
+            _ = resync.tick() => {
+                // from time to time re-check the unique accounts in the pool and resync them
+                metrics.resyncs.increment(1);
+                let senders = pool.inner().unique_senders();
+                reload_accounts(&client, &pool, senders, &mut dirty_addresses, &config, &metrics);
+                continue
+            }
+        };
+
+        let pool_info = pool.block_info();
 
         match event {
             CanonStateNotification::Reorg { old, new } => {
@@ -75,6 +146,7 @@ pub async fn maintain_transaction_pool<Client, V, T, St>(
                 // base fee for the next block: `new_tip+1`
                 let pending_block_base_fee =
                     new_tip.next_block_base_fee().unwrap_or_default() as u128;
+                let pending_block_blob_fee = new_tip.next_block_blob_fee();
 
                 // we know all changed account in the new chain
                 let new_changed_accounts: HashSet<_> =
@@ -132,6 +204,7 @@ pub async fn maintain_transaction_pool<Client, V, T, St>(
                     hash: new_tip.hash,
                     number: new_tip.number,
                     pending_block_base_fee,
+                    pending_block_blob_fee,
                     changed_accounts,
                     // all transactions mined in the new chain need to be removed from the pool
                     mined_transactions: new_mined_transactions.into_iter().collect(),
@@ -142,8 +215,7 @@ pub async fn maintain_transaction_pool<Client, V, T, St>(
                 // to be re-injected
                 //
                 // Note: we no longer know if the tx was local or external
-                let _ = pool.add_external_transactions(pruned_old_transactions).await;
-                // TODO: metrics
+                reinject_transactions(&pool, pruned_old_transactions, &metrics).await;
             }
             CanonStateNotification::Revert { old } => {
                 // this similar to the inverse of a commit where we need to insert the transactions
@@ -160,6 +232,7 @@ pub async fn maintain_transaction_pool<Client, V, T, St>(
                 // base fee for the next block: `first_block+1`
                 let pending_block_base_fee =
                     first_block.next_block_base_fee().unwrap_or_default() as u128;
+                let pending_block_blob_fee = first_block.next_block_blob_fee();
 
                 let mut changed_accounts = Vec::with_capacity(state.accounts().len());
                 for acc in changed_accounts_iter(state) {
@@ -172,6 +245,7 @@ pub async fn maintain_transaction_pool<Client, V, T, St>(
                     hash: first_block.hash,
                     number: first_block.number,
                     pending_block_base_fee,
+                    pending_block_blob_fee,
                     changed_accounts,
                     // no tx to prune in the reverted chain
                     mined_transactions: vec![],
@@ -189,8 +263,7 @@ pub async fn maintain_transaction_pool<Client, V, T, St>(
                 // all transactions that were mined in the old chain need to be re-injected
                 //
                 // Note: we no longer know if the tx was local or external
-                let _ = pool.add_external_transactions(pruned_old_transactions).await;
-                // TODO: metrics
+                reinject_transactions(&pool, pruned_old_transactions, &metrics).await;
             }
             CanonStateNotification::Commit { new } => {
                 let (blocks, state) = new.inner();
@@ -198,19 +271,21 @@ pub async fn maintain_transaction_pool<Client, V, T, St>(
 
                 // base fee for the next block: `tip+1`
                 let pending_block_base_fee = tip.next_block_base_fee().unwrap_or_default() as u128;
+                let pending_block_blob_fee = tip.next_block_blob_fee();
 
                 let first_block = blocks.first();
 
                 // check if the depth is too large and should be skipped, this could happen after
                 // initial sync or long re-sync
                 let depth = tip.number.abs_diff(pool_info.last_seen_block_number);
-                if depth > MAX_UPDATE_DEPTH {
+                if depth > config.max_update_depth {
                     maintained_state = MaintainedPoolState::Drift;
                     debug!(?depth, "skipping deep canonical update");
                     let info = BlockInfo {
                         last_seen_block_hash: tip.hash,
                         last_seen_block_number: tip.number,
                         pending_basefee: pending_block_base_fee,
+                        pending_blob_fee: pending_block_blob_fee,
                     };
                     pool.set_block_info(info);
                     continue
@@ -238,6 +313,7 @@ pub async fn maintain_transaction_pool<Client, V, T, St>(
                     hash: tip.hash,
                     number: tip.number,
                     pending_block_base_fee,
+                    pending_block_blob_fee,
                     changed_accounts,
                     mined_transactions,
                 };
@@ -247,6 +323,130 @@ pub async fn maintain_transaction_pool<Client, V, T, St>(
     }
 }
 
+/// Re-injects the transactions of reverted blocks into the pool.
+async fn reinject_transactions<V, T>(
+    pool: &Pool<V, T>,
+    transactions: Vec<<V as TransactionValidator>::Transaction>,
+    metrics: &MaintainPoolMetrics,
+) where
+    V: TransactionValidator,
+    T: TransactionOrdering<Transaction = <V as TransactionValidator>::Transaction>,
+{
+    if transactions.is_empty() {
+        return
+    }
+    let total = transactions.len();
+    if let Ok(results) = pool.add_external_transactions(transactions).await {
+        let reinserted = results.iter().filter(|res| res.is_ok()).count();
+        metrics.reinserted_transactions.increment(reinserted as u64);
+        debug!(target: "txpool", total, reinserted, "re-injected transactions of reverted blocks");
+    }
+}
+
+/// Reloads the nonce and balance of the given accounts from the state of the pool's last seen
+/// block and applies them to the pool.
+///
+/// Accounts that fail to load are marked as dirty, loaded accounts are no longer dirty.
+fn reload_accounts<Client, V, T>(
+    client: &Client,
+    pool: &Pool<V, T>,
+    addresses: impl IntoIterator<Item = Address>,
+    dirty_addresses: &mut HashSet<Address>,
+    config: &MaintainPoolConfig,
+    metrics: &MaintainPoolMetrics,
+) where
+    Client: StateProviderFactory,
+    V: TransactionValidator,
+    T: TransactionOrdering<Transaction = <V as TransactionValidator>::Transaction>,
+{
+    let at = pool.block_info().last_seen_block_hash;
+    let addresses = addresses.into_iter().collect::<Vec<_>>();
+
+    for chunk in addresses.chunks(config.max_reload_accounts.max(1)) {
+        match load_accounts(client, at, chunk.iter().copied()) {
+            Ok(LoadedAccounts { accounts, failed_to_load }) => {
+                for acc in &accounts {
+                    dirty_addresses.remove(&acc.address);
+                }
+                dirty_addresses.extend(failed_to_load);
+
+                let drifted = pool.inner().update_accounts(accounts);
+                if drifted > 0 {
+                    debug!(target: "txpool", drifted, "reloaded accounts that drifted from the state");
+                    metrics.drifted_accounts.increment(drifted as u64);
+                }
+            }
+            Err(err) => {
+                let (addresses, err) = *err;
+                warn!(target: "txpool", ?err, "failed to reload accounts at block: {:?}", at);
+                dirty_addresses.extend(addresses);
+            }
+        }
+    }
+
+    metrics.dirty_accounts.set(dirty_addresses.len() as f64);
+}
+
+/// Persists the local transactions of the pool in a [LocalTransactionJournal].
+///
+/// On startup all journaled transactions are validated and re-injected into the pool, afterwards
+/// every new local transaction is appended to the journal, which is rotated periodically so that
+/// it only contains the local transactions that are still in the pool.
+///
+/// Private transactions are not journaled and are lost on restart, see
+/// [LocalTransactionJournal].
+pub async fn journal_local_transactions<P>(pool: P, config: JournalConfig)
+where
+    P: TransactionPool,
+{
+    let journal = LocalTransactionJournal::new(config.path);
+
+    // replay the journal, the transactions are validated by the pool's validator
+    match journal.load::<P::Transaction>() {
+        Ok(transactions) if !transactions.is_empty() => {
+            let total = transactions.len();
+            match pool.add_transactions(TransactionOrigin::Local, transactions).await {
+                Ok(results) => {
+                    let imported = results.iter().filter(|res| res.is_ok()).count();
+                    info!(target: "txpool", imported, dropped = total - imported, "Replayed local transactions from journal");
+                }
+                Err(err) => {
+                    warn!(target: "txpool", ?err, "Failed to replay local transactions from journal")
+                }
+            }
+        }
+        Ok(_) => {}
+        Err(err) => {
+            warn!(target: "txpool", ?err, path = ?journal.path(), "Failed to load transaction journal")
+        }
+    }
+
+    let mut new_transactions = pool.transactions_listener();
+    let mut rotation = tokio::time::interval(config.rotation_interval);
+
+    loop {
+        tokio::select! {
+            event = new_transactions.recv() => {
+                let Some(event) = event else { break };
+                // private transactions are never journaled, they'd be replayed as local
+                if !event.transaction.is_local() {
+                    continue
+                }
+                if let Err(err) = journal.insert(&event.transaction.transaction) {
+                    warn!(target: "txpool", ?err, "Failed to journal local transaction");
+                }
+            }
+            // the first tick completes immediately and compacts the replayed journal
+            _ = rotation.tick() => {
+                let local = pool.pooled_transactions().into_iter().filter(|tx| tx.is_local());
+                if let Err(err) = journal.rotate(local) {
+                    warn!(target: "txpool", ?err, "Failed to rotate transaction journal");
+                }
+            }
+        }
+    }
+}
+
 /// Keeps track of the pool's state, whether the accounts in the pool are in sync with the actual
 /// state.
 #[derive(Eq, PartialEq)]
@@ -1,7 +1,7 @@
 //! Transaction pool metrics.
 
 use reth_metrics::{
-    metrics::{self, Counter, Gauge},
+    metrics::{self, Counter, Gauge, Histogram},
     Metrics,
 };
 
@@ -15,6 +15,36 @@ pub struct TxPoolMetrics {
     pub(crate) invalid_transactions: Counter,
     /// Number of removed transactions from the pool
     pub(crate) removed_transactions: Counter,
+    /// Number of transactions in the entire pool
+    pub(crate) total_transactions: Gauge,
+
+    /// Number of transactions that replaced a transaction of the same sender with the same nonce
+    pub(crate) replaced_transactions: Counter,
+    /// Number of transactions removed from the pool because they were mined
+    pub(crate) mined_transactions: Counter,
+    /// Number of transactions discarded because they became stale, for example because their
+    /// nonce was used on chain by another transaction
+    pub(crate) discarded_stale_transactions: Counter,
+    /// Number of private transactions discarded because they were not included before their max
+    /// block number
+    pub(crate) discarded_expired_transactions: Counter,
+    /// Number of transactions evicted because a sub-pool exceeded its limits
+    pub(crate) evicted_subpool_limit_transactions: Counter,
+    /// Number of transactions evicted because the pool exceeded the global transaction limit
+    pub(crate) evicted_global_limit_transactions: Counter,
+
+    /// Number of rejected replacement transactions that didn't bump the fees enough
+    pub(crate) rejected_underpriced_replacements: Counter,
+    /// Number of transactions rejected because the fee cap is below the minimum protocol fee cap
+    pub(crate) rejected_fee_cap_below_minimum: Counter,
+    /// Number of transactions rejected because the priority fee is below the configured minimum
+    pub(crate) rejected_priority_fee_below_minimum: Counter,
+    /// Number of transactions rejected because the sender exceeded its account slots
+    pub(crate) rejected_sender_capacity_exceeded: Counter,
+    /// Number of transactions rejected because they exceed the maximum transaction size
+    pub(crate) rejected_oversized_transactions: Counter,
+    /// Number of transactions rejected because their gas limit exceeds the block gas limit
+    pub(crate) rejected_gas_limit_exceeded: Counter,
 
     /// Number of transactions in the pending sub-pool
     pub(crate) pending_pool_transactions: Gauge,
@@ -30,4 +60,55 @@ pub struct TxPoolMetrics {
     pub(crate) queued_pool_transactions: Gauge,
     /// Total amount of memory used by the transactions in the queued sub-pool in bytes
     pub(crate) queued_pool_size_bytes: Gauge,
+
+    /// Number of transactions in the blob sub-pool
+    pub(crate) blob_pool_transactions: Gauge,
+    /// Total amount of memory used by the transactions in the blob sub-pool in bytes
+    pub(crate) blob_pool_size_bytes: Gauge,
+}
+
+/// Transaction validation metrics
+#[derive(Metrics)]
+#[metrics(scope = "transaction_pool")]
+pub struct TxValidationMetrics {
+    /// Time it took to validate a single transaction in seconds
+    pub(crate) validation_duration: Histogram,
+    /// Time it took to validate a batch of transactions in seconds
+    pub(crate) batch_validation_duration: Histogram,
+    /// Number of transactions the validator considered invalid
+    pub(crate) validation_failures: Counter,
+    /// Number of transactions that could not be validated because of an internal error
+    pub(crate) validation_errors: Counter,
+}
+
+/// Blob store metrics
+#[derive(Metrics)]
+#[metrics(scope = "transaction_pool")]
+pub struct BlobStoreMetrics {
+    /// Number of failed inserts into the blob store
+    pub(crate) blobstore_failed_inserts: Counter,
+    /// Number of failed deletes from the blob store
+    pub(crate) blobstore_failed_deletes: Counter,
+    /// Number of transactions with a sidecar in the blob store
+    pub(crate) blobstore_entries: Gauge,
+    /// Total size of all sidecars in the blob store in bytes
+    pub(crate) blobstore_byte_size: Gauge,
+}
+
+/// Transaction pool maintenance metrics
+#[derive(Metrics)]
+#[metrics(scope = "transaction_pool")]
+pub struct MaintainPoolMetrics {
+    /// Number of accounts that failed to load and are out of sync with the pool
+    pub(crate) dirty_accounts: Gauge,
+    /// Number of accounts whose state tracked by the pool differed from the actual state when
+    /// they were reloaded
+    pub(crate) drifted_accounts: Counter,
+    /// Number of times the pool was found to be out of sync with the chain, for example after a
+    /// deep reorg or missed notifications
+    pub(crate) drift_count: Counter,
+    /// Number of times all senders of the pool were reloaded from the state
+    pub(crate) resyncs: Counter,
+    /// Number of transactions of reverted blocks that were re-injected into the pool
+    pub(crate) reinserted_transactions: Counter,
 }
@@ -7,6 +7,10 @@ use std::{fmt, marker::PhantomData};
 /// Decides how transactions should be ordered within the pool, depending on a `Priority` value.
 ///
 /// The returned priority must reflect [total order](https://en.wikipedia.org/wiki/Total_order).
+///
+/// The priority may depend on the base fee of the pending block, in which case the pool re-sorts
+/// its pending transactions whenever the base fee changes, see
+/// [TransactionOrdering::depends_on_base_fee].
 pub trait TransactionOrdering: Send + Sync + 'static {
     /// Priority of a transaction.
     ///
@@ -16,8 +20,16 @@ pub trait TransactionOrdering: Send + Sync + 'static {
     /// The transaction type to determine the priority of.
     type Transaction: PoolTransaction;
 
-    /// Returns the priority score for the given transaction.
-    fn priority(&self, transaction: &Self::Transaction) -> Self::Priority;
+    /// Returns the priority score for the given transaction, given the base fee of the pending
+    /// block.
+    fn priority(&self, transaction: &Self::Transaction, base_fee: u128) -> Self::Priority;
+
+    /// Returns whether the priority of a transaction depends on the base fee of the pending block.
+    ///
+    /// If it doesn't, the pool skips re-sorting its pending transactions when the base fee changes.
+    fn depends_on_base_fee(&self) -> bool {
+        true
+    }
 }
 
 /// Default ordering for the pool.
@@ -35,9 +47,13 @@ where
     type Priority = U256;
     type Transaction = T;
 
-    fn priority(&self, transaction: &Self::Transaction) -> Self::Priority {
+    fn priority(&self, transaction: &Self::Transaction, _base_fee: u128) -> Self::Priority {
         transaction.cost()
     }
+
+    fn depends_on_base_fee(&self) -> bool {
+        false
+    }
 }
 
 impl<T> Default for CostOrdering<T> {
@@ -45,3 +61,152 @@ impl<T> Default for CostOrdering<T> {
         Self(Default::default())
     }
 }
+
+/// Orders transactions by the tip per gas the block author receives at the current base fee.
+///
+/// See [PoolTransaction::effective_tip_per_gas]. Transactions with a fee cap below the base fee
+/// have the lowest priority.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct EffectiveTipOrdering<T>(PhantomData<T>);
+
+impl<T> TransactionOrdering for EffectiveTipOrdering<T>
+where
+    T: PoolTransaction + 'static,
+{
+    type Priority = u128;
+    type Transaction = T;
+
+    fn priority(&self, transaction: &Self::Transaction, base_fee: u128) -> Self::Priority {
+        transaction.effective_tip_per_gas(base_fee).unwrap_or_default()
+    }
+}
+
+impl<T> Default for EffectiveTipOrdering<T> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+/// A hook that provides externally determined scores for transactions, for example the bids of a
+/// bundle auction.
+///
+/// Used by [ScoreOrdering].
+pub trait TransactionScorer<T>: Send + Sync + 'static {
+    /// Returns the score of the given transaction at the given base fee, or `None` if there's no
+    /// score for this transaction.
+    fn score(&self, transaction: &T, base_fee: u128) -> Option<U256>;
+}
+
+impl<T, F> TransactionScorer<T> for F
+where
+    F: Fn(&T, u128) -> Option<U256> + Send + Sync + 'static,
+{
+    fn score(&self, transaction: &T, base_fee: u128) -> Option<U256> {
+        self(transaction, base_fee)
+    }
+}
+
+/// The priority assigned by [ScoreOrdering].
+///
+/// Scored transactions always rank higher than transactions without a score, ties are broken by
+/// the effective tip per gas.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ScorePriority {
+    /// The score returned by the [TransactionScorer], if any.
+    pub score: Option<U256>,
+    /// The effective tip per gas at the current base fee.
+    pub effective_tip: u128,
+}
+
+/// Orders transactions by the scores of a [TransactionScorer], falling back to the effective tip
+/// per gas.
+///
+/// Scores are re-evaluated whenever the base fee of the pending block changes.
+pub struct ScoreOrdering<T, S> {
+    scorer: S,
+    _marker: PhantomData<T>,
+}
+
+impl<T, S> ScoreOrdering<T, S> {
+    /// Creates a new ordering that uses the given scorer.
+    pub fn new(scorer: S) -> Self {
+        Self { scorer, _marker: PhantomData }
+    }
+
+    /// Returns the scorer of this ordering.
+    pub fn scorer(&self) -> &S {
+        &self.scorer
+    }
+}
+
+impl<T, S> TransactionOrdering for ScoreOrdering<T, S>
+where
+    T: PoolTransaction + 'static,
+    S: TransactionScorer<T>,
+{
+    type Priority = ScorePriority;
+    type Transaction = T;
+
+    fn priority(&self, transaction: &Self::Transaction, base_fee: u128) -> Self::Priority {
+        ScorePriority {
+            score: self.scorer.score(transaction, base_fee),
+            effective_tip: transaction.effective_tip_per_gas(base_fee).unwrap_or_default(),
+        }
+    }
+}
+
+impl<T, S> fmt::Debug for ScoreOrdering<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScoreOrdering").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockTransaction;
+
+    #[test]
+    fn effective_tip_ordering_respects_base_fee() {
+        let ordering = EffectiveTipOrdering::<MockTransaction>::default();
+
+        // max fee 100, priority fee 10
+        let capped = MockTransaction::eip1559().with_max_fee(100).with_priority_fee(10);
+        // max fee 1000, priority fee 20
+        let generous = MockTransaction::eip1559().with_max_fee(1000).with_priority_fee(20);
+        let legacy = MockTransaction::legacy().with_gas_price(105);
+
+        assert_eq!(ordering.priority(&capped, 50), 10);
+        assert_eq!(ordering.priority(&generous, 50), 20);
+        assert_eq!(ordering.priority(&legacy, 50), 55);
+
+        // the fee cap limits the tip once the base fee rises
+        assert_eq!(ordering.priority(&capped, 95), 5);
+        assert_eq!(ordering.priority(&generous, 95), 20);
+        assert_eq!(ordering.priority(&legacy, 95), 10);
+
+        // below the base fee
+        assert_eq!(ordering.priority(&capped, 101), 0);
+        assert!(ordering.priority(&legacy, 101) < ordering.priority(&generous, 101));
+    }
+
+    #[test]
+    fn score_ordering_prefers_scored_transactions() {
+        let scored = MockTransaction::eip1559().with_max_fee(100).with_priority_fee(1);
+        let scored_hash = *scored.hash();
+        let unscored = MockTransaction::eip1559().with_max_fee(1000).with_priority_fee(100);
+
+        let ordering = ScoreOrdering::<MockTransaction, _>::new(
+            move |tx: &MockTransaction, _base_fee: u128| {
+                (*tx.hash() == scored_hash).then_some(U256::from(1))
+            },
+        );
+
+        assert!(ordering.priority(&scored, 10) > ordering.priority(&unscored, 10));
+        assert_eq!(
+            ordering.priority(&unscored, 10),
+            ScorePriority { score: None, effective_tip: 100 }
+        );
+    }
+}
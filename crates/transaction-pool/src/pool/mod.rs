@@ -32,7 +32,7 @@
 //! fee cap of the transaction needs to be no less than the base fee of block.
 //!
 //!
-//! In essence the transaction pool is made of three separate sub-pools:
+//! In essence the transaction pool is made of four separate sub-pools:
 //!
 //!  - Pending Pool: Contains all transactions that are valid on the current state and satisfy
 //! (3. a)(1): _No_ nonce gaps. A _pending_ transaction is considered _ready_ when it has the lowest
@@ -45,6 +45,10 @@
 //!  - Basefee Pool: To account for the dynamic base fee requirement (3. b) which could render
 //! an EIP-1559 and all subsequent transactions of the sender currently invalid.
 //!
+//!  - Blob Pool: Contains all EIP-4844 blob transactions that are not pending, for example because
+//! their blob fee cap is below the blob fee of the pending block. The sidecars of all blob
+//! transactions are kept in the [`BlobStore`](crate::blobstore::BlobStore).
+//!
 //! The classification of transactions is always dependent on the current state that is changed as
 //! soon as a new block is mined. Once a new block is mined, the account changeset must be applied
 //! to the transaction pool.
@@ -66,8 +70,10 @@
 //!    category (2.) and become pending.
 
 use crate::{
+    blobstore::{BlobAndProof, BlobStore, BlobStoreError},
     error::{PoolError, PoolResult},
     identifier::{SenderId, SenderIdentifiers, TransactionId},
+    metrics::{BlobStoreMetrics, TxValidationMetrics},
     pool::{
         listener::PoolEventBroadcast,
         state::SubPool,
@@ -75,7 +81,7 @@ use crate::{
     },
     traits::{
         AllPoolTransactions, BlockInfo, NewTransactionEvent, PoolSize, PoolTransaction,
-        PropagatedTransactions, TransactionOrigin,
+        PoolTransactionStatus, PropagatedTransactions, TransactionOrigin,
     },
     validate::{TransactionValidationOutcome, ValidPoolTransaction},
     CanonicalStateUpdate, ChangedAccount, PoolConfig, TransactionOrdering, TransactionValidator,
@@ -83,7 +89,10 @@ use crate::{
 use best::BestTransactions;
 pub use events::TransactionEvent;
 use parking_lot::{Mutex, RwLock};
-use reth_primitives::{Address, TxHash, H256};
+use reth_primitives::{
+    Address, BlobTransaction, BlobTransactionSidecar, IntoRecoveredTransaction,
+    PooledTransactionsElement, TxHash, H256,
+};
 use std::{
     collections::{HashMap, HashSet},
     fmt,
@@ -114,6 +123,12 @@ pub struct PoolInner<V: TransactionValidator, T: TransactionOrdering> {
     pool: RwLock<TxPool<T>>,
     /// Pool settings.
     config: PoolConfig,
+    /// Stores the sidecars of blob transactions.
+    blob_store: Arc<dyn BlobStore>,
+    /// Metrics for the blob store.
+    blob_store_metrics: BlobStoreMetrics,
+    /// Metrics for transaction validation.
+    validation_metrics: TxValidationMetrics,
     /// Manages listeners for transaction state change events.
     event_listener: RwLock<PoolEventBroadcast>,
     /// Listeners for new ready transactions.
@@ -130,7 +145,12 @@ where
     T: TransactionOrdering<Transaction = <V as TransactionValidator>::Transaction>,
 {
     /// Create a new transaction pool instance.
-    pub(crate) fn new(validator: V, ordering: T, config: PoolConfig) -> Self {
+    pub(crate) fn new(
+        validator: V,
+        ordering: T,
+        blob_store: Arc<dyn BlobStore>,
+        config: PoolConfig,
+    ) -> Self {
         Self {
             identifiers: Default::default(),
             validator,
@@ -139,6 +159,9 @@ where
             pending_transaction_listener: Default::default(),
             transaction_listener: Default::default(),
             config,
+            blob_store,
+            blob_store_metrics: Default::default(),
+            validation_metrics: Default::default(),
         }
     }
 
@@ -187,6 +210,16 @@ where
         &self.validator
     }
 
+    /// Returns the metrics for transaction validation.
+    pub(crate) fn validation_metrics(&self) -> &TxValidationMetrics {
+        &self.validation_metrics
+    }
+
+    /// Returns the store for the sidecars of blob transactions.
+    pub fn blob_store(&self) -> &Arc<dyn BlobStore> {
+        &self.blob_store
+    }
+
     /// Adds a new transaction listener to the pool that gets notified about every new _pending_
     /// transaction.
     pub fn add_pending_listener(&self) -> mpsc::Receiver<TxHash> {
@@ -222,6 +255,7 @@ where
             hash,
             number,
             pending_block_base_fee,
+            pending_block_blob_fee,
             changed_accounts,
             mined_transactions,
         } = update;
@@ -230,15 +264,44 @@ where
             last_seen_block_hash: hash,
             last_seen_block_number: number,
             pending_basefee: pending_block_base_fee,
+            pending_blob_fee: pending_block_blob_fee,
         };
         let outcome = self.pool.write().on_canonical_state_change(
             block_info,
             mined_transactions,
             changed_senders,
         );
+
+        // sidecars of mined and discarded transactions are no longer needed
+        self.delete_blobs(outcome.mined.iter().chain(&outcome.discarded).copied().collect());
+
         self.notify_on_new_state(outcome);
     }
 
+    /// Updates the state of the given accounts without advancing the pool's block.
+    ///
+    /// Returns the number of accounts whose state differed from the state tracked by the pool.
+    pub(crate) fn update_accounts(&self, accounts: Vec<ChangedAccount>) -> usize {
+        let changed_senders = self.changed_senders(accounts.into_iter());
+        let (drifted, outcome) = {
+            let mut pool = self.pool.write();
+            let drifted = pool.drifted_senders(&changed_senders);
+            (drifted, pool.update_accounts(changed_senders))
+        };
+
+        self.delete_blobs(outcome.discarded.clone());
+        self.notify_on_new_state(outcome);
+
+        drifted
+    }
+
+    /// Returns the addresses of all senders that currently have transactions in the pool.
+    pub(crate) fn unique_senders(&self) -> HashSet<Address> {
+        let senders = self.pool.read().unique_senders();
+        let identifiers = self.identifiers.read();
+        senders.iter().filter_map(|id| identifiers.address(id).copied()).collect()
+    }
+
     /// Add a single validated transaction into the pool.
     ///
     /// Note: this is only used internally by [`Self::add_transactions()`], all new transaction(s)
@@ -249,24 +312,46 @@ where
         tx: TransactionValidationOutcome<T::Transaction>,
     ) -> PoolResult<TxHash> {
         match tx {
-            TransactionValidationOutcome::Valid { balance, state_nonce, transaction } => {
+            TransactionValidationOutcome::Valid { balance, state_nonce, mut transaction } => {
                 let sender_id = self.get_sender_id(transaction.sender());
                 let transaction_id = TransactionId::new(sender_id, transaction.nonce());
                 let encoded_length = transaction.encoded_length();
 
+                // the sidecar is kept in the blob store, not in the pool
+                let tx_hash = *transaction.hash();
+                let blob_sidecar = transaction.take_blob_sidecar();
+                let has_blob_sidecar = blob_sidecar.is_some();
+                if let Some(sidecar) = blob_sidecar {
+                    self.insert_blob(tx_hash, sidecar)?;
+                }
+
                 let tx = ValidPoolTransaction {
                     cost: transaction.cost(),
                     transaction,
                     transaction_id,
-                    propagate: false,
+                    // private transactions must never reach the network
+                    propagate: !origin.is_private(),
                     timestamp: Instant::now(),
                     origin,
                     encoded_length,
                 };
 
-                let added = self.pool.write().add_transaction(tx, balance, state_nonce)?;
+                let added = match self.pool.write().add_transaction(tx, balance, state_nonce) {
+                    Ok(added) => added,
+                    Err(err) => {
+                        if has_blob_sidecar {
+                            self.delete_blob(tx_hash);
+                        }
+                        return Err(err)
+                    }
+                };
                 let hash = *added.hash();
 
+                // the replaced blob transaction's sidecar is no longer needed
+                if let Some(replaced) = added.replaced().filter(|tx| tx.is_eip4844()) {
+                    self.delete_blob(*replaced.hash());
+                }
+
                 // Notify about new pending transactions
                 if let Some(pending_hash) = added.as_pending() {
                     self.on_new_pending_transaction(pending_hash);
@@ -281,11 +366,13 @@ where
                 Ok(hash)
             }
             TransactionValidationOutcome::Invalid(tx, err) => {
+                self.validation_metrics.validation_failures.increment(1);
                 let mut listener = self.event_listener.write();
                 listener.discarded(tx.hash());
                 Err(PoolError::InvalidTransaction(*tx.hash(), err))
             }
             TransactionValidationOutcome::Error(tx, err) => {
+                self.validation_metrics.validation_errors.increment(1);
                 let mut listener = self.event_listener.write();
                 listener.discarded(tx.hash());
                 Err(PoolError::Other(*tx.hash(), err))
@@ -436,6 +523,10 @@ where
     ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
         let removed = self.pool.write().remove_transactions(hashes);
 
+        self.delete_blobs(
+            removed.iter().filter(|tx| tx.is_eip4844()).map(|tx| *tx.hash()).collect(),
+        );
+
         let mut listener = self.event_listener.write();
 
         removed.iter().for_each(|tx| listener.discarded(tx.hash()));
@@ -466,6 +557,16 @@ where
         self.pool.read().get_transactions_by_sender(sender_id)
     }
 
+    /// Returns all transactions of the given sender, together with the reasons why they're
+    /// parked.
+    pub(crate) fn get_transactions_by_sender_with_status(
+        &self,
+        sender: Address,
+    ) -> Vec<PoolTransactionStatus<T::Transaction>> {
+        let sender_id = self.get_sender_id(sender);
+        self.pool.read().get_transactions_by_sender_with_status(sender_id)
+    }
+
     /// Returns all the transactions belonging to the hashes.
     ///
     /// If no transaction exists, it is skipped.
@@ -476,6 +577,100 @@ where
         self.pool.read().get_all(txs).collect()
     }
 
+    /// Returns the transactions for the given hashes in the form in which they're exchanged in
+    /// `PooledTransactions` responses, blob transactions include their sidecar.
+    ///
+    /// Blob transactions without a sidecar in the blob store are skipped.
+    pub(crate) fn get_pooled_transaction_elements(
+        &self,
+        tx_hashes: Vec<TxHash>,
+    ) -> Vec<PooledTransactionsElement> {
+        let transactions = self.get_all(tx_hashes);
+        let mut elements = Vec::with_capacity(transactions.len());
+        // private transactions are never served to peers
+        for tx in transactions.into_iter().filter(|tx| tx.propagate) {
+            let signed = tx.transaction.to_recovered_transaction().into_signed();
+            if tx.is_eip4844() {
+                let sidecar = match self.blob_store.get(*tx.hash()) {
+                    Ok(Some(sidecar)) => sidecar,
+                    Ok(None) => continue,
+                    Err(err) => {
+                        warn!(target: "txpool", %err, tx=?tx.hash(), "failed to read blob sidecar");
+                        continue
+                    }
+                };
+                if let Ok(blob_tx) = BlobTransaction::try_from_signed(signed, sidecar) {
+                    elements.push(PooledTransactionsElement::BlobTransaction(blob_tx));
+                }
+            } else {
+                elements.push(PooledTransactionsElement::Transaction(signed));
+            }
+        }
+        elements
+    }
+
+    /// Returns the sidecar of the blob transaction.
+    pub(crate) fn get_blob(
+        &self,
+        tx_hash: TxHash,
+    ) -> Result<Option<BlobTransactionSidecar>, BlobStoreError> {
+        self.blob_store.get(tx_hash)
+    }
+
+    /// Returns the sidecars of all given blob transactions.
+    pub(crate) fn get_all_blobs(
+        &self,
+        tx_hashes: Vec<TxHash>,
+    ) -> Result<Vec<(TxHash, BlobTransactionSidecar)>, BlobStoreError> {
+        self.blob_store.get_all(tx_hashes)
+    }
+
+    /// Returns the blob for the given versioned hash.
+    pub(crate) fn get_blob_by_versioned_hash(
+        &self,
+        versioned_hash: H256,
+    ) -> Result<Option<BlobAndProof>, BlobStoreError> {
+        self.blob_store.get_by_versioned_hash(versioned_hash)
+    }
+
+    /// Inserts the sidecar of the blob transaction into the blob store.
+    fn insert_blob(&self, tx_hash: TxHash, sidecar: BlobTransactionSidecar) -> PoolResult<()> {
+        if let Err(err) = self.blob_store.insert(tx_hash, sidecar) {
+            warn!(target: "txpool", %err, tx=?tx_hash, "failed to insert blob sidecar");
+            self.blob_store_metrics.blobstore_failed_inserts.increment(1);
+            return Err(PoolError::Other(tx_hash, Box::new(err)))
+        }
+        self.update_blob_store_metrics();
+        Ok(())
+    }
+
+    /// Deletes the sidecar of the blob transaction from the blob store.
+    fn delete_blob(&self, tx_hash: TxHash) {
+        if let Err(err) = self.blob_store.delete(tx_hash) {
+            warn!(target: "txpool", %err, tx=?tx_hash, "failed to delete blob sidecar");
+            self.blob_store_metrics.blobstore_failed_deletes.increment(1);
+        }
+        self.update_blob_store_metrics();
+    }
+
+    /// Deletes the sidecars of all given transactions from the blob store.
+    fn delete_blobs(&self, txs: Vec<TxHash>) {
+        if txs.is_empty() {
+            return
+        }
+        if let Err(err) = self.blob_store.delete_all(txs) {
+            warn!(target: "txpool", %err, "failed to delete blob sidecars");
+            self.blob_store_metrics.blobstore_failed_deletes.increment(1);
+        }
+        self.update_blob_store_metrics();
+    }
+
+    /// Updates the blob store gauges.
+    fn update_blob_store_metrics(&self) {
+        self.blob_store_metrics.blobstore_entries.set(self.blob_store.blobs_len() as f64);
+        self.blob_store_metrics.blobstore_byte_size.set(self.blob_store.data_size_hint() as f64);
+    }
+
     /// Notify about propagated transactions.
     pub(crate) fn on_propagated(&self, txs: PropagatedTransactions) {
         let mut listener = self.event_listener.write();
@@ -495,7 +690,13 @@ where
 
     /// Enforces the size limits of pool and returns the discarded transactions if violated.
     pub(crate) fn discard_worst(&self) -> HashSet<TxHash> {
-        self.pool.write().discard_worst().into_iter().map(|tx| *tx.hash()).collect()
+        let discarded = self.pool.write().discard_worst();
+
+        self.delete_blobs(
+            discarded.iter().filter(|tx| tx.is_eip4844()).map(|tx| *tx.hash()).collect(),
+        );
+
+        discarded.into_iter().map(|tx| *tx.hash()).collect()
     }
 }
 
@@ -510,6 +711,8 @@ impl<V: TransactionValidator, T: TransactionOrdering> fmt::Debug for PoolInner<V
 pub struct AddedPendingTransaction<T: PoolTransaction> {
     /// Inserted transaction.
     transaction: Arc<ValidPoolTransaction<T>>,
+    /// Replaced transaction.
+    replaced: Option<Arc<ValidPoolTransaction<T>>>,
     /// transactions promoted to the ready queue
     promoted: Vec<TxHash>,
     /// transaction that failed and became discarded
@@ -526,6 +729,8 @@ pub enum AddedTransaction<T: PoolTransaction> {
     Parked {
         /// Inserted transaction.
         transaction: Arc<ValidPoolTransaction<T>>,
+        /// Replaced transaction.
+        replaced: Option<Arc<ValidPoolTransaction<T>>>,
         /// The subpool it was moved to.
         subpool: SubPool,
     },
@@ -541,6 +746,14 @@ impl<T: PoolTransaction> AddedTransaction<T> {
         }
     }
 
+    /// Returns the transaction that was replaced by the inserted transaction, if any.
+    pub(crate) fn replaced(&self) -> Option<&Arc<ValidPoolTransaction<T>>> {
+        match self {
+            AddedTransaction::Pending(tx) => tx.replaced.as_ref(),
+            AddedTransaction::Parked { replaced, .. } => replaced.as_ref(),
+        }
+    }
+
     /// Returns the hash of the transaction
     pub(crate) fn hash(&self) -> &TxHash {
         match self {
@@ -555,7 +768,7 @@ impl<T: PoolTransaction> AddedTransaction<T> {
             AddedTransaction::Pending(tx) => {
                 NewTransactionEvent { subpool: SubPool::Pending, transaction: tx.transaction }
             }
-            AddedTransaction::Parked { transaction, subpool } => {
+            AddedTransaction::Parked { transaction, subpool, .. } => {
                 NewTransactionEvent { transaction, subpool }
             }
         }
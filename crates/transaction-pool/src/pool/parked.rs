@@ -80,8 +80,21 @@ impl<T: ParkedOrd> ParkedPool<T> {
     }
 
     /// Removes the worst transaction from this pool.
+    #[cfg(test)]
     pub(crate) fn pop_worst(&mut self) -> Option<Arc<ValidPoolTransaction<T::Transaction>>> {
-        let worst = self.best.iter().next().map(|tx| *tx.transaction.id())?;
+        self.pop_worst_where(|_| true)
+    }
+
+    /// Removes the worst transaction from this pool that matches the given predicate.
+    pub(crate) fn pop_worst_where(
+        &mut self,
+        mut predicate: impl FnMut(&ValidPoolTransaction<T::Transaction>) -> bool,
+    ) -> Option<Arc<ValidPoolTransaction<T::Transaction>>> {
+        let worst = self
+            .best
+            .iter()
+            .find(|tx| predicate(&tx.transaction))
+            .map(|tx| *tx.transaction.id())?;
         self.remove_transaction(&worst)
     }
 
@@ -109,38 +122,27 @@ impl<T: ParkedOrd> ParkedPool<T> {
     }
 }
 
-impl<T: PoolTransaction> ParkedPool<BasefeeOrd<T>> {
-    /// Removes all transactions and their dependent transaction from the subpool that no longer
-    /// satisfy the given basefee.
-    ///
-    /// Note: the transactions are not returned in a particular order.
-    pub(crate) fn enforce_basefee(&mut self, basefee: u128) -> Vec<Arc<ValidPoolTransaction<T>>> {
-        let mut to_remove = Vec::new();
-
-        {
-            let mut iter = self.by_id.iter().peekable();
-
-            while let Some((id, tx)) = iter.next() {
-                if tx.transaction.transaction.max_fee_per_gas() < basefee {
-                    // still parked -> skip descendant transactions
-                    'this: while let Some((peek, _)) = iter.peek() {
-                        if peek.sender != id.sender {
-                            break 'this
-                        }
-                        iter.next();
-                    }
-                } else {
-                    to_remove.push(*id);
-                }
-            }
-        }
+// Additional test impls
+#[cfg(any(test, feature = "test-utils"))]
+impl<T: ParkedOrd> ParkedPool<T> {
+    /// Returns `true` if the transaction with the given id is in this pool.
+    pub(crate) fn contains(&self, id: &TransactionId) -> bool {
+        self.by_id.contains_key(id)
+    }
 
-        let mut removed = Vec::with_capacity(to_remove.len());
-        for id in to_remove {
-            removed.push(self.remove_transaction(&id).expect("transaction exists"));
+    /// Asserts that the `best` and `by_id` sets of this pool are a bijection.
+    ///
+    /// # Panics
+    ///
+    /// If an invariant is violated.
+    pub(crate) fn assert_invariants(&self) {
+        assert_eq!(self.by_id.len(), self.best.len(), "by_id.len() != best.len()");
+        let size = self.by_id.values().map(|tx| tx.transaction.size()).sum::<usize>();
+        assert_eq!(self.size(), size, "tracked size does not match the transactions");
+
+        for (id, tx) in self.by_id.iter() {
+            assert!(self.best.contains(tx), "transaction {id:?} missing in best");
         }
-
-        removed
     }
 }
 
@@ -272,6 +274,29 @@ impl<T: PoolTransaction> Ord for BasefeeOrd<T> {
     }
 }
 
+/// A new type wrapper for [`ValidPoolTransaction`]
+///
+/// This sorts transactions by their blob fee cap first and their fee cap second, so that the
+/// transactions that are least likely to become executable are evicted first.
+///
+/// Caution: This assumes all transaction in the `Blob` sub-pool are blob transactions.
+#[derive(Debug)]
+pub(crate) struct BlobOrd<T: PoolTransaction>(Arc<ValidPoolTransaction<T>>);
+
+impl_ord_wrapper!(BlobOrd);
+
+impl<T: PoolTransaction> Ord for BlobOrd<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0
+            .transaction
+            .max_fee_per_blob_gas()
+            .cmp(&other.0.transaction.max_fee_per_blob_gas())
+            .then_with(|| {
+                self.0.transaction.max_fee_per_gas().cmp(&other.0.transaction.max_fee_per_gas())
+            })
+    }
+}
+
 /// A new type wrapper for [`ValidPoolTransaction`]
 ///
 /// This sorts transactions by their distance.
@@ -301,56 +326,21 @@ mod tests {
     use crate::test_utils::{MockTransaction, MockTransactionFactory};
 
     #[test]
-    fn test_enforce_parked_basefee() {
-        let mut f = MockTransactionFactory::default();
-        let mut pool = ParkedPool::<BasefeeOrd<_>>::default();
-        let tx = f.validated_arc(MockTransaction::eip1559().inc_price());
-        pool.add_transaction(tx.clone());
-
-        assert!(pool.by_id.contains_key(tx.id()));
-        assert_eq!(pool.len(), 1);
-
-        let removed = pool.enforce_basefee(u128::MAX);
-        assert!(removed.is_empty());
-
-        let removed = pool.enforce_basefee(tx.max_fee_per_gas() - 1);
-        assert_eq!(removed.len(), 1);
-        assert!(pool.is_empty());
-    }
-
-    #[test]
-    fn test_enforce_parked_basefee_descendant() {
+    fn test_blob_pool_pop_worst() {
         let mut f = MockTransactionFactory::default();
-        let mut pool = ParkedPool::<BasefeeOrd<_>>::default();
-        let t = MockTransaction::eip1559().inc_price_by(10);
-        let root_tx = f.validated_arc(t.clone());
-        pool.add_transaction(root_tx.clone());
-
-        let descendant_tx = f.validated_arc(t.inc_nonce().inc_price());
-        pool.add_transaction(descendant_tx.clone());
+        let mut pool = ParkedPool::<BlobOrd<_>>::default();
 
-        assert!(pool.by_id.contains_key(root_tx.id()));
-        assert!(pool.by_id.contains_key(descendant_tx.id()));
+        let cheap = f.validated_arc(MockTransaction::eip4844().with_blob_fee(1).inc_price_by(10));
+        let expensive = f.validated_arc(MockTransaction::eip4844().with_blob_fee(10));
+        pool.add_transaction(cheap.clone());
+        pool.add_transaction(expensive.clone());
         assert_eq!(pool.len(), 2);
 
-        let removed = pool.enforce_basefee(u128::MAX);
-        assert!(removed.is_empty());
-
-        // two dependent tx in the pool with decreasing fee
-
-        {
-            let mut pool2 = pool.clone();
-            let removed = pool2.enforce_basefee(descendant_tx.max_fee_per_gas());
-            assert_eq!(removed.len(), 1);
-            assert_eq!(pool2.len(), 1);
-            // descendant got popped
-            assert!(pool2.by_id.contains_key(root_tx.id()));
-            assert!(!pool2.by_id.contains_key(descendant_tx.id()));
-        }
-
-        // remove root transaction via root tx fee
-        let removed = pool.enforce_basefee(root_tx.max_fee_per_gas());
-        assert_eq!(removed.len(), 2);
+        // the lowest blob fee is evicted first, regardless of the fee cap
+        let worst = pool.pop_worst().unwrap();
+        assert_eq!(worst.hash(), cheap.hash());
+        let worst = pool.pop_worst().unwrap();
+        assert_eq!(worst.hash(), expensive.hash());
         assert!(pool.is_empty());
     }
 }
@@ -6,7 +6,7 @@ use crate::{
 
 use std::{
     cmp::Ordering,
-    collections::{BTreeMap, BTreeSet},
+    collections::{BTreeMap, BTreeSet, HashSet},
     sync::Arc,
 };
 
@@ -24,6 +24,8 @@ use std::{
 pub(crate) struct PendingPool<T: TransactionOrdering> {
     /// How to order transactions.
     ordering: T,
+    /// The base fee of the pending block the priorities of the transactions were computed with.
+    base_fee: u128,
     /// Keeps track of transactions inserted in the pool.
     ///
     /// This way we can determine when transactions where submitted to the pool.
@@ -46,10 +48,11 @@ pub(crate) struct PendingPool<T: TransactionOrdering> {
 // === impl PendingPool ===
 
 impl<T: TransactionOrdering> PendingPool<T> {
-    /// Create a new pool instance.
-    pub(crate) fn new(ordering: T) -> Self {
+    /// Create a new pool instance that computes priorities with the given pending base fee.
+    pub(crate) fn new(ordering: T, base_fee: u128) -> Self {
         Self {
             ordering,
+            base_fee,
             submission_id: 0,
             by_id: Default::default(),
             all: Default::default(),
@@ -91,40 +94,46 @@ impl<T: TransactionOrdering> PendingPool<T> {
         self.by_id.values().map(|tx| tx.transaction.transaction.clone())
     }
 
-    /// Removes all transactions and their dependent transaction from the subpool that no longer
-    /// satisfy the given basefee (`tx.fee < basefee`)
+    /// Updates the base fee of the pending block.
     ///
-    /// Note: the transactions are not returned in a particular order.
-    pub(crate) fn enforce_basefee(
-        &mut self,
-        basefee: u128,
-    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
-        let mut to_remove = Vec::new();
-
-        {
-            let mut iter = self.by_id.iter().peekable();
-            while let Some((id, tx)) = iter.next() {
-                if tx.transaction.transaction.max_fee_per_gas() < basefee {
-                    // this transaction no longer satisfies the basefee: remove it and all its
-                    // descendants
-                    to_remove.push(*id);
-                    'this: while let Some((peek, _)) = iter.peek() {
-                        if peek.sender != id.sender {
-                            break 'this
-                        }
-                        to_remove.push(**peek);
-                        iter.next();
-                    }
-                }
-            }
+    /// Since the priority of a transaction can depend on the base fee, this re-computes the
+    /// priorities of all transactions and re-sorts the pool if the base fee changed and the
+    /// ordering depends on it.
+    pub(crate) fn update_base_fee(&mut self, base_fee: u128) {
+        if self.base_fee == base_fee {
+            return
         }
-
-        let mut removed = Vec::with_capacity(to_remove.len());
-        for id in to_remove {
-            removed.push(self.remove_transaction(&id).expect("transaction exists"));
+        self.base_fee = base_fee;
+        if self.ordering.depends_on_base_fee() {
+            self.reprioritize();
         }
+    }
 
-        removed
+    /// Re-computes the priority of all transactions with the current base fee.
+    ///
+    /// Submission ids are retained, so transactions with equal priority keep their relative order.
+    fn reprioritize(&mut self) {
+        let independent = std::mem::take(&mut self.independent_transactions)
+            .into_iter()
+            .map(|tx| *tx.transaction.id())
+            .collect::<HashSet<_>>();
+        self.all.clear();
+
+        for (id, tx) in self.by_id.iter_mut() {
+            let PendingTransactionRef { submission_id, transaction, .. } = &tx.transaction;
+            let priority = self.ordering.priority(&transaction.transaction, self.base_fee);
+            let transaction = PendingTransactionRef {
+                submission_id: *submission_id,
+                transaction: transaction.clone(),
+                priority,
+            };
+
+            if independent.contains(id) {
+                self.independent_transactions.insert(transaction.clone());
+            }
+            self.all.insert(transaction.clone());
+            *tx = Arc::new(PendingTransaction { transaction });
+        }
     }
 
     /// Returns the ancestor the given transaction, the transaction with `nonce - 1`.
@@ -150,7 +159,7 @@ impl<T: TransactionOrdering> PendingPool<T> {
         let tx_id = *tx.id();
         let submission_id = self.next_id();
 
-        let priority = self.ordering.priority(&tx.transaction);
+        let priority = self.ordering.priority(&tx.transaction, self.base_fee);
 
         // keep track of size
         self.size_of += tx.size();
@@ -203,9 +212,13 @@ impl<T: TransactionOrdering> PendingPool<T> {
         id
     }
 
-    /// Removes the worst transaction from this pool.
-    pub(crate) fn pop_worst(&mut self) -> Option<Arc<ValidPoolTransaction<T::Transaction>>> {
-        let worst = self.all.iter().next_back().map(|tx| *tx.transaction.id())?;
+    /// Removes the worst transaction from this pool that matches the given predicate.
+    pub(crate) fn pop_worst_where(
+        &mut self,
+        mut predicate: impl FnMut(&ValidPoolTransaction<T::Transaction>) -> bool,
+    ) -> Option<Arc<ValidPoolTransaction<T::Transaction>>> {
+        let worst =
+            self.all.iter().find(|tx| predicate(&tx.transaction)).map(|tx| *tx.transaction.id())?;
         self.remove_transaction(&worst)
     }
 
@@ -226,6 +239,40 @@ impl<T: TransactionOrdering> PendingPool<T> {
     }
 }
 
+// Additional test impls
+#[cfg(any(test, feature = "test-utils"))]
+impl<T: TransactionOrdering> PendingPool<T> {
+    /// Returns `true` if the transaction with the given id is in this pool.
+    pub(crate) fn contains(&self, id: &TransactionId) -> bool {
+        self.by_id.contains_key(id)
+    }
+
+    /// Asserts that the internal sets of this pool are consistent.
+    ///
+    /// # Panics
+    ///
+    /// If an invariant is violated.
+    pub(crate) fn assert_invariants(&self) {
+        assert_eq!(self.by_id.len(), self.all.len(), "by_id.len() != all.len()");
+        assert!(
+            self.independent_transactions.len() <= self.all.len(),
+            "independent.len() > all.len()"
+        );
+        let size = self.by_id.values().map(|tx| tx.transaction.transaction.size()).sum::<usize>();
+        assert_eq!(self.size(), size, "tracked size does not match the transactions");
+
+        for (id, tx) in self.by_id.iter() {
+            assert!(self.all.contains(&tx.transaction), "transaction {id:?} missing in all");
+            // a transaction is independent iff its ancestor is not pending
+            assert_eq!(
+                self.independent_transactions.contains(&tx.transaction),
+                self.ancestor(id).is_none(),
+                "independent state mismatch for transaction {id:?}"
+            );
+        }
+    }
+}
+
 /// A transaction that is ready to be included in a block.
 pub(crate) struct PendingTransaction<T: TransactionOrdering> {
     /// Reference to the actual transaction.
@@ -293,61 +340,68 @@ impl<T: TransactionOrdering> Ord for PendingTransactionRef<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::{MockOrdering, MockTransaction, MockTransactionFactory};
+    use crate::{
+        test_utils::{MockTransaction, MockTransactionFactory},
+        EffectiveTipOrdering, PoolTransaction,
+    };
+    use reth_primitives::U256;
+    use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
 
     #[test]
-    fn test_enforce_basefee() {
+    fn test_reprioritize_on_base_fee_update() {
         let mut f = MockTransactionFactory::default();
-        let mut pool = PendingPool::new(MockOrdering::default());
-        let tx = f.validated_arc(MockTransaction::eip1559().inc_price());
-        pool.add_transaction(tx.clone());
+        let mut pool = PendingPool::new(EffectiveTipOrdering::<MockTransaction>::default(), 0);
 
-        assert!(pool.by_id.contains_key(tx.id()));
-        assert_eq!(pool.len(), 1);
+        let capped =
+            f.validated_arc(MockTransaction::eip1559().with_max_fee(100).with_priority_fee(10));
+        let generous =
+            f.validated_arc(MockTransaction::eip1559().with_max_fee(1000).with_priority_fee(5));
+        pool.add_transaction(capped.clone());
+        pool.add_transaction(generous.clone());
 
-        let removed = pool.enforce_basefee(0);
-        assert!(removed.is_empty());
+        assert_eq!(pool.best().next().unwrap().hash(), capped.hash());
 
-        let removed = pool.enforce_basefee(tx.max_fee_per_gas() + 1);
-        assert_eq!(removed.len(), 1);
-        assert!(pool.is_empty());
+        // the fee cap of `capped` now limits its tip to 2
+        pool.update_base_fee(98);
+        assert_eq!(pool.len(), 2);
+        assert_eq!(pool.independent_transactions.len(), 2);
+        assert_eq!(pool.best().next().unwrap().hash(), generous.hash());
+
+        pool.update_base_fee(0);
+        assert_eq!(pool.best().next().unwrap().hash(), capped.hash());
     }
 
     #[test]
-    fn test_enforce_basefee_descendant() {
-        let mut f = MockTransactionFactory::default();
-        let mut pool = PendingPool::new(MockOrdering::default());
-        let t = MockTransaction::eip1559().inc_price_by(10);
-        let root_tx = f.validated_arc(t.clone());
-        pool.add_transaction(root_tx.clone());
-
-        let descendant_tx = f.validated_arc(t.inc_nonce().decr_price());
-        pool.add_transaction(descendant_tx.clone());
-
-        assert!(pool.by_id.contains_key(root_tx.id()));
-        assert!(pool.by_id.contains_key(descendant_tx.id()));
-        assert_eq!(pool.len(), 2);
-
-        assert_eq!(pool.independent_transactions.len(), 1);
+    fn test_skip_reprioritize_if_independent_of_base_fee() {
+        /// Orders by cost and counts how often priorities are computed.
+        #[derive(Default)]
+        struct CountingOrdering(AtomicUsize);
+
+        impl TransactionOrdering for CountingOrdering {
+            type Priority = U256;
+            type Transaction = MockTransaction;
+
+            fn priority(&self, transaction: &Self::Transaction, _base_fee: u128) -> Self::Priority {
+                self.0.fetch_add(1, AtomicOrdering::Relaxed);
+                transaction.cost()
+            }
 
-        let removed = pool.enforce_basefee(0);
-        assert!(removed.is_empty());
+            fn depends_on_base_fee(&self) -> bool {
+                false
+            }
+        }
 
-        // two dependent tx in the pool with decreasing fee
+        let mut f = MockTransactionFactory::default();
+        let mut pool = PendingPool::new(CountingOrdering::default(), 7);
+        assert_eq!(pool.base_fee, 7);
 
-        {
-            let mut pool2 = pool.clone();
-            let removed = pool2.enforce_basefee(descendant_tx.max_fee_per_gas() + 1);
-            assert_eq!(removed.len(), 1);
-            assert_eq!(pool2.len(), 1);
-            // descendant got popped
-            assert!(pool2.by_id.contains_key(root_tx.id()));
-            assert!(!pool2.by_id.contains_key(descendant_tx.id()));
-        }
+        pool.add_transaction(f.validated_arc(MockTransaction::eip1559()));
+        pool.add_transaction(f.validated_arc(MockTransaction::eip1559()));
+        assert_eq!(pool.ordering.0.load(AtomicOrdering::Relaxed), 2);
 
-        // remove root transaction via fee
-        let removed = pool.enforce_basefee(root_tx.max_fee_per_gas() + 1);
-        assert_eq!(removed.len(), 2);
-        assert!(pool.is_empty());
+        pool.update_base_fee(98);
+        assert_eq!(pool.base_fee, 98);
+        assert_eq!(pool.ordering.0.load(AtomicOrdering::Relaxed), 2);
+        assert_eq!(pool.len(), 2);
     }
 }
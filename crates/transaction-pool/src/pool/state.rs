@@ -1,3 +1,5 @@
+use crate::traits::ParkedReason;
+
 bitflags::bitflags! {
     /// Marker to represents the current state of a transaction in the pool and from which the corresponding sub-pool is derived, depending on what bits are set.
     ///
@@ -19,6 +21,14 @@ bitflags::bitflags! {
         ///
         /// Set to 1 if `feeCap` of the transaction meets the requirement of the pending block.
         const ENOUGH_FEE_CAP_BLOCK = 0b000010;
+        /// Covers the EIP-4844 blob fee requirement, only relevant for blob transactions.
+        ///
+        /// Set to 1 if `maxFeePerBlobGas` of the transaction meets the blob fee of the pending block.
+        const ENOUGH_BLOB_FEE_CAP_BLOCK = 0b000001;
+        /// Marks the transaction as an EIP-4844 blob transaction.
+        ///
+        /// Blob transactions that are not pending are parked in the blob sub-pool.
+        const BLOB_TRANSACTION = 0b1000000;
 
         const PENDING_POOL_BITS = Self::NO_PARKED_ANCESTORS.bits | Self::NO_NONCE_GAPS.bits | Self::ENOUGH_BALANCE.bits | Self::NOT_TOO_MUCH_GAS.bits |  Self::ENOUGH_FEE_CAP_BLOCK.bits;
 
@@ -36,9 +46,19 @@ impl TxState {
     ///   - _No_ parked ancestors
     ///   - enough balance
     ///   - enough fee cap
+    ///   - enough blob fee cap, if this is a blob transaction
     #[inline]
     pub(crate) fn is_pending(&self) -> bool {
-        *self >= TxState::PENDING_POOL_BITS
+        if self.is_blob() && !self.contains(TxState::ENOUGH_BLOB_FEE_CAP_BLOCK) {
+            return false
+        }
+        self.contains(TxState::PENDING_POOL_BITS)
+    }
+
+    /// Returns `true` if the transaction is a blob transaction.
+    #[inline]
+    pub(crate) fn is_blob(&self) -> bool {
+        self.contains(TxState::BLOB_TRANSACTION)
     }
 
     /// Returns `true` if the transaction has a nonce gap.
@@ -46,6 +66,29 @@ impl TxState {
     pub(crate) fn has_nonce_gap(&self) -> bool {
         !self.intersects(TxState::NO_NONCE_GAPS)
     }
+
+    /// Returns all reasons why a transaction in this state is not pending.
+    pub(crate) fn parked_reasons(&self) -> Vec<ParkedReason> {
+        let mut reasons = Vec::new();
+        if self.has_nonce_gap() {
+            reasons.push(ParkedReason::NonceGap);
+        } else if !self.contains(TxState::NO_PARKED_ANCESTORS) {
+            reasons.push(ParkedReason::ParkedAncestor);
+        }
+        if !self.contains(TxState::ENOUGH_BALANCE) {
+            reasons.push(ParkedReason::InsufficientBalance);
+        }
+        if !self.contains(TxState::NOT_TOO_MUCH_GAS) {
+            reasons.push(ParkedReason::ExceedsBlockGasLimit);
+        }
+        if !self.contains(TxState::ENOUGH_FEE_CAP_BLOCK) {
+            reasons.push(ParkedReason::FeeCapBelowBaseFee);
+        }
+        if self.is_blob() && !self.contains(TxState::ENOUGH_BLOB_FEE_CAP_BLOCK) {
+            reasons.push(ParkedReason::BlobFeeCapBelowBlobFee);
+        }
+        reasons
+    }
 }
 
 /// Identifier for the used Sub-pool
@@ -55,6 +98,7 @@ pub enum SubPool {
     Queued = 0,
     Pending,
     BaseFee,
+    Blob,
 }
 
 // === impl PoolDestination ===
@@ -65,6 +109,11 @@ impl SubPool {
         matches!(self, SubPool::Pending)
     }
 
+    /// Whether this transaction is to be moved to the blob sub-pool.
+    pub fn is_blob(&self) -> bool {
+        matches!(self, SubPool::Blob)
+    }
+
     /// Returns whether this is a promotion depending on the current sub-pool location.
     pub fn is_promoted(&self, other: SubPool) -> bool {
         self > &other
@@ -76,6 +125,10 @@ impl From<TxState> for SubPool {
         if value.is_pending() {
             return SubPool::Pending
         }
+        if value.is_blob() {
+            // all non-pending blob transactions are parked in the blob sub-pool
+            return SubPool::Blob
+        }
         if value < TxState::BASE_FEE_POOL_BITS {
             return SubPool::Queued
         }
@@ -122,4 +175,38 @@ mod tests {
         assert_eq!(SubPool::Pending, state.into());
         assert!(state.is_pending());
     }
+
+    #[test]
+    fn test_tx_blob() {
+        let state = TxState::PENDING_POOL_BITS | TxState::BLOB_TRANSACTION;
+        assert_eq!(SubPool::Blob, state.into());
+        assert!(!state.is_pending());
+
+        let state = state | TxState::ENOUGH_BLOB_FEE_CAP_BLOCK;
+        assert_eq!(SubPool::Pending, state.into());
+        assert!(state.is_pending());
+
+        let state = TxState::BLOB_TRANSACTION | TxState::ENOUGH_BLOB_FEE_CAP_BLOCK;
+        assert_eq!(SubPool::Blob, state.into());
+    }
+
+    #[test]
+    fn test_parked_reasons() {
+        assert!(TxState::PENDING_POOL_BITS.parked_reasons().is_empty());
+
+        let state = TxState::NO_PARKED_ANCESTORS | TxState::NO_NONCE_GAPS | TxState::ENOUGH_BALANCE;
+        assert_eq!(
+            state.parked_reasons(),
+            vec![ParkedReason::ExceedsBlockGasLimit, ParkedReason::FeeCapBelowBaseFee]
+        );
+
+        let state = TxState::NOT_TOO_MUCH_GAS | TxState::ENOUGH_FEE_CAP_BLOCK;
+        assert_eq!(
+            state.parked_reasons(),
+            vec![ParkedReason::NonceGap, ParkedReason::InsufficientBalance]
+        );
+
+        let state = TxState::PENDING_POOL_BITS | TxState::BLOB_TRANSACTION;
+        assert_eq!(state.parked_reasons(), vec![ParkedReason::BlobFeeCapBelowBlobFee]);
+    }
 }
@@ -1,25 +1,29 @@
 //! The internal transaction pool implementation.
 use crate::{
-    config::MAX_ACCOUNT_SLOTS_PER_SENDER,
+    config::{LocalTransactionConfig, PriceBumpConfig, MAX_ACCOUNT_SLOTS_PER_SENDER},
     error::{InvalidPoolTransactionError, PoolError},
     identifier::{SenderId, TransactionId},
     metrics::TxPoolMetrics,
     pool::{
         best::BestTransactions,
-        parked::{BasefeeOrd, ParkedPool, QueuedOrd},
+        parked::{BasefeeOrd, BlobOrd, ParkedPool, QueuedOrd},
         pending::PendingPool,
         state::{SubPool, TxState},
         update::{Destination, PoolUpdate},
         AddedPendingTransaction, AddedTransaction, OnNewCanonicalStateOutcome,
     },
-    traits::{BlockInfo, PoolSize},
-    PoolConfig, PoolResult, PoolTransaction, TransactionOrdering, ValidPoolTransaction, U256,
+    traits::{BlockInfo, PoolSize, PoolTransactionStatus},
+    PoolConfig, PoolResult, PoolTransaction, TransactionOrdering, ValidPoolTransaction,
+    TX_MAX_SIZE, U256,
 };
 use fnv::FnvHashMap;
-use reth_primitives::{constants::MIN_PROTOCOL_BASE_FEE, TxHash, H256};
+use reth_primitives::{
+    constants::{BLOB_TX_MIN_BLOB_GASPRICE, MIN_PROTOCOL_BASE_FEE},
+    TxHash, H256,
+};
 use std::{
     cmp::Ordering,
-    collections::{btree_map::Entry, hash_map, BTreeMap, HashMap},
+    collections::{btree_map::Entry, hash_map, BTreeMap, HashMap, HashSet},
     fmt,
     ops::Bound::{Excluded, Unbounded},
     sync::Arc,
@@ -40,6 +44,7 @@ use std::{
 ///         B3[(Queued)]
 ///         B1[(Pending)]
 ///         B2[(Basefee)]
+///         B4[(Blob)]
 ///     end
 ///   end
 ///   discard([discard])
@@ -49,14 +54,18 @@ use std::{
 ///   pool --> |if ready| B1
 ///   pool --> |if ready + basfee too low| B2
 ///   pool --> |nonce gap or lack of funds| B3
+///   pool --> |blob tx not ready| B4
 ///   pool --> |update| pool
 ///   B1 --> |best| production
 ///   B2 --> |worst| discard
 ///   B3 --> |worst| discard
+///   B4 --> |lowest blob fee| discard
 ///   B1 --> |increased fee| B2
 ///   B2 --> |decreased fee| B1
 ///   B3 --> |promote| B1
 ///   B3 -->  |promote| B2
+///   B1 --> |increased blob fee| B4
+///   B4 --> |decreased blob fee| B1
 ///   new -->  |apply state changes| pool
 /// ```
 pub struct TxPool<T: TransactionOrdering> {
@@ -80,6 +89,13 @@ pub struct TxPool<T: TransactionOrdering> {
     /// Holds all parked transactions that currently violate the dynamic fee requirement but could
     /// be moved to pending if the base fee changes in their favor (decreases) in future blocks.
     basefee_pool: ParkedPool<BasefeeOrd<T::Transaction>>,
+    /// blob subpool
+    ///
+    /// Holds all EIP-4844 blob transactions that are not pending, for example because their blob
+    /// fee cap is below the blob fee of the pending block. Blob transactions are kept separate
+    /// because their sidecars make them much more expensive to keep around, this pool has its own
+    /// limits and evicts by blob fee.
+    blob_pool: ParkedPool<BlobOrd<T::Transaction>>,
     /// All transactions in the pool.
     all_transactions: AllTransactions<T::Transaction>,
     /// Transaction pool metrics
@@ -91,12 +107,14 @@ pub struct TxPool<T: TransactionOrdering> {
 impl<T: TransactionOrdering> TxPool<T> {
     /// Create a new graph pool instance.
     pub(crate) fn new(ordering: T, config: PoolConfig) -> Self {
+        let all_transactions = AllTransactions::new(&config);
         Self {
             sender_info: Default::default(),
-            pending_pool: PendingPool::new(ordering),
+            pending_pool: PendingPool::new(ordering, all_transactions.pending_basefee),
             queued_pool: Default::default(),
             basefee_pool: Default::default(),
-            all_transactions: AllTransactions::new(config.max_account_slots),
+            blob_pool: Default::default(),
+            all_transactions,
             config,
             metrics: Default::default(),
         }
@@ -116,6 +134,8 @@ impl<T: TransactionOrdering> TxPool<T> {
             basefee_size: self.basefee_pool.size(),
             queued: self.queued_pool.len(),
             queued_size: self.queued_pool.size(),
+            blob: self.blob_pool.len(),
+            blob_size: self.blob_pool.size(),
         }
     }
 
@@ -125,55 +145,63 @@ impl<T: TransactionOrdering> TxPool<T> {
             last_seen_block_hash: self.all_transactions.last_seen_block_hash,
             last_seen_block_number: self.all_transactions.last_seen_block_number,
             pending_basefee: self.all_transactions.pending_basefee,
+            pending_blob_fee: self.all_transactions.pending_blob_fee,
         }
     }
 
     /// Updates the tracked basefee
     ///
-    /// Depending on the change in direction of the basefee, this will promote or demote
-    /// transactions from the basefee pool.
+    /// Depending on the change in direction of the basefee, this will promote transactions from
+    /// the basefee pool or demote them from the pending pool.
+    ///
+    /// The pending pool is re-sorted first, since the priority of its transactions can depend on
+    /// the basefee.
     fn update_basefee(&mut self, pending_basefee: u128) {
-        match pending_basefee.cmp(&self.all_transactions.pending_basefee) {
-            Ordering::Equal => {
-                // fee unchanged, nothing to update
-            }
-            Ordering::Greater => {
-                // increased base fee: recheck pending pool and remove all that are no longer valid
-                for tx in self.pending_pool.enforce_basefee(pending_basefee) {
-                    let to = {
-                        let tx =
-                            self.all_transactions.txs.get_mut(tx.id()).expect("tx exists in set");
-                        tx.state.remove(TxState::ENOUGH_FEE_CAP_BLOCK);
-                        tx.subpool = tx.state.into();
-                        tx.subpool
-                    };
-                    self.add_transaction_to_subpool(to, tx);
-                }
-            }
-            Ordering::Less => {
-                // decreased base fee: recheck basefee pool and promote all that are now valid
-                for tx in self.basefee_pool.enforce_basefee(pending_basefee) {
-                    let to = {
-                        let tx =
-                            self.all_transactions.txs.get_mut(tx.id()).expect("tx exists in set");
-                        tx.state.insert(TxState::ENOUGH_FEE_CAP_BLOCK);
-                        tx.subpool = tx.state.into();
-                        tx.subpool
-                    };
-                    self.add_transaction_to_subpool(to, tx);
-                }
-            }
+        self.pending_pool.update_base_fee(pending_basefee);
+        if pending_basefee == self.all_transactions.pending_basefee {
+            // fee unchanged, nothing to update
+            return
+        }
+        self.all_transactions.pending_basefee = pending_basefee;
+
+        // recheck all transactions, since descendants of a transaction are affected by its state
+        // as well: a demoted transaction parks all its descendants and a descendant must not be
+        // promoted before its ancestors
+        let updates = self.all_transactions.update(Default::default());
+        self.process_updates(updates);
+    }
+
+    /// Updates the tracked blob fee
+    ///
+    /// Depending on the change in direction of the blob fee, this will promote blob transactions
+    /// from the blob pool or demote them from the pending pool.
+    fn update_blob_fee(&mut self, pending_blob_fee: Option<u128>) {
+        if pending_blob_fee == self.all_transactions.pending_blob_fee {
+            // fee unchanged, nothing to update
+            return
         }
+        self.all_transactions.pending_blob_fee = pending_blob_fee;
+
+        // recheck all transactions, since descendants of a blob transaction are affected by its
+        // state as well
+        let updates = self.all_transactions.update(Default::default());
+        self.process_updates(updates);
     }
 
     /// Sets the current block info for the pool.
     ///
     /// This will also apply updates to the pool based on the new base fee
     pub(crate) fn set_block_info(&mut self, info: BlockInfo) {
-        let BlockInfo { last_seen_block_hash, last_seen_block_number, pending_basefee } = info;
+        let BlockInfo {
+            last_seen_block_hash,
+            last_seen_block_number,
+            pending_basefee,
+            pending_blob_fee,
+        } = info;
         self.all_transactions.last_seen_block_hash = last_seen_block_hash;
         self.all_transactions.last_seen_block_number = last_seen_block_number;
-        self.update_basefee(pending_basefee)
+        self.update_basefee(pending_basefee);
+        self.update_blob_fee(pending_blob_fee)
     }
 
     /// Returns an iterator that yields transactions that are ready to be included in the block.
@@ -190,6 +218,7 @@ impl<T: TransactionOrdering> TxPool<T> {
     pub(crate) fn queued_transactions(&self) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
         let mut queued = self.basefee_pool.all().collect::<Vec<_>>();
         queued.extend(self.queued_pool.all());
+        queued.extend(self.blob_pool.all());
         queued
     }
 
@@ -222,6 +251,21 @@ impl<T: TransactionOrdering> TxPool<T> {
         self.all_transactions.txs_iter(sender).map(|(_, tx)| Arc::clone(&tx.transaction)).collect()
     }
 
+    /// Returns all transactions from a given sender, together with the reasons why they're
+    /// parked.
+    pub(crate) fn get_transactions_by_sender_with_status(
+        &self,
+        sender: SenderId,
+    ) -> Vec<PoolTransactionStatus<T::Transaction>> {
+        self.all_transactions
+            .txs_iter(sender)
+            .map(|(_, tx)| PoolTransactionStatus {
+                transaction: Arc::clone(&tx.transaction),
+                parked_reasons: tx.state.parked_reasons(),
+            })
+            .collect()
+    }
+
     /// Updates the entire pool after a new block was mined.
     ///
     /// This removes all mined transactions, updates according to the new base fee and rechecks
@@ -238,12 +282,14 @@ impl<T: TransactionOrdering> TxPool<T> {
         // update block info
         let block_hash = block_info.last_seen_block_hash;
         self.all_transactions.set_block_info(block_info);
+        self.pending_pool.update_base_fee(self.all_transactions.pending_basefee);
 
         // Remove all transaction that were included in the block
         for tx_hash in mined_transactions.iter() {
             if self.prune_transaction_by_hash(tx_hash).is_some() {
                 // Update removed transactions metric
                 self.metrics.removed_transactions.increment(1);
+                self.metrics.mined_transactions.increment(1);
             }
         }
 
@@ -251,7 +297,11 @@ impl<T: TransactionOrdering> TxPool<T> {
         let updates = self.all_transactions.update(changed_senders);
 
         // Process the sub-pool updates
-        let UpdateOutcome { promoted, discarded } = self.process_updates(updates);
+        let UpdateOutcome { promoted, mut discarded } = self.process_updates(updates);
+
+        // Discard all private transactions that can no longer be included in the next block
+        let next_block = self.all_transactions.last_seen_block_number + 1;
+        discarded.extend(self.remove_expired(next_block).iter().map(|tx| *tx.hash()));
 
         // update the metrics after the update
         self.update_size_metrics();
@@ -259,6 +309,68 @@ impl<T: TransactionOrdering> TxPool<T> {
         OnNewCanonicalStateOutcome { block_hash, mined: mined_transactions, promoted, discarded }
     }
 
+    /// Updates the state of the given senders without advancing the pool's block.
+    ///
+    /// This is used to heal the pool if the state it tracks drifted from the actual state, all
+    /// affected transactions are moved between the sub-pools and stale transactions are discarded.
+    pub(crate) fn update_accounts(
+        &mut self,
+        changed_senders: HashMap<SenderId, SenderInfo>,
+    ) -> OnNewCanonicalStateOutcome {
+        self.sender_info.extend(changed_senders.clone());
+
+        let updates = self.all_transactions.update(changed_senders);
+        let UpdateOutcome { promoted, discarded } = self.process_updates(updates);
+
+        self.update_size_metrics();
+
+        OnNewCanonicalStateOutcome {
+            block_hash: self.all_transactions.last_seen_block_hash,
+            mined: Vec::new(),
+            promoted,
+            discarded,
+        }
+    }
+
+    /// Returns the number of the given senders whose state differs from the state tracked by the
+    /// pool.
+    pub(crate) fn drifted_senders(&self, senders: &HashMap<SenderId, SenderInfo>) -> usize {
+        senders.iter().filter(|(id, info)| self.sender_info.get(id) != Some(info)).count()
+    }
+
+    /// Returns all senders that currently have transactions in the pool.
+    pub(crate) fn unique_senders(&self) -> HashSet<SenderId> {
+        self.all_transactions.txs.keys().map(|id| id.sender).collect()
+    }
+
+    /// Removes all private transactions that can't be included in the block with the given number
+    /// anymore, together with their descendants.
+    fn remove_expired(
+        &mut self,
+        block_number: u64,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        let expired = self
+            .all_transactions
+            .txs
+            .iter()
+            .filter(|(_, tx)| tx.transaction.origin.is_expired(block_number))
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+
+        let mut removed = Vec::new();
+        for id in expired {
+            // the transaction is already removed if it's a descendant of an expired transaction
+            if let Some(tx) = self.remove_transaction(&id) {
+                self.metrics.discarded_expired_transactions.increment(1);
+                removed.push(tx);
+                self.remove_descendants(&id, &mut removed);
+            }
+        }
+        self.metrics.removed_transactions.increment(removed.len() as u64);
+
+        removed
+    }
+
     /// Update sub-pools size metrics.
     pub(crate) fn update_size_metrics(&mut self) {
         let stats = self.size();
@@ -268,6 +380,9 @@ impl<T: TransactionOrdering> TxPool<T> {
         self.metrics.basefee_pool_size_bytes.set(stats.basefee_size as f64);
         self.metrics.queued_pool_transactions.set(stats.queued as f64);
         self.metrics.queued_pool_size_bytes.set(stats.queued_size as f64);
+        self.metrics.blob_pool_transactions.set(stats.blob as f64);
+        self.metrics.blob_pool_size_bytes.set(stats.blob_size as f64);
+        self.metrics.total_transactions.set(self.all_transactions.len() as f64);
     }
 
     /// Adds the transaction into the pool.
@@ -290,6 +405,9 @@ impl<T: TransactionOrdering> TxPool<T> {
     /// the sender's balance or nonce and instead their `feeCap` determines whether the
     /// transaction is _currently_ (on the current state) ready or needs to be parked until the
     /// `feeCap` satisfies the block's `baseFee`.
+    ///
+    /// The `Blob` pool contains all EIP-4844 blob transactions that are not pending. Their
+    /// sidecars are not part of the pool, see [BlobStore](crate::blobstore::BlobStore).
     pub(crate) fn add_transaction(
         &mut self,
         tx: ValidPoolTransaction<T::Transaction>,
@@ -308,20 +426,25 @@ impl<T: TransactionOrdering> TxPool<T> {
 
         match self.all_transactions.insert_tx(tx, on_chain_balance, on_chain_nonce) {
             Ok(InsertOk { transaction, move_to, replaced_tx, updates, .. }) => {
+                let replaced = replaced_tx.as_ref().map(|(tx, _)| tx.clone());
                 self.add_new_transaction(transaction.clone(), replaced_tx, move_to);
                 // Update inserted transactions metric
                 self.metrics.inserted_transactions.increment(1);
+                if replaced.is_some() {
+                    self.metrics.replaced_transactions.increment(1);
+                }
                 let UpdateOutcome { promoted, discarded } = self.process_updates(updates);
 
                 // This transaction was moved to the pending pool.
                 let res = if move_to.is_pending() {
                     AddedTransaction::Pending(AddedPendingTransaction {
                         transaction,
+                        replaced,
                         promoted,
                         discarded,
                     })
                 } else {
-                    AddedTransaction::Parked { transaction, subpool: move_to }
+                    AddedTransaction::Parked { transaction, replaced, subpool: move_to }
                 };
 
                 Ok(res)
@@ -331,25 +454,52 @@ impl<T: TransactionOrdering> TxPool<T> {
                 self.metrics.invalid_transactions.increment(1);
                 match e {
                     InsertErr::Underpriced { existing, transaction: _ } => {
+                        self.metrics.rejected_underpriced_replacements.increment(1);
                         Err(PoolError::ReplacementUnderpriced(existing))
                     }
-                    InsertErr::FeeCapBelowMinimumProtocolFeeCap { transaction, fee_cap } => Err(
-                        PoolError::FeeCapBelowMinimumProtocolFeeCap(*transaction.hash(), fee_cap),
-                    ),
+                    InsertErr::FeeCapBelowMinimumProtocolFeeCap { transaction, fee_cap } => {
+                        self.metrics.rejected_fee_cap_below_minimum.increment(1);
+                        Err(PoolError::FeeCapBelowMinimumProtocolFeeCap(
+                            *transaction.hash(),
+                            fee_cap,
+                        ))
+                    }
                     InsertErr::ExceededSenderTransactionsCapacity { transaction } => {
+                        self.metrics.rejected_sender_capacity_exceeded.increment(1);
                         Err(PoolError::SpammerExceededCapacity(
                             transaction.sender(),
                             *transaction.hash(),
                         ))
                     }
+                    InsertErr::PriorityFeeBelowMinimum { transaction, minimal_priority_fee } => {
+                        self.metrics.rejected_priority_fee_below_minimum.increment(1);
+                        Err(PoolError::PriorityFeeBelowMinimum(
+                            *transaction.hash(),
+                            minimal_priority_fee,
+                        ))
+                    }
+                    InsertErr::ExceededMaxTransactionSize { transaction, size, max_size } => {
+                        self.metrics.rejected_oversized_transactions.increment(1);
+                        Err(PoolError::ExceededMaxTransactionSize(
+                            *transaction.hash(),
+                            size,
+                            max_size,
+                        ))
+                    }
                     InsertErr::TxGasLimitMoreThanAvailableBlockGas {
                         transaction,
                         block_gas_limit,
                         tx_gas_limit,
-                    } => Err(PoolError::InvalidTransaction(
-                        *transaction.hash(),
-                        InvalidPoolTransactionError::ExceedsGasLimit(block_gas_limit, tx_gas_limit),
-                    )),
+                    } => {
+                        self.metrics.rejected_gas_limit_exceeded.increment(1);
+                        Err(PoolError::InvalidTransaction(
+                            *transaction.hash(),
+                            InvalidPoolTransactionError::ExceedsGasLimit(
+                                block_gas_limit,
+                                tx_gas_limit,
+                            ),
+                        ))
+                    }
                 }
             }
         }
@@ -364,6 +514,12 @@ impl<T: TransactionOrdering> TxPool<T> {
             let PoolUpdate { id, hash, current, destination } = update;
             match destination {
                 Destination::Discard => {
+                    // stale transaction, for example its nonce is already used on chain, so the
+                    // sub-pool can advance its descendant like it would for a mined transaction
+                    if self.prune_transaction(&id).is_some() {
+                        self.metrics.removed_transactions.increment(1);
+                        self.metrics.discarded_stale_transactions.increment(1);
+                    }
                     outcome.discarded.push(hash);
                 }
                 Destination::Pool(move_to) => {
@@ -410,6 +566,16 @@ impl<T: TransactionOrdering> TxPool<T> {
         self.remove_from_subpool(pool, tx.id())
     }
 
+    /// Removes the transaction from the entire pool and advances any descendant state inside the
+    /// sub-pool, with the expectation that the transaction's nonce was used on chain.
+    fn prune_transaction(
+        &mut self,
+        id: &TransactionId,
+    ) -> Option<Arc<ValidPoolTransaction<T::Transaction>>> {
+        let (tx, pool) = self.all_transactions.remove_transaction(id)?;
+        self.prune_from_subpool(pool, tx.id())
+    }
+
     /// Remove the transaction from the entire pool via its hash.
     ///
     /// This includes the total set of transactions and the subpool it currently resides in.
@@ -446,6 +612,7 @@ impl<T: TransactionOrdering> TxPool<T> {
             SubPool::Queued => self.queued_pool.remove_transaction(tx),
             SubPool::Pending => self.pending_pool.remove_transaction(tx),
             SubPool::BaseFee => self.basefee_pool.remove_transaction(tx),
+            SubPool::Blob => self.blob_pool.remove_transaction(tx),
         }
     }
 
@@ -460,6 +627,7 @@ impl<T: TransactionOrdering> TxPool<T> {
             SubPool::Queued => self.queued_pool.remove_transaction(tx),
             SubPool::Pending => self.pending_pool.prune_transaction(tx),
             SubPool::BaseFee => self.basefee_pool.remove_transaction(tx),
+            SubPool::Blob => self.blob_pool.remove_transaction(tx),
         }
     }
 
@@ -504,6 +672,9 @@ impl<T: TransactionOrdering> TxPool<T> {
             SubPool::BaseFee => {
                 self.basefee_pool.add_transaction(tx);
             }
+            SubPool::Blob => {
+                self.blob_pool.add_transaction(tx);
+            }
         }
     }
 
@@ -523,10 +694,12 @@ impl<T: TransactionOrdering> TxPool<T> {
         self.add_transaction_to_subpool(pool, transaction)
     }
 
-    /// Ensures that the transactions in the sub-pools are within the given bounds.
+    /// Ensures that the transactions in the sub-pools and the entire pool are within the
+    /// configured bounds.
     ///
     /// If the current size exceeds the given bounds, the worst transactions are evicted from the
-    /// pool and returned.
+    /// pool and returned. Local transactions count towards the limits but are never evicted, see
+    /// [LocalTransactionConfig].
     pub(crate) fn discard_worst(&mut self) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
         let mut removed = Vec::new();
 
@@ -539,11 +712,12 @@ impl<T: TransactionOrdering> TxPool<T> {
                         .$limit
                         .is_exceeded($this.$pool.len(), $this.$pool.size())
                     {
-                        if let Some(tx) = $this.$pool.pop_worst() {
-                            let id = tx.transaction_id;
-                            removed.push(tx);
-                            $this.remove_descendants(&id, &mut $removed);
-                        }
+                        let local = &$this.config.local_transactions;
+                        let Some(tx) = $this.$pool.pop_worst_where(|tx| !local.is_exempt(tx))
+                        else {
+                            break
+                        };
+                        $this.evict(tx, &mut $removed);
                     }
 
                 )*
@@ -554,13 +728,50 @@ impl<T: TransactionOrdering> TxPool<T> {
             self, removed, [
                 pending_limit  => pending_pool,
                 basefee_limit  => basefee_pool,
-                queued_limit  => queued_pool
+                queued_limit  => queued_pool,
+                blob_limit  => blob_pool
             ]
         );
+        let evicted_subpool_limit = removed.len();
+        self.metrics.evicted_subpool_limit_transactions.increment(evicted_subpool_limit as u64);
+
+        // Helper macro that enforces the global limit, starting with the least valuable pools
+        macro_rules! discard_worst_global {
+            ($this:ident, $removed:ident,  [$($pool:ident),*]  ) => {
+                $ (
+                while $this.all_transactions.len() > $this.config.max_total_transactions {
+                    let local = &$this.config.local_transactions;
+                    let Some(tx) = $this.$pool.pop_worst_where(|tx| !local.is_exempt(tx)) else {
+                        break
+                    };
+                    $this.evict(tx, &mut $removed);
+                }
+                )*
+            };
+        }
+
+        discard_worst_global!(self, removed, [queued_pool, basefee_pool, blob_pool, pending_pool]);
+        self.metrics
+            .evicted_global_limit_transactions
+            .increment((removed.len() - evicted_subpool_limit) as u64);
+        self.metrics.removed_transactions.increment(removed.len() as u64);
 
         removed
     }
 
+    /// Removes the transaction that was popped from its sub-pool and all its descendants from the
+    /// entire pool.
+    fn evict(
+        &mut self,
+        tx: Arc<ValidPoolTransaction<T::Transaction>>,
+        removed: &mut Vec<Arc<ValidPoolTransaction<T::Transaction>>>,
+    ) {
+        let id = *tx.id();
+        self.all_transactions.remove_transaction(&id);
+        removed.push(tx);
+        self.remove_descendants(&id, removed);
+    }
+
     /// Number of transactions in the entire pool
     pub(crate) fn len(&self) -> usize {
         self.all_transactions.len()
@@ -576,6 +787,10 @@ impl<T: TransactionOrdering> TxPool<T> {
 #[cfg(any(test, feature = "test-utils"))]
 #[allow(missing_docs)]
 impl<T: TransactionOrdering> TxPool<T> {
+    pub(crate) fn config(&self) -> &PoolConfig {
+        &self.config
+    }
+
     pub(crate) fn pending(&self) -> &PendingPool<T> {
         &self.pending_pool
     }
@@ -587,6 +802,80 @@ impl<T: TransactionOrdering> TxPool<T> {
     pub(crate) fn queued(&self) -> &ParkedPool<QueuedOrd<T::Transaction>> {
         &self.queued_pool
     }
+
+    pub(crate) fn blob(&self) -> &ParkedPool<BlobOrd<T::Transaction>> {
+        &self.blob_pool
+    }
+
+    /// Asserts that the state of all transactions is consistent with the sub-pools they're in.
+    ///
+    /// # Panics
+    ///
+    /// If an invariant is violated.
+    pub(crate) fn assert_invariants(&self) {
+        let size = self.size();
+        assert_eq!(
+            self.all_transactions.len(),
+            size.pending + size.basefee + size.queued + size.blob,
+            "Tx in AllTransactions and sum(subpools) must match"
+        );
+        assert_eq!(
+            self.all_transactions.by_hash.len(),
+            self.all_transactions.txs.len(),
+            "by_hash.len() != txs.len()"
+        );
+
+        for (id, tx) in self.all_transactions.txs.iter() {
+            assert_eq!(tx.subpool, SubPool::from(tx.state), "sub-pool mismatch for {id:?}");
+            assert!(self.all_transactions.by_hash.contains_key(tx.transaction.hash()));
+            let in_subpool = match tx.subpool {
+                SubPool::Pending => self.pending_pool.contains(id),
+                SubPool::BaseFee => self.basefee_pool.contains(id),
+                SubPool::Queued => self.queued_pool.contains(id),
+                SubPool::Blob => self.blob_pool.contains(id),
+            };
+            assert!(in_subpool, "transaction {id:?} missing in {:?} sub-pool", tx.subpool);
+
+            if tx.subpool.is_pending() {
+                assert!(
+                    tx.transaction.max_fee_per_gas() >= self.all_transactions.pending_basefee,
+                    "pending transaction {id:?} below base fee"
+                );
+                // pending transactions must be gapless
+                let ancestor = id.unchecked_ancestor();
+                match ancestor.and_then(|ancestor| self.all_transactions.txs.get(&ancestor)) {
+                    Some(ancestor) => assert!(
+                        ancestor.subpool.is_pending(),
+                        "pending transaction {id:?} has a parked ancestor"
+                    ),
+                    None => assert_eq!(
+                        self.sender_info.get(&id.sender).map(|info| info.state_nonce),
+                        Some(id.nonce),
+                        "nonce gap in pending transaction {id:?}"
+                    ),
+                }
+            }
+        }
+
+        self.pending_pool.assert_invariants();
+        self.basefee_pool.assert_invariants();
+        self.queued_pool.assert_invariants();
+        self.blob_pool.assert_invariants();
+    }
+
+    /// Asserts that all sub-pools and the entire pool are within the configured limits.
+    ///
+    /// # Panics
+    ///
+    /// If a limit is exceeded.
+    pub(crate) fn assert_limits(&self) {
+        let size = self.size();
+        assert!(!self.config.pending_limit.is_exceeded(size.pending, size.pending_size));
+        assert!(!self.config.basefee_limit.is_exceeded(size.basefee, size.basefee_size));
+        assert!(!self.config.queued_limit.is_exceeded(size.queued, size.queued_size));
+        assert!(!self.config.blob_limit.is_exceeded(size.blob, size.blob_size));
+        assert!(self.all_transactions.len() <= self.config.max_total_transactions);
+    }
 }
 
 impl<T: TransactionOrdering> fmt::Debug for TxPool<T> {
@@ -608,6 +897,14 @@ pub(crate) struct AllTransactions<T: PoolTransaction> {
     block_gas_limit: u64,
     /// Max number of executable transaction slots guaranteed per account
     max_account_slots: usize,
+    /// Minimum price bumps required to replace a transaction.
+    price_bumps: PriceBumpConfig,
+    /// Minimum priority fee required for acceptance into the pool.
+    minimal_priority_fee: u128,
+    /// Max size of a transaction in its network encoding.
+    max_tx_size: usize,
+    /// Which transactions are considered local.
+    local_transactions: LocalTransactionConfig,
     /// _All_ transactions identified by their hash.
     by_hash: HashMap<TxHash, Arc<ValidPoolTransaction<T>>>,
     /// _All_ transaction in the pool sorted by their sender and nonce pair.
@@ -620,12 +917,21 @@ pub(crate) struct AllTransactions<T: PoolTransaction> {
     last_seen_block_hash: H256,
     /// Expected base fee for the pending block.
     pending_basefee: u128,
+    /// Expected blob fee for the pending block, `None` if blob transactions are not enabled.
+    pending_blob_fee: Option<u128>,
 }
 
 impl<T: PoolTransaction> AllTransactions<T> {
     /// Create a new instance
-    fn new(max_account_slots: usize) -> Self {
-        Self { max_account_slots, ..Default::default() }
+    fn new(config: &PoolConfig) -> Self {
+        Self {
+            max_account_slots: config.max_account_slots,
+            price_bumps: config.price_bumps,
+            minimal_priority_fee: config.minimal_priority_fee,
+            max_tx_size: config.max_tx_size,
+            local_transactions: config.local_transactions.clone(),
+            ..Default::default()
+        }
     }
 
     /// Returns an iterator over all _unique_ hashes in the pool
@@ -671,11 +977,16 @@ impl<T: PoolTransaction> AllTransactions<T> {
 
     /// Updates the block specific info
     fn set_block_info(&mut self, block_info: BlockInfo) {
-        let BlockInfo { last_seen_block_hash, last_seen_block_number, pending_basefee } =
-            block_info;
+        let BlockInfo {
+            last_seen_block_hash,
+            last_seen_block_number,
+            pending_basefee,
+            pending_blob_fee,
+        } = block_info;
         self.last_seen_block_number = last_seen_block_number;
         self.last_seen_block_hash = last_seen_block_hash;
         self.pending_basefee = pending_basefee;
+        self.pending_blob_fee = pending_blob_fee;
     }
 
     /// Rechecks all transactions in the pool against the changes.
@@ -685,6 +996,8 @@ impl<T: PoolTransaction> AllTransactions<T> {
     /// For all transactions:
     ///   - decreased basefee: promotes from `basefee` to `pending` sub-pool.
     ///   - increased basefee: demotes from `pending` to `basefee` sub-pool.
+    ///   - decreased blob fee: promotes blob transactions from `blob` to `pending` sub-pool.
+    ///   - increased blob fee: demotes blob transactions from `pending` to `blob` sub-pool.
     /// Individually:
     ///   - decreased sender allowance: demote from (`basefee`|`pending`) to `queued`.
     ///   - increased sender allowance: promote from `queued` to
@@ -750,14 +1063,29 @@ impl<T: PoolTransaction> AllTransactions<T> {
                     } else {
                         tx.state.insert(TxState::ENOUGH_BALANCE);
                     }
+                } else {
+                    // This is the first transaction of the sender in the pool, but not the next
+                    // transaction on chain, for example if the nonce decreased after a reorg.
+                    tx.state.remove(TxState::NO_NONCE_GAPS);
                 }
 
                 changed_balance = Some(info.balance);
             }
 
-            // If there's a nonce gap, we can shortcircuit, because there's nothing to update yet.
+            // If there's a nonce gap, all transactions of this sender are parked until the gap is
+            // closed.
             if tx.state.has_nonce_gap() {
-                next_sender!(iter);
+                tx.state.remove(TxState::NO_PARKED_ANCESTORS);
+                Self::record_subpool_update(&mut updates, tx);
+                while let Some((peek, ref mut tx)) = iter.peek_mut() {
+                    if peek.sender != id.sender {
+                        continue 'transactions
+                    }
+                    tx.state.remove(TxState::NO_NONCE_GAPS);
+                    tx.state.remove(TxState::NO_PARKED_ANCESTORS);
+                    Self::record_subpool_update(&mut updates, tx);
+                    iter.next();
+                }
                 continue 'transactions
             }
 
@@ -766,6 +1094,7 @@ impl<T: PoolTransaction> AllTransactions<T> {
 
             // Update the first transaction of this sender.
             Self::update_tx_base_fee(&self.pending_basefee, tx);
+            Self::update_tx_blob_fee(self.pending_blob_fee, tx);
             // Track if the transaction's sub-pool changed.
             Self::record_subpool_update(&mut updates, tx);
 
@@ -812,6 +1141,7 @@ impl<T: PoolTransaction> AllTransactions<T> {
 
                 // Update and record sub-pool changes.
                 Self::update_tx_base_fee(&self.pending_basefee, tx);
+                Self::update_tx_blob_fee(self.pending_blob_fee, tx);
                 Self::record_subpool_update(&mut updates, tx);
 
                 // Advance iterator
@@ -852,6 +1182,24 @@ impl<T: PoolTransaction> AllTransactions<T> {
         }
     }
 
+    /// Rechecks the transaction's blob fee condition, this is a no-op for non-blob transactions.
+    fn update_tx_blob_fee(pending_blob_fee: Option<u128>, tx: &mut PoolInternalTransaction<T>) {
+        if !tx.state.is_blob() {
+            return
+        }
+        if Self::has_enough_blob_fee(pending_blob_fee, &tx.transaction) {
+            tx.state.insert(TxState::ENOUGH_BLOB_FEE_CAP_BLOCK);
+        } else {
+            tx.state.remove(TxState::ENOUGH_BLOB_FEE_CAP_BLOCK);
+        }
+    }
+
+    /// Returns `true` if the blob fee cap of the transaction satisfies the pending blob fee.
+    fn has_enough_blob_fee(pending_blob_fee: Option<u128>, tx: &ValidPoolTransaction<T>) -> bool {
+        let blob_fee = pending_blob_fee.unwrap_or(BLOB_TX_MIN_BLOB_GASPRICE);
+        tx.max_fee_per_blob_gas().unwrap_or_default() >= blob_fee
+    }
+
     /// Returns an iterator over all transactions for the given sender, starting with the lowest
     /// nonce
     pub(crate) fn txs_iter(
@@ -944,12 +1292,15 @@ impl<T: PoolTransaction> AllTransactions<T> {
     /// This will enforce all additional rules in the context of this pool, such as:
     ///   - Spam protection: reject new non-local transaction from a sender that exhausted its slot
     ///     capacity.
+    ///   - Minimum priority fee: reject new non-local transactions that pay less than the
+    ///     configured minimum.
+    ///   - Size: reject transactions that exceed the configured max size.
     ///   - Gas limit: reject transactions if they exceed a block's maximum gas.
     fn ensure_valid(
         &self,
         transaction: ValidPoolTransaction<T>,
     ) -> Result<ValidPoolTransaction<T>, InsertErr<T>> {
-        if !transaction.origin.is_local() {
+        if !self.local_transactions.is_exempt(&transaction) {
             let current_txs =
                 self.tx_counter.get(&transaction.sender_id()).copied().unwrap_or_default();
             if current_txs >= self.max_account_slots {
@@ -957,6 +1308,19 @@ impl<T: PoolTransaction> AllTransactions<T> {
                     transaction: Arc::new(transaction),
                 })
             }
+            if transaction.priority_fee_or_price() < self.minimal_priority_fee {
+                return Err(InsertErr::PriorityFeeBelowMinimum {
+                    minimal_priority_fee: self.minimal_priority_fee,
+                    transaction: Arc::new(transaction),
+                })
+            }
+        }
+        if transaction.encoded_length > self.max_tx_size {
+            return Err(InsertErr::ExceededMaxTransactionSize {
+                size: transaction.encoded_length,
+                max_size: self.max_tx_size,
+                transaction: Arc::new(transaction),
+            })
         }
         if transaction.gas_limit() > self.block_gas_limit {
             return Err(InsertErr::TxGasLimitMoreThanAvailableBlockGas {
@@ -1016,6 +1380,14 @@ impl<T: PoolTransaction> AllTransactions<T> {
             state.insert(TxState::NOT_TOO_MUCH_GAS);
         }
 
+        // Check blob fee
+        if transaction.is_eip4844() {
+            state.insert(TxState::BLOB_TRANSACTION);
+            if Self::has_enough_blob_fee(self.pending_blob_fee, &transaction) {
+                state.insert(TxState::ENOUGH_BLOB_FEE_CAP_BLOCK);
+            }
+        }
+
         let mut replaced_tx = None;
 
         let pool_tx = PoolInternalTransaction {
@@ -1034,8 +1406,8 @@ impl<T: PoolTransaction> AllTransactions<T> {
             }
             Entry::Occupied(mut entry) => {
                 // Transaction already exists
-                // Ensure the new transaction is not underpriced
-                if transaction.is_underpriced(entry.get().transaction.as_ref()) {
+                // Ensure the new transaction bumps the price of the existing one sufficiently
+                if self.price_bumps.is_underpriced(&entry.get().transaction, &transaction) {
                     return Err(InsertErr::Underpriced {
                         transaction: pool_tx.transaction,
                         existing: *entry.get().transaction.hash(),
@@ -1053,6 +1425,8 @@ impl<T: PoolTransaction> AllTransactions<T> {
 
         // The next transaction of this sender
         let on_chain_id = TransactionId::new(transaction.sender_id(), on_chain_nonce);
+        let pending_basefee = self.pending_basefee;
+        let pending_blob_fee = self.pending_blob_fee;
         {
             let mut descendants = self.descendant_txs_mut(&on_chain_id).peekable();
 
@@ -1100,6 +1474,10 @@ impl<T: PoolTransaction> AllTransactions<T> {
                     tx.state.insert(TxState::ENOUGH_BALANCE);
                 }
 
+                // The fee conditions of parked transactions might be outdated
+                Self::update_tx_base_fee(&pending_basefee, tx);
+                Self::update_tx_blob_fee(pending_blob_fee, tx);
+
                 // Update ancestor condition.
                 if has_parked_ancestor {
                     tx.state.remove(TxState::NO_PARKED_ANCESTORS);
@@ -1162,6 +1540,10 @@ impl<T: PoolTransaction> Default for AllTransactions<T> {
     fn default() -> Self {
         Self {
             max_account_slots: MAX_ACCOUNT_SLOTS_PER_SENDER,
+            price_bumps: Default::default(),
+            minimal_priority_fee: 0,
+            max_tx_size: TX_MAX_SIZE,
+            local_transactions: Default::default(),
             minimal_protocol_basefee: MIN_PROTOCOL_BASE_FEE,
             block_gas_limit: 30_000_000,
             by_hash: Default::default(),
@@ -1170,6 +1552,7 @@ impl<T: PoolTransaction> Default for AllTransactions<T> {
             last_seen_block_number: 0,
             last_seen_block_hash: Default::default(),
             pending_basefee: Default::default(),
+            pending_blob_fee: None,
         }
     }
 }
@@ -1194,6 +1577,17 @@ pub(crate) enum InsertErr<T: PoolTransaction> {
     ///
     /// The sender can be considered a spammer at this point.
     ExceededSenderTransactionsCapacity { transaction: Arc<ValidPoolTransaction<T>> },
+    /// The transaction's priority fee is below the configured minimum.
+    PriorityFeeBelowMinimum {
+        transaction: Arc<ValidPoolTransaction<T>>,
+        minimal_priority_fee: u128,
+    },
+    /// The transaction's encoded size exceeds the configured maximum.
+    ExceededMaxTransactionSize {
+        transaction: Arc<ValidPoolTransaction<T>>,
+        size: usize,
+        max_size: usize,
+    },
     /// Transaction gas limit exceeds block's gas limit
     TxGasLimitMoreThanAvailableBlockGas {
         transaction: Arc<ValidPoolTransaction<T>>,
@@ -1283,7 +1677,7 @@ impl<T: PoolTransaction> fmt::Debug for PruneResult<T> {
 }
 
 /// Stores relevant context about a sender.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub(crate) struct SenderInfo {
     /// current nonce of the sender.
     pub(crate) state_nonce: u64,
@@ -1304,8 +1698,10 @@ impl SenderInfo {
 mod tests {
     use super::*;
     use crate::{
+        config::SubPoolLimit,
         test_utils::{MockOrdering, MockTransaction, MockTransactionFactory},
-        traits::TransactionOrigin,
+        traits::{ParkedReason, TransactionOrigin},
+        EffectiveTipOrdering,
     };
 
     #[test]
@@ -1585,11 +1981,411 @@ mod tests {
 
         assert_eq!(pool.pending_pool.len(), 1);
 
+        // a lower base fee doesn't affect the transaction
+        pool.update_basefee(1);
+        assert_eq!(pool.pending_pool.len(), 1);
+
         pool.update_basefee(tx.max_fee_per_gas() + 1);
 
         assert!(pool.pending_pool.is_empty());
         assert_eq!(pool.basefee_pool.len(), 1);
 
-        assert_eq!(pool.all_transactions.txs.get(&id).unwrap().subpool, SubPool::BaseFee)
+        assert_eq!(pool.all_transactions.txs.get(&id).unwrap().subpool, SubPool::BaseFee);
+
+        // a still too high base fee keeps the transaction parked
+        pool.update_basefee(tx.max_fee_per_gas() + 2);
+        assert_eq!(pool.basefee_pool.len(), 1);
+
+        pool.update_basefee(tx.max_fee_per_gas());
+        assert!(pool.basefee_pool.is_empty());
+        assert_eq!(pool.pending_pool.len(), 1);
+        assert_eq!(pool.all_transactions.txs.get(&id).unwrap().subpool, SubPool::Pending)
+    }
+
+    #[test]
+    fn update_basefee_demotes_descendants() {
+        let on_chain_balance = U256::from(1_000);
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        // two dependent transactions with decreasing fee
+        let t = MockTransaction::eip1559().inc_price_by(10);
+        let root = f.validated(t.clone());
+        let descendant = f.validated(t.next().decr_price());
+        pool.add_transaction(root.clone(), on_chain_balance, 0).unwrap();
+        pool.add_transaction(descendant.clone(), on_chain_balance, 0).unwrap();
+        assert_eq!(pool.pending_pool.len(), 2);
+
+        // only the descendant no longer satisfies the base fee
+        pool.update_basefee(descendant.max_fee_per_gas() + 1);
+        assert_eq!(pool.all_transactions.txs.get(root.id()).unwrap().subpool, SubPool::Pending);
+        assert_eq!(
+            pool.all_transactions.txs.get(descendant.id()).unwrap().subpool,
+            SubPool::BaseFee
+        );
+
+        // both transactions are parked via the root's fee
+        pool.update_basefee(root.max_fee_per_gas() + 1);
+        assert!(pool.pending_pool.is_empty());
+        assert_eq!(pool.all_transactions.txs.get(root.id()).unwrap().subpool, SubPool::BaseFee);
+        assert_eq!(
+            pool.all_transactions.txs.get(descendant.id()).unwrap().subpool,
+            SubPool::Queued
+        );
+        pool.assert_invariants();
+    }
+
+    #[test]
+    fn update_basefee_parks_descendants_of_demoted_transactions() {
+        let on_chain_balance = U256::from(1_000);
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        // the descendant pays a higher fee than its ancestor
+        let t = MockTransaction::eip1559().inc_price_by(10);
+        let root = f.validated(t.clone());
+        let descendant = f.validated(t.next().inc_price_by(10));
+        pool.add_transaction(root.clone(), on_chain_balance, 0).unwrap();
+        pool.add_transaction(descendant.clone(), on_chain_balance, 0).unwrap();
+        assert_eq!(pool.pending_pool.len(), 2);
+
+        // the descendant satisfies the base fee, but must not stay pending without its ancestor
+        pool.update_basefee(root.max_fee_per_gas() + 1);
+        assert!(pool.pending_pool.is_empty());
+        assert_eq!(pool.all_transactions.txs.get(root.id()).unwrap().subpool, SubPool::BaseFee);
+        assert_eq!(
+            pool.all_transactions.txs.get(descendant.id()).unwrap().subpool,
+            SubPool::Queued
+        );
+        pool.assert_invariants();
+    }
+
+    #[test]
+    fn update_basefee_promotes_descendants() {
+        let on_chain_balance = U256::from(1_000);
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        let t = MockTransaction::eip1559().inc_price_by(10);
+        let root = f.validated(t.clone());
+        let descendant = f.validated(t.next().inc_price_by(10));
+        pool.add_transaction(root.clone(), on_chain_balance, 0).unwrap();
+        pool.add_transaction(descendant.clone(), on_chain_balance, 0).unwrap();
+
+        // park both transactions via the root's fee
+        pool.update_basefee(descendant.max_fee_per_gas() + 1);
+        assert!(pool.pending_pool.is_empty());
+
+        // the descendant can't be promoted before its ancestor
+        pool.update_basefee(descendant.max_fee_per_gas());
+        assert!(pool.pending_pool.is_empty());
+        assert_eq!(
+            pool.all_transactions.txs.get(descendant.id()).unwrap().subpool,
+            SubPool::Queued
+        );
+        pool.assert_invariants();
+
+        // promoting the root promotes its descendant as well
+        pool.update_basefee(root.max_fee_per_gas());
+        assert_eq!(pool.pending_pool.len(), 2);
+        assert!(pool.basefee_pool.is_empty());
+        assert!(pool.queued_pool.is_empty());
+        pool.assert_invariants();
+    }
+
+    #[test]
+    fn closing_nonce_gap_rechecks_basefee() {
+        let on_chain_balance = U256::from(1_000);
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        let t = MockTransaction::eip1559().inc_price_by(10);
+        let gapped = f.validated(t.next());
+        pool.add_transaction(gapped.clone(), on_chain_balance, 0).unwrap();
+        assert_eq!(pool.queued_pool.len(), 1);
+
+        // the base fee rises while the transaction waits for its ancestor
+        pool.update_basefee(gapped.max_fee_per_gas() + 1);
+
+        let root = f.validated(t.inc_price_by(10));
+        pool.add_transaction(root.clone(), on_chain_balance, 0).unwrap();
+        assert_eq!(pool.all_transactions.txs.get(root.id()).unwrap().subpool, SubPool::Pending);
+        assert_eq!(pool.all_transactions.txs.get(gapped.id()).unwrap().subpool, SubPool::BaseFee);
+        pool.assert_invariants();
+    }
+
+    #[test]
+    fn canonical_state_change_reprioritizes_pending() {
+        let on_chain_balance = U256::MAX;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(EffectiveTipOrdering::default(), Default::default());
+
+        let capped =
+            f.validated(MockTransaction::eip1559().with_max_fee(100).with_priority_fee(10));
+        let generous =
+            f.validated(MockTransaction::eip1559().with_max_fee(1000).with_priority_fee(5));
+        pool.add_transaction(capped.clone(), on_chain_balance, 0).unwrap();
+        pool.add_transaction(generous.clone(), on_chain_balance, 0).unwrap();
+        assert_eq!(pool.best_transactions().next().unwrap().hash(), capped.hash());
+
+        // the fee cap of `capped` now limits its tip to 2
+        let block_info = BlockInfo { pending_basefee: 98, ..pool.block_info() };
+        pool.on_canonical_state_change(block_info, vec![], Default::default());
+        assert_eq!(pool.pending_pool.len(), 2);
+        assert_eq!(pool.best_transactions().next().unwrap().hash(), generous.hash());
+    }
+
+    #[test]
+    fn update_blob_fee_subpools() {
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        let tx = MockTransaction::eip4844().inc_price_by(10).with_blob_fee(10);
+        let validated = f.validated(tx);
+        let id = *validated.id();
+        pool.add_transaction(validated, U256::from(1_000), 0).unwrap();
+
+        assert_eq!(pool.pending_pool.len(), 1);
+
+        let mut info = pool.block_info();
+        info.pending_blob_fee = Some(11);
+        pool.set_block_info(info);
+
+        assert!(pool.pending_pool.is_empty());
+        assert_eq!(pool.blob_pool.len(), 1);
+        assert_eq!(pool.all_transactions.txs.get(&id).unwrap().subpool, SubPool::Blob);
+
+        info.pending_blob_fee = Some(10);
+        pool.set_block_info(info);
+
+        assert_eq!(pool.pending_pool.len(), 1);
+        assert!(pool.blob_pool.is_empty());
+        assert_eq!(pool.all_transactions.txs.get(&id).unwrap().subpool, SubPool::Pending)
+    }
+
+    #[test]
+    fn discard_worst_blob_transactions() {
+        let mut f = MockTransactionFactory::default();
+        let config = PoolConfig {
+            blob_limit: SubPoolLimit { max_txs: 1, max_size: usize::MAX },
+            ..Default::default()
+        };
+        let mut pool = TxPool::new(MockOrdering::default(), config);
+        let mut info = pool.block_info();
+        info.pending_blob_fee = Some(100);
+        pool.set_block_info(info);
+
+        let cheap = f.validated(MockTransaction::eip4844().inc_price_by(10).with_blob_fee(1));
+        let cheap_hash = *cheap.hash();
+        pool.add_transaction(cheap, U256::from(1_000), 0).unwrap();
+        let better = f.validated(MockTransaction::eip4844().inc_price_by(10).with_blob_fee(2));
+        pool.add_transaction(better, U256::from(1_000), 0).unwrap();
+        assert_eq!(pool.blob_pool.len(), 2);
+
+        let discarded = pool.discard_worst();
+        assert_eq!(discarded.len(), 1);
+        assert_eq!(*discarded[0].hash(), cheap_hash);
+        assert_eq!(pool.blob_pool.len(), 1);
+    }
+
+    #[test]
+    fn update_accounts_discards_stale_transactions() {
+        let on_chain_balance = U256::from(1_000);
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+        let tx = MockTransaction::eip1559().inc_price().inc_limit();
+        let first = f.validated(tx.clone());
+        let second = f.validated(tx.next());
+        pool.add_transaction(first.clone(), on_chain_balance, 0).unwrap();
+        pool.add_transaction(second.clone(), on_chain_balance, 0).unwrap();
+
+        // the first transaction was mined, but the pool missed the update
+        let sender = f.ids.sender_id(&tx.get_sender()).unwrap();
+        let changed =
+            HashMap::from([(sender, SenderInfo { state_nonce: 1, balance: on_chain_balance })]);
+        assert_eq!(pool.drifted_senders(&changed), 1);
+
+        let outcome = pool.update_accounts(changed.clone());
+        assert_eq!(outcome.discarded, vec![*first.hash()]);
+        assert!(!pool.contains(first.hash()));
+        assert!(pool.contains(second.hash()));
+        assert_eq!(pool.pending_pool.len(), 1);
+        assert_eq!(pool.drifted_senders(&changed), 0);
+    }
+
+    #[test]
+    fn discarded_stale_transaction_unlocks_descendant() {
+        let on_chain_balance = U256::from(1_000);
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+        let tx = MockTransaction::eip1559().inc_price().inc_limit();
+        let first = f.validated(tx.clone());
+        let second = f.validated(tx.next());
+        pool.add_transaction(first.clone(), on_chain_balance, 0).unwrap();
+        pool.add_transaction(second.clone(), on_chain_balance, 0).unwrap();
+        assert_eq!(pool.best_transactions().count(), 2);
+
+        // the first transaction's nonce was used on chain
+        let sender = f.ids.sender_id(&tx.get_sender()).unwrap();
+        let changed =
+            HashMap::from([(sender, SenderInfo { state_nonce: 1, balance: on_chain_balance })]);
+        let outcome = pool.update_accounts(changed);
+        assert_eq!(outcome.discarded, vec![*first.hash()]);
+
+        // the descendant is now the next transaction of the sender and can be included
+        let best = pool.best_transactions().map(|tx| *tx.hash()).collect::<Vec<_>>();
+        assert_eq!(best, vec![*second.hash()]);
+        pool.assert_invariants();
+    }
+
+    #[test]
+    fn lowered_nonce_parks_sender_transactions() {
+        let on_chain_balance = U256::from(1_000);
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+        let tx = MockTransaction::eip1559().inc_price().inc_limit().inc_nonce();
+        let first = f.validated(tx.clone());
+        let second = f.validated(tx.next());
+        pool.add_transaction(first.clone(), on_chain_balance, 1).unwrap();
+        pool.add_transaction(second.clone(), on_chain_balance, 1).unwrap();
+        assert_eq!(pool.pending_pool.len(), 2);
+
+        // a reorg reverted the sender's transaction with nonce 0, which opens a nonce gap
+        let sender = f.ids.sender_id(&tx.get_sender()).unwrap();
+        let changed =
+            HashMap::from([(sender, SenderInfo { state_nonce: 0, balance: on_chain_balance })]);
+        pool.update_accounts(changed);
+        assert!(pool.pending_pool.is_empty());
+        assert_eq!(pool.queued_pool.len(), 2);
+        assert_eq!(pool.all_transactions.txs.get(first.id()).unwrap().subpool, SubPool::Queued);
+        assert_eq!(pool.all_transactions.txs.get(second.id()).unwrap().subpool, SubPool::Queued);
+        pool.assert_invariants();
+    }
+
+    #[test]
+    fn discard_expired_private_transactions() {
+        let on_chain_balance = U256::from(1_000);
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+        let tx = MockTransaction::eip1559().inc_price().inc_limit();
+        let origin = TransactionOrigin::Private { max_block_number: Some(1) };
+        let private = f.validated_with_origin(origin, tx.clone());
+        let descendant = f.validated(tx.next());
+        let other = f.validated(MockTransaction::eip1559().inc_price().inc_limit());
+        pool.add_transaction(private.clone(), on_chain_balance, 0).unwrap();
+        pool.add_transaction(descendant.clone(), on_chain_balance, 0).unwrap();
+        pool.add_transaction(other.clone(), on_chain_balance, 0).unwrap();
+
+        // the private transaction can still be included in block 1
+        let block_info = BlockInfo { last_seen_block_number: 0, ..pool.block_info() };
+        let outcome = pool.on_canonical_state_change(block_info, vec![], Default::default());
+        assert!(outcome.discarded.is_empty());
+        assert_eq!(pool.len(), 3);
+
+        let block_info = BlockInfo { last_seen_block_number: 1, ..pool.block_info() };
+        let outcome = pool.on_canonical_state_change(block_info, vec![], Default::default());
+        assert_eq!(outcome.discarded, vec![*private.hash(), *descendant.hash()]);
+        assert!(pool.contains(other.hash()));
+        assert_eq!(pool.len(), 1);
+        assert_eq!(pool.pending_pool.len(), 1);
+    }
+
+    #[test]
+    fn transactions_by_sender_with_status() {
+        let on_chain_balance = U256::from(1_000);
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+        let tx = MockTransaction::eip1559().inc_price().inc_limit();
+        let first = f.validated(tx.clone());
+        let gapped = f.validated(tx.next().next());
+        pool.add_transaction(first.clone(), on_chain_balance, 0).unwrap();
+        pool.add_transaction(gapped.clone(), on_chain_balance, 0).unwrap();
+
+        let sender = f.ids.sender_id(&tx.get_sender()).unwrap();
+        let statuses = pool.get_transactions_by_sender_with_status(sender);
+        assert_eq!(statuses.len(), 2);
+        assert_eq!(statuses[0].transaction.hash(), first.hash());
+        assert!(statuses[0].is_pending());
+        assert_eq!(statuses[1].transaction.hash(), gapped.hash());
+        assert!(statuses[1].parked_reasons.contains(&ParkedReason::NonceGap));
+    }
+
+    #[test]
+    fn reject_insufficient_price_bump() {
+        let on_chain_balance = U256::ZERO;
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = AllTransactions::default();
+        let tx = MockTransaction::eip1559().with_gas_price(100);
+        pool.insert_tx(f.validated(tx.clone()), on_chain_balance, on_chain_nonce).unwrap();
+
+        // neither fee cap nor priority fee bumped by 10%
+        let replacement = f.validated(tx.clone().rng_hash().with_gas_price(105));
+        assert!(matches!(
+            pool.insert_tx(replacement, on_chain_balance, on_chain_nonce),
+            Err(InsertErr::Underpriced { .. })
+        ));
+
+        // fee cap bumped but priority fee unchanged
+        let replacement = f.validated(tx.clone().rng_hash().with_max_fee(110));
+        assert!(matches!(
+            pool.insert_tx(replacement, on_chain_balance, on_chain_nonce),
+            Err(InsertErr::Underpriced { .. })
+        ));
+
+        let replacement = f.validated(tx.rng_hash().with_gas_price(110));
+        let InsertOk { replaced_tx, .. } =
+            pool.insert_tx(replacement, on_chain_balance, on_chain_nonce).unwrap();
+        assert!(replaced_tx.is_some());
+    }
+
+    #[test]
+    fn reject_below_minimal_priority_fee() {
+        let on_chain_balance = U256::from(1_000);
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let config = PoolConfig { minimal_priority_fee: 10, ..Default::default() };
+        let mut pool = AllTransactions::new(&config);
+
+        let tx = MockTransaction::eip1559().with_max_fee(100).with_priority_fee(5);
+        assert!(matches!(
+            pool.insert_tx(f.validated(tx.clone()), on_chain_balance, on_chain_nonce),
+            Err(InsertErr::PriorityFeeBelowMinimum { minimal_priority_fee: 10, .. })
+        ));
+
+        // local transactions are exempt
+        pool.insert_tx(
+            f.validated_with_origin(TransactionOrigin::Local, tx),
+            on_chain_balance,
+            on_chain_nonce,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn discard_worst_global_limit_keeps_locals() {
+        let mut f = MockTransactionFactory::default();
+        let config = PoolConfig { max_total_transactions: 2, ..Default::default() };
+        let mut pool = TxPool::new(MockOrdering::default(), config);
+
+        let local = f.validated_with_origin(
+            TransactionOrigin::Local,
+            MockTransaction::eip1559().inc_limit().inc_price_by(1),
+        );
+        let local_hash = *local.hash();
+        pool.add_transaction(local, U256::from(1_000), 0).unwrap();
+        let cheap = f.validated(MockTransaction::eip1559().inc_limit().inc_price_by(2));
+        let cheap_hash = *cheap.hash();
+        pool.add_transaction(cheap, U256::from(1_000), 0).unwrap();
+        let better = f.validated(MockTransaction::eip1559().inc_limit().inc_price_by(3));
+        pool.add_transaction(better, U256::from(1_000), 0).unwrap();
+        assert_eq!(pool.len(), 3);
+
+        let discarded = pool.discard_worst();
+        assert_eq!(discarded.len(), 1);
+        assert_eq!(*discarded[0].hash(), cheap_hash);
+        assert!(pool.contains(&local_hash));
+        assert!(!pool.contains(&cheap_hash));
+        assert_eq!(pool.len(), 2);
     }
 }
@@ -12,9 +12,10 @@ use rand::{
     prelude::Distribution,
 };
 use reth_primitives::{
-    constants::MIN_PROTOCOL_BASE_FEE, hex, Address, FromRecoveredTransaction,
-    IntoRecoveredTransaction, Signature, Transaction, TransactionKind, TransactionSigned,
-    TransactionSignedEcRecovered, TxEip1559, TxHash, TxLegacy, TxType, H256, U128, U256,
+    constants::{BLOB_TX_MIN_BLOB_GASPRICE, MIN_PROTOCOL_BASE_FEE},
+    hex, Address, FromRecoveredTransaction, IntoRecoveredTransaction, Signature, Transaction,
+    TransactionKind, TransactionSigned, TransactionSignedEcRecovered, TxEip1559, TxEip4844, TxHash,
+    TxLegacy, TxType, H256, U128, U256,
 };
 use std::{ops::Range, sync::Arc, time::Instant};
 
@@ -38,6 +39,9 @@ macro_rules! set_value {
             MockTransaction::Eip1559 { ref mut $field, .. } => {
                 *$field = new_value;
             }
+            MockTransaction::Eip4844 { ref mut $field, .. } => {
+                *$field = new_value;
+            }
         }
     };
 }
@@ -48,6 +52,7 @@ macro_rules! get_value {
         match $this {
             MockTransaction::Legacy { $field, .. } => $field,
             MockTransaction::Eip1559 { $field, .. } => $field,
+            MockTransaction::Eip4844 { $field, .. } => $field,
         }
     };
 }
@@ -99,6 +104,17 @@ pub enum MockTransaction {
         to: TransactionKind,
         value: U256,
     },
+    Eip4844 {
+        hash: H256,
+        sender: Address,
+        nonce: u64,
+        max_fee_per_gas: u128,
+        max_priority_fee_per_gas: u128,
+        max_fee_per_blob_gas: u128,
+        gas_limit: u64,
+        to: TransactionKind,
+        value: U256,
+    },
 }
 
 // === impl MockTransaction ===
@@ -139,22 +155,43 @@ impl MockTransaction {
         }
     }
 
+    /// Returns a new EIP4844 transaction with random address and hash and empty values
+    pub fn eip4844() -> Self {
+        MockTransaction::Eip4844 {
+            hash: H256::random(),
+            sender: Address::random(),
+            nonce: 0,
+            max_fee_per_gas: MIN_PROTOCOL_BASE_FEE,
+            max_priority_fee_per_gas: MIN_PROTOCOL_BASE_FEE,
+            max_fee_per_blob_gas: BLOB_TX_MIN_BLOB_GASPRICE,
+            gas_limit: 0,
+            to: TransactionKind::Call(Address::random()),
+            value: Default::default(),
+        }
+    }
+
     pub fn set_priority_fee(&mut self, val: u128) -> &mut Self {
-        if let MockTransaction::Eip1559 { max_priority_fee_per_gas, .. } = self {
+        if let MockTransaction::Eip1559 { max_priority_fee_per_gas, .. } |
+        MockTransaction::Eip4844 { max_priority_fee_per_gas, .. } = self
+        {
             *max_priority_fee_per_gas = val;
         }
         self
     }
 
     pub fn with_priority_fee(mut self, val: u128) -> Self {
-        if let MockTransaction::Eip1559 { ref mut max_priority_fee_per_gas, .. } = self {
+        if let MockTransaction::Eip1559 { ref mut max_priority_fee_per_gas, .. } |
+        MockTransaction::Eip4844 { ref mut max_priority_fee_per_gas, .. } = self
+        {
             *max_priority_fee_per_gas = val;
         }
         self
     }
 
     pub fn get_priority_fee(&self) -> Option<u128> {
-        if let MockTransaction::Eip1559 { max_priority_fee_per_gas, .. } = self {
+        if let MockTransaction::Eip1559 { max_priority_fee_per_gas, .. } |
+        MockTransaction::Eip4844 { max_priority_fee_per_gas, .. } = self
+        {
             Some(*max_priority_fee_per_gas)
         } else {
             None
@@ -162,21 +199,27 @@ impl MockTransaction {
     }
 
     pub fn set_max_fee(&mut self, val: u128) -> &mut Self {
-        if let MockTransaction::Eip1559 { max_fee_per_gas, .. } = self {
+        if let MockTransaction::Eip1559 { max_fee_per_gas, .. } |
+        MockTransaction::Eip4844 { max_fee_per_gas, .. } = self
+        {
             *max_fee_per_gas = val;
         }
         self
     }
 
     pub fn with_max_fee(mut self, val: u128) -> Self {
-        if let MockTransaction::Eip1559 { ref mut max_fee_per_gas, .. } = self {
+        if let MockTransaction::Eip1559 { ref mut max_fee_per_gas, .. } |
+        MockTransaction::Eip4844 { ref mut max_fee_per_gas, .. } = self
+        {
             *max_fee_per_gas = val;
         }
         self
     }
 
     pub fn get_max_fee(&self) -> Option<u128> {
-        if let MockTransaction::Eip1559 { max_fee_per_gas, .. } = self {
+        if let MockTransaction::Eip1559 { max_fee_per_gas, .. } |
+        MockTransaction::Eip4844 { max_fee_per_gas, .. } = self
+        {
             Some(*max_fee_per_gas)
         } else {
             None
@@ -188,7 +231,8 @@ impl MockTransaction {
             MockTransaction::Legacy { gas_price, .. } => {
                 *gas_price = val;
             }
-            MockTransaction::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas, .. } => {
+            MockTransaction::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas, .. } |
+            MockTransaction::Eip4844 { max_fee_per_gas, max_priority_fee_per_gas, .. } => {
                 *max_fee_per_gas = val;
                 *max_priority_fee_per_gas = val;
             }
@@ -205,6 +249,11 @@ impl MockTransaction {
                 ref mut max_fee_per_gas,
                 ref mut max_priority_fee_per_gas,
                 ..
+            } |
+            MockTransaction::Eip4844 {
+                ref mut max_fee_per_gas,
+                ref mut max_priority_fee_per_gas,
+                ..
             } => {
                 *max_fee_per_gas = val;
                 *max_priority_fee_per_gas = val;
@@ -216,7 +265,28 @@ impl MockTransaction {
     pub fn get_gas_price(&self) -> u128 {
         match self {
             MockTransaction::Legacy { gas_price, .. } => *gas_price,
-            MockTransaction::Eip1559 { max_fee_per_gas, .. } => *max_fee_per_gas,
+            MockTransaction::Eip1559 { max_fee_per_gas, .. } |
+            MockTransaction::Eip4844 { max_fee_per_gas, .. } => *max_fee_per_gas,
+        }
+    }
+
+    pub fn set_blob_fee(&mut self, val: u128) -> &mut Self {
+        if let MockTransaction::Eip4844 { max_fee_per_blob_gas, .. } = self {
+            *max_fee_per_blob_gas = val;
+        }
+        self
+    }
+
+    pub fn with_blob_fee(mut self, val: u128) -> Self {
+        self.set_blob_fee(val);
+        self
+    }
+
+    pub fn get_blob_fee(&self) -> Option<u128> {
+        if let MockTransaction::Eip4844 { max_fee_per_blob_gas, .. } = self {
+            Some(*max_fee_per_blob_gas)
+        } else {
+            None
         }
     }
 
@@ -294,6 +364,10 @@ impl MockTransaction {
     pub fn is_eip1559(&self) -> bool {
         matches!(self, MockTransaction::Eip1559 { .. })
     }
+
+    pub fn is_eip4844(&self) -> bool {
+        matches!(self, MockTransaction::Eip4844 { .. })
+    }
 }
 
 impl PoolTransaction for MockTransaction {
@@ -301,6 +375,7 @@ impl PoolTransaction for MockTransaction {
         match self {
             MockTransaction::Legacy { hash, .. } => hash,
             MockTransaction::Eip1559 { hash, .. } => hash,
+            MockTransaction::Eip4844 { hash, .. } => hash,
         }
     }
 
@@ -308,6 +383,7 @@ impl PoolTransaction for MockTransaction {
         match self {
             MockTransaction::Legacy { sender, .. } => *sender,
             MockTransaction::Eip1559 { sender, .. } => *sender,
+            MockTransaction::Eip4844 { sender, .. } => *sender,
         }
     }
 
@@ -315,6 +391,7 @@ impl PoolTransaction for MockTransaction {
         match self {
             MockTransaction::Legacy { nonce, .. } => *nonce,
             MockTransaction::Eip1559 { nonce, .. } => *nonce,
+            MockTransaction::Eip4844 { nonce, .. } => *nonce,
         }
     }
 
@@ -326,6 +403,9 @@ impl PoolTransaction for MockTransaction {
             MockTransaction::Eip1559 { max_fee_per_gas, value, gas_limit, .. } => {
                 U256::from(*gas_limit) * U256::from(*max_fee_per_gas) + *value
             }
+            MockTransaction::Eip4844 { max_fee_per_gas, value, gas_limit, .. } => {
+                U256::from(*gas_limit) * U256::from(*max_fee_per_gas) + *value
+            }
         }
     }
 
@@ -340,23 +420,30 @@ impl PoolTransaction for MockTransaction {
     fn max_fee_per_gas(&self) -> u128 {
         match self {
             MockTransaction::Legacy { gas_price, .. } => *gas_price,
-            MockTransaction::Eip1559 { max_fee_per_gas, .. } => *max_fee_per_gas,
+            MockTransaction::Eip1559 { max_fee_per_gas, .. } |
+            MockTransaction::Eip4844 { max_fee_per_gas, .. } => *max_fee_per_gas,
         }
     }
 
     fn max_priority_fee_per_gas(&self) -> Option<u128> {
         match self {
             MockTransaction::Legacy { .. } => None,
-            MockTransaction::Eip1559 { max_priority_fee_per_gas, .. } => {
+            MockTransaction::Eip1559 { max_priority_fee_per_gas, .. } |
+            MockTransaction::Eip4844 { max_priority_fee_per_gas, .. } => {
                 Some(*max_priority_fee_per_gas)
             }
         }
     }
 
+    fn max_fee_per_blob_gas(&self) -> Option<u128> {
+        self.get_blob_fee()
+    }
+
     fn kind(&self) -> &TransactionKind {
         match self {
             MockTransaction::Legacy { to, .. } => to,
             MockTransaction::Eip1559 { to, .. } => to,
+            MockTransaction::Eip4844 { to, .. } => to,
         }
     }
 
@@ -368,6 +455,7 @@ impl PoolTransaction for MockTransaction {
         match self {
             MockTransaction::Legacy { .. } => TxType::Legacy.into(),
             MockTransaction::Eip1559 { .. } => TxType::EIP1559.into(),
+            MockTransaction::Eip4844 { .. } => TxType::EIP4844.into(),
         }
     }
 
@@ -423,6 +511,26 @@ impl FromRecoveredTransaction for MockTransaction {
                 to,
                 value: U256::from(value),
             },
+            Transaction::Eip4844(TxEip4844 {
+                nonce,
+                gas_limit,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                max_fee_per_blob_gas,
+                to,
+                value,
+                ..
+            }) => MockTransaction::Eip4844 {
+                hash,
+                sender,
+                nonce,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                max_fee_per_blob_gas,
+                gas_limit,
+                to,
+                value: U256::from(value),
+            },
             Transaction::Eip2930 { .. } => {
                 unimplemented!()
             }
@@ -500,6 +608,10 @@ impl MockTransactionFactory {
     pub fn create_eip1559(&mut self) -> MockValidTx {
         self.validated(MockTransaction::eip1559())
     }
+
+    pub fn create_eip4844(&mut self) -> MockValidTx {
+        self.validated(MockTransaction::eip4844())
+    }
 }
 
 #[derive(Clone, Default)]
@@ -510,9 +622,13 @@ impl TransactionOrdering for MockOrdering {
     type Priority = U256;
     type Transaction = MockTransaction;
 
-    fn priority(&self, transaction: &Self::Transaction) -> Self::Priority {
+    fn priority(&self, transaction: &Self::Transaction, _base_fee: u128) -> Self::Priority {
         transaction.cost()
     }
+
+    fn depends_on_base_fee(&self) -> bool {
+        false
+    }
 }
 
 /// A configured distribution that can generate transactions
@@ -553,5 +669,5 @@ fn test_mock_priority() {
     let o = MockOrdering;
     let lo = MockTransaction::eip1559();
     let hi = lo.next().inc_value();
-    assert!(o.priority(&hi) > o.priority(&lo));
+    assert!(o.priority(&hi, 0) > o.priority(&lo, 0));
 }
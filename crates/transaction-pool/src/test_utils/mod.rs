@@ -5,7 +5,8 @@ mod mock;
 mod pool;
 
 use crate::{
-    Pool, PoolTransaction, TransactionOrigin, TransactionValidationOutcome, TransactionValidator,
+    blobstore::InMemoryBlobStore, Pool, PoolTransaction, TransactionOrigin,
+    TransactionValidationOutcome, TransactionValidator,
 };
 use async_trait::async_trait;
 pub use mock::*;
@@ -16,7 +17,12 @@ pub type TestPool = Pool<NoopTransactionValidator<MockTransaction>, MockOrdering
 
 /// Returns a new [Pool] used for testing purposes
 pub fn testing_pool() -> TestPool {
-    Pool::new(NoopTransactionValidator::default(), MockOrdering::default(), Default::default())
+    Pool::new(
+        NoopTransactionValidator::default(),
+        MockOrdering::default(),
+        Arc::new(InMemoryBlobStore::default()),
+        Default::default(),
+    )
 }
 
 // A [`TransactionValidator`] that does nothing.
@@ -1,20 +1,23 @@
 //! Test helpers for mocking an entire pool.
 
 use crate::{
-    error::PoolResult,
-    pool::{txpool::TxPool, AddedTransaction},
+    error::PoolError,
+    pool::{
+        state::SubPool,
+        txpool::{SenderInfo, TxPool},
+        AddedTransaction,
+    },
     test_utils::{
         MockOrdering, MockTransaction, MockTransactionDistribution, MockTransactionFactory,
     },
-    TransactionOrdering,
+    BlockInfo, PoolConfig, PoolTransaction, TransactionOrdering,
 };
 use rand::Rng;
-use reth_primitives::{Address, U128, U256};
+use reth_primitives::{constants::MIN_PROTOCOL_BASE_FEE, Address, H256, U256};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
     ops::{Deref, DerefMut},
-    sync::Arc,
 };
 
 /// A wrapped `TxPool` with additional helpers for testing
@@ -24,24 +27,26 @@ pub struct MockPool<T: TransactionOrdering = MockOrdering> {
 }
 
 impl MockPool {
-    /// The total size of all subpools
-    fn total_subpool_size(&self) -> usize {
-        self.pool.pending().len() + self.pool.base_fee().len() + self.pool.queued().len()
+    /// Creates a new pool with the given config.
+    pub fn with_config(config: PoolConfig) -> Self {
+        Self { pool: TxPool::new(MockOrdering::default(), config) }
     }
 
     /// Checks that all pool invariants hold.
-    fn enforce_invariants(&self) {
-        assert_eq!(
-            self.pool.len(),
-            self.total_subpool_size(),
-            "Tx in AllTransactions and sum(subpools) must match"
-        );
+    ///
+    /// # Panics
+    ///
+    /// If the sub-pools are inconsistent with the state of the transactions, a pending
+    /// transaction has a nonce gap, the size accounting is off or a limit is exceeded.
+    pub fn enforce_invariants(&self) {
+        self.pool.assert_invariants();
+        self.pool.assert_limits();
     }
 }
 
 impl Default for MockPool {
     fn default() -> Self {
-        Self { pool: TxPool::new(MockOrdering::default(), Default::default()) }
+        Self::with_config(Default::default())
     }
 }
 
@@ -60,6 +65,9 @@ impl<T: TransactionOrdering> DerefMut for MockPool<T> {
 }
 
 /// Simulates transaction execution.
+///
+/// Each step picks a random sender and [ScenarioType], applies it to the pool, checks the outcome
+/// against the expected outcome and finally enforces all invariants of the pool.
 pub struct MockTransactionSimulator<R: Rng> {
     /// The pending base fee
     base_fee: u128,
@@ -73,8 +81,14 @@ pub struct MockTransactionSimulator<R: Rng> {
     senders: Vec<Address>,
     /// What scenarios to execute.
     scenarios: Vec<ScenarioType>,
-    /// All previous scenarios executed by a sender.
-    executed: HashMap<Address, ExecutedScenarios>,
+    /// All previous scenarios executed.
+    executed: Vec<ExecutedScenario>,
+    /// All blocks that were mined and not reorged yet.
+    mined: Vec<MinedBlock>,
+    /// The number of the current block.
+    block_number: u64,
+    /// The max number of transactions mined in a block.
+    max_block_transactions: usize,
     /// "Validates" generated transactions.
     validator: MockTransactionFactory,
     /// The rng instance used to select senders and scenarios.
@@ -95,11 +109,19 @@ impl<R: Rng> MockTransactionSimulator<R> {
             scenarios: config.scenarios,
             tx_generator: config.tx_generator,
             executed: Default::default(),
+            mined: Default::default(),
+            block_number: 0,
+            max_block_transactions: config.max_block_transactions,
             validator: Default::default(),
             rng,
         }
     }
 
+    /// Returns all scenarios executed so far.
+    pub fn executed(&self) -> &[ExecutedScenario] {
+        &self.executed
+    }
+
     /// Returns a random address from the senders set
     fn rng_address(&mut self) -> Address {
         let idx = self.rng.gen_range(0..self.senders.len());
@@ -112,6 +134,100 @@ impl<R: Rng> MockTransactionSimulator<R> {
         self.scenarios[idx].clone()
     }
 
+    /// Returns a random gas price that is at least the minimum protocol base fee and can be lower
+    /// or higher than the current base fee.
+    fn rng_gas_price(&mut self) -> u128 {
+        self.rng.gen_range(MIN_PROTOCOL_BASE_FEE..=self.base_fee * 2)
+    }
+
+    /// Returns all transactions of the sender that are currently in the pool, sorted by nonce.
+    fn pool_transactions(&self, pool: &MockPool, sender: Address) -> Vec<MockTransaction> {
+        self.validator
+            .ids
+            .sender_id(&sender)
+            .map(|id| {
+                pool.get_transactions_by_sender(id)
+                    .into_iter()
+                    .map(|tx| tx.transaction.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the next nonce of the sender, taking the transactions in the pool into account.
+    fn next_pool_nonce(&self, pool: &MockPool, sender: Address) -> u64 {
+        let on_chain_nonce = self.nonces[&sender];
+        self.pool_transactions(pool, sender)
+            .last()
+            .map(|tx| tx.get_nonce() + 1)
+            .unwrap_or(on_chain_nonce)
+            .max(on_chain_nonce)
+    }
+
+    /// Generates a new transaction of the sender with the given nonce and a random gas price.
+    fn rng_tx(&mut self, sender: Address, nonce: u64) -> MockTransaction {
+        let gas_price = self.rng_gas_price();
+        self.tx_generator.tx(nonce, &mut self.rng).with_sender(sender).with_gas_price(gas_price)
+    }
+
+    /// Adds the transaction to the pool and checks the outcome against the expected outcome.
+    ///
+    /// Returns the sub-pool the transaction was added to, if any.
+    fn add_transaction(&mut self, pool: &mut MockPool, tx: MockTransaction) -> Option<SubPool> {
+        let sender = tx.get_sender();
+        let on_chain_nonce = self.nonces[&sender];
+        let on_chain_balance = self.balances[&sender];
+
+        let existing = self.pool_transactions(pool, sender);
+        let sender_txs = existing.len();
+        let replaced = existing
+            .into_iter()
+            .find(|existing| existing.get_nonce() == tx.get_nonce())
+            .and_then(|existing| pool.get(existing.hash()));
+
+        let valid_tx = self.validator.validated(tx.clone());
+        let underpriced = replaced
+            .as_ref()
+            .map(|replaced| pool.config().price_bumps.is_underpriced(replaced, &valid_tx))
+            .unwrap_or_default();
+
+        let res = pool.add_transaction(valid_tx, on_chain_balance, on_chain_nonce);
+
+        if sender_txs >= pool.config().max_account_slots {
+            assert!(
+                matches!(res, Err(PoolError::SpammerExceededCapacity(..))),
+                "expected sender capacity to be exceeded: {res:?}"
+            );
+            return None
+        }
+        if underpriced {
+            assert!(
+                matches!(res, Err(PoolError::ReplacementUnderpriced(..))),
+                "expected underpriced replacement: {res:?}"
+            );
+            return None
+        }
+
+        let added = res.expect("transaction should be added");
+        assert_eq!(
+            added.replaced().map(|tx| *tx.hash()),
+            replaced.map(|tx| *tx.hash()),
+            "unexpected replaced transaction"
+        );
+
+        // the next transaction of the sender is pending if it can be afforded and pays the fee
+        if tx.get_nonce() == on_chain_nonce {
+            let expect_pending =
+                tx.get_gas_price() >= self.base_fee && tx.cost() <= on_chain_balance;
+            assert_eq!(added.as_pending().is_some(), expect_pending, "{added:?}");
+        }
+
+        match added {
+            AddedTransaction::Pending(_) => Some(SubPool::Pending),
+            AddedTransaction::Parked { subpool, .. } => Some(subpool),
+        }
+    }
+
     /// Executes the next scenario and applies it to the pool
     pub fn next(&mut self, pool: &mut MockPool) {
         let sender = self.rng_address();
@@ -119,36 +235,141 @@ impl<R: Rng> MockTransactionSimulator<R> {
         let on_chain_nonce = self.nonces[&sender];
         let on_chain_balance = self.balances[&sender];
 
-        match scenario {
+        // the simulator tracks the chain, so the pool must enforce its base fee
+        if pool.block_info().pending_basefee != self.base_fee {
+            pool.set_block_info(BlockInfo { pending_basefee: self.base_fee, ..pool.block_info() });
+        }
+
+        let scenario = match scenario {
             ScenarioType::OnchainNonce => {
-                let tx = self
-                    .tx_generator
-                    .tx(on_chain_nonce, &mut self.rng)
-                    .with_gas_price(self.base_fee);
-                let valid_tx = self.validator.validated(tx);
-
-                let res = pool.add_transaction(valid_tx, on_chain_balance, on_chain_nonce).unwrap();
-
-                // TODO(mattsse): need a way expect based on the current state of the pool and tx
-                // settings
-
-                match res {
-                    AddedTransaction::Pending(_) => {}
-                    AddedTransaction::Parked { .. } => {
-                        panic!("expected pending")
-                    }
+                let tx = self.rng_tx(sender, on_chain_nonce);
+                self.add_transaction(pool, tx);
+                Scenario::OnchainNonce { nonce: on_chain_nonce }
+            }
+            ScenarioType::HigherNonce { skip } => {
+                let nonce = self.next_pool_nonce(pool, sender) + skip + 1;
+                let tx = self.rng_tx(sender, nonce);
+                if let Some(subpool) = self.add_transaction(pool, tx) {
+                    assert_eq!(subpool, SubPool::Queued, "expected nonce gap");
                 }
-
-                // TODO(mattsse): check subpools
+                Scenario::HigherNonce { onchain: on_chain_nonce, nonce }
             }
-            ScenarioType::HigherNonce { .. } => {
-                unimplemented!()
+            ScenarioType::NextNonce => {
+                let nonce = self.next_pool_nonce(pool, sender);
+                let tx = self.rng_tx(sender, nonce);
+                self.add_transaction(pool, tx);
+                Scenario::NextNonce { nonce }
             }
-        }
+            ScenarioType::Replacement { price_bump } => {
+                let existing = self.pool_transactions(pool, sender);
+                if existing.is_empty() {
+                    // nothing to replace, send the next transaction instead
+                    let tx = self.rng_tx(sender, on_chain_nonce);
+                    self.add_transaction(pool, tx);
+                    Scenario::OnchainNonce { nonce: on_chain_nonce }
+                } else {
+                    let idx = self.rng.gen_range(0..existing.len());
+                    let existing = &existing[idx];
+                    let bump = self.rng.gen_range(0..=price_bump);
+                    let price = existing.get_gas_price() * (100 + bump) / 100;
+                    let tx = existing.clone().rng_hash().with_gas_price(price);
+                    self.add_transaction(pool, tx);
+                    Scenario::Replacement { nonce: existing.get_nonce(), price }
+                }
+            }
+            ScenarioType::BaseFeeChange => {
+                // the base fee changes by at most 12.5% per block
+                let delta = (self.base_fee / 8).max(1);
+                let min = self.base_fee.saturating_sub(delta).max(MIN_PROTOCOL_BASE_FEE);
+                self.base_fee = self.rng.gen_range(min..=self.base_fee + delta);
+                pool.set_block_info(BlockInfo {
+                    pending_basefee: self.base_fee,
+                    ..pool.block_info()
+                });
+                Scenario::BaseFeeChange { base_fee: self.base_fee }
+            }
+            ScenarioType::CanonicalUpdate => self.mine_block(pool),
+            ScenarioType::Reorg => self.reorg(pool),
+        };
+
+        self.executed.push(ExecutedScenario {
+            sender,
+            balance: on_chain_balance,
+            nonce: on_chain_nonce,
+            scenario,
+        });
 
-        // make sure everything is set
+        // respect the limits and make sure everything is set
+        pool.discard_worst();
         pool.enforce_invariants()
     }
+
+    /// Mines the best transactions of the pool and applies the state changes to the pool.
+    fn mine_block(&mut self, pool: &mut MockPool) -> Scenario {
+        let mined = pool
+            .best_transactions()
+            .take(self.max_block_transactions)
+            .map(|tx| tx.transaction.clone())
+            .collect::<Vec<_>>();
+
+        let mut previous = HashMap::new();
+        let mut changed_senders = HashMap::new();
+        for tx in mined.iter() {
+            let sender = tx.get_sender();
+            let nonce = self.nonces.get_mut(&sender).unwrap();
+            let balance = self.balances.get_mut(&sender).unwrap();
+            previous.entry(sender).or_insert((*nonce, *balance));
+            assert_eq!(*nonce, tx.get_nonce(), "mined transaction with invalid nonce");
+
+            *nonce += 1;
+            *balance = balance.saturating_sub(tx.cost());
+            let sender_id = self.validator.ids.sender_id(&sender).unwrap();
+            changed_senders
+                .insert(sender_id, SenderInfo { state_nonce: *nonce, balance: *balance });
+        }
+
+        self.block_number += 1;
+        let block_info = BlockInfo {
+            last_seen_block_hash: H256::random(),
+            last_seen_block_number: self.block_number,
+            ..pool.block_info()
+        };
+        let hashes = mined.iter().map(|tx| *tx.hash()).collect::<Vec<_>>();
+        pool.on_canonical_state_change(block_info, hashes.clone(), changed_senders);
+
+        for hash in hashes.iter() {
+            assert!(!pool.contains(hash), "mined transaction {hash:?} still in pool");
+        }
+
+        self.mined.push(MinedBlock { transactions: mined, previous });
+        Scenario::CanonicalUpdate { block: self.block_number, mined: hashes }
+    }
+
+    /// Reverts the last mined block and re-injects its transactions into the pool.
+    fn reorg(&mut self, pool: &mut MockPool) -> Scenario {
+        let Some(MinedBlock { transactions, previous }) = self.mined.pop() else {
+            return Scenario::Reorg { block: self.block_number, reinjected: Vec::new() }
+        };
+
+        let mut changed_senders = HashMap::new();
+        for (sender, (nonce, balance)) in previous {
+            self.nonces.insert(sender, nonce);
+            self.balances.insert(sender, balance);
+            let sender_id = self.validator.ids.sender_id(&sender).unwrap();
+            changed_senders.insert(sender_id, SenderInfo { state_nonce: nonce, balance });
+        }
+        pool.update_accounts(changed_senders);
+
+        // the transactions of a sender are mined in nonce order, so they can be re-added in order
+        let reinjected = transactions.iter().map(|tx| *tx.hash()).collect();
+        for tx in transactions {
+            self.add_transaction(pool, tx);
+        }
+
+        let block = self.block_number;
+        self.block_number -= 1;
+        Scenario::Reorg { block, reinjected }
+    }
 }
 
 /// How to configure a new mock transaction stream
@@ -163,6 +384,8 @@ pub struct MockSimulatorConfig {
     pub base_fee: u128,
     /// generator for transactions
     pub tx_generator: MockTransactionDistribution,
+    /// The max number of transactions mined in a block.
+    pub max_block_transactions: usize,
 }
 
 impl MockSimulatorConfig {
@@ -172,11 +395,23 @@ impl MockSimulatorConfig {
     }
 }
 
-/// Represents
+/// Represents the type of scenario the simulator can execute
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ScenarioType {
+    /// Send a tx with the on chain nonce of the sender.
     OnchainNonce,
+    /// Send a tx that skips `skip` nonces after the sender's last transaction.
     HigherNonce { skip: u64 },
+    /// Send a tx with the next nonce after the sender's last transaction.
+    NextNonce,
+    /// Replace a tx of the sender with a price bumped by up to `price_bump` %.
+    Replacement { price_bump: u128 },
+    /// Change the pending base fee.
+    BaseFeeChange,
+    /// Mine the best transactions of the pool.
+    CanonicalUpdate,
+    /// Revert the last mined block and re-inject its transactions.
+    Reorg,
 }
 
 /// The actual scenario, ready to be executed
@@ -190,15 +425,23 @@ pub enum Scenario {
     OnchainNonce { nonce: u64 },
     /// Send a tx with a higher nonce that what the sender has on chain
     HigherNonce { onchain: u64, nonce: u64 },
-    Multi {
-        // Execute multiple test scenarios
-        scenario: Vec<Scenario>,
-    },
+    /// Send a tx with the next nonce after the sender's last transaction.
+    NextNonce { nonce: u64 },
+    /// Replace the tx with the given nonce.
+    Replacement { nonce: u64, price: u128 },
+    /// Set a new pending base fee.
+    BaseFeeChange { base_fee: u128 },
+    /// Mine a new block with the given transactions.
+    CanonicalUpdate { block: u64, mined: Vec<H256> },
+    /// Revert the block and re-inject its transactions.
+    Reorg { block: u64, reinjected: Vec<H256> },
 }
 
 /// Represents an executed scenario
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutedScenario {
+    /// The sender that was picked for the scenario.
+    sender: Address,
     /// balance at the time of execution
     balance: U256,
     /// nonce at the time of execution
@@ -207,24 +450,76 @@ pub struct ExecutedScenario {
     scenario: Scenario,
 }
 
-/// All executed scenarios by a sender
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ExecutedScenarios {
-    sender: Address,
-    scenarios: Vec<ExecutedScenario>,
+/// A block mined by the simulator.
+#[derive(Debug, Clone)]
+struct MinedBlock {
+    /// The transactions included in the block.
+    transactions: Vec<MockTransaction>,
+    /// The nonce and balance of all senders in the block before the block was mined.
+    previous: HashMap<Address, (u64, U256)>,
 }
 
-#[test]
-fn test_on_chain_nonce_scenario() {
-    let config = MockSimulatorConfig {
-        num_senders: 10,
-        balance: U256::from(200_000u64),
-        scenarios: vec![ScenarioType::OnchainNonce],
-        base_fee: 10,
-        tx_generator: MockTransactionDistribution::new(30, 10..100),
-    };
-    let mut simulator = MockTransactionSimulator::new(rand::thread_rng(), config);
-    let mut pool = MockPool::default();
-
-    simulator.next(&mut pool);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SubPoolLimit;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn config(scenarios: Vec<ScenarioType>) -> MockSimulatorConfig {
+        MockSimulatorConfig {
+            num_senders: 10,
+            balance: U256::from(200_000u64),
+            scenarios,
+            base_fee: 10,
+            tx_generator: MockTransactionDistribution::new(30, 10..100),
+            max_block_transactions: 20,
+        }
+    }
+
+    #[test]
+    fn test_on_chain_nonce_scenario() {
+        let mut simulator = MockTransactionSimulator::new(
+            rand::thread_rng(),
+            config(vec![ScenarioType::OnchainNonce]),
+        );
+        let mut pool = MockPool::default();
+
+        for _ in 0..100 {
+            simulator.next(&mut pool);
+        }
+    }
+
+    #[test]
+    fn test_random_scenarios() {
+        let scenarios = vec![
+            ScenarioType::OnchainNonce,
+            ScenarioType::HigherNonce { skip: 2 },
+            ScenarioType::NextNonce,
+            ScenarioType::NextNonce,
+            ScenarioType::Replacement { price_bump: 20 },
+            ScenarioType::BaseFeeChange,
+            ScenarioType::CanonicalUpdate,
+            ScenarioType::Reorg,
+        ];
+        let limit = SubPoolLimit { max_txs: 20, max_size: usize::MAX };
+        let pool_config = PoolConfig {
+            pending_limit: limit.clone(),
+            basefee_limit: limit.clone(),
+            queued_limit: limit.clone(),
+            blob_limit: limit,
+            max_account_slots: 6,
+            max_total_transactions: 50,
+            ..Default::default()
+        };
+
+        for seed in 0..32 {
+            let rng = StdRng::seed_from_u64(seed);
+            let mut simulator = MockTransactionSimulator::new(rng, config(scenarios.clone()));
+            let mut pool = MockPool::with_config(pool_config.clone());
+
+            for _ in 0..500 {
+                simulator.next(&mut pool);
+            }
+        }
+    }
 }
@@ -1,11 +1,13 @@
 use crate::{
+    blobstore::{BlobAndProof, BlobStoreError},
     error::PoolResult,
     pool::{state::SubPool, TransactionEvents},
     validate::ValidPoolTransaction,
 };
 use reth_primitives::{
-    Address, FromRecoveredTransaction, IntoRecoveredTransaction, PeerId, Transaction,
-    TransactionKind, TransactionSignedEcRecovered, TxHash, EIP1559_TX_TYPE_ID, H256, U256,
+    Address, BlobTransactionSidecar, FromRecoveredTransaction, IntoRecoveredTransaction, PeerId,
+    PooledTransactionsElement, Transaction, TransactionKind, TransactionSignedEcRecovered, TxHash,
+    EIP1559_TX_TYPE_ID, EIP4844_TX_TYPE_ID, H256, U256,
 };
 use reth_rlp::Encodable;
 use std::{collections::HashMap, fmt, sync::Arc};
@@ -195,6 +197,39 @@ pub trait TransactionPool: Send + Sync + Clone {
         txs: impl IntoIterator<Item = TxHash>,
     ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
 
+    /// Returns the transactions for the given hashes in the form in which they're exchanged in
+    /// [`PooledTransactions`](https://github.com/ethereum/devp2p/blob/master/caps/eth.md#pooledtransactions-0x0a)
+    /// responses: blob transactions include their sidecar.
+    ///
+    /// Same as [TransactionPool::get_all], transactions that are not available are skipped.
+    /// Private transactions are skipped as well, see [TransactionOrigin::Private].
+    ///
+    /// Consumer: P2P
+    fn get_pooled_transaction_elements(
+        &self,
+        tx_hashes: Vec<TxHash>,
+    ) -> Vec<PooledTransactionsElement>;
+
+    /// Returns the sidecar of the blob transaction with the given hash, if it's in the blob store.
+    fn get_blob(&self, tx_hash: TxHash) -> Result<Option<BlobTransactionSidecar>, BlobStoreError>;
+
+    /// Returns the sidecars of all given blob transactions that are in the blob store.
+    ///
+    /// Consumer: Block production
+    fn get_all_blobs(
+        &self,
+        tx_hashes: Vec<TxHash>,
+    ) -> Result<Vec<(TxHash, BlobTransactionSidecar)>, BlobStoreError>;
+
+    /// Returns the blob with its commitment and proof for the given versioned hash, if a blob
+    /// transaction in the pool carries it.
+    ///
+    /// Consumer: Block production
+    fn get_blob_by_versioned_hash(
+        &self,
+        versioned_hash: H256,
+    ) -> Result<Option<BlobAndProof>, BlobStoreError>;
+
     /// Notify the pool about transactions that are propagated to peers.
     ///
     /// Consumer: P2P
@@ -205,6 +240,50 @@ pub trait TransactionPool: Send + Sync + Clone {
         &self,
         sender: Address,
     ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
+
+    /// Returns all transactions sent by a given user, together with the reasons why they're
+    /// parked, if they're not pending.
+    fn get_transactions_by_sender_with_status(
+        &self,
+        sender: Address,
+    ) -> Vec<PoolTransactionStatus<Self::Transaction>>;
+}
+
+/// A transaction in the pool and the reasons why it is not pending, if any.
+#[derive(Debug, Clone)]
+pub struct PoolTransactionStatus<T: PoolTransaction> {
+    /// The transaction.
+    pub transaction: Arc<ValidPoolTransaction<T>>,
+    /// All reasons why this transaction is parked.
+    ///
+    /// This is empty if the transaction is pending.
+    pub parked_reasons: Vec<ParkedReason>,
+}
+
+impl<T: PoolTransaction> PoolTransactionStatus<T> {
+    /// Returns true if the transaction is ready to be included in the next block.
+    pub fn is_pending(&self) -> bool {
+        self.parked_reasons.is_empty()
+    }
+}
+
+/// The reason why a transaction is parked and can't be included in the next block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParkedReason {
+    /// There's a gap between the sender's on chain nonce and the transaction's nonce that isn't
+    /// filled by other transactions in the pool.
+    NonceGap,
+    /// A transaction of the same sender with a lower nonce is parked.
+    ParkedAncestor,
+    /// The sender's balance doesn't cover the cost of this transaction and all transactions of
+    /// the sender with a lower nonce.
+    InsufficientBalance,
+    /// The gas limit of the transaction exceeds the block gas limit.
+    ExceedsBlockGasLimit,
+    /// The fee cap of the transaction is below the base fee of the pending block.
+    FeeCapBelowBaseFee,
+    /// The blob fee cap of the transaction is below the blob fee of the pending block.
+    BlobFeeCapBelowBlobFee,
 }
 
 /// A Helper type that bundles all transactions in the pool.
@@ -297,6 +376,15 @@ pub enum TransactionOrigin {
     /// This is usually considered an "untrusted" source, for example received from another in the
     /// network.
     External,
+    /// Transaction was submitted privately, for example via `eth_sendPrivateRawTransaction`.
+    ///
+    /// Private transactions are included in locally built blocks, but they're never announced to
+    /// or served to peers.
+    Private {
+        /// The last block the transaction can be included in, the transaction is discarded once
+        /// the chain advanced past this block.
+        max_block_number: Option<u64>,
+    },
 }
 
 // === impl TransactionOrigin ===
@@ -306,6 +394,20 @@ impl TransactionOrigin {
     pub fn is_local(&self) -> bool {
         matches!(self, TransactionOrigin::Local)
     }
+
+    /// Whether the transaction was submitted privately and must not be propagated.
+    pub fn is_private(&self) -> bool {
+        matches!(self, TransactionOrigin::Private { .. })
+    }
+
+    /// Returns `true` if this is a private transaction that can no longer be included in the
+    /// block with the given number.
+    pub fn is_expired(&self, block_number: u64) -> bool {
+        match self {
+            TransactionOrigin::Private { max_block_number: Some(max) } => *max < block_number,
+            _ => false,
+        }
+    }
 }
 
 /// Represents changes after a new canonical block or range of canonical blocks was added to the
@@ -326,6 +428,8 @@ pub struct CanonicalStateUpdate {
     ///
     /// The base fee of a block depends on the utilization of the last block and its base fee.
     pub pending_block_base_fee: u128,
+    /// EIP-4844 blob fee of the _next_ (pending) block, if blob transactions are enabled.
+    pub pending_block_blob_fee: Option<u128>,
     /// A set of changed accounts across a range of blocks.
     pub changed_accounts: Vec<ChangedAccount>,
     /// All mined transactions in the block range.
@@ -408,6 +512,26 @@ pub trait PoolTransaction:
     /// This will return `None` for non-EIP1559 transactions
     fn max_priority_fee_per_gas(&self) -> Option<u128>;
 
+    /// Returns the tip per gas the block author would receive if this transaction is included in
+    /// a block with the given base fee.
+    ///
+    /// For EIP-1559 transactions this is `min(maxFeePerGas - baseFee, maxPriorityFeePerGas)`, for
+    /// legacy transactions this is `gasPrice - baseFee`.
+    ///
+    /// Returns `None` if the transaction's fee cap is below the base fee.
+    fn effective_tip_per_gas(&self, base_fee: u128) -> Option<u128> {
+        let fee_headroom = self.max_fee_per_gas().checked_sub(base_fee)?;
+        match self.max_priority_fee_per_gas() {
+            Some(priority_fee) => Some(priority_fee.min(fee_headroom)),
+            None => Some(fee_headroom),
+        }
+    }
+
+    /// Returns the EIP-4844 max fee per blob gas the caller is willing to pay.
+    ///
+    /// This will return `None` for non-EIP4844 transactions
+    fn max_fee_per_blob_gas(&self) -> Option<u128>;
+
     /// Returns the transaction's [`TransactionKind`], which is the address of the recipient or
     /// [`TransactionKind::Create`] if the transaction is a contract creation.
     fn kind(&self) -> &TransactionKind;
@@ -423,11 +547,44 @@ pub trait PoolTransaction:
         self.tx_type() == EIP1559_TX_TYPE_ID
     }
 
+    /// Returns true if the transaction is an EIP-4844 blob transaction.
+    fn is_eip4844(&self) -> bool {
+        self.tx_type() == EIP4844_TX_TYPE_ID
+    }
+
     /// Returns the length of the rlp encoded object
     fn encoded_length(&self) -> usize;
 
     /// Returns chain_id
     fn chain_id(&self) -> Option<u64>;
+
+    /// Creates the pool transaction from a recovered transaction and the sidecar it was received
+    /// with, if this is a blob transaction.
+    ///
+    /// By default the sidecar is dropped.
+    fn from_recovered_pooled_transaction(
+        tx: TransactionSignedEcRecovered,
+        sidecar: Option<BlobTransactionSidecar>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        let _ = sidecar;
+        Self::from_recovered_transaction(tx)
+    }
+
+    /// Returns the sidecar of this blob transaction, if it carries one.
+    fn blob_sidecar(&self) -> Option<&BlobTransactionSidecar> {
+        None
+    }
+
+    /// Takes the sidecar out of this blob transaction.
+    ///
+    /// This is used by the pool to move the sidecar into the blob store before the transaction
+    /// is inserted.
+    fn take_blob_sidecar(&mut self) -> Option<BlobTransactionSidecar> {
+        None
+    }
 }
 
 /// The default [PoolTransaction] for the [Pool](crate::Pool).
@@ -444,6 +601,9 @@ pub struct PooledTransaction {
 
     /// This is `priority + basefee`for EIP-1559 and `gasPrice` for legacy transactions.
     pub(crate) effective_gas_price: u128,
+
+    /// The sidecar of a blob transaction, until it's moved into the blob store.
+    pub(crate) blob_sidecar: Option<BlobTransactionSidecar>,
 }
 
 impl PooledTransaction {
@@ -498,6 +658,7 @@ impl PoolTransaction for PooledTransaction {
             Transaction::Legacy(tx) => tx.gas_price,
             Transaction::Eip2930(tx) => tx.gas_price,
             Transaction::Eip1559(tx) => tx.max_fee_per_gas,
+            Transaction::Eip4844(tx) => tx.max_fee_per_gas,
         }
     }
 
@@ -509,9 +670,17 @@ impl PoolTransaction for PooledTransaction {
             Transaction::Legacy(_) => None,
             Transaction::Eip2930(_) => None,
             Transaction::Eip1559(tx) => Some(tx.max_priority_fee_per_gas),
+            Transaction::Eip4844(tx) => Some(tx.max_priority_fee_per_gas),
         }
     }
 
+    /// Returns the EIP-4844 max fee per blob gas the caller is willing to pay.
+    ///
+    /// This will return `None` for non-EIP4844 transactions
+    fn max_fee_per_blob_gas(&self) -> Option<u128> {
+        self.transaction.max_fee_per_blob_gas()
+    }
+
     /// Returns the transaction's [`TransactionKind`], which is the address of the recipient or
     /// [`TransactionKind::Create`] if the transaction is a contract creation.
     fn kind(&self) -> &TransactionKind {
@@ -537,6 +706,26 @@ impl PoolTransaction for PooledTransaction {
     fn chain_id(&self) -> Option<u64> {
         self.transaction.chain_id()
     }
+
+    /// Creates the pool transaction and keeps the sidecar of a blob transaction.
+    fn from_recovered_pooled_transaction(
+        tx: TransactionSignedEcRecovered,
+        sidecar: Option<BlobTransactionSidecar>,
+    ) -> Self {
+        let mut tx = Self::from_recovered_transaction(tx);
+        tx.blob_sidecar = sidecar;
+        tx
+    }
+
+    /// Returns the sidecar of this blob transaction, if it carries one.
+    fn blob_sidecar(&self) -> Option<&BlobTransactionSidecar> {
+        self.blob_sidecar.as_ref()
+    }
+
+    /// Takes the sidecar out of this blob transaction.
+    fn take_blob_sidecar(&mut self) -> Option<BlobTransactionSidecar> {
+        self.blob_sidecar.take()
+    }
 }
 
 impl FromRecoveredTransaction for PooledTransaction {
@@ -558,9 +747,17 @@ impl FromRecoveredTransaction for PooledTransaction {
                 let effective_gas_price = t.max_priority_fee_per_gas;
                 (cost, effective_gas_price)
             }
+            Transaction::Eip4844(t) => {
+                let blob_cost = U256::from(t.max_fee_per_blob_gas) * U256::from(t.blob_gas());
+                let cost = U256::from(t.max_fee_per_gas) * U256::from(t.gas_limit) +
+                    U256::from(t.value) +
+                    blob_cost;
+                let effective_gas_price = t.max_priority_fee_per_gas;
+                (cost, effective_gas_price)
+            }
         };
 
-        PooledTransaction { transaction: tx, cost, effective_gas_price }
+        PooledTransaction { transaction: tx, cost, effective_gas_price, blob_sidecar: None }
     }
 }
 
@@ -585,6 +782,10 @@ pub struct PoolSize {
     pub queued: usize,
     /// Reported size of transactions in the _queued_ sub-pool.
     pub queued_size: usize,
+    /// Number of transactions in the _blob_ sub-pool.
+    pub blob: usize,
+    /// Reported size of transactions in the _blob_ sub-pool.
+    pub blob_size: usize,
 }
 
 /// Represents the current status of the pool.
@@ -599,4 +800,9 @@ pub struct BlockInfo {
     /// Note: this is the derived base fee of the _next_ block that builds on the clock the pool is
     /// currently tracking.
     pub pending_basefee: u128,
+    /// Currently enforced blob fee: the threshold for blob transactions in the blob sub-pool.
+    ///
+    /// Note: this is the derived blob fee of the _next_ block, `None` if blob transactions are
+    /// not enabled yet.
+    pub pending_blob_fee: Option<u128>,
 }
@@ -7,12 +7,18 @@ use crate::{
     MAX_INIT_CODE_SIZE, TX_MAX_SIZE,
 };
 use reth_primitives::{
+    eip4844::{KzgSettings, MAINNET_KZG_TRUSTED_SETUP},
     Address, ChainSpec, IntoRecoveredTransaction, InvalidTransactionError, TransactionKind,
     TransactionSignedEcRecovered, TxHash, EIP1559_TX_TYPE_ID, EIP2930_TX_TYPE_ID,
-    LEGACY_TX_TYPE_ID, U256,
+    EIP4844_TX_TYPE_ID, LEGACY_TX_TYPE_ID, U256,
 };
 use reth_provider::{AccountProvider, StateProviderFactory};
-use std::{fmt, marker::PhantomData, sync::Arc, time::Instant};
+use std::{
+    fmt,
+    marker::PhantomData,
+    sync::Arc,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
 
 /// A Result type returned after checking a transaction's validity.
 #[derive(Debug)]
@@ -119,8 +125,10 @@ pub struct EthTransactionValidator<Client, T> {
     eip1559: bool,
     /// The current max gas limit
     block_gas_limit: u64,
-    /// Minimum priority fee to enforce for acceptance into the pool.
-    minimum_priority_fee: Option<u128>,
+    /// The maximum encoded size of a transaction, see [PoolTransaction::encoded_length].
+    max_tx_size: usize,
+    /// The KZG trusted setup the proofs of blob transactions are verified with.
+    kzg_settings: Arc<KzgSettings>,
     /// Marker for the transaction type
     _marker: PhantomData<T>,
 }
@@ -139,15 +147,37 @@ impl<Client, Tx> EthTransactionValidator<Client, Tx> {
             eip2718: true,
             eip1559: true,
             block_gas_limit: 30_000_000,
-            minimum_priority_fee: None,
+            max_tx_size: TX_MAX_SIZE,
+            kzg_settings: Arc::clone(&MAINNET_KZG_TRUSTED_SETUP),
             _marker: Default::default(),
         }
     }
 
+    /// Sets the maximum encoded size of a transaction.
+    ///
+    /// Larger transactions are rejected before their sender's state is fetched.
+    pub fn with_max_tx_size(mut self, max_tx_size: usize) -> Self {
+        self.max_tx_size = max_tx_size;
+        self
+    }
+
+    /// Sets the KZG trusted setup that is used to verify the proofs of blob transactions.
+    pub fn with_kzg_settings(mut self, kzg_settings: Arc<KzgSettings>) -> Self {
+        self.kzg_settings = kzg_settings;
+        self
+    }
+
     /// Returns the configured chain id
     pub fn chain_id(&self) -> u64 {
         self.chain_spec.chain().id()
     }
+
+    /// Returns `true` if Cancun is active at the current time, which enables EIP-4844 blob
+    /// transactions.
+    fn is_cancun_active(&self) -> bool {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        self.chain_spec.is_cancun_activated_at_timestamp(now)
+    }
 }
 
 #[async_trait::async_trait]
@@ -160,7 +190,7 @@ where
 
     async fn validate_transaction(
         &self,
-        origin: TransactionOrigin,
+        _origin: TransactionOrigin,
         transaction: Self::Transaction,
     ) -> TransactionValidationOutcome<Self::Transaction> {
         // Checks for tx_type
@@ -188,6 +218,16 @@ where
                 }
             }
 
+            EIP4844_TX_TYPE_ID => {
+                // Reject blob transactions until Cancun activates.
+                if !self.is_cancun_active() {
+                    return TransactionValidationOutcome::Invalid(
+                        transaction,
+                        InvalidTransactionError::Eip4844Disabled.into(),
+                    )
+                }
+            }
+
             _ => {
                 return TransactionValidationOutcome::Invalid(
                     transaction,
@@ -197,14 +237,33 @@ where
         };
 
         // Reject transactions over defined size to prevent DOS attacks
-        if transaction.size() > TX_MAX_SIZE {
-            let size = transaction.size();
+        if transaction.encoded_length() > self.max_tx_size {
+            let size = transaction.encoded_length();
             return TransactionValidationOutcome::Invalid(
                 transaction,
-                InvalidPoolTransactionError::OversizedData(size, TX_MAX_SIZE),
+                InvalidPoolTransactionError::OversizedData(size, self.max_tx_size),
             )
         }
 
+        // Blob transactions must carry a sidecar that matches their versioned hashes and whose
+        // proofs are valid.
+        if transaction.is_eip4844() {
+            let Some(sidecar) = transaction.blob_sidecar() else {
+                return TransactionValidationOutcome::Invalid(
+                    transaction,
+                    InvalidPoolTransactionError::MissingBlobSidecar,
+                )
+            };
+            let recovered = transaction.to_recovered_transaction();
+            let versioned_hashes = recovered.blob_versioned_hashes().unwrap_or_default();
+            if let Err(err) = sidecar.validate(versioned_hashes, &self.kzg_settings) {
+                return TransactionValidationOutcome::Invalid(
+                    transaction,
+                    InvalidPoolTransactionError::InvalidBlobSidecar(err),
+                )
+            }
+        }
+
         // Check whether the init code size has been exceeded.
         if self.shanghai {
             if let Err(err) = self.ensure_max_init_code_size(&transaction, MAX_INIT_CODE_SIZE) {
@@ -229,18 +288,6 @@ where
             )
         }
 
-        // Drop non-local transactions with a fee lower than the configured fee for acceptance into
-        // the pool.
-        if !origin.is_local() &&
-            transaction.is_eip1559() &&
-            transaction.max_priority_fee_per_gas() < self.minimum_priority_fee
-        {
-            return TransactionValidationOutcome::Invalid(
-                transaction,
-                InvalidPoolTransactionError::Underpriced,
-            )
-        }
-
         // Checks for chainid
         if let Some(chain_id) = transaction.chain_id() {
             if chain_id != self.chain_id() {
@@ -305,7 +352,7 @@ pub struct ValidPoolTransaction<T: PoolTransaction> {
     pub transaction: T,
     /// The identifier for this transaction.
     pub transaction_id: TransactionId,
-    /// Whether to propagate the transaction.
+    /// Whether to propagate the transaction, this is `false` for private transactions.
     pub propagate: bool,
     /// Total cost of the transaction: `feeCap x gasLimit + transferredValue`.
     pub cost: U256,
@@ -367,12 +414,27 @@ impl<T: PoolTransaction> ValidPoolTransaction<T> {
         self.transaction.gas_limit()
     }
 
-    /// Returns true if this transaction is underpriced compared to the other.
-    pub(crate) fn is_underpriced(&self, other: &Self) -> bool {
-        self.transaction.effective_gas_price() <= other.transaction.effective_gas_price()
+    /// Returns the max fee per blob gas for EIP-4844 blob transactions, `None` otherwise.
+    pub fn max_fee_per_blob_gas(&self) -> Option<u128> {
+        self.transaction.max_fee_per_blob_gas()
+    }
+
+    /// Returns true if the transaction is an EIP-4844 blob transaction.
+    pub fn is_eip4844(&self) -> bool {
+        self.transaction.is_eip4844()
+    }
+
+    /// Returns the EIP-1559 priority fee the caller is paying to the block author.
+    ///
+    /// For legacy transactions this is gas_price.
+    pub fn priority_fee_or_price(&self) -> u128 {
+        self.transaction.max_priority_fee_per_gas().unwrap_or_else(|| self.max_fee_per_gas())
     }
 
     /// Whether the transaction originated locally.
+    ///
+    /// Note: unlike [LocalTransactionConfig::is_local](crate::LocalTransactionConfig::is_local),
+    /// this does not include private transactions.
     pub fn is_local(&self) -> bool {
         self.origin.is_local()
     }
@@ -25,6 +25,16 @@ impl From<Account> for EthAccount {
     }
 }
 
+impl From<EthAccount> for Account {
+    fn from(acc: EthAccount) -> Self {
+        Account {
+            nonce: acc.nonce,
+            balance: acc.balance,
+            bytecode_hash: (acc.code_hash != KECCAK_EMPTY).then_some(acc.code_hash),
+        }
+    }
+}
+
 impl EthAccount {
     /// Set storage root on account.
     pub fn with_storage_root(mut self, storage_root: H256) -> Self {
@@ -27,3 +27,14 @@ pub enum StorageRootError {
     #[error(transparent)]
     DB(#[from] reth_db::DatabaseError),
 }
+
+/// Error of a [SparseTrie](crate::SparseTrie).
+#[derive(Error, PartialEq, Eq, Clone, Debug)]
+pub enum SparseTrieError {
+    /// A trie node needed to access a path is not known.
+    #[error("Trie node {0:?} is missing")]
+    MissingNode(reth_primitives::H256),
+    /// A trie node or a value in the trie could not be decoded.
+    #[error("Invalid trie node: {0}")]
+    InvalidNode(#[from] reth_rlp::DecodeError),
+}
@@ -26,12 +26,24 @@ pub mod hashed_cursor;
 pub mod walker;
 
 mod errors;
-pub use errors::{StateRootError, StorageRootError};
+pub use errors::{SparseTrieError, StateRootError, StorageRootError};
 
 /// The implementation of the Merkle Patricia Trie.
 mod trie;
 pub use trie::{StateRoot, StorageRoot};
 
+/// Merkle proofs of accounts and storage slots.
+mod proof;
+pub use proof::{AccountProof, MultiProof, Proof, StorageMultiProof};
+
+/// A partially known trie for updating the state without a database.
+mod sparse;
+pub use sparse::{SparseStateTrie, SparseTrie};
+
+/// Parallel computation of the state root.
+mod parallel;
+pub use parallel::ParallelStateRoot;
+
 /// Buffer for trie updates.
 pub mod updates;
 
@@ -0,0 +1,353 @@
+use crate::{
+    hashed_cursor::{HashedPostState, HashedPostStateCursorFactory},
+    prefix_set::{PrefixSet, PrefixSetLoader},
+    updates::TrieUpdates,
+    StateRoot, StateRootError, StorageRoot, StorageRootError,
+};
+use reth_db::{database::Database, transaction::DbTx};
+use reth_primitives::{BlockNumber, H256};
+use std::{
+    collections::HashMap,
+    ops::RangeInclusive,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// The storage roots and storage trie updates of accounts, keyed by hashed address.
+type StorageRoots = HashMap<H256, (H256, TrieUpdates)>;
+
+/// ParallelStateRoot computes the root node of the state trie like [StateRoot], but calculates the
+/// storage roots of all accounts with changed storage concurrently beforehand.
+///
+/// Every worker computes storage roots in its own read-only transaction, as does the final walk of
+/// the account trie. The database must therefore contain the committed hashed state and trie nodes
+/// the root is computed on top of. Changes that are not yet committed can be provided as
+/// [HashedPostState], which is overlaid on the database state.
+///
+/// Intermediate progress is not supported, the root is always computed in one go.
+#[derive(Debug)]
+pub struct ParallelStateRoot<'a, DB> {
+    /// The database to open transactions on.
+    db: &'a DB,
+    /// The state changes on top of the database state.
+    hashed_state: HashedPostState,
+    /// A set of account prefixes that have changed.
+    changed_account_prefixes: PrefixSet,
+    /// A map containing storage changes with the hashed address as key and a set of storage key
+    /// prefixes as the value.
+    changed_storage_prefixes: HashMap<H256, PrefixSet>,
+    /// The number of threads storage roots are computed on.
+    workers: usize,
+}
+
+impl<'a, DB> ParallelStateRoot<'a, DB>
+where
+    DB: Database,
+{
+    /// Create a new [ParallelStateRoot] instance using all available cores.
+    pub fn new(db: &'a DB) -> Self {
+        Self {
+            db,
+            hashed_state: HashedPostState::default(),
+            changed_account_prefixes: PrefixSet::default(),
+            changed_storage_prefixes: HashMap::default(),
+            workers: std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+        }
+    }
+
+    /// Set the changed account prefixes.
+    pub fn with_changed_account_prefixes(mut self, prefixes: PrefixSet) -> Self {
+        self.changed_account_prefixes = prefixes;
+        self
+    }
+
+    /// Set the changed storage prefixes.
+    pub fn with_changed_storage_prefixes(mut self, prefixes: HashMap<H256, PrefixSet>) -> Self {
+        self.changed_storage_prefixes = prefixes;
+        self
+    }
+
+    /// Set the state changes to overlay on the database state.
+    ///
+    /// Note: this does not set the changed prefixes, see
+    /// [HashedPostState::construct_prefix_sets].
+    pub fn with_hashed_state(mut self, hashed_state: HashedPostState) -> Self {
+        self.hashed_state = hashed_state;
+        self
+    }
+
+    /// Set the number of threads storage roots are computed on.
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    /// Given a block number range, identifies all the accounts and storage keys that have changed.
+    ///
+    /// # Returns
+    ///
+    /// An instance of state root calculator with account and storage prefixes loaded.
+    pub fn incremental_root_calculator(
+        db: &'a DB,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Self, StateRootError> {
+        let tx = db.tx()?;
+        let (account_prefixes, storage_prefixes) = PrefixSetLoader::new(&tx).load(range)?;
+        tx.commit()?;
+        Ok(Self::new(db)
+            .with_changed_account_prefixes(account_prefixes)
+            .with_changed_storage_prefixes(storage_prefixes))
+    }
+
+    /// Computes the state root of the trie with the changed account and storage prefixes of the
+    /// given block range and existing trie nodes collecting updates in the process.
+    ///
+    /// # Returns
+    ///
+    /// The updated state root and the trie updates.
+    pub fn incremental_root_with_updates(
+        db: &'a DB,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<(H256, TrieUpdates), StateRootError> {
+        tracing::debug!(target: "loader", "incremental parallel state root");
+        Self::incremental_root_calculator(db, range)?.root_with_updates()
+    }
+
+    /// Walks the intermediate nodes of existing state trie (if any) and hashed entries. Feeds the
+    /// nodes into the hash builder. Collects the updates in the process.
+    ///
+    /// # Returns
+    ///
+    /// The state root and the trie updates.
+    pub fn root_with_updates(self) -> Result<(H256, TrieUpdates), StateRootError> {
+        self.calculate(true)
+    }
+
+    /// Walks the intermediate nodes of existing state trie (if any) and hashed entries. Feeds the
+    /// nodes into the hash builder.
+    ///
+    /// # Returns
+    ///
+    /// The state root hash.
+    pub fn root(self) -> Result<H256, StateRootError> {
+        let (root, _) = self.calculate(false)?;
+        Ok(root)
+    }
+
+    fn calculate(self, retain_updates: bool) -> Result<(H256, TrieUpdates), StateRootError> {
+        let storage_roots = self.storage_roots(retain_updates)?;
+        tracing::debug!(target: "trie::parallel", storage_roots = storage_roots.len(), "calculated storage roots");
+
+        let tx = self.db.tx()?;
+        let result = account_root(
+            &tx,
+            &self.hashed_state,
+            self.changed_account_prefixes,
+            self.changed_storage_prefixes,
+            storage_roots,
+            retain_updates,
+        )?;
+        tx.commit()?;
+        Ok(result)
+    }
+
+    /// Computes the storage roots of all accounts with changed storage on the configured number of
+    /// threads.
+    fn storage_roots(&self, retain_updates: bool) -> Result<StorageRoots, StorageRootError> {
+        let targets = self.changed_storage_prefixes.iter().collect::<Vec<_>>();
+        if targets.is_empty() {
+            return Ok(StorageRoots::default())
+        }
+
+        let next = AtomicUsize::new(0);
+        std::thread::scope(|scope| {
+            let handles = (0..self.workers.min(targets.len()))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let tx = self.db.tx()?;
+                        let mut roots = Vec::new();
+                        while let Some((hashed_address, prefixes)) =
+                            targets.get(next.fetch_add(1, Ordering::Relaxed))
+                        {
+                            let result = storage_root(
+                                &tx,
+                                &self.hashed_state,
+                                **hashed_address,
+                                (*prefixes).clone(),
+                                retain_updates,
+                            )?;
+                            roots.push((**hashed_address, result));
+                        }
+                        tx.commit()?;
+                        Ok::<_, StorageRootError>(roots)
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            let mut storage_roots = StorageRoots::with_capacity(targets.len());
+            for handle in handles {
+                storage_roots.extend(handle.join().expect("Expects for thread to not panic")?);
+            }
+            Ok(storage_roots)
+        })
+    }
+}
+
+/// Computes the storage root of a single account on top of the given transaction.
+fn storage_root<'tx, TX>(
+    tx: &TX,
+    hashed_state: &HashedPostState,
+    hashed_address: H256,
+    changed_prefixes: PrefixSet,
+    retain_updates: bool,
+) -> Result<(H256, TrieUpdates), StorageRootError>
+where
+    TX: DbTx<'tx>,
+{
+    let hashed_cursor_factory = HashedPostStateCursorFactory::new(tx, hashed_state);
+    let calculator =
+        StorageRoot::new_hashed_with_factory(tx, &hashed_cursor_factory, hashed_address)
+            .with_changed_prefixes(changed_prefixes);
+    if retain_updates {
+        calculator.root_with_updates()
+    } else {
+        Ok((calculator.root()?, TrieUpdates::default()))
+    }
+}
+
+/// Walks the account trie on top of the given transaction, using the precomputed storage roots.
+fn account_root<'tx, TX>(
+    tx: &TX,
+    hashed_state: &HashedPostState,
+    changed_account_prefixes: PrefixSet,
+    changed_storage_prefixes: HashMap<H256, PrefixSet>,
+    storage_roots: StorageRoots,
+    retain_updates: bool,
+) -> Result<(H256, TrieUpdates), StateRootError>
+where
+    TX: DbTx<'tx>,
+{
+    let hashed_cursor_factory = HashedPostStateCursorFactory::new(tx, hashed_state);
+    let calculator = StateRoot::new(tx)
+        .with_hashed_cursor_factory(&hashed_cursor_factory)
+        .with_changed_account_prefixes(changed_account_prefixes)
+        .with_changed_storage_prefixes(changed_storage_prefixes)
+        .with_storage_roots(storage_roots);
+    if retain_updates {
+        calculator.root_with_updates()
+    } else {
+        Ok((calculator.root()?, TrieUpdates::default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::state_root_prehashed;
+    use proptest::{prelude::ProptestConfig, proptest};
+    use reth_db::{mdbx::test_utils::create_test_rw_db, tables, transaction::DbTxMut};
+    use reth_primitives::{keccak256, Account, StorageEntry, U256};
+    use reth_provider::Transaction;
+    use std::{
+        collections::{BTreeMap, HashSet},
+        ops::DerefMut,
+    };
+
+    type State = BTreeMap<H256, (Account, BTreeMap<H256, U256>)>;
+
+    fn insert_state<'a, TX: DbTxMut<'a>>(tx: &mut TX, state: &State) {
+        for (hashed_address, (account, storage)) in state {
+            tx.put::<tables::HashedAccount>(*hashed_address, *account).unwrap();
+            for (slot, value) in storage {
+                if *value != U256::ZERO {
+                    tx.put::<tables::HashedStorage>(
+                        *hashed_address,
+                        StorageEntry { key: *slot, value: *value },
+                    )
+                    .unwrap();
+                }
+            }
+        }
+    }
+
+    fn expected_root(state: &State) -> H256 {
+        state_root_prehashed(state.iter().map(|(address, (account, storage))| {
+            let storage = storage.iter().filter(|(_, value)| **value != U256::ZERO);
+            (*address, (*account, storage.map(|(slot, value)| (*slot, *value))))
+        }))
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            cases: 16, ..ProptestConfig::default()
+        })]
+
+        #[test]
+        fn parallel_root_matches_sequential(
+            state: State,
+            changes: BTreeMap<H256, BTreeMap<H256, U256>>,
+            workers in 1usize..8,
+        ) {
+            let db = create_test_rw_db();
+            let mut tx = Transaction::new(db.as_ref()).unwrap();
+            insert_state(tx.deref_mut(), &state);
+            let (_, updates) = StateRoot::new(tx.deref_mut()).root_with_updates().unwrap();
+            updates.flush(tx.deref_mut()).unwrap();
+            tx.commit().unwrap();
+
+            // change the storage of existing accounts on top of the committed trie
+            let mut hashed_state = HashedPostState::default();
+            let mut expected = state.clone();
+            for ((hashed_address, (account, _)), slots) in state.iter().zip(changes.values()) {
+                let hashed_storage = hashed_state.storages.entry(*hashed_address).or_default();
+                hashed_storage.storage.extend(slots.clone());
+                hashed_state.accounts.insert(*hashed_address, Some(*account));
+                expected.get_mut(hashed_address).unwrap().1.extend(slots.clone());
+            }
+            let (account_prefixes, storage_prefixes) = hashed_state.construct_prefix_sets();
+
+            let (root, updates) = ParallelStateRoot::new(db.as_ref())
+                .with_workers(workers)
+                .with_hashed_state(hashed_state.clone())
+                .with_changed_account_prefixes(account_prefixes.clone())
+                .with_changed_storage_prefixes(storage_prefixes.clone())
+                .root_with_updates()
+                .unwrap();
+            assert_eq!(root, expected_root(&expected));
+
+            let tx = db.tx().unwrap();
+            let hashed_cursor_factory = HashedPostStateCursorFactory::new(&tx, &hashed_state);
+            let (sequential_root, sequential_updates) = StateRoot::new(&tx)
+                .with_hashed_cursor_factory(&hashed_cursor_factory)
+                .with_changed_account_prefixes(account_prefixes)
+                .with_changed_storage_prefixes(storage_prefixes)
+                .root_with_updates()
+                .unwrap();
+            assert_eq!(root, sequential_root);
+            assert_eq!(
+                updates.into_iter().map(|(key, _)| key).collect::<HashSet<_>>(),
+                sequential_updates.into_iter().map(|(key, _)| key).collect::<HashSet<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn incremental_parallel_root_without_changes() {
+        let db = create_test_rw_db();
+        let mut tx = Transaction::new(db.as_ref()).unwrap();
+        let state = State::from([(
+            keccak256(H256::random()),
+            (
+                Account { nonce: 1, balance: U256::from(10), bytecode_hash: None },
+                BTreeMap::from([(H256::random(), U256::from(1))]),
+            ),
+        )]);
+        insert_state(tx.deref_mut(), &state);
+        let (_, updates) = StateRoot::new(tx.deref_mut()).root_with_updates().unwrap();
+        updates.flush(tx.deref_mut()).unwrap();
+        tx.commit().unwrap();
+
+        let (root, _) =
+            ParallelStateRoot::incremental_root_with_updates(db.as_ref(), 1..=1).unwrap();
+        assert_eq!(root, expected_root(&state));
+    }
+}
@@ -0,0 +1,375 @@
+use crate::{
+    account::EthAccount,
+    hashed_cursor::{HashedAccountCursor, HashedCursorFactory, HashedStorageCursor},
+    prefix_set::PrefixSet,
+    trie_cursor::{AccountTrieCursor, StorageTrieCursor},
+    walker::TrieWalker,
+    StateRootError, StorageRoot, StorageRootError,
+};
+use reth_db::{tables, transaction::DbTx};
+use reth_primitives::{
+    keccak256,
+    proofs::EMPTY_ROOT,
+    trie::{hash_builder::ProofRetainer, HashBuilder, Nibbles},
+    Address, Bytes, StorageEntry, H256,
+};
+use reth_rlp::Encodable;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// The merkle proof of an account and a set of its storage slots.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AccountProof {
+    /// The account trie nodes from the root to the account.
+    pub proof: Vec<Bytes>,
+    /// The storage root of the account.
+    pub storage_root: H256,
+    /// The storage trie nodes from the storage root to each of the requested slots.
+    pub storage_proofs: Vec<Vec<Bytes>>,
+}
+
+/// The storage trie nodes of an account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageMultiProof {
+    /// The storage root of the account.
+    pub root: H256,
+    /// The storage trie nodes, keyed by their path.
+    pub nodes: BTreeMap<Nibbles, Bytes>,
+}
+
+impl Default for StorageMultiProof {
+    fn default() -> Self {
+        Self { root: EMPTY_ROOT, nodes: BTreeMap::default() }
+    }
+}
+
+/// The merkle proof of a set of accounts and storage slots.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MultiProof {
+    /// The account trie nodes, keyed by their path.
+    pub account_nodes: BTreeMap<Nibbles, Bytes>,
+    /// The storage proofs of the target accounts that exist, keyed by the hashed address.
+    pub storages: BTreeMap<H256, StorageMultiProof>,
+}
+
+impl MultiProof {
+    /// Returns all nodes of the account and storage tries that are referenced by their hash.
+    ///
+    /// Nodes shorter than 32 bytes are embedded in their parent and are skipped, unless they are
+    /// the root of a trie.
+    pub fn nodes(&self) -> impl Iterator<Item = &Bytes> + '_ {
+        self.account_nodes
+            .iter()
+            .chain(self.storages.values().flat_map(|storage| storage.nodes.iter()))
+            .filter(|(path, node)| is_hashed_node(path, node))
+            .map(|(_, node)| node)
+    }
+
+    /// Returns the proof of the given account and storage slots.
+    ///
+    /// The account and slots must have been part of the targets the proof was generated for.
+    pub fn account_proof(&self, address: Address, slots: &[H256]) -> AccountProof {
+        let hashed_address = keccak256(address);
+        let storage = self.storages.get(&hashed_address);
+        AccountProof {
+            proof: path_nodes(&self.account_nodes, &Nibbles::unpack(hashed_address)),
+            storage_root: storage.map(|storage| storage.root).unwrap_or(EMPTY_ROOT),
+            storage_proofs: slots
+                .iter()
+                .map(|slot| {
+                    storage
+                        .map(|storage| {
+                            path_nodes(&storage.nodes, &Nibbles::unpack(keccak256(slot)))
+                        })
+                        .unwrap_or_default()
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Returns `true` if the node is referenced by its hash in the trie.
+fn is_hashed_node(path: &Nibbles, node: &Bytes) -> bool {
+    path.is_empty() || node.len() >= H256::len_bytes()
+}
+
+/// Returns the nodes on the path to the given key, from the root down.
+fn path_nodes(nodes: &BTreeMap<Nibbles, Bytes>, key: &Nibbles) -> Vec<Bytes> {
+    nodes
+        .iter()
+        .filter(|(path, node)| key.has_prefix(path) && is_hashed_node(path, node))
+        .map(|(_, node)| node.clone())
+        .collect()
+}
+
+/// Proof is used to generate merkle proofs of accounts and storage slots.
+pub struct Proof<'a, 'b, TX, H> {
+    /// A reference to the database transaction.
+    pub tx: &'a TX,
+    /// The factory for hashed cursors.
+    pub hashed_cursor_factory: &'b H,
+    /// A set of account prefixes that have changed.
+    pub changed_account_prefixes: PrefixSet,
+    /// A map containing storage changes with the hashed address as key and a set of storage key
+    /// prefixes as the value.
+    pub changed_storage_prefixes: HashMap<H256, PrefixSet>,
+    /// Whether to include the siblings of the nodes on the paths to the targets.
+    pub with_siblings: bool,
+}
+
+impl<'a, 'b, TX, H> Proof<'a, 'b, TX, H> {
+    /// Set the changed account prefixes.
+    pub fn with_changed_account_prefixes(mut self, prefixes: PrefixSet) -> Self {
+        self.changed_account_prefixes = prefixes;
+        self
+    }
+
+    /// Set the changed storage prefixes.
+    pub fn with_changed_storage_prefixes(mut self, prefixes: HashMap<H256, PrefixSet>) -> Self {
+        self.changed_storage_prefixes = prefixes;
+        self
+    }
+
+    /// Include the siblings of the nodes on the paths to the targets.
+    ///
+    /// The siblings are needed to remove keys from the trie without access to the database.
+    pub fn with_siblings(mut self, with_siblings: bool) -> Self {
+        self.with_siblings = with_siblings;
+        self
+    }
+
+    /// Set the hashed cursor factory.
+    pub fn with_hashed_cursor_factory<'c, HF>(
+        self,
+        hashed_cursor_factory: &'c HF,
+    ) -> Proof<'a, 'c, TX, HF> {
+        Proof {
+            tx: self.tx,
+            changed_account_prefixes: self.changed_account_prefixes,
+            changed_storage_prefixes: self.changed_storage_prefixes,
+            with_siblings: self.with_siblings,
+            hashed_cursor_factory,
+        }
+    }
+}
+
+impl<'a, 'tx, TX> Proof<'a, 'a, TX, TX>
+where
+    TX: DbTx<'tx> + HashedCursorFactory<'a>,
+{
+    /// Create a new [Proof] instance.
+    pub fn new(tx: &'a TX) -> Self {
+        Self {
+            tx,
+            changed_account_prefixes: PrefixSet::default(),
+            changed_storage_prefixes: HashMap::default(),
+            with_siblings: false,
+            hashed_cursor_factory: tx,
+        }
+    }
+}
+
+impl<'a, 'b, 'tx, TX, H> Proof<'a, 'b, TX, H>
+where
+    TX: DbTx<'tx>,
+    H: HashedCursorFactory<'b>,
+{
+    /// Generates the proof of the given account and storage slots.
+    pub fn account_proof(
+        &self,
+        address: Address,
+        slots: &[H256],
+    ) -> Result<AccountProof, StateRootError> {
+        let targets = BTreeMap::from([(address, slots.iter().copied().collect())]);
+        Ok(self.multiproof(&targets)?.account_proof(address, slots))
+    }
+
+    /// Walks the intermediate nodes of the existing state trie and the hashed entries, and retains
+    /// the nodes on the paths to the target accounts and their storage slots.
+    pub fn multiproof(
+        &self,
+        targets: &BTreeMap<Address, BTreeSet<H256>>,
+    ) -> Result<MultiProof, StateRootError> {
+        let targets = targets
+            .iter()
+            .map(|(address, slots)| (keccak256(address), slots.iter().map(keccak256).collect()))
+            .collect::<BTreeMap<H256, Vec<H256>>>();
+
+        let mut prefix_set = self.changed_account_prefixes.clone();
+        let mut target_nibbles = Vec::with_capacity(targets.len());
+        for hashed_address in targets.keys() {
+            let nibbles = Nibbles::unpack(hashed_address);
+            prefix_set.insert(nibbles.clone());
+            target_nibbles.push(nibbles);
+        }
+
+        let mut hashed_account_cursor = self.hashed_cursor_factory.hashed_account_cursor()?;
+        let mut trie_cursor =
+            AccountTrieCursor::new(self.tx.cursor_read::<tables::AccountsTrie>()?);
+        let mut walker = TrieWalker::new(&mut trie_cursor, prefix_set);
+
+        let retainer = ProofRetainer::new(target_nibbles).with_siblings(self.with_siblings);
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(retainer);
+
+        let mut storages = BTreeMap::default();
+        let mut account_rlp = Vec::with_capacity(128);
+
+        while let Some(key) = walker.key() {
+            if walker.can_skip_current_node {
+                hash_builder.add_branch(key, walker.hash().unwrap(), walker.children_are_in_trie());
+            }
+
+            let seek_key = match walker.next_unprocessed_key() {
+                Some(key) => key,
+                None => break, // no more keys
+            };
+
+            let next_key = walker.advance()?;
+            let mut next_account_entry = hashed_account_cursor.seek(seek_key)?;
+            while let Some((hashed_address, account)) = next_account_entry {
+                let account_nibbles = Nibbles::unpack(hashed_address);
+                if let Some(ref key) = next_key {
+                    if key < &account_nibbles {
+                        break
+                    }
+                }
+
+                let storage_root = match targets.get(&hashed_address) {
+                    Some(slots) => {
+                        let storage = self.storage_multiproof(hashed_address, slots)?;
+                        let root = storage.root;
+                        storages.insert(hashed_address, storage);
+                        root
+                    }
+                    None => StorageRoot::new_hashed_with_factory(
+                        self.tx,
+                        self.hashed_cursor_factory,
+                        hashed_address,
+                    )
+                    .with_changed_prefixes(
+                        self.changed_storage_prefixes
+                            .get(&hashed_address)
+                            .cloned()
+                            .unwrap_or_default(),
+                    )
+                    .root()?,
+                };
+
+                let account = EthAccount::from(account).with_storage_root(storage_root);
+
+                account_rlp.clear();
+                account.encode(&mut &mut account_rlp);
+
+                hash_builder.add_leaf(account_nibbles, &account_rlp);
+                next_account_entry = hashed_account_cursor.next()?;
+            }
+        }
+
+        hash_builder.root();
+
+        Ok(MultiProof { account_nodes: hash_builder.take_proofs(), storages })
+    }
+
+    /// Walks the storage trie of the given account and retains the nodes on the paths to the
+    /// given hashed slots.
+    fn storage_multiproof(
+        &self,
+        hashed_address: H256,
+        hashed_slots: &[H256],
+    ) -> Result<StorageMultiProof, StorageRootError> {
+        let mut hashed_storage_cursor = self.hashed_cursor_factory.hashed_storage_cursor()?;
+
+        // short circuit on empty storage
+        if hashed_storage_cursor.is_storage_empty(hashed_address)? {
+            return Ok(StorageMultiProof::default())
+        }
+
+        let mut prefix_set =
+            self.changed_storage_prefixes.get(&hashed_address).cloned().unwrap_or_default();
+        let target_nibbles = hashed_slots.iter().map(Nibbles::unpack).collect::<Vec<_>>();
+        for nibbles in &target_nibbles {
+            prefix_set.insert(nibbles.clone());
+        }
+
+        let mut trie_cursor = StorageTrieCursor::new(
+            self.tx.cursor_dup_read::<tables::StoragesTrie>()?,
+            hashed_address,
+        );
+        let mut walker = TrieWalker::new(&mut trie_cursor, prefix_set);
+
+        let retainer = ProofRetainer::new(target_nibbles).with_siblings(self.with_siblings);
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(retainer);
+
+        while let Some(key) = walker.key() {
+            if walker.can_skip_current_node {
+                hash_builder.add_branch(key, walker.hash().unwrap(), walker.children_are_in_trie());
+            }
+
+            let seek_key = match walker.next_unprocessed_key() {
+                Some(key) => key,
+                None => break, // no more keys
+            };
+
+            let next_key = walker.advance()?;
+            let mut storage = hashed_storage_cursor.seek(hashed_address, seek_key)?;
+            while let Some(StorageEntry { key: hashed_key, value }) = storage {
+                let storage_key_nibbles = Nibbles::unpack(hashed_key);
+                if let Some(ref key) = next_key {
+                    if key < &storage_key_nibbles {
+                        break
+                    }
+                }
+                hash_builder
+                    .add_leaf(storage_key_nibbles, reth_rlp::encode_fixed_size(&value).as_ref());
+                storage = hashed_storage_cursor.next()?;
+            }
+        }
+
+        let root = hash_builder.root();
+
+        Ok(StorageMultiProof { root, nodes: hash_builder.take_proofs() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StateRoot;
+    use reth_db::{mdbx::test_utils::create_test_rw_db, transaction::DbTxMut};
+    use reth_primitives::{Account, U256};
+    use reth_provider::Transaction;
+    use std::ops::Deref;
+
+    #[test]
+    fn account_proof() {
+        let db = create_test_rw_db();
+        let tx = Transaction::new(db.as_ref()).unwrap();
+
+        let account = Account { nonce: 1, balance: U256::from(1), bytecode_hash: None };
+        for i in 0..32u64 {
+            let hashed_address = keccak256(Address::from_low_u64_be(i));
+            tx.put::<tables::HashedAccount>(hashed_address, account).unwrap();
+            for slot in 0..32u64 {
+                let entry = StorageEntry {
+                    key: keccak256(H256::from_low_u64_be(slot)),
+                    value: U256::from(1),
+                };
+                tx.put::<tables::HashedStorage>(hashed_address, entry).unwrap();
+            }
+        }
+        let root = StateRoot::new(tx.deref()).root().unwrap();
+
+        let address = Address::from_low_u64_be(7);
+        let slots = [H256::from_low_u64_be(3), H256::from_low_u64_be(100)];
+        let proof = Proof::new(tx.deref()).account_proof(address, &slots).unwrap();
+
+        assert_eq!(keccak256(&proof.proof[0]), root);
+        assert_eq!(
+            proof.storage_root,
+            StorageRoot::new_hashed(tx.deref(), keccak256(address)).root().unwrap()
+        );
+        assert_eq!(proof.storage_proofs.len(), 2);
+        for storage_proof in &proof.storage_proofs {
+            assert_eq!(keccak256(&storage_proof[0]), proof.storage_root);
+        }
+    }
+}
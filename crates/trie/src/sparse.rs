@@ -0,0 +1,574 @@
+use crate::{account::EthAccount, hashed_cursor::HashedPostState, SparseTrieError};
+use reth_primitives::{
+    keccak256,
+    proofs::EMPTY_ROOT,
+    trie::{
+        nodes::{rlp_hash, BranchNode, ExtensionNode, LeafNode},
+        Nibbles, TrieMask,
+    },
+    Account, Address, Bytes, H256, U256,
+};
+use reth_rlp::{Decodable, DecodeError, Encodable, Header};
+use std::collections::HashMap;
+
+/// A node of a [SparseTrie].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SparseNode {
+    /// An empty trie or an absent child.
+    Empty,
+    /// A node that is only known by its hash and has not been resolved yet.
+    Hash(H256),
+    /// A leaf node with the remainder of its key.
+    Leaf { key: Nibbles, value: Vec<u8> },
+    /// An extension node with its shared key.
+    Extension { key: Nibbles, child: Box<SparseNode> },
+    /// A branch node. The value slot is always empty in the secure tries of Ethereum.
+    Branch { children: Box<[SparseNode; 16]> },
+}
+
+impl SparseNode {
+    fn empty_branch() -> Self {
+        Self::Branch { children: Box::new(std::array::from_fn(|_| SparseNode::Empty)) }
+    }
+
+    /// Decodes an RLP encoded trie node.
+    fn decode(mut buf: &[u8]) -> Result<Self, DecodeError> {
+        let header = Header::decode(&mut buf)?;
+        if !header.list {
+            return Err(DecodeError::UnexpectedString)
+        }
+        if buf.len() < header.payload_length {
+            return Err(DecodeError::InputTooShort)
+        }
+
+        let mut payload = &buf[..header.payload_length];
+        let mut items = Vec::with_capacity(17);
+        while !payload.is_empty() {
+            items.push(next_item(&mut payload)?);
+        }
+
+        match items.len() {
+            17 => {
+                let mut branch = Self::empty_branch();
+                if let Self::Branch { children } = &mut branch {
+                    for (child, item) in children.iter_mut().zip(items) {
+                        *child = Self::decode_child(item)?;
+                    }
+                }
+                Ok(branch)
+            }
+            2 => {
+                let (key, is_leaf) = decode_path(&Bytes::decode(&mut &items[0][..])?)?;
+                if is_leaf {
+                    let value = Bytes::decode(&mut &items[1][..])?.to_vec();
+                    Ok(Self::Leaf { key, value })
+                } else {
+                    Ok(Self::Extension { key, child: Box::new(Self::decode_child(items[1])?) })
+                }
+            }
+            _ => Err(DecodeError::Custom("invalid number of trie node items")),
+        }
+    }
+
+    /// Decodes a child reference, which is either empty, a hash or an embedded node.
+    fn decode_child(item: &[u8]) -> Result<Self, DecodeError> {
+        let mut buf = item;
+        let header = Header::decode(&mut buf)?;
+        if header.list {
+            return Self::decode(item)
+        }
+        match header.payload_length {
+            0 => Ok(Self::Empty),
+            32 => Ok(Self::Hash(H256::from_slice(&buf[..32]))),
+            _ => Err(DecodeError::UnexpectedLength),
+        }
+    }
+
+    /// Returns the reference to this node in its parent: either its RLP encoding if it is shorter
+    /// than 32 bytes, or the RLP encoded hash of it.
+    fn rlp_node(&self, buf: &mut Vec<u8>) -> Vec<u8> {
+        buf.clear();
+        match self {
+            Self::Empty => vec![reth_rlp::EMPTY_STRING_CODE],
+            Self::Hash(hash) => rlp_hash(*hash),
+            Self::Leaf { key, value } => LeafNode::new(key, value).rlp(buf),
+            Self::Extension { key, child } => {
+                let child = child.rlp_node(buf);
+                buf.clear();
+                ExtensionNode::new(key, &child).rlp(buf)
+            }
+            Self::Branch { children } => {
+                let mut state_mask = TrieMask::default();
+                let mut stack = Vec::with_capacity(16);
+                for (nibble, child) in children.iter().enumerate() {
+                    if *child != Self::Empty {
+                        state_mask |= TrieMask::from_nibble(nibble as u8);
+                        stack.push(child.rlp_node(buf));
+                    }
+                }
+                buf.clear();
+                BranchNode::new(&stack).rlp(state_mask, buf)
+            }
+        }
+    }
+}
+
+/// Splits the next RLP item off the buffer, returning it together with its header.
+fn next_item<'a>(buf: &mut &'a [u8]) -> Result<&'a [u8], DecodeError> {
+    let start = *buf;
+    let header = Header::decode(buf)?;
+    if buf.len() < header.payload_length {
+        return Err(DecodeError::InputTooShort)
+    }
+    *buf = &buf[header.payload_length..];
+    Ok(&start[..start.len() - buf.len()])
+}
+
+/// Decodes a hex-prefix encoded path, returning the nibbles and whether the path is a leaf's.
+fn decode_path(encoded: &[u8]) -> Result<(Nibbles, bool), DecodeError> {
+    let Some(first) = encoded.first() else { return Err(DecodeError::InputTooShort) };
+    let flag = first >> 4;
+    if flag > 3 {
+        return Err(DecodeError::Custom("invalid path flag"))
+    }
+
+    let mut nibbles = Vec::with_capacity(encoded.len() * 2);
+    if flag & 1 == 1 {
+        nibbles.push(first & 0x0f);
+    }
+    nibbles.extend(Nibbles::unpack(&encoded[1..]).hex_data);
+    Ok((Nibbles::from_hex(nibbles), flag & 2 == 2))
+}
+
+/// An in-memory Merkle Patricia Trie of which only some paths are known.
+///
+/// The trie starts out as the hash of its root and nodes are resolved from a set of RLP encoded
+/// nodes as they are accessed. Accessing a path whose nodes are not part of that set fails with
+/// [SparseTrieError::MissingNode].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseTrie {
+    root: SparseNode,
+}
+
+impl SparseTrie {
+    /// Creates a new trie with the given root.
+    pub fn new(root: H256) -> Self {
+        let root = if root == EMPTY_ROOT { SparseNode::Empty } else { SparseNode::Hash(root) };
+        Self { root }
+    }
+
+    /// Returns the value stored under the given key.
+    pub fn get(
+        &mut self,
+        nodes: &HashMap<H256, Bytes>,
+        key: &Nibbles,
+    ) -> Result<Option<Vec<u8>>, SparseTrieError> {
+        let mut node = &mut self.root;
+        let mut path = key.clone();
+        loop {
+            resolve(node, nodes)?;
+            match node {
+                SparseNode::Leaf { key, value } => return Ok((*key == path).then(|| value.clone())),
+                SparseNode::Extension { key, child } => {
+                    if !path.has_prefix(key) {
+                        return Ok(None)
+                    }
+                    path = path.slice_from(key.len());
+                    node = child.as_mut();
+                }
+                SparseNode::Branch { children } => {
+                    let Some(nibble) = path.first().copied() else { return Ok(None) };
+                    path = path.slice_from(1);
+                    node = &mut children[nibble as usize];
+                }
+                SparseNode::Empty | SparseNode::Hash(_) => return Ok(None),
+            }
+        }
+    }
+
+    /// Inserts or updates the value stored under the given key.
+    pub fn insert(
+        &mut self,
+        nodes: &HashMap<H256, Bytes>,
+        key: Nibbles,
+        value: Vec<u8>,
+    ) -> Result<(), SparseTrieError> {
+        insert(&mut self.root, nodes, key, value)
+    }
+
+    /// Removes the value stored under the given key, if any.
+    pub fn remove(
+        &mut self,
+        nodes: &HashMap<H256, Bytes>,
+        key: &Nibbles,
+    ) -> Result<(), SparseTrieError> {
+        remove(&mut self.root, nodes, key)
+    }
+
+    /// Returns the root hash of the trie.
+    pub fn root(&self) -> H256 {
+        if self.root == SparseNode::Empty {
+            return EMPTY_ROOT
+        }
+        let node = self.root.rlp_node(&mut Vec::new());
+        if node.len() == H256::len_bytes() + 1 {
+            H256::from_slice(&node[1..])
+        } else {
+            keccak256(node)
+        }
+    }
+}
+
+/// Replaces a hash node with the node it refers to.
+fn resolve(node: &mut SparseNode, nodes: &HashMap<H256, Bytes>) -> Result<(), SparseTrieError> {
+    if let SparseNode::Hash(hash) = node {
+        let rlp = nodes.get(hash).ok_or(SparseTrieError::MissingNode(*hash))?;
+        *node = SparseNode::decode(rlp)?;
+    }
+    Ok(())
+}
+
+fn insert(
+    node: &mut SparseNode,
+    nodes: &HashMap<H256, Bytes>,
+    path: Nibbles,
+    value: Vec<u8>,
+) -> Result<(), SparseTrieError> {
+    resolve(node, nodes)?;
+    match node {
+        SparseNode::Empty => *node = SparseNode::Leaf { key: path, value },
+        SparseNode::Leaf { key, value: existing } => {
+            if *key == path {
+                *existing = value;
+                return Ok(())
+            }
+            // keys in a secure trie have the same length, so neither key is a prefix of the other
+            let common = key.common_prefix_length(&path);
+            let mut branch = SparseNode::empty_branch();
+            if let SparseNode::Branch { children } = &mut branch {
+                children[key[common] as usize] =
+                    SparseNode::Leaf { key: key.slice_from(common + 1), value: existing.clone() };
+                children[path[common] as usize] =
+                    SparseNode::Leaf { key: path.slice_from(common + 1), value };
+            }
+            *node = with_extension(path.slice(0, common), branch);
+        }
+        SparseNode::Extension { key, child } => {
+            let common = key.common_prefix_length(&path);
+            if common == key.len() {
+                return insert(child, nodes, path.slice_from(common), value)
+            }
+            let mut branch = SparseNode::empty_branch();
+            if let SparseNode::Branch { children } = &mut branch {
+                let child = std::mem::replace(child.as_mut(), SparseNode::Empty);
+                children[key[common] as usize] = with_extension(key.slice_from(common + 1), child);
+                children[path[common] as usize] =
+                    SparseNode::Leaf { key: path.slice_from(common + 1), value };
+            }
+            *node = with_extension(path.slice(0, common), branch);
+        }
+        SparseNode::Branch { children } => {
+            let nibble = path[0] as usize;
+            return insert(&mut children[nibble], nodes, path.slice_from(1), value)
+        }
+        SparseNode::Hash(_) => unreachable!("resolved above"),
+    }
+    Ok(())
+}
+
+fn remove(
+    node: &mut SparseNode,
+    nodes: &HashMap<H256, Bytes>,
+    path: &Nibbles,
+) -> Result<(), SparseTrieError> {
+    resolve(node, nodes)?;
+    match node {
+        SparseNode::Empty => {}
+        SparseNode::Leaf { key, .. } => {
+            if key == path {
+                *node = SparseNode::Empty;
+            }
+        }
+        SparseNode::Extension { key, child } => {
+            if !path.has_prefix(key) {
+                return Ok(())
+            }
+            remove(child, nodes, &path.slice_from(key.len()))?;
+            let key = key.clone();
+            let child = std::mem::replace(child.as_mut(), SparseNode::Empty);
+            *node = with_extension(key, child);
+        }
+        SparseNode::Branch { children } => {
+            remove(&mut children[path[0] as usize], nodes, &path.slice_from(1))?;
+
+            let mut remaining =
+                children.iter().enumerate().filter(|(_, c)| **c != SparseNode::Empty);
+            let (Some((nibble, _)), None) = (remaining.next(), remaining.next()) else {
+                return Ok(())
+            };
+
+            // the branch node has a single child left and is collapsed into it
+            let mut child = std::mem::replace(&mut children[nibble], SparseNode::Empty);
+            match resolve(&mut child, nodes) {
+                Ok(()) => {}
+                // Only branch nodes are omitted from the siblings of a proof, as they are stored
+                // by their hash in the database. A missing leaf or extension results in an
+                // invalid root.
+                Err(SparseTrieError::MissingNode(_)) => {}
+                Err(err) => return Err(err),
+            }
+            *node = with_extension(Nibbles::from_hex(vec![nibble as u8]), child);
+        }
+        SparseNode::Hash(_) => unreachable!("resolved above"),
+    }
+    Ok(())
+}
+
+/// Prepends the given key to the node, merging it with the node's own key if it has one.
+fn with_extension(key: Nibbles, node: SparseNode) -> SparseNode {
+    if key.is_empty() {
+        return node
+    }
+    match node {
+        SparseNode::Empty => SparseNode::Empty,
+        SparseNode::Leaf { key: rest, value } => SparseNode::Leaf { key: key.join(&rest), value },
+        SparseNode::Extension { key: rest, child } => {
+            SparseNode::Extension { key: key.join(&rest), child }
+        }
+        node @ (SparseNode::Branch { .. } | SparseNode::Hash(_)) => {
+            SparseNode::Extension { key, child: Box::new(node) }
+        }
+    }
+}
+
+/// The state trie and the storage tries of the accounts in it, built from the trie nodes of an
+/// execution witness.
+#[derive(Debug, Clone)]
+pub struct SparseStateTrie {
+    /// The RLP encoded trie nodes, keyed by their hash.
+    nodes: HashMap<H256, Bytes>,
+    /// The account trie.
+    accounts: SparseTrie,
+    /// The storage tries of the accessed accounts, keyed by the hashed address.
+    storages: HashMap<H256, SparseTrie>,
+}
+
+impl SparseStateTrie {
+    /// Creates the state trie with the given root from the given RLP encoded trie nodes.
+    pub fn new(state_root: H256, nodes: impl IntoIterator<Item = Bytes>) -> Self {
+        Self {
+            nodes: nodes.into_iter().map(|node| (keccak256(&node), node)).collect(),
+            accounts: SparseTrie::new(state_root),
+            storages: HashMap::default(),
+        }
+    }
+
+    /// Returns the state root.
+    pub fn root(&self) -> H256 {
+        self.accounts.root()
+    }
+
+    /// Returns the account and its storage root.
+    fn trie_account(
+        &mut self,
+        hashed_address: H256,
+    ) -> Result<Option<EthAccount>, SparseTrieError> {
+        let Some(rlp) = self.accounts.get(&self.nodes, &Nibbles::unpack(hashed_address))? else {
+            return Ok(None)
+        };
+        Ok(Some(EthAccount::decode(&mut rlp.as_slice())?))
+    }
+
+    /// Returns the account with the given address.
+    pub fn account(&mut self, address: Address) -> Result<Option<Account>, SparseTrieError> {
+        Ok(self.trie_account(keccak256(address))?.map(Account::from))
+    }
+
+    /// Returns the value of the storage slot of the given account.
+    pub fn storage(&mut self, address: Address, slot: H256) -> Result<U256, SparseTrieError> {
+        let hashed_address = keccak256(address);
+        if !self.load_storage_trie(hashed_address)? {
+            return Ok(U256::ZERO)
+        }
+        let storage = self.storages.get_mut(&hashed_address).expect("storage trie is loaded");
+        let Some(rlp) = storage.get(&self.nodes, &Nibbles::unpack(keccak256(slot)))? else {
+            return Ok(U256::ZERO)
+        };
+        Ok(U256::decode(&mut rlp.as_slice())?)
+    }
+
+    /// Loads the storage trie of the account, returning `false` if the account does not exist.
+    fn load_storage_trie(&mut self, hashed_address: H256) -> Result<bool, SparseTrieError> {
+        if !self.storages.contains_key(&hashed_address) {
+            let Some(account) = self.trie_account(hashed_address)? else { return Ok(false) };
+            self.storages.insert(hashed_address, SparseTrie::new(account.storage_root()));
+        }
+        Ok(true)
+    }
+
+    /// Applies the changes of the hashed post state to the tries and returns the new state root.
+    pub fn update(&mut self, state: &HashedPostState) -> Result<H256, SparseTrieError> {
+        let mut storage_roots = HashMap::with_capacity(state.storages.len());
+        for (hashed_address, storage) in &state.storages {
+            if storage.wiped {
+                self.storages.insert(*hashed_address, SparseTrie::new(EMPTY_ROOT));
+            }
+            if !self.load_storage_trie(*hashed_address)? {
+                self.storages.insert(*hashed_address, SparseTrie::new(EMPTY_ROOT));
+            }
+            let trie = self.storages.get_mut(hashed_address).expect("storage trie is loaded");
+            for (hashed_slot, value) in &storage.storage {
+                let key = Nibbles::unpack(hashed_slot);
+                if *value == U256::ZERO {
+                    trie.remove(&self.nodes, &key)?;
+                } else {
+                    trie.insert(&self.nodes, key, reth_rlp::encode_fixed_size(value).to_vec())?;
+                }
+            }
+            storage_roots.insert(*hashed_address, trie.root());
+        }
+
+        // accounts that only had their storage changed keep their nonce, balance and code
+        for (hashed_address, storage_root) in &storage_roots {
+            if state.accounts.contains_key(hashed_address) {
+                continue
+            }
+            if let Some(account) = self.trie_account(*hashed_address)? {
+                let account = account.with_storage_root(*storage_root);
+                self.insert_account(*hashed_address, account)?;
+            }
+        }
+
+        for (hashed_address, account) in &state.accounts {
+            let key = Nibbles::unpack(hashed_address);
+            match account {
+                Some(account) => {
+                    let storage_root = match storage_roots.get(hashed_address) {
+                        Some(root) => *root,
+                        None => match self.trie_account(*hashed_address)? {
+                            Some(existing) => existing.storage_root(),
+                            None => EMPTY_ROOT,
+                        },
+                    };
+                    let account = EthAccount::from(*account).with_storage_root(storage_root);
+                    self.insert_account(*hashed_address, account)?;
+                }
+                None => {
+                    self.accounts.remove(&self.nodes, &key)?;
+                    self.storages.remove(hashed_address);
+                }
+            }
+        }
+
+        Ok(self.root())
+    }
+
+    fn insert_account(
+        &mut self,
+        hashed_address: H256,
+        account: EthAccount,
+    ) -> Result<(), SparseTrieError> {
+        let mut rlp = Vec::with_capacity(128);
+        account.encode(&mut rlp);
+        self.accounts.insert(&self.nodes, Nibbles::unpack(hashed_address), rlp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hashed_cursor::HashedStorage, test_utils::state_root, Proof, StateRoot};
+    use reth_db::{mdbx::test_utils::create_test_rw_db, tables, transaction::DbTxMut};
+    use reth_primitives::StorageEntry;
+    use reth_provider::Transaction;
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        ops::Deref,
+    };
+
+    fn address(i: u64) -> Address {
+        Address::from_low_u64_be(i)
+    }
+
+    fn slot(i: u64) -> H256 {
+        H256::from_low_u64_be(i)
+    }
+
+    #[test]
+    fn missing_node() {
+        let mut trie = SparseStateTrie::new(H256::random(), vec![]);
+        assert!(matches!(trie.account(address(1)), Err(SparseTrieError::MissingNode(_))));
+
+        let mut trie = SparseStateTrie::new(EMPTY_ROOT, vec![]);
+        assert_eq!(trie.account(address(1)), Ok(None));
+    }
+
+    #[test]
+    fn update_state_from_multiproof() {
+        let db = create_test_rw_db();
+        let tx = Transaction::new(db.as_ref()).unwrap();
+
+        let mut state = BTreeMap::<Address, (Account, BTreeMap<H256, U256>)>::default();
+        for i in 1..=64u64 {
+            let account = Account { nonce: i, balance: U256::from(i), bytecode_hash: None };
+            let slots = if i == 2 { vec![3, 4] } else { (1..=8).collect() };
+            let storage = slots.into_iter().map(|s| (slot(s), U256::from(i * s))).collect();
+            state.insert(address(i), (account, storage));
+        }
+        for (address, (account, storage)) in &state {
+            let hashed_address = keccak256(address);
+            tx.put::<tables::HashedAccount>(hashed_address, *account).unwrap();
+            for (slot, value) in storage {
+                let entry = StorageEntry { key: keccak256(slot), value: *value };
+                tx.put::<tables::HashedStorage>(hashed_address, entry).unwrap();
+            }
+        }
+
+        let root = StateRoot::new(tx.deref()).root().unwrap();
+        assert_eq!(root, state_root(state.clone().into_iter()));
+
+        let targets = BTreeMap::from([
+            (address(1), BTreeSet::from([slot(1), slot(2)])),
+            (address(2), BTreeSet::from([slot(3)])),
+            (address(3), BTreeSet::default()),
+            (address(100), BTreeSet::default()),
+        ]);
+        let proof = Proof::new(tx.deref()).with_siblings(true).multiproof(&targets).unwrap();
+        let mut trie = SparseStateTrie::new(root, proof.nodes().cloned());
+
+        assert_eq!(trie.account(address(1)), Ok(Some(state[&address(1)].0)));
+        assert_eq!(trie.storage(address(1), slot(2)), Ok(U256::from(2)));
+        assert_eq!(trie.storage(address(2), slot(1)), Ok(U256::ZERO));
+        assert_eq!(trie.account(address(100)), Ok(None));
+
+        // update a slot, remove the second to last slot of an account, destroy an account and
+        // create a new one
+        let new_account = Account { nonce: 1, balance: U256::from(1), bytecode_hash: None };
+        state.get_mut(&address(1)).unwrap().1.insert(slot(1), U256::from(1000));
+        state.get_mut(&address(2)).unwrap().1.remove(&slot(3));
+        state.remove(&address(3));
+        state.insert(address(100), (new_account, BTreeMap::from([(slot(1), U256::from(1))])));
+
+        let storage = |slots: Vec<(H256, U256)>| HashedStorage {
+            wiped: false,
+            storage: slots.into_iter().map(|(slot, value)| (keccak256(slot), value)).collect(),
+        };
+        let post_state = HashedPostState {
+            accounts: BTreeMap::from([
+                (keccak256(address(3)), None),
+                (keccak256(address(100)), Some(new_account)),
+            ]),
+            storages: BTreeMap::from([
+                (keccak256(address(1)), storage(vec![(slot(1), U256::from(1000))])),
+                (keccak256(address(2)), storage(vec![(slot(3), U256::ZERO)])),
+                (
+                    keccak256(address(3)),
+                    HashedStorage { wiped: true, storage: BTreeMap::default() },
+                ),
+                (keccak256(address(100)), storage(vec![(slot(1), U256::from(1))])),
+            ]),
+        };
+
+        assert_eq!(trie.update(&post_state), Ok(state_root(state.into_iter())));
+    }
+}
@@ -33,6 +33,8 @@ pub struct StateRoot<'a, 'b, TX, H> {
     previous_state: Option<IntermediateStateRootState>,
     /// The number of updates after which the intermediate progress should be returned.
     threshold: u64,
+    /// Storage roots and storage trie updates of accounts that were computed beforehand.
+    storage_roots: HashMap<H256, (H256, TrieUpdates)>,
 }
 
 impl<'a, 'b, TX, H> StateRoot<'a, 'b, TX, H> {
@@ -66,6 +68,15 @@ impl<'a, 'b, TX, H> StateRoot<'a, 'b, TX, H> {
         self
     }
 
+    /// Set the storage roots and storage trie updates of accounts that were computed beforehand.
+    ///
+    /// These are used instead of computing the storage roots of the accounts while walking the
+    /// account trie. See [ParallelStateRoot](crate::ParallelStateRoot).
+    pub fn with_storage_roots(mut self, storage_roots: HashMap<H256, (H256, TrieUpdates)>) -> Self {
+        self.storage_roots = storage_roots;
+        self
+    }
+
     /// Set the hashed cursor factory.
     pub fn with_hashed_cursor_factory<'c, HF>(
         self,
@@ -77,6 +88,7 @@ impl<'a, 'b, TX, H> StateRoot<'a, 'b, TX, H> {
             changed_storage_prefixes: self.changed_storage_prefixes,
             threshold: self.threshold,
             previous_state: self.previous_state,
+            storage_roots: self.storage_roots,
             hashed_cursor_factory,
         }
     }
@@ -94,6 +106,7 @@ where
             changed_storage_prefixes: HashMap::default(),
             previous_state: None,
             threshold: 100_000,
+            storage_roots: HashMap::default(),
             hashed_cursor_factory: tx,
         }
     }
@@ -205,6 +218,7 @@ where
     fn calculate(self, retain_updates: bool) -> Result<StateRootProgress, StateRootError> {
         tracing::debug!(target: "loader", "calculating state root");
         let mut trie_updates = TrieUpdates::default();
+        let mut storage_roots = self.storage_roots;
 
         let mut hashed_account_cursor = self.hashed_cursor_factory.hashed_account_cursor()?;
         let mut trie_cursor =
@@ -276,21 +290,28 @@ where
                 // progress.
                 // TODO: We can consider introducing the TrieProgress::Progress/Complete
                 // abstraction inside StorageRoot, but let's give it a try as-is for now.
-                let storage_root_calculator = StorageRoot::new_hashed(self.tx, hashed_address)
-                    .with_hashed_cursor_factory(self.hashed_cursor_factory)
-                    .with_changed_prefixes(
-                        self.changed_storage_prefixes
-                            .get(&hashed_address)
-                            .cloned()
-                            .unwrap_or_default(),
-                    );
-
-                let storage_root = if retain_updates {
-                    let (root, updates) = storage_root_calculator.root_with_updates()?;
+                let storage_root = if let Some((root, updates)) =
+                    storage_roots.remove(&hashed_address)
+                {
                     trie_updates.extend(updates.into_iter());
                     root
                 } else {
-                    storage_root_calculator.root()?
+                    let storage_root_calculator = StorageRoot::new_hashed(self.tx, hashed_address)
+                        .with_hashed_cursor_factory(self.hashed_cursor_factory)
+                        .with_changed_prefixes(
+                            self.changed_storage_prefixes
+                                .get(&hashed_address)
+                                .cloned()
+                                .unwrap_or_default(),
+                        );
+
+                    if retain_updates {
+                        let (root, updates) = storage_root_calculator.root_with_updates()?;
+                        trie_updates.extend(updates.into_iter());
+                        root
+                    } else {
+                        storage_root_calculator.root()?
+                    }
                 };
 
                 let account = EthAccount::from(account).with_storage_root(storage_root);